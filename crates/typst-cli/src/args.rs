@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use clap::builder::ValueParser;
 use clap::{ArgAction, Args, ColorChoice, Parser, Subcommand, ValueEnum};
 use semver::Version;
+use typst::layout::PageRanges;
 
 /// The character typically used to separate path components
 /// in environment variables.
@@ -52,6 +53,9 @@ pub enum Command {
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
+    /// Explains a diagnostic code in detail
+    Explain(ExplainCommand),
+
     /// Self update the Typst CLI
     #[cfg_attr(not(feature = "self-update"), doc = " (disabled)")]
     Update(UpdateCommand),
@@ -76,15 +80,166 @@ pub struct CompileCommand {
     #[arg(long = "open")]
     pub open: Option<Option<String>>,
 
+    /// The document's creation date formatted as a UNIX timestamp
+    ///
+    /// Overrides the current time that is otherwise embedded into the PDF if
+    /// `set document(date: ..)` is `auto`. Setting this makes output
+    /// reproducible across builds.
+    #[clap(
+        long = "creation-timestamp",
+        env = "SOURCE_DATE_EPOCH",
+        value_name = "UNIX_TIMESTAMP"
+    )]
+    pub creation_timestamp: Option<i64>,
+
+    /// Encrypts the PDF, requiring this password to open it
+    #[clap(
+        long = "user-password",
+        env = "TYPST_PDF_USER_PASSWORD",
+        value_name = "PASSWORD"
+    )]
+    pub user_password: Option<String>,
+
+    /// Encrypts the PDF, granting full access with this password
+    #[clap(
+        long = "owner-password",
+        env = "TYPST_PDF_OWNER_PASSWORD",
+        value_name = "PASSWORD"
+    )]
+    pub owner_password: Option<String>,
+
+    /// Encrypts the PDF and forbids printing it without the owner password
+    #[arg(long = "no-print")]
+    pub no_print: bool,
+
+    /// Encrypts the PDF and forbids copying from it without the owner password
+    #[arg(long = "no-copy")]
+    pub no_copy: bool,
+
+    /// Encrypts the PDF and forbids modifying it without the owner password
+    #[arg(long = "no-modify")]
+    pub no_modify: bool,
+
+    /// Which pages to export, as a comma-separated list of pages and ranges
+    /// like `1,3-5,8-`
+    ///
+    /// If the output path contains `{n}`, each page is written to a separate
+    /// file, with `{n}` replaced by the page number. This also works for PDF.
+    #[arg(
+        long = "pages",
+        value_name = "PAGES",
+        value_parser = ValueParser::new(parse_page_ranges),
+    )]
+    pub pages: Option<PageRanges>,
+
+    /// Does not embed the font family with this name into the PDF
+    ///
+    /// The PDF then only refers to the font by name, which makes it much
+    /// smaller, but it only displays correctly where the exact same font is
+    /// installed. Useful for drafts that aren't shared.
+    #[arg(long = "no-embed-font", value_name = "FAMILY", action = ArgAction::Append)]
+    pub no_embed_fonts: Vec<String>,
+
+    /// Turns links to local files other than PDFs into launch actions
+    ///
+    /// Such links open the file with its default application. Since that
+    /// includes executables, they are written as plain URIs by default.
+    #[arg(long = "allow-launch")]
+    pub allow_launch: bool,
+
+    /// Leaves out links that would run scripts
+    ///
+    /// These are links to `javascript:`, `vbscript:`, and `data:` URLs. By
+    /// default, they are written like all other links.
+    #[arg(long = "strip-script-links")]
+    pub strip_script_links: bool,
+
+    /// Checks the exported PDF for common preflight problems
+    ///
+    /// Reports images without alternative text, fonts that aren't embedded,
+    /// colors outside of the sRGB gamut or a CMYK output intent, and
+    /// oversized pages as warnings.
+    #[arg(long = "validate")]
+    pub validate: bool,
+
+    /// Converts all colors in the PDF to grayscale, for single-plate printing
+    #[arg(long = "grayscale", conflicts_with = "ink_limit")]
+    pub grayscale: bool,
+
+    /// Limits the total ink coverage of CMYK colors in the PDF to the given
+    /// percentage, like `280`
+    #[arg(long = "ink-limit", value_name = "PERCENT")]
+    pub ink_limit: Option<f32>,
+
+    /// The quality from 1 to 100 at which JPEG images in the PDF are encoded
+    #[arg(
+        long = "jpeg-quality",
+        value_name = "QUALITY",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+    )]
+    pub jpeg_quality: Option<u8>,
+
+    /// Downsamples images in the PDF whose resolution exceeds this many pixels
+    /// per inch at the largest size they are shown at
+    #[arg(long = "max-image-ppi", value_name = "PPI")]
+    pub max_image_ppi: Option<f32>,
+
+    /// Converts PNG and GIF images in the PDF with more than this many
+    /// megapixels to JPEG
+    #[arg(long = "jpeg-above", value_name = "MEGAPIXELS")]
+    pub jpeg_above: Option<f32>,
+
+    /// Writes the document's speaker notes as JSON to the given file
+    ///
+    /// The file contains a list of objects with the `page` number and the
+    /// `text` of each note, for use in presenter tools.
+    #[arg(long = "notes", value_name = "OUTPUT_JSON")]
+    pub notes: Option<PathBuf>,
+
+    /// Writes a reference manifest of the document as JSON to the given file
+    ///
+    /// The manifest lists the document's referenceable labels with their
+    /// numbers, so that other documents can reference them with
+    /// `ref(.., from: ..)`.
+    #[arg(long = "refs", value_name = "OUTPUT_JSON")]
+    pub refs: Option<PathBuf>,
+
     /// The PPI (pixels per inch) to use for PNG export
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
 
+    /// Leaves the background of PNG exports transparent instead of white
+    #[arg(long = "transparent")]
+    pub transparent: bool,
+
+    /// Snaps glyphs to whole pixels in PNG exports for crisper text
+    #[arg(long = "snap-text")]
+    pub snap_text: bool,
+
+    /// Only exports the element with the given label (PNG only)
+    #[arg(long = "element", value_name = "LABEL")]
+    pub element: Option<String>,
+
+    /// Caches compilation results in the given directory, to skip compiling
+    /// again when nothing changed
+    ///
+    /// If none of the files that the document read changed since the last
+    /// compilation with the same arguments and fonts, the existing output is
+    /// kept and the warnings of that compilation and its export are printed
+    /// again. Otherwise, the document is compiled from scratch. Only
+    /// compilations that write nothing but a single unencrypted PDF, DOCX,
+    /// EPUB, or JSON file and don't depend on the current date are cached. Has
+    /// no effect in watch mode.
+    #[arg(long = "cache-dir", env = "TYPST_CACHE_DIR", value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
     /// Produces performance timings of the compilation process (experimental)
     ///
     /// The resulting JSON file can be loaded into a tracing tool such as
     /// https://ui.perfetto.dev. It does not contain any sensitive information
-    /// apart from file names and line numbers.
+    /// apart from file names and line numbers. If the path ends with
+    /// `.folded`, the timings are instead written as folded stacks for
+    /// flamegraph tools.
     #[arg(long = "timings", value_name = "OUTPUT_JSON")]
     pub timings: Option<Option<PathBuf>>,
 }
@@ -107,6 +262,10 @@ pub struct QueryCommand {
     #[clap(long = "one", default_value = "false")]
     pub one: bool,
 
+    /// Includes the page and position of each element
+    #[clap(long = "positions", default_value = "false")]
+    pub positions: bool,
+
     /// The format to serialize in
     #[clap(long = "format", default_value = "json")]
     pub format: SerializationFormat,
@@ -148,6 +307,11 @@ pub struct SharedArgs {
     )]
     pub font_paths: Vec<PathBuf>,
 
+    /// Permits the document to write files with `write` and stores them in
+    /// the given directory
+    #[clap(long = "write-dir", value_name = "DIR")]
+    pub write_dir: Option<PathBuf>,
+
     /// The format to emit diagnostics in
     #[clap(
         long,
@@ -155,6 +319,11 @@ pub struct SharedArgs {
         value_parser = clap::value_parser!(DiagnosticFormat)
     )]
     pub diagnostic_format: DiagnosticFormat,
+
+    /// Warns about content that doesn't fit into the space available to it,
+    /// like lines that are too wide for their paragraph
+    #[arg(long = "warn-overflow")]
+    pub warn_overflow: bool,
 }
 
 /// An input that is either stdin or a real path.
@@ -193,6 +362,11 @@ fn parse_input_pair(raw: &str) -> Result<(String, String), String> {
     Ok((key, val))
 }
 
+/// Parses a selection of pages like `1,3-5,8-`.
+fn parse_page_ranges(raw: &str) -> Result<PageRanges, String> {
+    raw.parse().map_err(|err: ecow::EcoString| err.to_string())
+}
+
 /// Lists all discovered fonts in system and custom font paths
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
@@ -210,11 +384,22 @@ pub struct FontsCommand {
     pub variants: bool,
 }
 
+/// Explains a diagnostic code in detail
+#[derive(Debug, Clone, Parser)]
+pub struct ExplainCommand {
+    /// The code to explain, like `E0001`
+    pub code: String,
+}
+
 /// Which format to use for diagnostics.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, ValueEnum)]
 pub enum DiagnosticFormat {
+    /// Messages with excerpts of the source code
     Human,
+    /// One message per line
     Short,
+    /// One JSON object per line, for consumption by other programs
+    Json,
 }
 
 impl Display for DiagnosticFormat {
@@ -243,11 +428,14 @@ pub struct UpdateCommand {
 }
 
 /// Which format to use for the generated output file.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, ValueEnum)]
 pub enum OutputFormat {
     Pdf,
     Png,
     Svg,
+    Docx,
+    Epub,
+    Json,
 }
 
 impl Display for OutputFormat {
@@ -0,0 +1,268 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use codespan_reporting::term::termcolor::{Buffer, WriteColor};
+use comemo::Prehashed;
+use ecow::eco_format;
+use serde::{Deserialize, Serialize};
+use typst::diag::{SourceDiagnostic, StrResult};
+use typst::layout::PageRanges;
+use typst::syntax::VirtualPath;
+use typst::text::FontBook;
+use typst::World;
+
+use crate::args::{CompileCommand, DiagnosticFormat, Input, OutputFormat, SharedArgs};
+use crate::compile::write_diagnostics;
+use crate::terminal;
+use crate::world::SystemWorld;
+
+/// Remembers which files a compilation read, so that a later compilation with
+/// the same arguments can be skipped if none of them changed.
+///
+/// The in-memory caches of the compiler can't be persisted as they refer to
+/// live data, so this works at the level of whole documents: An entry either
+/// allows to skip the compilation entirely or it doesn't help at all. Even a
+/// one-character edit means compiling from scratch. Use `typst watch` to
+/// recompile incrementally.
+pub struct BuildCache {
+    /// The path of the entry for the compilation.
+    path: PathBuf,
+    /// The path of the compilation's output file.
+    output: PathBuf,
+    /// The format in which diagnostics are printed.
+    diagnostic_format: DiagnosticFormat,
+}
+
+/// The arguments and environment of a compilation that affect its output or
+/// the warnings printed for it. Its hash names the cache entry.
+#[derive(Hash)]
+struct Key<'a> {
+    /// The version of the compiler.
+    version: &'static str,
+    /// The working directory, against which the paths are resolved.
+    dir: PathBuf,
+    /// The project root.
+    root: &'a Path,
+    /// The main file within the project.
+    main: &'a VirtualPath,
+    /// The values of `sys.inputs`.
+    inputs: &'a [(String, String)],
+    /// The available fonts.
+    fonts: &'a Prehashed<FontBook>,
+    /// The output file.
+    output: &'a Path,
+    /// The output format.
+    format: OutputFormat,
+    /// The creation timestamp given on the command line.
+    creation_timestamp: Option<i64>,
+    /// The exported pages.
+    pages: Option<&'a PageRanges>,
+    /// The PDF export options, with floats by their bits.
+    no_embed_fonts: &'a [String],
+    allow_launch: bool,
+    strip_script_links: bool,
+    validate: bool,
+    grayscale: bool,
+    ink_limit: Option<u32>,
+    jpeg_quality: Option<u8>,
+    max_image_ppi: Option<u32>,
+    jpeg_above: Option<u32>,
+    /// How the stored warnings are printed.
+    diagnostic_format: DiagnosticFormat,
+    warn_overflow: bool,
+    color: bool,
+}
+
+/// What is stored for a compilation.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// The files that the compilation tried to read with the fingerprints of
+    /// their contents, or `None` for files that didn't exist or couldn't be
+    /// read.
+    dependencies: Vec<(PathBuf, Option<String>)>,
+    /// The fingerprint of the output file.
+    output: String,
+    /// The warnings of the compilation, as they were printed.
+    warnings: String,
+}
+
+impl BuildCache {
+    /// Open the cache entry for a compilation command.
+    ///
+    /// Returns `None` if no cache directory was given or if the command's
+    /// results can't be cached.
+    pub fn new(world: &SystemWorld, command: &CompileCommand) -> Option<Self> {
+        // Destructured without `..`, so that no argument can be added without
+        // deciding whether it affects the output.
+        let CompileCommand {
+            common,
+            output: _,
+            format: _,
+            open: _,
+            creation_timestamp,
+            user_password: _,
+            owner_password: _,
+            no_print: _,
+            no_copy: _,
+            no_modify: _,
+            pages,
+            no_embed_fonts,
+            allow_launch,
+            strip_script_links,
+            validate,
+            grayscale,
+            ink_limit,
+            jpeg_quality,
+            max_image_ppi,
+            jpeg_above,
+            notes,
+            refs,
+            ppi: _,
+            transparent: _,
+            snap_text: _,
+            element: _,
+            cache_dir,
+            timings,
+        } = command;
+        let SharedArgs {
+            input,
+            root: _,
+            inputs,
+            font_paths: _,
+            write_dir: _,
+            diagnostic_format,
+            warn_overflow,
+        } = common;
+
+        let dir = cache_dir.as_ref()?;
+        let output = command.output();
+        let format = command.output_format().ok()?;
+
+        // Only single output files can be checked for freshness. Encrypted
+        // PDFs are salted randomly anyway and their passwords must not
+        // influence anything that ends up on disk.
+        if !matches!(
+            format,
+            OutputFormat::Pdf
+                | OutputFormat::Docx
+                | OutputFormat::Epub
+                | OutputFormat::Json
+        ) || output.to_string_lossy().contains("{n}")
+            || matches!(input, Input::Stdin)
+            || command.encryption().is_some()
+            || notes.is_some()
+            || refs.is_some()
+            || timings.is_some()
+        {
+            return None;
+        }
+
+        let key = Key {
+            version: env!("CARGO_PKG_VERSION"),
+            dir: std::env::current_dir().ok()?,
+            root: world.root(),
+            main: world.main().vpath(),
+            inputs,
+            fonts: world.book(),
+            output: &output,
+            format,
+            creation_timestamp: *creation_timestamp,
+            pages: pages.as_ref(),
+            no_embed_fonts,
+            allow_launch: *allow_launch,
+            strip_script_links: *strip_script_links,
+            validate: *validate,
+            grayscale: *grayscale,
+            ink_limit: ink_limit.map(f32::to_bits),
+            jpeg_quality: *jpeg_quality,
+            max_image_ppi: max_image_ppi.map(f32::to_bits),
+            jpeg_above: jpeg_above.map(f32::to_bits),
+            diagnostic_format: *diagnostic_format,
+            warn_overflow: *warn_overflow,
+            color: terminal::out().supports_color(),
+        };
+
+        Some(Self {
+            path: dir.join(format!("{:032x}.json", typst::util::hash128(&key))),
+            output,
+            diagnostic_format: *diagnostic_format,
+        })
+    }
+
+    /// Returns the warnings of the last compilation, ready to be printed, if
+    /// its output is still up to date.
+    pub fn lookup(&self) -> Option<String> {
+        let data = fs::read(&self.path).ok()?;
+        let entry = serde_json::from_slice::<Entry>(&data).ok()?;
+        let fresh = fingerprint(&self.output)
+            .is_some_and(|output| output == entry.output)
+            && entry
+                .dependencies
+                .iter()
+                .all(|(path, hash)| fingerprint(path) == *hash);
+        fresh.then_some(entry.warnings)
+    }
+
+    /// Record a successful compilation and the warnings it produced.
+    ///
+    /// Compilations that depend on the current date are not recorded, as they
+    /// are never up to date.
+    pub fn store(
+        &self,
+        world: &mut SystemWorld,
+        warnings: &[SourceDiagnostic],
+    ) -> StrResult<()> {
+        if world.accessed_now() {
+            return self.clear();
+        }
+
+        let Some(output) = fingerprint(&self.output) else { return self.clear() };
+
+        let mut buffer = if terminal::out().supports_color() {
+            Buffer::ansi()
+        } else {
+            Buffer::no_color()
+        };
+        write_diagnostics(&mut buffer, world, &[], warnings, self.diagnostic_format)
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        let warnings = String::from_utf8_lossy(buffer.as_slice()).into_owned();
+
+        // Files that couldn't be read are recorded, too, so that the entry is
+        // invalidated once they appear.
+        let dependencies = world
+            .dependencies()
+            .map(|path| {
+                let hash = fingerprint(&path);
+                (path, hash)
+            })
+            .collect();
+
+        let entry = Entry { dependencies, output, warnings };
+        let data = serde_json::to_vec(&entry)
+            .map_err(|err| eco_format!("failed to serialize cache entry ({err})"))?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| eco_format!("failed to create cache directory ({err})"))?;
+        }
+
+        fs::write(&self.path, data)
+            .map_err(|err| eco_format!("failed to write cache entry ({err})"))
+    }
+
+    /// Remove the entry, so that the next compilation isn't skipped.
+    pub fn clear(&self) -> StrResult<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(eco_format!("failed to remove cache entry ({err})"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A fingerprint of a file's contents.
+fn fingerprint(path: &Path) -> Option<String> {
+    let data = fs::read(path).ok()?;
+    Some(format!("{:032x}", typst::util::hash128(&data)))
+}
@@ -1,22 +1,32 @@
 use std::fs;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
 use chrono::{Datelike, Timelike};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term;
-use ecow::{eco_format, EcoString};
+use codespan_reporting::term::termcolor::WriteColor;
+use ecow::{eco_format, EcoString, EcoVec};
 use parking_lot::RwLock;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use typst::diag::{bail, At, Severity, SourceDiagnostic, StrResult};
+use serde::Serialize;
+use typst::diag::{
+    bail, At, Fix, ResolvedDiagnostic, Severity, SourceDiagnostic, StrResult,
+};
 use typst::eval::Tracer;
-use typst::foundations::Datetime;
-use typst::layout::Frame;
+use typst::foundations::{Bytes, Datetime, Label as TypstLabel};
+use typst::layout::{Frame, PageRanges};
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
 use typst::visualize::Color;
 use typst::{World, WorldExt};
+use typst_pdf::{
+    ColorConversion, ImageCompression, PdfEncryption, PdfOptions, PdfPermissions,
+};
 
 use crate::args::{CompileCommand, DiagnosticFormat, Input, OutputFormat};
+use crate::cache::BuildCache;
 use crate::timings::Timer;
 use crate::watch::Status;
 use crate::world::SystemWorld;
@@ -37,11 +47,65 @@ impl CompileCommand {
                     OutputFormat::Pdf => "pdf",
                     OutputFormat::Png => "png",
                     OutputFormat::Svg => "svg",
+                    OutputFormat::Docx => "docx",
+                    OutputFormat::Epub => "epub",
+                    OutputFormat::Json => "json",
                 },
             )
         })
     }
 
+    /// The encryption settings for PDF export, if any were requested.
+    pub fn encryption(&self) -> Option<PdfEncryption> {
+        if self.user_password.is_none()
+            && self.owner_password.is_none()
+            && !self.no_print
+            && !self.no_copy
+            && !self.no_modify
+        {
+            return None;
+        }
+
+        Some(PdfEncryption {
+            user_password: self.user_password.clone().unwrap_or_default(),
+            owner_password: self.owner_password.clone(),
+            permissions: PdfPermissions {
+                print: !self.no_print,
+                copy: !self.no_copy,
+                modify: !self.no_modify,
+                ..PdfPermissions::default()
+            },
+        })
+    }
+
+    /// The color conversion to apply during PDF export, if any.
+    pub fn color_conversion(&self) -> Option<ColorConversion> {
+        if self.grayscale {
+            Some(ColorConversion::Grayscale)
+        } else {
+            self.ink_limit
+                .map(|percent| ColorConversion::InkLimit(percent / 100.0))
+        }
+    }
+
+    /// The settings for re-encoding raster images during PDF export.
+    pub fn image_compression(&self) -> ImageCompression {
+        ImageCompression {
+            jpeg_quality: self.jpeg_quality,
+            max_ppi: self.max_image_ppi,
+            jpeg_above: self
+                .jpeg_above
+                .map(|megapixels| (f64::from(megapixels) * 1e6) as u64),
+        }
+    }
+
+    /// Whether the page with the given index should be exported.
+    pub fn exports_page(&self, index: usize) -> bool {
+        self.pages
+            .as_ref()
+            .map_or(true, |pages| pages.includes_page_index(index))
+    }
+
     /// The format to use for generated output, either specified by the user or inferred from the extension.
     ///
     /// Will return `Err` if the format was not specified and could not be inferred.
@@ -53,6 +117,9 @@ impl CompileCommand {
                 Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
                 Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
                 Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
+                Some(ext) if ext.eq_ignore_ascii_case("docx") => OutputFormat::Docx,
+                Some(ext) if ext.eq_ignore_ascii_case("epub") => OutputFormat::Epub,
+                Some(ext) if ext.eq_ignore_ascii_case("json") => OutputFormat::Json,
                 _ => bail!("could not infer output format for path {}.\nconsider providing the format manually with `--format/-f`", output.display()),
             }
         } else {
@@ -95,14 +162,38 @@ pub fn compile_once(
         return Ok(());
     }
 
+    // Skip the compilation if its output is still up to date.
+    let cache = if watching { None } else { BuildCache::new(world, command) };
+    if let Some(warnings) = cache.as_ref().and_then(BuildCache::lookup) {
+        terminal::out()
+            .write_all(warnings.as_bytes())
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        if let Some(open) = command.open.take() {
+            open_file(open.as_deref(), &command.output())?;
+        }
+        return Ok(());
+    }
+
     let mut tracer = Tracer::new();
+    if command.common.warn_overflow {
+        tracer.warn_overflow();
+    }
     let result = typst::compile(world, &mut tracer);
-    let warnings = tracer.warnings();
+    let outputs = tracer.outputs();
+    let mut warnings = tracer.warnings();
 
     match result {
         // Export the PDF / PNG.
         Ok(document) => {
-            export(world, &document, command, watching)?;
+            warnings.extend(export(world, &document, command, watching)?);
+            write_outputs(world, &outputs)?;
+            if let Some(cache) = &cache {
+                if outputs.is_empty() {
+                    cache.store(world, &warnings)?;
+                } else {
+                    cache.clear()?;
+                }
+            }
             let duration = start.elapsed();
 
             if watching {
@@ -142,41 +233,225 @@ pub fn compile_once(
     Ok(())
 }
 
+/// Store the files that the document wrote with `write`.
+fn write_outputs(world: &SystemWorld, outputs: &[(FileId, Bytes)]) -> StrResult<()> {
+    let Some(dir) = world.write_dir() else { return Ok(()) };
+    for (id, data) in outputs {
+        let path = id.vpath().resolve(dir).ok_or_else(|| {
+            eco_format!("cannot write file outside of {}", dir.display())
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| eco_format!("failed to create directory ({err})"))?;
+        }
+        fs::write(&path, data).map_err(|err| {
+            eco_format!("failed to write file {} ({err})", path.display())
+        })?;
+    }
+    Ok(())
+}
+
 /// Export into the target format.
+///
+/// Returns the warnings of the export.
 fn export(
     world: &mut SystemWorld,
     document: &Document,
     command: &CompileCommand,
     watching: bool,
-) -> StrResult<()> {
+) -> StrResult<EcoVec<SourceDiagnostic>> {
+    if let Some(path) = &command.notes {
+        export_notes(document, path)?;
+    }
+
+    if let Some(path) = &command.refs {
+        export_refs(world, document, path)?;
+    }
+
     match command.output_format()? {
         OutputFormat::Png => {
-            export_image(world, document, command, watching, ImageExportFormat::Png)
+            export_image(world, document, command, watching, ImageExportFormat::Png)?
         }
         OutputFormat::Svg => {
-            export_image(world, document, command, watching, ImageExportFormat::Svg)
+            export_image(world, document, command, watching, ImageExportFormat::Svg)?
         }
-        OutputFormat::Pdf => export_pdf(document, command, world),
+        OutputFormat::Pdf => return export_pdf(document, command, world),
+        OutputFormat::Docx => export_docx(document, command)?,
+        OutputFormat::Epub => export_epub(document, command, world)?,
+        OutputFormat::Json => export_json(document, command)?,
     }
+
+    Ok(EcoVec::new())
+}
+
+/// Export to a DOCX file.
+fn export_docx(document: &Document, command: &CompileCommand) -> StrResult<()> {
+    let buffer = typst_docx::docx(document)?;
+    fs::write(command.output(), buffer)
+        .map_err(|err| eco_format!("failed to write DOCX file ({err})"))
+}
+
+/// Export to an EPUB file.
+fn export_epub(
+    document: &Document,
+    command: &CompileCommand,
+    world: &SystemWorld,
+) -> StrResult<()> {
+    let ident = ident(world);
+    let buffer =
+        typst_epub::epub(document, ident.as_deref(), creation_timestamp(command)?)?;
+    fs::write(command.output(), buffer)
+        .map_err(|err| eco_format!("failed to write EPUB file ({err})"))
+}
+
+/// Export to a JSON file.
+fn export_json(document: &Document, command: &CompileCommand) -> StrResult<()> {
+    let json = typst_json::json(document)?;
+    fs::write(command.output(), json)
+        .map_err(|err| eco_format!("failed to write JSON file ({err})"))
+}
+
+/// Export the speaker notes to a JSON file.
+fn export_notes(document: &Document, path: &Path) -> StrResult<()> {
+    #[derive(Serialize)]
+    struct Note<'a> {
+        page: usize,
+        text: &'a str,
+    }
+
+    let notes = document.speaker_notes();
+    let notes: Vec<_> = notes
+        .iter()
+        .map(|note| Note { page: note.page.get(), text: &note.text })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&notes)
+        .map_err(|err| eco_format!("failed to serialize speaker notes ({err})"))?;
+    fs::write(path, json)
+        .map_err(|err| eco_format!("failed to write speaker notes ({err})"))?;
+    Ok(())
+}
+
+/// Export the reference manifest to a JSON file.
+fn export_refs(world: &SystemWorld, document: &Document, path: &Path) -> StrResult<()> {
+    let manifest = document.reference_manifest(world).map_err(|errors| {
+        let message = errors.first().map(|error| error.message.clone());
+        eco_format!(
+            "failed to create reference manifest ({})",
+            message.unwrap_or_default()
+        )
+    })?;
+
+    fs::write(path, manifest.to_json()?)
+        .map_err(|err| eco_format!("failed to write reference manifest ({err})"))
 }
 
 /// Export to a PDF.
+///
+/// Returns the warnings of the validation or about unembedded fonts.
 fn export_pdf(
     document: &Document,
     command: &CompileCommand,
     world: &SystemWorld,
-) -> StrResult<()> {
-    let ident = world.input().map(|i| i.to_string_lossy());
-    let buffer = typst_pdf::pdf(document, ident.as_deref(), now());
+) -> StrResult<EcoVec<SourceDiagnostic>> {
+    let ident = ident(world);
+    let options = PdfOptions {
+        ident: ident.as_deref(),
+        timestamp: creation_timestamp(command)?,
+        encryption: command.encryption(),
+        page_ranges: command.pages.clone(),
+        unembedded_fonts: command
+            .no_embed_fonts
+            .iter()
+            .map(|family| family.as_str().into())
+            .collect(),
+        color_conversion: command.color_conversion(),
+        allow_launch: command.allow_launch,
+        strip_script_links: command.strip_script_links,
+        image_compression: command.image_compression(),
+        cancellation: None,
+    };
+
     let output = command.output();
-    fs::write(output, buffer)
-        .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
-    Ok(())
+    let string = output.to_str().unwrap_or_default();
+    if !string.contains("{n}") {
+        let file = fs::File::create(output)
+            .map_err(|err| eco_format!("failed to create PDF file ({err})"))?;
+        typst_pdf::pdf_into(document, &options, io::BufWriter::new(file))?;
+    } else {
+        // Write each page into a file of its own.
+        let mut options = options.clone();
+        let width = page_number_width(document);
+        for i in (0..document.pages.len()).filter(|&i| command.exports_page(i)) {
+            let number = NonZeroUsize::new(i + 1).unwrap();
+            options.page_ranges =
+                Some(PageRanges::new(vec![Some(number)..=Some(number)]));
+            let buffer = typst_pdf::pdf_with(document, &options)?;
+            let path = string.replace("{n}", &format!("{:0width$}", i + 1));
+            fs::write(path, buffer)
+                .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
+        }
+    }
+
+    // The validator reports unembedded fonts where they are used. Otherwise,
+    // not embedding fonts is a deliberate choice, but the result must never
+    // be mistaken for a portable file.
+    Ok(if command.validate {
+        typst_pdf::validate(document, &options)
+    } else {
+        command
+            .no_embed_fonts
+            .iter()
+            .map(|family| {
+                SourceDiagnostic::warning(
+                    Span::detached(),
+                    eco_format!("font family `{family}` is not embedded into the PDF"),
+                )
+                .with_hint(
+                    "the PDF only displays correctly where this exact font is installed",
+                )
+            })
+            .collect()
+    })
+}
+
+/// Find a number width that accommodates all pages. For instance, the first
+/// page should be numbered "001" if there are between 100 and 999 pages.
+fn page_number_width(document: &Document) -> usize {
+    1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize
+}
+
+/// The identifier of the document for PDF and EPUB export.
+///
+/// Consists of the name of the project root and the path of the main file
+/// relative to it. This distinguishes documents with the same file name in
+/// different projects without depending on where a project is on disk.
+fn ident(world: &SystemWorld) -> Option<String> {
+    world.input()?;
+    let root = world.root().file_name().unwrap_or_default().to_string_lossy();
+    let path = world.main().vpath().as_rootless_path().to_string_lossy();
+    Some(format!("{root}/{path}"))
+}
+
+/// The creation timestamp given on the command line, or else the current
+/// time.
+fn creation_timestamp(command: &CompileCommand) -> StrResult<Option<Datetime>> {
+    Ok(match command.creation_timestamp {
+        Some(secs) => convert_datetime(
+            chrono::NaiveDateTime::from_timestamp_opt(secs, 0)
+                .ok_or("creation timestamp is out of range")?,
+        ),
+        None => now(),
+    })
 }
 
 /// Get the current date and time in UTC.
 fn now() -> Option<Datetime> {
-    let now = chrono::Local::now().naive_utc();
+    convert_datetime(chrono::Local::now().naive_utc())
+}
+
+/// Convert a chrono datetime to a Typst datetime.
+fn convert_datetime(now: chrono::NaiveDateTime) -> Option<Datetime> {
     Datetime::from_ymd_hms(
         now.year(),
         now.month().try_into().ok()?,
@@ -201,18 +476,37 @@ fn export_image(
     watching: bool,
     fmt: ImageExportFormat,
 ) -> StrResult<()> {
-    // Determine whether we have a `{n}` numbering.
     let output = command.output();
+    let options = typst_render::RenderOptions {
+        pixel_per_pt: command.ppi / 72.0,
+        fill: (!command.transparent).then_some(Color::WHITE),
+        snap_text: command.snap_text,
+    };
+
+    if let Some(label) = &command.element {
+        let ImageExportFormat::Png = fmt else {
+            bail!("exporting a single element is only supported for PNG");
+        };
+        let elem = document.introspector.query_label(TypstLabel::new(label.as_str()))?;
+        let pixmap = elem
+            .location()
+            .and_then(|loc| typst_render::render_element(document, loc, &options))
+            .ok_or_else(|| eco_format!("element <{label}> was not laid out"))?;
+        pixmap
+            .save_png(&output)
+            .map_err(|err| eco_format!("failed to write PNG file ({err})"))?;
+        return Ok(());
+    }
+
+    // Determine whether we have a `{n}` numbering.
     let string = output.to_str().unwrap_or_default();
     let numbered = string.contains("{n}");
-    if !numbered && document.pages.len() > 1 {
+    let exported = (0..document.pages.len()).filter(|&i| command.exports_page(i)).count();
+    if !numbered && exported > 1 {
         bail!("cannot export multiple images without `{{n}}` in output path");
     }
 
-    // Find a number width that accommodates all pages. For instance, the
-    // first page should be numbered "001" if there are between 100 and
-    // 999 pages.
-    let width = 1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize;
+    let width = page_number_width(document);
 
     let cache = world.export_cache();
 
@@ -221,6 +515,7 @@ fn export_image(
         .pages
         .par_iter()
         .enumerate()
+        .filter(|&(i, _)| command.exports_page(i))
         .map(|(i, page)| {
             let storage;
             let path = if numbered {
@@ -239,11 +534,7 @@ fn export_image(
 
             match fmt {
                 ImageExportFormat::Png => {
-                    let pixmap = typst_render::render(
-                        &page.frame,
-                        command.ppi / 72.0,
-                        Color::WHITE,
-                    );
+                    let pixmap = typst_render::render_with(&page.frame, &options);
                     pixmap
                         .save_png(path)
                         .map_err(|err| eco_format!("failed to write PNG file ({err})"))?;
@@ -315,13 +606,33 @@ pub fn print_diagnostics(
     warnings: &[SourceDiagnostic],
     diagnostic_format: DiagnosticFormat,
 ) -> Result<(), codespan_reporting::files::Error> {
+    write_diagnostics(&mut terminal::out(), world, errors, warnings, diagnostic_format)
+}
+
+/// Write diagnostic messages to an optionally colored output.
+pub fn write_diagnostics(
+    out: &mut impl WriteColor,
+    world: &SystemWorld,
+    errors: &[SourceDiagnostic],
+    warnings: &[SourceDiagnostic],
+    diagnostic_format: DiagnosticFormat,
+) -> Result<(), codespan_reporting::files::Error> {
+    if diagnostic_format == DiagnosticFormat::Json {
+        for diagnostic in warnings.iter().chain(errors) {
+            let resolved = ResolvedDiagnostic::new(world, diagnostic);
+            serde_json::to_writer(&mut *out, &resolved).map_err(io::Error::from)?;
+            writeln!(out)?;
+        }
+        return Ok(());
+    }
+
     let mut config = term::Config { tab_width: 2, ..Default::default() };
     if diagnostic_format == DiagnosticFormat::Short {
         config.display_style = term::DisplayStyle::Short;
     }
 
     for diagnostic in warnings.iter().chain(errors) {
-        let diag = match diagnostic.severity {
+        let mut diag = match diagnostic.severity {
             Severity::Error => Diagnostic::error(),
             Severity::Warning => Diagnostic::warning(),
         }
@@ -331,11 +642,26 @@ pub fn print_diagnostics(
                 .hints
                 .iter()
                 .map(|e| (eco_format!("hint: {e}")).into())
+                .chain(diagnostic.fixes.iter().filter_map(|fix| fix_note(world, fix)))
                 .collect(),
         )
-        .with_labels(label(world, diagnostic.span).into_iter().collect());
+        .with_labels(
+            label(world, diagnostic.span)
+                .into_iter()
+                .chain(diagnostic.related.iter().filter_map(|related| {
+                    Some(
+                        secondary_label(world, related.span)?
+                            .with_message(related.v.as_str()),
+                    )
+                }))
+                .collect(),
+        );
+
+        if let Some(code) = diagnostic.code {
+            diag = diag.with_code(code);
+        }
 
-        term::emit(&mut terminal::out(), &config, world, &diag)?;
+        term::emit(out, &config, world, &diag)?;
 
         // Stacktrace-like helper diagnostics.
         for point in &diagnostic.trace {
@@ -344,7 +670,7 @@ pub fn print_diagnostics(
                 .with_message(message)
                 .with_labels(label(world, point.span).into_iter().collect());
 
-            term::emit(&mut terminal::out(), &config, world, &help)?;
+            term::emit(out, &config, world, &help)?;
         }
     }
 
@@ -356,6 +682,22 @@ fn label(world: &SystemWorld, span: Span) -> Option<Label<FileId>> {
     Some(Label::primary(span.id()?, world.range(span)?))
 }
 
+/// Describe a fix as a note, like ``fix: replace `Arial` with `"Arial"` ``.
+fn fix_note(world: &SystemWorld, fix: &Fix) -> Option<String> {
+    let source = world.source(fix.span.id()?).ok()?;
+    let code = source.get(source.range(fix.span)?)?;
+    Some(if fix.replacement.is_empty() {
+        format!("fix: remove `{code}`")
+    } else {
+        format!("fix: replace `{code}` with `{}`", fix.replacement)
+    })
+}
+
+/// Create a secondary label for a span.
+fn secondary_label(world: &SystemWorld, span: Span) -> Option<Label<FileId>> {
+    Some(Label::secondary(span.id()?, world.range(span)?))
+}
+
 impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
     type FileId = FileId;
     type Name = String;
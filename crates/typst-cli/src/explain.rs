@@ -0,0 +1,13 @@
+use typst::diag::{bail, StrResult};
+
+use crate::args::ExplainCommand;
+
+/// Execute an explain command.
+pub fn explain(command: &ExplainCommand) -> StrResult<()> {
+    let Some(explanation) = typst::diag::explain(&command.code) else {
+        bail!("unknown diagnostic code: {}", command.code);
+    };
+
+    println!("{explanation}");
+    Ok(())
+}
@@ -1,6 +1,8 @@
 mod args;
+mod cache;
 mod compile;
 mod download;
+mod explain;
 mod fonts;
 mod package;
 mod query;
@@ -41,6 +43,7 @@ fn main() -> ExitCode {
         Command::Watch(command) => crate::watch::watch(timer, command.clone()),
         Command::Query(command) => crate::query::query(command),
         Command::Fonts(command) => crate::fonts::fonts(command),
+        Command::Explain(command) => crate::explain::explain(command),
         Command::Update(command) => crate::update::update(command),
     };
 
@@ -6,7 +6,7 @@ use codespan_reporting::term::{self, termcolor};
 use ecow::eco_format;
 use termcolor::WriteColor;
 use typst::diag::{PackageError, PackageResult};
-use typst::syntax::PackageSpec;
+use typst::syntax::{PackageSpec, PackageVersion};
 
 use crate::download::download_with_progress;
 use crate::terminal;
@@ -28,7 +28,10 @@ pub fn prepare_package(spec: &PackageSpec) -> PackageResult<PathBuf> {
 
         // Download from network if it doesn't exist yet.
         if spec.namespace == "preview" && !dir.exists() {
-            download_package(spec, &dir)?;
+            match download_package(spec, &dir) {
+                Err(PackageError::NotFound(_)) => {}
+                result => result?,
+            }
         }
 
         if dir.exists() {
@@ -36,9 +39,26 @@ pub fn prepare_package(spec: &PackageSpec) -> PackageResult<PathBuf> {
         }
     }
 
+    // Point to other versions of the package if there are any.
+    if let Some(latest) = latest_local_version(spec) {
+        return Err(PackageError::VersionNotFound(spec.clone(), latest));
+    }
+
     Err(PackageError::NotFound(spec.clone()))
 }
 
+/// Find the latest version of a package that is available on disk.
+fn latest_local_version(spec: &PackageSpec) -> Option<PackageVersion> {
+    let subdir = format!("typst/packages/{}/{}", spec.namespace, spec.name);
+    [dirs::data_dir(), dirs::cache_dir()]
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| fs::read_dir(dir.join(&subdir)).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .max()
+}
+
 /// Download a package over the network.
 fn download_package(spec: &PackageSpec, package_dir: &Path) -> PackageResult<()> {
     // The `@preview` namespace is the only namespace that supports on-demand
@@ -1,11 +1,9 @@
-use comemo::Track;
-use ecow::{eco_format, EcoString};
+use ecow::eco_format;
 use serde::Serialize;
 use typst::diag::{bail, StrResult};
-use typst::eval::{eval_string, EvalMode, Tracer};
-use typst::foundations::{Content, IntoValue, LocatableSelector, Scope};
-use typst::model::Document;
-use typst::syntax::Span;
+use typst::eval::Tracer;
+use typst::foundations::IntoValue;
+use typst::introspection::QueryMatch;
 use typst::World;
 
 use crate::args::{QueryCommand, SerializationFormat};
@@ -21,14 +19,17 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     world.source(world.main()).map_err(|err| err.to_string())?;
 
     let mut tracer = Tracer::new();
+    if command.common.warn_overflow {
+        tracer.warn_overflow();
+    }
     let result = typst::compile(&world, &mut tracer);
     let warnings = tracer.warnings();
 
     match result {
         // Retrieve and print query results.
         Ok(document) => {
-            let data = retrieve(&world, command, &document)?;
-            let serialized = format(data, command)?;
+            let matches = typst::query(&world, &document, &command.selector)?;
+            let serialized = format(matches, command)?;
             println!("{serialized}");
             print_diagnostics(&world, &[], &warnings, command.common.diagnostic_format)
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
@@ -50,48 +51,25 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     Ok(())
 }
 
-/// Retrieve the matches for the selector.
-fn retrieve(
-    world: &dyn World,
-    command: &QueryCommand,
-    document: &Document,
-) -> StrResult<Vec<Content>> {
-    let selector = eval_string(
-        world.track(),
-        &command.selector,
-        Span::detached(),
-        EvalMode::Code,
-        Scope::default(),
-    )
-    .map_err(|errors| {
-        let mut message = EcoString::from("failed to evaluate selector");
-        for (i, error) in errors.into_iter().enumerate() {
-            message.push_str(if i == 0 { ": " } else { ", " });
-            message.push_str(&error.message);
-        }
-        message
-    })?
-    .cast::<LocatableSelector>()?;
-
-    Ok(document
-        .introspector
-        .query(&selector.0)
-        .into_iter()
-        .map(|x| x.into_inner())
-        .collect::<Vec<_>>())
-}
-
 /// Format the query result in the output format.
-fn format(elements: Vec<Content>, command: &QueryCommand) -> StrResult<String> {
-    if command.one && elements.len() != 1 {
-        bail!("expected exactly one element, found {}", elements.len());
+fn format(matches: Vec<QueryMatch>, command: &QueryCommand) -> StrResult<String> {
+    if command.one && matches.len() != 1 {
+        bail!("expected exactly one element, found {}", matches.len());
+    }
+
+    if command.positions && command.field.is_none() {
+        return if command.one {
+            serialize(&matches[0], command.format)
+        } else {
+            serialize(&matches, command.format)
+        };
     }
 
-    let mapped: Vec<_> = elements
+    let mapped: Vec<_> = matches
         .into_iter()
-        .filter_map(|c| match &command.field {
-            Some(field) => c.get_by_name(field),
-            _ => Some(c.into_value()),
+        .filter_map(|m| match &command.field {
+            Some(field) => m.element.get_by_name(field),
+            _ => Some(m.element.into_value()),
         })
         .collect();
 
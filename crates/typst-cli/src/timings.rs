@@ -71,9 +71,13 @@ impl Timer {
             File::create(path).map_err(|e| format!("failed to create file: {e}"))?;
         let writer = BufWriter::with_capacity(1 << 20, file);
 
-        typst_timing::export_json(writer, |span| {
-            resolve_span(world, span).unwrap_or_else(|| ("unknown".to_string(), 0))
-        })?;
+        if path.extension().is_some_and(|ext| ext == "folded") {
+            typst_timing::export_folded(writer)?;
+        } else {
+            typst_timing::export_json(writer, |span| {
+                resolve_span(world, span).unwrap_or_else(|| ("unknown".to_string(), 0))
+            })?;
+        }
 
         Ok(output)
     }
@@ -42,7 +42,7 @@ pub fn watch(mut timer: Timer, mut command: CompileCommand) -> StrResult<()> {
     let timeout = std::time::Duration::from_millis(100);
     let output = command.output();
     while terminal::out().is_active() {
-        let mut recompile = false;
+        let mut changed = vec![];
         if let Ok(event) = rx.recv_timeout(timeout) {
             let event =
                 event.map_err(|err| eco_format!("failed to watch directory ({err})"))?;
@@ -62,12 +62,14 @@ pub fn watch(mut timer: Timer, mut command: CompileCommand) -> StrResult<()> {
                 watcher.unwatch(path).ok();
             }
 
-            recompile |= is_event_relevant(&event, &output);
+            if is_event_relevant(&event, &output) {
+                changed.extend(event.paths);
+            }
         }
 
-        if recompile {
-            // Reset all dependencies.
-            world.reset();
+        if !changed.is_empty() {
+            // Reset the changed dependencies, keeping the others in memory.
+            world.invalidate(changed.iter().map(PathBuf::as_path));
 
             // Recompile.
             timer
@@ -50,6 +50,9 @@ pub struct SystemWorld {
     /// The export cache, used for caching output files in `typst watch`
     /// sessions.
     export_cache: ExportCache,
+    /// The directory in which files written by the document are stored. If
+    /// this is `None`, writing files is not permitted.
+    write_dir: Option<PathBuf>,
 }
 
 impl SystemWorld {
@@ -110,6 +113,7 @@ impl SystemWorld {
             slots: Mutex::new(HashMap::new()),
             now: OnceLock::new(),
             export_cache: ExportCache::new(),
+            write_dir: command.write_dir.clone(),
         })
     }
 
@@ -123,6 +127,12 @@ impl SystemWorld {
         &self.root
     }
 
+    /// The directory in which files written by the document are stored, if
+    /// writing is permitted.
+    pub fn write_dir(&self) -> Option<&Path> {
+        self.write_dir.as_deref()
+    }
+
     /// The current working directory.
     pub fn workdir(&self) -> &Path {
         self.workdir.as_deref().unwrap_or(Path::new("."))
@@ -137,7 +147,14 @@ impl SystemWorld {
             .filter_map(|slot| system_path(&self.root, slot.id).ok())
     }
 
+    /// Whether the last compilation depended on the current date.
+    pub fn accessed_now(&self) -> bool {
+        self.now.get().is_some()
+    }
+
     /// Reset the compilation state in preparation of a new compilation.
+    ///
+    /// All files are read again when the next compilation accesses them.
     pub fn reset(&mut self) {
         for slot in self.slots.get_mut().values_mut() {
             slot.reset();
@@ -145,6 +162,27 @@ impl SystemWorld {
         self.now.take();
     }
 
+    /// Reset the compilation state in preparation of a new compilation, after
+    /// only the files at the given paths changed.
+    ///
+    /// Only these files and those that couldn't be read previously are read
+    /// again when the next compilation accesses them. The others are served
+    /// from memory, so that memoized results which depend on them can be
+    /// reused without touching the disk.
+    pub fn invalidate<'a>(&mut self, paths: impl IntoIterator<Item = &'a Path>) {
+        let paths: Vec<&Path> = paths.into_iter().collect();
+        for slot in self.slots.get_mut().values_mut() {
+            let changed = system_path(&self.root, slot.id)
+                .map_or(true, |path| paths.contains(&path.as_path()));
+            if changed {
+                slot.reset();
+            } else {
+                slot.retain();
+            }
+        }
+        self.now.take();
+    }
+
     /// Return the canonical path to the input file.
     pub fn input(&self) -> Option<&PathBuf> {
         self.input.as_ref()
@@ -201,6 +239,10 @@ impl World for SystemWorld {
             naive.day().try_into().ok()?,
         )
     }
+
+    fn can_write(&self) -> bool {
+        self.write_dir.is_some()
+    }
 }
 
 impl SystemWorld {
@@ -237,13 +279,20 @@ impl FileSlot {
         self.source.accessed() || self.file.accessed()
     }
 
-    /// Marks the file as not yet accessed in preparation of the next
-    /// compilation.
+    /// Marks the file as not yet accessed and as changed in preparation of
+    /// the next compilation.
     fn reset(&mut self) {
         self.source.reset();
         self.file.reset();
     }
 
+    /// Marks the file as not yet accessed, but unchanged, in preparation of
+    /// the next compilation.
+    fn retain(&mut self) {
+        self.source.retain();
+        self.file.retain();
+    }
+
     /// Retrieve the source for this file.
     fn source(&mut self, project_root: &Path) -> FileResult<Source> {
         self.source.get_or_init(
@@ -277,12 +326,19 @@ struct SlotCell<T> {
     fingerprint: u128,
     /// Whether the slot has been accessed in the current compilation.
     accessed: bool,
+    /// Whether the file must be read again on the next access.
+    stale: bool,
 }
 
 impl<T: Clone> SlotCell<T> {
     /// Creates a new, empty cell.
     fn new() -> Self {
-        Self { data: None, fingerprint: 0, accessed: false }
+        Self {
+            data: None,
+            fingerprint: 0,
+            accessed: false,
+            stale: true,
+        }
     }
 
     /// Whether the cell was accessed in the ongoing compilation.
@@ -291,9 +347,19 @@ impl<T: Clone> SlotCell<T> {
     }
 
     /// Marks the cell as not yet accessed in preparation of the next
-    /// compilation.
+    /// compilation. The file is read again on the next access.
     fn reset(&mut self) {
         self.accessed = false;
+        self.stale = true;
+    }
+
+    /// Marks the cell as not yet accessed in preparation of the next
+    /// compilation, but keeps its data unless the file couldn't be read.
+    fn retain(&mut self) {
+        self.accessed = false;
+        if matches!(self.data, Some(Err(_))) {
+            self.stale = true;
+        }
     }
 
     /// Gets the contents of the cell or initialize them.
@@ -302,8 +368,10 @@ impl<T: Clone> SlotCell<T> {
         load: impl FnOnce() -> FileResult<Vec<u8>>,
         f: impl FnOnce(Vec<u8>, Option<T>) -> FileResult<T>,
     ) -> FileResult<T> {
-        // If we accessed the file already in this compilation, retrieve it.
-        if mem::replace(&mut self.accessed, true) {
+        // If we read the file already in this compilation or it is known to
+        // be unchanged, retrieve it.
+        self.accessed = true;
+        if !mem::replace(&mut self.stale, false) {
             if let Some(data) = &self.data {
                 return data.clone();
             }
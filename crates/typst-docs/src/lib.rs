@@ -725,6 +725,8 @@ const TYPE_ORDER: &[&str] = &[
     "bool",
     "int",
     "float",
+    "decimal",
+    "bigint",
     "length",
     "angle",
     "ratio",
@@ -741,9 +743,11 @@ const TYPE_ORDER: &[&str] = &[
     "content",
     "array",
     "dict",
+    "generator",
     "func",
     "args",
     "selector",
+    "styles",
     "location",
     "direction",
     "alignment",
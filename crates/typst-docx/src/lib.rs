@@ -0,0 +1,111 @@
+//! Exporting into DOCX documents.
+
+mod package;
+
+use typst::diag::StrResult;
+use typst::model::Document;
+use typst::visualize::ImageKind;
+
+/// Export a document into a DOCX file.
+///
+/// Since Typst documents are laid out into fixed pages, the export is
+/// best-effort: The structure of the document is recovered from its frames.
+/// Text is grouped into paragraphs with their character formatting, while
+/// headings, tables, and footnotes are recognized through the elements they
+/// were laid out from. Raster images are embedded. Page layout, math, and
+/// graphics are not preserved, and table cells are reduced to plain text.
+///
+/// Returns the raw bytes making up the DOCX package.
+#[typst_macros::time(name = "docx")]
+pub fn docx(document: &Document) -> StrResult<Vec<u8>> {
+    // Older versions of Word can't display SVGs without a raster fallback.
+    let collected = typst_reflow::collect(document, &|image| {
+        !matches!(image.kind(), ImageKind::Svg(_))
+    });
+    package::write_package(document, &collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use typst::eval::Tracer;
+    use typst::foundations::Bytes;
+    use typst::text::Font;
+    use typst::MemoryWorld;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn compile(text: &str) -> Document {
+        let fonts = [
+            &include_bytes!("../../../assets/fonts/LinLibertine_R.ttf")[..],
+            &include_bytes!("../../../assets/fonts/LinLibertine_RB.ttf")[..],
+        ];
+        let world = MemoryWorld::new(text).with_fonts(
+            fonts.map(|data| Font::new(Bytes::from_static(data), 0).unwrap()),
+        );
+        typst::compile(&world, &mut Tracer::new()).unwrap()
+    }
+
+    fn part(document: &Document, name: &str) -> String {
+        let data = docx(document).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut text = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn test_docx_paragraphs() {
+        let document = compile("= Title\nSome *bold* text.\n\nNext.");
+        let xml = part(&document, "word/document.xml");
+        assert!(xml.contains(r#"<w:pPr><w:pStyle w:val="Heading1"/></w:pPr>"#));
+        assert!(xml.contains(r#"<w:t xml:space="preserve">Title</w:t>"#));
+        assert!(xml.contains(r#"<w:b/>"#));
+        assert!(xml.contains(r#"<w:t xml:space="preserve">bold</w:t>"#));
+        assert!(xml.contains(r#"<w:t xml:space="preserve">Next.</w:t>"#));
+        assert_eq!(xml.matches("<w:p>").count() + xml.matches("<w:p ").count(), 3);
+        assert!(xml.contains("<w:sectPr>"));
+    }
+
+    #[test]
+    fn test_docx_table() {
+        let document = compile(
+            "Before\n\
+             #table(columns: 2, table.cell(colspan: 2)[A], [B], [C], [D])",
+        );
+        let xml = part(&document, "word/document.xml");
+        let table = &xml[xml.find("<w:tbl>").unwrap()..xml.find("</w:tbl>").unwrap()];
+        assert_eq!(table.matches("<w:gridCol/>").count(), 2);
+        assert_eq!(table.matches("<w:tr>").count(), 3);
+        assert!(table.contains(concat!(
+            r#"<w:tc><w:tcPr><w:gridSpan w:val="2"/></w:tcPr>"#,
+            r#"<w:p><w:r><w:t xml:space="preserve">A</w:t></w:r></w:p></w:tc>"#,
+        )));
+
+        // The last row is filled up with an empty cell.
+        assert!(table.ends_with("<w:tc><w:p/></w:tc></w:tr>"));
+
+        // The cells are not repeated as paragraphs and the body doesn't end
+        // with the table.
+        assert_eq!(xml.matches(">B<").count(), 1);
+        assert!(xml.contains("</w:tbl><w:p/><w:sectPr>"));
+    }
+
+    #[test]
+    fn test_docx_footnotes() {
+        let document = compile("Text#footnote[Note one] and#footnote[Note two].");
+        let xml = part(&document, "word/document.xml");
+        assert!(xml.contains(r#"<w:footnoteReference w:id="1"/>"#));
+        assert!(xml.contains(r#"<w:footnoteReference w:id="2"/>"#));
+
+        // The entries at the bottom of the page are not part of the body.
+        assert!(!xml.contains("Note one"));
+
+        let notes = part(&document, "word/footnotes.xml");
+        assert!(notes.contains(r#"<w:footnote w:type="separator" w:id="-1">"#));
+        assert!(notes.contains(r#"<w:t xml:space="preserve"> Note one</w:t>"#));
+        assert!(notes.contains(r#"<w:t xml:space="preserve"> Note two</w:t>"#));
+    }
+}
@@ -0,0 +1,661 @@
+use std::fmt::Display;
+use std::io::{Cursor, Write};
+
+use ecow::{eco_format, EcoString};
+use typst::diag::StrResult;
+use typst::layout::Abs;
+use typst::model::Document;
+use typst::visualize::{Image, ImageFormat, RasterFormat};
+use typst_reflow::{Block, Collected, Paragraph, Run, RunStyle, Table};
+use xmlwriter::XmlWriter;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const NS_W: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const NS_R: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_WP: &str =
+    "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing";
+const NS_A: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+const NS_PIC: &str = "http://schemas.openxmlformats.org/drawingml/2006/picture";
+const NS_RELS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const NS_TYPES: &str = "http://schemas.openxmlformats.org/package/2006/content-types";
+const NS_CP: &str =
+    "http://schemas.openxmlformats.org/package/2006/metadata/core-properties";
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+
+/// The font sizes of the heading styles, in points.
+const HEADING_SIZES: [f64; 6] = [20.0, 16.0, 14.0, 13.0, 12.0, 11.0];
+
+/// Write the parts of the package and zip them.
+pub fn write_package(document: &Document, collected: &Collected) -> StrResult<Vec<u8>> {
+    let mut parts = vec![
+        ("[Content_Types].xml".into(), content_types()),
+        ("_rels/.rels".into(), package_relationships()),
+        ("docProps/core.xml".into(), core_properties(document)),
+        ("word/_rels/document.xml.rels".into(), document_relationships(collected)),
+        ("word/document.xml".into(), main_document(document, collected)),
+        ("word/styles.xml".into(), styles()),
+        ("word/footnotes.xml".into(), footnotes(collected)),
+    ];
+
+    for (i, image) in collected.images.iter().enumerate() {
+        let path = eco_format!("word/media/image{}.{}", i + 1, extension(image));
+        parts.push((path, image.data().to_vec()));
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (path, data) in parts {
+        zip.start_file(path.as_str(), options).map_err(failed)?;
+        zip.write_all(&data).map_err(failed)?;
+    }
+
+    Ok(zip.finish().map_err(failed)?.into_inner())
+}
+
+/// Format an error that occurred while zipping.
+fn failed(err: impl Display) -> EcoString {
+    eco_format!("failed to write DOCX package ({err})")
+}
+
+/// Create an XML writer for a part.
+fn writer() -> XmlWriter {
+    XmlWriter::new(xmlwriter::Options {
+        indent: xmlwriter::Indent::None,
+        ..Default::default()
+    })
+}
+
+/// Finish a part, prepending the XML declaration.
+fn finish(xml: XmlWriter) -> Vec<u8> {
+    let mut buf =
+        String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    buf.push('\n');
+    buf.push_str(&xml.end_document());
+    buf.into_bytes()
+}
+
+/// The file extension of a raster image.
+fn extension(image: &Image) -> &'static str {
+    match image.format() {
+        ImageFormat::Raster(RasterFormat::Jpg) => "jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "gif",
+        _ => "png",
+    }
+}
+
+/// Declare the content types of the parts.
+fn content_types() -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("Types");
+    xml.write_attribute("xmlns", NS_TYPES);
+
+    let defaults = [
+        ("rels", "application/vnd.openxmlformats-package.relationships+xml"),
+        ("xml", "application/xml"),
+        ("png", "image/png"),
+        ("jpeg", "image/jpeg"),
+        ("gif", "image/gif"),
+    ];
+    for (extension, content_type) in defaults {
+        xml.start_element("Default");
+        xml.write_attribute("Extension", extension);
+        xml.write_attribute("ContentType", content_type);
+        xml.end_element();
+    }
+
+    let wml = "application/vnd.openxmlformats-officedocument.wordprocessingml";
+    let overrides = [
+        ("/word/document.xml", eco_format!("{wml}.document.main+xml")),
+        ("/word/styles.xml", eco_format!("{wml}.styles+xml")),
+        ("/word/footnotes.xml", eco_format!("{wml}.footnotes+xml")),
+        (
+            "/docProps/core.xml",
+            "application/vnd.openxmlformats-package.core-properties+xml".into(),
+        ),
+    ];
+    for (part, content_type) in overrides {
+        xml.start_element("Override");
+        xml.write_attribute("PartName", part);
+        xml.write_attribute("ContentType", &content_type);
+        xml.end_element();
+    }
+
+    finish(xml)
+}
+
+/// Write a relationships part.
+fn relationships<'a>(
+    rels: impl IntoIterator<Item = (EcoString, &'a str, EcoString)>,
+) -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("Relationships");
+    xml.write_attribute("xmlns", NS_RELS);
+    for (id, kind, target) in rels {
+        xml.start_element("Relationship");
+        xml.write_attribute("Id", &id);
+        xml.write_attribute("Type", kind);
+        xml.write_attribute("Target", &target);
+        xml.end_element();
+    }
+    finish(xml)
+}
+
+/// Relate the package to its main document and properties.
+fn package_relationships() -> Vec<u8> {
+    let rels: [(EcoString, &str, EcoString); 2] = [
+        (
+            "rId1".into(),
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument",
+            "word/document.xml".into(),
+        ),
+        (
+            "rId2".into(),
+            "http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties",
+            "docProps/core.xml".into(),
+        ),
+    ];
+    relationships(rels)
+}
+
+/// Relate the main document to its styles, footnotes, and images.
+fn document_relationships(collected: &Collected) -> Vec<u8> {
+    let rels: [(EcoString, &str, EcoString); 2] = [
+        (
+            "rIdStyles".into(),
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles",
+            "styles.xml".into(),
+        ),
+        (
+            "rIdFootnotes".into(),
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/footnotes",
+            "footnotes.xml".into(),
+        ),
+    ];
+
+    let images = collected.images.iter().enumerate().map(|(i, image)| {
+        (
+            image_rel(i),
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image",
+            eco_format!("media/image{}.{}", i + 1, extension(image)),
+        )
+    });
+
+    relationships(rels.into_iter().chain(images))
+}
+
+/// The relationship ID of the image with the given index.
+fn image_rel(index: usize) -> EcoString {
+    eco_format!("rIdImage{}", index + 1)
+}
+
+/// Write the document's metadata.
+fn core_properties(document: &Document) -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("cp:coreProperties");
+    xml.write_attribute("xmlns:cp", NS_CP);
+    xml.write_attribute("xmlns:dc", NS_DC);
+
+    if let Some(title) = &document.title {
+        xml.start_element("dc:title");
+        xml.write_text(title);
+        xml.end_element();
+    }
+
+    if !document.author.is_empty() {
+        xml.start_element("dc:creator");
+        xml.write_text(&document.author.join(", "));
+        xml.end_element();
+    }
+
+    if !document.keywords.is_empty() {
+        xml.start_element("cp:keywords");
+        xml.write_text(&document.keywords.join(", "));
+        xml.end_element();
+    }
+
+    finish(xml)
+}
+
+/// Write the main document with its blocks.
+fn main_document(document: &Document, collected: &Collected) -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("w:document");
+    xml.write_attribute("xmlns:w", NS_W);
+    xml.write_attribute("xmlns:r", NS_R);
+    xml.write_attribute("xmlns:wp", NS_WP);
+    xml.write_attribute("xmlns:a", NS_A);
+    xml.write_attribute("xmlns:pic", NS_PIC);
+    xml.start_element("w:body");
+
+    let mut drawings = 0;
+    for block in &collected.blocks {
+        match block {
+            Block::Paragraph(par) => {
+                write_paragraph(&mut xml, par, collected, &mut drawings)
+            }
+            Block::Table(table) => write_table(&mut xml, table),
+        }
+    }
+
+    // The body must not end with a table.
+    if matches!(collected.blocks.last(), Some(Block::Table(_))) {
+        xml.start_element("w:p");
+        xml.end_element();
+    }
+
+    // Use the size of the first page for the whole document. Word has its
+    // own page layout, so Typst's margins are estimated.
+    if let Some(page) = document.pages.first() {
+        let size = page.frame.size();
+        let margin = twips(size.x.min(size.y) * (2.5 / 21.0));
+        xml.start_element("w:sectPr");
+        xml.start_element("w:pgSz");
+        xml.write_attribute("w:w", &twips(size.x));
+        xml.write_attribute("w:h", &twips(size.y));
+        xml.end_element();
+        xml.start_element("w:pgMar");
+        for side in ["w:top", "w:right", "w:bottom", "w:left"] {
+            xml.write_attribute(side, &margin);
+        }
+        for side in ["w:header", "w:footer", "w:gutter"] {
+            xml.write_attribute(side, "0");
+        }
+        xml.end_element();
+        xml.end_element();
+    }
+
+    xml.end_element();
+    finish(xml)
+}
+
+/// Write a paragraph.
+fn write_paragraph(
+    xml: &mut XmlWriter,
+    par: &Paragraph,
+    collected: &Collected,
+    drawings: &mut usize,
+) {
+    xml.start_element("w:p");
+    if let Some(level) = par.heading {
+        xml.start_element("w:pPr");
+        xml.start_element("w:pStyle");
+        xml.write_attribute_fmt("w:val", format_args!("Heading{}", level.get().min(6)));
+        xml.end_element();
+        xml.end_element();
+    }
+
+    for run in &par.runs {
+        match run {
+            Run::Text(text, style) => write_text_run(xml, text, style),
+            Run::Footnote(index) => {
+                xml.start_element("w:r");
+                write_run_style(xml, "FootnoteReference");
+                xml.start_element("w:footnoteReference");
+                xml.write_attribute("w:id", &(index + 1));
+                xml.end_element();
+                xml.end_element();
+            }
+            Run::Image(index, size) => {
+                *drawings += 1;
+                let alt = collected.images[*index].alt().unwrap_or_default();
+                write_drawing(xml, *index, *drawings, emu(size.x), emu(size.y), alt);
+            }
+        }
+    }
+
+    xml.end_element();
+}
+
+/// Write a run of formatted text.
+fn write_text_run(xml: &mut XmlWriter, text: &str, style: &RunStyle) {
+    xml.start_element("w:r");
+    xml.start_element("w:rPr");
+
+    xml.start_element("w:rFonts");
+    for script in ["w:ascii", "w:hAnsi", "w:eastAsia", "w:cs"] {
+        xml.write_attribute(script, &style.font);
+    }
+    xml.end_element();
+
+    if style.bold {
+        xml.start_element("w:b");
+        xml.end_element();
+    }
+
+    if style.italic {
+        xml.start_element("w:i");
+        xml.end_element();
+    }
+
+    if let Some([r, g, b]) = style.color {
+        xml.start_element("w:color");
+        xml.write_attribute_fmt("w:val", format_args!("{r:02X}{g:02X}{b:02X}"));
+        xml.end_element();
+    }
+
+    // Font sizes are given in half points.
+    let half_points = (style.size.to_pt() * 2.0).round() as i64;
+    for name in ["w:sz", "w:szCs"] {
+        xml.start_element(name);
+        xml.write_attribute("w:val", &half_points);
+        xml.end_element();
+    }
+
+    xml.end_element();
+    xml.start_element("w:t");
+    xml.write_attribute("xml:space", "preserve");
+    xml.write_text(text);
+    xml.end_element();
+    xml.end_element();
+}
+
+/// Write the properties of a run with the given character style.
+fn write_run_style(xml: &mut XmlWriter, style: &str) {
+    xml.start_element("w:rPr");
+    xml.start_element("w:rStyle");
+    xml.write_attribute("w:val", style);
+    xml.end_element();
+    xml.end_element();
+}
+
+/// Write an inline image.
+fn write_drawing(
+    xml: &mut XmlWriter,
+    index: usize,
+    id: usize,
+    cx: i64,
+    cy: i64,
+    alt: &str,
+) {
+    xml.start_element("w:r");
+    xml.start_element("w:drawing");
+    xml.start_element("wp:inline");
+
+    xml.start_element("wp:extent");
+    xml.write_attribute("cx", &cx);
+    xml.write_attribute("cy", &cy);
+    xml.end_element();
+
+    xml.start_element("wp:docPr");
+    xml.write_attribute("id", &id);
+    xml.write_attribute_fmt("name", format_args!("Picture {id}"));
+    xml.write_attribute("descr", alt);
+    xml.end_element();
+
+    xml.start_element("a:graphic");
+    xml.start_element("a:graphicData");
+    xml.write_attribute("uri", NS_PIC);
+    xml.start_element("pic:pic");
+
+    xml.start_element("pic:nvPicPr");
+    xml.start_element("pic:cNvPr");
+    xml.write_attribute("id", &id);
+    xml.write_attribute_fmt("name", format_args!("Picture {id}"));
+    xml.end_element();
+    xml.start_element("pic:cNvPicPr");
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("pic:blipFill");
+    xml.start_element("a:blip");
+    xml.write_attribute("r:embed", &image_rel(index));
+    xml.end_element();
+    xml.start_element("a:stretch");
+    xml.start_element("a:fillRect");
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("pic:spPr");
+    xml.start_element("a:xfrm");
+    xml.start_element("a:off");
+    xml.write_attribute("x", "0");
+    xml.write_attribute("y", "0");
+    xml.end_element();
+    xml.start_element("a:ext");
+    xml.write_attribute("cx", &cx);
+    xml.write_attribute("cy", &cy);
+    xml.end_element();
+    xml.end_element();
+    xml.start_element("a:prstGeom");
+    xml.write_attribute("prst", "rect");
+    xml.start_element("a:avLst");
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+
+    xml.end_element(); // pic:pic
+    xml.end_element(); // a:graphicData
+    xml.end_element(); // a:graphic
+    xml.end_element(); // wp:inline
+    xml.end_element(); // w:drawing
+    xml.end_element(); // w:r
+}
+
+/// Write a table with plain text cells.
+fn write_table(xml: &mut XmlWriter, table: &Table) {
+    xml.start_element("w:tbl");
+    xml.start_element("w:tblPr");
+    xml.start_element("w:tblStyle");
+    xml.write_attribute("w:val", "TableGrid");
+    xml.end_element();
+    xml.start_element("w:tblW");
+    xml.write_attribute("w:w", "0");
+    xml.write_attribute("w:type", "auto");
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("w:tblGrid");
+    for _ in 0..table.columns {
+        xml.start_element("w:gridCol");
+        xml.end_element();
+    }
+    xml.end_element();
+
+    // Distribute the cells into rows, respecting their spans.
+    let mut column = 0;
+    for (text, span) in &table.cells {
+        if column == 0 {
+            xml.start_element("w:tr");
+        }
+
+        let span = span.get().min(table.columns - column);
+        xml.start_element("w:tc");
+        if span > 1 {
+            xml.start_element("w:tcPr");
+            xml.start_element("w:gridSpan");
+            xml.write_attribute("w:val", &span);
+            xml.end_element();
+            xml.end_element();
+        }
+
+        // A cell must contain at least one paragraph.
+        for line in text.split('\n') {
+            xml.start_element("w:p");
+            if !line.is_empty() {
+                xml.start_element("w:r");
+                xml.start_element("w:t");
+                xml.write_attribute("xml:space", "preserve");
+                xml.write_text(line);
+                xml.end_element();
+                xml.end_element();
+            }
+            xml.end_element();
+        }
+        xml.end_element();
+
+        column += span;
+        if column == table.columns {
+            xml.end_element();
+            column = 0;
+        }
+    }
+
+    // Fill up the last row.
+    if column > 0 {
+        for _ in column..table.columns {
+            xml.start_element("w:tc");
+            xml.start_element("w:p");
+            xml.end_element();
+            xml.end_element();
+        }
+        xml.end_element();
+    }
+
+    xml.end_element();
+}
+
+/// Write the styles referenced by the document.
+fn styles() -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("w:styles");
+    xml.write_attribute("xmlns:w", NS_W);
+
+    xml.start_element("w:style");
+    xml.write_attribute("w:type", "paragraph");
+    xml.write_attribute("w:default", "1");
+    xml.write_attribute("w:styleId", "Normal");
+    write_style_name(&mut xml, "Normal");
+    xml.end_element();
+
+    for (i, size) in HEADING_SIZES.iter().enumerate() {
+        let level = i + 1;
+        xml.start_element("w:style");
+        xml.write_attribute("w:type", "paragraph");
+        xml.write_attribute_fmt("w:styleId", format_args!("Heading{level}"));
+        write_style_name(&mut xml, &eco_format!("heading {level}"));
+        xml.start_element("w:basedOn");
+        xml.write_attribute("w:val", "Normal");
+        xml.end_element();
+        xml.start_element("w:next");
+        xml.write_attribute("w:val", "Normal");
+        xml.end_element();
+        xml.start_element("w:pPr");
+        xml.start_element("w:keepNext");
+        xml.end_element();
+        xml.start_element("w:outlineLvl");
+        xml.write_attribute("w:val", &i);
+        xml.end_element();
+        xml.end_element();
+        xml.start_element("w:rPr");
+        xml.start_element("w:b");
+        xml.end_element();
+        xml.start_element("w:sz");
+        xml.write_attribute("w:val", &(size * 2.0));
+        xml.end_element();
+        xml.end_element();
+        xml.end_element();
+    }
+
+    xml.start_element("w:style");
+    xml.write_attribute("w:type", "paragraph");
+    xml.write_attribute("w:styleId", "FootnoteText");
+    write_style_name(&mut xml, "footnote text");
+    xml.start_element("w:basedOn");
+    xml.write_attribute("w:val", "Normal");
+    xml.end_element();
+    xml.start_element("w:rPr");
+    xml.start_element("w:sz");
+    xml.write_attribute("w:val", "18");
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("w:style");
+    xml.write_attribute("w:type", "character");
+    xml.write_attribute("w:styleId", "FootnoteReference");
+    write_style_name(&mut xml, "footnote reference");
+    xml.start_element("w:rPr");
+    xml.start_element("w:vertAlign");
+    xml.write_attribute("w:val", "superscript");
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("w:style");
+    xml.write_attribute("w:type", "table");
+    xml.write_attribute("w:styleId", "TableGrid");
+    write_style_name(&mut xml, "Table Grid");
+    xml.start_element("w:tblPr");
+    xml.start_element("w:tblBorders");
+    for side in ["w:top", "w:left", "w:bottom", "w:right", "w:insideH", "w:insideV"] {
+        xml.start_element(side);
+        xml.write_attribute("w:val", "single");
+        xml.write_attribute("w:sz", "4");
+        xml.write_attribute("w:space", "0");
+        xml.write_attribute("w:color", "auto");
+        xml.end_element();
+    }
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+
+    finish(xml)
+}
+
+/// Write the name of a style.
+fn write_style_name(xml: &mut XmlWriter, name: &str) {
+    xml.start_element("w:name");
+    xml.write_attribute("w:val", name);
+    xml.end_element();
+}
+
+/// Write the footnotes, including the separators Word expects.
+fn footnotes(collected: &Collected) -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("w:footnotes");
+    xml.write_attribute("xmlns:w", NS_W);
+
+    for (id, kind) in [(-1, "separator"), (0, "continuationSeparator")] {
+        xml.start_element("w:footnote");
+        xml.write_attribute("w:type", kind);
+        xml.write_attribute("w:id", &id);
+        xml.start_element("w:p");
+        xml.start_element("w:r");
+        xml.start_element(&format!("w:{kind}"));
+        xml.end_element();
+        xml.end_element();
+        xml.end_element();
+        xml.end_element();
+    }
+
+    for (i, text) in collected.footnotes.iter().enumerate() {
+        xml.start_element("w:footnote");
+        xml.write_attribute("w:id", &(i + 1));
+        xml.start_element("w:p");
+        xml.start_element("w:pPr");
+        xml.start_element("w:pStyle");
+        xml.write_attribute("w:val", "FootnoteText");
+        xml.end_element();
+        xml.end_element();
+
+        xml.start_element("w:r");
+        write_run_style(&mut xml, "FootnoteReference");
+        xml.start_element("w:footnoteRef");
+        xml.end_element();
+        xml.end_element();
+
+        xml.start_element("w:r");
+        xml.start_element("w:t");
+        xml.write_attribute("xml:space", "preserve");
+        xml.write_text(&eco_format!(" {text}"));
+        xml.end_element();
+        xml.end_element();
+
+        xml.end_element();
+        xml.end_element();
+    }
+
+    finish(xml)
+}
+
+/// Convert a length to twentieths of a point.
+fn twips(length: Abs) -> i64 {
+    (length.to_pt() * 20.0).round() as i64
+}
+
+/// Convert a length to English Metric Units.
+fn emu(length: Abs) -> i64 {
+    (length.to_pt() * 12700.0).round() as i64
+}
@@ -0,0 +1,201 @@
+//! Exporting into EPUB packages.
+
+mod package;
+
+use typst::diag::StrResult;
+use typst::foundations::Datetime;
+use typst::model::Document;
+
+/// Export a document into a reflowable EPUB 3 package.
+///
+/// Since Typst documents are laid out into fixed pages, the export is
+/// best-effort: The structure of the document is recovered from its frames in
+/// the same way as for DOCX export. Text is grouped into paragraphs, keeping
+/// bold and italic text apart, while headings, tables, and footnotes are
+/// recognized through the elements they were laid out from. Images are
+/// embedded. Page layout, math, and graphics are not preserved, and table
+/// cells are reduced to plain text.
+///
+/// The document is split into one chapter per top-level heading, which make up
+/// the reading order. The table of contents lists all headings. The title,
+/// authors, keywords, and date of the document are used as the package's
+/// metadata and the language of its text is taken from its first text.
+///
+/// The `ident` and `timestamp` parameters work like those of the PDF exporter:
+/// The `ident` shall uniquely and stably identify the document and is hashed
+/// into the package's identifier. If it is `None`, a hash of the content is
+/// used instead. The `timestamp` is used as the modification date if
+/// `set document(date: ..)` is `auto`.
+///
+/// Returns the raw bytes making up the EPUB package.
+#[typst_macros::time(name = "epub")]
+pub fn epub(
+    document: &Document,
+    ident: Option<&str>,
+    timestamp: Option<Datetime>,
+) -> StrResult<Vec<u8>> {
+    let collected = typst_reflow::collect(document, &|_| true);
+    package::write_package(document, &collected, ident, timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use typst::eval::Tracer;
+    use typst::foundations::Bytes;
+    use typst::text::Font;
+    use typst::MemoryWorld;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn world(text: &str) -> MemoryWorld {
+        let fonts = [
+            &include_bytes!("../../../assets/fonts/LinLibertine_R.ttf")[..],
+            &include_bytes!("../../../assets/fonts/LinLibertine_RB.ttf")[..],
+        ];
+        MemoryWorld::new(text)
+            .with_fonts(fonts.map(|data| Font::new(Bytes::from_static(data), 0).unwrap()))
+    }
+
+    fn compile(text: &str) -> Document {
+        compile_world(&world(text))
+    }
+
+    fn compile_world(world: &MemoryWorld) -> Document {
+        typst::compile(world, &mut Tracer::new()).unwrap()
+    }
+
+    fn unzip(data: Vec<u8>) -> Vec<(String, String)> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut file = archive.by_index(i).unwrap();
+                let mut text = String::new();
+                file.read_to_string(&mut text).unwrap();
+                (file.name().to_string(), text)
+            })
+            .collect()
+    }
+
+    fn part<'a>(parts: &'a [(String, String)], name: &str) -> &'a str {
+        &parts.iter().find(|(path, _)| path == name).unwrap().1
+    }
+
+    #[test]
+    fn test_epub_package() {
+        let mut world = world(
+            "#set document(title: \"Book\", author: \"Jane\", keywords: (\"tales\",))\n\
+             #set text(lang: \"de\")\n\
+             = One\n\
+             Some *bold* text.\n\
+             == Inner\n\
+             More.\n\
+             = Two\n\
+             #image(\"logo.svg\", width: 2cm)",
+        );
+        let logo = include_bytes!("../../../assets/files/logo.svg");
+        world.insert_file("logo.svg", Bytes::from_static(logo));
+        let document = compile_world(&world);
+
+        let data = epub(&document, Some("book.typ"), None).unwrap();
+        assert!(data[30..].starts_with(b"mimetypeapplication/epub+zip"));
+
+        let parts = unzip(data);
+        let names: Vec<_> = parts.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "mimetype",
+                "META-INF/container.xml",
+                "OEBPS/content.opf",
+                "OEBPS/nav.xhtml",
+                "OEBPS/style.css",
+                "OEBPS/chapter-1.xhtml",
+                "OEBPS/chapter-2.xhtml",
+                "OEBPS/images/image-1.svg",
+            ]
+        );
+
+        let opf = part(&parts, "OEBPS/content.opf");
+        assert!(opf.contains("<dc:title>Book</dc:title>"));
+        assert!(opf.contains("<dc:creator>Jane</dc:creator>"));
+        assert!(opf.contains("<dc:subject>tales</dc:subject>"));
+        assert!(opf.contains("<dc:language>de</dc:language>"));
+        assert!(opf.contains("1970-01-01T00:00:00Z"));
+        assert!(
+            opf.contains(r#"<itemref idref="chapter-1"/><itemref idref="chapter-2"/>"#)
+        );
+        assert!(opf.contains(r#"href="images/image-1.svg" media-type="image/svg+xml""#));
+
+        let nav = part(&parts, "OEBPS/nav.xhtml");
+        assert!(nav.contains(concat!(
+            r#"<ol><li><a href="chapter-1.xhtml#heading-1">One</a>"#,
+            r#"<ol><li><a href="chapter-1.xhtml#heading-2">Inner</a></li></ol></li>"#,
+            r#"<li><a href="chapter-2.xhtml#heading-3">Two</a></li></ol>"#,
+        )));
+
+        let first = part(&parts, "OEBPS/chapter-1.xhtml");
+        assert!(first.contains("<title>One</title>"));
+        assert!(first.contains(r#"<h1 id="heading-1">One</h1>"#));
+        assert!(first.contains("<p>Some <strong>bold</strong> text.</p>"));
+        assert!(first.contains(r#"<h2 id="heading-2">Inner</h2>"#));
+
+        let second = part(&parts, "OEBPS/chapter-2.xhtml");
+        assert!(second.contains(r#"<h1 id="heading-3">Two</h1>"#));
+        assert!(
+            second.contains(r#"<figure><img src="images/image-1.svg" alt=""/></figure>"#)
+        );
+    }
+
+    #[test]
+    fn test_epub_without_headings() {
+        let document = compile("Hello\n\nWorld");
+        let parts = unzip(epub(&document, None, None).unwrap());
+
+        let chapter = part(&parts, "OEBPS/chapter-1.xhtml");
+        assert!(chapter.contains("<p>Hello</p><p>World</p>"));
+
+        let nav = part(&parts, "OEBPS/nav.xhtml");
+        assert!(
+            nav.contains(r#"<ol><li><a href="chapter-1.xhtml">Chapter 1</a></li></ol>"#)
+        );
+    }
+
+    #[test]
+    fn test_epub_tables_and_footnotes() {
+        let document = compile(
+            "Text#footnote[Note]\n\n\
+             #table(columns: 2, table.cell(colspan: 2)[A], [B], [C])",
+        );
+        let parts = unzip(epub(&document, None, None).unwrap());
+
+        let chapter = part(&parts, "OEBPS/chapter-1.xhtml");
+        assert!(chapter.contains(
+            r##"Text<sup><a epub:type="noteref" href="#footnote-1">1</a></sup>"##
+        ));
+        assert!(chapter.contains(concat!(
+            r#"<table><tr><td colspan="2">A</td></tr>"#,
+            r#"<tr><td>B</td><td>C</td></tr></table>"#,
+        )));
+        assert!(chapter.contains(concat!(
+            r#"</table><aside epub:type="footnote" id="footnote-1">"#,
+            "<p>Note</p></aside></body>",
+        )));
+    }
+
+    #[test]
+    fn test_epub_identifier() {
+        let a = compile("A");
+        let b = compile("B");
+        let opf = |document, ident| {
+            let parts = unzip(epub(document, ident, None).unwrap());
+            part(&parts, "OEBPS/content.opf").to_string()
+        };
+
+        // A stable identifier doesn't depend on the content.
+        assert_eq!(opf(&a, Some("x")), opf(&b, Some("x")));
+        assert_ne!(opf(&a, None), opf(&b, None));
+    }
+}
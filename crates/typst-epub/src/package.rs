@@ -0,0 +1,628 @@
+use std::fmt::Display;
+use std::io::{Cursor, Write};
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use typst::diag::StrResult;
+use typst::foundations::{Datetime, Smart};
+use typst::model::Document;
+use typst::util::hash128;
+use typst::visualize::{Image, ImageFormat, RasterFormat, VectorFormat};
+use typst_reflow::{Block, Collected, Paragraph, Run, RunStyle, Table};
+use xmlwriter::XmlWriter;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const NS_XHTML: &str = "http://www.w3.org/1999/xhtml";
+const NS_OPS: &str = "http://www.idpf.org/2007/ops";
+const NS_OPF: &str = "http://www.idpf.org/2007/opf";
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+const NS_CONTAINER: &str = "urn:oasis:names:tc:opendocument:xmlns:container";
+
+/// The style sheet shared by all chapters.
+const STYLE: &str = "\
+img { max-width: 100%; }
+figure { margin: 1em 0; text-align: center; }
+table { border-collapse: collapse; margin: 1em 0; }
+td { border: 1px solid; padding: 0.25em 0.5em; }
+";
+
+/// A chapter of the book, which is one document in the spine.
+struct Chapter<'a> {
+    /// The chapter's title.
+    title: EcoString,
+    /// The chapter's blocks.
+    blocks: &'a [Block],
+    /// The index of the chapter's first heading among all headings.
+    first_heading: usize,
+}
+
+/// Write the parts of the package and zip them.
+pub fn write_package(
+    document: &Document,
+    collected: &Collected,
+    ident: Option<&str>,
+    timestamp: Option<Datetime>,
+) -> StrResult<Vec<u8>> {
+    let lang = collected.lang.map_or("en", |lang| lang.as_str());
+    let chapters = split(document, &collected.blocks);
+
+    let mut parts = vec![];
+    for (i, chapter) in chapters.iter().enumerate() {
+        parts.push((chapter_path(i), write_chapter(chapter, collected, lang)));
+    }
+
+    // Without a stable identifier, the content identifies the book.
+    let id = match ident {
+        Some(ident) => hash128(&["EPUB\0", ident]),
+        None => hash128(&parts),
+    };
+
+    let mut parts = [
+        ("META-INF/container.xml".into(), container()),
+        (
+            "OEBPS/content.opf".into(),
+            write_opf(document, collected, &chapters, lang, id, timestamp),
+        ),
+        ("OEBPS/nav.xhtml".into(), write_nav(document, &chapters, lang)),
+        ("OEBPS/style.css".into(), STYLE.as_bytes().to_vec()),
+    ]
+    .into_iter()
+    .chain(
+        parts
+            .into_iter()
+            .map(|(path, data)| (eco_format!("OEBPS/{path}"), data)),
+    )
+    .collect::<Vec<_>>();
+
+    for (i, image) in collected.images.iter().enumerate() {
+        parts
+            .push((eco_format!("OEBPS/{}", image_path(i, image)), image.data().to_vec()));
+    }
+
+    // The media type must come first and be stored uncompressed, so that it
+    // can be recognized at a fixed offset.
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("mimetype", stored).map_err(failed)?;
+    zip.write_all(b"application/epub+zip").map_err(failed)?;
+
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (path, data) in parts {
+        zip.start_file(path.as_str(), options).map_err(failed)?;
+        zip.write_all(&data).map_err(failed)?;
+    }
+
+    Ok(zip.finish().map_err(failed)?.into_inner())
+}
+
+/// Format an error that occurred while zipping.
+fn failed(err: impl Display) -> EcoString {
+    eco_format!("failed to write EPUB package ({err})")
+}
+
+/// Split the blocks into chapters at the top-level headings.
+fn split<'a>(document: &Document, blocks: &'a [Block]) -> Vec<Chapter<'a>> {
+    let top = blocks
+        .iter()
+        .filter_map(|block| heading(block).map(|(level, _)| level))
+        .min();
+
+    let mut chapters = vec![];
+    let mut start = 0;
+    let mut headings = 0;
+    let mut first_heading = 0;
+    for (i, block) in blocks.iter().enumerate() {
+        if let Some((level, _)) = heading(block) {
+            if Some(level) == top && i > start {
+                chapters.push(Chapter {
+                    title: EcoString::new(),
+                    blocks: &blocks[start..i],
+                    first_heading,
+                });
+                start = i;
+                first_heading = headings;
+            }
+            headings += 1;
+        }
+    }
+
+    chapters.push(Chapter {
+        title: EcoString::new(),
+        blocks: &blocks[start..],
+        first_heading,
+    });
+
+    for (i, chapter) in chapters.iter_mut().enumerate() {
+        chapter.title = chapter
+            .blocks
+            .iter()
+            .find_map(|block| heading(block).map(|(_, runs)| plain_text(runs)))
+            .or_else(|| document.title.clone())
+            .unwrap_or_else(|| eco_format!("Chapter {}", i + 1));
+    }
+
+    chapters
+}
+
+/// The level and text of a block if it is a heading.
+fn heading(block: &Block) -> Option<(NonZeroUsize, &[Run])> {
+    match block {
+        Block::Paragraph(Paragraph { heading: Some(level), runs }) => {
+            Some((*level, runs.as_slice()))
+        }
+        _ => None,
+    }
+}
+
+/// The path of the chapter with the given index, relative to the package
+/// document.
+fn chapter_path(index: usize) -> EcoString {
+    eco_format!("chapter-{}.xhtml", index + 1)
+}
+
+/// The path of an image, relative to the package document.
+fn image_path(index: usize, image: &Image) -> EcoString {
+    eco_format!("images/image-{}.{}", index + 1, extension(image))
+}
+
+/// The anchor of the heading with the given index among all headings.
+fn heading_id(index: usize) -> EcoString {
+    eco_format!("heading-{}", index + 1)
+}
+
+/// The anchor of the footnote with the given index.
+fn footnote_id(index: usize) -> EcoString {
+    eco_format!("footnote-{}", index + 1)
+}
+
+/// The file extension of an image.
+fn extension(image: &Image) -> &'static str {
+    match image.format() {
+        ImageFormat::Raster(RasterFormat::Png) => "png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+        ImageFormat::Raster(RasterFormat::Gif) => "gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "svg",
+    }
+}
+
+/// The media type of an image.
+fn media_type(image: &Image) -> &'static str {
+    match image.format() {
+        ImageFormat::Raster(RasterFormat::Png) => "image/png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+    }
+}
+
+/// The text of some runs without their formatting.
+fn plain_text(runs: &[Run]) -> EcoString {
+    runs.iter()
+        .filter_map(|run| match run {
+            Run::Text(text, _) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Create an XML writer for a part.
+fn writer() -> XmlWriter {
+    XmlWriter::new(xmlwriter::Options {
+        indent: xmlwriter::Indent::None,
+        ..Default::default()
+    })
+}
+
+/// Finish an XML part, prepending the XML declaration.
+fn finish(xml: XmlWriter) -> Vec<u8> {
+    let mut buf = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    buf.push('\n');
+    buf.push_str(&xml.end_document());
+    buf.into_bytes()
+}
+
+/// Finish an XHTML document, prepending the XML declaration and doctype.
+fn finish_xhtml(xml: XmlWriter) -> Vec<u8> {
+    let mut buf = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    buf.push_str("\n<!DOCTYPE html>\n");
+    buf.push_str(&xml.end_document());
+    buf.into_bytes()
+}
+
+/// Point the reader to the package document.
+fn container() -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("container");
+    xml.write_attribute("version", "1.0");
+    xml.write_attribute("xmlns", NS_CONTAINER);
+    xml.start_element("rootfiles");
+    xml.start_element("rootfile");
+    xml.write_attribute("full-path", "OEBPS/content.opf");
+    xml.write_attribute("media-type", "application/oebps-package+xml");
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+    finish(xml)
+}
+
+/// Write the package document with the metadata, the manifest of all
+/// resources, and the spine.
+fn write_opf(
+    document: &Document,
+    collected: &Collected,
+    chapters: &[Chapter],
+    lang: &str,
+    id: u128,
+    timestamp: Option<Datetime>,
+) -> Vec<u8> {
+    let mut xml = writer();
+    xml.start_element("package");
+    xml.write_attribute("xmlns", NS_OPF);
+    xml.write_attribute("version", "3.0");
+    xml.write_attribute("unique-identifier", "uid");
+    xml.write_attribute("xml:lang", lang);
+
+    xml.start_element("metadata");
+    xml.write_attribute("xmlns:dc", NS_DC);
+
+    xml.start_element("dc:identifier");
+    xml.write_attribute("id", "uid");
+    let hex = format!("{id:032x}");
+    xml.write_text(&format!(
+        "urn:uuid:{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ));
+    xml.end_element();
+
+    xml.start_element("dc:title");
+    xml.write_text(document.title.as_deref().unwrap_or(&chapters[0].title));
+    xml.end_element();
+
+    xml.start_element("dc:language");
+    xml.write_text(lang);
+    xml.end_element();
+
+    for author in &document.author {
+        xml.start_element("dc:creator");
+        xml.write_text(author);
+        xml.end_element();
+    }
+
+    for keyword in &document.keywords {
+        xml.start_element("dc:subject");
+        xml.write_text(keyword);
+        xml.end_element();
+    }
+
+    let date = match document.date {
+        Smart::Custom(date) => date,
+        Smart::Auto => timestamp,
+    };
+
+    if let Some(date) = date.filter(|date| date.year().is_some()) {
+        xml.start_element("dc:date");
+        xml.write_text(&format_date(date));
+        xml.end_element();
+    }
+
+    // The modification date is required, so we fall back to a fixed one
+    // to keep the output reproducible.
+    xml.start_element("meta");
+    xml.write_attribute("property", "dcterms:modified");
+    xml.write_text(&match date.filter(|date| date.year().is_some()) {
+        Some(date) => format_datetime(date),
+        None => "1970-01-01T00:00:00Z".into(),
+    });
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("manifest");
+    let item = |xml: &mut XmlWriter, id: &str, href: &str, media_type: &str| {
+        xml.start_element("item");
+        xml.write_attribute("id", id);
+        xml.write_attribute("href", href);
+        xml.write_attribute("media-type", media_type);
+    };
+
+    item(&mut xml, "nav", "nav.xhtml", "application/xhtml+xml");
+    xml.write_attribute("properties", "nav");
+    xml.end_element();
+
+    item(&mut xml, "style", "style.css", "text/css");
+    xml.end_element();
+
+    for i in 0..chapters.len() {
+        let id = eco_format!("chapter-{}", i + 1);
+        item(&mut xml, &id, &chapter_path(i), "application/xhtml+xml");
+        xml.end_element();
+    }
+
+    for (i, image) in collected.images.iter().enumerate() {
+        let id = eco_format!("image-{}", i + 1);
+        item(&mut xml, &id, &image_path(i, image), media_type(image));
+        xml.end_element();
+    }
+    xml.end_element();
+
+    xml.start_element("spine");
+    for i in 0..chapters.len() {
+        xml.start_element("itemref");
+        xml.write_attribute_fmt("idref", format_args!("chapter-{}", i + 1));
+        xml.end_element();
+    }
+    xml.end_element();
+
+    xml.end_element();
+    finish(xml)
+}
+
+/// Format the date part of a datetime.
+fn format_date(date: Datetime) -> EcoString {
+    eco_format!(
+        "{:04}-{:02}-{:02}",
+        date.year().unwrap_or(1970),
+        date.month().unwrap_or(1),
+        date.day().unwrap_or(1)
+    )
+}
+
+/// Format a datetime in UTC with second precision.
+fn format_datetime(date: Datetime) -> EcoString {
+    eco_format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_date(date),
+        date.hour().unwrap_or(0),
+        date.minute().unwrap_or(0),
+        date.second().unwrap_or(0)
+    )
+}
+
+/// Start an XHTML document with its head.
+fn start_xhtml(xml: &mut XmlWriter, title: &str, lang: &str) {
+    xml.start_element("html");
+    xml.write_attribute("xmlns", NS_XHTML);
+    xml.write_attribute("xmlns:epub", NS_OPS);
+    xml.write_attribute("lang", lang);
+    xml.write_attribute("xml:lang", lang);
+
+    xml.start_element("head");
+    xml.start_element("meta");
+    xml.write_attribute("charset", "utf-8");
+    xml.end_element();
+    xml.start_element("title");
+    xml.write_text(title);
+    xml.end_element();
+    xml.start_element("link");
+    xml.write_attribute("rel", "stylesheet");
+    xml.write_attribute("type", "text/css");
+    xml.write_attribute("href", "style.css");
+    xml.end_element();
+    xml.end_element();
+
+    xml.start_element("body");
+}
+
+/// Write a chapter as an XHTML document.
+fn write_chapter(chapter: &Chapter, collected: &Collected, lang: &str) -> Vec<u8> {
+    let mut xml = writer();
+    start_xhtml(&mut xml, &chapter.title, lang);
+
+    let mut heading = chapter.first_heading;
+    let mut notes = vec![];
+    for block in chapter.blocks {
+        match block {
+            Block::Paragraph(par) => {
+                if let Some(level) = par.heading {
+                    xml.start_element(&format!("h{}", level.get().min(6)));
+                    xml.write_attribute("id", &heading_id(heading));
+                    heading += 1;
+                } else if let [Run::Image(index, _)] = par.runs.as_slice() {
+                    xml.start_element("figure");
+                    write_image(&mut xml, collected, *index);
+                    xml.end_element();
+                    continue;
+                } else {
+                    xml.start_element("p");
+                }
+                write_runs(&mut xml, &par.runs, collected, &mut notes);
+                xml.end_element();
+            }
+            Block::Table(table) => write_table(&mut xml, table),
+        }
+    }
+
+    // The footnotes referenced in the chapter follow its text.
+    for index in notes {
+        xml.start_element("aside");
+        xml.write_attribute("epub:type", "footnote");
+        xml.write_attribute("id", &footnote_id(index));
+        xml.start_element("p");
+        xml.write_text(collected.footnotes[index].trim());
+        xml.end_element();
+        xml.end_element();
+    }
+
+    xml.end_element();
+    xml.end_element();
+    finish_xhtml(xml)
+}
+
+/// Write runs, remembering the footnotes they reference.
+///
+/// Only emphasis is kept from the formatting, so adjacent text with the same
+/// emphasis is merged.
+fn write_runs(
+    xml: &mut XmlWriter,
+    runs: &[Run],
+    collected: &Collected,
+    notes: &mut Vec<usize>,
+) {
+    let mut pending: Option<(EcoString, &RunStyle)> = None;
+    for run in runs {
+        if let (Run::Text(text, style), Some((prev, prev_style))) = (run, &mut pending) {
+            if (prev_style.bold, prev_style.italic) == (style.bold, style.italic) {
+                prev.push_str(text);
+                continue;
+            }
+        }
+
+        if let Some((text, style)) = pending.take() {
+            write_text(xml, &text, style);
+        }
+
+        match run {
+            Run::Text(text, style) => pending = Some((text.clone(), style)),
+            Run::Footnote(index) => {
+                xml.start_element("sup");
+                xml.start_element("a");
+                xml.write_attribute("epub:type", "noteref");
+                xml.write_attribute_fmt("href", format_args!("#{}", footnote_id(*index)));
+                xml.write_text(&(index + 1).to_string());
+                xml.end_element();
+                xml.end_element();
+                notes.push(*index);
+            }
+            Run::Image(index, _) => write_image(xml, collected, *index),
+        }
+    }
+
+    if let Some((text, style)) = pending {
+        write_text(xml, &text, style);
+    }
+}
+
+/// Write text with its emphasis.
+fn write_text(xml: &mut XmlWriter, text: &str, style: &RunStyle) {
+    if style.bold {
+        xml.start_element("strong");
+    }
+    if style.italic {
+        xml.start_element("em");
+    }
+    xml.write_text(text);
+    if style.italic {
+        xml.end_element();
+    }
+    if style.bold {
+        xml.end_element();
+    }
+}
+
+/// Write the image with the given index.
+fn write_image(xml: &mut XmlWriter, collected: &Collected, index: usize) {
+    let image = &collected.images[index];
+    xml.start_element("img");
+    xml.write_attribute("src", &image_path(index, image));
+    xml.write_attribute("alt", image.alt().unwrap_or_default());
+    xml.end_element();
+}
+
+/// Write a table whose cells are plain text.
+fn write_table(xml: &mut XmlWriter, table: &Table) {
+    xml.start_element("table");
+
+    // Distribute the cells into rows, respecting their spans.
+    let mut column = 0;
+    for (text, span) in &table.cells {
+        if column == 0 {
+            xml.start_element("tr");
+        }
+
+        let span = span.get().min(table.columns - column);
+        xml.start_element("td");
+        if span > 1 {
+            xml.write_attribute("colspan", &span);
+        }
+        xml.write_text(text);
+        xml.end_element();
+
+        column += span;
+        if column == table.columns {
+            xml.end_element();
+            column = 0;
+        }
+    }
+
+    if column > 0 {
+        xml.end_element();
+    }
+
+    xml.end_element();
+}
+
+/// Write the navigation document with the table of contents.
+///
+/// The table of contents lists all headings, nested by their level. Without
+/// headings, it lists the chapters instead.
+fn write_nav(document: &Document, chapters: &[Chapter], lang: &str) -> Vec<u8> {
+    let mut entries = vec![];
+    let mut index = 0;
+    for (i, chapter) in chapters.iter().enumerate() {
+        for block in chapter.blocks {
+            if let Some((level, runs)) = heading(block) {
+                let href = eco_format!("{}#{}", chapter_path(i), heading_id(index));
+                entries.push((level, plain_text(runs), href));
+                index += 1;
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        entries = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                (NonZeroUsize::MIN, chapter.title.clone(), chapter_path(i))
+            })
+            .collect();
+    }
+
+    let title = document.title.as_deref().unwrap_or("Contents");
+    let mut xml = writer();
+    start_xhtml(&mut xml, title, lang);
+    xml.start_element("nav");
+    xml.write_attribute("epub:type", "toc");
+    xml.write_attribute("id", "toc");
+    xml.start_element("h1");
+    xml.write_text(title);
+    xml.end_element();
+
+    // Each entry is nested at most one level deeper than the previous one, so
+    // that skipped heading levels don't produce entries without a parent.
+    let top = entries.iter().map(|(level, _, _)| level.get()).min().unwrap_or(1);
+    let mut current = 0;
+    for (level, text, href) in &entries {
+        let depth = (level.get() - top + 1).min(current + 1);
+        if depth > current {
+            xml.start_element("ol");
+        } else {
+            xml.end_element();
+            for _ in depth..current {
+                xml.end_element();
+                xml.end_element();
+            }
+        }
+        current = depth;
+
+        xml.start_element("li");
+        xml.start_element("a");
+        xml.write_attribute("href", href);
+        xml.write_text(text);
+        xml.end_element();
+    }
+
+    for _ in 0..current {
+        xml.end_element();
+        xml.end_element();
+    }
+
+    xml.end_element();
+    xml.end_element();
+    xml.end_element();
+    finish_xhtml(xml)
+}
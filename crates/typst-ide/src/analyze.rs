@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use comemo::Track;
 use ecow::{eco_vec, EcoString, EcoVec};
 use typst::engine::{Engine, Route};
@@ -5,9 +7,12 @@ use typst::eval::{Tracer, Vm};
 use typst::foundations::{Label, Scopes, Value};
 use typst::introspection::{Introspector, Locator};
 use typst::model::{BibliographyElem, Document};
-use typst::syntax::{ast, LinkedNode, Span, SyntaxKind};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{is_ident, LinkedNode, PackageSpec, Span, SyntaxKind, SyntaxNode};
 use typst::World;
 
+use crate::docs::{find_docs, Docs};
+
 /// Try to determine a set of possible values for an expression.
 pub fn analyze_expr(world: &dyn World, node: &LinkedNode) -> EcoVec<Value> {
     match node.cast::<ast::Expr>() {
@@ -102,3 +107,265 @@ pub fn analyze_labels(document: &Document) -> (Vec<(Label, Option<EcoString>)>,
 
     (output, split)
 }
+
+/// A name that is bound in a source file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Binding {
+    /// The bound name.
+    pub name: EcoString,
+    /// The span of the identifier that introduces the binding.
+    pub span: Span,
+    /// How the name is bound.
+    pub kind: BindingKind,
+    /// Whether the binding is at the top level of the file, from where it is
+    /// exported to other modules.
+    pub top_level: bool,
+    /// The spans of the identifiers that refer to the binding.
+    pub uses: Vec<Span>,
+    /// The span of a binding from an enclosing scope that this one shadows.
+    pub shadows: Option<Span>,
+    /// The documentation from a doc comment in front of the binding.
+    pub docs: Option<Docs>,
+}
+
+/// How a name is bound.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BindingKind {
+    /// A variable defined with `let`.
+    Variable,
+    /// A function defined with `let`.
+    Function,
+    /// A parameter of a function.
+    Parameter,
+    /// The variable of a `for` loop.
+    Loop,
+    /// An imported module or item.
+    Import,
+}
+
+/// Find the bindings in a syntax tree together with the identifiers that refer
+/// to them.
+///
+/// Identifiers that do not refer to a binding in the tree, like those of
+/// functions from the standard library or of wildcard imports, are not
+/// resolved.
+pub fn analyze_bindings(root: &SyntaxNode) -> Vec<Binding> {
+    let mut resolver = Resolver { bindings: vec![], scopes: vec![vec![]], docs: None };
+    resolver.node(root);
+    resolver.bindings
+}
+
+/// Resolves identifiers to the bindings in scope.
+struct Resolver {
+    /// The bindings found so far.
+    bindings: Vec<Binding>,
+    /// The indices of the bindings in each of the nested scopes.
+    scopes: Vec<Vec<usize>>,
+    /// The documentation for the `let` binding that is resolved next.
+    docs: Option<Docs>,
+}
+
+impl Resolver {
+    /// Resolve the identifiers in a node and its children.
+    fn node(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock => {
+                self.scopes.push(vec![]);
+                self.children(node);
+                self.scopes.pop();
+            }
+            SyntaxKind::Ident | SyntaxKind::MathIdent => self.refer(node),
+            SyntaxKind::Named => {
+                if let Some(named) = node.cast::<ast::Named>() {
+                    self.node(named.expr().to_untyped());
+                }
+            }
+            SyntaxKind::FieldAccess => {
+                if let Some(access) = node.cast::<ast::FieldAccess>() {
+                    self.node(access.target().to_untyped());
+                }
+            }
+            SyntaxKind::LetBinding => {
+                if let Some(binding) = node.cast::<ast::LetBinding>() {
+                    self.let_binding(binding);
+                }
+            }
+            SyntaxKind::Closure => {
+                if let Some(closure) = node.cast::<ast::Closure>() {
+                    self.closure(closure);
+                }
+            }
+            SyntaxKind::ForLoop => {
+                if let Some(for_loop) = node.cast::<ast::ForLoop>() {
+                    self.node(for_loop.iterable().to_untyped());
+                    self.scopes.push(vec![]);
+                    for ident in for_loop.pattern().idents() {
+                        self.declare(ident, BindingKind::Loop);
+                    }
+                    self.node(for_loop.body().to_untyped());
+                    self.scopes.pop();
+                }
+            }
+            SyntaxKind::ModuleImport => {
+                if let Some(import) = node.cast::<ast::ModuleImport>() {
+                    self.import(import);
+                }
+            }
+            _ => self.children(node),
+        }
+    }
+
+    /// Resolve the identifiers in the children of a node.
+    fn children(&mut self, node: &SyntaxNode) {
+        let siblings = node.children().as_slice();
+        for (i, child) in siblings.iter().enumerate() {
+            if child.kind() == SyntaxKind::LetBinding {
+                self.docs = find_docs(siblings, i);
+            }
+            self.node(child);
+        }
+    }
+
+    /// Resolve a `let` binding.
+    fn let_binding(&mut self, binding: ast::LetBinding) {
+        let docs = self.docs.take();
+        match binding.kind() {
+            ast::LetBindingKind::Normal(pattern) => {
+                if let Some(init) = binding.init() {
+                    self.node(init.to_untyped());
+                }
+                for ident in pattern.idents() {
+                    self.declare(ident, BindingKind::Variable);
+                }
+
+                // Documentation only applies to a single variable.
+                if let ast::Pattern::Normal(_) = pattern {
+                    self.document(docs);
+                }
+            }
+            ast::LetBindingKind::Closure(name) => {
+                // Declare the name first, so that the function can call itself.
+                self.declare(name, BindingKind::Function);
+                self.document(docs);
+                if let Some(init) = binding.init() {
+                    self.node(init.to_untyped());
+                }
+            }
+        }
+    }
+
+    /// Attach documentation to the binding that was declared last.
+    fn document(&mut self, docs: Option<Docs>) {
+        if let Some(binding) = self.bindings.last_mut() {
+            binding.docs = docs;
+        }
+    }
+
+    /// Resolve a closure.
+    fn closure(&mut self, closure: ast::Closure) {
+        // Default values and type annotations are evaluated where the closure
+        // is defined.
+        let params = closure.params().annotated();
+        for (param, annotation) in &params {
+            if let ast::Param::Named(named) = param {
+                self.node(named.expr().to_untyped());
+            }
+            if let Some(annotation) = annotation {
+                self.node(annotation.ty().to_untyped());
+            }
+        }
+
+        self.scopes.push(vec![]);
+        for (param, _) in params {
+            match param {
+                ast::Param::Pos(pattern) => {
+                    for ident in pattern.idents() {
+                        self.declare(ident, BindingKind::Parameter);
+                    }
+                }
+                ast::Param::Named(named) => {
+                    self.declare(named.name(), BindingKind::Parameter)
+                }
+                ast::Param::Sink(spread) => {
+                    if let Some(name) = spread.name() {
+                        self.declare(name, BindingKind::Parameter);
+                    }
+                }
+            }
+        }
+        self.node(closure.body().to_untyped());
+        self.scopes.pop();
+    }
+
+    /// Resolve a module import.
+    fn import(&mut self, import: ast::ModuleImport) {
+        self.node(import.source().to_untyped());
+        if let Some(name) = import.new_name() {
+            self.declare(name, BindingKind::Import);
+        } else if import.imports().is_none() {
+            // Like the evaluator, bind an imported file by its stem and a
+            // package by its name.
+            if let ast::Expr::Str(path) = import.source() {
+                if let Some(name) = module_name(&path.get()) {
+                    self.bind(name, path.span(), BindingKind::Import);
+                }
+            }
+        }
+        if let Some(ast::Imports::Items(items)) = import.imports() {
+            for item in items.iter() {
+                self.declare(item.bound_name(), BindingKind::Import);
+            }
+        }
+    }
+
+    /// Bind an identifier in the innermost scope.
+    fn declare(&mut self, ident: ast::Ident, kind: BindingKind) {
+        self.bind(ident.get().clone(), ident.span(), kind);
+    }
+
+    /// Bind a name in the innermost scope, introduced by the node with the
+    /// given span.
+    fn bind(&mut self, name: EcoString, span: Span, kind: BindingKind) {
+        let outer = &self.scopes[..self.scopes.len() - 1];
+        let shadows = self.find(outer, &name).map(|i| self.bindings[i].span);
+        self.bindings.push(Binding {
+            name,
+            span,
+            kind,
+            top_level: self.scopes.len() == 1,
+            uses: vec![],
+            shadows,
+            docs: None,
+        });
+        let index = self.bindings.len() - 1;
+        self.scopes.last_mut().unwrap().push(index);
+    }
+
+    /// Resolve an identifier to the innermost binding of its name.
+    fn refer(&mut self, ident: &SyntaxNode) {
+        if let Some(i) = self.find(&self.scopes, ident.text()) {
+            self.bindings[i].uses.push(ident.span());
+        }
+    }
+
+    /// Find the innermost binding of a name in the given scopes.
+    fn find(&self, scopes: &[Vec<usize>], name: &str) -> Option<usize> {
+        scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .find(|&i| self.bindings[i].name == name)
+    }
+}
+
+/// The name under which an import without items binds the module from the
+/// given path.
+fn module_name(path: &str) -> Option<EcoString> {
+    let name = if path.starts_with('@') {
+        path.parse::<PackageSpec>().ok()?.name
+    } else {
+        Path::new(path).file_stem()?.to_str()?.into()
+    };
+    is_ident(&name).then_some(name)
+}
@@ -1,5 +1,5 @@
 use std::cmp::Reverse;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use ecow::{eco_format, EcoString};
 use if_chain::if_chain;
@@ -18,6 +18,7 @@ use typst::World;
 use unscanny::Scanner;
 
 use crate::analyze::{analyze_expr, analyze_import, analyze_labels};
+use crate::docs::find_docs;
 use crate::{plain_docs_sentence, summarize_font_family};
 
 /// Autocomplete a cursor position in a source file.
@@ -1279,13 +1280,24 @@ impl<'a> CompletionContext<'a> {
     /// Filters the global/math scope with the given filter.
     fn scope_completions(&mut self, parens: bool, filter: impl Fn(&Value) -> bool) {
         let mut defined = BTreeSet::new();
+        let mut docs = HashMap::new();
 
         let mut ancestor = Some(self.leaf.clone());
         while let Some(node) = &ancestor {
             let mut sibling = Some(node.clone());
             while let Some(node) = &sibling {
                 if let Some(v) = node.cast::<ast::LetBinding>() {
-                    for ident in v.kind().idents() {
+                    let idents = v.kind().idents();
+                    if let ([ident], Some(parent)) = (idents.as_slice(), node.parent()) {
+                        let siblings = parent.get().children().as_slice();
+                        if let Some(found) = find_docs(siblings, node.index()) {
+                            docs.entry(ident.get().clone()).or_insert_with(|| {
+                                plain_docs_sentence(&found.description)
+                            });
+                        }
+                    }
+
+                    for ident in idents {
                         defined.insert(ident.get().clone());
                     }
                 }
@@ -1355,9 +1367,9 @@ impl<'a> CompletionContext<'a> {
             if !name.is_empty() {
                 self.completions.push(Completion {
                     kind: CompletionKind::Constant,
+                    detail: docs.get(&name).cloned(),
                     label: name,
                     apply: None,
-                    detail: None,
                 });
             }
         }
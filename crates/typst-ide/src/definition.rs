@@ -0,0 +1,157 @@
+use ecow::EcoString;
+use typst::foundations::Value;
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{LinkedNode, Source, Span, SyntaxKind};
+use typst::World;
+
+use crate::analyze::{analyze_bindings, analyze_expr, Binding, BindingKind};
+
+/// How many imports are followed at most when resolving a definition.
+const MAX_IMPORTS: usize = 16;
+
+/// Find the definition of the identifier at the cursor.
+///
+/// Imports of files are followed to the binding in the imported file, also
+/// for fields of imported modules, like `x` in `utils.x`. Returns `None` if
+/// the identifier isn't defined in the file or in one it imports, like for
+/// the definitions of the standard library.
+pub fn definition(world: &dyn World, source: &Source, cursor: usize) -> Option<Span> {
+    let (_, chain) = resolve(world, source, cursor)?;
+    chain.last().map(|binding| binding.span)
+}
+
+/// Find all places that refer to the same binding as the identifier at the
+/// cursor.
+///
+/// The result starts with the definition, followed by the identifiers that
+/// refer to it in the file where it is defined and in the files through which
+/// it was imported. Files that are not imported by the current file are not
+/// searched.
+pub fn references(world: &dyn World, source: &Source, cursor: usize) -> Vec<Span> {
+    let Some((leaf, chain)) = resolve(world, source, cursor) else { return vec![] };
+
+    let mut spans = vec![];
+    for binding in chain.iter().rev() {
+        spans.push(binding.span);
+        spans.extend(binding.uses.iter().copied());
+    }
+    spans.push(leaf);
+
+    let mut unique = Vec::with_capacity(spans.len());
+    for span in spans {
+        if !unique.contains(&span) {
+            unique.push(span);
+        }
+    }
+    unique
+}
+
+/// Resolve the identifier at the cursor.
+///
+/// Returns its span and the bindings it refers to: First the one in the
+/// current file and then those it was imported from, with the definition
+/// coming last.
+pub(crate) fn resolve(
+    world: &dyn World,
+    source: &Source,
+    cursor: usize,
+) -> Option<(Span, Vec<Binding>)> {
+    let root = LinkedNode::new(source.root());
+    let leaf = [cursor, cursor + 1]
+        .into_iter()
+        .filter_map(|cursor| root.leaf_at(cursor))
+        .find(|leaf| matches!(leaf.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent))?;
+
+    let bindings = analyze_bindings(source.root());
+    let mut current = source.clone();
+    let mut chain = vec![];
+    if let Some(binding) = find(&bindings, leaf.span()) {
+        chain.push(binding.clone());
+    } else {
+        // The identifier may be the field of an imported module.
+        let access = leaf.parent()?.cast::<ast::FieldAccess>()?;
+        if access.field().span() != leaf.span() {
+            return None;
+        }
+
+        let ast::Expr::Ident(target) = access.target() else { return None };
+        let module = find(&bindings, target.span())?;
+        let (imported, None) = follow(world, source, module)? else { return None };
+        chain.push(exported(&imported, leaf.text())?);
+        current = imported;
+    }
+
+    // Follow imported items to their definition.
+    while chain.len() <= MAX_IMPORTS {
+        let binding = chain.last().unwrap();
+        if binding.kind != BindingKind::Import {
+            break;
+        }
+
+        let Some((imported, Some(name))) = follow(world, &current, binding) else {
+            break;
+        };
+        let Some(binding) = exported(&imported, &name) else { break };
+        chain.push(binding);
+        current = imported;
+    }
+
+    Some((leaf.span(), chain))
+}
+
+/// Find the binding that is introduced or used at a span.
+fn find(bindings: &[Binding], span: Span) -> Option<&Binding> {
+    bindings
+        .iter()
+        .find(|binding| binding.span == span || binding.uses.contains(&span))
+}
+
+/// Find the binding of a name at the top level of a file.
+///
+/// If the name is bound multiple times, the last binding is the one that is
+/// exported.
+fn exported(source: &Source, name: &str) -> Option<Binding> {
+    analyze_bindings(source.root())
+        .into_iter()
+        .filter(|binding| binding.top_level && binding.name == name)
+        .last()
+}
+
+/// Determine the file that an import binding imports from.
+///
+/// Returns the imported file and, if the binding is for an imported item
+/// rather than for the module, the item's name in the imported file.
+fn follow(
+    world: &dyn World,
+    source: &Source,
+    binding: &Binding,
+) -> Option<(Source, Option<EcoString>)> {
+    let node = source.find(binding.span)?;
+    let mut import = node.parent()?;
+    while import.kind() != SyntaxKind::ModuleImport {
+        import = import.parent()?;
+    }
+
+    let module = import.cast::<ast::ModuleImport>()?;
+    let path = import.find(module.source().span())?;
+    let Some(Value::Str(path)) = analyze_expr(world, &path).into_iter().next() else {
+        return None;
+    };
+
+    // Packages are not followed.
+    if path.starts_with('@') {
+        return None;
+    }
+
+    let id = module.source().span().resolve_path(&path).ok()?;
+    let imported = world.source(id).ok()?;
+    let name = match module.imports() {
+        Some(ast::Imports::Items(items)) => items
+            .iter()
+            .find(|item| item.bound_name().span() == binding.span)
+            .map(|item| item.original_name().get().clone()),
+        _ => None,
+    };
+
+    Some((imported, name))
+}
@@ -0,0 +1,142 @@
+use std::fmt::Write;
+
+use ecow::EcoString;
+use typst::syntax::{is_ident, is_newline, Source, SyntaxKind, SyntaxNode};
+
+use crate::analyze::analyze_bindings;
+
+/// Documentation of a user-defined variable or function.
+///
+/// It is written in `///` comments directly in front of a `let` binding. The
+/// comment starts with a description of the item in Markdown. Parameters are
+/// documented with list items that start with the parameter's name and a
+/// colon and the return value with a line starting with an arrow:
+///
+/// ```typ
+/// /// Computes the area of a rectangle.
+/// ///
+/// /// - width: The width of the rectangle.
+/// /// - height: The height of the rectangle.
+/// ///   Defaults to the width.
+/// /// -> The area as a length squared.
+/// #let area(width, height: none) = { ... }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Docs {
+    /// The description of the item in Markdown.
+    pub description: EcoString,
+    /// The documentation of the parameters, in the order in which they are
+    /// documented.
+    pub params: Vec<(EcoString, EcoString)>,
+    /// The documentation of the return value.
+    pub returns: Option<EcoString>,
+}
+
+impl Docs {
+    /// Parse documentation from the text of a doc comment, without the
+    /// slashes.
+    pub fn parse(text: &str) -> Self {
+        let mut description = String::new();
+        let mut params: Vec<(EcoString, EcoString)> = vec![];
+        let mut returns: Option<EcoString> = None;
+
+        // Whether indented lines continue the last parameter (`false`) or the
+        // return value (`true`).
+        let mut continued = None;
+
+        for line in text.lines() {
+            let trimmed = line.trim_start();
+            if let Some(param) = trimmed.strip_prefix("- ").and_then(param) {
+                params.push(param);
+                continued = Some(false);
+                continue;
+            }
+
+            if let Some(rest) = trimmed.strip_prefix("->") {
+                returns = Some(rest.trim().into());
+                continued = Some(true);
+                continue;
+            }
+
+            let target = match continued {
+                _ if !line.starts_with(' ') || trimmed.is_empty() => None,
+                Some(false) => params.last_mut().map(|(_, docs)| docs),
+                Some(true) => returns.as_mut(),
+                None => None,
+            };
+
+            if let Some(target) = target {
+                target.push(' ');
+                target.push_str(trimmed);
+            } else {
+                continued = None;
+                description.push_str(line);
+                description.push('\n');
+            }
+        }
+
+        Self {
+            description: description.trim().into(),
+            params,
+            returns,
+        }
+    }
+
+    /// Render the documentation as Markdown.
+    pub fn to_markdown(&self) -> EcoString {
+        let mut markdown = self.description.clone();
+        if !self.params.is_empty() {
+            markdown.push_str("\n\n# Parameters");
+            for (name, docs) in &self.params {
+                write!(markdown, "\n- `{name}`: {docs}").unwrap();
+            }
+        }
+        if let Some(returns) = &self.returns {
+            write!(markdown, "\n\n# Returns\n{returns}").unwrap();
+        }
+        markdown
+    }
+}
+
+/// Parse the documentation of a parameter, like `width: The width.`
+fn param(item: &str) -> Option<(EcoString, EcoString)> {
+    let (name, docs) = item.split_once(':')?;
+    let name = name.trim();
+    is_ident(name).then(|| (name.into(), docs.trim().into()))
+}
+
+/// Find the doc comment in front of the child at the given index.
+pub(crate) fn find_docs(siblings: &[SyntaxNode], index: usize) -> Option<Docs> {
+    let mut lines = vec![];
+    for node in siblings[..index].iter().rev() {
+        match node.kind() {
+            SyntaxKind::Hash => {}
+            SyntaxKind::Space
+                if node.text().chars().filter(|&c| is_newline(c)).count() < 2 => {}
+            SyntaxKind::LineComment => match node.text().strip_prefix("///") {
+                Some(line) => lines.push(line.strip_prefix(' ').unwrap_or(line)),
+                None => break,
+            },
+            _ => break,
+        }
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    lines.reverse();
+    Some(Docs::parse(&lines.join("\n")))
+}
+
+/// Find the documentation of the definitions a file exports.
+///
+/// Returns the names of the documented top-level bindings together with their
+/// documentation, in the order in which they are defined.
+pub fn module_docs(source: &Source) -> Vec<(EcoString, Docs)> {
+    analyze_bindings(source.root())
+        .into_iter()
+        .filter(|binding| binding.top_level)
+        .filter_map(|binding| Some((binding.name, binding.docs?)))
+        .collect()
+}
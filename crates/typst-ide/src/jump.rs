@@ -123,9 +123,10 @@ pub fn jump_from_cursor(
     let span = node.span();
     for (i, page) in document.pages.iter().enumerate() {
         if let Some(pos) = find_in_frame(&page.frame, span) {
+            let offset = page.bleed + page.slug;
             return Some(Position {
                 page: NonZeroUsize::new(i + 1).unwrap(),
-                point: pos,
+                point: pos - Point::splat(offset),
             });
         }
     }
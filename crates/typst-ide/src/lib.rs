@@ -2,13 +2,24 @@
 
 mod analyze;
 mod complete;
+mod definition;
+mod docs;
 mod jump;
+mod lint;
+mod symbols;
 mod tooltip;
 
-pub use self::analyze::analyze_labels;
+pub use self::analyze::{analyze_bindings, analyze_labels, Binding, BindingKind};
 pub use self::complete::{autocomplete, Completion, CompletionKind};
+pub use self::definition::{definition, references};
+pub use self::docs::{module_docs, Docs};
 pub use self::jump::{jump_from_click, jump_from_cursor, Jump};
-pub use self::tooltip::{tooltip, Tooltip};
+pub use self::lint::{
+    Deprecated, EmptyHeadings, LintContext, Linter, Rule, ShadowedBindings,
+    UnusedImports, UnusedVariables,
+};
+pub use self::symbols::{document_symbols, DocumentSymbol, SymbolKind};
+pub use self::tooltip::{hover, tooltip, Hover, Tooltip};
 
 use std::fmt::Write;
 
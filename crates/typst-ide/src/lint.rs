@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use ecow::{eco_format, EcoString, EcoVec};
+use typst::diag::SourceDiagnostic;
+use typst::foundations::Value;
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{Source, Span, SyntaxKind, SyntaxNode};
+use typst::Library;
+
+use crate::analyze::{analyze_bindings, Binding, BindingKind};
+
+/// A check that is run over a source file by a [`Linter`].
+pub trait Rule {
+    /// The name of the rule, by which it can be disabled.
+    fn name(&self) -> &'static str;
+
+    /// Check a file and report problems through the context.
+    fn check(&self, ctx: &mut LintContext);
+}
+
+/// What a [`Rule`] knows about the file it checks.
+pub struct LintContext<'a> {
+    source: &'a Source,
+    bindings: &'a [Binding],
+    diagnostics: EcoVec<SourceDiagnostic>,
+}
+
+impl<'a> LintContext<'a> {
+    /// The file that is checked.
+    pub fn source(&self) -> &'a Source {
+        self.source
+    }
+
+    /// The root of the file's syntax tree.
+    pub fn root(&self) -> &'a SyntaxNode {
+        self.source.root()
+    }
+
+    /// The bindings in the file, as determined by [`analyze_bindings`].
+    pub fn bindings(&self) -> &'a [Binding] {
+        self.bindings
+    }
+
+    /// Report a problem that is already described by a diagnostic.
+    pub fn report(&mut self, warning: SourceDiagnostic) {
+        self.diagnostics.push(warning);
+    }
+
+    /// Report a problem at a span.
+    ///
+    /// Returns the warning, so that hints can be added to it.
+    pub fn warn(
+        &mut self,
+        span: Span,
+        message: impl Into<EcoString>,
+    ) -> &mut SourceDiagnostic {
+        self.diagnostics.push(SourceDiagnostic::warning(span, message));
+        self.diagnostics.make_mut().last_mut().unwrap()
+    }
+}
+
+/// Checks source files for likely mistakes.
+///
+/// A linter consists of a set of rules. Besides the built-in ones, custom
+/// rules can be registered by implementing [`Rule`]. The problems the rules
+/// find are reported as warnings, just like those of the compiler.
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Linter {
+    /// Create a linter with the built-in rules.
+    ///
+    /// These are `unused-imports`, `unused-variables`, `shadowed-bindings`,
+    /// `deprecated`, and `empty-headings`.
+    pub fn new() -> Self {
+        let mut linter = Self::empty();
+        linter
+            .register(UnusedImports)
+            .register(UnusedVariables)
+            .register(ShadowedBindings)
+            .register(Deprecated::default())
+            .register(EmptyHeadings);
+        linter
+    }
+
+    /// Create a linter without any rules.
+    pub fn empty() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Add a rule to the linter.
+    pub fn register(&mut self, rule: impl Rule + 'static) -> &mut Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Remove the rules with the given name.
+    pub fn disable(&mut self, name: &str) -> &mut Self {
+        self.rules.retain(|rule| rule.name() != name);
+        self
+    }
+
+    /// Check a source file with all rules.
+    pub fn lint(&self, source: &Source) -> EcoVec<SourceDiagnostic> {
+        let bindings = analyze_bindings(source.root());
+        let mut ctx = LintContext {
+            source,
+            bindings: &bindings,
+            diagnostics: EcoVec::new(),
+        };
+        for rule in &self.rules {
+            rule.check(&mut ctx);
+        }
+        ctx.diagnostics
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reports imported names that are never used.
+pub struct UnusedImports;
+
+impl Rule for UnusedImports {
+    fn name(&self) -> &'static str {
+        "unused-imports"
+    }
+
+    fn check(&self, ctx: &mut LintContext) {
+        for binding in ctx.bindings() {
+            if binding.kind == BindingKind::Import && binding.uses.is_empty() {
+                ctx.warn(binding.span, eco_format!("unused import `{}`", binding.name));
+            }
+        }
+    }
+}
+
+/// Reports local variables, functions, and parameters that are never used.
+///
+/// Bindings at the top level of a file are exempt, as other files can import
+/// them. So are names starting with an underscore.
+pub struct UnusedVariables;
+
+impl Rule for UnusedVariables {
+    fn name(&self) -> &'static str {
+        "unused-variables"
+    }
+
+    fn check(&self, ctx: &mut LintContext) {
+        for binding in ctx.bindings() {
+            if binding.kind == BindingKind::Import
+                || binding.top_level
+                || !binding.uses.is_empty()
+                || binding.name.starts_with('_')
+            {
+                continue;
+            }
+
+            let what = match binding.kind {
+                BindingKind::Function => "function",
+                BindingKind::Parameter => "parameter",
+                _ => "variable",
+            };
+
+            ctx.warn(binding.span, eco_format!("unused {what} `{}`", binding.name))
+                .hint("if this is intentional, prefix it with an underscore");
+        }
+    }
+}
+
+/// Reports bindings that hide a binding of the same name from an enclosing
+/// scope.
+pub struct ShadowedBindings;
+
+impl Rule for ShadowedBindings {
+    fn name(&self) -> &'static str {
+        "shadowed-bindings"
+    }
+
+    fn check(&self, ctx: &mut LintContext) {
+        for binding in ctx.bindings() {
+            if binding.shadows.is_some() {
+                ctx.warn(
+                    binding.span,
+                    eco_format!(
+                        "`{}` shadows a binding from an outer scope",
+                        binding.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Reports uses of deprecated definitions from the standard library.
+///
+/// Which definitions are deprecated is determined by the scopes of the
+/// library, so the rule reports the same uses as the compiler, with the same
+/// hints and fixes, but without compiling the file.
+pub struct Deprecated {
+    library: Library,
+}
+
+impl Deprecated {
+    /// Check for deprecated definitions of the given library.
+    pub fn new(library: Library) -> Self {
+        Self { library }
+    }
+
+    /// Resolve an identifier or a chain of field accesses on one to the
+    /// library definition it refers to.
+    fn resolve(&self, node: &SyntaxNode, local: &HashSet<Span>) -> Option<Value> {
+        match node.kind() {
+            SyntaxKind::Ident if !local.contains(&node.span()) => {
+                self.library.global.scope().get(node.text()).cloned()
+            }
+            SyntaxKind::MathIdent if !local.contains(&node.span()) => {
+                self.library.math.scope().get(node.text()).cloned()
+            }
+            SyntaxKind::FieldAccess => {
+                let access = node.cast::<ast::FieldAccess>()?;
+                let target = self.resolve(access.target().to_untyped(), local)?;
+                target.scope()?.get(access.field().get()).cloned()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Deprecated {
+    fn default() -> Self {
+        Self::new(Library::default())
+    }
+}
+
+impl Rule for Deprecated {
+    fn name(&self) -> &'static str {
+        "deprecated"
+    }
+
+    fn check(&self, ctx: &mut LintContext) {
+        // Identifiers that refer to bindings in the file don't refer to the
+        // standard library.
+        let mut local: HashSet<Span> = ctx
+            .bindings()
+            .iter()
+            .flat_map(|b| b.uses.iter().copied().chain([b.span]))
+            .collect();
+
+        // Neither do identifiers that aren't expressions: The names of
+        // accessed fields, named arguments, dictionary keys, and imported
+        // items. Imported items are checked against the scope of the module
+        // they are imported from instead.
+        walk(ctx.root(), &mut |node| {
+            if let Some(access) = node.cast::<ast::FieldAccess>() {
+                local.insert(access.field().span());
+            } else if let Some(named) = node.cast::<ast::Named>() {
+                local.insert(named.name().span());
+            } else if let Some(items) = node.cast::<ast::ImportItems>() {
+                for item in items.iter() {
+                    local.insert(item.original_name().span());
+                    local.insert(item.bound_name().span());
+                }
+            }
+        });
+
+        let mut found = vec![];
+        walk(ctx.root(), &mut |node| {
+            let (scope, name, span) = match node.kind() {
+                SyntaxKind::Ident if !local.contains(&node.span()) => {
+                    (self.library.global.scope(), node.text(), node.span())
+                }
+                SyntaxKind::MathIdent if !local.contains(&node.span()) => {
+                    (self.library.math.scope(), node.text(), node.span())
+                }
+                SyntaxKind::FieldAccess => {
+                    let Some(access) = node.cast::<ast::FieldAccess>() else { return };
+                    let Some(target) = self.resolve(access.target().to_untyped(), &local)
+                    else {
+                        return;
+                    };
+                    let Some(scope) = target.scope() else { return };
+                    let field = access.field();
+                    found.extend(scope.get_deprecation(field.get()).map(|deprecation| {
+                        deprecation.warning(field.span(), field.get())
+                    }));
+                    return;
+                }
+                SyntaxKind::ModuleImport => {
+                    let Some(import) = node.cast::<ast::ModuleImport>() else { return };
+                    let Some(ast::Imports::Items(items)) = import.imports() else {
+                        return;
+                    };
+                    let Some(source) = self.resolve(import.source().to_untyped(), &local)
+                    else {
+                        return;
+                    };
+                    let Some(scope) = source.scope() else { return };
+                    for item in items.iter() {
+                        let name = item.original_name();
+                        found.extend(scope.get_deprecation(name.get()).map(
+                            |deprecation| deprecation.warning(name.span(), name.get()),
+                        ));
+                    }
+                    return;
+                }
+                _ => return,
+            };
+
+            found.extend(
+                scope
+                    .get_deprecation(name)
+                    .map(|deprecation| deprecation.warning(span, name)),
+            );
+        });
+
+        for warning in found {
+            ctx.report(warning);
+        }
+    }
+}
+
+/// Reports headings without a body.
+pub struct EmptyHeadings;
+
+impl Rule for EmptyHeadings {
+    fn name(&self) -> &'static str {
+        "empty-headings"
+    }
+
+    fn check(&self, ctx: &mut LintContext) {
+        let mut found = vec![];
+        walk(ctx.root(), &mut |node| {
+            if let Some(heading) = node.cast::<ast::Heading>() {
+                if heading.body().exprs().all(|expr| matches!(expr, ast::Expr::Space(_)))
+                {
+                    found.push(node.span());
+                }
+            }
+        });
+
+        for span in found {
+            ctx.warn(span, "heading is empty");
+        }
+    }
+}
+
+/// Call a function for a node and all of its descendants.
+fn walk(node: &SyntaxNode, f: &mut impl FnMut(&SyntaxNode)) {
+    f(node);
+    for child in node.children() {
+        walk(child, f);
+    }
+}
@@ -0,0 +1,148 @@
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{Source, Span, SyntaxKind, SyntaxNode};
+
+/// An item in the outline of a source file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DocumentSymbol {
+    /// The name of the symbol, like a heading's text.
+    pub name: EcoString,
+    /// What kind of symbol this is.
+    pub kind: SymbolKind,
+    /// The span of the whole syntax node that defines the symbol.
+    pub span: Span,
+    /// The symbols contained in this one.
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// The kind of a [`DocumentSymbol`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SymbolKind {
+    /// A section heading of the given level.
+    Heading(NonZeroUsize),
+    /// A label.
+    Label,
+    /// A function definition.
+    Function,
+    /// A show rule.
+    ShowRule,
+}
+
+/// Extract the outline of a source file.
+///
+/// Symbols that are syntactically inside of others, like a show rule in a
+/// function, are their children. Moreover, a heading contains everything up
+/// to the next heading of the same or a higher level, so that sections nest.
+pub fn document_symbols(source: &Source) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    collect(source.root(), &mut symbols);
+    nest(symbols)
+}
+
+/// Collect the symbols in a node and its descendants.
+fn collect(node: &SyntaxNode, symbols: &mut Vec<DocumentSymbol>) {
+    let Some((name, kind)) = describe(node) else {
+        for child in node.children() {
+            collect(child, symbols);
+        }
+        return;
+    };
+
+    let mut children = vec![];
+    for child in node.children() {
+        collect(child, &mut children);
+    }
+
+    symbols.push(DocumentSymbol {
+        name,
+        kind,
+        span: node.span(),
+        children: nest(children),
+    });
+}
+
+/// Determine the name and kind of the symbol a node defines, if any.
+fn describe(node: &SyntaxNode) -> Option<(EcoString, SymbolKind)> {
+    match node.kind() {
+        SyntaxKind::Heading => {
+            let heading = node.cast::<ast::Heading>()?;
+            let name = plain(heading.body().to_untyped());
+            Some((name, SymbolKind::Heading(heading.level())))
+        }
+        SyntaxKind::Label => {
+            let label = node.cast::<ast::Label>()?;
+            Some((label.get().into(), SymbolKind::Label))
+        }
+        SyntaxKind::LetBinding => {
+            let binding = node.cast::<ast::LetBinding>()?;
+            let name = match binding.kind() {
+                ast::LetBindingKind::Closure(name) => name,
+                ast::LetBindingKind::Normal(ast::Pattern::Normal(ast::Expr::Ident(
+                    name,
+                ))) if matches!(binding.init(), Some(ast::Expr::Closure(_))) => name,
+                _ => return None,
+            };
+            Some((name.get().clone(), SymbolKind::Function))
+        }
+        SyntaxKind::ShowRule => {
+            let rule = node.cast::<ast::ShowRule>()?;
+            let name = match rule.selector() {
+                Some(selector) => eco_format!("show {}", plain(selector.to_untyped())),
+                None => "show".into(),
+            };
+            Some((name, SymbolKind::ShowRule))
+        }
+        _ => None,
+    }
+}
+
+/// The source text of a node with whitespace collapsed.
+fn plain(node: &SyntaxNode) -> EcoString {
+    let text = node.clone().into_text();
+    let mut plain = EcoString::new();
+    for word in text.split_whitespace() {
+        if !plain.is_empty() {
+            plain.push(' ');
+        }
+        plain.push_str(word);
+    }
+    plain
+}
+
+/// Make the symbols that follow a heading its children, up to the next
+/// heading of the same or a higher level.
+fn nest(symbols: Vec<DocumentSymbol>) -> Vec<DocumentSymbol> {
+    let mut output = vec![];
+    let mut stack: Vec<(NonZeroUsize, DocumentSymbol)> = vec![];
+
+    for symbol in symbols {
+        if let SymbolKind::Heading(level) = symbol.kind {
+            close(&mut stack, &mut output, level);
+            stack.push((level, symbol));
+        } else if let Some((_, parent)) = stack.last_mut() {
+            parent.children.push(symbol);
+        } else {
+            output.push(symbol);
+        }
+    }
+
+    close(&mut stack, &mut output, NonZeroUsize::MIN);
+    output
+}
+
+/// Close all sections of the given or a deeper level.
+fn close(
+    stack: &mut Vec<(NonZeroUsize, DocumentSymbol)>,
+    output: &mut Vec<DocumentSymbol>,
+    level: NonZeroUsize,
+) {
+    while stack.last().is_some_and(|&(top, _)| top >= level) {
+        let (_, symbol) = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(symbol),
+            None => output.push(symbol),
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use std::fmt::Write;
+use std::ops::Range;
 
 use ecow::{eco_format, EcoString};
 use if_chain::if_chain;
 use typst::eval::{CapturesVisitor, Tracer};
-use typst::foundations::{repr, CastInfo, Repr, Value};
+use typst::foundations::{repr, CastInfo, Func, ParamInfo, Repr, Value};
 use typst::layout::Length;
 use typst::model::Document;
 use typst::syntax::{ast, LinkedNode, Source, SyntaxKind};
@@ -11,6 +12,7 @@ use typst::util::{round_2, Numeric};
 use typst::World;
 
 use crate::analyze::{analyze_expr, analyze_labels};
+use crate::definition::resolve;
 use crate::{plain_docs_sentence, summarize_font_family};
 
 /// Describe the item under the cursor.
@@ -45,6 +47,129 @@ pub enum Tooltip {
     Code(EcoString),
 }
 
+/// Detailed information about the item under the cursor.
+///
+/// Unlike a [`Tooltip`], which is a ready-made text, this is meant for
+/// language servers that lay out hover popups themselves.
+#[derive(Debug, Clone)]
+pub struct Hover {
+    /// The byte range of the hovered item.
+    pub range: Range<usize>,
+    /// The type of the item, like `length` or `str | auto` for a parameter.
+    pub ty: Option<EcoString>,
+    /// A preview of the item's value, if it is a constant.
+    pub value: Option<EcoString>,
+    /// The item's documentation in Markdown.
+    pub docs: Option<EcoString>,
+    /// The parameters, if the item is a function from the library.
+    pub params: &'static [ParamInfo],
+}
+
+/// Describe the item under the cursor in detail.
+///
+/// Returns `None` if nothing is known about the item.
+pub fn hover(world: &dyn World, source: &Source, cursor: usize) -> Option<Hover> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    if leaf.kind().is_trivia() {
+        return None;
+    }
+
+    // Hovering over the name of an argument to a library function.
+    if_chain! {
+        if let Some((func, _)) = named_param(world, &leaf);
+        if leaf.index() == 0;
+        if let Some(ident) = leaf.cast::<ast::Ident>();
+        if let Some(param) = func.param(&ident);
+        then {
+            return Some(Hover {
+                range: leaf.range(),
+                ty: Some(describe_input(&param.input)),
+                value: None,
+                docs: Some(param.docs.into()),
+                params: &[],
+            });
+        }
+    }
+
+    let mut ancestor = &leaf;
+    while !ancestor.is::<ast::Expr>() {
+        ancestor = ancestor.parent()?;
+    }
+
+    let expr = ancestor.cast::<ast::Expr>()?;
+    if !expr.hash() && !matches!(expr, ast::Expr::MathIdent(_)) {
+        return None;
+    }
+
+    let values = analyze_expr(world, ancestor);
+    let value = match values.as_slice() {
+        [value] => Some(value),
+        _ => None,
+    };
+
+    // Library definitions have built-in documentation, user-defined ones may
+    // have a doc comment.
+    let docs = value.and_then(Value::docs).map(Into::into).or_else(|| {
+        let (_, chain) = resolve(world, source, cursor)?;
+        Some(chain.last()?.docs.as_ref()?.to_markdown())
+    });
+
+    if value.is_none() && docs.is_none() {
+        return None;
+    }
+
+    let constant = value.filter(|value| {
+        !expr.is_literal()
+            && !matches!(value, Value::Func(_) | Value::Module(_) | Value::Type(_))
+    });
+
+    Some(Hover {
+        range: ancestor.range(),
+        ty: value.map(|value| value.ty().long_name().into()),
+        value: constant.map(preview),
+        docs,
+        params: match value {
+            Some(Value::Func(func)) => func.params().unwrap_or_default(),
+            _ => &[],
+        },
+    })
+}
+
+/// The maximum length of a value preview in a [`Hover`], in characters.
+const MAX_PREVIEW: usize = 100;
+
+/// Preview a value with its representation, shortened if necessary.
+fn preview(value: &Value) -> EcoString {
+    let repr = value.repr();
+    if repr.chars().count() <= MAX_PREVIEW {
+        return repr;
+    }
+
+    let mut shortened: EcoString = repr.chars().take(MAX_PREVIEW - 3).collect();
+    shortened.push_str("...");
+    shortened
+}
+
+/// Describe the values a parameter accepts, like `length | auto`.
+fn describe_input(info: &CastInfo) -> EcoString {
+    let mut parts: Vec<EcoString> = vec![];
+    info.walk(|info| match info {
+        CastInfo::Any => parts.push("any".into()),
+        CastInfo::Value(value, _) => parts.push(value.repr()),
+        CastInfo::Type(ty) => parts.push(ty.long_name().into()),
+        CastInfo::Union(_) => {}
+    });
+
+    let mut unique: Vec<EcoString> = vec![];
+    for part in parts {
+        if !unique.contains(&part) {
+            unique.push(part);
+        }
+    }
+
+    unique.join(" | ").into()
+}
+
 /// Tooltip for a hovered expression.
 fn expr_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip> {
     let mut ancestor = leaf;
@@ -168,26 +293,7 @@ fn label_tooltip(document: &Document, leaf: &LinkedNode) -> Option<Tooltip> {
 
 /// Tooltips for components of a named parameter.
 fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip> {
-    let (func, named) = if_chain! {
-        // Ensure that we are in a named pair in the arguments to a function
-        // call or set rule.
-        if let Some(parent) = leaf.parent();
-        if let Some(named) = parent.cast::<ast::Named>();
-        if let Some(grand) = parent.parent();
-        if matches!(grand.kind(), SyntaxKind::Args);
-        if let Some(grand_grand) = grand.parent();
-        if let Some(expr) = grand_grand.cast::<ast::Expr>();
-        if let Some(ast::Expr::Ident(callee)) = match expr {
-            ast::Expr::FuncCall(call) => Some(call.callee()),
-            ast::Expr::Set(set) => Some(set.target()),
-            _ => None,
-        };
-
-        // Find metadata about the function.
-        if let Some(Value::Func(func)) = world.library().global.scope().get(&callee);
-        then { (func, named) }
-        else { return None; }
-    };
+    let (func, named) = named_param(world, leaf)?;
 
     // Hovering over the parameter name.
     if_chain! {
@@ -212,6 +318,34 @@ fn named_param_tooltip(world: &dyn World, leaf: &LinkedNode) -> Option<Tooltip>
     None
 }
 
+/// Find the library function and the named pair when the leaf is part of a
+/// named argument to a function call or set rule.
+fn named_param<'a>(
+    world: &dyn World,
+    leaf: &'a LinkedNode,
+) -> Option<(Func, ast::Named<'a>)> {
+    if_chain! {
+        // Ensure that we are in a named pair in the arguments to a function
+        // call or set rule.
+        if let Some(parent) = leaf.parent();
+        if let Some(named) = parent.cast::<ast::Named>();
+        if let Some(grand) = parent.parent();
+        if matches!(grand.kind(), SyntaxKind::Args);
+        if let Some(grand_grand) = grand.parent();
+        if let Some(expr) = grand_grand.cast::<ast::Expr>();
+        if let Some(ast::Expr::Ident(callee)) = match expr {
+            ast::Expr::FuncCall(call) => Some(call.callee()),
+            ast::Expr::Set(set) => Some(set.target()),
+            _ => None,
+        };
+
+        // Find metadata about the function.
+        if let Some(Value::Func(func)) = world.library().global.scope().get(&callee);
+        then { Some((func.clone(), named)) }
+        else { None }
+    }
+}
+
 /// Find documentation for a castable string.
 fn find_string_doc(info: &CastInfo, string: &str) -> Option<&'static str> {
     match info {
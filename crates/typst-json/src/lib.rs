@@ -0,0 +1,329 @@
+//! Exporting documents, content, and frames into JSON.
+//!
+//! The JSON follows a stable schema, so that external tools can inspect,
+//! diff, and post-process documents without linking against Typst. Whenever
+//! the schema changes incompatibly, [`SCHEMA_VERSION`] is increased.
+//!
+//! # Content
+//! Content is an object with the element's name in `func` and its fields
+//! under their names, like `{"func": "heading", "level": 1, "body": ...}`.
+//! Nested content is serialized the same way. Values that have no JSON
+//! equivalent, like lengths or colors, are given as their Typst
+//! representation, like `"12pt"`.
+//!
+//! # Frames
+//! A frame is an object with its `width`, `height`, and `baseline` and a list
+//! of `items`. All lengths are in points. Each item has its position
+//! relative to the frame's top left in `x` and `y` and its type in `kind`:
+//!
+//! - `"group"`: A nested `frame` with a `transform` given as the six numbers
+//!   `[sx, ky, kx, sy, tx, ty]` and whether it `clips` its contents.
+//! - `"text"`: A run of `text` in a `font` family with a `size`, a `fill`,
+//!   a `lang`, and the run's `width`.
+//! - `"shape"`: A `"line"`, `"rect"`, or `"path"` `geometry` with its
+//!   `width` and `height` and an optional `fill` and `stroke`.
+//! - `"image"`: An image of a `format` with its `width` and `height`.
+//! - `"meta"`: Meta information of a `type` that applies to an area
+//!   of the given `width` and `height`, like a `"link"` to a `url` or to a
+//!   `page` with the `x` and `y` of the target on it, or an `"elem"` that was
+//!   laid out with its `func`.
+//!
+//! # Documents
+//! A document is an object with the `version` of the schema, the document's
+//! `title`, and its `pages`, which are frames.
+
+use ecow::eco_format;
+use serde_json::{json, Map, Value as Json};
+use typst::diag::StrResult;
+use typst::foundations::{Content, IntoValue, Repr};
+use typst::introspection::{Introspector, Meta};
+use typst::layout::{Frame, FrameItem, Point, Position};
+use typst::model::{Destination, Document};
+use typst::visualize::{Geometry, Paint};
+
+/// The version of the JSON schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Export a document into JSON.
+#[typst_macros::time(name = "json")]
+pub fn json(document: &Document) -> StrResult<String> {
+    let pages: Vec<_> = document
+        .pages
+        .iter()
+        .map(|page| frame(&page.frame, &document.introspector))
+        .collect();
+    let json = json!({
+        "version": SCHEMA_VERSION,
+        "title": document.title.as_deref(),
+        "pages": pages,
+    });
+
+    serde_json::to_string_pretty(&json)
+        .map_err(|err| eco_format!("failed to serialize document ({err})"))
+}
+
+/// Convert content into JSON.
+pub fn content(content: &Content) -> StrResult<Json> {
+    serde_json::to_value(content)
+        .map_err(|err| eco_format!("failed to serialize content ({err})"))
+}
+
+/// Convert a frame into JSON.
+///
+/// The introspector resolves links to locations in the document to the
+/// position they point to.
+pub fn frame(frame: &Frame, introspector: &Introspector) -> Json {
+    let items: Vec<_> = frame
+        .items()
+        .map(|(pos, item)| frame_item(*pos, item, introspector))
+        .collect();
+    json!({
+        "width": frame.width().to_pt(),
+        "height": frame.height().to_pt(),
+        "baseline": frame.baseline().to_pt(),
+        "items": items,
+    })
+}
+
+/// Convert a frame item into JSON.
+fn frame_item(pos: Point, item: &FrameItem, introspector: &Introspector) -> Json {
+    let mut object = Map::new();
+    object.insert("x".into(), pos.x.to_pt().into());
+    object.insert("y".into(), pos.y.to_pt().into());
+
+    let details = match item {
+        FrameItem::Group(group) => {
+            let ts = group.transform;
+            json!({
+                "kind": "group",
+                "frame": frame(&group.frame, introspector),
+                "transform": [
+                    ts.sx.get(),
+                    ts.ky.get(),
+                    ts.kx.get(),
+                    ts.sy.get(),
+                    ts.tx.to_pt(),
+                    ts.ty.to_pt(),
+                ],
+                "clips": group.clip_path.is_some(),
+            })
+        }
+        FrameItem::Text(text) => json!({
+            "kind": "text",
+            "text": text.text.as_str(),
+            "font": text.font.info().family.as_str(),
+            "size": text.size.to_pt(),
+            "fill": paint(&text.fill),
+            "lang": text.lang.as_str(),
+            "width": text.width().to_pt(),
+        }),
+        FrameItem::Shape(shape, _) => {
+            let (geometry, size) = match &shape.geometry {
+                Geometry::Line(to) => ("line", (to.x.to_pt(), to.y.to_pt())),
+                Geometry::Rect(size) => ("rect", (size.x.to_pt(), size.y.to_pt())),
+                Geometry::Path(_) => {
+                    let size = shape.geometry.bbox_size();
+                    ("path", (size.x.to_pt(), size.y.to_pt()))
+                }
+            };
+            json!({
+                "kind": "shape",
+                "geometry": geometry,
+                "width": size.0,
+                "height": size.1,
+                "fill": shape.fill.as_ref().map(paint),
+                "stroke": shape.stroke.as_ref().map(|stroke| paint(&stroke.paint)),
+            })
+        }
+        FrameItem::Image(image, size, _) => json!({
+            "kind": "image",
+            "format": image.format().into_value(),
+            "width": size.x.to_pt(),
+            "height": size.y.to_pt(),
+        }),
+        FrameItem::Meta(meta, size) => {
+            let mut details = match meta {
+                Meta::Link(Destination::Url(url)) => {
+                    json!({ "type": "link", "url": url.as_str() })
+                }
+                Meta::Link(Destination::Position(position)) => link(*position),
+                Meta::Link(Destination::Location(loc)) => {
+                    link(introspector.position(*loc))
+                }
+                Meta::Elem(elem) => {
+                    json!({ "type": "elem", "func": elem.elem().name() })
+                }
+                Meta::Field(field) => {
+                    json!({ "type": "field", "name": field.name.as_str() })
+                }
+                Meta::Hide => json!({ "type": "hide" }),
+            };
+            details["kind"] = "meta".into();
+            details["width"] = size.x.to_pt().into();
+            details["height"] = size.y.to_pt().into();
+            details
+        }
+    };
+
+    if let Json::Object(details) = details {
+        object.extend(details);
+    }
+
+    Json::Object(object)
+}
+
+/// Convert a link to a position into JSON.
+fn link(position: Position) -> Json {
+    json!({
+        "type": "link",
+        "page": position.page.get(),
+        "x": position.point.x.to_pt(),
+        "y": position.point.y.to_pt(),
+    })
+}
+
+/// Convert a paint into JSON, as a hex color if possible.
+fn paint(paint: &Paint) -> Json {
+    match paint {
+        Paint::Solid(color) => color.to_hex().as_str().into(),
+        other => other.repr().as_str().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::eval::Tracer;
+    use typst::foundations::NativeElement;
+    use typst::layout::{Abs, GroupItem, Size};
+    use typst::model::StrongElem;
+    use typst::syntax::Span;
+    use typst::text::TextElem;
+    use typst::visualize::Color;
+    use typst::MemoryWorld;
+
+    use super::*;
+
+    /// Find the first item of the given meta type in a frame.
+    fn find<'a>(frame: &'a Json, ty: &str) -> Option<&'a Json> {
+        frame["items"].as_array()?.iter().find_map(|item| {
+            if item["type"] == ty {
+                Some(item)
+            } else {
+                find(&item["frame"], ty)
+            }
+        })
+    }
+
+    #[test]
+    fn test_frame_schema() {
+        let pt = Abs::pt;
+        let mut inner = Frame::soft(Size::new(pt(2.0), pt(3.0)));
+        inner.push(
+            Point::zero(),
+            FrameItem::Meta(Meta::Hide, Size::new(pt(2.0), pt(3.0))),
+        );
+
+        let mut frame = Frame::soft(Size::new(pt(20.0), pt(10.0)));
+        let shape =
+            Geometry::Rect(Size::new(pt(5.0), pt(5.0))).filled(Color::BLACK.into());
+        frame.push(
+            Point::new(pt(1.0), pt(2.0)),
+            FrameItem::Shape(shape, Span::detached()),
+        );
+        frame.push(
+            Point::zero(),
+            FrameItem::Meta(
+                Meta::Link(Destination::Url("https://typst.app".into())),
+                Size::new(pt(20.0), pt(10.0)),
+            ),
+        );
+        frame.push(Point::new(pt(4.0), pt(4.0)), FrameItem::Group(GroupItem::new(inner)));
+
+        assert_eq!(
+            super::frame(&frame, &Introspector::default()),
+            json!({
+                "width": 20.0,
+                "height": 10.0,
+                "baseline": 10.0,
+                "items": [
+                    {
+                        "x": 1.0,
+                        "y": 2.0,
+                        "kind": "shape",
+                        "geometry": "rect",
+                        "width": 5.0,
+                        "height": 5.0,
+                        "fill": "#000000",
+                        "stroke": null,
+                    },
+                    {
+                        "x": 0.0,
+                        "y": 0.0,
+                        "kind": "meta",
+                        "type": "link",
+                        "url": "https://typst.app",
+                        "width": 20.0,
+                        "height": 10.0,
+                    },
+                    {
+                        "x": 4.0,
+                        "y": 4.0,
+                        "kind": "group",
+                        "transform": [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+                        "clips": false,
+                        "frame": {
+                            "width": 2.0,
+                            "height": 3.0,
+                            "baseline": 3.0,
+                            "items": [{
+                                "x": 0.0,
+                                "y": 0.0,
+                                "kind": "meta",
+                                "type": "hide",
+                                "width": 2.0,
+                                "height": 3.0,
+                            }],
+                        },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_content_schema() {
+        let strong = StrongElem::new(TextElem::packed("hi")).pack();
+        assert_eq!(
+            content(&strong).unwrap(),
+            json!({
+                "func": "strong",
+                "body": { "func": "text", "text": "hi" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_document_schema() {
+        let world = MemoryWorld::new(
+            "#set document(title: \"Test\")\n\
+             #set page(width: 50pt, height: 50pt, margin: 0pt)\n\
+             #link(<target>, box(width: 10pt, height: 10pt))\n\
+             #pagebreak()\n\
+             #v(20pt)\n\
+             #box(width: 5pt, height: 5pt) <target>",
+        );
+        let document = typst::compile(&world, &mut Tracer::new()).unwrap();
+        let json: Json = serde_json::from_str(&super::json(&document).unwrap()).unwrap();
+
+        assert_eq!(json["version"], SCHEMA_VERSION);
+        assert_eq!(json["title"], "Test");
+        assert_eq!(json["pages"].as_array().unwrap().len(), 2);
+
+        // Links to a location point to the position of their target.
+        let link = find(&json["pages"][0], "link").unwrap();
+        assert_eq!(link["page"], 2);
+        assert_eq!(link["x"], 0.0);
+        assert!(link["y"].as_f64().unwrap() >= 20.0);
+        assert!(link.get("url").is_none());
+    }
+}
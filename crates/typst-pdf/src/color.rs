@@ -4,6 +4,7 @@ use pdf_writer::{writers, Chunk, Dict, Filter, Name, Ref};
 use typst::visualize::{Color, ColorSpace, Paint};
 
 use crate::deflate;
+use crate::encrypt::Crypt;
 use crate::page::{PageContext, Transforms};
 
 // The names of the color spaces.
@@ -11,6 +12,7 @@ pub const SRGB: Name<'static> = Name(b"srgb");
 pub const D65_GRAY: Name<'static> = Name(b"d65gray");
 pub const OKLAB: Name<'static> = Name(b"oklab");
 pub const LINEAR_SRGB: Name<'static> = Name(b"linearrgb");
+pub const CMYK: Name<'static> = Name(b"cmyk");
 
 // The names of the color components.
 const OKLAB_L: Name<'static> = Name(b"L");
@@ -33,6 +35,7 @@ pub struct ColorSpaces {
     oklab: Option<Ref>,
     srgb: Option<Ref>,
     d65_gray: Option<Ref>,
+    cmyk: Option<(Ref, Vec<u8>)>,
     use_linear_rgb: bool,
 }
 
@@ -56,6 +59,22 @@ impl ColorSpaces {
         *self.d65_gray.get_or_insert_with(|| alloc.bump())
     }
 
+    /// Use an ICC profile for CMYK colors instead of the device CMYK space.
+    ///
+    /// The deflated profile is always written, so the returned reference can
+    /// also be used by other parts of the document.
+    pub fn set_cmyk_profile(&mut self, profile: &[u8], alloc: &mut Ref) -> Ref {
+        let id = alloc.bump();
+        self.cmyk = Some((id, deflate(profile)));
+        id
+    }
+
+    /// Get a reference to the ICC-based CMYK color space, if a CMYK profile
+    /// was set.
+    pub fn cmyk(&self) -> Option<Ref> {
+        self.cmyk.as_ref().map(|&(id, _)| id)
+    }
+
     /// Mark linear RGB as used.
     pub fn linear_rgb(&mut self) {
         self.use_linear_rgb = true;
@@ -89,7 +108,10 @@ impl ColorSpaces {
                     ]),
                 );
             }
-            ColorSpace::Cmyk => writer.device_cmyk(),
+            ColorSpace::Cmyk => match self.cmyk() {
+                Some(id) => writer.icc_based(id),
+                None => writer.device_cmyk(),
+            },
         }
     }
 
@@ -110,15 +132,19 @@ impl ColorSpaces {
         if self.use_linear_rgb {
             self.write(ColorSpace::LinearRgb, spaces.insert(LINEAR_SRGB).start(), alloc);
         }
+
+        if self.cmyk.is_some() {
+            self.write(ColorSpace::Cmyk, spaces.insert(CMYK).start(), alloc);
+        }
     }
 
     /// Write the necessary color spaces functions and ICC profiles to the
     /// PDF file.
-    pub fn write_functions(&self, chunk: &mut Chunk) {
+    pub fn write_functions(&self, chunk: &mut Chunk, crypt: &Crypt) {
         // Write the Oklab function & color space.
         if let Some(oklab) = self.oklab {
             chunk
-                .post_script_function(oklab, &OKLAB_DEFLATED)
+                .post_script_function(oklab, &crypt.stream(&OKLAB_DEFLATED))
                 .domain([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -127,7 +153,7 @@ impl ColorSpaces {
         // Write the sRGB color space.
         if let Some(srgb) = self.srgb {
             chunk
-                .icc_profile(srgb, &SRGB_ICC_DEFLATED)
+                .icc_profile(srgb, &crypt.stream(&SRGB_ICC_DEFLATED))
                 .n(3)
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -136,11 +162,20 @@ impl ColorSpaces {
         // Write the gray color space.
         if let Some(gray) = self.d65_gray {
             chunk
-                .icc_profile(gray, &GRAY_ICC_DEFLATED)
+                .icc_profile(gray, &crypt.stream(&GRAY_ICC_DEFLATED))
                 .n(1)
                 .range([0.0, 1.0])
                 .filter(Filter::FlateDecode);
         }
+
+        // Write the custom CMYK color space.
+        if let Some((cmyk, profile)) = &self.cmyk {
+            chunk
+                .icc_profile(*cmyk, &crypt.stream(profile))
+                .n(4)
+                .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+                .filter(Filter::FlateDecode);
+        }
     }
 }
 
@@ -264,10 +299,14 @@ impl PaintEncode for Color {
                 ctx.content.set_fill_color([r, g, b]);
             }
             Color::Cmyk(_) => {
-                ctx.reset_fill_color_space();
-
                 let [c, m, y, k] = ColorSpace::Cmyk.encode(*self);
-                ctx.content.set_fill_cmyk(c, m, y, k);
+                if ctx.parent.colors.cmyk().is_some() {
+                    ctx.set_fill_color_space(CMYK);
+                    ctx.content.set_fill_color([c, m, y, k]);
+                } else {
+                    ctx.reset_fill_color_space();
+                    ctx.content.set_fill_cmyk(c, m, y, k);
+                }
             }
         }
     }
@@ -304,10 +343,14 @@ impl PaintEncode for Color {
                 ctx.content.set_stroke_color([r, g, b]);
             }
             Color::Cmyk(_) => {
-                ctx.reset_stroke_color_space();
-
                 let [c, m, y, k] = ColorSpace::Cmyk.encode(*self);
-                ctx.content.set_stroke_cmyk(c, m, y, k);
+                if ctx.parent.colors.cmyk().is_some() {
+                    ctx.set_stroke_color_space(CMYK);
+                    ctx.content.set_stroke_color([c, m, y, k]);
+                } else {
+                    ctx.reset_stroke_color_space();
+                    ctx.content.set_stroke_cmyk(c, m, y, k);
+                }
             }
         }
     }
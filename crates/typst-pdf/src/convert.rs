@@ -0,0 +1,241 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use typst::layout::{Frame, FrameItem, GroupItem, Page, Ratio};
+use typst::model::Document;
+use typst::text::TextItem;
+use typst::visualize::{
+    Cmyk, Color, ColorSpace, ConicGradient, FixedStroke, Gradient, Image, ImageKind,
+    LinearGradient, Paint, RadialGradient, RasterFormat, Shape,
+};
+
+/// A conversion applied to all colors of a document during export.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ColorConversion {
+    /// Convert text, shapes, gradients, patterns, and raster images to
+    /// grayscale, so that the document can be printed on a single plate.
+    ///
+    /// The colors of SVG images are not converted.
+    Grayscale,
+    /// Limit the total ink coverage of CMYK colors, where `1.0` is the
+    /// coverage of one fully saturated ink. Print shops typically require
+    /// values between `2.4` and `3.2`.
+    ///
+    /// The ink shared by cyan, magenta, and yellow is replaced by black
+    /// first. If that is not enough, the colored inks are reduced
+    /// proportionally. Colors in other color spaces are not converted.
+    InkLimit(f32),
+}
+
+impl ColorConversion {
+    /// Convert a single color.
+    fn color(self, color: Color) -> Color {
+        match self {
+            Self::Grayscale => grayscale(color),
+            Self::InkLimit(limit) => limit_ink(color, limit),
+        }
+    }
+
+    /// Convert the colors of a paint.
+    fn paint(self, paint: &Paint) -> Paint {
+        match paint {
+            Paint::Solid(color) => Paint::Solid(self.color(*color)),
+            Paint::Gradient(gradient) => Paint::Gradient(self.gradient(gradient)),
+            Paint::Pattern(pattern) => {
+                Paint::Pattern(pattern.clone().with_frame(self.frame(pattern.frame())))
+            }
+        }
+    }
+
+    /// Convert the paint of a stroke.
+    fn stroke(self, stroke: &FixedStroke) -> FixedStroke {
+        FixedStroke { paint: self.paint(&stroke.paint), ..stroke.clone() }
+    }
+
+    /// Convert the stops of a gradient.
+    fn gradient(self, gradient: &Gradient) -> Gradient {
+        let stops = |stops: &[(Color, Ratio)]| -> Vec<(Color, Ratio)> {
+            stops
+                .iter()
+                .map(|&(color, offset)| (self.color(color), offset))
+                .collect()
+        };
+
+        // Interpolating in a gray space ensures that no other colors are
+        // produced between the stops.
+        let space = |space| match self {
+            Self::Grayscale => ColorSpace::D65Gray,
+            Self::InkLimit(_) => space,
+        };
+
+        match gradient {
+            Gradient::Linear(linear) => Gradient::Linear(Arc::new(LinearGradient {
+                stops: stops(&linear.stops),
+                space: space(linear.space),
+                ..linear.as_ref().clone()
+            })),
+            Gradient::Radial(radial) => Gradient::Radial(Arc::new(RadialGradient {
+                stops: stops(&radial.stops),
+                space: space(radial.space),
+                ..radial.as_ref().clone()
+            })),
+            Gradient::Conic(conic) => Gradient::Conic(Arc::new(ConicGradient {
+                stops: stops(&conic.stops),
+                space: space(conic.space),
+                ..conic.as_ref().clone()
+            })),
+        }
+    }
+
+    /// Convert all colors in a frame.
+    fn frame(self, frame: &Frame) -> Frame {
+        let mut output = Frame::new(frame.size(), frame.kind());
+        if frame.has_baseline() {
+            output.set_baseline(frame.baseline());
+        }
+
+        for (pos, item) in frame.items() {
+            let item = match item {
+                FrameItem::Group(group) => FrameItem::Group(GroupItem {
+                    frame: self.frame(&group.frame),
+                    ..group.clone()
+                }),
+                FrameItem::Text(text) => FrameItem::Text(TextItem {
+                    fill: self.paint(&text.fill),
+                    stroke: text.stroke.as_ref().map(|stroke| self.stroke(stroke)),
+                    ..text.clone()
+                }),
+                FrameItem::Shape(shape, span) => FrameItem::Shape(
+                    Shape {
+                        fill: shape.fill.as_ref().map(|fill| self.paint(fill)),
+                        stroke: shape.stroke.as_ref().map(|stroke| self.stroke(stroke)),
+                        ..shape.clone()
+                    },
+                    *span,
+                ),
+                FrameItem::Image(image, size, span) if self == Self::Grayscale => {
+                    FrameItem::Image(grayscale_image(image), *size, *span)
+                }
+                _ => item.clone(),
+            };
+            output.push(*pos, item);
+        }
+
+        output
+    }
+}
+
+/// Apply a color conversion to all pages of a document.
+pub(crate) fn convert_document(
+    document: &Document,
+    conversion: ColorConversion,
+) -> Document {
+    Document {
+        pages: document
+            .pages
+            .iter()
+            .map(|page| Page {
+                frame: conversion.frame(&page.frame),
+                ..page.clone()
+            })
+            .collect(),
+        ..document.clone()
+    }
+}
+
+/// Convert a color to gray, keeping its opacity.
+fn grayscale(color: Color) -> Color {
+    let gray = color.to_luma();
+    match color.alpha() {
+        // Gray colors can't be translucent, so we fall back to an RGB color
+        // with equal components.
+        Some(alpha) if alpha < 1.0 => {
+            let [luma, ..] = gray.to_vec4().map(|v| (v * 255.0).round() as u8);
+            Color::from_u8(luma, luma, luma, (alpha * 255.0).round() as u8)
+        }
+        _ => gray,
+    }
+}
+
+/// Limit the total ink coverage of a CMYK color.
+fn limit_ink(color: Color, limit: f32) -> Color {
+    let Color::Cmyk(Cmyk { mut c, mut m, mut y, mut k }) = color else {
+        return color;
+    };
+
+    if c + m + y + k <= limit {
+        return color;
+    }
+
+    // Replace the gray component of the colored inks with black.
+    let gray = c.min(m).min(y).min(1.0 - k);
+    c -= gray;
+    m -= gray;
+    y -= gray;
+    k += gray;
+
+    // Reduce the colored inks proportionally if that wasn't enough.
+    let colored = c + m + y;
+    let excess = colored + k - limit;
+    if excess > 0.0 && colored > 0.0 {
+        let scale = ((colored - excess) / colored).max(0.0);
+        c *= scale;
+        m *= scale;
+        y *= scale;
+    }
+
+    Color::Cmyk(Cmyk { c, m, y, k })
+}
+
+/// Convert a raster image to grayscale. SVG images are returned as-is.
+#[comemo::memoize]
+fn grayscale_image(image: &Image) -> Image {
+    let ImageKind::Raster(raster) = image.kind() else {
+        return image.clone();
+    };
+
+    let mut data = vec![];
+    let gray = raster.dynamic().grayscale();
+    if gray
+        .write_to(&mut Cursor::new(&mut data), image::ImageFormat::Png)
+        .is_err()
+    {
+        return image.clone();
+    }
+
+    Image::new(data.into(), RasterFormat::Png.into(), image.alt().map(Into::into))
+        .unwrap_or_else(|_| image.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmyk(c: f32, m: f32, y: f32, k: f32) -> Color {
+        Color::Cmyk(Cmyk { c, m, y, k })
+    }
+
+    fn total(color: Color) -> f32 {
+        color.to_vec4().iter().sum()
+    }
+
+    #[test]
+    fn test_limit_ink() {
+        // Colors within the limit are untouched.
+        let color = cmyk(0.5, 0.5, 0.2, 0.3);
+        assert_eq!(limit_ink(color, 2.4), color);
+
+        // Rich black is limited by replacing colored ink with black.
+        let limited = limit_ink(cmyk(1.0, 1.0, 1.0, 0.5), 2.4);
+        assert!(total(limited) <= 2.4 + 1e-6);
+        assert_eq!(limited.to_vec4()[3], 1.0);
+
+        // Without a shared gray component, the colored inks are reduced.
+        let limited = limit_ink(cmyk(1.0, 1.0, 0.0, 0.8), 2.4);
+        assert!((total(limited) - 2.4).abs() < 1e-6);
+
+        // Other color spaces have no ink.
+        let rgb = Color::from_u8(255, 0, 0, 255);
+        assert_eq!(limit_ink(rgb, 1.0), rgb);
+    }
+}
@@ -0,0 +1,47 @@
+use ecow::EcoString;
+use pdf_writer::{Filter, Finish, Name, Ref};
+
+use crate::{deflate, PdfContext};
+
+/// Write the document's file attachments.
+///
+/// Returns the file names together with the references to their file
+/// specifications, sorted by name as required for the catalog's name tree.
+#[typst_macros::time(name = "write embedded files")]
+pub(crate) fn write_embedded_files(ctx: &mut PdfContext) -> Vec<(EcoString, Ref)> {
+    let mut specs = vec![];
+
+    for file in &ctx.document.files {
+        let stream_ref = ctx.alloc.bump();
+        let spec_ref = ctx.alloc.bump();
+
+        let data = deflate(&file.data);
+        let data = ctx.crypt.stream(&data);
+        let mut stream = ctx.pdf.stream(stream_ref, &data);
+        stream.filter(Filter::FlateDecode);
+        stream.pair(Name(b"Type"), Name(b"EmbeddedFile"));
+        if let Some(mime) = &file.mime {
+            stream.pair(Name(b"Subtype"), Name(mime.as_bytes()));
+        }
+        stream
+            .insert(Name(b"Params"))
+            .dict()
+            .pair(Name(b"Size"), file.data.len() as i32);
+        stream.finish();
+
+        let mut spec = ctx.pdf.indirect(spec_ref).dict();
+        spec.pair(Name(b"Type"), Name(b"Filespec"));
+        spec.pair(Name(b"F"), ctx.crypt.str(file.name.as_bytes()));
+        spec.pair(Name(b"UF"), ctx.crypt.text(&file.name));
+        if let Some(description) = &file.description {
+            spec.pair(Name(b"Desc"), ctx.crypt.text(description));
+        }
+        spec.insert(Name(b"EF")).dict().pair(Name(b"F"), stream_ref);
+        spec.finish();
+
+        specs.push((file.name.clone(), spec_ref));
+    }
+
+    specs.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+    specs
+}
@@ -0,0 +1,469 @@
+//! Standard security handler encryption (AES-256, revision 6).
+//!
+//! Since `pdf-writer` has no notion of encryption, all strings and streams
+//! outside of content streams are written through a [`Crypt`], which encrypts
+//! them with the file key as they are written. This includes the document
+//! metadata. Content streams are encrypted as a whole like any other stream.
+
+use std::borrow::Cow;
+
+use aes::cipher::block_padding::{NoPadding, Pkcs7};
+use aes::cipher::{BlockEncrypt, BlockEncryptMut, KeyInit, KeyIvInit};
+use ecow::eco_format;
+use pdf_writer::{Chunk, Finish, Name, Primitive, Ref, Str, TextStr};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use typst::diag::StrResult;
+
+type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
+
+/// Settings for encrypting a PDF file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PdfEncryption {
+    /// The password needed to open the file. May be empty, in which case any
+    /// viewer can open the file, but has to respect the permissions.
+    pub user_password: String,
+    /// The password that grants full access to the file, independently of the
+    /// permissions. If this is `None`, a random password is used.
+    pub owner_password: Option<String>,
+    /// What a user without the owner password may do with the file.
+    pub permissions: PdfPermissions,
+}
+
+/// What a user of an encrypted PDF may do without the owner password.
+///
+/// Note that these are merely advisory: They are enforced by well-behaved
+/// viewers, but can be bypassed by anyone who can open the file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfPermissions {
+    /// Whether the document may be printed.
+    pub print: bool,
+    /// Whether the document may be printed at full quality. Only has an
+    /// effect if `print` is `true`.
+    pub print_high_quality: bool,
+    /// Whether the contents may be modified.
+    pub modify: bool,
+    /// Whether text and graphics may be copied. Extraction for accessibility
+    /// purposes is always permitted.
+    pub copy: bool,
+    /// Whether annotations may be added and form fields may be filled in.
+    pub annotate: bool,
+    /// Whether pages may be inserted, removed, and rotated.
+    pub assemble: bool,
+}
+
+impl PdfPermissions {
+    /// Encode the permissions into the value of the `P` entry.
+    fn bits(self) -> u32 {
+        // Bits 7, 8, and 13-32 are reserved and must be set. Bit 10
+        // (accessibility extraction) is always granted.
+        let mut bits = 0xFFFF_F0C0 | (1 << 9);
+        let mut flag = |enabled: bool, bit: u32| {
+            if enabled {
+                bits |= 1 << (bit - 1);
+            }
+        };
+        flag(self.print, 3);
+        flag(self.modify, 4);
+        flag(self.copy, 5);
+        flag(self.annotate, 6);
+        flag(self.annotate, 9);
+        flag(self.assemble, 11);
+        flag(self.print && self.print_high_quality, 12);
+        bits
+    }
+}
+
+impl Default for PdfPermissions {
+    fn default() -> Self {
+        Self {
+            print: true,
+            print_high_quality: true,
+            modify: true,
+            copy: true,
+            annotate: true,
+            assemble: true,
+        }
+    }
+}
+
+/// Encrypts strings and streams while they are written.
+///
+/// All strings and streams outside of content streams are written through
+/// this. For an unencrypted file, it passes them through unchanged.
+#[derive(Default)]
+pub(crate) struct Crypt {
+    keys: Option<Keys>,
+}
+
+impl Crypt {
+    /// Derive the keys for a file encrypted with the given settings.
+    pub fn new(encryption: &PdfEncryption) -> StrResult<Self> {
+        Ok(Self { keys: Some(Keys::new(encryption)?) })
+    }
+
+    /// Whether the file is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// A text string.
+    pub fn text<'a>(&self, text: &'a str) -> CryptStr<'a> {
+        match &self.keys {
+            Some(keys) => CryptStr::Encrypted(keys.encrypt(&encode_text(text))),
+            None => CryptStr::Text(TextStr(text)),
+        }
+    }
+
+    /// A byte string.
+    pub fn str<'a>(&self, bytes: &'a [u8]) -> CryptStr<'a> {
+        match &self.keys {
+            Some(keys) => CryptStr::Encrypted(keys.encrypt(bytes)),
+            None => CryptStr::Bytes(Str(bytes)),
+        }
+    }
+
+    /// The data of a stream.
+    pub fn stream<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match &self.keys {
+            Some(keys) => Cow::Owned(keys.encrypt(data)),
+            None => Cow::Borrowed(data),
+        }
+    }
+
+    /// Write the encryption dictionary, if the file is encrypted, and return
+    /// its reference.
+    pub fn write_dict(&self, chunk: &mut Chunk, alloc: &mut Ref) -> Option<Ref> {
+        let keys = self.keys.as_ref()?;
+        let id = alloc.bump();
+        let mut dict = chunk.indirect(id).dict();
+        dict.pair(Name(b"Filter"), Name(b"Standard"));
+        dict.pair(Name(b"V"), 5);
+        dict.pair(Name(b"R"), 6);
+        dict.pair(Name(b"Length"), 256);
+
+        let mut filters = dict.insert(Name(b"CF")).dict();
+        let mut filter = filters.insert(Name(b"StdCF")).dict();
+        filter.pair(Name(b"Type"), Name(b"CryptFilter"));
+        filter.pair(Name(b"CFM"), Name(b"AESV3"));
+        filter.pair(Name(b"AuthEvent"), Name(b"DocOpen"));
+        filter.pair(Name(b"Length"), 32);
+        filter.finish();
+        filters.finish();
+
+        // The entries of this dictionary are the only strings that are
+        // never encrypted.
+        dict.pair(Name(b"StmF"), Name(b"StdCF"));
+        dict.pair(Name(b"StrF"), Name(b"StdCF"));
+        dict.pair(Name(b"O"), Str(&keys.o));
+        dict.pair(Name(b"U"), Str(&keys.u));
+        dict.pair(Name(b"OE"), Str(&keys.oe));
+        dict.pair(Name(b"UE"), Str(&keys.ue));
+        dict.pair(Name(b"P"), keys.p as i32);
+        dict.pair(Name(b"Perms"), Str(&keys.perms));
+        dict.finish();
+        Some(id)
+    }
+}
+
+/// A string that is encrypted if the file is.
+pub(crate) enum CryptStr<'a> {
+    /// An unencrypted text string.
+    Text(TextStr<'a>),
+    /// An unencrypted byte string.
+    Bytes(Str<'a>),
+    /// The encrypted bytes of a string.
+    Encrypted(Vec<u8>),
+}
+
+impl Primitive for CryptStr<'_> {
+    fn write(self, buf: &mut Vec<u8>) {
+        match self {
+            Self::Text(text) => text.write(buf),
+            Self::Bytes(bytes) => bytes.write(buf),
+            Self::Encrypted(bytes) => Str(&bytes).write(buf),
+        }
+    }
+}
+
+/// Refer to the encryption dictionary from the trailer of a finished file.
+///
+/// `pdf-writer` has no way to add entries to the trailer, so the entry is
+/// inserted at the end of the trailer dictionary. Since the trailer follows
+/// all objects and the cross-reference table, nothing else moves.
+pub(crate) fn link_dict(pdf: &mut Vec<u8>, id: Ref) {
+    let Some(end) = pdf.windows(2).rposition(|window| window == b">>") else {
+        return;
+    };
+    let entry = format!("  /Encrypt {} 0 R\n", id.get());
+    pdf.splice(end..end, entry.into_bytes());
+}
+
+/// Encode a text string like `TextStr` does: As PDFDocEncoding if it is ASCII
+/// and as UTF-16BE with a byte order mark otherwise.
+fn encode_text(text: &str) -> Vec<u8> {
+    if text.is_ascii() {
+        return text.as_bytes().to_vec();
+    }
+
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    bytes
+}
+
+/// The derived keys and dictionary entries of the security handler.
+struct Keys {
+    file_key: [u8; 32],
+    o: Vec<u8>,
+    u: Vec<u8>,
+    oe: Vec<u8>,
+    ue: Vec<u8>,
+    perms: Vec<u8>,
+    p: u32,
+}
+
+impl Keys {
+    /// Derive all keys with Algorithms 8, 9, and 10 of ISO 32000-2.
+    fn new(encryption: &PdfEncryption) -> StrResult<Self> {
+        let mut file_key = [0; 32];
+        random(&mut file_key)?;
+
+        let owner_password = match &encryption.owner_password {
+            Some(password) => password.clone(),
+            None => {
+                let mut bytes = [0; 16];
+                random(&mut bytes)?;
+                bytes.iter().map(|b| format!("{b:02x}")).collect()
+            }
+        };
+
+        let user = truncate_password(&encryption.user_password);
+        let owner = truncate_password(&owner_password);
+
+        // Algorithm 8: The user entries.
+        let mut salts = [0; 16];
+        random(&mut salts)?;
+        let (validation, key_salt) = salts.split_at(8);
+        let mut u = hash(user, validation, &[]);
+        u.extend_from_slice(validation);
+        u.extend_from_slice(key_salt);
+        let ue = aes256_no_iv(&hash(user, key_salt, &[]), &file_key);
+
+        // Algorithm 9: The owner entries.
+        random(&mut salts)?;
+        let (validation, key_salt) = salts.split_at(8);
+        let mut o = hash(owner, validation, &u);
+        o.extend_from_slice(validation);
+        o.extend_from_slice(key_salt);
+        let oe = aes256_no_iv(&hash(owner, key_salt, &u), &file_key);
+
+        // Algorithm 10: The encrypted permissions.
+        let p = encryption.permissions.bits();
+        let mut block = aes::Block::default();
+        block[..4].copy_from_slice(&p.to_le_bytes());
+        block[4..8].fill(0xFF);
+        block[8..12].copy_from_slice(b"Fadb");
+        random(&mut block[12..])?;
+        aes::Aes256::new_from_slice(&file_key)
+            .map_err(|_| "invalid key length")?
+            .encrypt_block(&mut block);
+
+        Ok(Self { file_key, o, u, oe, ue, perms: block.to_vec(), p })
+    }
+
+    /// Encrypt a string or a stream's data with a random initialization
+    /// vector.
+    fn encrypt(&self, data: &[u8]) -> Vec<u8> {
+        let mut iv = [0; 16];
+        if random(&mut iv).is_err() {
+            // Fall back to a data-derived vector. This is still valid since
+            // the vector need not be secret, only unpredictable.
+            iv.copy_from_slice(&Sha256::digest(data)[..16]);
+        }
+        let encrypted = Aes256CbcEnc::new_from_slices(&self.file_key, &iv)
+            .unwrap()
+            .encrypt_padded_vec_mut::<Pkcs7>(data);
+        let mut out = iv.to_vec();
+        out.extend(encrypted);
+        out
+    }
+}
+
+/// Algorithm 2.B of ISO 32000-2: Compute a password hash.
+fn hash(password: &[u8], salt: &[u8], user_key: &[u8]) -> Vec<u8> {
+    let mut k = Sha256::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(user_key)
+        .finalize()
+        .to_vec();
+
+    let mut round = 0;
+    loop {
+        let mut k1 = Vec::with_capacity(64 * (password.len() + k.len() + user_key.len()));
+        for _ in 0..64 {
+            k1.extend_from_slice(password);
+            k1.extend_from_slice(&k);
+            k1.extend_from_slice(user_key);
+        }
+
+        let e = Aes128CbcEnc::new_from_slices(&k[..16], &k[16..32])
+            .unwrap()
+            .encrypt_padded_vec_mut::<NoPadding>(&k1);
+
+        // The first 16 bytes as a big-endian number modulo 3 are the same as
+        // their sum modulo 3 because 256 ≡ 1 (mod 3).
+        k = match e[..16].iter().map(|&b| b as u32).sum::<u32>() % 3 {
+            0 => Sha256::digest(&e).to_vec(),
+            1 => Sha384::digest(&e).to_vec(),
+            _ => Sha512::digest(&e).to_vec(),
+        };
+
+        round += 1;
+        if round >= 64 && (e[e.len() - 1] as u32) <= round - 32 {
+            break;
+        }
+    }
+
+    k.truncate(32);
+    k
+}
+
+/// Encrypt the file key with AES-256 in CBC mode with a zero vector.
+fn aes256_no_iv(key: &[u8], data: &[u8]) -> Vec<u8> {
+    Aes256CbcEnc::new_from_slices(key, &[0; 16])
+        .unwrap()
+        .encrypt_padded_vec_mut::<NoPadding>(data)
+}
+
+/// Passwords are limited to 127 bytes of UTF-8.
+fn truncate_password(password: &str) -> &[u8] {
+    let mut end = password.len().min(127);
+    while !password.is_char_boundary(end) {
+        end -= 1;
+    }
+    password[..end].as_bytes()
+}
+
+/// Fill the buffer with cryptographically secure random bytes.
+fn random(buf: &mut [u8]) -> StrResult<()> {
+    getrandom::getrandom(buf)
+        .map_err(|err| eco_format!("failed to gather randomness ({err})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pdf_writer::Pdf;
+    use typst::eval::Tracer;
+    use typst::foundations::Bytes;
+    use typst::model::Document;
+    use typst::text::Font;
+    use typst::MemoryWorld;
+
+    use super::*;
+    use crate::PdfOptions;
+
+    fn compile(text: &str) -> Document {
+        let data = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        compile_world(&MemoryWorld::new(text).with_fonts([font]))
+    }
+
+    fn compile_world(world: &MemoryWorld) -> Document {
+        typst::compile(world, &mut Tracer::new()).unwrap()
+    }
+
+    #[test]
+    fn test_permission_bits() {
+        let none = PdfPermissions {
+            print: false,
+            print_high_quality: false,
+            modify: false,
+            copy: false,
+            annotate: false,
+            assemble: false,
+        };
+        assert_eq!(none.bits(), 0xFFFF_F2C0);
+        assert_eq!(PdfPermissions::default().bits(), 0xFFFF_FFFC);
+    }
+
+    fn encryption() -> PdfEncryption {
+        PdfEncryption {
+            user_password: "user".into(),
+            owner_password: Some("owner".into()),
+            permissions: PdfPermissions::default(),
+        }
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_encrypt_while_writing() {
+        let crypt = Crypt::new(&encryption()).unwrap();
+
+        let mut alloc = Ref::new(4);
+        let mut pdf = Pdf::new();
+        pdf.catalog(Ref::new(1)).pages(Ref::new(2));
+        pdf.pages(Ref::new(2)).count(0).finish();
+        pdf.stream(Ref::new(3), &crypt.stream(b"hello world"))
+            .pair(Name(b"Title"), crypt.text("Secret"));
+        let id = crypt.write_dict(&mut pdf, &mut alloc).unwrap();
+        let mut buffer = pdf.finish();
+        link_dict(&mut buffer, id);
+
+        assert!(!contains(&buffer, b"hello world"));
+        assert!(!contains(&buffer, b"Secret"));
+        // Initialization vector plus one padded block.
+        assert!(contains(&buffer, b"/Length 32"));
+        assert!(contains(&buffer, b"/StrF /StdCF"));
+        assert!(contains(&buffer, b"  /Encrypt 4 0 R\n>>\nstartxref"));
+    }
+
+    #[test]
+    fn test_encrypt_document() {
+        let document = compile(
+            "#set document(title: \"Secret\", author: \"Jane\")\n\
+             #link(\"https://typst.app\")[Hello]",
+        );
+        let options = PdfOptions {
+            encryption: Some(encryption()),
+            ..PdfOptions::default()
+        };
+        let buffer = crate::pdf_with(&document, &options).unwrap();
+
+        // Neither the document information nor the XMP metadata nor the
+        // link annotation leak their strings.
+        assert!(!contains(&buffer, b"Secret"));
+        assert!(!contains(&buffer, b"Jane"));
+        assert!(!contains(&buffer, b"typst.app"));
+        assert!(contains(&buffer, b"/Encrypt"));
+    }
+
+    #[test]
+    fn test_encrypt_svg() {
+        let mut world = MemoryWorld::new("#image(\"logo.svg\")");
+        let logo = include_bytes!("../../../assets/files/logo.svg");
+        world.insert_file("logo.svg", Bytes::from_static(logo));
+        let document = compile_world(&world);
+        let options = PdfOptions {
+            encryption: Some(encryption()),
+            ..PdfOptions::default()
+        };
+        assert!(crate::pdf_with(&document, &options).is_err());
+        assert!(crate::pdf_with(&document, &PdfOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_unencrypted_passthrough() {
+        let crypt = Crypt::default();
+        let mut buf = vec![];
+        crypt.text("Title").write(&mut buf);
+        assert_eq!(buf, b"(Title)");
+        assert_eq!(crypt.stream(b"data"), &b"data"[..]);
+        assert!(crypt.write_dict(&mut Chunk::new(), &mut Ref::new(1)).is_none());
+    }
+}
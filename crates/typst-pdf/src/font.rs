@@ -21,16 +21,22 @@ const SYSTEM_INFO: SystemInfo = SystemInfo {
 };
 
 /// Embed all used fonts into the PDF.
+///
+/// Fonts whose family is in `unembedded` are only referenced by name, so that
+/// viewers have to use a locally installed copy of the exact same font.
 #[typst_macros::time(name = "write fonts")]
-pub(crate) fn write_fonts(ctx: &mut PdfContext) {
+pub(crate) fn write_fonts(ctx: &mut PdfContext, unembedded: &[EcoString]) {
     for font in ctx.font_map.items() {
         let type0_ref = ctx.alloc.bump();
         let cid_ref = ctx.alloc.bump();
         let descriptor_ref = ctx.alloc.bump();
         let cmap_ref = ctx.alloc.bump();
-        let data_ref = ctx.alloc.bump();
         ctx.font_refs.push(type0_ref);
 
+        let family = &font.info().family;
+        let embed = !unembedded.iter().any(|name| name.eq_ignore_ascii_case(family));
+        let data_ref = embed.then(|| ctx.alloc.bump());
+
         let glyph_set = ctx.glyph_sets.get_mut(font).unwrap();
         let metrics = font.metrics();
         let ttf = font.ttf();
@@ -49,8 +55,14 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
             .find_name(name_id::POST_SCRIPT_NAME)
             .unwrap_or_else(|| "unknown".to_string());
 
-        let subset_tag = subset_tag(glyph_set);
-        let base_font = eco_format!("{subset_tag}+{postscript_name}");
+        // A font that isn't embedded is looked up by its plain name, while an
+        // embedded subset is tagged to distinguish it from the full font.
+        let base_font = if embed {
+            let subset_tag = subset_tag(glyph_set);
+            eco_format!("{subset_tag}+{postscript_name}")
+        } else {
+            postscript_name.as_str().into()
+        };
         let base_font_type0 = if is_cff {
             eco_format!("{base_font}-Identity-H")
         } else {
@@ -69,7 +81,11 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
         let mut cid = ctx.pdf.cid_font(cid_ref);
         cid.subtype(if is_cff { CidFontType::Type0 } else { CidFontType::Type2 });
         cid.base_font(Name(base_font.as_bytes()));
-        cid.system_info(SYSTEM_INFO);
+        let mut system_info = cid.insert(Name(b"CIDSystemInfo")).dict();
+        system_info.pair(Name(b"Registry"), ctx.crypt.str(SYSTEM_INFO.registry.0));
+        system_info.pair(Name(b"Ordering"), ctx.crypt.str(SYSTEM_INFO.ordering.0));
+        system_info.pair(Name(b"Supplement"), SYSTEM_INFO.supplement);
+        system_info.finish();
         cid.font_descriptor(descriptor_ref);
         cid.default_width(0.0);
         if !is_cff {
@@ -136,22 +152,27 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
             .cap_height(cap_height)
             .stem_v(stem_v);
 
-        if is_cff {
-            font_descriptor.font_file3(data_ref);
-        } else {
-            font_descriptor.font_file2(data_ref);
+        if let Some(data_ref) = data_ref {
+            if is_cff {
+                font_descriptor.font_file3(data_ref);
+            } else {
+                font_descriptor.font_file2(data_ref);
+            }
         }
 
         font_descriptor.finish();
 
         // Write the /ToUnicode character map, which maps glyph ids back to
         // unicode codepoints to enable copying out of the PDF.
-        let cmap = create_cmap(ttf, glyph_set);
-        ctx.pdf.cmap(cmap_ref, &cmap.finish());
+        let cmap = create_cmap(ttf, glyph_set).finish();
+        ctx.pdf.cmap(cmap_ref, &ctx.crypt.stream(&cmap));
+
+        let Some(data_ref) = data_ref else { continue };
 
         // Subset and write the font's bytes.
         let glyphs: Vec<_> = glyph_set.keys().copied().collect();
         let data = subset_font(font, &glyphs);
+        let data = ctx.crypt.stream(&data);
 
         let mut stream = ctx.pdf.stream(data_ref, &data);
         stream.filter(Filter::FlateDecode);
@@ -188,7 +209,14 @@ fn subset_font(font: &Font, glyphs: &[u16]) -> Arc<Vec<u8>> {
 fn subset_tag(glyphs: &BTreeMap<u16, EcoString>) -> EcoString {
     const LEN: usize = 6;
     const BASE: u128 = 26;
-    let mut hash = typst::util::hash128(&glyphs);
+    let mut bytes = vec![];
+    for (gid, text) in glyphs {
+        bytes.extend(gid.to_le_bytes());
+        bytes.extend(text.as_bytes());
+        bytes.push(0xFF);
+    }
+
+    let mut hash = typst::util::hash128_bytes(&bytes);
     let mut letter = [b'A'; LEN];
     for l in letter.iter_mut() {
         *l = b'A' + (hash % BASE) as u8;
@@ -0,0 +1,205 @@
+use ecow::EcoString;
+use pdf_writer::types::AnnotationFlags;
+use pdf_writer::writers::Annotation;
+use pdf_writer::{Content, Finish, Name, Rect, Ref};
+use typst::model::{FormField, FormFieldKind};
+
+use crate::PdfContext;
+
+/// The default appearance of text in variable text fields.
+const DEFAULT_APPEARANCE: &[u8] = b"/Helv 0 Tf 0 g";
+
+// Field flags.
+const MULTILINE: i32 = 1 << 12;
+const NO_TOGGLE_TO_OFF: i32 = 1 << 14;
+const RADIO: i32 = 1 << 15;
+const COMBO: i32 = 1 << 17;
+
+/// Write the interactive form fields of all pages.
+///
+/// Widgets with the same name are merged into a single field. Returns the
+/// reference to the interactive form dictionary, if there are any fields.
+#[typst_macros::time(name = "write forms")]
+pub(crate) fn write_forms(ctx: &mut PdfContext) -> Option<Ref> {
+    // Group the widgets by name, keeping the order of first appearance.
+    let mut groups: Vec<(EcoString, Vec<(Ref, FormField, Rect, Ref)>)> = vec![];
+    for (page, &page_ref) in ctx.pages.iter().zip(&ctx.page_refs) {
+        for (widget_ref, field, rect) in &page.fields {
+            let widget = (*widget_ref, field.clone(), *rect, page_ref);
+            match groups.iter_mut().find(|(name, _)| *name == field.name) {
+                Some((_, widgets)) => widgets.push(widget),
+                None => groups.push((field.name.clone(), vec![widget])),
+            }
+        }
+    }
+
+    if groups.is_empty() {
+        return None;
+    }
+
+    let mut field_refs = vec![];
+    for (name, widgets) in &groups {
+        let field_ref = ctx.alloc.bump();
+        field_refs.push(field_ref);
+
+        // The first widget defines the field's type and initial value.
+        let first = &widgets[0].1;
+        let mut field = ctx.pdf.indirect(field_ref).dict();
+        field.pair(Name(b"T"), ctx.crypt.text(name));
+        field
+            .insert(Name(b"Kids"))
+            .array()
+            .items(widgets.iter().map(|widget| widget.0));
+
+        match &first.kind {
+            FormFieldKind::Text { value, multiline, max_length } => {
+                field.pair(Name(b"FT"), Name(b"Tx"));
+                field.pair(Name(b"V"), ctx.crypt.text(value));
+                field.pair(Name(b"DV"), ctx.crypt.text(value));
+                field.pair(Name(b"DA"), ctx.crypt.str(DEFAULT_APPEARANCE));
+                if *multiline {
+                    field.pair(Name(b"Ff"), MULTILINE);
+                }
+                if let Some(max) = max_length {
+                    field.pair(Name(b"MaxLen"), max.get() as i32);
+                }
+            }
+            FormFieldKind::Checkbox { checked } => {
+                let state = if *checked { Name(b"Yes") } else { Name(b"Off") };
+                field.pair(Name(b"FT"), Name(b"Btn"));
+                field.pair(Name(b"V"), state);
+                field.pair(Name(b"DV"), state);
+            }
+            FormFieldKind::Radio { .. } => {
+                // The group's value is the value of the checked button.
+                let checked = widgets.iter().find_map(|widget| match &widget.1.kind {
+                    FormFieldKind::Radio { value, checked: true } => Some(value),
+                    _ => None,
+                });
+                let state = checked.map_or(Name(b"Off"), |value| Name(value.as_bytes()));
+                field.pair(Name(b"FT"), Name(b"Btn"));
+                field.pair(Name(b"Ff"), RADIO | NO_TOGGLE_TO_OFF);
+                field.pair(Name(b"V"), state);
+                field.pair(Name(b"DV"), state);
+            }
+            FormFieldKind::Dropdown { options, value } => {
+                field.pair(Name(b"FT"), Name(b"Ch"));
+                field.pair(Name(b"Ff"), COMBO);
+                field
+                    .insert(Name(b"Opt"))
+                    .array()
+                    .items(options.iter().map(|option| ctx.crypt.text(option)));
+                if let Some(value) = value {
+                    field.pair(Name(b"V"), ctx.crypt.text(value));
+                    field.pair(Name(b"DV"), ctx.crypt.text(value));
+                }
+                field.pair(Name(b"DA"), ctx.crypt.str(DEFAULT_APPEARANCE));
+            }
+        }
+
+        field.finish();
+
+        for (widget_ref, field, rect, page_ref) in widgets {
+            write_widget(ctx, *widget_ref, field, *rect, *page_ref, field_ref);
+        }
+    }
+
+    let form_ref = ctx.alloc.bump();
+    let mut form = ctx.pdf.indirect(form_ref).dict();
+    form.insert(Name(b"Fields")).array().items(field_refs);
+    form.pair(Name(b"NeedAppearances"), true);
+    form.pair(Name(b"DA"), ctx.crypt.str(DEFAULT_APPEARANCE));
+
+    // Standard fonts for the appearances generated by the viewer.
+    let mut resources = form.insert(Name(b"DR")).dict();
+    let mut fonts = resources.insert(Name(b"Font")).dict();
+    for (name, base) in [("Helv", "Helvetica"), ("ZaDb", "ZapfDingbats")] {
+        let mut font = fonts.insert(Name(name.as_bytes())).dict();
+        font.pair(Name(b"Type"), Name(b"Font"));
+        font.pair(Name(b"Subtype"), Name(b"Type1"));
+        font.pair(Name(b"BaseFont"), Name(base.as_bytes()));
+    }
+    fonts.finish();
+    resources.finish();
+    form.finish();
+
+    Some(form_ref)
+}
+
+/// Write a single widget annotation of a field.
+fn write_widget(
+    ctx: &mut PdfContext,
+    widget_ref: Ref,
+    field: &FormField,
+    rect: Rect,
+    page_ref: Ref,
+    parent_ref: Ref,
+) {
+    let w = (rect.x2 - rect.x1).abs();
+    let h = (rect.y1 - rect.y2).abs();
+
+    // Buttons need appearances for their states.
+    let button = match &field.kind {
+        FormFieldKind::Checkbox { checked } => Some((Name(b"Yes"), *checked, false)),
+        FormFieldKind::Radio { value, checked } => {
+            Some((Name(value.as_bytes()), *checked, true))
+        }
+        _ => None,
+    };
+
+    let appearances = button.map(|(_, _, round)| {
+        let on_ref = ctx.alloc.bump();
+        let off_ref = ctx.alloc.bump();
+        let on = if round { radio_appearance(w, h) } else { check_appearance(w, h) };
+        let (on, off) = (ctx.crypt.stream(&on), ctx.crypt.stream(&[]));
+        ctx.pdf.form_xobject(on_ref, &on).bbox(Rect::new(0.0, 0.0, w, h));
+        ctx.pdf.form_xobject(off_ref, &off).bbox(Rect::new(0.0, 0.0, w, h));
+        (on_ref, off_ref)
+    });
+
+    let mut widget = ctx.pdf.indirect(widget_ref).start::<Annotation>();
+    widget.pair(Name(b"Subtype"), Name(b"Widget"));
+    widget.rect(rect);
+    widget.flags(AnnotationFlags::PRINT);
+    widget.pair(Name(b"P"), page_ref);
+    widget.pair(Name(b"Parent"), parent_ref);
+
+    if let (Some((on, checked, _)), Some((on_ref, off_ref))) = (button, appearances) {
+        widget.pair(Name(b"AS"), if checked { on } else { Name(b"Off") });
+        let mut ap = widget.insert(Name(b"AP")).dict();
+        let mut normal = ap.insert(Name(b"N")).dict();
+        normal.pair(on, on_ref);
+        normal.pair(Name(b"Off"), off_ref);
+    }
+}
+
+/// A cross that fills a checkbox of the given size.
+fn check_appearance(w: f32, h: f32) -> Vec<u8> {
+    let inset = 0.2 * w.min(h);
+    let mut content = Content::new();
+    content.set_line_width(0.1 * w.min(h));
+    content.move_to(inset, inset);
+    content.line_to(w - inset, h - inset);
+    content.move_to(inset, h - inset);
+    content.line_to(w - inset, inset);
+    content.stroke();
+    content.finish()
+}
+
+/// A filled dot in the center of a radio button of the given size.
+fn radio_appearance(w: f32, h: f32) -> Vec<u8> {
+    // Approximate the circle with four cubic bezier curves.
+    const K: f32 = 0.5523;
+    let r = 0.25 * w.min(h);
+    let (cx, cy) = (w / 2.0, h / 2.0);
+    let d = K * r;
+
+    let mut content = Content::new();
+    content.move_to(cx + r, cy);
+    content.cubic_to(cx + r, cy + d, cx + d, cy + r, cx, cy + r);
+    content.cubic_to(cx - d, cy + r, cx - r, cy + d, cx - r, cy);
+    content.cubic_to(cx - r, cy - d, cx - d, cy - r, cx, cy - r);
+    content.cubic_to(cx + d, cy - r, cx + r, cy - d, cx + r, cy);
+    content.fill_nonzero();
+    content.finish()
+}
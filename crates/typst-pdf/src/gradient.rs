@@ -105,6 +105,7 @@ pub(crate) fn write_gradients(ctx: &mut PdfContext) {
             }
             Gradient::Conic(_) => {
                 let vertices = compute_vertex_stream(&gradient, aspect_ratio);
+                let vertices = ctx.crypt.stream(&vertices);
 
                 let stream_shading_id = ctx.alloc.bump();
                 let mut stream_shading =
@@ -1,29 +1,147 @@
 use std::collections::HashMap;
 use std::io::Cursor;
 
-use image::{DynamicImage, GenericImageView, Rgba};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat, Rgba};
 use pdf_writer::{Chunk, Filter, Finish, Ref};
+use typst::diag::{bail, StrResult};
+use typst::layout::{Frame, FrameItem, Size};
+use typst::model::Document;
 use typst::util::Deferred;
-use typst::visualize::{
-    ColorSpace, Image, ImageKind, RasterFormat, RasterImage, SvgImage,
-};
+use typst::visualize::{ColorSpace, Image, ImageKind, RasterFormat, SvgImage};
 
 use crate::{deflate, PdfContext};
 
+/// The quality at which JPEG images are encoded by default.
+const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// Settings for re-encoding raster images to reduce the file size.
+///
+/// By default, images are embedded at their full resolution and only JPEG
+/// images are encoded lossily.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ImageCompression {
+    /// The quality from 1 to 100 at which JPEG images are encoded.
+    pub jpeg_quality: Option<u8>,
+    /// The maximum resolution in pixels per inch. Images with a higher
+    /// resolution at the largest size they are shown at are downsampled.
+    pub max_ppi: Option<f32>,
+    /// The number of pixels above which PNG and GIF images are converted to
+    /// JPEG. Their alpha channel, if any, stays lossless.
+    pub jpeg_above: Option<u64>,
+}
+
+/// How a raster image is encoded.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RasterEncoding {
+    /// The size in pixels to downsample to, if any.
+    size: Option<(u32, u32)>,
+    /// The JPEG quality, if the image is encoded as JPEG.
+    jpeg_quality: Option<u8>,
+}
+
+/// Determine how to encode an image with the export's compression settings.
+pub(crate) fn raster_encoding(ctx: &PdfContext, image: &Image) -> RasterEncoding {
+    let ImageKind::Raster(raster) = image.kind() else {
+        return RasterEncoding::default();
+    };
+
+    let compression = &ctx.image_compression;
+    let (width, height) = (image.width(), image.height());
+    let pixels = u64::from(width) * u64::from(height);
+    let jpeg_quality = if raster.format() == RasterFormat::Jpg
+        || compression.jpeg_above.map_or(false, |limit| pixels > limit)
+    {
+        Some(compression.jpeg_quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100))
+    } else {
+        None
+    };
+
+    let size = match (compression.max_ppi, ctx.image_sizes.get(image)) {
+        (Some(ppi), Some(&shown)) => downsampled_size(width, height, shown, ppi),
+        _ => None,
+    };
+
+    RasterEncoding { size, jpeg_quality }
+}
+
+/// The size in pixels to downsample an image to, so that it doesn't exceed the
+/// resolution when shown at the given size. Returns `None` if the image's
+/// resolution is already low enough.
+fn downsampled_size(
+    width: u32,
+    height: u32,
+    shown: Size,
+    ppi: f32,
+) -> Option<(u32, u32)> {
+    let max_width = (shown.x.to_inches() * f64::from(ppi)).ceil().max(1.0);
+    let max_height = (shown.y.to_inches() * f64::from(ppi)).ceil().max(1.0);
+    let scale = (max_width / f64::from(width)).min(max_height / f64::from(height));
+    if scale >= 1.0 {
+        return None;
+    }
+
+    let scaled = |v: u32| ((f64::from(v) * scale).round() as u32).max(1);
+    Some((scaled(width), scaled(height)))
+}
+
+/// Determine the largest size at which each image in the document is shown.
+pub(crate) fn image_sizes(document: &Document) -> HashMap<Image, Size> {
+    fn visit(frame: &Frame, scale: (f64, f64), sizes: &mut HashMap<Image, Size>) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = group.transform;
+                    let scale = (
+                        scale.0 * ts.sx.get().hypot(ts.ky.get()),
+                        scale.1 * ts.sy.get().hypot(ts.kx.get()),
+                    );
+                    visit(&group.frame, scale, sizes);
+                }
+                FrameItem::Image(image, size, _) => {
+                    let shown = Size::new(size.x * scale.0, size.y * scale.1);
+                    let max = sizes.entry(image.clone()).or_insert(shown);
+                    max.x = max.x.max(shown.x);
+                    max.y = max.y.max(shown.y);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut sizes = HashMap::new();
+    for page in &document.pages {
+        visit(&page.frame, (1.0, 1.0), &mut sizes);
+    }
+    sizes
+}
+
 /// Creates a new PDF image from the given image.
 ///
 /// Also starts the deferred encoding of the image.
 #[comemo::memoize]
-pub fn deferred_image(image: Image) -> Deferred<EncodedImage> {
+pub fn deferred_image(image: Image, encoding: RasterEncoding) -> Deferred<EncodedImage> {
     Deferred::new(move || match image.kind() {
         ImageKind::Raster(raster) => {
             let raster = raster.clone();
-            let (width, height) = (image.width(), image.height());
-            let (data, filter, has_color) = encode_raster_image(&raster);
-            let icc = raster.icc().map(deflate);
+            let resized;
+            let dynamic = match encoding.size {
+                Some((width, height)) => {
+                    resized = raster.dynamic().resize_exact(
+                        width,
+                        height,
+                        FilterType::Lanczos3,
+                    );
+                    &resized
+                }
+                None => raster.dynamic(),
+            };
 
-            let alpha =
-                raster.dynamic().color().has_alpha().then(|| encode_alpha(&raster));
+            let (width, height) = dynamic.dimensions();
+            let (data, filter, has_color) =
+                encode_raster_image(dynamic, encoding.jpeg_quality);
+            let icc = raster.icc().map(deflate);
+            let alpha = dynamic.color().has_alpha().then(|| encode_alpha(dynamic));
 
             EncodedImage::Raster { data, filter, has_color, width, height, icc, alpha }
         }
@@ -32,8 +150,11 @@ pub fn deferred_image(image: Image) -> Deferred<EncodedImage> {
 }
 
 /// Embed all used images into the PDF.
+///
+/// Fails for SVG images in encrypted files because their objects are written
+/// by `svg2pdf`, which can't encrypt them.
 #[typst_macros::time(name = "write images")]
-pub(crate) fn write_images(ctx: &mut PdfContext) {
+pub(crate) fn write_images(ctx: &mut PdfContext) -> StrResult<()> {
     for (i, _) in ctx.image_map.items().enumerate() {
         let handle = ctx.image_deferred_map.get(&i).unwrap();
         match handle.wait() {
@@ -49,7 +170,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                 let image_ref = ctx.alloc.bump();
                 ctx.image_refs.push(image_ref);
 
-                let mut image = ctx.pdf.image_xobject(image_ref, data);
+                let data = ctx.crypt.stream(data);
+                let mut image = ctx.pdf.image_xobject(image_ref, &data);
                 image.filter(*filter);
                 image.width(*width as i32);
                 image.height(*height as i32);
@@ -74,7 +196,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                     image.s_mask(mask_ref);
                     image.finish();
 
-                    let mut mask = ctx.pdf.image_xobject(mask_ref, alpha_data);
+                    let alpha_data = ctx.crypt.stream(alpha_data);
+                    let mut mask = ctx.pdf.image_xobject(mask_ref, &alpha_data);
                     mask.filter(*alpha_filter);
                     mask.width(*width as i32);
                     mask.height(*height as i32);
@@ -85,7 +208,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                 }
 
                 if let (Some(icc), Some(icc_ref)) = (icc, icc_ref) {
-                    let mut stream = ctx.pdf.icc_profile(icc_ref, icc);
+                    let icc = ctx.crypt.stream(icc);
+                    let mut stream = ctx.pdf.icc_profile(icc_ref, &icc);
                     stream.filter(Filter::FlateDecode);
                     if *has_color {
                         stream.n(3);
@@ -96,6 +220,9 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                     }
                 }
             }
+            EncodedImage::Svg(_) if ctx.crypt.is_encrypted() => {
+                bail!("SVG images are not supported in encrypted PDFs");
+            }
             EncodedImage::Svg(chunk) => {
                 let mut map = HashMap::new();
                 chunk.renumber_into(&mut ctx.pdf, |old| {
@@ -105,20 +232,31 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
             }
         }
     }
+
+    Ok(())
 }
 
 /// Encode an image with a suitable filter and return the data, filter and
 /// whether the image has color.
 ///
-/// Skips the alpha channel as that's encoded separately.
-fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
-    let dynamic = image.dynamic();
+/// The image is encoded as JPEG if a quality is given. Skips the alpha channel
+/// as that's encoded separately.
+fn encode_raster_image(
+    dynamic: &DynamicImage,
+    jpeg_quality: Option<u8>,
+) -> (Vec<u8>, Filter, bool) {
     let channel_count = dynamic.color().channel_count();
     let has_color = channel_count > 2;
 
-    if image.format() == RasterFormat::Jpg {
+    if let Some(quality) = jpeg_quality {
+        // JPEG can't store an alpha channel.
+        let opaque = if has_color {
+            DynamicImage::ImageRgb8(dynamic.to_rgb8())
+        } else {
+            DynamicImage::ImageLuma8(dynamic.to_luma8())
+        };
         let mut data = Cursor::new(vec![]);
-        dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
+        opaque.write_to(&mut data, ImageOutputFormat::Jpeg(quality)).unwrap();
         (data.into_inner(), Filter::DctDecode, has_color)
     } else {
         // TODO: Encode flate streams with PNG-predictor?
@@ -135,17 +273,17 @@ fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
 }
 
 /// Encode an image's alpha channel if present.
-fn encode_alpha(raster: &RasterImage) -> (Vec<u8>, Filter) {
-    let pixels: Vec<_> = raster
-        .dynamic()
-        .pixels()
-        .map(|(_, _, Rgba([_, _, _, a]))| a)
-        .collect();
+fn encode_alpha(dynamic: &DynamicImage) -> (Vec<u8>, Filter) {
+    let pixels: Vec<_> = dynamic.pixels().map(|(_, _, Rgba([_, _, _, a]))| a).collect();
     (deflate(&pixels), Filter::FlateDecode)
 }
 
 /// Encode an SVG into a chunk of PDF objects.
 ///
+/// Paths, gradients, and text are translated into native PDF content, so the
+/// image stays crisp at any zoom level. The fonts for the text were already
+/// resolved through the font book when the image was decoded.
+///
 /// The main XObject will have ID 1.
 fn encode_svg(svg: &SvgImage) -> Chunk {
     let mut chunk = Chunk::new();
@@ -190,3 +328,19 @@ pub enum EncodedImage {
     /// The chunk is the SVG converted to PDF objects.
     Svg(Chunk),
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::Abs;
+
+    use super::*;
+
+    #[test]
+    fn test_downsampled_size() {
+        let shown = Size::new(Abs::inches(2.0), Abs::inches(1.0));
+        assert_eq!(downsampled_size(1200, 600, shown, 600.0), None);
+        assert_eq!(downsampled_size(1200, 600, shown, 300.0), Some((600, 300)));
+        assert_eq!(downsampled_size(3000, 600, shown, 150.0), Some((300, 60)));
+        assert_eq!(downsampled_size(5000, 1, shown, 1.0), Some((2, 1)));
+    }
+}
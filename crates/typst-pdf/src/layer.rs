@@ -0,0 +1,75 @@
+use pdf_writer::{Finish, Name, Ref};
+
+use crate::PdfContext;
+
+/// Write an optional content group for each layer used in the document.
+///
+/// This must happen after all pages were constructed, but before the page
+/// tree and patterns are written, as they refer to the groups.
+#[typst_macros::time(name = "write layers")]
+pub(crate) fn write_layers(ctx: &mut PdfContext) {
+    for layer in &ctx.layers {
+        let layer_ref = ctx.alloc.bump();
+        ctx.layer_refs.push(layer_ref);
+
+        let state = |on: bool| if on { Name(b"ON") } else { Name(b"OFF") };
+        let mut group = ctx.pdf.indirect(layer_ref).dict();
+        group.pair(Name(b"Type"), Name(b"OCG"));
+        group.pair(Name(b"Name"), ctx.crypt.text(&layer.name));
+
+        let mut usage = group.insert(Name(b"Usage")).dict();
+        usage
+            .insert(Name(b"View"))
+            .dict()
+            .pair(Name(b"ViewState"), state(layer.visible));
+        usage
+            .insert(Name(b"Print"))
+            .dict()
+            .pair(Name(b"PrintState"), state(layer.printable));
+        usage.finish();
+        group.finish();
+    }
+}
+
+/// Write the optional content properties, which define the default
+/// visibility of the layers.
+///
+/// Returns the reference to the properties dictionary, if there are any
+/// layers.
+pub(crate) fn write_layer_properties(ctx: &mut PdfContext) -> Option<Ref> {
+    if ctx.layer_refs.is_empty() {
+        return None;
+    }
+
+    let refs = || ctx.layer_refs.iter().copied();
+    let with = |f: fn(bool, bool) -> bool| {
+        ctx.layers
+            .iter()
+            .zip(refs())
+            .filter(move |(layer, _)| f(layer.visible, layer.printable))
+            .map(|(_, layer_ref)| layer_ref)
+    };
+
+    let properties_ref = ctx.alloc.bump();
+    let mut properties = ctx.pdf.indirect(properties_ref).dict();
+    properties.insert(Name(b"OCGs")).array().items(refs());
+
+    let mut config = properties.insert(Name(b"D")).dict();
+    config.pair(Name(b"Name"), ctx.crypt.text("Default"));
+    config.insert(Name(b"Order")).array().items(refs());
+    config.insert(Name(b"ON")).array().items(with(|visible, _| visible));
+    config.insert(Name(b"OFF")).array().items(with(|visible, _| !visible));
+
+    // Let viewers apply the print state of the layers when printing.
+    if ctx.layers.iter().any(|layer| !layer.printable) {
+        let mut usage = config.insert(Name(b"AS")).array().push().dict();
+        usage.pair(Name(b"Event"), Name(b"Print"));
+        usage.insert(Name(b"Category")).array().item(Name(b"Print"));
+        usage.insert(Name(b"OCGs")).array().items(refs());
+    }
+
+    config.finish();
+    properties.finish();
+
+    Some(properties_ref)
+}
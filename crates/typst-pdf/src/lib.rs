@@ -1,41 +1,60 @@
 //! Exporting into PDF documents.
 
 mod color;
+mod convert;
+mod embed;
+mod encrypt;
 mod extg;
 mod font;
+mod form;
 mod gradient;
 mod image;
+mod layer;
 mod outline;
+mod output;
 mod page;
 mod pattern;
+mod validate;
 
 use std::cmp::Eq;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
 use std::hash::Hash;
+use std::io;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use base64::Engine;
 use ecow::{eco_format, EcoString};
 use pdf_writer::types::Direction;
-use pdf_writer::{Finish, Name, Pdf, Ref, TextStr};
+use pdf_writer::{Filter, Finish, Name, Pdf, Ref};
+use typst::diag::{bail, StrResult};
 use typst::foundations::Datetime;
-use typst::layout::{Abs, Dir, Em, Transform};
+use typst::layout::{Abs, Dir, Em, Layer, PageRanges, Size, Transform};
 use typst::model::Document;
 use typst::text::{Font, Lang};
-use typst::util::Deferred;
-use typst::visualize::Image;
+use typst::util::{CancellationToken, Deferred};
+use typst::visualize::{IccProfile, IccSpace, Image};
 use xmp_writer::{DateTime, LangId, RenditionClass, Timezone, XmpWriter};
 
+pub use crate::convert::ColorConversion;
+pub use crate::encrypt::{PdfEncryption, PdfPermissions};
+pub use crate::image::ImageCompression;
+pub use crate::validate::validate;
+
 use crate::color::ColorSpaces;
+use crate::encrypt::Crypt;
 use crate::extg::ExtGState;
 use crate::gradient::PdfGradient;
 use crate::image::EncodedImage;
+use crate::output::Output;
 use crate::page::EncodedPage;
 use crate::pattern::PdfPattern;
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
+/// Returns the raw bytes making up the PDF file. The whole file is assembled in
+/// memory before it is returned.
 ///
 /// The `ident` parameter shall be a string that uniquely and stably identifies
 /// the document. It should not change between compilations of the same
@@ -47,22 +66,131 @@ use crate::pattern::PdfPattern;
 /// The `timestamp`, if given, is expected to be the creation date of the
 /// document as a UTC datetime. It will only be used if `set document(date: ..)`
 /// is `auto`.
-#[typst_macros::time(name = "pdf")]
 pub fn pdf(
     document: &Document,
     ident: Option<&str>,
     timestamp: Option<Datetime>,
 ) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
-    page::construct_pages(&mut ctx, &document.pages);
-    font::write_fonts(&mut ctx);
-    image::write_images(&mut ctx);
+    let options = PdfOptions { ident, timestamp, ..PdfOptions::default() };
+
+    // Without encryption and cancellation, the export can't fail.
+    pdf_with(document, &options).unwrap_or_default()
+}
+
+/// Export a document into a PDF file with custom options.
+///
+/// Returns the raw bytes making up the PDF file. Fails if the export is
+/// cancelled or if the file can't be encrypted.
+pub fn pdf_with(document: &Document, options: &PdfOptions) -> StrResult<Vec<u8>> {
+    let mut buffer = vec![];
+    write_pdf(document, options, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Export a document into a PDF file that is written into the given writer.
+///
+/// In contrast to [`pdf_with`], the content stream of each page is written as
+/// soon as it is ready and isn't kept in memory until the end. Fails if the
+/// export is cancelled, if the file can't be encrypted, or if writing fails.
+/// The writer may have received part of the file in that case.
+pub fn pdf_into(
+    document: &Document,
+    options: &PdfOptions,
+    mut writer: impl io::Write,
+) -> StrResult<()> {
+    write_pdf(document, options, &mut writer)
+}
+
+/// Options for PDF export.
+#[derive(Debug, Default, Clone)]
+pub struct PdfOptions<'a> {
+    /// A string that uniquely and stably identifies the document. See
+    /// [`pdf`] for details.
+    pub ident: Option<&'a str>,
+    /// The creation date of the document as a UTC datetime. Only used if
+    /// `set document(date: ..)` is `auto`.
+    pub timestamp: Option<Datetime>,
+    /// If given, the file is encrypted with these settings. Documents with
+    /// SVG images can't be encrypted.
+    pub encryption: Option<PdfEncryption>,
+    /// If given, only these pages are exported. Links and outline entries
+    /// that point to other pages are dropped.
+    pub page_ranges: Option<PageRanges>,
+    /// Font families (matched case-insensitively) that are not embedded.
+    ///
+    /// Such fonts are only referenced by name, which makes the file much
+    /// smaller, but it only displays correctly on systems that have the exact
+    /// same fonts installed. This is meant for drafts that aren't shared.
+    pub unembedded_fonts: Vec<EcoString>,
+    /// If given, all colors are converted like this before writing.
+    pub color_conversion: Option<ColorConversion>,
+    /// How raster images are re-encoded.
+    pub image_compression: ImageCompression,
+    /// Whether links to local files other than PDFs become launch actions,
+    /// which open the file with its default application.
+    ///
+    /// Since this includes executables, such links are written as plain URIs
+    /// by default, which viewers resolve relative to the document.
+    pub allow_launch: bool,
+    /// Whether links that would run scripts, that is links to `javascript:`,
+    /// `vbscript:`, and `data:` URLs, are left out.
+    ///
+    /// By default, they are written as plain URIs like all other links.
+    pub strip_script_links: bool,
+    /// If given, the export stops with an error once this is cancelled.
+    pub cancellation: Option<CancellationToken>,
+}
+
+/// Write the document into a PDF file.
+#[typst_macros::time(name = "pdf")]
+fn write_pdf(
+    document: &Document,
+    options: &PdfOptions,
+    writer: &mut dyn io::Write,
+) -> StrResult<()> {
+    let converted;
+    let document = match options.color_conversion {
+        Some(conversion) => {
+            converted = convert::convert_document(document, conversion);
+            &converted
+        }
+        None => document,
+    };
+
+    let mut ctx = PdfContext::new(document, writer)?;
+    if let Some(encryption) = &options.encryption {
+        ctx.crypt = Crypt::new(encryption)?;
+    }
+    ctx.allow_launch = options.allow_launch;
+    ctx.strip_script_links = options.strip_script_links;
+    ctx.cancellation = options.cancellation.clone();
+    ctx.image_compression = options.image_compression;
+    if options.image_compression.max_ppi.is_some() {
+        ctx.image_sizes = image::image_sizes(document);
+    }
+    page::construct_pages(&mut ctx, &document.pages, options.page_ranges.as_ref())?;
+
+    // The pages may be incomplete, but the result is discarded anyway.
+    if ctx.is_cancelled() {
+        bail!("export was cancelled");
+    }
+
+    layer::write_layers(&mut ctx);
+    font::write_fonts(&mut ctx, &options.unembedded_fonts);
+    image::write_images(&mut ctx)?;
     gradient::write_gradients(&mut ctx);
     extg::write_external_graphics_states(&mut ctx);
     pattern::write_patterns(&mut ctx);
     page::write_page_tree(&mut ctx);
-    write_catalog(&mut ctx, ident, timestamp);
-    ctx.pdf.finish()
+    write_catalog(&mut ctx, options.ident, options.timestamp);
+    if ctx.is_cancelled() {
+        bail!("export was cancelled");
+    }
+
+    let encrypt_ref = ctx.crypt.write_dict(&mut ctx.pdf, &mut ctx.alloc);
+
+    let pdf = ctx.pdf.finish();
+    ctx.output.finish(&pdf, encrypt_ref)
 }
 
 /// Context for exporting a whole PDF document.
@@ -71,6 +199,11 @@ struct PdfContext<'a> {
     document: &'a Document,
     /// The writer we are writing the PDF into.
     pdf: Pdf,
+    /// The destination of the file, which the content streams of the pages
+    /// are written into as soon as they are ready.
+    output: Output<'a>,
+    /// Encrypts strings and streams if the file is encrypted.
+    crypt: Crypt,
     /// Content of exported pages.
     pages: Vec<EncodedPage>,
     /// For each font a mapping from used glyphs to their text representation.
@@ -82,7 +215,7 @@ struct PdfContext<'a> {
     glyph_sets: HashMap<Font, BTreeMap<u16, EcoString>>,
     /// The number of glyphs for all referenced languages in the document.
     /// We keep track of this to determine the main document language.
-    languages: HashMap<Lang, usize>,
+    languages: BTreeMap<Lang, usize>,
 
     /// Allocator for indirect reference IDs.
     alloc: Ref,
@@ -90,6 +223,9 @@ struct PdfContext<'a> {
     page_tree_ref: Ref,
     /// The IDs of written pages.
     page_refs: Vec<Ref>,
+    /// For each page of the document, its index among the written pages, if
+    /// it is written at all.
+    page_indices: Vec<Option<usize>>,
     /// The IDs of written fonts.
     font_refs: Vec<Ref>,
     /// The IDs of written images.
@@ -98,6 +234,8 @@ struct PdfContext<'a> {
     gradient_refs: Vec<Ref>,
     /// The IDs of written patterns.
     pattern_refs: Vec<Ref>,
+    /// The IDs of written optional content groups.
+    layer_refs: Vec<Ref>,
     /// The IDs of written external graphics states.
     ext_gs_refs: Vec<Ref>,
     /// Handles color space writing.
@@ -109,39 +247,101 @@ struct PdfContext<'a> {
     image_map: Remapper<Image>,
     /// Handles to deferred image conversions.
     image_deferred_map: HashMap<usize, Deferred<EncodedImage>>,
+    /// How raster images are re-encoded.
+    image_compression: ImageCompression,
+    /// The largest size at which each image is shown, if images may be
+    /// downsampled.
+    image_sizes: HashMap<Image, Size>,
     /// Deduplicates gradients used across the document.
     gradient_map: Remapper<PdfGradient>,
     /// Deduplicates patterns used across the document.
     pattern_map: Remapper<PdfPattern>,
     /// Deduplicates external graphics states used across the document.
     extg_map: Remapper<ExtGState>,
+    /// The layers used across the document, deduplicated by name.
+    layers: Vec<Layer>,
+    /// Whether links to local files may become launch actions.
+    allow_launch: bool,
+    /// Whether links that would run scripts are left out.
+    strip_script_links: bool,
+    /// Stops the export once it is cancelled.
+    cancellation: Option<CancellationToken>,
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, writer: &'a mut dyn io::Write) -> StrResult<Self> {
+        let pdf = Pdf::new();
+        let output = Output::new(writer, pdf.as_bytes())?;
+
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
-        Self {
+
+        // A CMYK output profile also defines how CMYK colors are interpreted.
+        let mut colors = ColorSpaces::default();
+        if let Some(icc) = &document.icc {
+            if icc.space() == IccSpace::Cmyk {
+                colors.set_cmyk_profile(icc.data(), &mut alloc);
+            }
+        }
+
+        Ok(Self {
             document,
-            pdf: Pdf::new(),
+            pdf,
+            output,
+            crypt: Crypt::default(),
             pages: vec![],
             glyph_sets: HashMap::new(),
-            languages: HashMap::new(),
+            languages: BTreeMap::new(),
             alloc,
             page_tree_ref,
             page_refs: vec![],
+            page_indices: vec![],
             font_refs: vec![],
             image_refs: vec![],
             gradient_refs: vec![],
             pattern_refs: vec![],
+            layer_refs: vec![],
             ext_gs_refs: vec![],
-            colors: ColorSpaces::default(),
+            colors,
             font_map: Remapper::new(),
             image_map: Remapper::new(),
             image_deferred_map: HashMap::default(),
+            image_compression: ImageCompression::default(),
+            image_sizes: HashMap::new(),
             gradient_map: Remapper::new(),
             pattern_map: Remapper::new(),
             extg_map: Remapper::new(),
+            layers: vec![],
+            allow_launch: false,
+            strip_script_links: false,
+            cancellation: None,
+        })
+    }
+}
+
+impl PdfContext<'_> {
+    /// Whether the export was cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// The index among the written pages of the page with the given physical
+    /// number, if it is written.
+    fn written_page(&self, page: NonZeroUsize) -> Option<usize> {
+        self.page_indices.get(page.get() - 1).copied().flatten()
+    }
+
+    /// The index of the layer with the given layer's name, registering it if
+    /// it wasn't used before.
+    fn layer_index(&mut self, layer: &Layer) -> usize {
+        match self.layers.iter().position(|other| other.name == layer.name) {
+            Some(index) => index,
+            None => {
+                self.layers.push(layer.clone());
+                self.layers.len() - 1
+            }
         }
     }
 }
@@ -166,14 +366,22 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     // Write the page labels.
     let page_labels = page::write_page_labels(ctx);
 
+    // Write the named destinations.
+    let dests_ref = page::write_named_destinations(ctx);
+
     // Write the document information.
     let mut info = ctx.pdf.document_info(ctx.alloc.bump());
     let mut xmp = XmpWriter::new();
     if let Some(title) = &ctx.document.title {
-        info.title(TextStr(title));
+        info.pair(Name(b"Title"), ctx.crypt.text(title));
         xmp.title([(None, title.as_str())]);
     }
 
+    if let Some(subject) = &ctx.document.subject {
+        info.pair(Name(b"Subject"), ctx.crypt.text(subject));
+        xmp.description([(None, subject.as_str())]);
+    }
+
     let authors = &ctx.document.author;
     if !authors.is_empty() {
         // Turns out that if the authors are given in both the document
@@ -193,26 +401,26 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         // bit weird to not use the array (and it makes Acrobat show the author
         // list in quotes), but there's not much we can do about that.
         let joined = authors.join(", ");
-        info.author(TextStr(&joined));
+        info.pair(Name(b"Author"), ctx.crypt.text(&joined));
         xmp.creator([joined.as_str()]);
     }
 
     let creator = eco_format!("Typst {}", env!("CARGO_PKG_VERSION"));
-    info.creator(TextStr(&creator));
+    info.pair(Name(b"Creator"), ctx.crypt.text(&creator));
     xmp.creator_tool(&creator);
 
     let keywords = &ctx.document.keywords;
     if !keywords.is_empty() {
         let joined = keywords.join(", ");
-        info.keywords(TextStr(&joined));
+        info.pair(Name(b"Keywords"), ctx.crypt.text(&joined));
         xmp.pdf_keywords(&joined);
     }
 
     if let Some(date) = ctx.document.date.unwrap_or(timestamp) {
         let tz = ctx.document.date.is_auto();
         if let Some(pdf_date) = pdf_date(date, tz) {
-            info.creation_date(pdf_date);
-            info.modified_date(pdf_date);
+            info.pair(Name(b"CreationDate"), ctx.crypt.str(pdf_date.as_bytes()));
+            info.pair(Name(b"ModDate"), ctx.crypt.str(pdf_date.as_bytes()));
         }
         if let Some(xmp_date) = xmp_date(date, tz) {
             xmp.create_date(xmp_date);
@@ -220,18 +428,26 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         }
     }
 
+    // Custom entries are only written into the document information
+    // dictionary because XMP would require a custom schema for them.
+    for (key, value) in &ctx.document.custom {
+        info.pair(Name(key.as_bytes()), ctx.crypt.text(value));
+    }
+
     info.finish();
-    xmp.num_pages(ctx.document.pages.len() as u32);
+    xmp.num_pages(ctx.pages.len() as u32);
     xmp.format("application/pdf");
     xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
 
     // A unique ID for this instance of the document. Changes if anything
     // changes in the frames.
-    let instance_id = hash_base64(&ctx.pdf.as_bytes());
+    let instance_id = hash_base64(
+        &typst::util::hash128(&(ctx.output.hash(), ctx.pdf.as_bytes())).to_be_bytes(),
+    );
 
     if let Some(ident) = ident {
         // A unique ID for the document that stays stable across compilations.
-        let doc_id = hash_base64(&("PDF-1.7", ident));
+        let doc_id = hash_base64(&[b"PDF-1.7\0", ident.as_bytes()].concat());
         xmp.document_id(&doc_id);
         xmp.instance_id(&instance_id);
         ctx.pdf
@@ -246,18 +462,36 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     xmp.pdf_version("1.7");
 
     let xmp_buf = xmp.finish(None);
+    let xmp_data = ctx.crypt.stream(xmp_buf.as_bytes());
     let meta_ref = ctx.alloc.bump();
     ctx.pdf
-        .stream(meta_ref, xmp_buf.as_bytes())
+        .stream(meta_ref, &xmp_data)
         .pair(Name(b"Type"), Name(b"Metadata"))
         .pair(Name(b"Subtype"), Name(b"XML"));
 
+    // Write the file attachments.
+    let embedded_files = embed::write_embedded_files(ctx);
+
+    // Write the interactive form fields.
+    let form_ref = form::write_forms(ctx);
+
+    // Write the layer configuration.
+    let layer_properties = layer::write_layer_properties(ctx);
+
+    // Embed the output profile.
+    let document = ctx.document;
+    let output_profile = document.icc.as_ref().map(|icc| write_output_profile(ctx, icc));
+
     // Write the document catalog.
     let mut catalog = ctx.pdf.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.metadata(meta_ref);
 
+    if ctx.document.fullscreen {
+        catalog.pair(Name(b"PageMode"), Name(b"FullScreen"));
+    }
+
     // Insert the page labels.
     if !page_labels.is_empty() {
         let mut num_tree = catalog.page_labels();
@@ -267,13 +501,73 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         }
     }
 
+    if let Some(dests_ref) = dests_ref {
+        catalog.pair(Name(b"Dests"), dests_ref);
+    }
+
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
     }
 
     if let Some(lang) = lang {
-        catalog.lang(TextStr(lang.as_str()));
+        catalog.pair(Name(b"Lang"), ctx.crypt.text(lang.as_str()));
+    }
+
+    // Insert the file attachments into the name tree and also mark them as
+    // associated files of the document.
+    if !embedded_files.is_empty() {
+        let mut names = catalog.insert(Name(b"Names")).dict();
+        let mut tree = names.insert(Name(b"EmbeddedFiles")).dict();
+        let mut entries = tree.insert(Name(b"Names")).array();
+        for (name, spec_ref) in &embedded_files {
+            entries.item(ctx.crypt.text(name));
+            entries.item(*spec_ref);
+        }
+        entries.finish();
+        tree.finish();
+        names.finish();
+
+        catalog
+            .insert(Name(b"AF"))
+            .array()
+            .items(embedded_files.iter().map(|&(_, spec_ref)| spec_ref));
     }
+
+    if let Some(layer_ref) = layer_properties {
+        catalog.pair(Name(b"OCProperties"), layer_ref);
+    }
+
+    if let Some(form_ref) = form_ref {
+        catalog.pair(Name(b"AcroForm"), form_ref);
+    }
+
+    if let Some(profile_ref) = output_profile {
+        let mut intent = catalog.insert(Name(b"OutputIntents")).array().push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFX"));
+        intent.pair(Name(b"OutputConditionIdentifier"), ctx.crypt.text("Custom"));
+        intent.pair(Name(b"DestOutputProfile"), profile_ref);
+    }
+}
+
+/// Write the document's output ICC profile, returning its reference.
+///
+/// A CMYK profile was already written as the CMYK color space.
+fn write_output_profile(ctx: &mut PdfContext, icc: &IccProfile) -> Ref {
+    if let Some(id) = ctx.colors.cmyk() {
+        return id;
+    }
+
+    let id = ctx.alloc.bump();
+    let n = icc.components();
+    let range: Vec<f32> = [0.0, 1.0].repeat(n);
+    let data = deflate(icc.data());
+    ctx.pdf
+        .icc_profile(id, &ctx.crypt.stream(&data))
+        .n(n as i32)
+        .range(range)
+        .filter(Filter::FlateDecode);
+    id
 }
 
 /// Compress data with the DEFLATE algorithm.
@@ -295,40 +589,35 @@ fn deflate_deferred(content: Vec<u8>) -> Deferred<Vec<u8>> {
     Deferred::new(move || deflate(&content))
 }
 
-/// Create a base64-encoded hash of the value.
-fn hash_base64<T: Hash>(value: &T) -> String {
+/// Create a base64-encoded hash of the bytes.
+fn hash_base64(bytes: &[u8]) -> String {
     base64::engine::general_purpose::STANDARD
-        .encode(typst::util::hash128(value).to_be_bytes())
+        .encode(typst::util::hash128_bytes(bytes).to_be_bytes())
 }
 
-/// Converts a datetime to a pdf-writer date.
-fn pdf_date(datetime: Datetime, tz: bool) -> Option<pdf_writer::Date> {
-    let year = datetime.year().filter(|&y| y >= 0)? as u16;
-
-    let mut pdf_date = pdf_writer::Date::new(year);
-
-    if let Some(month) = datetime.month() {
-        pdf_date = pdf_date.month(month);
-    }
-
-    if let Some(day) = datetime.day() {
-        pdf_date = pdf_date.day(day);
-    }
-
-    if let Some(h) = datetime.hour() {
-        pdf_date = pdf_date.hour(h);
-    }
-
-    if let Some(m) = datetime.minute() {
-        pdf_date = pdf_date.minute(m);
-    }
-
-    if let Some(s) = datetime.second() {
-        pdf_date = pdf_date.second(s);
+/// Converts a datetime to the text of a PDF date string.
+///
+/// The date is formatted like `pdf-writer` does, but as text, so that it can be
+/// encrypted like any other string.
+fn pdf_date(datetime: Datetime, tz: bool) -> Option<EcoString> {
+    let year = datetime.year().filter(|&y| y >= 0)?;
+    let mut pdf_date = eco_format!("D:{year:04}");
+
+    // Each part is only written if all larger parts are.
+    let parts = [
+        datetime.month(),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second(),
+    ];
+    for part in parts {
+        let Some(part) = part else { return Some(pdf_date) };
+        write!(pdf_date, "{part:02}").unwrap();
     }
 
     if tz {
-        pdf_date = pdf_date.utc_offset_hour(0).utc_offset_minute(0);
+        pdf_date.push('Z');
     }
 
     Some(pdf_date)
@@ -1,11 +1,10 @@
 use std::num::NonZeroUsize;
 
-use pdf_writer::{Finish, Ref, TextStr};
+use pdf_writer::{Finish, Name, Ref};
 use typst::foundations::{NativeElement, Packed, StyleChain};
-use typst::layout::Abs;
 use typst::model::HeadingElem;
 
-use crate::{AbsExt, PdfContext};
+use crate::PdfContext;
 
 /// Construct the outline for the document.
 pub(crate) fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
@@ -22,7 +21,12 @@ pub(crate) fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
         let heading = elem.to_packed::<HeadingElem>().unwrap();
         let leaf = HeadingNode::leaf(heading);
 
-        if leaf.bookmarked {
+        // Headings on pages that aren't exported are treated like skipped
+        // ones, as there is nothing to navigate to.
+        let pos = ctx.document.introspector.position(heading.location().unwrap());
+        let exported = ctx.written_page(pos.page).is_some();
+
+        if leaf.bookmarked && exported {
             let mut children = &mut tree;
 
             // Descend the tree through the latest bookmarked heading of each
@@ -160,18 +164,13 @@ fn write_outline_item(
     }
 
     let body = node.element.body();
-    outline.title(TextStr(body.plain_text().trim()));
+    outline.pair(Name(b"Title"), ctx.crypt.text(body.plain_text().trim()));
 
     let loc = node.element.location().unwrap();
     let pos = ctx.document.introspector.position(loc);
-    let index = pos.page.get() - 1;
-    if let Some(page) = ctx.pages.get(index) {
-        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
-        outline.dest().page(ctx.page_refs[index]).xyz(
-            pos.point.x.to_f32(),
-            (page.size.y - y).to_f32(),
-            None,
-        );
+    if let Some(index) = ctx.written_page(pos.page) {
+        let (x, y) = ctx.pages[index].destination(pos.point);
+        outline.dest().page(ctx.page_refs[index]).xyz(x, y, None);
     }
 
     outline.finish();
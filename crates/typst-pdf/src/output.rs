@@ -0,0 +1,259 @@
+//! Writing the PDF file into its destination.
+//!
+//! `pdf-writer` assembles a whole file in memory. To avoid keeping all page
+//! content streams around until the end, they are written into the destination
+//! as soon as they are ready, each as a chunk of its own. Everything else is
+//! assembled by `pdf-writer` as usual and appended once the export is done,
+//! with a cross-reference table that covers the objects of both parts.
+
+use std::io::Write;
+
+use ecow::eco_format;
+use pdf_writer::{Chunk, Ref};
+use typst::diag::{bail, StrResult};
+use typst::util::hash128;
+
+use crate::encrypt;
+
+/// The destination of a PDF file, which objects can be written into early.
+pub(crate) struct Output<'a> {
+    /// The writer the file ends up in.
+    writer: &'a mut dyn Write,
+    /// How many bytes were written so far.
+    len: usize,
+    /// The length of the file header that `pdf-writer` starts its file with.
+    header_len: usize,
+    /// The objects that were written early and their offsets in the file.
+    offsets: Vec<(Ref, usize)>,
+    /// A hash of everything written so far.
+    hash: u128,
+}
+
+impl<'a> Output<'a> {
+    /// Start a file with the given header, which must be the one `pdf-writer`
+    /// starts its file with.
+    pub fn new(writer: &'a mut dyn Write, header: &[u8]) -> StrResult<Self> {
+        let mut output = Self {
+            writer,
+            len: 0,
+            header_len: header.len(),
+            offsets: vec![],
+            hash: 0,
+        };
+        output.write(header)?;
+        Ok(output)
+    }
+
+    /// A hash of everything written so far.
+    pub fn hash(&self) -> u128 {
+        self.hash
+    }
+
+    /// Write a chunk that consists of a single indirect object.
+    pub fn write_object(&mut self, id: Ref, chunk: &Chunk) -> StrResult<()> {
+        self.offsets.push((id, self.len));
+        self.write(chunk.as_bytes())
+    }
+
+    /// Write the rest of the file, given the file that `pdf-writer` assembled
+    /// in memory. The trailer refers to the encryption dictionary if there is
+    /// one.
+    pub fn finish(mut self, pdf: &[u8], encrypt_ref: Option<Ref>) -> StrResult<()> {
+        let Some(xref) = Xref::parse(pdf) else {
+            bail!("failed to write PDF file (malformed cross-reference table)");
+        };
+
+        // The objects assembled in memory follow the ones written early.
+        let start = self.len;
+        self.write(&pdf[self.header_len..xref.offset])?;
+
+        let mut offsets: Vec<Option<usize>> = xref
+            .entries
+            .iter()
+            .map(|entry| entry.map(|offset| offset - self.header_len + start))
+            .collect();
+        for &(id, offset) in &self.offsets {
+            let i = id.get() as usize;
+            if offsets.len() <= i {
+                offsets.resize(i + 1, None);
+            }
+            offsets[i] = Some(offset);
+        }
+        offsets[0] = None;
+
+        let xref_offset = self.len;
+        self.write(&write_xref(&offsets))?;
+
+        let mut trailer = pdf[xref.trailer..xref.end].to_vec();
+        set_size(&mut trailer, offsets.len());
+        if let Some(encrypt_ref) = encrypt_ref {
+            encrypt::link_dict(&mut trailer, encrypt_ref);
+        }
+        self.write(&trailer)?;
+        self.write(format!("startxref\n{xref_offset}\n%%EOF").as_bytes())?;
+
+        self.writer
+            .flush()
+            .map_err(|err| eco_format!("failed to write PDF file ({err})"))
+    }
+
+    /// Write bytes into the file.
+    fn write(&mut self, bytes: &[u8]) -> StrResult<()> {
+        self.writer
+            .write_all(bytes)
+            .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
+        self.len += bytes.len();
+        self.hash = hash128(&(self.hash, bytes));
+        Ok(())
+    }
+}
+
+/// The cross-reference table of a file written by `pdf-writer`.
+struct Xref {
+    /// The offset of the table, which directly follows the last object.
+    offset: usize,
+    /// For each object id, the offset of the object if it is in use.
+    entries: Vec<Option<usize>>,
+    /// The offset of the trailer.
+    trailer: usize,
+    /// The offset of the `startxref` keyword that follows the trailer.
+    end: usize,
+}
+
+impl Xref {
+    /// Parse the table by following the file's `startxref` offset.
+    fn parse(pdf: &[u8]) -> Option<Self> {
+        let end = rfind(pdf, b"startxref")?;
+        let offset = std::str::from_utf8(&pdf[end + b"startxref".len()..])
+            .ok()?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()?;
+
+        let mut lines = pdf.get(offset..end)?.split(|&b| b == b'\n');
+        if lines.next()? != b"xref" {
+            return None;
+        }
+
+        let subsection = std::str::from_utf8(lines.next()?).ok()?;
+        let (first, count) = subsection.trim().split_once(' ')?;
+        if first != "0" {
+            return None;
+        }
+
+        // Each entry is a ten-digit offset, a five-digit generation number,
+        // and `n` for objects in use or `f` for free ones.
+        let entries = (0..count.parse::<usize>().ok()?)
+            .map(|_| {
+                let line = lines.next()?;
+                let in_use = *line.get(17)? == b'n';
+                let offset = std::str::from_utf8(line.get(..10)?).ok()?.parse().ok()?;
+                Some(in_use.then_some(offset))
+            })
+            .collect::<Option<_>>()?;
+
+        let trailer = offset + rfind(&pdf[offset..end], b"trailer")?;
+        Some(Self { offset, entries, trailer, end })
+    }
+}
+
+/// Write a cross-reference table with the given offsets of objects in use.
+/// The remaining entries form the list of free objects.
+fn write_xref(offsets: &[Option<usize>]) -> Vec<u8> {
+    let free: Vec<usize> = (0..offsets.len()).filter(|&i| offsets[i].is_none()).collect();
+    let mut next_free = free.iter().skip(1).chain([&0usize]);
+
+    let mut xref = format!("xref\n0 {}\n", offsets.len());
+    for (i, offset) in offsets.iter().enumerate() {
+        let entry = match offset {
+            Some(offset) => format!("{offset:010} 00000 n\r\n"),
+            None => {
+                let generation = if i == 0 { 65535 } else { 0 };
+                let next = next_free.next().unwrap();
+                format!("{next:010} {generation:05} f\r\n")
+            }
+        };
+        xref.push_str(&entry);
+    }
+
+    xref.into_bytes()
+}
+
+/// Replace the number of objects in a trailer dictionary.
+fn set_size(trailer: &mut Vec<u8>, size: usize) {
+    let Some(key) = rfind(trailer, b"/Size ") else { return };
+    let start = key + b"/Size ".len();
+    let digits = trailer[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+    trailer.splice(start..start + digits, size.to_string().into_bytes());
+}
+
+/// The offset of the last occurrence of the needle in the haystack.
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use pdf_writer::{Filter, Finish, Pdf};
+    use typst::eval::Tracer;
+    use typst::foundations::Bytes;
+    use typst::text::Font;
+    use typst::MemoryWorld;
+
+    use super::*;
+    use crate::PdfOptions;
+
+    /// Check that each entry in use points to its object.
+    fn check_xref(file: &[u8]) -> Xref {
+        let xref = Xref::parse(file).unwrap();
+        for (i, offset) in xref.entries.iter().enumerate() {
+            if let Some(offset) = *offset {
+                assert!(file[offset..].starts_with(format!("{i} 0 obj").as_bytes()));
+            }
+        }
+        xref
+    }
+
+    #[test]
+    fn test_objects_written_early() {
+        let mut pdf = Pdf::new();
+        let mut file = vec![];
+        let mut output = Output::new(&mut file, pdf.as_bytes()).unwrap();
+
+        // Object 3 is written early, object 4 is never written.
+        let mut chunk = Chunk::new();
+        chunk.stream(Ref::new(3), b"content").filter(Filter::FlateDecode);
+        output.write_object(Ref::new(3), &chunk).unwrap();
+
+        pdf.catalog(Ref::new(1)).pages(Ref::new(2));
+        pdf.pages(Ref::new(2)).count(0).finish();
+        pdf.indirect(Ref::new(5)).primitive(true);
+        output.finish(&pdf.finish(), None).unwrap();
+
+        let xref = check_xref(&file);
+        assert!(file.starts_with(b"%PDF-1.7"));
+        assert!(file.ends_with(b"%%EOF"));
+        assert_eq!(xref.entries.len(), 6);
+        assert_eq!(xref.entries.iter().filter(|entry| entry.is_some()).count(), 4);
+        assert!(xref.entries[4].is_none());
+        assert!(String::from_utf8_lossy(&file).contains("/Size 6"));
+        assert!(String::from_utf8_lossy(&file).contains("0000000004 65535 f\r\n"));
+    }
+
+    #[test]
+    fn test_document_streamed_into_writer() {
+        let data = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        let world =
+            MemoryWorld::new("#set page(height: 100pt)\n#lorem(200)").with_fonts([font]);
+        let document = typst::compile(&world, &mut Tracer::new()).unwrap();
+        assert!(document.pages.len() > 1);
+
+        let mut file = vec![];
+        crate::pdf_into(&document, &PdfOptions::default(), &mut file).unwrap();
+        let xref = check_xref(&file);
+        let objects = file.windows(6).filter(|&window| window == b" 0 obj").count();
+        assert_eq!(xref.entries.iter().flatten().count(), objects);
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::num::NonZeroUsize;
 
 use ecow::{eco_format, EcoString};
@@ -6,13 +7,16 @@ use pdf_writer::types::{
     ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
     LineJoinStyle, NumberingStyle,
 };
-use pdf_writer::writers::PageLabel;
-use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
+use pdf_writer::writers::{Action, Annotation, PageLabel};
+use pdf_writer::{Chunk, Content, Filter, Finish, Name, Rect, Ref, Str};
+use typst::diag::StrResult;
+use typst::foundations::Duration;
 use typst::introspection::Meta;
 use typst::layout::{
-    Abs, Em, Frame, FrameItem, GroupItem, Page, Point, Ratio, Size, Transform,
+    Abs, Em, Frame, FrameItem, GroupItem, Page, PageRanges, Point, Ratio, Size,
+    Transform, Transition,
 };
-use typst::model::{Destination, Numbering};
+use typst::model::{Destination, FormField, Numbering};
 use typst::text::{Case, Font, TextItem};
 use typst::util::{Deferred, Numeric};
 use typst::visualize::{
@@ -20,27 +24,89 @@ use typst::visualize::{
 };
 
 use crate::color::PaintEncode;
+use crate::encrypt::Crypt;
 use crate::extg::ExtGState;
-use crate::image::deferred_image;
+use crate::image::{deferred_image, raster_encoding};
 use crate::{deflate_deferred, AbsExt, EmExt, PdfContext};
 
+/// How many content streams may be compressed in the background at once
+/// before construction waits for the oldest one.
+const MAX_PENDING_STREAMS: usize = 32;
+
 /// Construct page objects.
+///
+/// The content streams are compressed in the background and written into the
+/// output as soon as they are ready, so that only a bounded number of them is
+/// kept in memory at any time. The page objects are only written in
+/// [`write_page_tree`] because their links may point to later pages.
 #[typst_macros::time(name = "construct pages")]
-pub(crate) fn construct_pages(ctx: &mut PdfContext, pages: &[Page]) {
-    for page in pages {
-        let (page_ref, mut encoded) = construct_page(ctx, &page.frame);
+pub(crate) fn construct_pages(
+    ctx: &mut PdfContext,
+    pages: &[Page],
+    ranges: Option<&PageRanges>,
+) -> StrResult<()> {
+    let mut pending = VecDeque::new();
+    for (i, page) in pages.iter().enumerate() {
+        if ctx.is_cancelled() {
+            return Ok(());
+        }
+
+        if ranges.map_or(false, |ranges| !ranges.includes_page_index(i)) {
+            ctx.page_indices.push(None);
+            continue;
+        }
+
+        let (page_ref, mut encoded, content) = construct_page(ctx, &page.frame);
+        let content_id = ctx.alloc.bump();
+        encoded.content_id = Some(content_id);
+        pending.push_back((content_id, content));
+        if pending.len() > MAX_PENDING_STREAMS {
+            if let Some((content_id, content)) = pending.pop_front() {
+                write_content_stream(ctx, content_id, content)?;
+            }
+        }
+
         encoded.label = page
             .numbering
             .as_ref()
             .and_then(|num| PdfPageLabel::generate(num, page.number));
+        encoded.bleed = page.bleed;
+        encoded.slug = page.slug;
+        encoded.transition = page.transition;
+        encoded.duration = page.duration;
+        ctx.page_indices.push(Some(ctx.pages.len()));
         ctx.page_refs.push(page_ref);
         ctx.pages.push(encoded);
     }
+
+    for (content_id, content) in pending {
+        write_content_stream(ctx, content_id, content)?;
+    }
+
+    Ok(())
+}
+
+/// Write a page's compressed content stream into the output.
+fn write_content_stream(
+    ctx: &mut PdfContext,
+    id: Ref,
+    content: Deferred<Vec<u8>>,
+) -> StrResult<()> {
+    let data = ctx.crypt.stream(content.wait());
+    let mut chunk = Chunk::new();
+    chunk.stream(id, &data).filter(Filter::FlateDecode);
+    ctx.output.write_object(id, &chunk)
 }
 
 /// Construct a page object.
+///
+/// Returns the page's reference, the page, and its compressed content stream.
+/// Writing the content stream is up to the caller.
 #[typst_macros::time(name = "construct page")]
-pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, EncodedPage) {
+pub(crate) fn construct_page(
+    ctx: &mut PdfContext,
+    frame: &Frame,
+) -> (Ref, EncodedPage, Deferred<Vec<u8>>) {
     let page_ref = ctx.alloc.bump();
 
     let size = frame.size();
@@ -53,7 +119,8 @@ pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Encod
         saves: vec![],
         bottom: 0.0,
         links: vec![],
-        resources: HashMap::default(),
+        fields: vec![],
+        resources: BTreeMap::new(),
     };
 
     // Make the coordinate system start at the top-left.
@@ -70,17 +137,27 @@ pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Encod
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
+    // Fields with a tab index come first, the others keep document order.
+    ctx.fields
+        .sort_by_key(|(_, field, _)| (field.tab_index.is_none(), field.tab_index));
+
+    let content = deflate_deferred(ctx.content.finish());
     let page = EncodedPage {
         size,
-        content: deflate_deferred(ctx.content.finish()),
+        content_id: None,
         id: ctx.page_ref,
         uses_opacities: ctx.uses_opacities,
         links: ctx.links,
+        fields: ctx.fields,
         label: None,
         resources: ctx.resources,
+        bleed: Abs::zero(),
+        slug: Abs::zero(),
+        transition: None,
+        duration: None,
     };
 
-    (page_ref, page)
+    (page_ref, page, content)
 }
 
 /// Write the page tree.
@@ -134,17 +211,24 @@ pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
     }
     ext_gs_states.finish();
 
+    if !ctx.layer_refs.is_empty() {
+        let mut properties = resources.insert(Name(b"Properties")).dict();
+        for (i, layer_ref) in ctx.layer_refs.iter().enumerate() {
+            let name = eco_format!("L{}", i);
+            properties.pair(Name(name.as_bytes()), *layer_ref);
+        }
+    }
+
     resources.finish();
     pages.finish();
 
     // Write all of the functions used by the document.
-    ctx.colors.write_functions(&mut ctx.pdf);
+    ctx.colors.write_functions(&mut ctx.pdf, &ctx.crypt);
 }
 
 /// Write a page tree node.
 fn write_page(ctx: &mut PdfContext, i: usize) {
     let page = &ctx.pages[i];
-    let content_id = ctx.alloc.bump();
 
     let mut page_writer = ctx.pdf.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
@@ -152,7 +236,29 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
     let w = page.size.x.to_f32();
     let h = page.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
-    page_writer.contents(content_id);
+
+    // Describe which part of the media is the bleed and the final page.
+    let offset = page.bleed + page.slug;
+    if !offset.is_zero() {
+        let slug = page.slug.to_f32();
+        page_writer.bleed_box(Rect::new(slug, slug, w - slug, h - slug));
+
+        let offset = offset.to_f32();
+        page_writer.trim_box(Rect::new(offset, offset, w - offset, h - offset));
+    }
+    if let Some(content_id) = page.content_id {
+        page_writer.contents(content_id);
+    }
+
+    if let Some(transition) = page.transition {
+        let mut trans = page_writer.insert(Name(b"Trans")).dict();
+        trans.pair(Name(b"Type"), Name(b"Trans"));
+        trans.pair(Name(b"S"), to_pdf_transition(transition));
+    }
+
+    if let Some(duration) = page.duration {
+        page_writer.pair(Name(b"Dur"), duration.seconds() as f32);
+    }
 
     if page.uses_opacities {
         page_writer
@@ -164,42 +270,209 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
             .srgb();
     }
 
-    let mut annotations = page_writer.annotations();
+    // Form field widgets come first so that they define the tab order.
+    let mut annotations = page_writer.insert(Name(b"Annots")).array();
+    for (widget_ref, _, _) in &page.fields {
+        annotations.item(*widget_ref);
+    }
+
     for (dest, rect) in &page.links {
-        let mut annotation = annotations.push();
+        let mut annotation = annotations.push().start::<Annotation>();
         annotation.subtype(AnnotationType::Link).rect(*rect);
         annotation.border(0.0, 0.0, 0.0, None).flags(AnnotationFlags::PRINT);
 
         let pos = match dest {
-            Destination::Url(uri) => {
-                annotation
-                    .action()
-                    .action_type(ActionType::Uri)
-                    .uri(Str(uri.as_bytes()));
+            Destination::Url(url) => {
+                if let Some(action) =
+                    UrlAction::new(url, ctx.allow_launch, ctx.strip_script_links)
+                {
+                    action.write(annotation.action(), &ctx.crypt);
+                }
                 continue;
             }
             Destination::Position(pos) => *pos,
             Destination::Location(loc) => ctx.document.introspector.position(*loc),
         };
 
-        let index = pos.page.get() - 1;
-        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
-        if let Some(page) = ctx.pages.get(index) {
+        if let Some(index) = ctx.written_page(pos.page) {
+            let (x, y) = ctx.pages[index].destination(pos.point);
             annotation
                 .action()
                 .action_type(ActionType::GoTo)
                 .destination()
                 .page(ctx.page_refs[index])
-                .xyz(pos.point.x.to_f32(), (page.size.y - y).to_f32(), None);
+                .xyz(x, y, None);
         }
     }
 
     annotations.finish();
     page_writer.finish();
+}
+
+/// Write a named destination for the first element with each label, so that
+/// other documents can link to it with a fragment like `doc.pdf#intro`.
+///
+/// Returns the reference to the destination dictionary, if there are any
+/// labels on exported pages.
+pub(crate) fn write_named_destinations(ctx: &mut PdfContext) -> Option<Ref> {
+    let document = ctx.document;
+    let introspector = &document.introspector;
+    let mut seen = HashSet::new();
+    let mut dests = vec![];
+    for elem in introspector.all() {
+        let (Some(label), Some(loc)) = (elem.label(), elem.location()) else {
+            continue;
+        };
+
+        let pos = introspector.position(loc);
+        let Some(index) = ctx.written_page(pos.page) else { continue };
+        if seen.insert(label) {
+            dests.push((label, index, pos.point));
+        }
+    }
+
+    if dests.is_empty() {
+        return None;
+    }
+
+    let dests_ref = ctx.alloc.bump();
+    let mut dict = ctx.pdf.indirect(dests_ref).dict();
+    for (label, index, point) in dests {
+        let (x, y) = ctx.pages[index].destination(point);
+        dict.insert(Name(label.as_str().as_bytes()))
+            .start::<pdf_writer::writers::Destination>()
+            .page(ctx.page_refs[index])
+            .xyz(x, y, None);
+    }
+    dict.finish();
+
+    Some(dests_ref)
+}
+
+/// How a link to a URL is represented in the PDF.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum UrlAction<'a> {
+    /// A URI that the viewer opens in a browser or mail client.
+    Uri(&'a str),
+    /// A named destination within this document, from a bare `#fragment`.
+    GoTo(Cow<'a, str>),
+    /// A PDF file and optionally a named destination within it.
+    RemoteGoTo(&'a str, Option<Cow<'a, str>>),
+    /// A local file that is opened with its default application.
+    Launch(&'a str),
+}
+
+impl<'a> UrlAction<'a> {
+    /// Determine the action for a URL.
+    ///
+    /// Returns `None` for URLs that would run scripts if `strip_scripts` is
+    /// set. Local files other than PDFs only become launch actions if
+    /// `allow_launch` is set. Fragments are percent-decoded, so that they
+    /// match the names of the destinations.
+    fn new(url: &'a str, allow_launch: bool, strip_scripts: bool) -> Option<Self> {
+        let path = match url_scheme(url) {
+            Some(scheme) => match scheme.to_ascii_lowercase().as_str() {
+                "javascript" | "vbscript" | "data" if strip_scripts => return None,
+                "file" => {
+                    let rest = &url[scheme.len() + 1..];
+                    rest.strip_prefix("//").unwrap_or(rest)
+                }
+                _ => return Some(Self::Uri(url)),
+            },
+            None => url,
+        };
+
+        let (file, fragment) = match path.split_once('#') {
+            Some((file, fragment)) => {
+                (file, Some(fragment).filter(|f| !f.is_empty()).map(percent_decode))
+            }
+            None => (path, None),
+        };
+
+        if file.is_empty() {
+            return Some(fragment.map_or(Self::Uri(url), Self::GoTo));
+        }
+
+        if file.to_ascii_lowercase().ends_with(".pdf") {
+            Some(Self::RemoteGoTo(file, fragment))
+        } else if allow_launch {
+            Some(Self::Launch(file))
+        } else {
+            Some(Self::Uri(url))
+        }
+    }
+
+    /// Write the action.
+    fn write(self, mut action: Action, crypt: &Crypt) {
+        match self {
+            Self::Uri(uri) => {
+                action.action_type(ActionType::Uri);
+                action.pair(Name(b"URI"), crypt.str(uri.as_bytes()));
+            }
+            Self::GoTo(dest) => {
+                action.action_type(ActionType::GoTo);
+                action.pair(Name(b"D"), Name(dest.as_bytes()));
+            }
+            Self::RemoteGoTo(file, dest) => {
+                action.pair(Name(b"S"), Name(b"GoToR"));
+                action.pair(Name(b"F"), crypt.str(file.as_bytes()));
+                if let Some(dest) = dest {
+                    action.pair(Name(b"D"), Name(dest.as_bytes()));
+                } else {
+                    // Without a destination, open the first page.
+                    action.insert(Name(b"D")).array().item(0).item(Name(b"Fit"));
+                }
+            }
+            Self::Launch(file) => {
+                action.pair(Name(b"S"), Name(b"Launch"));
+                action.pair(Name(b"F"), crypt.str(file.as_bytes()));
+                action.pair(Name(b"NewWindow"), true);
+            }
+        }
+    }
+}
+
+/// The scheme of a URL, if it has one.
+///
+/// Single letters are not considered a scheme, so that Windows paths like
+/// `C:\file.pdf` are treated as paths.
+fn url_scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = scheme.len() > 1
+        && chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// Decode the `%XX` escapes in a URL fragment.
+///
+/// Invalid escapes are kept as they are.
+fn percent_decode(fragment: &str) -> Cow<'_, str> {
+    if !fragment.contains('%') {
+        return Cow::Borrowed(fragment);
+    }
+
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match hex {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
 
-    ctx.pdf
-        .stream(content_id, page.content.wait())
-        .filter(Filter::FlateDecode);
+    Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
 }
 
 /// Write the page labels.
@@ -232,7 +505,7 @@ pub(crate) fn write_page_labels(ctx: &mut PdfContext) -> Vec<(NonZeroUsize, Ref)
         // Only add what is actually provided. Don't add empty prefix string if
         // it wasn't given for example.
         if let Some(prefix) = &label.prefix {
-            entry.prefix(TextStr(prefix));
+            entry.pair(Name(b"P"), ctx.crypt.text(prefix));
         }
 
         if let Some(style) = label.style {
@@ -334,20 +607,44 @@ pub struct EncodedPage {
     pub id: Ref,
     /// The page's dimensions.
     pub size: Size,
-    /// The page's content stream.
-    pub content: Deferred<Vec<u8>>,
+    /// The indirect object id of the page's content stream. Only set for the
+    /// pages of the document, whose content streams are written during
+    /// construction.
+    pub content_id: Option<Ref>,
     /// Whether the page uses opacities.
     pub uses_opacities: bool,
     /// Links in the PDF coordinate system.
     pub links: Vec<(Destination, Rect)>,
+    /// Form field widgets with their references, in tab order.
+    pub fields: Vec<(Ref, FormField, Rect)>,
     /// The page's used resources
-    pub resources: HashMap<PageResource, usize>,
+    pub resources: BTreeMap<PageResource, usize>,
     /// The page's PDF label.
     label: Option<PdfPageLabel>,
+    /// How far the page's contents extend beyond the trim box.
+    bleed: Abs,
+    /// The space between the bleed box and the media box.
+    slug: Abs,
+    /// The presentation transition effect.
+    transition: Option<Transition>,
+    /// How long the page is shown in a presentation.
+    duration: Option<Duration>,
+}
+
+impl EncodedPage {
+    /// The PDF coordinates a link to a position on the trimmed page should
+    /// jump to, a bit above the position itself.
+    pub(crate) fn destination(&self, point: Point) -> (f32, f32) {
+        let offset = self.bleed + self.slug;
+        let y = (point.y - Abs::pt(10.0)).max(Abs::zero());
+        ((offset + point.x).to_f32(), (self.size.y - offset - y).to_f32())
+    }
 }
 
 /// Represents a resource being used in a PDF page by its name.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Resources are ordered so that they are always written in the same order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PageResource {
     kind: ResourceKind,
     name: EcoString,
@@ -360,13 +657,14 @@ impl PageResource {
 }
 
 /// A kind of resource being used in a PDF page.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ResourceKind {
     XObject,
     Font,
     Gradient,
     Pattern,
     ExtGState,
+    Properties,
 }
 
 impl PageResource {
@@ -399,6 +697,11 @@ impl PageResource {
     pub fn is_ext_g_state(&self) -> bool {
         matches!(self.kind, ResourceKind::ExtGState)
     }
+
+    /// Returns whether the resource is a property list.
+    pub fn is_properties(&self) -> bool {
+        matches!(self.kind, ResourceKind::Properties)
+    }
 }
 
 /// An exporter for the contents of a single PDF page.
@@ -411,8 +714,9 @@ pub struct PageContext<'a, 'b> {
     bottom: f32,
     uses_opacities: bool,
     links: Vec<(Destination, Rect)>,
+    fields: Vec<(Ref, FormField, Rect)>,
     /// Keep track of the resources being used in the page.
-    pub resources: HashMap<PageResource, usize>,
+    pub resources: BTreeMap<PageResource, usize>,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
@@ -643,6 +947,7 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
             FrameItem::Meta(meta, size) => match meta {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
+                Meta::Field(field) => write_field(ctx, pos, field, *size),
                 Meta::Hide => {}
             },
         }
@@ -653,6 +958,18 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
 fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
     let translation = Transform::translate(pos.x, pos.y);
 
+    // Mark the contents as belonging to an optional content group.
+    if let Some(layer) = &group.layer {
+        let index = ctx.parent.layer_index(layer);
+        let name = eco_format!("L{index}");
+        ctx.content
+            .op("BDC")
+            .operand(Name(b"OC"))
+            .operand(Name(name.as_bytes()));
+        ctx.resources
+            .insert(PageResource::new(ResourceKind::Properties, name), index);
+    }
+
     ctx.save_state();
 
     if group.frame.kind().is_hard() {
@@ -675,6 +992,10 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
 
     write_frame(ctx, &group.frame);
     ctx.restore_state();
+
+    if group.layer.is_some() {
+        ctx.content.end_marked_content();
+    }
 }
 
 /// Encode a text run into the content stream.
@@ -826,10 +1147,11 @@ fn write_path(ctx: &mut PageContext, x: f32, y: f32, path: &Path) {
 /// Encode a vector or raster image into the content stream.
 fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size) {
     let index = ctx.parent.image_map.insert(image.clone());
+    let encoding = raster_encoding(ctx.parent, image);
     ctx.parent
         .image_deferred_map
         .entry(index)
-        .or_insert_with(|| deferred_image(image.clone()));
+        .or_insert_with(|| deferred_image(image.clone(), encoding));
 
     let name = eco_format!("Im{index}");
     let w = size.x.to_f32();
@@ -858,12 +1180,24 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
 
 /// Save a link for later writing in the annotations dictionary.
 fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size) {
+    let rect = annotation_rect(ctx, pos, size);
+    ctx.links.push((dest.clone(), rect));
+}
+
+/// Save a form field for later.
+fn write_field(ctx: &mut PageContext, pos: Point, field: &FormField, size: Size) {
+    let rect = annotation_rect(ctx, pos, size);
+    let widget_ref = ctx.parent.alloc.bump();
+    ctx.fields.push((widget_ref, field.clone(), rect));
+}
+
+/// Compute the bounding box of a transformed area in PDF coordinates.
+fn annotation_rect(ctx: &PageContext, pos: Point, size: Size) -> Rect {
     let mut min_x = Abs::inf();
     let mut min_y = Abs::inf();
     let mut max_x = -Abs::inf();
     let mut max_y = -Abs::inf();
 
-    // Compute the bounding box of the transformed link.
     for point in [
         pos,
         pos + Point::with_x(size.x),
@@ -881,9 +1215,24 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let x2 = max_x.to_f32();
     let y1 = max_y.to_f32();
     let y2 = min_y.to_f32();
-    let rect = Rect::new(x1, y1, x2, y2);
+    Rect::new(x1, y1, x2, y2)
+}
 
-    ctx.links.push((dest.clone(), rect));
+fn to_pdf_transition(transition: Transition) -> Name<'static> {
+    let name: &[u8] = match transition {
+        Transition::Split => b"Split",
+        Transition::Blinds => b"Blinds",
+        Transition::Box => b"Box",
+        Transition::Wipe => b"Wipe",
+        Transition::Dissolve => b"Dissolve",
+        Transition::Glitter => b"Glitter",
+        Transition::Fly => b"Fly",
+        Transition::Push => b"Push",
+        Transition::Cover => b"Cover",
+        Transition::Uncover => b"Uncover",
+        Transition::Fade => b"Fade",
+    };
+    Name(name)
 }
 
 fn to_pdf_line_cap(cap: LineCap) -> LineCapStyle {
@@ -911,3 +1260,58 @@ fn to_pdf_numbering_style(style: PdfPageLabelStyle) -> NumberingStyle {
         PdfPageLabelStyle::UpperAlpha => NumberingStyle::UpperAlpha,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_action() {
+        let action = |url| UrlAction::new(url, false, false);
+        assert_eq!(
+            action("https://typst.app"),
+            Some(UrlAction::Uri("https://typst.app"))
+        );
+        assert_eq!(action("mailto:a@b.c"), Some(UrlAction::Uri("mailto:a@b.c")));
+        assert_eq!(action("#intro"), Some(UrlAction::GoTo("intro".into())));
+        assert_eq!(action("doc.pdf"), Some(UrlAction::RemoteGoTo("doc.pdf", None)));
+        assert_eq!(
+            action("../Doc.PDF#section-3"),
+            Some(UrlAction::RemoteGoTo("../Doc.PDF", Some("section-3".into())))
+        );
+        assert_eq!(
+            action("file:///tmp/doc.pdf"),
+            Some(UrlAction::RemoteGoTo("/tmp/doc.pdf", None))
+        );
+        assert_eq!(
+            action(r"C:\doc.pdf"),
+            Some(UrlAction::RemoteGoTo(r"C:\doc.pdf", None))
+        );
+        assert_eq!(action("data.csv"), Some(UrlAction::Uri("data.csv")));
+        assert_eq!(
+            UrlAction::new("data.csv", true, false),
+            Some(UrlAction::Launch("data.csv"))
+        );
+    }
+
+    #[test]
+    fn test_url_action_scripts() {
+        let url = "javascript:alert(1)";
+        assert_eq!(UrlAction::new(url, false, false), Some(UrlAction::Uri(url)));
+        assert_eq!(UrlAction::new(url, false, true), None);
+        assert_eq!(UrlAction::new("JavaScript:alert(1)", false, true), None);
+        assert_eq!(UrlAction::new("data:text/html,hi", false, true), None);
+    }
+
+    #[test]
+    fn test_url_action_fragment_decoding() {
+        let action = |url| UrlAction::new(url, false, false);
+        assert_eq!(action("#%C3%BCber"), Some(UrlAction::GoTo("über".into())));
+        assert_eq!(action("#a%20b"), Some(UrlAction::GoTo("a b".into())));
+        assert_eq!(action("#100%"), Some(UrlAction::GoTo("100%".into())));
+        assert_eq!(
+            action("doc.pdf#%E2%88%9E"),
+            Some(UrlAction::RemoteGoTo("doc.pdf", Some("∞".into())))
+        );
+    }
+}
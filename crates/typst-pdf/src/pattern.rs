@@ -16,7 +16,8 @@ pub(crate) fn write_patterns(ctx: &mut PdfContext) {
         let tiling = ctx.alloc.bump();
         ctx.pattern_refs.push(tiling);
 
-        let mut tiling_pattern = ctx.pdf.tiling_pattern(tiling, content);
+        let content = ctx.crypt.stream(content);
+        let mut tiling_pattern = ctx.pdf.tiling_pattern(tiling, &content);
         tiling_pattern
             .tiling_type(TilingType::ConstantSpacing)
             .paint_type(PaintType::Colored)
@@ -70,6 +71,15 @@ pub(crate) fn write_patterns(ctx: &mut PdfContext) {
                 .map(|(res, ref_)| (res.name(), ctx.ext_gs_refs[*ref_])),
         );
 
+        if resources.iter().any(|(res, _)| res.is_properties()) {
+            resources_map.insert(Name(b"Properties")).dict().pairs(
+                resources
+                    .iter()
+                    .filter(|(res, _)| res.is_properties())
+                    .map(|(res, ref_)| (res.name(), ctx.layer_refs[*ref_])),
+            );
+        }
+
         resources_map.finish();
         tiling_pattern
             .matrix(transform_to_array(
@@ -114,12 +124,12 @@ fn register_pattern(
     };
 
     // Render the body.
-    let (_, content) = construct_page(ctx.parent, pattern.frame());
+    let (_, content, stream) = construct_page(ctx.parent, pattern.frame());
 
     let pdf_pattern = PdfPattern {
         transform,
         pattern: pattern.clone(),
-        content: content.content.wait().clone(),
+        content: stream.wait().clone(),
         resources: content.resources.into_iter().collect(),
     };
 
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use ecow::{eco_format, EcoString, EcoVec};
+use ttf_parser::Permissions;
+use typst::diag::SourceDiagnostic;
+use typst::foundations::Repr;
+use typst::layout::{Frame, FrameItem, Size};
+use typst::model::Document;
+use typst::syntax::Span;
+use typst::text::{Font, TextItem};
+use typst::visualize::{Color, FixedStroke, IccSpace, Image, Paint};
+
+use crate::PdfOptions;
+
+/// The largest page side that PDF viewers must support, which is 200 inches.
+const MAX_PAGE_SIDE: f64 = 14400.0;
+
+/// Check a document for problems that commonly make exported PDFs fail a
+/// preflight check.
+///
+/// This checks the pages that would be exported with the given options for
+/// images without alternative text, fonts that are not embedded or don't
+/// permit embedding, colors outside of the sRGB gamut or the document's CMYK
+/// output intent, and oversized pages. The problems are returned as warnings;
+/// the export itself is not affected.
+#[typst_macros::time(name = "validate pdf")]
+pub fn validate(document: &Document, options: &PdfOptions) -> EcoVec<SourceDiagnostic> {
+    let cmyk = document.icc.as_ref().is_some_and(|icc| icc.space() == IccSpace::Cmyk);
+    let mut validator = Validator {
+        options,
+        cmyk,
+        fonts: HashSet::new(),
+        seen: HashSet::new(),
+        warnings: EcoVec::new(),
+    };
+
+    for (i, page) in document.pages.iter().enumerate() {
+        if let Some(ranges) = &options.page_ranges {
+            if !ranges.includes_page_index(i) {
+                continue;
+            }
+        }
+
+        validator.page(i + 1, page.frame.size());
+        validator.frame(&page.frame);
+    }
+
+    validator.warnings
+}
+
+/// Walks through the frames of a document and collects problems.
+struct Validator<'a> {
+    /// The options the document is exported with.
+    options: &'a PdfOptions<'a>,
+    /// Whether the document has a CMYK output intent.
+    cmyk: bool,
+    /// The fonts that were already checked.
+    fonts: HashSet<Font>,
+    /// The problems that were already reported, to report each problem only
+    /// once per source location.
+    seen: HashSet<(Span, EcoString)>,
+    /// The collected warnings.
+    warnings: EcoVec<SourceDiagnostic>,
+}
+
+impl Validator<'_> {
+    /// Report a problem, unless it was already reported for the same span.
+    fn warn(&mut self, span: Span, message: EcoString, hint: &str) {
+        if self.seen.insert((span, message.clone())) {
+            self.warnings
+                .push(SourceDiagnostic::warning(span, message).with_hint(hint));
+        }
+    }
+
+    /// Check the size of a page.
+    fn page(&mut self, number: usize, size: Size) {
+        if size.x.to_pt() > MAX_PAGE_SIDE || size.y.to_pt() > MAX_PAGE_SIDE {
+            self.warn(
+                Span::detached(),
+                eco_format!("page {number} is larger than 200 inches on one side"),
+                "many PDF viewers and printers can't display pages of this size",
+            );
+        }
+    }
+
+    /// Check everything in a frame.
+    fn frame(&mut self, frame: &Frame) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => self.frame(&group.frame),
+                FrameItem::Text(text) => self.text(text),
+                FrameItem::Shape(shape, span) => {
+                    if let Some(fill) = &shape.fill {
+                        self.paint(fill, *span);
+                    }
+                    if let Some(stroke) = &shape.stroke {
+                        self.stroke(stroke, *span);
+                    }
+                }
+                FrameItem::Image(image, _, span) => self.image(image, *span),
+                FrameItem::Meta(..) => {}
+            }
+        }
+    }
+
+    /// Check the font and colors of a text run.
+    fn text(&mut self, text: &TextItem) {
+        let span = text.glyphs.first().map_or(Span::detached(), |glyph| glyph.span.0);
+        self.font(&text.font, span);
+        self.paint(&text.fill, span);
+        if let Some(stroke) = &text.stroke {
+            self.stroke(stroke, span);
+        }
+    }
+
+    /// Check whether a font is embedded, the first time it is used.
+    fn font(&mut self, font: &Font, span: Span) {
+        if !self.fonts.insert(font.clone()) {
+            return;
+        }
+
+        let family = &font.info().family;
+        if self
+            .options
+            .unembedded_fonts
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(family))
+        {
+            self.warn(
+                span,
+                eco_format!("font `{family}` is not embedded"),
+                "the PDF only displays correctly where this exact font is installed",
+            );
+        } else if font.ttf().permissions() == Some(Permissions::Restricted) {
+            self.warn(
+                span,
+                eco_format!("the license of font `{family}` does not permit embedding"),
+                "the font is embedded anyway, consider using a different font",
+            );
+        }
+    }
+
+    /// Check that an image has alternative text.
+    fn image(&mut self, image: &Image, span: Span) {
+        if image.alt().is_none() {
+            self.warn(
+                span,
+                "image has no alternative text".into(),
+                "describe the image with the `alt` parameter for readers that use \
+                 assistive technology",
+            );
+        }
+    }
+
+    /// Check the colors of a stroke.
+    fn stroke(&mut self, stroke: &FixedStroke, span: Span) {
+        self.paint(&stroke.paint, span);
+    }
+
+    /// Check all colors of a paint.
+    fn paint(&mut self, paint: &Paint, span: Span) {
+        match paint {
+            Paint::Solid(color) => self.color(*color, span),
+            Paint::Gradient(gradient) => {
+                for &(color, _) in gradient.stops_ref() {
+                    self.color(color, span);
+                }
+            }
+            Paint::Pattern(pattern) => self.frame(pattern.frame()),
+        }
+    }
+
+    /// Check a single color.
+    fn color(&mut self, color: Color, span: Span) {
+        if !color.is_in_srgb_gamut() {
+            self.warn(
+                span,
+                eco_format!("color {} is outside of the sRGB gamut", color.repr()),
+                "PDF viewers clamp it to the nearest displayable color, which may \
+                 change its appearance",
+            );
+        }
+
+        if self.cmyk && !matches!(color, Color::Cmyk(_) | Color::Luma(_)) {
+            self.warn(
+                span,
+                "color is not a CMYK color, but the output intent is CMYK".into(),
+                "use `cmyk` colors to control exactly how it is printed",
+            );
+        }
+    }
+}
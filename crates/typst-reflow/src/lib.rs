@@ -0,0 +1,442 @@
+//! Recovering the structure of laid out documents.
+//!
+//! Exporters into reflowable formats like DOCX and EPUB can't use the fixed
+//! layout of a document's pages. Instead, they write the blocks collected here
+//! with their own markup.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use typst::foundations::{Content, StyleChain};
+use typst::introspection::{Location, Meta};
+use typst::layout::{Abs, Frame, FrameItem, Point, Size, Transform};
+use typst::model::{Document, FootnoteElem, FootnoteEntry, HeadingElem, TableElem};
+use typst::text::{FontStyle, FontWeight, Lang, TextItem};
+use typst::visualize::{Color, Image, Paint};
+
+/// The structure recovered from a laid out document.
+#[derive(Default)]
+pub struct Collected {
+    /// The document's blocks in reading order.
+    pub blocks: Vec<Block>,
+    /// The plain text of the footnotes, referenced by index.
+    pub footnotes: Vec<EcoString>,
+    /// The images, referenced by index.
+    pub images: Vec<Image>,
+    /// The language of the document's first text.
+    pub lang: Option<Lang>,
+}
+
+/// A block-level part of the document.
+pub enum Block {
+    /// A paragraph or heading.
+    Paragraph(Paragraph),
+    /// A table.
+    Table(Table),
+}
+
+/// A paragraph made up of formatted runs.
+#[derive(Default)]
+pub struct Paragraph {
+    /// The heading level, if the paragraph is a heading.
+    pub heading: Option<NonZeroUsize>,
+    /// The paragraph's contents.
+    pub runs: Vec<Run>,
+}
+
+/// A piece of a paragraph.
+pub enum Run {
+    /// Text with uniform formatting.
+    Text(EcoString, RunStyle),
+    /// A reference to the footnote with the given index.
+    Footnote(usize),
+    /// The image with the given index, shown at the given size.
+    Image(usize, Size),
+}
+
+/// The character formatting of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunStyle {
+    /// The font family.
+    pub font: EcoString,
+    /// The font size.
+    pub size: Abs,
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is italic.
+    pub italic: bool,
+    /// The text color as RGB, if it is not black.
+    pub color: Option<[u8; 3]>,
+}
+
+impl RunStyle {
+    fn new(text: &TextItem) -> Self {
+        let info = text.font.info();
+        let color = match &text.fill {
+            Paint::Solid(color) if *color != Color::BLACK => {
+                let [r, g, b, _] = color.to_vec4_u8();
+                Some([r, g, b])
+            }
+            _ => None,
+        };
+
+        Self {
+            font: info.family.as_str().into(),
+            size: text.size,
+            bold: info.variant.weight >= FontWeight::SEMIBOLD,
+            italic: info.variant.style != FontStyle::Normal,
+            color,
+        }
+    }
+}
+
+/// A table whose cells are reduced to plain text.
+pub struct Table {
+    /// The number of columns.
+    pub columns: usize,
+    /// The text of the cells with the number of columns they span.
+    pub cells: Vec<(EcoString, NonZeroUsize)>,
+}
+
+/// Recover the structure of a document from its frames.
+///
+/// Text is grouped into paragraphs by its position. Headings, tables, and
+/// footnotes are recognized by the metadata of the elements they were laid out
+/// from. Images are kept as paragraphs of their own if `embed` accepts them
+/// and are dropped otherwise.
+pub fn collect(document: &Document, embed: &dyn Fn(&Image) -> bool) -> Collected {
+    let mut collector = Collector {
+        output: Collected::default(),
+        embed,
+        regions: vec![],
+        seen: HashSet::new(),
+        par: None,
+    };
+    for page in &document.pages {
+        collector.regions.clear();
+        collector.frame(&page.frame, Transform::identity());
+        collector.finish_par();
+    }
+    collector.output
+}
+
+/// Walks through frames to recover structure.
+struct Collector<'a> {
+    /// The structure recovered so far.
+    output: Collected,
+    /// Decides which images are kept.
+    embed: &'a dyn Fn(&Image) -> bool,
+    /// Areas on the current page that belong to an element.
+    regions: Vec<Region>,
+    /// Elements that were already handled, for those spanning multiple frames.
+    seen: HashSet<Location>,
+    /// The paragraph that is currently being built and its last line.
+    par: Option<(Paragraph, Line)>,
+}
+
+/// An area on a page that belongs to an element.
+struct Region {
+    min: Point,
+    max: Point,
+    kind: RegionKind,
+}
+
+impl Region {
+    fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+/// What to do with the content in a region.
+#[derive(Copy, Clone)]
+enum RegionKind {
+    /// The content belongs to a heading.
+    Heading(Location, NonZeroUsize),
+    /// The content was already handled through its element.
+    Skip,
+}
+
+/// The last line of a paragraph.
+#[derive(Copy, Clone)]
+struct Line {
+    /// The baseline.
+    y: Abs,
+    /// The font size of the line's first text.
+    size: Abs,
+    /// The end of the line's last text.
+    end: Abs,
+    /// The heading the line belongs to.
+    heading: Option<Location>,
+}
+
+impl Collector<'_> {
+    fn frame(&mut self, frame: &Frame, ts: Transform) {
+        for (pos, item) in frame.items() {
+            let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+            let origin = Point::zero().transform(ts);
+            match item {
+                FrameItem::Group(group) => {
+                    self.frame(&group.frame, ts.pre_concat(group.transform));
+                }
+                FrameItem::Meta(Meta::Elem(elem), size) => {
+                    self.elem(elem, origin, size.to_point().transform(ts));
+                }
+                FrameItem::Text(text) => self.text(text, origin),
+                FrameItem::Image(image, size, _) => self.image(image, *size, origin),
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle the start of an element's area.
+    fn elem(&mut self, elem: &Content, min: Point, max: Point) {
+        let Some(loc) = elem.location() else { return };
+        let kind = if let Some(heading) = elem.to_packed::<HeadingElem>() {
+            RegionKind::Heading(loc, heading.level(StyleChain::default()))
+        } else if let Some(table) = elem.to_packed::<TableElem>() {
+            if self.seen.insert(loc) {
+                self.finish_par();
+                self.output.blocks.push(Block::Table(Table {
+                    columns: table.columns(StyleChain::default()).0.len().max(1),
+                    cells: table
+                        .children()
+                        .iter()
+                        .map(|cell| {
+                            (
+                                cell.body().plain_text(),
+                                cell.colspan(StyleChain::default()),
+                            )
+                        })
+                        .collect(),
+                }));
+            }
+            RegionKind::Skip
+        } else if let Some(note) = elem.to_packed::<FootnoteElem>() {
+            if let Some(body) = note.body_content().filter(|_| self.seen.insert(loc)) {
+                self.output.footnotes.push(body.plain_text());
+                let index = self.output.footnotes.len() - 1;
+                let (par, _) = self.par.get_or_insert_with(|| {
+                    let line = Line {
+                        y: min.y,
+                        size: Abs::zero(),
+                        end: min.x,
+                        heading: None,
+                    };
+                    (Paragraph::default(), line)
+                });
+                par.runs.push(Run::Footnote(index));
+            }
+            RegionKind::Skip
+        } else if elem.is::<FootnoteEntry>() {
+            RegionKind::Skip
+        } else {
+            return;
+        };
+
+        self.regions.push(Region { min, max, kind });
+    }
+
+    /// Add text to the current or a new paragraph.
+    fn text(&mut self, text: &TextItem, pos: Point) {
+        self.output.lang.get_or_insert(text.lang);
+
+        // Probe slightly above the baseline, where the glyphs are.
+        let probe = Point::new(pos.x + text.size * 0.1, pos.y - text.size * 0.25);
+        let heading = match self.regions.iter().rev().find(|r| r.contains(probe)) {
+            Some(Region { kind: RegionKind::Skip, .. }) => return,
+            Some(Region { kind: RegionKind::Heading(loc, level), .. }) => {
+                Some((*loc, *level))
+            }
+            None => None,
+        };
+
+        let end = pos.x + text.width();
+        let mut separator = false;
+        let joined = match &mut self.par {
+            Some((_, line)) if line.heading != heading.map(|(loc, _)| loc) => false,
+            Some((_, line)) if (pos.y - line.y).abs() < text.size * 0.3 => {
+                separator = pos.x - line.end > text.size * 0.2;
+                line.end = line.end.max(end);
+                true
+            }
+            Some((_, line))
+                if pos.y > line.y
+                    && pos.y - line.y < text.size.max(line.size) * 1.8
+                    && (text.size - line.size).abs() < Abs::pt(0.5) =>
+            {
+                separator = true;
+                *line = Line {
+                    y: pos.y,
+                    size: text.size,
+                    end,
+                    heading: line.heading,
+                };
+                true
+            }
+            _ => false,
+        };
+
+        if !joined {
+            self.finish_par();
+            let line = Line {
+                y: pos.y,
+                size: text.size,
+                end,
+                heading: heading.map(|(loc, _)| loc),
+            };
+            let par = Paragraph {
+                heading: heading.map(|(_, level)| level),
+                runs: vec![],
+            };
+            self.par = Some((par, line));
+        }
+
+        let Some((par, _)) = &mut self.par else { return };
+        let style = RunStyle::new(text);
+        let mut content = EcoString::new();
+        if separator
+            && !text.text.starts_with(char::is_whitespace)
+            && !ends_with_whitespace(&par.runs)
+        {
+            content.push(' ');
+        }
+        content.push_str(&text.text);
+
+        match par.runs.last_mut() {
+            Some(Run::Text(prev, prev_style)) if *prev_style == style => {
+                prev.push_str(&content);
+            }
+            _ => par.runs.push(Run::Text(content, style)),
+        }
+    }
+
+    /// Add an image as a paragraph of its own.
+    fn image(&mut self, image: &Image, size: Size, pos: Point) {
+        if !(self.embed)(image) || self.regions.iter().any(|region| region.contains(pos))
+        {
+            return;
+        }
+
+        let images = &mut self.output.images;
+        let index = images.iter().position(|other| other == image).unwrap_or_else(|| {
+            images.push(image.clone());
+            images.len() - 1
+        });
+
+        self.finish_par();
+        self.output.blocks.push(Block::Paragraph(Paragraph {
+            heading: None,
+            runs: vec![Run::Image(index, size)],
+        }));
+    }
+
+    /// Finish the current paragraph.
+    fn finish_par(&mut self) {
+        if let Some((par, _)) = self.par.take() {
+            if !par.runs.is_empty() {
+                self.output.blocks.push(Block::Paragraph(par));
+            }
+        }
+    }
+}
+
+/// Whether the runs end with whitespace.
+fn ends_with_whitespace(runs: &[Run]) -> bool {
+    match runs.last() {
+        Some(Run::Text(text, _)) => text.ends_with(char::is_whitespace),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::eval::Tracer;
+    use typst::foundations::Bytes;
+    use typst::text::Font;
+    use typst::MemoryWorld;
+
+    use super::*;
+
+    fn collect_text(text: &str) -> Collected {
+        let fonts = [
+            &include_bytes!("../../../assets/fonts/LinLibertine_R.ttf")[..],
+            &include_bytes!("../../../assets/fonts/LinLibertine_RB.ttf")[..],
+        ];
+        let world = MemoryWorld::new(text).with_fonts(
+            fonts.map(|data| Font::new(Bytes::from_static(data), 0).unwrap()),
+        );
+        let document = typst::compile(&world, &mut Tracer::new()).unwrap();
+        collect(&document, &|_| true)
+    }
+
+    fn paragraph(block: &Block) -> &Paragraph {
+        match block {
+            Block::Paragraph(par) => par,
+            Block::Table(_) => panic!("expected a paragraph"),
+        }
+    }
+
+    fn plain(par: &Paragraph) -> String {
+        par.runs
+            .iter()
+            .filter_map(|run| match run {
+                Run::Text(text, _) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_paragraphs() {
+        let collected = collect_text("= Title\nSome *bold*\ntext.\n\nNext.");
+        let pars: Vec<_> = collected
+            .blocks
+            .iter()
+            .map(paragraph)
+            .map(|par| (par.heading.map(NonZeroUsize::get), plain(par)))
+            .collect();
+        assert_eq!(
+            pars,
+            [
+                (Some(1), "Title".into()),
+                (None, "Some bold text.".into()),
+                (None, "Next.".into()),
+            ]
+        );
+
+        let runs = &paragraph(&collected.blocks[1]).runs;
+        assert!(runs.iter().any(|run| {
+            matches!(run, Run::Text(text, style) if text == "bold" && style.bold)
+        }));
+        assert_eq!(collected.lang, Some(Lang::ENGLISH));
+    }
+
+    #[test]
+    fn test_collect_tables_and_footnotes() {
+        let collected = collect_text(
+            "Text#footnote[Note]\n\n\
+             #table(columns: 2, table.cell(colspan: 2)[A], [B], [C])",
+        );
+        assert_eq!(collected.blocks.len(), 2);
+        assert_eq!(collected.footnotes, [EcoString::from("Note")]);
+
+        // The marker is replaced by a reference and the entry at the bottom
+        // of the page is left out.
+        let par = paragraph(&collected.blocks[0]);
+        assert_eq!(plain(par), "Text");
+        assert!(matches!(par.runs.last(), Some(Run::Footnote(0))));
+
+        let Block::Table(table) = &collected.blocks[1] else {
+            panic!("expected a table");
+        };
+        let cells: Vec<_> = table
+            .cells
+            .iter()
+            .map(|(text, span)| (text.as_str(), span.get()))
+            .collect();
+        assert_eq!(table.columns, 2);
+        assert_eq!(cells, [("A", 2), ("B", 1), ("C", 1)]);
+    }
+}
@@ -3,7 +3,7 @@
 //! The AST is rooted in the [`Markup`] node.
 
 use std::num::NonZeroUsize;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 
 use ecow::EcoString;
 use unscanny::Scanner;
@@ -157,7 +157,7 @@ pub enum Expr<'a> {
     Float(Float<'a>),
     /// A numeric value with a unit: `12pt`, `3cm`, `2em`, `90deg`, `50%`.
     Numeric(Numeric<'a>),
-    /// A quoted string: `"..."`.
+    /// A quoted string: `"..."` or `r"..."`.
     Str(Str<'a>),
     /// A code block: `{ let x = 1; x + 2 }`.
     Code(CodeBlock<'a>),
@@ -631,6 +631,19 @@ impl<'a> Raw<'a> {
         let text = self.0.text();
         text.starts_with("```") && text.chars().any(is_newline)
     }
+
+    /// The byte range of the raw text within the node's text, without the
+    /// backticks and the language tag.
+    ///
+    /// Unlike [`text`](Self::text), the range includes the indentation and
+    /// the whitespace around the text, so that it maps directly back to the
+    /// source.
+    pub fn text_range(self) -> Range<usize> {
+        let text = self.0.text();
+        let ticks = (text.len() - text.trim_start_matches('`').len()).min(text.len() / 2);
+        let start = ticks + self.lang().map_or(0, str::len);
+        start..(text.len() - ticks).max(start)
+    }
 }
 
 node! {
@@ -1065,14 +1078,21 @@ pub enum Unit {
 }
 
 node! {
-    /// A quoted string: `"..."`.
+    /// A quoted string: `"..."` or `r"..."`.
     Str
 }
 
 impl Str<'_> {
     /// Get the string value with resolved escape sequences.
+    ///
+    /// The contents of raw strings are returned as is.
     pub fn get(self) -> EcoString {
         let text = self.0.text();
+        if let Some(raw) = text.strip_prefix('r') {
+            let hashes = raw.len() - raw.trim_start_matches('#').len();
+            return raw[hashes + 1..raw.len() - hashes - 1].into();
+        }
+
         let unquoted = &text[1..text.len() - 1];
         if !unquoted.contains('\\') {
             return unquoted.into();
@@ -1261,6 +1281,14 @@ impl<'a> Named<'a> {
     pub fn expr_ident(self) -> Option<Ident<'a>> {
         self.0.cast_last_match()
     }
+
+    /// The right-hand side of the pair as a pattern.
+    ///
+    /// This should only be called if the pair is part of a destructuring
+    /// pattern.
+    pub fn pattern(self) -> Pattern<'a> {
+        self.0.cast_last_match().unwrap_or_default()
+    }
 }
 
 node! {
@@ -1646,6 +1674,33 @@ impl<'a> Params<'a> {
     pub fn children(self) -> impl DoubleEndedIterator<Item = Param<'a>> {
         self.0.children().filter_map(SyntaxNode::cast)
     }
+
+    /// The parameter bindings along with their type annotations.
+    pub fn annotated(self) -> Vec<(Param<'a>, Option<TypeAnnotation<'a>>)> {
+        let mut params = vec![];
+        for node in self.0.children() {
+            if let Some(param) = node.cast::<Param>() {
+                params.push((param, None));
+            } else if let Some(annotation) = node.cast::<TypeAnnotation>() {
+                if let Some((_, slot)) = params.last_mut() {
+                    *slot = Some(annotation);
+                }
+            }
+        }
+        params
+    }
+}
+
+node! {
+    /// A type annotation of a closure parameter: `as int`.
+    TypeAnnotation
+}
+
+impl<'a> TypeAnnotation<'a> {
+    /// The expected type: `int`.
+    pub fn ty(self) -> Expr<'a> {
+        self.0.cast_last_match().unwrap_or_default()
+    }
 }
 
 node! {
@@ -1710,14 +1765,18 @@ impl<'a> Destructuring<'a> {
         self.0.children().filter_map(SyntaxNode::cast)
     }
 
-    /// Returns a list of all identifiers in the pattern.
-    pub fn idents(self) -> impl DoubleEndedIterator<Item = Ident<'a>> {
-        self.bindings().filter_map(|binding| match binding {
-            DestructuringKind::Normal(Expr::Ident(ident)) => Some(ident),
-            DestructuringKind::Sink(spread) => spread.name(),
-            DestructuringKind::Named(named) => named.expr_ident(),
-            _ => Option::None,
-        })
+    /// Returns a list of all identifiers in the pattern, including those in
+    /// nested patterns.
+    pub fn idents(self) -> Vec<Ident<'a>> {
+        self.bindings()
+            .flat_map(|binding| match binding {
+                DestructuringKind::Normal(Expr::Ident(ident)) => vec![ident],
+                DestructuringKind::Sink(spread) => spread.name().into_iter().collect(),
+                DestructuringKind::Named(named) => named.pattern().idents(),
+                DestructuringKind::Nested(nested) => nested.idents(),
+                _ => vec![],
+            })
+            .collect()
     }
 }
 
@@ -1732,6 +1791,8 @@ pub enum DestructuringKind<'a> {
     Named(Named<'a>),
     /// A placeholder: `_`.
     Placeholder(Underscore<'a>),
+    /// A nested destructuring pattern: `(x, y)`.
+    Nested(Destructuring<'a>),
 }
 
 impl<'a> AstNode<'a> for DestructuringKind<'a> {
@@ -1740,6 +1801,7 @@ impl<'a> AstNode<'a> for DestructuringKind<'a> {
             SyntaxKind::Named => node.cast().map(Self::Named),
             SyntaxKind::Spread => node.cast().map(Self::Sink),
             SyntaxKind::Underscore => node.cast().map(Self::Placeholder),
+            SyntaxKind::Destructuring => node.cast().map(Self::Nested),
             _ => node.cast().map(Self::Normal),
         }
     }
@@ -1750,6 +1812,7 @@ impl<'a> AstNode<'a> for DestructuringKind<'a> {
             Self::Named(v) => v.to_untyped(),
             Self::Sink(v) => v.to_untyped(),
             Self::Placeholder(v) => v.to_untyped(),
+            Self::Nested(v) => v.to_untyped(),
         }
     }
 }
@@ -1788,7 +1851,7 @@ impl<'a> Pattern<'a> {
     pub fn idents(self) -> Vec<Ident<'a>> {
         match self {
             Pattern::Normal(Expr::Ident(ident)) => vec![ident],
-            Pattern::Destructuring(destruct) => destruct.idents().collect(),
+            Pattern::Destructuring(destruct) => destruct.idents(),
             _ => vec![],
         }
     }
@@ -0,0 +1,498 @@
+//! Formatting of source code.
+//!
+//! The formatter prints a syntax tree back to source code with canonical
+//! spacing and indentation in code. Markup is preserved as written, apart from
+//! trailing whitespace, since whitespace in it is significant.
+
+use crate::{SyntaxKind, SyntaxNode};
+
+/// Configures how source code is formatted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Config {
+    /// The maximum width of a line, in characters. Argument lists, arrays,
+    /// dictionaries, parameter lists, and destructuring patterns that do not
+    /// fit into a line are broken into one item per line.
+    pub max_width: usize,
+    /// The number of spaces by which nested code is indented.
+    pub indent: usize,
+    /// Whether the last item of a collection that is broken across lines gets
+    /// a trailing comma.
+    pub trailing_comma: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { max_width: 80, indent: 2, trailing_comma: true }
+    }
+}
+
+/// Format a syntax tree as source code.
+///
+/// In code, spaces are normalized, multi-line code blocks get one statement
+/// per line, and collections are broken into multiple lines if they are too
+/// long. Comments are preserved. Trees with syntax errors are returned
+/// unchanged, as their structure cannot be trusted.
+pub fn format(root: &SyntaxNode, config: &Config) -> String {
+    if root.erroneous() {
+        return root.clone().into_text().into();
+    }
+
+    let mut p = Printer::new(config, false);
+    if root.kind() == SyntaxKind::Code {
+        p.stmts(root.children(), false);
+        if !p.out.is_empty() {
+            p.out.push('\n');
+        }
+    } else {
+        p.node(root, false);
+    }
+
+    p.out
+}
+
+/// Prints syntax nodes into a string.
+struct Printer<'a> {
+    /// The configuration.
+    config: &'a Config,
+    /// The source code printed so far.
+    out: String,
+    /// The indentation of the current code, in spaces.
+    indent: usize,
+    /// Whether to keep all collections on a single line. This is used to
+    /// measure how long a collection is when it is not broken.
+    flat: bool,
+}
+
+/// An entry of a collection.
+enum Entry<'a> {
+    /// An item, which may consist of an expression and a type annotation.
+    Item(Vec<&'a SyntaxNode>),
+    /// A comment and whether it is on the same line as the preceding item.
+    Comment(&'a SyntaxNode, bool),
+}
+
+impl<'a> Printer<'a> {
+    /// Create a new printer.
+    fn new(config: &'a Config, flat: bool) -> Self {
+        Self { config, out: String::new(), indent: 0, flat }
+    }
+
+    /// Print a node in code or markup.
+    fn node(&mut self, node: &SyntaxNode, code: bool) {
+        match node.kind() {
+            SyntaxKind::Markup => self.generic(node, false),
+            SyntaxKind::CodeBlock => self.code_block(node),
+            SyntaxKind::Args
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Params
+            | SyntaxKind::Destructuring => self.collection(node),
+            SyntaxKind::Binary | SyntaxKind::LetBinding => self.spaced(node, |_, _| " "),
+            SyntaxKind::Named | SyntaxKind::Keyed | SyntaxKind::ShowRule => self
+                .spaced(node, |_, next| if next == SyntaxKind::Colon { "" } else { " " }),
+            SyntaxKind::Closure => self.spaced(node, |prev, next| {
+                if prev == SyntaxKind::Ident && next == SyntaxKind::Params {
+                    ""
+                } else {
+                    " "
+                }
+            }),
+            SyntaxKind::Raw | SyntaxKind::Equation => node.write_text(&mut self.out),
+            _ if node.children().len() == 0 => self.out.push_str(node.text()),
+            _ => self.generic(node, code),
+        }
+    }
+
+    /// Print the children of a node, only normalizing spaces.
+    fn generic(&mut self, node: &SyntaxNode, code: bool) {
+        let children: Vec<_> = node.children().collect();
+        for (i, child) in children.iter().enumerate() {
+            match child.kind() {
+                SyntaxKind::Space | SyntaxKind::Parbreak => {
+                    let next = children[i + 1..]
+                        .iter()
+                        .map(|child| child.kind())
+                        .find(|kind| !kind.is_trivia());
+                    self.space(child.text(), code, next);
+                }
+                // The expression after a hash in markup is code.
+                _ if !code && i > 0 && children[i - 1].kind() == SyntaxKind::Hash => {
+                    let outer = self.indent;
+                    self.indent = self.line_indent();
+                    self.node(child, true);
+                    self.indent = outer;
+                }
+                _ => self.node(child, code),
+            }
+        }
+    }
+
+    /// Print whitespace.
+    fn space(&mut self, text: &str, code: bool, next: Option<SyntaxKind>) {
+        // In markup, only trailing whitespace is removed.
+        if !code {
+            let mut lines = text.split('\n').peekable();
+            while let Some(line) = lines.next() {
+                if lines.peek().is_some() {
+                    self.out.push_str(line.trim_end());
+                    self.out.push('\n');
+                } else {
+                    self.out.push_str(line);
+                }
+            }
+            return;
+        }
+
+        let newlines = text.chars().filter(|&c| c == '\n').count();
+        if newlines == 0 {
+            self.out.push(' ');
+            return;
+        }
+
+        // An `else` on a new line is aligned with its `if`, while other
+        // continued expressions are indented.
+        let outer = self.indent;
+        if next != Some(SyntaxKind::Else) {
+            self.indent += self.config.indent;
+        }
+        self.newline(newlines.min(2));
+        self.indent = outer;
+    }
+
+    /// Print the non-trivia children of a node with the given separators
+    /// between them.
+    fn spaced(
+        &mut self,
+        node: &SyntaxNode,
+        separator: impl Fn(SyntaxKind, SyntaxKind) -> &'static str,
+    ) {
+        if node.children().any(is_comment) {
+            self.generic(node, true);
+            return;
+        }
+
+        let mut prev = None;
+        for child in node.children().filter(|child| !child.kind().is_trivia()) {
+            if let Some(prev) = prev {
+                self.out.push_str(separator(prev, child.kind()));
+            }
+            self.node(child, true);
+            prev = Some(child.kind());
+        }
+    }
+
+    /// Print a code block, with one statement per line if it spans multiple
+    /// lines.
+    fn code_block(&mut self, node: &SyntaxNode) {
+        if !is_multiline(node) {
+            self.generic(node, true);
+            return;
+        }
+
+        let inner = node
+            .children()
+            .filter(|child| {
+                !matches!(child.kind(), SyntaxKind::LeftBrace | SyntaxKind::RightBrace)
+            })
+            .flat_map(|child| match child.kind() {
+                SyntaxKind::Code => child.children().collect(),
+                _ => vec![child],
+            });
+
+        self.out.push('{');
+        self.stmts(inner, true);
+        self.newline(1);
+        self.out.push('}');
+    }
+
+    /// Print a sequence of statements, each on its own line.
+    fn stmts<'b>(
+        &mut self,
+        children: impl IntoIterator<Item = &'b SyntaxNode>,
+        nested: bool,
+    ) {
+        let outer = self.indent;
+        if nested {
+            self.indent += self.config.indent;
+        }
+
+        let mut newlines = 0;
+        let mut first = true;
+        for child in children {
+            match child.kind() {
+                SyntaxKind::Space | SyntaxKind::Parbreak => {
+                    newlines += child.text().chars().filter(|&c| c == '\n').count();
+                }
+                SyntaxKind::Semicolon => self.out.push(';'),
+                _ => {
+                    if first {
+                        if nested {
+                            self.newline(1);
+                        }
+                    } else if newlines > 0 {
+                        self.newline(newlines.min(2));
+                    } else {
+                        self.out.push(' ');
+                    }
+
+                    self.node(child, true);
+                    newlines = 0;
+                    first = false;
+                }
+            }
+        }
+
+        self.indent = outer;
+    }
+
+    /// Print a parenthesized collection, on one line if it fits and with one
+    /// item per line otherwise.
+    fn collection(&mut self, node: &SyntaxNode) {
+        let children: Vec<_> = node.children().collect();
+        let open = children
+            .iter()
+            .position(|child| child.kind() == SyntaxKind::LeftParen);
+        let close = children
+            .iter()
+            .position(|child| child.kind() == SyntaxKind::RightParen);
+        let (Some(open), Some(close)) = (open, close) else {
+            self.generic(node, true);
+            return;
+        };
+
+        let Some((entries, colon)) = entries(&children[open + 1..close]) else {
+            self.generic(node, true);
+            return;
+        };
+
+        for child in &children[..open] {
+            self.node(child, true);
+        }
+
+        // A single item in an array or destructuring pattern needs a trailing
+        // comma to not be parenthesized.
+        let items: Vec<_> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Item(item) => Some(item),
+                Entry::Comment(..) => None,
+            })
+            .collect();
+        let single = matches!(node.kind(), SyntaxKind::Array | SyntaxKind::Destructuring)
+            && items.len() == 1
+            && items[0].first().map(|node| node.kind()) != Some(SyntaxKind::Spread);
+
+        let opening = if colon { "(:" } else { "(" };
+        let commented = entries.iter().any(|entry| matches!(entry, Entry::Comment(..)));
+        if !commented {
+            let mut p = Printer::new(self.config, true);
+            p.flat(&entries, opening, single);
+            let fits = !p.out.contains('\n')
+                && self.column() + p.out.chars().count() <= self.config.max_width;
+            if self.flat || fits {
+                self.out.push_str(&p.out);
+                for child in &children[close + 1..] {
+                    self.node(child, true);
+                }
+                return;
+            }
+        }
+
+        let last = entries.iter().rposition(|entry| matches!(entry, Entry::Item(_)));
+        self.out.push_str(opening);
+        self.indent += self.config.indent;
+        for (i, entry) in entries.iter().enumerate() {
+            match entry {
+                Entry::Item(item) => {
+                    self.newline(1);
+                    self.item(item);
+                    if Some(i) != last || single || self.config.trailing_comma {
+                        self.out.push(',');
+                    }
+                }
+                Entry::Comment(comment, true) => {
+                    self.out.push(' ');
+                    self.out.push_str(comment.text());
+                }
+                Entry::Comment(comment, false) => {
+                    self.newline(1);
+                    self.out.push_str(comment.text());
+                }
+            }
+        }
+        self.indent -= self.config.indent;
+        self.newline(1);
+        self.out.push(')');
+
+        for child in &children[close + 1..] {
+            self.node(child, true);
+        }
+    }
+
+    /// Print the entries of a collection on a single line.
+    fn flat(&mut self, entries: &[Entry], opening: &str, single: bool) {
+        self.out.push_str(opening);
+        for (i, entry) in entries.iter().enumerate() {
+            if let Entry::Item(item) = entry {
+                if i > 0 {
+                    self.out.push_str(", ");
+                }
+                self.item(item);
+            }
+        }
+        if single {
+            self.out.push(',');
+        }
+        self.out.push(')');
+    }
+
+    /// Print an item of a collection.
+    fn item(&mut self, item: &[&SyntaxNode]) {
+        for (i, node) in item.iter().enumerate() {
+            if i > 0 {
+                self.out.push(' ');
+            }
+            self.node(node, true);
+        }
+    }
+
+    /// Start new lines at the current indentation.
+    fn newline(&mut self, count: usize) {
+        self.out.push_str(&"\n".repeat(count));
+        self.out.push_str(&" ".repeat(self.indent));
+    }
+
+    /// The current line.
+    fn line(&self) -> &str {
+        let start = self.out.rfind('\n').map_or(0, |i| i + 1);
+        &self.out[start..]
+    }
+
+    /// The column at which the next character is printed.
+    fn column(&self) -> usize {
+        self.line().chars().count()
+    }
+
+    /// The indentation of the current line.
+    fn line_indent(&self) -> usize {
+        self.line().chars().take_while(|&c| c == ' ').count()
+    }
+}
+
+/// Split the children of a collection between its parentheses into entries.
+///
+/// Also returns whether the collection starts with a colon, like in an empty
+/// dictionary. Returns `None` if a comment is between the parts of an item, so
+/// that the collection is printed as it is.
+fn entries<'a>(children: &[&'a SyntaxNode]) -> Option<(Vec<Entry<'a>>, bool)> {
+    let mut entries = vec![];
+    let mut item = vec![];
+    let mut colon = false;
+    let mut newline = true;
+    for &child in children {
+        match child.kind() {
+            SyntaxKind::Space => newline |= child.text().contains('\n'),
+            SyntaxKind::Colon if entries.is_empty() && item.is_empty() => colon = true,
+            SyntaxKind::Comma => {
+                if !item.is_empty() {
+                    entries.push(Entry::Item(std::mem::take(&mut item)));
+                }
+                newline = false;
+            }
+            SyntaxKind::LineComment | SyntaxKind::BlockComment => {
+                if !item.is_empty() {
+                    return None;
+                }
+                let trailing = !newline && !entries.is_empty();
+                entries.push(Entry::Comment(child, trailing));
+                newline = child.kind() == SyntaxKind::LineComment;
+            }
+            _ => {
+                item.push(child);
+                newline = false;
+            }
+        }
+    }
+
+    if !item.is_empty() {
+        entries.push(Entry::Item(item));
+    }
+
+    Some((entries, colon))
+}
+
+/// Whether a node is a comment.
+fn is_comment(node: &SyntaxNode) -> bool {
+    matches!(node.kind(), SyntaxKind::LineComment | SyntaxKind::BlockComment)
+}
+
+/// Whether a node spans multiple lines.
+fn is_multiline(node: &SyntaxNode) -> bool {
+    node.text().contains('\n') || node.children().any(is_multiline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_code};
+
+    #[track_caller]
+    fn test(text: &str, expected: &str) {
+        test_with(text, &Config::default(), expected);
+    }
+
+    #[track_caller]
+    fn test_with(text: &str, config: &Config, expected: &str) {
+        let formatted = format(&parse(text), config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&parse(&formatted), config), expected, "not idempotent");
+    }
+
+    #[test]
+    fn test_format_code() {
+        test("#f(a,b,  c)", "#f(a, b, c)");
+        test("#f(a:1)[b]", "#f(a: 1)[b]");
+        test("#(a:1,b:2)", "#(a: 1, b: 2)");
+        test("#let x = (1 ,)", "#let x = (1,)");
+        test("#let f(x)=x+1", "#let f(x) = x + 1");
+        test("#show heading:it=>it.body", "#show heading: it => it.body");
+        test("#{\nlet x=1\n    x+1}", "#{\n  let x = 1\n  x + 1\n}");
+        test("#(:)", "#(:)");
+    }
+
+    #[test]
+    fn test_format_breaking() {
+        let config = Config { max_width: 20, ..Config::default() };
+        test_with(
+            "#f(alpha, beta, gamma, delta)",
+            &config,
+            "#f(\n  alpha,\n  beta,\n  gamma,\n  delta,\n)",
+        );
+
+        let config = Config { trailing_comma: false, ..config };
+        test_with(
+            "#let (alpha, beta, gamma) = x",
+            &config,
+            "#let (\n  alpha,\n  beta,\n  gamma\n) = x",
+        );
+    }
+
+    #[test]
+    fn test_format_comments() {
+        test("#f(\n  a, // first\n  b,\n)", "#f(\n  a, // first\n  b,\n)");
+        test("#{\n  // Note.\n  x /* y */\n}", "#{\n  // Note.\n  x /* y */\n}");
+    }
+
+    #[test]
+    fn test_format_markup() {
+        test("Hello   \nWorld", "Hello\nWorld");
+        test("- *a*  b", "- *a*  b");
+        test("#f(", "#f(");
+    }
+
+    #[test]
+    fn test_format_code_file() {
+        let config = Config::default();
+        assert_eq!(format(&parse_code("let x=1;x"), &config), "let x = 1; x\n");
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{ast, LinkedNode, SyntaxKind, SyntaxNode};
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use crate::{ast, LinkedNode, Span, SyntaxKind, SyntaxNode};
 
 /// A syntax highlighting tag.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -264,6 +268,7 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Spread => None,
         SyntaxKind::Closure => None,
         SyntaxKind::Params => None,
+        SyntaxKind::TypeAnnotation => None,
         SyntaxKind::LetBinding => None,
         SyntaxKind::SetRule => None,
         SyntaxKind::ShowRule => None,
@@ -402,10 +407,151 @@ fn highlight_html_impl(html: &mut String, node: &LinkedNode) {
     }
 }
 
+/// A classified range of source text.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SemanticToken {
+    /// The byte range of the token in the source text.
+    pub range: Range<usize>,
+    /// How the token should be highlighted.
+    pub kind: SemanticKind,
+}
+
+/// The classification of a [`SemanticToken`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SemanticKind {
+    /// A token that is highlighted with a tag.
+    Tag(Tag),
+    /// An identifier that refers to a variable.
+    Variable,
+    /// The text of a raw block in the given language, which should be
+    /// highlighted with that language's grammar.
+    Embedded(EcoString),
+}
+
+/// Classify the tokens of a syntax tree for semantic highlighting.
+///
+/// Unlike [`highlight`], this returns non-overlapping tokens in source order:
+/// Each leaf is classified by its own tag or, if it has none, by the tag of
+/// its closest highlighted ancestor. Leaves that should not be highlighted
+/// and whitespace are left out.
+pub fn semantic_tokens(root: &SyntaxNode) -> Vec<SemanticToken> {
+    let mut tokens = vec![];
+    semantic_tokens_impl(&mut tokens, &LinkedNode::new(root), None);
+    tokens
+}
+
+/// Classify the leaves of one source node.
+fn semantic_tokens_impl(
+    tokens: &mut Vec<SemanticToken>,
+    node: &LinkedNode,
+    outer: Option<Tag>,
+) {
+    let own = highlight(node);
+    let tag = own.or(outer);
+    if node.children().len() > 0 {
+        for child in node.children() {
+            semantic_tokens_impl(tokens, &child, tag);
+        }
+        return;
+    }
+
+    let range = node.range();
+    if range.is_empty() || node.kind() == SyntaxKind::Space {
+        return;
+    }
+
+    if let Some(raw) = node.cast::<ast::Raw>() {
+        if let Some(lang) = raw.lang() {
+            let inner = raw.text_range();
+            let start = range.start + inner.start;
+            let end = range.start + inner.end;
+            if start < end {
+                let kind = SemanticKind::Tag(Tag::Raw);
+                tokens.push(SemanticToken {
+                    range: range.start..start,
+                    kind: kind.clone(),
+                });
+                tokens.push(SemanticToken {
+                    range: start..end,
+                    kind: SemanticKind::Embedded(lang.into()),
+                });
+                tokens.push(SemanticToken { range: end..range.end, kind });
+                return;
+            }
+        }
+    }
+
+    let kind = if own.is_none() && is_variable(node) {
+        SemanticKind::Variable
+    } else if let Some(tag) = tag {
+        SemanticKind::Tag(tag)
+    } else {
+        return;
+    };
+
+    tokens.push(SemanticToken { range, kind });
+}
+
+/// A raw block whose text is written in another language.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Injection {
+    /// The language tag of the raw block, like `rust`.
+    pub lang: EcoString,
+    /// The span of the raw block.
+    pub span: Span,
+    /// The byte range of the block's text in the source text, without the
+    /// backticks and the language tag.
+    pub range: Range<usize>,
+}
+
+/// Find the non-empty raw blocks with a language tag in a syntax tree.
+///
+/// Editors can use these to highlight or format the text of the blocks with
+/// the tools for the respective language. The blocks are returned in source
+/// order.
+pub fn injections(root: &SyntaxNode) -> Vec<Injection> {
+    let mut injections = vec![];
+    injections_impl(&mut injections, &LinkedNode::new(root));
+    injections
+}
+
+/// Find the raw blocks with a language tag in one source node.
+fn injections_impl(injections: &mut Vec<Injection>, node: &LinkedNode) {
+    if let Some(raw) = node.cast::<ast::Raw>() {
+        let inner = raw.text_range();
+        if let Some(lang) = raw.lang().filter(|_| !inner.is_empty()) {
+            let start = node.offset();
+            injections.push(Injection {
+                lang: lang.into(),
+                span: node.span(),
+                range: start + inner.start..start + inner.end,
+            });
+        }
+        return;
+    }
+
+    for child in node.children() {
+        injections_impl(injections, &child);
+    }
+}
+
+/// Whether the node is an identifier in code that refers to a variable, as
+/// opposed to, for instance, a field name or the name of an argument.
+fn is_variable(node: &LinkedNode) -> bool {
+    if node.kind() != SyntaxKind::Ident {
+        return false;
+    }
+
+    match node.parent_kind() {
+        Some(SyntaxKind::FieldAccess) => node.prev_sibling().is_none(),
+        Some(SyntaxKind::Named) => node.next_sibling_kind() != Some(SyntaxKind::Colon),
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ops::Range;
 
     #[test]
     fn test_highlighting() {
@@ -454,5 +600,82 @@ mod tests {
                 (10..11, Operator),
             ],
         );
+
+        test(
+            r##"#f(r#"\d"#)"##,
+            &[
+                (0..1, Function),
+                (1..2, Function),
+                (2..3, Punctuation),
+                (3..10, String),
+                (10..11, Punctuation),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        use SemanticKind::{Embedded, Tag as T, Variable};
+        use Tag::*;
+
+        #[track_caller]
+        fn test(text: &str, goal: &[(Range<usize>, SemanticKind)]) {
+            let root = crate::parse(text);
+            let tokens: Vec<_> = semantic_tokens(&root)
+                .into_iter()
+                .map(|token| (token.range, token.kind))
+                .collect();
+            assert_eq!(tokens, goal);
+        }
+
+        test("= A <a>", &[(0..1, T(Heading)), (2..3, T(Heading)), (4..7, T(Label))]);
+
+        test(
+            "#let x = y.z",
+            &[
+                (0..1, T(Keyword)),
+                (1..4, T(Keyword)),
+                (5..6, Variable),
+                (7..8, T(Operator)),
+                (9..10, Variable),
+                (10..11, T(Punctuation)),
+            ],
+        );
+
+        test(
+            "$a_1$",
+            &[
+                (0..1, T(MathDelimiter)),
+                (1..2, T(Interpolated)),
+                (2..3, T(MathOperator)),
+                (4..5, T(MathDelimiter)),
+            ],
+        );
+
+        test(
+            "```rs fn```",
+            &[(0..5, T(Raw)), (5..8, Embedded("rs".into())), (8..11, T(Raw))],
+        );
+    }
+
+    #[test]
+    fn test_injections() {
+        #[track_caller]
+        fn test(text: &str, goal: &[(&str, Range<usize>)]) {
+            let root = crate::parse(text);
+            let found: Vec<_> = injections(&root)
+                .into_iter()
+                .map(|injection| (injection.lang, injection.range))
+                .collect();
+            let goal: Vec<_> = goal
+                .iter()
+                .map(|(lang, range)| ((*lang).into(), range.clone()))
+                .collect();
+            assert_eq!(found, goal);
+        }
+
+        test("`rs` ```rs```", &[]);
+        test("A ```rs fn``` B", &[("rs", 7..10)]);
+        test("```py\n  x = 1\n```\n#[```js f()```]", &[("py", 5..14), ("js", 25..29)]);
     }
 }
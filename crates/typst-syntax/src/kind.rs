@@ -226,6 +226,8 @@ pub enum SyntaxKind {
     Closure,
     /// A closure's parameters: `(x, y)`.
     Params,
+    /// A type annotation of a closure parameter: `as int`.
+    TypeAnnotation,
     /// A let binding: `let x = 1`.
     LetBinding,
     /// A set rule: `set text(...)`.
@@ -459,6 +461,7 @@ impl SyntaxKind {
             Self::Spread => "spread",
             Self::Closure => "closure",
             Self::Params => "closure parameters",
+            Self::TypeAnnotation => "type annotation",
             Self::LetBinding => "`let` expression",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
@@ -4,7 +4,7 @@ use unicode_script::{Script, UnicodeScript};
 use unicode_segmentation::UnicodeSegmentation;
 use unscanny::Scanner;
 
-use crate::SyntaxKind;
+use crate::{SyntaxError, SyntaxKind};
 
 /// Splits up a string of source code into tokens.
 #[derive(Clone)]
@@ -17,7 +17,7 @@ pub(super) struct Lexer<'s> {
     /// Whether the last token contained a newline.
     newline: bool,
     /// An error for the last token.
-    error: Option<EcoString>,
+    error: Option<SyntaxError>,
 }
 
 /// What kind of tokens to emit.
@@ -70,7 +70,7 @@ impl<'s> Lexer<'s> {
     }
 
     /// Take out the last error, if any.
-    pub fn take_error(&mut self) -> Option<EcoString> {
+    pub fn take_error(&mut self) -> Option<SyntaxError> {
         self.error.take()
     }
 }
@@ -78,7 +78,17 @@ impl<'s> Lexer<'s> {
 impl Lexer<'_> {
     /// Construct a full-positioned syntax error.
     fn error(&mut self, message: impl Into<EcoString>) -> SyntaxKind {
-        self.error = Some(message.into());
+        self.error = Some(SyntaxError::new(message));
+        SyntaxKind::Error
+    }
+
+    /// Construct a full-positioned syntax error with a stable code.
+    fn error_with_code(
+        &mut self,
+        code: &'static str,
+        message: impl Into<EcoString>,
+    ) -> SyntaxKind {
+        self.error = Some(SyntaxError::new(message).with_code(code));
         SyntaxKind::Error
     }
 }
@@ -243,7 +253,7 @@ impl Lexer<'_> {
         }
 
         if found != backticks {
-            return self.error("unclosed raw text");
+            return self.error_with_code("E0004", "unclosed raw text");
         }
 
         SyntaxKind::Raw
@@ -453,6 +463,13 @@ impl Lexer<'_> {
             '0'..='9' => self.number(start, c),
             '.' if self.s.at(char::is_ascii_digit) => self.number(start, c),
             '"' => self.string(),
+            // Directly after a hash, `#r"..."` keeps meaning the variable `r`
+            // followed by quoted text, as it did before raw strings existed.
+            'r' if !self.s.get(0..start).ends_with('#')
+                && self.s.after().trim_start_matches('#').starts_with('"') =>
+            {
+                self.raw_string()
+            }
 
             '=' if self.s.eat_if('=') => SyntaxKind::EqEq,
             '!' if self.s.eat_if('=') => SyntaxKind::ExclEq,
@@ -593,7 +610,32 @@ impl Lexer<'_> {
         });
 
         if !self.s.eat_if('"') {
-            return self.error("unclosed string");
+            return self.error_with_code("E0003", "unclosed string");
+        }
+
+        SyntaxKind::Str
+    }
+
+    fn raw_string(&mut self) -> SyntaxKind {
+        let hashes = self.s.eat_while('#').len();
+        self.s.expect('"');
+
+        loop {
+            if self.s.done() {
+                return self.error_with_code("E0003", "unclosed raw string");
+            }
+
+            if self.s.eat_if('"') {
+                if self.s.after().starts_with(&"#".repeat(hashes)) {
+                    self.s.jump(self.s.cursor() + hashes);
+                    break;
+                }
+
+                // A quote followed by too few hashes is part of the string.
+                continue;
+            }
+
+            self.s.eat();
         }
 
         SyntaxKind::Str
@@ -762,3 +804,30 @@ fn is_math_id_start(c: char) -> bool {
 fn is_math_id_continue(c: char) -> bool {
     is_xid_continue(c) && c != '_'
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_string_in_code() {
+        let mut lexer = Lexer::new(r##"(r"\d", r#"a"b"#)"##, LexMode::Code);
+        assert_eq!(lexer.next(), SyntaxKind::LeftParen);
+        assert_eq!(lexer.next(), SyntaxKind::Str);
+        assert_eq!(lexer.next(), SyntaxKind::Comma);
+        assert_eq!(lexer.next(), SyntaxKind::Space);
+        assert_eq!(lexer.next(), SyntaxKind::Str);
+        assert_eq!(lexer.next(), SyntaxKind::RightParen);
+    }
+
+    #[test]
+    fn test_raw_string_after_hash() {
+        // The `r` is a variable and the quotes belong to the markup.
+        let mut lexer = Lexer::new(r#"#r"\d""#, LexMode::Markup);
+        assert_eq!(lexer.next(), SyntaxKind::Hash);
+        lexer.set_mode(LexMode::Code);
+        assert_eq!(lexer.next(), SyntaxKind::Ident);
+        lexer.set_mode(LexMode::Markup);
+        assert_eq!(lexer.next(), SyntaxKind::SmartQuote);
+    }
+}
@@ -1,6 +1,8 @@
 //! Parser and syntax tree for Typst.
 
 pub mod ast;
+pub mod fmt;
+pub mod visit;
 
 mod file;
 mod highlight;
@@ -13,7 +15,10 @@ mod source;
 mod span;
 
 pub use self::file::{FileId, PackageSpec, PackageVersion, VirtualPath};
-pub use self::highlight::{highlight, highlight_html, Tag};
+pub use self::highlight::{
+    highlight, highlight_html, injections, semantic_tokens, Injection, SemanticKind,
+    SemanticToken, Tag,
+};
 pub use self::kind::SyntaxKind;
 pub use self::lexer::{
     is_id_continue, is_id_start, is_ident, is_newline, link_prefix, split_newlines,
@@ -36,7 +36,12 @@ impl SyntaxNode {
 
     /// Create a new error node.
     pub fn error(message: impl Into<EcoString>, text: impl Into<EcoString>) -> Self {
-        Self(Repr::Error(Arc::new(ErrorNode::new(message, text))))
+        Self::from_error(SyntaxError::new(message), text)
+    }
+
+    /// Create a new error node from a detached error.
+    pub(super) fn from_error(error: SyntaxError, text: impl Into<EcoString>) -> Self {
+        Self(Repr::Error(Arc::new(ErrorNode::new(error, text))))
     }
 
     /// The type of the node.
@@ -83,6 +88,22 @@ impl SyntaxNode {
         }
     }
 
+    /// Write the text of the node and all of its descendants to a buffer.
+    ///
+    /// The tree retains all whitespace, comments, and erroneous text, so this
+    /// prints the exact source the node was parsed from.
+    pub fn write_text(&self, buf: &mut String) {
+        match &self.0 {
+            Repr::Leaf(leaf) => buf.push_str(&leaf.text),
+            Repr::Inner(inner) => {
+                for child in &inner.children {
+                    child.write_text(buf);
+                }
+            }
+            Repr::Error(node) => buf.push_str(&node.text),
+        }
+    }
+
     /// Extract the text from the node.
     ///
     /// Builds the string if this is an inner node.
@@ -156,6 +177,13 @@ impl SyntaxNode {
         }
     }
 
+    /// Set the stable code of the error if this is an error node.
+    pub(super) fn set_code(&mut self, code: &'static str) {
+        if let Repr::Error(node) = &mut self.0 {
+            Arc::make_mut(node).error.code = Some(code);
+        }
+    }
+
     /// Set a synthetic span for the node and all its descendants.
     pub fn synthesize(&mut self, span: Span) {
         match &mut self.0 {
@@ -395,6 +423,13 @@ impl InnerNode {
         }
     }
 
+    /// Set the stable code of the error if this is an error node.
+    pub(super) fn set_code(&mut self, code: &'static str) {
+        if let Repr::Error(node) = &mut self.0 {
+            Arc::make_mut(node).error.code = Some(code);
+        }
+    }
+
     /// Set a synthetic span for the node and all its descendants.
     fn synthesize(&mut self, span: Span) {
         self.span = span;
@@ -609,15 +644,8 @@ struct ErrorNode {
 
 impl ErrorNode {
     /// Create new error node.
-    fn new(message: impl Into<EcoString>, text: impl Into<EcoString>) -> Self {
-        Self {
-            text: text.into(),
-            error: SyntaxError {
-                span: Span::detached(),
-                message: message.into(),
-                hints: eco_vec![],
-            },
-        }
+    fn new(error: SyntaxError, text: impl Into<EcoString>) -> Self {
+        Self { text: text.into(), error }
     }
 
     /// The byte length of the node in the source text.
@@ -652,12 +680,32 @@ pub struct SyntaxError {
     /// Additional hints to the user, indicating how this error could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// The stable code of the error, like `E0002`, if it has one.
+    pub code: Option<&'static str>,
 }
 
 impl SyntaxError {
+    /// Create a new detached error.
+    pub(super) fn new(message: impl Into<EcoString>) -> Self {
+        Self {
+            span: Span::detached(),
+            message: message.into(),
+            hints: eco_vec![],
+            code: None,
+        }
+    }
+
+    /// Set the stable code of the error.
+    pub(super) fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
     /// Whether the two errors are the same apart from spans.
     fn spanless_eq(&self, other: &Self) -> bool {
-        self.message == other.message && self.hints == other.hints
+        self.message == other.message
+            && self.hints == other.hints
+            && self.code == other.code
     }
 }
 
@@ -986,4 +1034,41 @@ mod tests {
         assert_eq!(leaf.text(), " ");
         assert_eq!(next.text(), "10");
     }
+
+    #[test]
+    fn test_round_trip() {
+        #[track_caller]
+        fn test(text: &str) {
+            for root in
+                [crate::parse(text), crate::parse_code(text), crate::parse_math(text)]
+            {
+                assert_eq!(root.len(), text.len());
+                assert_eq!(root.clone().into_text(), text);
+
+                let mut buf = String::new();
+                root.write_text(&mut buf);
+                assert_eq!(buf, text);
+
+                // Every subtree prints as the source it spans.
+                let linked = LinkedNode::new(&root);
+                check(&linked, text);
+            }
+        }
+
+        fn check(node: &LinkedNode, text: &str) {
+            let mut buf = String::new();
+            node.write_text(&mut buf);
+            assert_eq!(buf, text[node.range()]);
+            for child in node.children() {
+                check(&child, text);
+            }
+        }
+
+        test("");
+        test("= Heading // comment\r\n\n  *strong* /* block */ _emph_\t");
+        test("#let f(x, ..y) = {\n  // doc\n  x + /* inline */ 1\n}");
+        test("$ a_1 ^ (b + c) \\ sum_(i=0)^n $ `raw` ```rs\n  fn main() {}\n```");
+        test("#f(1, \"unclosed\n#{ ) ] // trailing");
+        test("#r#\"raw \"string\"\"# \\ #(1 +) @ref <label>\u{a0}");
+    }
 }
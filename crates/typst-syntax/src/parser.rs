@@ -223,9 +223,10 @@ fn whitespace_line(p: &mut Parser) {
 
 fn equation(p: &mut Parser) {
     let m = p.marker();
+    let open = p.current_start();
     p.enter(LexMode::Math);
     p.assert(SyntaxKind::Dollar);
-    math(p, |p| p.at(SyntaxKind::Dollar));
+    delimited(p, open, SyntaxKind::Dollar, |p| math(p, |p| p.at(SyntaxKind::Dollar)));
     p.expect_closing_delimiter(m, SyntaxKind::Dollar);
     p.exit();
     p.wrap(m, SyntaxKind::Equation);
@@ -766,13 +767,16 @@ pub(super) fn reparse_block(text: &str, range: Range<usize>) -> Option<SyntaxNod
 
 fn code_block(p: &mut Parser) {
     let m = p.marker();
+    let open = p.current_start();
     p.enter(LexMode::Code);
     p.enter_newline_mode(NewlineMode::Continue);
     p.assert(SyntaxKind::LeftBrace);
-    code(p, |p| {
-        p.at(SyntaxKind::RightBrace)
-            || p.at(SyntaxKind::RightBracket)
-            || p.at(SyntaxKind::RightParen)
+    delimited(p, open, SyntaxKind::RightBrace, |p| {
+        code(p, |p| {
+            p.at(SyntaxKind::RightBrace)
+                || p.at(SyntaxKind::RightBracket)
+                || p.at(SyntaxKind::RightParen)
+        })
     });
     p.expect_closing_delimiter(m, SyntaxKind::RightBrace);
     p.exit();
@@ -782,14 +786,73 @@ fn code_block(p: &mut Parser) {
 
 fn content_block(p: &mut Parser) {
     let m = p.marker();
+    let open = p.current_start();
     p.enter(LexMode::Markup);
     p.assert(SyntaxKind::LeftBracket);
-    markup(p, true, 0, |p| p.at(SyntaxKind::RightBracket));
+    delimited(p, open, SyntaxKind::RightBracket, |p| {
+        markup(p, true, 0, |p| p.at(SyntaxKind::RightBracket))
+    });
     p.expect_closing_delimiter(m, SyntaxKind::RightBracket);
     p.exit();
     p.wrap(m, SyntaxKind::ContentBlock);
 }
 
+/// Parse the contents of a block whose opening delimiter is at `open`.
+///
+/// If the block isn't closed, it would extend over everything that follows
+/// and turn it into a cascade of bogus errors. Instead, the contents are then
+/// parsed again up to the block's likely end, as determined by
+/// [`block_boundary`].
+fn delimited(
+    p: &mut Parser,
+    open: usize,
+    close: SyntaxKind,
+    mut contents: impl FnMut(&mut Parser),
+) {
+    let checkpoint = p.checkpoint();
+    contents(p);
+    if p.at(close) {
+        return;
+    }
+
+    let Some(boundary) = block_boundary(p.text, open) else { return };
+    if boundary >= p.current_start() {
+        return;
+    }
+
+    p.restore(checkpoint);
+    let prev = p.boundary;
+    p.boundary = Some(prev.map_or(boundary, |prev| prev.min(boundary)));
+    contents(p);
+    p.boundary = prev;
+
+    // The current token was cut off at the boundary and may continue an
+    // enclosing block, so lex it again.
+    p.unskip();
+    p.lexer.jump(p.current_start);
+    p.lex();
+    p.skip();
+}
+
+/// Determine where an unclosed block that was opened at `open` likely ends:
+/// At the start of the first non-blank line after the opening one that isn't
+/// indented more than the opening one.
+fn block_boundary(text: &str, open: usize) -> Option<usize> {
+    let indent = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let line_start = text[..open].rfind(is_newline).map_or(0, |i| i + 1);
+    let max = indent(&text[line_start..]);
+
+    let mut offset = open + text[open..].find(is_newline)?;
+    for line in text[offset..].split_inclusive(is_newline) {
+        if !line.trim().is_empty() && indent(line) <= max {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
 fn with_paren(p: &mut Parser, allow_destructuring: bool) {
     let m = p.marker();
     let mut kind = collection(p, true);
@@ -835,6 +898,9 @@ fn invalidate_destructuring(p: &mut Parser, m: Marker) {
                 _ => collection_kind = Some(SyntaxKind::Dict),
             },
             SyntaxKind::LeftParen | SyntaxKind::RightParen | SyntaxKind::Comma => {}
+            SyntaxKind::TypeAnnotation => {
+                child.convert_to_error("type annotations are only allowed on parameters");
+            }
             kind => match collection_kind {
                 Some(SyntaxKind::Dict) => child.convert_to_error(eco_format!(
                     "expected named or keyed pair, found {}",
@@ -933,6 +999,7 @@ fn item(p: &mut Parser, keyed: bool) -> SyntaxKind {
     code_expr_or_pattern(p);
 
     if !p.eat_if(SyntaxKind::Colon) {
+        type_annotation(p);
         return SyntaxKind::Int;
     }
 
@@ -961,9 +1028,19 @@ fn item(p: &mut Parser, keyed: bool) -> SyntaxKind {
     };
 
     p.wrap(m, kind);
+    type_annotation(p);
     kind
 }
 
+fn type_annotation(p: &mut Parser) {
+    if p.at(SyntaxKind::As) {
+        let m = p.marker();
+        p.assert(SyntaxKind::As);
+        code_expr(p);
+        p.wrap(m, SyntaxKind::TypeAnnotation);
+    }
+}
+
 fn args(p: &mut Parser) {
     if !p.at(SyntaxKind::LeftParen) && !p.at(SyntaxKind::LeftBracket) {
         p.expected("argument list");
@@ -1193,6 +1270,9 @@ fn validate_parenthesized_at(p: &mut Parser, m: Marker) {
                     kind.name()
                 ));
             }
+            SyntaxKind::TypeAnnotation => {
+                child.convert_to_error("type annotations are only allowed on parameters");
+            }
             _ => {}
         }
     }
@@ -1214,6 +1294,9 @@ fn validate_array<'a>(children: impl Iterator<Item = &'a mut SyntaxNode>) {
                     kind.name()
                 ));
             }
+            SyntaxKind::TypeAnnotation => {
+                child.convert_to_error("type annotations are only allowed on parameters");
+            }
             _ => {}
         }
     }
@@ -1243,6 +1326,9 @@ fn validate_dict<'a>(children: impl Iterator<Item = &'a mut SyntaxNode>) {
                 }
             }
             SyntaxKind::Spread => {}
+            SyntaxKind::TypeAnnotation => {
+                child.convert_to_error("type annotations are only allowed on parameters");
+            }
             SyntaxKind::LeftParen
             | SyntaxKind::RightParen
             | SyntaxKind::Comma
@@ -1269,6 +1355,7 @@ fn validate_params_at(p: &mut Parser, m: Marker) {
                         "duplicate parameter: {}",
                         child.text()
                     ));
+                    child.set_code("E0005");
                 }
             }
             SyntaxKind::Named => {
@@ -1278,6 +1365,7 @@ fn validate_params_at(p: &mut Parser, m: Marker) {
                         "duplicate parameter: {}",
                         within.text()
                     ));
+                    within.set_code("E0005");
                     child.make_erroneous();
                 }
             }
@@ -1303,6 +1391,7 @@ fn validate_params_at(p: &mut Parser, m: Marker) {
                         "duplicate parameter: {}",
                         within.text()
                     ));
+                    within.set_code("E0005");
                     child.make_erroneous();
                 }
             }
@@ -1313,7 +1402,8 @@ fn validate_params_at(p: &mut Parser, m: Marker) {
             SyntaxKind::LeftParen
             | SyntaxKind::RightParen
             | SyntaxKind::Comma
-            | SyntaxKind::Underscore => {}
+            | SyntaxKind::Underscore
+            | SyntaxKind::TypeAnnotation => {}
             kind => {
                 child.convert_to_error(eco_format!(
                     "expected identifier, named pair or argument sink, found {}",
@@ -1334,10 +1424,13 @@ fn validate_args_at(p: &mut Parser, m: Marker) {
                     "duplicate argument: {}",
                     within.text()
                 ));
+                within.set_code("E0005");
                 child.make_erroneous();
             }
         } else if child.kind() == SyntaxKind::Underscore {
             child.convert_to_error("unexpected underscore");
+        } else if child.kind() == SyntaxKind::TypeAnnotation {
+            child.convert_to_error("type annotations are only allowed on parameters");
         }
     }
 }
@@ -1397,19 +1490,31 @@ fn validate_pattern<'a>(
                     child.make_erroneous();
                 }
 
-                if forbid_expressions {
-                    let Some(within) = child.children_mut().last_mut() else { return };
-                    if within.kind() != SyntaxKind::Ident
-                        && within.kind() != SyntaxKind::Underscore
-                    {
+                let Some(within) = child.children_mut().last_mut() else { return };
+                match within.kind() {
+                    SyntaxKind::Array | SyntaxKind::Dict | SyntaxKind::Destructuring => {
+                        validate_nested_pattern(within, used, forbid_expressions);
+                        if within.erroneous() {
+                            child.make_erroneous();
+                        }
+                    }
+                    SyntaxKind::Ident | SyntaxKind::Underscore => {}
+                    kind if forbid_expressions => {
                         within.convert_to_error(eco_format!(
                             "expected identifier, found {}",
-                            within.kind().name(),
+                            kind.name(),
                         ));
                         child.make_erroneous();
                     }
+                    _ => {}
                 }
             }
+            SyntaxKind::Array | SyntaxKind::Dict | SyntaxKind::Destructuring => {
+                validate_nested_pattern(child, used, forbid_expressions);
+            }
+            SyntaxKind::TypeAnnotation => {
+                child.convert_to_error("type annotations are only allowed on parameters");
+            }
             SyntaxKind::LeftParen
             | SyntaxKind::RightParen
             | SyntaxKind::Comma
@@ -1426,6 +1531,20 @@ fn validate_pattern<'a>(
     }
 }
 
+/// Validate an array or dictionary nested in a pattern and turn it into a
+/// destructuring pattern.
+fn validate_nested_pattern(
+    node: &mut SyntaxNode,
+    used: &mut HashSet<EcoString>,
+    forbid_expressions: bool,
+) {
+    validate_pattern(node.children_mut().iter_mut(), used, forbid_expressions);
+    node.convert_to_kind(SyntaxKind::Destructuring);
+    if node.children().any(SyntaxNode::erroneous) {
+        node.make_erroneous();
+    }
+}
+
 /// Manages parsing of a stream of tokens.
 struct Parser<'s> {
     text: &'s str,
@@ -1437,6 +1556,17 @@ struct Parser<'s> {
     nodes: Vec<SyntaxNode>,
     newline_modes: Vec<NewlineMode>,
     balanced: bool,
+    boundary: Option<usize>,
+}
+
+/// A snapshot of the parser's state to which it can return.
+struct Checkpoint<'s> {
+    lexer: Lexer<'s>,
+    prev_end: usize,
+    current_start: usize,
+    current: SyntaxKind,
+    nodes: usize,
+    balanced: bool,
 }
 
 /// How to proceed with parsing when seeing a newline.
@@ -1467,6 +1597,7 @@ impl<'s> Parser<'s> {
             nodes: vec![],
             newline_modes: vec![],
             balanced: true,
+            boundary: None,
         }
     }
 
@@ -1580,6 +1711,26 @@ impl<'s> Parser<'s> {
         self.nodes.insert(from, SyntaxNode::inner(kind, children));
     }
 
+    fn checkpoint(&self) -> Checkpoint<'s> {
+        Checkpoint {
+            lexer: self.lexer.clone(),
+            prev_end: self.prev_end,
+            current_start: self.current_start,
+            current: self.current,
+            nodes: self.nodes.len(),
+            balanced: self.balanced,
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint<'s>) {
+        self.lexer = checkpoint.lexer;
+        self.prev_end = checkpoint.prev_end;
+        self.current_start = checkpoint.current_start;
+        self.current = checkpoint.current;
+        self.nodes.truncate(checkpoint.nodes);
+        self.balanced = checkpoint.balanced;
+    }
+
     fn progress(&self, offset: usize) -> bool {
         offset < self.prev_end
     }
@@ -1641,8 +1792,8 @@ impl<'s> Parser<'s> {
     fn save(&mut self) {
         let text = self.current_text();
         if self.at(SyntaxKind::Error) {
-            let message = self.lexer.take_error().unwrap();
-            self.nodes.push(SyntaxNode::error(message, text));
+            let error = self.lexer.take_error().unwrap();
+            self.nodes.push(SyntaxNode::from_error(error, text));
         } else {
             self.nodes.push(SyntaxNode::leaf(self.current, text));
         }
@@ -1669,6 +1820,10 @@ impl<'s> Parser<'s> {
         {
             self.current = SyntaxKind::Eof;
         }
+
+        if self.boundary.is_some_and(|boundary| self.current_start >= boundary) {
+            self.current = SyntaxKind::Eof;
+        }
     }
 }
 
@@ -1721,6 +1876,7 @@ impl<'s> Parser<'s> {
     fn expect_closing_delimiter(&mut self, open: Marker, kind: SyntaxKind) {
         if !self.eat_if(kind) {
             self.nodes[open.0].convert_to_error("unclosed delimiter");
+            self.nodes[open.0].set_code("E0002");
         }
     }
 
@@ -130,6 +130,12 @@ fn try_reparse(
             start -= 1;
         }
 
+        // Where an unclosed block ends depends on the text after it, so the
+        // edit could change it.
+        if children[..start].iter().any(has_unclosed_block) {
+            return None;
+        }
+
         // Synthesize what `at_start` and `nesting` would be at the start of the
         // reparse.
         let mut prefix_len = 0;
@@ -219,6 +225,16 @@ fn expand(node: &SyntaxNode) -> bool {
         || node.text() == ":"
 }
 
+/// Whether the node contains a block or equation without a closing delimiter.
+fn has_unclosed_block(node: &SyntaxNode) -> bool {
+    node.erroneous()
+        && ((matches!(
+            node.kind(),
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock | SyntaxKind::Equation
+        ) && node.children().next().is_some_and(|open| open.kind().is_error()))
+            || node.children().any(has_unclosed_block))
+}
+
 /// Whether `at_start` would still be true after this node given the
 /// previous value of the property.
 fn next_at_start(node: &SyntaxNode, at_start: &mut bool) {
@@ -322,5 +338,7 @@ mod tests {
         test("a#{call(); abc}b", 8..8, "[]", true);
         test("a #while x {\n g(x) \n}  b", 12..12, "//", true);
         test("a#[]b", 3..3, "[hey]", true);
+        test("#{ x\n= A\nB", 10..10, "}", false);
+        test("#{ x\n= A\nB", 7..8, "C", false);
     }
 }
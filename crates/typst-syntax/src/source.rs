@@ -112,6 +112,11 @@ impl Source {
 
     /// Edit the source file by replacing the given range.
     ///
+    /// Only the smallest subtree that contains the edit is reparsed. If that
+    /// is not possible, the whole file is reparsed instead. Spans of nodes
+    /// outside of the reparsed subtree stay the same, so that editors can
+    /// keep using them.
+    ///
     /// Returns the range in the new source that was ultimately reparsed.
     ///
     /// The method panics if the `replace` range is out of bounds.
@@ -119,6 +124,7 @@ impl Source {
     pub fn edit(&mut self, replace: Range<usize>, with: &str) -> Range<usize> {
         let start_byte = replace.start;
         let start_utf16 = self.byte_to_utf16(start_byte).unwrap();
+        let start_char = self.byte_to_char(start_byte).unwrap();
         let line = self.byte_to_line(start_byte).unwrap();
 
         let inner = Arc::make_mut(&mut self.0);
@@ -138,6 +144,7 @@ impl Source {
         inner.lines.extend(lines_from(
             start_byte,
             start_utf16,
+            start_char,
             &inner.text[start_byte..],
         ));
 
@@ -158,6 +165,12 @@ impl Source {
         last.utf16_idx + len_utf16(&self.0.text[last.byte_idx..])
     }
 
+    /// Get the length of the file in characters.
+    pub fn len_chars(&self) -> usize {
+        let last = self.0.lines.last().unwrap();
+        last.char_idx + self.0.text[last.byte_idx..].chars().count()
+    }
+
     /// Get the length of the file in lines.
     pub fn len_lines(&self) -> usize {
         self.0.lines.len()
@@ -185,6 +198,14 @@ impl Source {
         Some(line.utf16_idx + len_utf16(head))
     }
 
+    /// Return the index of the character at the byte index.
+    pub fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        let line_idx = self.byte_to_line(byte_idx)?;
+        let line = self.0.lines.get(line_idx)?;
+        let head = self.0.text.get(line.byte_idx..byte_idx)?;
+        Some(line.char_idx + head.chars().count())
+    }
+
     /// Return the index of the line that contains the given byte index.
     pub fn byte_to_line(&self, byte_idx: usize) -> Option<usize> {
         (byte_idx <= self.0.text.len()).then(|| {
@@ -226,6 +247,36 @@ impl Source {
         (k == utf16_idx).then_some(self.0.text.len())
     }
 
+    /// Return the byte index at the character index.
+    pub fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        let line = self.0.lines.get(
+            match self.0.lines.binary_search_by_key(&char_idx, |line| line.char_idx) {
+                Ok(i) => i,
+                Err(i) => i - 1,
+            },
+        )?;
+
+        let mut k = line.char_idx;
+        for (i, _) in self.0.text[line.byte_idx..].char_indices() {
+            if k >= char_idx {
+                return Some(line.byte_idx + i);
+            }
+            k += 1;
+        }
+
+        (k == char_idx).then_some(self.0.text.len())
+    }
+
+    /// Return the index of the UTF-16 column at the byte index.
+    ///
+    /// The column is defined as the number of UTF-16 code units in the line
+    /// before the byte index. This is how the Language Server Protocol counts
+    /// positions by default.
+    pub fn byte_to_utf16_column(&self, byte_idx: usize) -> Option<usize> {
+        let line = self.byte_to_line(byte_idx)?;
+        Some(self.byte_to_utf16(byte_idx)? - self.0.lines[line].utf16_idx)
+    }
+
     /// Return the byte position at which the given line starts.
     pub fn line_to_byte(&self, line_idx: usize) -> Option<usize> {
         self.0.lines.get(line_idx).map(|line| line.byte_idx)
@@ -255,6 +306,22 @@ impl Source {
         }
         Some(range.start + (line.len() - chars.as_str().len()))
     }
+
+    /// Return the byte index of the given line and UTF-16 column.
+    ///
+    /// The column defines the number of UTF-16 code units to go beyond the
+    /// start of the line. Returns `None` if the column lies beyond the end of
+    /// the line.
+    pub fn line_utf16_column_to_byte(
+        &self,
+        line_idx: usize,
+        column_idx: usize,
+    ) -> Option<usize> {
+        let range = self.line_to_range(line_idx)?;
+        let line = self.0.lines[line_idx];
+        let byte_idx = self.utf16_to_byte(line.utf16_idx + column_idx)?;
+        (byte_idx <= range.end).then_some(byte_idx)
+    }
 }
 
 impl Debug for Source {
@@ -284,12 +351,14 @@ struct Line {
     byte_idx: usize,
     /// The UTF-16 codepoint offset where the line starts.
     utf16_idx: usize,
+    /// The character offset where the line starts.
+    char_idx: usize,
 }
 
 /// Create a line vector.
 fn lines(text: &str) -> Vec<Line> {
-    std::iter::once(Line { byte_idx: 0, utf16_idx: 0 })
-        .chain(lines_from(0, 0, text))
+    std::iter::once(Line { byte_idx: 0, utf16_idx: 0, char_idx: 0 })
+        .chain(lines_from(0, 0, 0, text))
         .collect()
 }
 
@@ -297,14 +366,17 @@ fn lines(text: &str) -> Vec<Line> {
 fn lines_from(
     byte_offset: usize,
     utf16_offset: usize,
+    char_offset: usize,
     text: &str,
 ) -> impl Iterator<Item = Line> + '_ {
     let mut s = unscanny::Scanner::new(text);
     let mut utf16_idx = utf16_offset;
+    let mut char_idx = char_offset;
 
     std::iter::from_fn(move || {
         s.eat_until(|c: char| {
             utf16_idx += c.len_utf16();
+            char_idx += 1;
             is_newline(c)
         });
 
@@ -314,9 +386,14 @@ fn lines_from(
 
         if s.eat() == Some('\r') && s.eat_if('\n') {
             utf16_idx += 1;
+            char_idx += 1;
         }
 
-        Some(Line { byte_idx: byte_offset + s.cursor(), utf16_idx })
+        Some(Line {
+            byte_idx: byte_offset + s.cursor(),
+            utf16_idx,
+            char_idx,
+        })
     })
 }
 
@@ -338,10 +415,10 @@ mod tests {
         assert_eq!(
             source.0.lines,
             [
-                Line { byte_idx: 0, utf16_idx: 0 },
-                Line { byte_idx: 7, utf16_idx: 6 },
-                Line { byte_idx: 15, utf16_idx: 12 },
-                Line { byte_idx: 18, utf16_idx: 15 },
+                Line { byte_idx: 0, utf16_idx: 0, char_idx: 0 },
+                Line { byte_idx: 7, utf16_idx: 6, char_idx: 6 },
+                Line { byte_idx: 15, utf16_idx: 12, char_idx: 11 },
+                Line { byte_idx: 18, utf16_idx: 15, char_idx: 14 },
             ]
         );
     }
@@ -391,6 +468,39 @@ mod tests {
         assert_eq!(source.utf16_to_byte(19), None);
     }
 
+    #[test]
+    fn test_source_file_chars() {
+        #[track_caller]
+        fn roundtrip(source: &Source, byte_idx: usize, char_idx: usize) {
+            let middle = source.byte_to_char(byte_idx).unwrap();
+            let result = source.char_to_byte(middle).unwrap();
+            assert_eq!(middle, char_idx);
+            assert_eq!(result, byte_idx);
+        }
+
+        let source = Source::detached(TEST);
+        roundtrip(&source, 0, 0);
+        roundtrip(&source, 2, 1);
+        roundtrip(&source, 8, 7);
+        roundtrip(&source, 12, 8);
+        roundtrip(&source, 21, 17);
+        assert_eq!(source.len_chars(), 17);
+        assert_eq!(source.byte_to_char(22), None);
+        assert_eq!(source.char_to_byte(18), None);
+    }
+
+    #[test]
+    fn test_source_file_utf16_column() {
+        let source = Source::detached(TEST);
+        assert_eq!(source.byte_to_utf16_column(7), Some(0));
+        assert_eq!(source.byte_to_utf16_column(12), Some(3));
+        assert_eq!(source.byte_to_utf16_column(21), Some(3));
+        assert_eq!(source.line_utf16_column_to_byte(1, 0), Some(7));
+        assert_eq!(source.line_utf16_column_to_byte(1, 3), Some(12));
+        assert_eq!(source.line_utf16_column_to_byte(3, 3), Some(21));
+        assert_eq!(source.line_utf16_column_to_byte(3, 4), None);
+    }
+
     #[test]
     fn test_source_file_roundtrip() {
         #[track_caller]
@@ -0,0 +1,249 @@
+//! Traversal and rewriting of syntax trees.
+//!
+//! A [`Visitor`] is informed about each node of a tree as it is entered and
+//! exited. Together with a [`Rewriter`], which turns edits of individual nodes
+//! into edits of a [`Source`], this is the basis for tools that migrate code,
+//! for instance from deprecated syntax.
+
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use crate::ast::AstNode;
+use crate::{LinkedNode, Source, SyntaxNode};
+
+/// Observes the nodes of a syntax tree during a [`walk`].
+///
+/// Both methods receive the untyped node, which can be matched by its
+/// [kind](SyntaxNode::kind) and [cast](SyntaxNode::cast) to a typed AST node.
+pub trait Visitor<'a> {
+    /// Called when a node is entered, before its children.
+    ///
+    /// Returns whether the children should be visited.
+    fn enter(&mut self, node: &LinkedNode<'a>) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called when a node is exited, after its children.
+    fn exit(&mut self, node: &LinkedNode<'a>) {
+        let _ = node;
+    }
+}
+
+/// Visit all nodes of a syntax tree in source order.
+pub fn walk<'a>(root: &'a SyntaxNode, visitor: &mut impl Visitor<'a>) {
+    walk_impl(&LinkedNode::new(root), visitor);
+}
+
+/// Visit a node and its descendants.
+fn walk_impl<'a>(node: &LinkedNode<'a>, visitor: &mut impl Visitor<'a>) {
+    if visitor.enter(node) {
+        for child in node.children() {
+            walk_impl(&child, visitor);
+        }
+    }
+    visitor.exit(node);
+}
+
+/// Call a function for each node of a syntax tree that can be cast to the
+/// typed node `T`, in source order.
+///
+/// The function also receives the untyped node, for instance to record an
+/// edit with a [`Rewriter`].
+pub fn for_each<'a, T: AstNode<'a>>(
+    root: &'a SyntaxNode,
+    mut f: impl FnMut(T, &LinkedNode<'a>),
+) {
+    for_each_impl(&LinkedNode::new(root), &mut f);
+}
+
+/// Call a function for a node and its descendants that are typed nodes `T`.
+fn for_each_impl<'a, T: AstNode<'a>>(
+    node: &LinkedNode<'a>,
+    f: &mut impl FnMut(T, &LinkedNode<'a>),
+) {
+    if let Some(typed) = node.get().cast::<T>() {
+        f(typed, node);
+    }
+    for child in node.children() {
+        for_each_impl(&child, f);
+    }
+}
+
+/// Collects edits of the nodes of a source file and applies them at once.
+///
+/// The edits are given in terms of the unchanged file, so that they can be
+/// recorded while walking its syntax tree. When they are applied, only the
+/// parts of the file around them are reparsed, so all other nodes keep their
+/// spans.
+#[derive(Debug, Default, Clone)]
+pub struct Rewriter {
+    edits: Vec<(Range<usize>, EcoString)>,
+}
+
+impl Rewriter {
+    /// Create a rewriter without any edits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the text of a node.
+    pub fn replace(&mut self, node: &LinkedNode, with: impl Into<EcoString>) {
+        self.replace_range(node.range(), with);
+    }
+
+    /// Replace a byte range of the file.
+    pub fn replace_range(&mut self, range: Range<usize>, with: impl Into<EcoString>) {
+        self.edits.push((range, with.into()));
+    }
+
+    /// Insert text in front of a node.
+    pub fn insert_before(&mut self, node: &LinkedNode, text: impl Into<EcoString>) {
+        let start = node.offset();
+        self.replace_range(start..start, text);
+    }
+
+    /// Insert text after a node.
+    pub fn insert_after(&mut self, node: &LinkedNode, text: impl Into<EcoString>) {
+        let end = node.range().end;
+        self.replace_range(end..end, text);
+    }
+
+    /// Remove a node.
+    pub fn delete(&mut self, node: &LinkedNode) {
+        self.replace(node, "");
+    }
+
+    /// Whether no edits were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Apply the edits to the file they were recorded for.
+    ///
+    /// Of two overlapping edits, the one that starts first is applied and the
+    /// other one is ignored. Returns the ranges in the new file that were
+    /// replaced, in source order.
+    ///
+    /// The method panics if an edit's range is out of bounds.
+    #[track_caller]
+    pub fn apply(mut self, source: &mut Source) -> Vec<Range<usize>> {
+        self.edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+        let mut edits: Vec<(Range<usize>, EcoString)> = vec![];
+        for (range, with) in self.edits {
+            if edits.last().map_or(true, |(prev, _)| prev.end <= range.start) {
+                edits.push((range, with));
+            }
+        }
+
+        // Apply the edits back to front, so that the ranges of the remaining
+        // ones stay valid.
+        for (range, with) in edits.iter().rev() {
+            source.edit(range.clone(), with);
+        }
+
+        let mut shift = 0isize;
+        edits
+            .iter()
+            .map(|(range, with)| {
+                let start = range.start.checked_add_signed(shift).unwrap();
+                shift += with.len() as isize - range.len() as isize;
+                start..start + with.len()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast, SyntaxKind};
+
+    #[test]
+    fn test_walk() {
+        struct Depth(Vec<(SyntaxKind, usize)>, usize);
+
+        impl<'a> Visitor<'a> for Depth {
+            fn enter(&mut self, node: &LinkedNode<'a>) -> bool {
+                self.0.push((node.kind(), self.1));
+                self.1 += 1;
+                node.kind() != SyntaxKind::Strong
+            }
+
+            fn exit(&mut self, _: &LinkedNode<'a>) {
+                self.1 -= 1;
+            }
+        }
+
+        let root = crate::parse("*A* B");
+        let mut depth = Depth(vec![], 0);
+        walk(&root, &mut depth);
+        assert_eq!(
+            depth.0,
+            [
+                (SyntaxKind::Markup, 0),
+                (SyntaxKind::Strong, 1),
+                (SyntaxKind::Space, 1),
+                (SyntaxKind::Text, 1),
+            ]
+        );
+        assert_eq!(depth.1, 0);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let mut source = Source::detached(
+            "#let a = 1\n\nSome text.\n\n#sym.diff\n\nMore $diff$ text.\n\n#let b = 2",
+        );
+
+        let span = |source: &Source, name: &str| {
+            let offset = source.text().find(name).unwrap();
+            LinkedNode::new(source.root()).leaf_at(offset + 1).unwrap().span()
+        };
+
+        let a = span(&source, "a =");
+        let b = span(&source, "b =");
+
+        let mut rewriter = Rewriter::new();
+        for_each(source.root(), |access: ast::FieldAccess, node| {
+            if access.field().as_str() == "diff" {
+                let field = node.find(access.field().span()).unwrap();
+                rewriter.replace(&field, "partial");
+            }
+        });
+        for_each(source.root(), |ident: ast::MathIdent, node| {
+            if ident.as_str() == "diff" {
+                rewriter.replace(node, "partial");
+            }
+        });
+
+        let ranges = rewriter.apply(&mut source);
+        assert_eq!(
+            source.text(),
+            "#let a = 1\n\nSome text.\n\n#sym.partial\n\nMore $partial$ text.\n\n#let b = 2",
+        );
+        assert_eq!(ranges, [29..36, 44..51]);
+        assert_eq!(span(&source, "a ="), a);
+        assert_eq!(span(&source, "b ="), b);
+    }
+
+    #[test]
+    fn test_rewrite_overlapping() {
+        let mut source = Source::detached("#(1 + 2)");
+        let root = LinkedNode::new(source.root());
+        let one = root.leaf_at(3).unwrap();
+        let binary = one.parent().unwrap().clone();
+
+        let mut rewriter = Rewriter::new();
+        rewriter.replace(&one, "3");
+        rewriter.replace(&binary, "4");
+        rewriter.insert_before(&binary, "0 + ");
+        rewriter.insert_after(&binary, " * 5");
+
+        let ranges = rewriter.apply(&mut source);
+        assert_eq!(source.text(), "#(0 + 3 + 2 * 5)");
+        assert_eq!(ranges, [2..6, 6..7, 11..15]);
+    }
+}
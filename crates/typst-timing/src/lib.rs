@@ -1,5 +1,6 @@
 //! Performance timing for Typst.
 
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
 use std::io::Write;
 use std::thread::ThreadId;
@@ -231,3 +232,51 @@ pub fn export_json<W: Write>(
 
     Ok(())
 }
+
+/// Export data in the folded stack format for flamegraph tools.
+///
+/// Each line consists of the names of nested scopes, separated by
+/// semicolons, and the time in microseconds that was spent in the innermost
+/// scope itself, excluding its children. Identical stacks are merged, so that
+/// the output can be fed directly into tools like `inferno-flamegraph`.
+pub fn export_folded<W: Write>(mut writer: W) -> Result<(), String> {
+    /// A scope that was entered but not yet exited.
+    struct Open {
+        name: &'static str,
+        start: SystemTime,
+        children: Duration,
+    }
+
+    let recorder = RECORDER.lock();
+    let mut stacks: HashMap<ThreadId, Vec<Open>> = HashMap::new();
+    let mut folded: BTreeMap<String, Duration> = BTreeMap::new();
+
+    for event in recorder.events.iter() {
+        let stack = stacks.entry(event.thread_id).or_default();
+        match event.kind {
+            EventKind::Start => stack.push(Open {
+                name: event.name,
+                start: event.timestamp,
+                children: Duration::ZERO,
+            }),
+            EventKind::End => {
+                let path = stack.iter().map(|open| open.name).collect::<Vec<_>>();
+                let Some(open) = stack.pop() else { continue };
+                let total =
+                    event.timestamp.duration_since(open.start).unwrap_or(Duration::ZERO);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children += total;
+                }
+                *folded.entry(path.join(";")).or_default() +=
+                    total.saturating_sub(open.children);
+            }
+        }
+    }
+
+    for (path, duration) in folded {
+        writeln!(writer, "{path} {}", duration.as_micros())
+            .map_err(|e| format!("failed to write events: {e}"))?;
+    }
+
+    Ok(())
+}
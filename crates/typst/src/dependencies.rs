@@ -0,0 +1,232 @@
+//! Recording of the files and fonts a compilation depends on.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use comemo::Prehashed;
+use ecow::EcoString;
+
+use crate::diag::FileResult;
+use crate::foundations::{Bytes, Datetime};
+use crate::syntax::{FileId, PackageSpec, Source};
+use crate::text::{Font, FontBook};
+use crate::util::hash128;
+use crate::{Library, World};
+
+/// Everything a compilation read from its [`World`].
+///
+/// Created by [`compile_with_dependencies`](crate::compile_with_dependencies).
+/// A build system or a file watcher can use it to find out what to track:
+/// As long as none of the dependencies change, compiling again yields the
+/// same document.
+///
+/// The hashes are only meant to be compared with those of other compilations
+/// by the same build of Typst.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Dependencies {
+    /// The source files that were evaluated, including the main file.
+    pub sources: Vec<FileDependency>,
+    /// The other files that were read, like images, data files, and
+    /// bibliographies.
+    pub files: Vec<FileDependency>,
+    /// The fonts that were loaded.
+    pub fonts: Vec<FontDependency>,
+    /// The packages that a source file or another file was read from.
+    pub packages: Vec<PackageSpec>,
+}
+
+/// A file a compilation depends on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FileDependency {
+    /// The file, in the project or in a package.
+    pub id: FileId,
+    /// A hash of the file's contents, or `None` if it couldn't be read.
+    ///
+    /// A file that couldn't be read is a dependency, too: Once it exists,
+    /// the result of the compilation might change.
+    pub hash: Option<u128>,
+}
+
+/// A font a compilation depends on.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FontDependency {
+    /// The font's index in the font book.
+    pub index: usize,
+    /// The font's family name.
+    pub family: EcoString,
+    /// A hash of the font file's data and the index of the font in it, or
+    /// `None` if it couldn't be loaded.
+    pub hash: Option<u128>,
+}
+
+/// A world that records which files and fonts are accessed through it.
+///
+/// Memoized functions whose results are reused still access their
+/// dependencies through the world, to check whether they are unchanged.
+/// Hence, the recorded dependencies are complete even when most of a
+/// compilation is cached.
+pub(crate) struct Recorder<'a> {
+    world: &'a dyn World,
+    sources: Mutex<HashMap<FileId, Option<u128>>>,
+    files: Mutex<HashMap<FileId, Option<u128>>>,
+    fonts: Mutex<BTreeSet<usize>>,
+}
+
+impl<'a> Recorder<'a> {
+    /// Start recording the accesses to a world.
+    pub fn new(world: &'a dyn World) -> Self {
+        Self {
+            world,
+            sources: Mutex::default(),
+            files: Mutex::default(),
+            fonts: Mutex::default(),
+        }
+    }
+
+    /// Stop recording and collect the dependencies.
+    pub fn finish(self) -> Dependencies {
+        let sources = sorted(self.sources.into_inner().unwrap());
+        let files = sorted(self.files.into_inner().unwrap());
+
+        let mut packages: Vec<PackageSpec> = vec![];
+        for dep in sources.iter().chain(&files) {
+            if let Some(spec) = dep.id.package() {
+                if !packages.contains(spec) {
+                    packages.push(spec.clone());
+                }
+            }
+        }
+
+        let fonts = self
+            .fonts
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|index| {
+                let font = self.world.font(index);
+                FontDependency {
+                    index,
+                    family: self
+                        .world
+                        .book()
+                        .info(index)
+                        .map(|info| info.family.as_str().into())
+                        .unwrap_or_default(),
+                    hash: font.map(|font| hash128(&(font.data(), font.index()))),
+                }
+            })
+            .collect();
+
+        Dependencies { sources, files, fonts, packages }
+    }
+}
+
+impl World for Recorder<'_> {
+    fn library(&self) -> &Prehashed<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> Source {
+        let source = self.world.main();
+        record(&self.sources, source.id(), || Some(hash128(source.text())));
+        source
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        let result = self.world.source(id);
+        record(&self.sources, id, || {
+            result.as_ref().ok().map(|source| hash128(source.text()))
+        });
+        result
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        let result = self.world.file(id);
+        record(&self.files, id, || result.as_ref().ok().map(hash128));
+        result
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.lock().unwrap().insert(index);
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        self.world.packages()
+    }
+
+    fn can_write(&self) -> bool {
+        self.world.can_write()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.world.cancelled()
+    }
+}
+
+/// Record an access to a file, hashing it only on the first access.
+fn record(
+    map: &Mutex<HashMap<FileId, Option<u128>>>,
+    id: FileId,
+    hash: impl FnOnce() -> Option<u128>,
+) {
+    map.lock().unwrap().entry(id).or_insert_with(hash);
+}
+
+/// Turn recorded file accesses into dependencies, sorted by package and
+/// path.
+fn sorted(map: HashMap<FileId, Option<u128>>) -> Vec<FileDependency> {
+    let mut deps: Vec<_> = map
+        .into_iter()
+        .map(|(id, hash)| FileDependency { id, hash })
+        .collect();
+    deps.sort_by(|a, b| {
+        let key = |id: FileId| {
+            let package =
+                id.package().map(|spec| (&spec.namespace, &spec.name, spec.version));
+            (package, id.vpath())
+        };
+        key(a.id).cmp(&key(b.id))
+    });
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::Tracer;
+    use crate::syntax::VirtualPath;
+    use crate::MemoryWorld;
+
+    #[test]
+    fn test_dependencies_of_cached_compilation() {
+        let mut world = MemoryWorld::new(
+            "#import \"util.typ\": x\n#let _ = read(\"data.txt\")\n#let _ = x",
+        );
+        world.insert_file("util.typ", "#let x = 1".as_bytes());
+        world.insert_file("data.txt", "hello".as_bytes());
+
+        let id = |path| FileId::new(None, VirtualPath::new(path));
+        let paths =
+            |deps: &[FileDependency]| deps.iter().map(|dep| dep.id).collect::<Vec<_>>();
+
+        // The second compilation is served from the cache, but must report
+        // the same dependencies.
+        for _ in 0..2 {
+            let (output, deps) =
+                crate::compile_with_dependencies(&world, &mut Tracer::new());
+            assert!(output.is_ok());
+            assert_eq!(paths(&deps.sources), [id("main.typ"), id("util.typ")]);
+            assert_eq!(paths(&deps.files), [id("data.txt")]);
+            assert!(deps.packages.is_empty());
+        }
+    }
+}
@@ -2,14 +2,17 @@
 
 use std::fmt::{self, Display, Formatter};
 use std::io;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 
 use comemo::Tracked;
 use ecow::{eco_vec, EcoVec};
+use serde::Serialize;
 
-use crate::syntax::{PackageSpec, Span, Spanned, SyntaxError};
+use crate::syntax::visit::Rewriter;
+use crate::syntax::{PackageSpec, PackageVersion, Source, Span, Spanned, SyntaxError};
 use crate::{World, WorldExt};
 
 /// Early-return with a [`StrResult`] or [`SourceResult`].
@@ -120,6 +123,18 @@ pub use {
 /// A result that can carry multiple source errors.
 pub type SourceResult<T> = Result<T, EcoVec<SourceDiagnostic>>;
 
+/// An output alongside the warnings that were emitted while producing it.
+///
+/// Warnings don't stop compilation, so they are reported both when the
+/// output is a success and when it is a failure.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Warned<T> {
+    /// The produced output.
+    pub output: T,
+    /// Warnings that were emitted while producing the output.
+    pub warnings: EcoVec<SourceDiagnostic>,
+}
+
 /// An error or warning in a source file.
 ///
 /// The contained spans will only be detached if any of the input source files
@@ -137,10 +152,35 @@ pub struct SourceDiagnostic {
     /// Additional hints to the user, indicating how this problem could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// Other places in the source code that are related to the problem, each
+    /// with a note on how it is related, like "first defined here".
+    pub related: EcoVec<Spanned<EcoString>>,
+    /// Edits of the source code that resolve the problem and can be applied
+    /// without further thought, for instance by an editor's quick-fix.
+    pub fixes: EcoVec<Fix>,
+    /// Whether the error aborts compilation as a whole instead of just the
+    /// code that caused it, like when the compilation is cancelled or a
+    /// resource limit is exceeded. Such errors can't be
+    /// [caught](crate::foundations::catch).
+    pub fatal: bool,
+    /// The stable code that identifies the kind of the diagnostic, if it has
+    /// one. The code can be passed to [`explain`] to get an extended
+    /// description of the problem.
+    pub code: Option<&'static str>,
+}
+
+/// A machine-applicable edit that resolves a [`SourceDiagnostic`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Fix {
+    /// The span of the node whose code is replaced.
+    pub span: Span,
+    /// The code that replaces the node. Empty to remove it.
+    pub replacement: EcoString,
 }
 
 /// The severity of a [`SourceDiagnostic`].
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Severity {
     /// A fatal error.
     Error,
@@ -157,6 +197,10 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            related: eco_vec![],
+            fixes: eco_vec![],
+            fatal: false,
+            code: None,
         }
     }
 
@@ -168,6 +212,10 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            related: eco_vec![],
+            fixes: eco_vec![],
+            fatal: false,
+            code: None,
         }
     }
 
@@ -187,8 +235,445 @@ impl SourceDiagnostic {
         self.hints.extend(hints);
         self
     }
+
+    /// Adds a related place in the source code to the diagnostic.
+    pub fn related(&mut self, span: Span, note: impl Into<EcoString>) {
+        self.related.push(Spanned::new(note.into(), span));
+    }
+
+    /// Adds a related place in the source code to the diagnostic.
+    pub fn with_related(mut self, span: Span, note: impl Into<EcoString>) -> Self {
+        self.related(span, note);
+        self
+    }
+
+    /// Adds a fix that replaces the code at the span.
+    pub fn fix(&mut self, span: Span, replacement: impl Into<EcoString>) {
+        self.fixes.push(Fix { span, replacement: replacement.into() });
+    }
+
+    /// Adds a fix that replaces the code at the span.
+    pub fn with_fix(mut self, span: Span, replacement: impl Into<EcoString>) -> Self {
+        self.fix(span, replacement);
+        self
+    }
+
+    /// Marks the error as aborting compilation as a whole.
+    pub fn into_fatal(mut self) -> Self {
+        self.fatal = true;
+        self
+    }
+
+    /// Sets the stable code that identifies the kind of the diagnostic.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+}
+
+/// A [`SourceDiagnostic`] with its spans resolved to positions in files.
+///
+/// In this form, diagnostics can be serialized, so that continuous
+/// integration systems and editors can consume them without parsing the
+/// human-readable output of the compiler.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedDiagnostic {
+    /// Whether the diagnostic is an error or a warning.
+    pub severity: Severity,
+    /// The stable code of the diagnostic, if it has one.
+    pub code: Option<&'static str>,
+    /// A diagnostic message describing the problem.
+    pub message: EcoString,
+    /// Where the problem is, if it is in a source file.
+    pub location: Option<Location>,
+    /// Additional hints to the user.
+    pub hints: EcoVec<EcoString>,
+    /// The trace of function calls leading to the problem.
+    pub trace: Vec<ResolvedNote>,
+    /// Other places in the source code that are related to the problem.
+    pub related: Vec<ResolvedNote>,
+    /// Edits of the source code that resolve the problem.
+    pub fixes: Vec<ResolvedFix>,
+}
+
+impl ResolvedDiagnostic {
+    /// Resolve the spans of a diagnostic.
+    pub fn new(world: &dyn World, diag: &SourceDiagnostic) -> Self {
+        let note = |message: EcoString, span: Span| ResolvedNote {
+            message,
+            location: Location::new(world, span),
+        };
+
+        Self {
+            severity: diag.severity,
+            code: diag.code,
+            message: diag.message.clone(),
+            location: Location::new(world, diag.span),
+            hints: diag.hints.clone(),
+            trace: diag
+                .trace
+                .iter()
+                .map(|point| note(eco_format!("{}", point.v), point.span))
+                .collect(),
+            related: diag
+                .related
+                .iter()
+                .map(|related| note(related.v.clone(), related.span))
+                .collect(),
+            fixes: diag
+                .fixes
+                .iter()
+                .filter_map(|fix| {
+                    Some(ResolvedFix {
+                        location: Location::new(world, fix.span)?,
+                        replacement: fix.replacement.clone(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Serialize the diagnostic to JSON on a single line.
+    pub fn to_json(&self) -> StrResult<String> {
+        serde_json::to_string(self)
+            .map_err(|err| eco_format!("failed to serialize diagnostic ({err})"))
+    }
+}
+
+/// A note of a [`ResolvedDiagnostic`] that refers to a place in the source
+/// code.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedNote {
+    /// The note, like "first defined here".
+    pub message: EcoString,
+    /// The place the note refers to, if it is in a source file.
+    pub location: Option<Location>,
+}
+
+/// A [`Fix`] of a [`ResolvedDiagnostic`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedFix {
+    /// The code that is replaced.
+    pub location: Location,
+    /// The code that replaces it. Empty to remove it.
+    pub replacement: EcoString,
+}
+
+/// A range of code in a file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct Location {
+    /// The path of the file, relative to the project root or, prefixed with
+    /// the package specification, to the root of a package.
+    pub file: EcoString,
+    /// Where the range starts.
+    pub start: Position,
+    /// Where the range ends.
+    pub end: Position,
+}
+
+impl Location {
+    /// Find the location of a span.
+    ///
+    /// Returns `None` if the span does not point into any source file.
+    pub fn new(world: &dyn World, span: Span) -> Option<Self> {
+        let id = span.id()?;
+        let source = world.source(id).ok()?;
+        let range = source.range(span)?;
+        let path = id.vpath().as_rootless_path().to_string_lossy();
+        let file = match id.package() {
+            Some(package) => eco_format!("{package}/{path}"),
+            None => path.as_ref().into(),
+        };
+        Some(Self {
+            file,
+            start: Position::new(&source, range.start)?,
+            end: Position::new(&source, range.end)?,
+        })
+    }
 }
 
+/// A position in a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct Position {
+    /// The line, starting at one.
+    pub line: usize,
+    /// The column in characters, starting at one.
+    pub column: usize,
+    /// The byte offset from the start of the file.
+    pub offset: usize,
+}
+
+impl Position {
+    /// Find the position of a byte offset in a file.
+    fn new(source: &Source, offset: usize) -> Option<Self> {
+        Some(Self {
+            line: source.byte_to_line(offset)? + 1,
+            column: source.byte_to_column(offset)? + 1,
+            offset,
+        })
+    }
+}
+
+/// Apply the fixes of diagnostics that are in a source file to it.
+///
+/// Of two fixes for the same code, only the first is applied. Returns the
+/// ranges in the new file that were replaced, in source order.
+pub fn apply_fixes<'a>(
+    source: &mut Source,
+    diagnostics: impl IntoIterator<Item = &'a SourceDiagnostic>,
+) -> Vec<Range<usize>> {
+    let mut rewriter = Rewriter::new();
+    for fix in diagnostics.into_iter().flat_map(|diag| &diag.fixes) {
+        if let Some(range) = source.range(fix.span) {
+            rewriter.replace_range(range, fix.replacement.clone());
+        }
+    }
+    rewriter.apply(source)
+}
+
+/// Get the extended description of a diagnostic code, like `E0001`.
+///
+/// The description is written in Markdown and contains examples of code that
+/// produces the diagnostic and how to fix it. Returns `None` if the code is
+/// unknown.
+pub fn explain(code: &str) -> Option<&'static str> {
+    CODES
+        .iter()
+        .find(|entry| entry.code.eq_ignore_ascii_case(code))
+        .map(|entry| entry.explanation)
+}
+
+/// A kind of diagnostic with a stable code.
+struct CodeEntry {
+    /// The code, like `E0001`. Errors start with `E` and warnings with `W`.
+    code: &'static str,
+    /// The extended description of the code.
+    explanation: &'static str,
+}
+
+/// All diagnostic codes.
+///
+/// Codes are never reused or renumbered. Diagnostics get their code where they
+/// are created.
+static CODES: &[CodeEntry] = &[
+    CodeEntry {
+        code: "E0001",
+        explanation: r#"A variable was used that is not defined.
+
+Variables must be defined with `let` or imported before they are used.
+
+```typ
+#let name = "Typst"
+Hello #nmae!
+```
+
+Check the variable's name for typos. If you meant to subtract, surround the
+minus sign with spaces: `#(a - b)` instead of `#(a-b)`."#,
+    },
+    CodeEntry {
+        code: "E0002",
+        explanation: r#"A bracket, brace, parenthesis, or dollar sign was opened, but
+never closed.
+
+```typ
+#let f(x) = {
+  x + 1
+
+#f(2)
+```
+
+Add the closing delimiter at the end of the block."#,
+    },
+    CodeEntry {
+        code: "E0003",
+        explanation: r#"A string was opened with a quote, but never closed.
+
+```typ
+#let path = "C:\Users\"
+```
+
+Add the closing quote. Within normal strings, a backslash escapes the next
+character, so a quote that directly follows a backslash doesn't close the
+string. Use `\\` for a backslash or write a raw string like `r"C:\Users\"`."#,
+    },
+    CodeEntry {
+        code: "E0004",
+        explanation: r#"Raw text was opened with backticks, but never closed.
+
+````typ
+```rust
+fn main() {}
+````
+
+Close the raw text with the same number of backticks it was opened with."#,
+    },
+    CodeEntry {
+        code: "E0005",
+        explanation: r#"A named argument was passed twice to the same function call or a
+parameter was declared twice.
+
+```typ
+#set text(font: "PT Sans", font: "Inria Serif")
+```
+
+Remove one of the two arguments. To give multiple fonts, pass an array:
+`font: ("PT Sans", "Inria Serif")`."#,
+    },
+    CodeEntry {
+        code: "E0006",
+        explanation: r#"A function was called with an argument that it does not accept.
+
+```typ
+#set text(colour: red)
+```
+
+Check the function's documentation for the names of its parameters. Some
+parameters are positional and can't be passed by name, or vice versa."#,
+    },
+    CodeEntry {
+        code: "E0007",
+        explanation: r#"A function was called without an argument that it requires.
+
+```typ
+#let greet(name) = [Hello, #name!]
+#greet()
+```
+
+Pass the missing argument or give the parameter a default value:
+`#let greet(name: "World") = ...`."#,
+    },
+    CodeEntry {
+        code: "E0008",
+        explanation: r#"An item was imported from a module that doesn't define it.
+
+```typ
+#import "utils.typ": helper
+```
+
+Check that the imported file defines the item at its top level with
+`#let helper = ...` and that the name is spelled the same way."#,
+    },
+    CodeEntry {
+        code: "E0009",
+        explanation: r#"A file that was read, imported, or included does not exist.
+
+```typ
+#image("figures/plot.png")
+```
+
+Paths are relative to the file that contains them, unless they start with a
+slash, in which case they are relative to the project root. Files outside of
+the project root can't be accessed."#,
+    },
+    CodeEntry {
+        code: "E0010",
+        explanation: r#"A reference or query refers to a label that is not attached to
+any element.
+
+```typ
+= Introduction <intro>
+See @introduction.
+```
+
+Check the label's name for typos. Labels are attached to the element directly
+in front of them."#,
+    },
+    CodeEntry {
+        code: "E0011",
+        explanation: r#"A variable was modified that can't be modified at this place.
+
+Definitions from the standard library are constants, and functions can't
+modify the variables from outside of them.
+
+```typ
+#let count = 0
+#let increase() = { count += 1 }
+```
+
+Return the new value from the function instead, or use `state` for values that
+change over the course of the document."#,
+    },
+    CodeEntry {
+        code: "E0012",
+        explanation: r#"Functions called each other too deeply, usually because a
+recursive function never stops calling itself.
+
+```typ
+#let f(n) = f(n - 1)
+#f(5)
+```
+
+Make sure that the recursion has a case in which it returns without calling
+itself again."#,
+    },
+    CodeEntry {
+        code: "W0001",
+        explanation: r#"A font family is not available, so other fonts are used
+instead.
+
+```typ
+#set text(font: "Helvetica")
+```
+
+Check the name of the family for typos. Locally, you can list the available
+fonts with `typst fonts` and add directories with fonts through the
+`--font-path` argument."#,
+    },
+    CodeEntry {
+        code: "W0002",
+        explanation: r#"A deprecated definition was used. It still works, but will be
+removed in a future version.
+
+```typ
+$ diff x $
+```
+
+The hints of the warning name the replacement, like `partial` in this
+example."#,
+    },
+    CodeEntry {
+        code: "W0003",
+        explanation: r#"The document was laid out several times, but did not reach a
+stable state.
+
+This happens when states, counters, or queries depend on themselves, for
+instance when a state is updated based on its own final value. The document may
+contain outdated values. Check the states that are reported along with this
+warning."#,
+    },
+    CodeEntry {
+        code: "W0004",
+        explanation: r#"The value of a state kept changing between layout attempts.
+
+```typ
+#let s = state("s", 0)
+#context s.update(s.final() + 1)
+```
+
+Avoid updating a state based on its own final value."#,
+    },
+    CodeEntry {
+        code: "W0005",
+        explanation: r#"Strong or emphasized markup is empty.
+
+```typ
+This is **not bold**.
+```
+
+Unlike in Markdown, a single star makes text strong in Typst: `*bold*`."#,
+    },
+    CodeEntry {
+        code: "W0006",
+        explanation: r#"An import is renamed to the name it already has.
+
+```typ
+#import "utils.typ": helper as helper
+```
+
+Remove the `as` clause."#,
+    },
+];
+
 impl From<SyntaxError> for SourceDiagnostic {
     fn from(error: SyntaxError) -> Self {
         Self {
@@ -197,6 +682,10 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints,
+            related: eco_vec![],
+            fixes: eco_vec![],
+            fatal: false,
+            code: error.code,
         }
     }
 }
@@ -206,6 +695,10 @@ impl From<SyntaxError> for SourceDiagnostic {
 pub enum Tracepoint {
     /// A function call.
     Call(Option<EcoString>),
+    /// A call of a named closure from a native function, for example the
+    /// mapper in `array.map`. Such calls have no call site of their own, so
+    /// the tracepoint refers to the closure's definition instead.
+    Callback(EcoString),
     /// A show rule application.
     Show(EcoString),
     /// A module import.
@@ -221,6 +714,9 @@ impl Display for Tracepoint {
             Tracepoint::Call(None) => {
                 write!(f, "error occurred in this function call")
             }
+            Tracepoint::Callback(name) => {
+                write!(f, "error occurred in function `{name}`, defined here")
+            }
             Tracepoint::Show(name) => {
                 write!(f, "error occurred while applying show rule to this {name}")
             }
@@ -302,18 +798,38 @@ pub struct HintedString {
     /// Additional hints to the user, indicating how this error could be avoided
     /// or worked around.
     pub hints: Vec<EcoString>,
+    /// The stable code of the error, if it has one.
+    pub code: Option<&'static str>,
+}
+
+impl HintedString {
+    /// Sets the stable code of the error.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 impl From<EcoString> for HintedString {
     fn from(value: EcoString) -> Self {
-        Self { message: value, hints: vec![] }
+        Self { message: value, hints: vec![], code: None }
+    }
+}
+
+impl From<FileError> for HintedString {
+    fn from(err: FileError) -> Self {
+        let code = matches!(err, FileError::NotFound(_)).then_some("E0009");
+        Self { message: err.into(), hints: vec![], code }
     }
 }
 
 impl<T> At<T> for Result<T, HintedString> {
     fn at(self, span: Span) -> SourceResult<T> {
         self.map_err(|diags| {
-            eco_vec![SourceDiagnostic::error(span, diags.message).with_hints(diags.hints)]
+            let mut diag =
+                SourceDiagnostic::error(span, diags.message).with_hints(diags.hints);
+            diag.code = diags.code;
+            eco_vec![diag]
         })
     }
 }
@@ -332,6 +848,7 @@ where
         self.map_err(|message| HintedString {
             message: message.into(),
             hints: vec![hint.into()],
+            code: None,
         })
     }
 }
@@ -438,6 +955,9 @@ pub type PackageResult<T> = Result<T, PackageError>;
 pub enum PackageError {
     /// The specified package does not exist.
     NotFound(PackageSpec),
+    /// The specified package exists, but not in the requested version. Holds
+    /// the latest available version.
+    VersionNotFound(PackageSpec, PackageVersion),
     /// Failed to retrieve the package through the network.
     NetworkFailed(Option<EcoString>),
     /// The package archive was malformed.
@@ -454,6 +974,13 @@ impl Display for PackageError {
             Self::NotFound(spec) => {
                 write!(f, "package not found (searched for {spec})",)
             }
+            Self::VersionNotFound(spec, latest) => {
+                write!(
+                    f,
+                    "package found, but version {} does not exist (latest is {latest})",
+                    spec.version,
+                )
+            }
             Self::NetworkFailed(Some(err)) => {
                 write!(f, "failed to download package ({err})")
             }
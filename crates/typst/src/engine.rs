@@ -3,11 +3,13 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, error, SourceResult};
 use crate::eval::Tracer;
+use crate::foundations::Value;
 use crate::introspection::{Introspector, Locator};
-use crate::syntax::FileId;
+use crate::syntax::{FileId, Span};
 use crate::World;
 
 /// Holds all data needed during compilation.
@@ -42,6 +44,55 @@ impl Engine<'_> {
             }
         }
     }
+
+    /// Aborts with an error if the world requests that the compilation is
+    /// cancelled.
+    pub fn check_cancelled(&self, span: Span) -> SourceResult<()> {
+        if self.world.cancelled() {
+            bail!(error!(span, "compilation was cancelled").into_fatal());
+        }
+        Ok(())
+    }
+
+    /// Runs a task for each item on the thread pool and returns the outputs in
+    /// the order of the items.
+    ///
+    /// As the locator and the tracer cannot be shared across threads, each
+    /// task gets a fresh locator and a [forked](Tracer::fork) tracer. The
+    /// tracer is returned alongside a task's output, so that the caller can
+    /// [replay](Tracer::replay) it once it has decided to keep the output.
+    /// Likewise, the caller is responsible for advancing its locator past the
+    /// frames a task produced.
+    pub fn parallelize<T, U, F>(&mut self, items: &[T], f: F) -> Vec<(U, Tracer)>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&mut Engine, &T) -> U + Sync,
+    {
+        let world = self.world;
+        let introspector = self.introspector;
+        let route = self.route.track();
+        let forked = Tracer::fork(&self.tracer);
+
+        items
+            .par_iter()
+            .map(|item| {
+                let mut locator = Locator::new();
+                let mut tracer = forked.clone();
+                let output = {
+                    let mut engine = Engine {
+                        world,
+                        introspector,
+                        route: Route::extend(route).unnested(),
+                        locator: &mut locator,
+                        tracer: tracer.track_mut(),
+                    };
+                    f(&mut engine, item)
+                };
+                (output, tracer)
+            })
+            .collect()
+    }
 }
 
 /// The route the engine took during compilation. This is used to detect
@@ -142,6 +193,14 @@ impl<'a> Route<'a> {
         self.id == Some(id) || self.outer.map_or(false, |outer| outer.contains(id))
     }
 
+    /// Whether the route passes through the evaluation of a module.
+    ///
+    /// Modules are evaluated before layout, so code running as part of one
+    /// cannot introspect the document.
+    pub fn in_module(&self) -> bool {
+        self.id.is_some() || self.outer.map_or(false, |outer| outer.in_module())
+    }
+
     /// Whether the route's depth is less than or equal to the given depth.
     pub fn within(&self, depth: usize) -> bool {
         use Ordering::Relaxed;
@@ -185,3 +244,71 @@ impl Clone for Route<'_> {
         }
     }
 }
+
+/// Limits on the resources that evaluation may use.
+///
+/// The defaults are generous enough for regular documents. Services that
+/// compile untrusted documents may want to lower them. When a limit is
+/// exceeded, compilation fails with an error that points to the offending
+/// expression.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Limits {
+    /// The maximum function call nesting depth.
+    ///
+    /// Evaluation is recursive, so deeper nesting could overflow the native
+    /// stack instead of failing with an error. Values above
+    /// [`Route::MAX_CALL_DEPTH`] are thus clamped to it: This limit can only be
+    /// lowered.
+    pub call_depth: usize,
+    /// The maximum number of iterations of a single `while` loop. Also limits
+    /// how many values a generator without a known end may produce, for
+    /// instance when a `for` loop iterates over it.
+    pub iterations: usize,
+    /// The maximum size in bytes of a single string, byte buffer, big integer,
+    /// array, or dictionary produced by an operator or by joining values in a
+    /// block or loop.
+    pub value_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            call_depth: Route::MAX_CALL_DEPTH,
+            iterations: 10_000,
+            value_size: 1 << 30,
+        }
+    }
+}
+
+impl Limits {
+    /// The effective maximum function call nesting depth.
+    pub(crate) fn max_call_depth(&self) -> usize {
+        self.call_depth.min(Route::MAX_CALL_DEPTH)
+    }
+
+    /// Ensure that a value does not exceed the maximum value size.
+    pub(crate) fn check_size(&self, value: Value, span: Span) -> SourceResult<Value> {
+        let limit = self.value_size;
+        if heap_size(&value) > limit {
+            bail!(error!(
+                span, "maximum value size exceeded";
+                hint: "values may be at most {limit} bytes large"
+            )
+            .into_fatal());
+        }
+        Ok(value)
+    }
+}
+
+/// Estimate how many bytes a value occupies on the heap, not counting shared
+/// nested values.
+fn heap_size(value: &Value) -> usize {
+    match value {
+        Value::Str(v) => v.len(),
+        Value::BigInt(v) => v.size(),
+        Value::Bytes(v) => v.len(),
+        Value::Array(v) => v.len().saturating_mul(std::mem::size_of::<Value>()),
+        Value::Dict(v) => v.len().saturating_mul(2 * std::mem::size_of::<Value>()),
+        _ => 0,
+    }
+}
@@ -31,7 +31,7 @@ impl Eval for ast::DestructAssignment<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.value().eval(vm)?;
-        destructure_impl(vm, self.pattern(), value, |vm, expr, value| {
+        destructure_impl(vm, self.pattern(), value, &|vm, expr, value| {
             let location = expr.access(vm)?;
             *location = value;
             Ok(())
@@ -46,12 +46,16 @@ pub(crate) fn destructure(
     pattern: ast::Pattern,
     value: Value,
 ) -> SourceResult<()> {
-    destructure_impl(vm, pattern, value, |vm, expr, value| match expr {
+    destructure_impl(vm, pattern, value, &|vm, expr, value| match expr {
         ast::Expr::Ident(ident) => {
             vm.define(ident, value);
             Ok(())
         }
-        _ => bail!(expr.span(), "nested patterns are currently not supported"),
+        _ => bail!(
+            expr.span(),
+            "expected identifier, found {}",
+            expr.to_untyped().kind().name()
+        ),
     })
 }
 
@@ -60,7 +64,7 @@ fn destructure_impl<T>(
     vm: &mut Vm,
     pattern: ast::Pattern,
     value: Value,
-    f: T,
+    f: &T,
 ) -> SourceResult<()>
 where
     T: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<()>,
@@ -83,7 +87,7 @@ fn destructure_array<F>(
     vm: &mut Vm,
     pattern: ast::Pattern,
     value: Array,
-    f: F,
+    f: &F,
     destruct: ast::Destructuring,
 ) -> SourceResult<()>
 where
@@ -100,6 +104,13 @@ where
                 f(vm, expr, v)?;
                 i += 1;
             }
+            ast::DestructuringKind::Nested(nested) => {
+                let Ok(v) = value.at(i as i64, None) else {
+                    bail!(nested.span(), "not enough elements to destructure");
+                };
+                destructure_impl(vm, ast::Pattern::Destructuring(nested), v, f)?;
+                i += 1;
+            }
             ast::DestructuringKind::Sink(spread) => {
                 let sink_size = (1 + len).checked_sub(destruct.bindings().count());
                 let sink = sink_size.and_then(|s| value.as_slice().get(i..i + s));
@@ -134,7 +145,7 @@ where
 fn destructure_dict<F>(
     vm: &mut Vm,
     dict: Dict,
-    f: F,
+    f: &F,
     destruct: ast::Destructuring,
 ) -> SourceResult<()>
 where
@@ -153,10 +164,13 @@ where
             ast::DestructuringKind::Named(named) => {
                 let name = named.name();
                 let v = dict.get(&name).at(name.span())?;
-                f(vm, named.expr(), v.clone())?;
+                destructure_impl(vm, named.pattern(), v.clone(), f)?;
                 used.insert(name.as_str());
             }
             ast::DestructuringKind::Placeholder(_) => {}
+            ast::DestructuringKind::Nested(nested) => {
+                bail!(nested.span(), "expected key, found destructuring pattern");
+            }
             ast::DestructuringKind::Normal(expr) => {
                 bail!(expr.span(), "expected key, found expression");
             }
@@ -1,12 +1,16 @@
 use comemo::{Prehashed, Tracked, TrackedMut};
 use ecow::{eco_format, EcoVec};
 
-use crate::diag::{bail, error, At, HintedStrResult, SourceResult, Trace, Tracepoint};
+use crate::diag::{
+    bail, error, At, HintedStrResult, SourceDiagnostic, SourceResult, StrResult, Trace,
+    Tracepoint,
+};
 use crate::engine::Engine;
 use crate::eval::{Access, Eval, FlowEvent, Route, Tracer, Vm};
 use crate::foundations::{
-    call_method_mut, is_mutating_method, Arg, Args, Bytes, Closure, Content, Func,
-    IntoValue, NativeElement, Scope, Scopes, Value,
+    call_method_mut, is_mutating_method, Arg, Args, BigInt, Bytes, CastInfo, Closure,
+    Content, Decimal, Func, IntoValue, NativeElement, NoneValue, PluginArg, Reflect,
+    Scope, Scopes, Type, Value,
 };
 use crate::introspection::{Introspector, Locator};
 use crate::math::{Accent, AccentElem, LrElem};
@@ -27,8 +31,14 @@ impl Eval for ast::FuncCall<'_> {
         let args = self.args();
         let trailing_comma = args.trailing_comma();
 
-        if !vm.engine.route.within(Route::MAX_CALL_DEPTH) {
-            bail!(span, "maximum function call depth exceeded");
+        let max_depth = vm.limits.max_call_depth();
+        if !vm.engine.route.within(max_depth) {
+            bail!(error!(
+                span, "maximum function call depth exceeded";
+                hint: "functions may be nested at most {max_depth} levels deep"
+            )
+            .with_code("E0012")
+            .into_fatal());
         }
 
         // Try to evaluate as a call to an associated function or field.
@@ -62,7 +72,8 @@ impl Eval for ast::FuncCall<'_> {
 
             // Handle plugins.
             if let Value::Plugin(plugin) = &target {
-                let bytes = args.all::<Bytes>()?;
+                let bytes =
+                    args.all::<PluginArg>()?.into_iter().map(Bytes::from).collect();
                 args.finish()?;
                 return Ok(plugin.call(&field, bytes).at(span)?.into_value());
             }
@@ -77,6 +88,10 @@ impl Eval for ast::FuncCall<'_> {
             // new associated function a breaking change and prioritizing fields
             // would break associated functions for certain dictionaries).
             if let Some(callee) = target.ty().scope().get(&field) {
+                if let Some(deprecation) = target.ty().scope().get_deprecation(&field) {
+                    vm.engine.tracer.warn(deprecation.warning(field_span, &field));
+                }
+
                 let this = Arg {
                     span: target_span,
                     name: None,
@@ -89,7 +104,13 @@ impl Eval for ast::FuncCall<'_> {
                 target,
                 Value::Symbol(_) | Value::Func(_) | Value::Type(_) | Value::Module(_)
             ) {
-                (target.field(&field).at(field_span)?, args)
+                let callee = target.field(&field).at(field_span)?;
+                if let Some(deprecation) =
+                    target.scope().and_then(|scope| scope.get_deprecation(&field))
+                {
+                    vm.engine.tracer.warn(deprecation.warning(field_span, &field));
+                }
+                (callee, args)
             } else {
                 let mut error = error!(
                     field_span,
@@ -192,10 +213,18 @@ impl Eval for ast::Args<'_> {
                     });
                 }
                 ast::Arg::Named(named) => {
+                    let expr = named.expr();
+                    let value = expr.eval(vm).map_err(|errors| {
+                        if named.name().as_str() == "font" {
+                            suggest_quotes(expr, errors)
+                        } else {
+                            errors
+                        }
+                    })?;
                     items.push(Arg {
                         span,
                         name: Some(named.name().get().clone().into()),
-                        value: Spanned::new(named.expr().eval(vm)?, named.expr().span()),
+                        value: Spanned::new(value, expr.span()),
                     });
                 }
                 ast::Arg::Spread(expr) => match expr.eval(vm)? {
@@ -224,6 +253,22 @@ impl Eval for ast::Args<'_> {
     }
 }
 
+/// Suggest to quote an unknown identifier that is probably meant as a string,
+/// like the font name in `text(font: Arial)`.
+fn suggest_quotes(
+    expr: ast::Expr,
+    mut errors: EcoVec<SourceDiagnostic>,
+) -> EcoVec<SourceDiagnostic> {
+    let ast::Expr::Ident(ident) = expr else { return errors };
+    for error in errors.make_mut() {
+        if error.span == ident.span() && error.message.starts_with("unknown variable") {
+            error.hint("enclose the font name in quotes to use it as a string");
+            error.fix(ident.span(), eco_format!("\"{}\"", ident.as_str()));
+        }
+    }
+    errors
+}
+
 impl Eval for ast::Closure<'_> {
     type Output = Value;
 
@@ -236,6 +281,16 @@ impl Eval for ast::Closure<'_> {
             }
         }
 
+        // Evaluate type annotations of parameters.
+        let mut types = Vec::new();
+        for (_, annotation) in self.params().annotated() {
+            let ty = match annotation {
+                Some(annotation) => Some(eval_annotation(vm, annotation)?),
+                None => None,
+            };
+            types.push(ty);
+        }
+
         // Collect captured variables.
         let captured = {
             let mut visitor = CapturesVisitor::new(Some(&vm.scopes));
@@ -247,6 +302,7 @@ impl Eval for ast::Closure<'_> {
         let closure = Closure {
             node: self.to_untyped().clone(),
             defaults,
+            types,
             captured,
         };
 
@@ -284,6 +340,8 @@ pub(crate) fn call_closure(
         tracer,
     };
 
+    engine.check_cancelled(node.span())?;
+
     // Prepare VM.
     let mut vm = Vm::new(engine, scopes, node.span());
 
@@ -305,18 +363,20 @@ pub(crate) fn call_closure(
     let mut sink = None;
     let mut sink_pos_values = None;
     let mut defaults = closure.defaults.iter();
-    for p in node.params().children() {
+    for ((p, _), types) in node.params().annotated().into_iter().zip(&closure.types) {
         match p {
             ast::Param::Pos(pattern) => match pattern {
                 ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                    vm.define(ident, args.expect::<Value>(&ident)?)
+                    let value = args.expect::<Spanned<Value>>(&ident)?;
+                    vm.define(ident, check_type(types, value)?)
                 }
                 ast::Pattern::Normal(_) => unreachable!(),
                 pattern => {
+                    let value = args.expect::<Spanned<Value>>("pattern parameter")?;
                     crate::eval::destructure(
                         &mut vm,
                         pattern,
-                        args.expect::<Value>("pattern parameter")?,
+                        check_type(types, value)?,
                     )?;
                 }
             },
@@ -329,8 +389,10 @@ pub(crate) fn call_closure(
             ast::Param::Named(named) => {
                 let name = named.name();
                 let default = defaults.next().unwrap();
-                let value =
-                    args.named::<Value>(&name)?.unwrap_or_else(|| default.clone());
+                let value = match args.named::<Spanned<Value>>(&name)? {
+                    Some(value) => check_type(types, value)?,
+                    None => default.clone(),
+                };
                 vm.define(name, value);
             }
         }
@@ -362,6 +424,57 @@ pub(crate) fn call_closure(
     Ok(output)
 }
 
+/// Evaluate the type annotation of a parameter.
+fn eval_annotation(
+    vm: &mut Vm,
+    annotation: ast::TypeAnnotation,
+) -> SourceResult<Vec<Type>> {
+    let expr = annotation.ty();
+    match expr.eval(vm)? {
+        Value::Type(ty) => Ok(vec![ty]),
+        Value::Array(array) => array
+            .into_iter()
+            .map(|value| match value {
+                Value::None => Ok(Type::of::<NoneValue>()),
+                value => value.cast::<Type>(),
+            })
+            .collect::<StrResult<_>>()
+            .at(expr.span()),
+        v => bail!(expr.span(), "expected type or array of types, found {}", v.ty()),
+    }
+}
+
+/// Check that an argument has one of the types a parameter is annotated with.
+fn check_type(types: &Option<Vec<Type>>, value: Spanned<Value>) -> SourceResult<Value> {
+    let Spanned { v: value, span } = value;
+    let Some(types) = types else { return Ok(value) };
+    if types.contains(&value.ty()) {
+        return Ok(value);
+    }
+
+    // Allow the same conversions as for the parameters of native functions.
+    if types.contains(&Type::of::<Content>()) && Content::castable(&value) {
+        return value.cast::<Content>().map(Value::Content).at(span);
+    } else if types.contains(&Type::of::<f64>()) {
+        if let Value::Int(int) = value {
+            return Ok(Value::Float(int as f64));
+        }
+    } else if types.contains(&Type::of::<Decimal>()) {
+        if let Value::Int(int) = value {
+            return Ok(Value::Decimal(int.into()));
+        }
+    } else if types.contains(&Type::of::<BigInt>()) {
+        if let Value::Int(int) = value {
+            return Ok(Value::BigInt(int.into()));
+        }
+    }
+
+    let info = types
+        .iter()
+        .fold(CastInfo::Union(vec![]), |info, &ty| info + CastInfo::Type(ty));
+    bail!(span, "{}", info.error(&value))
+}
+
 fn in_math(expr: ast::Expr) -> bool {
     match expr {
         ast::Expr::MathIdent(_) => true,
@@ -422,10 +535,13 @@ impl<'a> CapturesVisitor<'a> {
             // body is evaluated. Care must be taken so that the default values
             // of named parameters cannot access previous parameter bindings.
             Some(ast::Expr::Closure(expr)) => {
-                for param in expr.params().children() {
+                for (param, annotation) in expr.params().annotated() {
                     if let ast::Param::Named(named) = param {
                         self.visit(named.expr().to_untyped());
                     }
+                    if let Some(annotation) = annotation {
+                        self.visit(annotation.ty().to_untyped());
+                    }
                 }
 
                 self.internal.enter();
@@ -569,6 +685,7 @@ mod tests {
         test("#((..x) => x + y)", &["y"]);
         test("#((x, y: x + z) => x + y)", &["x", "z"]);
         test("#{x => x; x}", &["x"]);
+        test("#((x as y, z: 1 as x) => x + z)", &["x", "y"]);
 
         // Show rule.
         test("#show y: x => x", &["y"]);
@@ -46,6 +46,7 @@ fn eval_code<'a>(
         };
 
         output = ops::join(output, value).at(span)?;
+        output = vm.limits.check_size(output, span)?;
 
         if vm.flow.is_some() {
             break;
@@ -140,7 +141,11 @@ impl Eval for ast::Ident<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get(&self).cloned().at(self.span())
+        let value = vm.scopes.get(&self).cloned().at(self.span())?;
+        if let Some(deprecation) = vm.scopes.get_deprecation(&self) {
+            vm.engine.tracer.warn(deprecation.warning(self.span(), &self));
+        }
+        Ok(value)
     }
 }
 
@@ -297,6 +302,10 @@ impl Eval for ast::FieldAccess<'_> {
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.target().eval(vm)?;
         let field = self.field();
-        value.field(&field).at(field.span())
+        let result = value.field(&field).at(field.span())?;
+        if let Some(deprecation) = value.scope().and_then(|s| s.get_deprecation(&field)) {
+            vm.engine.tracer.warn(deprecation.warning(field.span(), &field));
+        }
+        Ok(result)
     }
 }
@@ -2,13 +2,10 @@ use unicode_segmentation::UnicodeSegmentation;
 
 use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult};
 use crate::eval::{destructure, ops, Eval, Vm};
-use crate::foundations::{IntoValue, Value};
+use crate::foundations::{Generator, IntoValue, Value};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{Span, SyntaxKind, SyntaxNode};
 
-/// The maximum number of loop iterations.
-const MAX_ITERATIONS: usize = 10_000;
-
 /// A control flow event that occurred during evaluation.
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum FlowEvent {
@@ -71,12 +68,18 @@ impl Eval for ast::WhileLoop<'_> {
                 && !can_diverge(body.to_untyped())
             {
                 bail!(condition.span(), "condition is always true");
-            } else if i >= MAX_ITERATIONS {
-                bail!(self.span(), "loop seems to be infinite");
+            } else if i >= vm.limits.iterations {
+                bail!(error!(
+                    self.span(), "loop seems to be infinite";
+                    hint: "loops may run at most {} times", vm.limits.iterations
+                )
+                .into_fatal());
             }
 
+            vm.engine.check_cancelled(self.span())?;
             let value = body.eval(vm)?;
             output = ops::join(output, value).at(body.span())?;
+            output = vm.limits.check_size(output, body.span())?;
 
             match vm.flow {
                 Some(FlowEvent::Break(_)) => {
@@ -109,15 +112,20 @@ impl Eval for ast::ForLoop<'_> {
 
         macro_rules! iter {
             (for $pat:ident in $iterable:expr) => {{
+                let mut iter = $iterable.into_iter();
+                iter!(for $pat, next iter.next())
+            }};
+            (for $pat:ident, next $next:expr) => {{
                 vm.scopes.enter();
 
-                #[allow(unused_parens)]
-                for value in $iterable {
+                while let Some(value) = $next {
+                    vm.engine.check_cancelled(self.span())?;
                     destructure(vm, $pat, value.into_value())?;
 
                     let body = self.body();
                     let value = body.eval(vm)?;
                     output = ops::join(output, value).at(body.span())?;
+                    output = vm.limits.check_size(output, body.span())?;
 
                     match vm.flow {
                         Some(FlowEvent::Break(_)) => {
@@ -156,6 +164,13 @@ impl Eval for ast::ForLoop<'_> {
                 // Iterate over the integers of bytes.
                 iter!(for pattern in bytes.as_slice());
             }
+            (_, Value::Dyn(dynamic)) if dynamic.is::<Generator>() => {
+                // Produce the values of the generator one by one, so that
+                // infinite generators can be left with `break`.
+                let mut iter = dynamic.downcast::<Generator>().unwrap().iter();
+                let span = self.iterable().span();
+                iter!(for pattern, next iter.next(&mut vm.engine, span)?);
+            }
             (Pattern::Destructuring(_), Value::Str(_) | Value::Bytes(_)) => {
                 bail!(pattern.span(), "cannot destructure values of {}", iterable_type);
             }
@@ -7,9 +7,9 @@ use crate::diag::{
 };
 use crate::eval::{eval, Eval, Vm};
 use crate::foundations::{Content, Module, Value};
+use crate::loading::{load, load_source, ResourceKind};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{FileId, PackageSpec, PackageVersion, Span, VirtualPath};
-use crate::World;
 
 impl Eval for ast::ModuleImport<'_> {
     type Output = Value;
@@ -37,10 +37,13 @@ impl Eval for ast::ModuleImport<'_> {
             if let ast::Expr::Ident(ident) = self.source() {
                 if ident.as_str() == new_name.as_str() {
                     // Warn on `import x as x`
-                    vm.engine.tracer.warn(warning!(
-                        new_name.span(),
-                        "unnecessary import rename to same name",
-                    ));
+                    vm.engine.tracer.warn(
+                        warning!(
+                            new_name.span(),
+                            "unnecessary import rename to same name",
+                        )
+                        .with_code("W0006"),
+                    );
                 }
             }
 
@@ -60,6 +63,9 @@ impl Eval for ast::ModuleImport<'_> {
             Some(ast::Imports::Wildcard) => {
                 for (var, value) in scope.iter() {
                     vm.scopes.top.define(var.clone(), value.clone());
+                    if let Some(deprecation) = scope.get_deprecation(var) {
+                        vm.scopes.top.deprecate(var, *deprecation);
+                    }
                 }
             }
             Some(ast::Imports::Items(items)) => {
@@ -72,16 +78,46 @@ impl Eval for ast::ModuleImport<'_> {
                             if renamed_item.original_name().as_str()
                                 == renamed_item.new_name().as_str()
                             {
-                                vm.engine.tracer.warn(warning!(
-                                    renamed_item.new_name().span(),
-                                    "unnecessary import rename to same name",
-                                ));
+                                vm.engine.tracer.warn(
+                                    warning!(
+                                        renamed_item.new_name().span(),
+                                        "unnecessary import rename to same name",
+                                    )
+                                    .with_code("W0006")
+                                    .with_fix(
+                                        renamed_item.span(),
+                                        renamed_item.original_name().get().clone(),
+                                    ),
+                                );
+                            }
+                        }
+
+                        if let Some(deprecation) = scope.get_deprecation(&original_ident)
+                        {
+                            let mut warning = deprecation
+                                .warning(original_ident.span(), &original_ident);
+                            // Keep the bound name, so that its uses stay valid.
+                            if let (ast::ImportItem::Simple(_), Some(replacement)) =
+                                (&item, deprecation.replacement())
+                            {
+                                warning.fixes.clear();
+                                warning.fix(
+                                    original_ident.span(),
+                                    eco_format!(
+                                        "{replacement} as {}",
+                                        original_ident.as_str()
+                                    ),
+                                );
                             }
+                            vm.engine.tracer.warn(warning);
                         }
 
                         vm.define(item.bound_name(), value.clone());
                     } else {
-                        errors.push(error!(original_ident.span(), "unresolved import"));
+                        errors.push(
+                            error!(original_ident.span(), "unresolved import")
+                                .with_code("E0008"),
+                        );
                     }
                 }
                 if !errors.is_empty() {
@@ -135,13 +171,13 @@ pub fn import(
 fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
     // Evaluate the manifest.
     let manifest_id = FileId::new(Some(spec.clone()), VirtualPath::new("typst.toml"));
-    let bytes = vm.world().file(manifest_id).at(span)?;
+    let bytes = load(vm.world(), manifest_id, ResourceKind::Source).at(span)?;
     let manifest = PackageManifest::parse(&bytes).at(span)?;
     manifest.validate(&spec).at(span)?;
 
     // Evaluate the entry point.
     let entrypoint_id = manifest_id.join(&manifest.package.entrypoint);
-    let source = vm.world().source(entrypoint_id).at(span)?;
+    let source = load_source(vm.world(), entrypoint_id).at(span)?;
     let point = || Tracepoint::Import;
     Ok(eval(
         vm.world(),
@@ -158,7 +194,7 @@ fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
     // Load the source file.
     let world = vm.world();
     let id = span.resolve_path(path).at(span)?;
-    let source = world.source(id).at(span)?;
+    let source = load_source(world, id).at(span)?;
 
     // Prevent cyclic importing.
     if vm.engine.route.contains(source.id()) {
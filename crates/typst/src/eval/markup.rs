@@ -131,12 +131,13 @@ impl Eval for ast::Strong<'_> {
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let body = self.body();
         if body.exprs().next().is_none() {
-            vm.engine
-                .tracer
-                .warn(warning!(
+            vm.engine.tracer.warn(
+                warning!(
                     self.span(), "no text within stars";
                     hint: "using multiple consecutive stars (e.g. **) has no additional effect",
-                ));
+                )
+                .with_code("W0005"),
+            );
         }
 
         Ok(StrongElem::new(body.eval(vm)?).pack())
@@ -149,12 +150,13 @@ impl Eval for ast::Emph<'_> {
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let body = self.body();
         if body.exprs().next().is_none() {
-            vm.engine
-                .tracer
-                .warn(warning!(
+            vm.engine.tracer.warn(
+                warning!(
                     self.span(), "no text within underscores";
                     hint: "using multiple consecutive underscores (e.g. __) has no additional effect"
-                ));
+                )
+                .with_code("W0005"),
+            );
         }
 
         Ok(EmphElem::new(body.eval(vm)?).pack())
@@ -22,7 +22,11 @@ impl Eval for ast::MathIdent<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get_in_math(&self).cloned().at(self.span())
+        let value = vm.scopes.get_in_math(&self).cloned().at(self.span())?;
+        if let Some(deprecation) = vm.scopes.get_deprecation_in_math(&self) {
+            vm.engine.tracer.warn(deprecation.warning(self.span(), &self));
+        }
+        Ok(value)
     }
 }
 
@@ -30,7 +30,9 @@ use crate::engine::{Engine, Route};
 use crate::foundations::{Cast, Module, NativeElement, Scope, Scopes, Value};
 use crate::introspection::{Introspector, Locator};
 use crate::math::EquationElem;
-use crate::syntax::{ast, parse, parse_code, parse_math, Source, Span};
+use crate::syntax::{
+    ast, parse, parse_code, parse_math, Source, Span, SyntaxKind, SyntaxNode,
+};
 use crate::World;
 
 /// Evaluate a source file and return the resulting module.
@@ -100,6 +102,35 @@ pub fn eval_string(
     span: Span,
     mode: EvalMode,
     scope: Scope,
+) -> SourceResult<Value> {
+    eval_string_impl(world, string, span, mode, scope, false)
+}
+
+/// Evaluate a string without access to the standard library and without
+/// being able to import or include modules. Only the definitions in `scope`
+/// are available.
+///
+/// Everything in the output is associated with the given `span`.
+#[comemo::memoize]
+pub fn eval_string_restricted(
+    world: Tracked<dyn World + '_>,
+    string: &str,
+    span: Span,
+    mode: EvalMode,
+    scope: Scope,
+) -> SourceResult<Value> {
+    eval_string_impl(world, string, span, mode, scope, true)
+}
+
+/// The shared implementation of [`eval_string`] and
+/// [`eval_string_restricted`].
+fn eval_string_impl(
+    world: Tracked<dyn World + '_>,
+    string: &str,
+    span: Span,
+    mode: EvalMode,
+    scope: Scope,
+    restricted: bool,
 ) -> SourceResult<Value> {
     let mut root = match mode {
         EvalMode::Code => parse_code(string),
@@ -115,6 +146,16 @@ pub fn eval_string(
         return Err(errors.into_iter().map(Into::into).collect());
     }
 
+    // Restricted code must not be able to access files.
+    if restricted {
+        if let Some(node) = find_module_access(&root) {
+            bail!(
+                node.span(),
+                "cannot import or include modules in restricted evaluation"
+            );
+        }
+    }
+
     // Prepare the engine.
     let mut tracer = Tracer::new();
     let mut locator = Locator::new();
@@ -128,7 +169,7 @@ pub fn eval_string(
     };
 
     // Prepare VM.
-    let scopes = Scopes::new(Some(world.library()));
+    let scopes = Scopes::new((!restricted).then(|| &**world.library()));
     let mut vm = Vm::new(engine, scopes, root.span());
     vm.scopes.scopes.push(scope);
 
@@ -153,6 +194,14 @@ pub fn eval_string(
     Ok(output)
 }
 
+/// Find an import or include in a syntax tree.
+fn find_module_access(node: &SyntaxNode) -> Option<&SyntaxNode> {
+    if matches!(node.kind(), SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude) {
+        return Some(node);
+    }
+    node.children().find_map(find_module_access)
+}
+
 /// In which mode to evaluate a string.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum EvalMode {
@@ -6,9 +6,12 @@ use ecow::eco_format;
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::eval::{access_dict, Access, Eval, Vm};
-use crate::foundations::{format_str, Datetime, IntoValue, Regex, Repr, Value};
+use crate::foundations::{
+    self, format_str, CustomElem, CustomOp, Datetime, Func, IntoValue, Regex, Repr, Value,
+};
 use crate::layout::{Alignment, Length, Rel};
 use crate::syntax::ast::{self, AstNode};
+use crate::syntax::Span;
 use crate::text::TextElem;
 use crate::util::Numeric;
 use crate::visualize::Stroke;
@@ -18,6 +21,13 @@ impl Eval for ast::Unary<'_> {
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.expr().eval(vm)?;
+        if self.op() == ast::UnOp::Neg {
+            let custom = CustomElem::element_of(&value).and_then(|e| e.op(CustomOp::Neg));
+            if let Some(func) = custom.cloned() {
+                return func.call(&mut vm.engine, [value]);
+            }
+        }
+
         let result = match self.op() {
             ast::UnOp::Pos => pos(value),
             ast::UnOp::Neg => neg(value),
@@ -71,7 +81,11 @@ fn apply_binary(
     }
 
     let rhs = binary.rhs().eval(vm)?;
-    op(lhs, rhs).at(binary.span())
+    let value = match find_overload(binary.op(), &lhs, &rhs) {
+        Some(func) => call_overload(vm, &func, binary.op(), lhs, rhs, binary.span())?,
+        None => op(lhs, rhs).at(binary.span())?,
+    };
+    vm.limits.check_size(value, binary.span())
 }
 
 /// Apply an assignment operation.
@@ -93,12 +107,79 @@ fn apply_assignment(
         }
     }
 
+    let limits = vm.limits;
     let location = binary.lhs().access(vm)?;
+    if let Some(func) = find_overload(binary.op(), location, &rhs) {
+        // The overloading function needs the engine, so we can't hold on to
+        // the location while calling it and access it again afterwards.
+        let lhs = location.clone();
+        let value = call_overload(vm, &func, binary.op(), lhs, rhs, binary.span())?;
+        *binary.lhs().access(vm)? = limits.check_size(value, binary.span())?;
+        return Ok(Value::None);
+    }
+
     let lhs = std::mem::take(&mut *location);
-    *location = op(lhs, rhs).at(binary.span())?;
+    let value = op(lhs, rhs).at(binary.span())?;
+    *location = limits.check_size(value, binary.span())?;
     Ok(Value::None)
 }
 
+/// Find the function with which a custom element overloads a binary operator
+/// for the given operands.
+fn find_overload(op: ast::BinOp, lhs: &Value, rhs: &Value) -> Option<Func> {
+    let custom = match op {
+        ast::BinOp::Add | ast::BinOp::AddAssign => CustomOp::Add,
+        ast::BinOp::Sub | ast::BinOp::SubAssign => CustomOp::Sub,
+        ast::BinOp::Mul | ast::BinOp::MulAssign => CustomOp::Mul,
+        ast::BinOp::Div | ast::BinOp::DivAssign => CustomOp::Div,
+        ast::BinOp::Lt | ast::BinOp::Leq | ast::BinOp::Gt | ast::BinOp::Geq => {
+            CustomOp::Compare
+        }
+        // Equality is only overloaded between instances of the same element.
+        ast::BinOp::Eq | ast::BinOp::Neq
+            if CustomElem::element_of(lhs) == CustomElem::element_of(rhs) =>
+        {
+            CustomOp::Compare
+        }
+        _ => return None,
+    };
+
+    CustomElem::element_of(lhs)
+        .and_then(|elem| elem.op(custom))
+        .or_else(|| CustomElem::element_of(rhs).and_then(|elem| elem.op(custom)))
+        .cloned()
+}
+
+/// Call the function with which a custom element overloads a binary operator.
+fn call_overload(
+    vm: &mut Vm,
+    func: &Func,
+    op: ast::BinOp,
+    lhs: Value,
+    rhs: Value,
+    span: Span,
+) -> SourceResult<Value> {
+    let result = func.call(&mut vm.engine, [lhs, rhs])?;
+    let ordering = match op {
+        ast::BinOp::Eq
+        | ast::BinOp::Neq
+        | ast::BinOp::Lt
+        | ast::BinOp::Leq
+        | ast::BinOp::Gt
+        | ast::BinOp::Geq => result.cast::<i64>().at(span)?.cmp(&0),
+        _ => return Ok(result),
+    };
+
+    Ok(Value::Bool(match op {
+        ast::BinOp::Eq => ordering.is_eq(),
+        ast::BinOp::Neq => ordering.is_ne(),
+        ast::BinOp::Lt => ordering.is_lt(),
+        ast::BinOp::Leq => ordering.is_le(),
+        ast::BinOp::Gt => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }))
+}
+
 /// Bail with a type mismatch error.
 macro_rules! mismatch {
     ($fmt:expr, $($value:expr),* $(,)?) => {
@@ -139,6 +220,8 @@ pub fn pos(value: Value) -> StrResult<Value> {
     Ok(match value {
         Int(v) => Int(v),
         Float(v) => Float(v),
+        Decimal(v) => Decimal(v),
+        BigInt(v) => BigInt(v),
         Length(v) => Length(v),
         Angle(v) => Angle(v),
         Ratio(v) => Ratio(v),
@@ -164,6 +247,8 @@ pub fn neg(value: Value) -> StrResult<Value> {
     Ok(match value {
         Int(v) => Int(v.checked_neg().ok_or_else(too_large)?),
         Float(v) => Float(-v),
+        Decimal(v) => Decimal(-v),
+        BigInt(v) => BigInt(-v),
         Length(v) => Length(-v),
         Angle(v) => Angle(-v),
         Ratio(v) => Ratio(-v),
@@ -187,6 +272,16 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Float(a), Int(b)) => Float(a + b as f64),
         (Float(a), Float(b)) => Float(a + b),
 
+        (Decimal(a), Decimal(b)) => Decimal(a.checked_add(b).ok_or_else(too_large)?),
+        (Decimal(a), Int(b)) => Decimal(a.checked_add(b.into()).ok_or_else(too_large)?),
+        (Int(a), Decimal(b)) => {
+            Decimal(foundations::Decimal::from(a).checked_add(b).ok_or_else(too_large)?)
+        }
+
+        (BigInt(a), BigInt(b)) => BigInt(a + b),
+        (BigInt(a), Int(b)) => BigInt(a + b.into()),
+        (Int(a), BigInt(b)) => BigInt(foundations::BigInt::from(a) + b),
+
         (Angle(a), Angle(b)) => Angle(a + b),
 
         (Length(a), Length(b)) => Length(a + b),
@@ -216,6 +311,7 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
+        (Styles(a), Styles(b)) => Styles(a + b),
 
         (Color(color), Length(thickness)) | (Length(thickness), Color(color)) => {
             Stroke::from_pair(color, thickness).into_value()
@@ -260,6 +356,16 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Float(a), Int(b)) => Float(a - b as f64),
         (Float(a), Float(b)) => Float(a - b),
 
+        (Decimal(a), Decimal(b)) => Decimal(a.checked_sub(b).ok_or_else(too_large)?),
+        (Decimal(a), Int(b)) => Decimal(a.checked_sub(b.into()).ok_or_else(too_large)?),
+        (Int(a), Decimal(b)) => {
+            Decimal(foundations::Decimal::from(a).checked_sub(b).ok_or_else(too_large)?)
+        }
+
+        (BigInt(a), BigInt(b)) => BigInt(a - b),
+        (BigInt(a), Int(b)) => BigInt(a - b.into()),
+        (Int(a), BigInt(b)) => BigInt(foundations::BigInt::from(a) - b),
+
         (Angle(a), Angle(b)) => Angle(a - b),
 
         (Length(a), Length(b)) => Length(a - b),
@@ -293,6 +399,16 @@ pub fn mul(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Float(a), Int(b)) => Float(a * b as f64),
         (Float(a), Float(b)) => Float(a * b),
 
+        (Decimal(a), Decimal(b)) => Decimal(a.checked_mul(b).ok_or_else(too_large)?),
+        (Decimal(a), Int(b)) => Decimal(a.checked_mul(b.into()).ok_or_else(too_large)?),
+        (Int(a), Decimal(b)) => {
+            Decimal(foundations::Decimal::from(a).checked_mul(b).ok_or_else(too_large)?)
+        }
+
+        (BigInt(a), BigInt(b)) => BigInt(a * b),
+        (BigInt(a), Int(b)) => BigInt(a * b.into()),
+        (Int(a), BigInt(b)) => BigInt(foundations::BigInt::from(a) * b),
+
         (Length(a), Int(b)) => Length(a * b as f64),
         (Length(a), Float(b)) => Length(a * b),
         (Length(a), Ratio(b)) => Length(a * b.get()),
@@ -356,6 +472,12 @@ pub fn div(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Float(a), Int(b)) => Float(a / b as f64),
         (Float(a), Float(b)) => Float(a / b),
 
+        (Decimal(a), Decimal(b)) => Decimal(a.checked_div(b).ok_or_else(too_large)?),
+        (Decimal(a), Int(b)) => Decimal(a.checked_div(b.into()).ok_or_else(too_large)?),
+        (Int(a), Decimal(b)) => {
+            Decimal(foundations::Decimal::from(a).checked_div(b).ok_or_else(too_large)?)
+        }
+
         (Length(a), Int(b)) => Length(a / b as f64),
         (Length(a), Float(b)) => Length(a / b),
         (Length(a), Length(b)) => Float(try_div_length(a, b)?),
@@ -394,6 +516,7 @@ fn is_zero(v: &Value) -> bool {
     match *v {
         Int(v) => v == 0,
         Float(v) => v == 0.0,
+        Decimal(v) => v.is_zero(),
         Length(v) => v.is_zero(),
         Angle(v) => v.is_zero(),
         Ratio(v) => v.is_zero(),
@@ -474,6 +597,8 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Bool(a), Bool(b)) => a == b,
         (Int(a), Int(b)) => a == b,
         (Float(a), Float(b)) => a == b,
+        (Decimal(a), Decimal(b)) => a == b,
+        (BigInt(a), BigInt(b)) => a == b,
         (Length(a), Length(b)) => a == b,
         (Angle(a), Angle(b)) => a == b,
         (Ratio(a), Ratio(b)) => a == b,
@@ -490,6 +615,7 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Dict(a), Dict(b)) => a == b,
         (Func(a), Func(b)) => a == b,
         (Args(a), Args(b)) => a == b,
+        (Styles(a), Styles(b)) => a == b,
         (Type(a), Type(b)) => a == b,
         (Module(a), Module(b)) => a == b,
         (Plugin(a), Plugin(b)) => a == b,
@@ -500,6 +626,10 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         // Some technically different things should compare equal.
         (&Int(a), &Float(b)) => a as f64 == b,
         (&Float(a), &Int(b)) => a == b as f64,
+        (&Decimal(a), &Int(b)) => a == b.into(),
+        (&Int(a), &Decimal(b)) => b == a.into(),
+        (BigInt(a), &Int(b)) => *a == b.into(),
+        (&Int(a), BigInt(b)) => *b == a.into(),
         (&Length(a), &Relative(b)) => a == b.abs && b.rel.is_zero(),
         (&Ratio(a), &Relative(b)) => a == b.rel && b.abs.is_zero(),
         (&Relative(a), &Length(b)) => a.abs == b && a.rel.is_zero(),
@@ -520,6 +650,8 @@ pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
         (Bool(a), Bool(b)) => a.cmp(b),
         (Int(a), Int(b)) => a.cmp(b),
         (Float(a), Float(b)) => try_cmp_values(a, b)?,
+        (Decimal(a), Decimal(b)) => a.cmp(b),
+        (BigInt(a), BigInt(b)) => a.cmp(b),
         (Length(a), Length(b)) => try_cmp_values(a, b)?,
         (Angle(a), Angle(b)) => a.cmp(b),
         (Ratio(a), Ratio(b)) => a.cmp(b),
@@ -531,6 +663,10 @@ pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
         // Some technically different things should be comparable.
         (Int(a), Float(b)) => try_cmp_values(&(*a as f64), b)?,
         (Float(a), Int(b)) => try_cmp_values(a, &(*b as f64))?,
+        (Decimal(a), Int(b)) => a.cmp(&(*b).into()),
+        (Int(a), Decimal(b)) => foundations::Decimal::from(*a).cmp(b),
+        (BigInt(a), Int(b)) => a.cmp(&(*b).into()),
+        (Int(a), BigInt(b)) => foundations::BigInt::from(*a).cmp(b),
         (Length(a), Relative(b)) if b.rel.is_zero() => try_cmp_values(a, &b.abs)?,
         (Ratio(a), Relative(b)) if b.abs.is_zero() => a.cmp(&b.rel),
         (Relative(a), Length(b)) if a.rel.is_zero() => try_cmp_values(&a.abs, b)?,
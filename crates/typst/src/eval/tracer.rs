@@ -1,20 +1,26 @@
 use std::collections::HashSet;
 
-use ecow::EcoVec;
+use comemo::TrackedMut;
+use ecow::{EcoString, EcoVec};
 
 use crate::diag::SourceDiagnostic;
-use crate::foundations::Value;
+use crate::foundations::{Bytes, Value};
+use crate::introspection::Location;
 use crate::syntax::{FileId, Span};
 use crate::util::hash128;
 
-/// Traces warnings and which values existed for an expression at a span.
+/// Traces warnings, written files, and which values existed for an expression
+/// at a span.
 #[derive(Default, Clone)]
 pub struct Tracer {
     inspected: Option<Span>,
+    overflow: bool,
     warnings: EcoVec<SourceDiagnostic>,
     warnings_set: HashSet<u128>,
     delayed: EcoVec<SourceDiagnostic>,
     values: EcoVec<Value>,
+    outputs: EcoVec<(FileId, Bytes)>,
+    observed: EcoVec<(EcoString, Location, u128)>,
 }
 
 impl Tracer {
@@ -36,16 +42,71 @@ impl Tracer {
         self.warnings
     }
 
+    /// Get the files that the document wrote with the `write` function.
+    pub fn outputs(&mut self) -> EcoVec<(FileId, Bytes)> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    /// Get the values of states, counters, and queries observed during layout.
+    ///
+    /// Each entry consists of a description of what was observed, like
+    /// `state "x"`, the location at which it was observed and a hash of its
+    /// value there.
+    pub fn observed(&mut self) -> EcoVec<(EcoString, Location, u128)> {
+        std::mem::take(&mut self.observed)
+    }
+
     /// Mark a span as inspected. All values observed for this span can be
     /// retrieved via `values` later.
     pub fn inspect(&mut self, span: Span) {
         self.inspected = Some(span);
     }
 
+    /// Enable warnings about content that overflows the space that is
+    /// available to it, like lines that are too wide for their paragraph.
+    ///
+    /// These warnings are disabled by default, as overflowing content is
+    /// often intentional.
+    pub fn warn_overflow(&mut self) {
+        self.overflow = true;
+    }
+
     /// Get the values for the inspected span.
     pub fn values(self) -> EcoVec<Value> {
         self.values
     }
+
+    /// Create a tracer for a task that runs on another thread.
+    ///
+    /// It inspects the same span and has the same warnings enabled as the
+    /// given one, but has nothing recorded yet.
+    pub fn fork(parent: &TrackedMut<Self>) -> Self {
+        Self {
+            inspected: parent.inspected_span(),
+            overflow: parent.overflow(),
+            ..Self::default()
+        }
+    }
+
+    /// Record everything that this tracer recorded in another one.
+    ///
+    /// This merges the tracer of a [forked](Self::fork) task back into the
+    /// one it was forked from.
+    pub fn replay(self, mut into: TrackedMut<Self>) {
+        into.delay(self.delayed);
+        for warning in self.warnings {
+            into.warn(warning);
+        }
+        for (id, data) in self.outputs {
+            into.write(id, data);
+        }
+        for (what, location, hash) in self.observed {
+            into.observe(what, location, hash);
+        }
+        for value in self.values {
+            into.value(value);
+        }
+    }
 }
 
 #[comemo::track]
@@ -64,6 +125,23 @@ impl Tracer {
         }
     }
 
+    /// Record a file written by the document.
+    ///
+    /// A later write to the same file replaces the earlier one. This way, files
+    /// are not duplicated when the document is laid out multiple times.
+    pub fn write(&mut self, id: FileId, data: Bytes) {
+        if let Some(output) = self.outputs.make_mut().iter_mut().find(|(i, _)| *i == id) {
+            output.1 = data;
+        } else {
+            self.outputs.push((id, data));
+        }
+    }
+
+    /// Record the value of a state, counter, or query at a location.
+    pub fn observe(&mut self, what: EcoString, location: Location, hash: u128) {
+        self.observed.push((what, location, hash));
+    }
+
     /// The inspected span if it is part of the given source file.
     pub fn inspected(&self, id: FileId) -> Option<Span> {
         if self.inspected.and_then(Span::id) == Some(id) {
@@ -73,6 +151,16 @@ impl Tracer {
         }
     }
 
+    /// The inspected span, regardless of the file it is in.
+    pub fn inspected_span(&self) -> Option<Span> {
+        self.inspected
+    }
+
+    /// Whether warnings about overflowing content are enabled.
+    pub fn overflow(&self) -> bool {
+        self.overflow
+    }
+
     /// Trace a value for the span.
     pub fn value(&mut self, v: Value) {
         if self.values.len() < Self::MAX_VALUES {
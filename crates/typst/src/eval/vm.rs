@@ -1,6 +1,6 @@
 use comemo::Tracked;
 
-use crate::engine::Engine;
+use crate::engine::{Engine, Limits};
 use crate::eval::FlowEvent;
 use crate::foundations::{IntoValue, Scopes};
 use crate::syntax::ast::{self, AstNode};
@@ -20,13 +20,16 @@ pub struct Vm<'a> {
     pub(crate) scopes: Scopes<'a>,
     /// A span that is currently under inspection.
     pub(crate) inspected: Option<Span>,
+    /// The limits on the resources that evaluation may use.
+    pub(crate) limits: Limits,
 }
 
 impl<'a> Vm<'a> {
     /// Create a new virtual machine.
     pub fn new(engine: Engine<'a>, scopes: Scopes<'a>, target: Span) -> Self {
         let inspected = target.id().and_then(|id| engine.tracer.inspected(id));
-        Self { engine, flow: None, scopes, inspected }
+        let limits = engine.world.library().limits;
+        Self { engine, flow: None, scopes, inspected, limits }
     }
 
     /// Access the underlying world.
@@ -2,9 +2,10 @@ use std::fmt::{self, Debug, Formatter};
 
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
-use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult};
+use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult, StrResult};
 use crate::foundations::{
-    func, repr, scope, ty, Array, Dict, FromValue, IntoValue, Repr, Str, Value,
+    cast, func, repr, scope, ty, Array, Dict, FromValue, Func, IntoValue, Repr, Str,
+    Value,
 };
 use crate::syntax::{Span, Spanned};
 
@@ -28,6 +29,28 @@ use crate::syntax::{Span, Spanned};
 /// #format("ArtosFlow", "Jane", "Joe")
 /// ```
 ///
+/// # Wrapper Functions
+/// Argument sinks make it easy to write functions that wrap others. Such a
+/// function can [check]($arguments.check) which arguments it received,
+/// inspect individual ones with [`at`]($arguments.at), and pass subsets on to
+/// other functions with [`only`]($arguments.only) and
+/// [`without`]($arguments.without).
+///
+/// ```example
+/// #let note(..args) = {
+///   args.check(box, "tone")
+///   let tone = args.at("tone", default: blue)
+///   box(
+///     fill: tone.lighten(80%),
+///     inset: 4pt,
+///     ..args.without("tone"),
+///   )
+/// }
+///
+/// #note(tone: green)[Done] \
+/// #note(radius: 4pt)[Pending]
+/// ```
+///
 /// # Spreading
 /// Inversely to an argument sink, you can _spread_ arguments, arrays and
 /// dictionaries into a function call with the `..spread` operator:
@@ -138,7 +161,7 @@ impl Args {
             }
         }
 
-        error!(self.span, "missing argument: {what}")
+        error!(self.span, "missing argument: {what}").with_code("E0007")
     }
 
     /// Find and consume the first castable positional argument.
@@ -227,8 +250,11 @@ impl Args {
     pub fn finish(self) -> SourceResult<()> {
         if let Some(arg) = self.items.first() {
             match &arg.name {
-                Some(name) => bail!(arg.span, "unexpected argument: {name}"),
-                _ => bail!(arg.span, "unexpected argument"),
+                Some(name) => {
+                    bail!(error!(arg.span, "unexpected argument: {name}")
+                        .with_code("E0006"))
+                }
+                _ => bail!(error!(arg.span, "unexpected argument").with_code("E0006")),
             }
         }
         Ok(())
@@ -276,6 +302,194 @@ impl Args {
             .filter_map(|item| item.name.clone().map(|name| (name, item.value.v.clone())))
             .collect()
     }
+
+    /// Returns the positional argument at the given index or the named
+    /// argument with the given name.
+    ///
+    /// Fails with an error if there is no such argument, unless a default
+    /// value is specified.
+    ///
+    /// ```example
+    /// #let args = arguments(1, 2, fill: red)
+    /// #args.at(0), #args.at(-1) \
+    /// #args.at("fill") \
+    /// #args.at("stroke", default: none)
+    /// ```
+    #[func]
+    pub fn at(
+        &self,
+        /// The index of a positional argument or the name of a named
+        /// argument. An index may be negative to count from the back.
+        key: ArgumentKey,
+        /// A default value to return if there is no such argument.
+        #[named]
+        default: Option<Value>,
+    ) -> StrResult<Value> {
+        let found = match &key {
+            ArgumentKey::Index(index) => {
+                let pos = self.to_pos();
+                pos.at(*index, None).ok()
+            }
+            ArgumentKey::Name(name) => self
+                .items
+                .iter()
+                .rev()
+                .find(|item| item.name.as_ref() == Some(name))
+                .map(|item| item.value.v.clone()),
+        };
+
+        found.or(default).ok_or_else(|| match key {
+            ArgumentKey::Index(index) => eco_format!(
+                "positional argument index out of bounds (index: {index}, len: {})",
+                self.remaining(),
+            ),
+            ArgumentKey::Name(name) => {
+                eco_format!("arguments do not contain named argument `{name}`")
+            }
+        })
+    }
+
+    /// Returns just the named arguments with the given names.
+    ///
+    /// Positional arguments are not included.
+    ///
+    /// ```example
+    /// #let args = arguments([Hi], fill: red, stroke: blue, inset: 5pt)
+    /// #args.only("fill", "inset")
+    /// ```
+    #[func]
+    pub fn only(
+        &self,
+        /// The names of the arguments to keep.
+        #[variadic]
+        names: Vec<Str>,
+    ) -> Args {
+        self.filtered(|item| item.name.as_ref().map_or(false, |n| names.contains(n)))
+    }
+
+    /// Returns all arguments except for the named arguments with the given
+    /// names.
+    ///
+    /// ```example
+    /// #let args = arguments([Hi], fill: red, stroke: blue)
+    /// #args.without("stroke")
+    /// ```
+    #[func]
+    pub fn without(
+        &self,
+        /// The names of the arguments to remove.
+        #[variadic]
+        names: Vec<Str>,
+    ) -> Args {
+        self.filtered(|item| item.name.as_ref().map_or(true, |n| !names.contains(n)))
+    }
+
+    /// Checks that all named arguments are expected and returns the arguments
+    /// unchanged.
+    ///
+    /// Fails with an error for the first unexpected named argument. If its
+    /// name is similar to an expected one, the error suggests that name.
+    ///
+    /// ```example
+    /// #let args = arguments(fill: red, inset: 5pt)
+    /// #box(..args.check(box))
+    /// ```
+    #[func]
+    pub fn check(
+        self,
+        /// The expected names. Functions stand for the names of their named
+        /// parameters, so a wrapper can accept everything that the wrapped
+        /// function accepts.
+        #[variadic]
+        expected: Vec<ExpectedNames>,
+    ) -> SourceResult<Args> {
+        let expected: Vec<EcoString> =
+            expected.into_iter().flat_map(|names| names.0).collect();
+
+        for item in &self.items {
+            let Some(name) = &item.name else { continue };
+            if expected.iter().any(|e| e.as_str() == name.as_str()) {
+                continue;
+            }
+
+            let mut diag =
+                error!(item.span, "unexpected argument: {name}").with_code("E0006");
+            if let Some(similar) = most_similar(name, &expected) {
+                diag.hint(eco_format!("did you mean `{similar}`?"));
+            }
+            return Err(eco_vec![diag]);
+        }
+
+        Ok(self)
+    }
+}
+
+impl Args {
+    /// The arguments for which the filter returns `true`.
+    fn filtered(&self, f: impl Fn(&Arg) -> bool) -> Self {
+        Self {
+            span: self.span,
+            items: self.items.iter().filter(|item| f(item)).cloned().collect(),
+        }
+    }
+}
+
+/// Identifies a positional or named argument.
+pub enum ArgumentKey {
+    /// The index of a positional argument.
+    Index(i64),
+    /// The name of a named argument.
+    Name(Str),
+}
+
+cast! {
+    ArgumentKey,
+    v: i64 => Self::Index(v),
+    v: Str => Self::Name(v),
+}
+
+/// Names of named arguments that a function expects.
+pub struct ExpectedNames(Vec<EcoString>);
+
+cast! {
+    ExpectedNames,
+    v: Str => Self(vec![v.into()]),
+    v: Func => Self(
+        v.params()
+            .ok_or("cannot determine the parameters of this function")?
+            .iter()
+            .filter(|param| param.named)
+            .map(|param| param.name.into())
+            .collect()
+    ),
+}
+
+/// The candidate that is most similar to the given name, if any is
+/// sufficiently similar.
+fn most_similar<'a>(name: &str, candidates: &'a [EcoString]) -> Option<&'a str> {
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= threshold)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let next = (prev + usize::from(ca != cb)).min(row[j] + 1).min(row[j + 1] + 1);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
 }
 
 impl Debug for Args {
@@ -1,18 +1,18 @@
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
-use std::num::NonZeroI64;
+use std::num::{NonZeroI64, NonZeroUsize};
 use std::ops::{Add, AddAssign};
 
 use ecow::{eco_format, EcoString, EcoVec};
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-use crate::diag::{At, SourceResult, StrResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::ops;
 use crate::foundations::{
     cast, func, repr, scope, ty, Args, Bytes, CastInfo, FromValue, Func, IntoValue,
-    Reflect, Repr, Value, Version,
+    Reflect, Repr, Rng, Seed, Value, Version,
 };
 use crate::syntax::Span;
 
@@ -704,6 +704,36 @@ impl Array {
         Array(vec)
     }
 
+    /// Splits an array into non-overlapping chunks, starting at the beginning,
+    /// ending with a single remainder chunk.
+    ///
+    /// All chunks but the last have `chunk-size` elements. If `exact` is set
+    /// to `{true}`, the remainder is dropped if it contains less than
+    /// `chunk-size` elements.
+    ///
+    /// ```example
+    /// #let array = (1, 2, 3, 4, 5, 6, 7, 8)
+    /// #array.chunks(3) \
+    /// #array.chunks(3, exact: true)
+    /// ```
+    #[func]
+    pub fn chunks(
+        self,
+        /// How many elements each chunk may at most contain.
+        chunk_size: NonZeroUsize,
+        /// Whether to keep the remainder if its size is less than `chunk-size`.
+        #[named]
+        #[default(false)]
+        exact: bool,
+    ) -> Array {
+        let to_array = |chunk: &[Value]| Value::Array(chunk.into());
+        if exact {
+            self.0.chunks_exact(chunk_size.get()).map(to_array).collect()
+        } else {
+            self.0.chunks(chunk_size.get()).map(to_array).collect()
+        }
+    }
+
     /// Return a sorted version of this array, optionally by a given key
     /// function. The sorting algorithm used is stable.
     ///
@@ -796,6 +826,62 @@ impl Array {
 
         Ok(Self(out))
     }
+
+    /// Returns a new array with the same items in a pseudorandom order.
+    ///
+    /// The order is derived from the seed, so the same seed always produces
+    /// the same order. See [`calc.random`]($calc.random) for more details on
+    /// seeds.
+    ///
+    /// ```example
+    /// #range(1, 7).shuffle(42) \
+    /// #("a", "b", "c").shuffle("exam-b")
+    /// ```
+    #[func]
+    pub fn shuffle(
+        self,
+        /// The seed to derive the order from.
+        seed: Seed,
+    ) -> Array {
+        let mut vec = self.0;
+        Rng::new(seed).shuffle(vec.make_mut());
+        Self(vec)
+    }
+
+    /// Returns `count` pseudorandomly chosen items from the array.
+    ///
+    /// Each item is chosen at most once. The items are returned in the order
+    /// in which they were chosen. The choice is derived from the seed, so the
+    /// same seed always produces the same items. See
+    /// [`calc.random`]($calc.random) for more details on seeds.
+    ///
+    /// ```example
+    /// #range(1, 50).sample(42, 6) \
+    /// #("red", "green", "blue").sample("exam-b", 1).first()
+    /// ```
+    #[func]
+    pub fn sample(
+        self,
+        /// The seed to derive the choice from.
+        seed: Seed,
+        /// How many items to choose. Must not be larger than the array's
+        /// length.
+        count: usize,
+    ) -> StrResult<Array> {
+        if count > self.len() {
+            bail!("cannot sample {count} items from an array of length {}", self.len());
+        }
+
+        let mut vec = self.0;
+        let items = vec.make_mut();
+        let mut rng = Rng::new(seed);
+        for i in 0..count {
+            let j = i + rng.below((items.len() - i) as u64) as usize;
+            items.swap(i, j);
+        }
+        vec.truncate(count);
+        Ok(Self(vec))
+    }
 }
 
 /// A value that can be cast to bytes.
@@ -0,0 +1,298 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+use num_bigint::{BigUint, Sign};
+
+use crate::diag::{bail, error, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{cast, func, repr, scope, ty, Decimal, Repr, Str};
+use crate::syntax::Span;
+
+/// An arbitrary-precision integer.
+///
+/// Unlike [integers]($int), which are limited to 64 bits, big integers can
+/// grow as large as needed. This makes them well-suited for number theory,
+/// for example to compute large factorials or powers exactly.
+///
+/// Big integers can be added, subtracted, multiplied, and compared with each
+/// other and with integers. The result of such an operation is always a big
+/// integer. Since the division of two integers is generally not a whole
+/// number, big integers can't be divided with `/`. Use the
+/// [`quo`]($bigint.quo) and [`rem`]($bigint.rem) methods instead.
+///
+/// # Example
+/// ```example
+/// #let n = bigint(2).pow(100)
+/// #n \
+/// #(n * n - 1) \
+/// #bigint("123456789012345678901234567890").rem(97)
+/// ```
+#[ty(scope, cast, name = "bigint", title = "Big Integer")]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BigInt(Arc<num_bigint::BigInt>);
+
+impl BigInt {
+    /// Parse a big integer from a string like `-1234`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(rest) => (Sign::Minus, rest),
+            None => match s.strip_prefix(repr::MINUS_SIGN) {
+                Some(rest) => (Sign::Minus, rest),
+                None => (Sign::Plus, s.strip_prefix('+').unwrap_or(s)),
+            },
+        };
+
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let magnitude = BigUint::parse_bytes(s.as_bytes(), 10)?;
+        Some(Self::new(num_bigint::BigInt::from_biguint(sign, magnitude)))
+    }
+
+    /// Whether the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0.sign() == Sign::NoSign
+    }
+
+    /// Whether the number is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.0.sign() == Sign::Minus
+    }
+
+    /// The absolute value of the number.
+    pub fn abs(&self) -> Self {
+        Self::new(num_bigint::BigInt::from(self.0.magnitude().clone()))
+    }
+
+    /// How many bytes the number occupies.
+    pub fn size(&self) -> usize {
+        usize::try_from(self.0.bits().div_ceil(8)).unwrap_or(usize::MAX)
+    }
+
+    /// Format the number in the given base.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        self.0.to_str_radix(radix)
+    }
+
+    /// Convert the number to an integer, if it fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        i64::try_from(&*self.0).ok()
+    }
+
+    /// Convert the number to the closest float.
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Wrap a number.
+    fn new(v: num_bigint::BigInt) -> Self {
+        Self(Arc::new(v))
+    }
+
+    /// Divide two numbers, rounding the quotient down.
+    ///
+    /// Returns the quotient and the remainder, which has the same sign as the
+    /// divisor.
+    fn div_floor(&self, divisor: &Self) -> StrResult<(Self, Self)> {
+        if divisor.is_zero() {
+            bail!("divisor must not be zero");
+        }
+
+        let mut quotient = &*self.0 / &*divisor.0;
+        let mut remainder = &*self.0 % &*divisor.0;
+        if remainder.sign() != Sign::NoSign && remainder.sign() != divisor.0.sign() {
+            quotient -= 1u32;
+            remainder += &*divisor.0;
+        }
+
+        Ok((Self::new(quotient), Self::new(remainder)))
+    }
+}
+
+#[scope]
+impl BigInt {
+    /// Converts a value to a big integer.
+    ///
+    /// - Integers are converted exactly.
+    /// - Decimal numbers are truncated to their integral part.
+    /// - Strings are parsed in base 10.
+    ///
+    /// ```example
+    /// #bigint(42) \
+    /// #bigint(decimal("-2.7")) \
+    /// #bigint("100000000000000000000000")
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The value that should be converted to a big integer.
+        value: ToBigInt,
+    ) -> BigInt {
+        value.0
+    }
+
+    /// Raises the number to some exponent.
+    ///
+    /// ```example
+    /// #bigint(3).pow(50)
+    /// ```
+    #[func]
+    pub fn pow(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The exponent of the power.
+        exponent: u32,
+    ) -> SourceResult<BigInt> {
+        let limit = engine.world.library().limits.value_size;
+        let bits = self.0.bits().saturating_mul(exponent.into());
+        if bits / 8 > limit as u64 {
+            bail!(error!(span, "maximum value size exceeded").into_fatal());
+        }
+        Ok(Self::new(self.0.pow(exponent)))
+    }
+
+    /// Calculates the quotient of the number and a divisor, rounded down.
+    ///
+    /// ```example
+    /// #bigint(14).quo(5) \
+    /// #bigint(-14).quo(5)
+    /// ```
+    #[func(title = "Quotient")]
+    pub fn quo(
+        &self,
+        /// The divisor of the quotient.
+        divisor: BigInt,
+    ) -> StrResult<BigInt> {
+        self.div_floor(&divisor).map(|(quotient, _)| quotient)
+    }
+
+    /// Calculates the remainder of the division of the number by a divisor.
+    ///
+    /// The remainder always has the same sign as the divisor, so that
+    /// `{x == x.quo(y) * y + x.rem(y)}`.
+    ///
+    /// ```example
+    /// #bigint(14).rem(5) \
+    /// #bigint(-14).rem(5)
+    /// ```
+    #[func(title = "Remainder")]
+    pub fn rem(
+        &self,
+        /// The divisor of the remainder.
+        divisor: BigInt,
+    ) -> StrResult<BigInt> {
+        self.div_floor(&divisor).map(|(_, remainder)| remainder)
+    }
+}
+
+impl std::ops::Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-&*self.0)
+    }
+}
+
+impl std::ops::Add for BigInt {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(&*self.0 + &*other.0)
+    }
+}
+
+impl std::ops::Sub for BigInt {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(&*self.0 - &*other.0)
+    }
+}
+
+impl std::ops::Mul for BigInt {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(&*self.0 * &*other.0)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(v: i64) -> Self {
+        Self::new(v.into())
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Repr for BigInt {
+    fn repr(&self) -> EcoString {
+        eco_format!("bigint({})", Str::from(eco_format!("{self}")).repr())
+    }
+}
+
+/// A value that can be cast to a big integer.
+pub struct ToBigInt(BigInt);
+
+cast! {
+    ToBigInt,
+    v: i64 => Self(v.into()),
+    v: Decimal => Self(
+        BigInt::parse(&v.trunc().to_string()).ok_or("invalid big integer")?
+    ),
+    v: Str => Self(
+        BigInt::parse(&v).ok_or_else(|| eco_format!("invalid big integer: {}", v))?
+    ),
+    v: BigInt => Self(v),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        BigInt::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_bigint_parse_and_display() {
+        assert_eq!(
+            big("123456789012345678901234567890").to_string(),
+            "123456789012345678901234567890"
+        );
+        assert_eq!(big("-0042").to_string(), "-42");
+        assert_eq!(big("+7").to_string(), "7");
+        assert_eq!(BigInt::parse(""), None);
+        assert_eq!(BigInt::parse("-"), None);
+        assert_eq!(BigInt::parse("1_000"), None);
+        assert_eq!(BigInt::parse("1.5"), None);
+    }
+
+    #[test]
+    fn test_bigint_arithmetic() {
+        let max = BigInt::from(i64::MAX);
+        assert_eq!((max.clone() + max.clone()).to_string(), "18446744073709551614");
+        assert_eq!((max.clone() * max).to_i64(), None);
+        assert_eq!(big("5") - big("8"), BigInt::from(-3));
+        assert!(big("-1").is_negative());
+        assert_eq!(big("-12").abs(), big("12"));
+    }
+
+    #[test]
+    fn test_bigint_floored_division() {
+        let div = |a: i64, b: i64| BigInt::from(a).div_floor(&BigInt::from(b)).unwrap();
+        assert_eq!(div(14, 5), (BigInt::from(2), BigInt::from(4)));
+        assert_eq!(div(-14, 5), (BigInt::from(-3), BigInt::from(1)));
+        assert_eq!(div(14, -5), (BigInt::from(-3), BigInt::from(-1)));
+        assert_eq!(div(-14, -5), (BigInt::from(2), BigInt::from(-4)));
+        assert!(BigInt::from(1).div_floor(&BigInt::from(0)).is_err());
+    }
+}
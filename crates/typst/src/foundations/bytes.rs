@@ -1,11 +1,14 @@
 use std::borrow::Cow;
-use std::fmt::{self, Debug, Formatter};
+use std::fmt::{self, Debug, Formatter, Write};
 use std::ops::{Add, AddAssign, Deref};
 use std::sync::Arc;
 
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
 use comemo::Prehashed;
 use ecow::{eco_format, EcoString};
 use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{cast, func, scope, ty, Array, Reflect, Repr, Str, Value};
@@ -21,6 +24,8 @@ use crate::foundations::{cast, func, scope, ty, Array, Reflect, Repr, Str, Value
 ///   [`bytes`]($bytes) constructor
 /// - bytes to a string with the [`str`]($str) constructor, with UTF-8 encoding
 /// - bytes to an array of integers with the [`array`]($array) constructor
+/// - bytes to and from Base64 or hexadecimal strings with the
+///   [`base64`]($bytes.base64) and [`hex`]($bytes.hex) methods
 ///
 /// When [reading]($read) data from a file, you can decide whether to load it
 /// as a string or as raw bytes.
@@ -152,6 +157,122 @@ impl Bytes {
         let end = self.locate(end.unwrap_or(self.len() as i64))?.max(start);
         Ok(self.0[start..end].into())
     }
+
+    /// Computes the SHA-256 hash of the bytes.
+    ///
+    /// Returns the 32 bytes of the hash. Use [`hex`]($bytes.hex) to display
+    /// them.
+    ///
+    /// ```example
+    /// #bytes("Hello").sha256().hex()
+    /// ```
+    #[func(title = "SHA-256")]
+    pub fn sha256(&self) -> Bytes {
+        Sha256::digest(self.as_slice()).as_slice().into()
+    }
+
+    /// Computes the CRC-32 checksum of the bytes.
+    ///
+    /// This is the checksum used by ZIP and PNG files.
+    ///
+    /// ```example
+    /// #bytes("Hello").crc32()
+    /// ```
+    #[func(title = "CRC-32")]
+    pub fn crc32(&self) -> i64 {
+        crc32(self.as_slice()).into()
+    }
+
+    /// Encodes the bytes with Base64.
+    ///
+    /// This uses the standard alphabet with padding. The result can, for
+    /// instance, be used in a data URI.
+    ///
+    /// ```example
+    /// #bytes("Hello").base64()
+    /// ```
+    #[func(title = "Base64")]
+    pub fn base64(&self) -> Str {
+        BASE64_STANDARD.encode(self.as_slice()).into()
+    }
+
+    /// Decodes Base64-encoded bytes.
+    ///
+    /// ```example
+    /// #str(bytes.from-base64("SGVsbG8="))
+    /// ```
+    #[func(title = "From Base64")]
+    pub fn from_base64(
+        /// The Base64-encoded string.
+        string: Str,
+    ) -> StrResult<Bytes> {
+        BASE64_STANDARD
+            .decode(string.as_bytes())
+            .map(Bytes::from)
+            .map_err(|err| eco_format!("failed to decode base64 ({err})"))
+    }
+
+    /// Encodes the bytes as a string of lowercase hexadecimal digits, two per
+    /// byte.
+    ///
+    /// ```example
+    /// #bytes((1, 171, 255)).hex()
+    /// ```
+    #[func(title = "Hexadecimal")]
+    pub fn hex(&self) -> Str {
+        let mut buf = EcoString::with_capacity(2 * self.len());
+        for byte in self.iter() {
+            write!(buf, "{byte:02x}").unwrap();
+        }
+        buf.into()
+    }
+
+    /// Decodes a string of hexadecimal digits, two per byte.
+    ///
+    /// Both lowercase and uppercase digits are accepted.
+    ///
+    /// ```example
+    /// #array(bytes.from-hex("01abFF"))
+    /// ```
+    #[func(title = "From Hexadecimal")]
+    pub fn from_hex(
+        /// The hexadecimal string.
+        string: Str,
+    ) -> StrResult<Bytes> {
+        if string.len() % 2 != 0 {
+            bail!("hexadecimal string must have an even number of digits");
+        }
+
+        string
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair)
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| {
+                        eco_format!(
+                            "invalid hexadecimal digits: {}",
+                            String::from_utf8_lossy(pair)
+                        )
+                    })
+            })
+            .collect::<StrResult<Vec<u8>>>()
+            .map(Bytes::from)
+    }
+}
+
+/// Compute the CRC-32 (ISO-HDLC) checksum of some data.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
 }
 
 impl Debug for Bytes {
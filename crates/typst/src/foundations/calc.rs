@@ -6,7 +6,9 @@ use std::ops::{Div, Rem};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::eval::ops;
-use crate::foundations::{cast, func, IntoValue, Module, Scope, Value};
+use crate::foundations::{
+    cast, func, Array, BigInt, Decimal, IntoValue, Module, Rng, Scope, Seed, Value,
+};
 use crate::layout::{Angle, Fr, Length, Ratio};
 use crate::syntax::{Span, Spanned};
 
@@ -49,6 +51,7 @@ pub fn module() -> Module {
     scope.define_func::<div_euclid>();
     scope.define_func::<rem_euclid>();
     scope.define_func::<quo>();
+    scope.define_func::<random>();
     scope.define("inf", f64::INFINITY);
     scope.define("nan", f64::NAN);
     scope.define("pi", std::f64::consts::PI);
@@ -79,6 +82,8 @@ cast! {
     ToAbs,
     v: i64 => Self(v.abs().into_value()),
     v: f64 => Self(v.abs().into_value()),
+    v: Decimal => Self(Value::Decimal(v.abs())),
+    v: BigInt => Self(Value::BigInt(v.abs())),
     v: Length => Self(Value::Length(v.try_abs()
         .ok_or("cannot take absolute value of this length")?)),
     v: Angle => Self(Value::Angle(v.abs())),
@@ -910,6 +915,43 @@ pub fn quo(
     Ok(floor(dividend.apply2(divisor.v, Div::div, Div::div)))
 }
 
+/// Generates pseudorandom floats between `{0.0}` (inclusive) and `{1.0}`
+/// (exclusive).
+///
+/// Typst documents always compile to the same output, so there is no true
+/// randomness. Instead, the numbers are derived from a seed: The same seed
+/// always produces the same numbers and different seeds produce different
+/// numbers. The seed can be an integer or a string, for example the name of
+/// an exam variant.
+///
+/// To shuffle an array or to choose items from it, use
+/// [`array.shuffle`]($array.shuffle) and [`array.sample`]($array.sample).
+///
+/// ```example
+/// #calc.random(42) \
+/// #calc.random("exam-b", count: 3) \
+/// #(calc.floor(calc.random(7) * 6) + 1)
+/// ```
+#[func]
+pub fn random(
+    /// The seed to derive the numbers from.
+    seed: Seed,
+    /// How many numbers to generate. If this is `{none}`, a single float is
+    /// returned. Otherwise, an array with this many floats is returned.
+    #[named]
+    #[default]
+    count: Option<usize>,
+) -> Value {
+    let mut rng = Rng::new(seed);
+    match count {
+        None => rng.next_f64().into_value(),
+        Some(n) => (0..n)
+            .map(|_| rng.next_f64().into_value())
+            .collect::<Array>()
+            .into_value(),
+    }
+}
+
 /// A value which can be passed to functions that work with integers and floats.
 #[derive(Debug, Copy, Clone)]
 pub enum Num {
@@ -7,15 +7,16 @@ use std::ops::{Add, AddAssign, Deref, DerefMut};
 use std::sync::Arc;
 
 use comemo::Prehashed;
-use ecow::{eco_format, EcoString};
+use ecow::{eco_format, EcoString, EcoVec};
 use serde::{Serialize, Serializer};
 use smallvec::smallvec;
 
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, func, scope, ty, Dict, Element, Fields, IntoValue, Label, NativeElement,
-    Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles, Value,
+    elem, func, scope, ty, CustomElem, Dict, Element, Fields, Func, IntoValue, Label,
+    NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles,
+    Value,
 };
 use crate::introspection::{Location, Meta, MetaElem};
 use crate::layout::{AlignElem, Alignment, Axes, Length, MoveElem, PadElem, Rel, Sides};
@@ -84,6 +85,9 @@ struct Inner<T: ?Sized> {
     label: Option<Label>,
     /// The element's location which identifies it in the layouted output.
     location: Option<Location>,
+    /// The locations of the located elements this element is nested in, from
+    /// the innermost to the outermost. Only known for located elements.
+    ancestors: EcoVec<Location>,
     /// Manages the element during realization.
     /// - If bit 0 is set, the element is prepared.
     /// - If bit n is set, the element is guarded against the n-th show rule
@@ -100,6 +104,7 @@ impl Content {
             inner: Arc::new(Inner {
                 label: None,
                 location: None,
+                ancestors: EcoVec::new(),
                 lifecycle: BitSet::new(),
                 elem,
             }),
@@ -156,6 +161,17 @@ impl Content {
         self.make_mut().location = Some(location);
     }
 
+    /// The locations of the located elements this content is nested in, from
+    /// the innermost to the outermost.
+    pub fn ancestors(&self) -> &[Location] {
+        &self.inner.ancestors
+    }
+
+    /// Set the locations of the located elements this content is nested in.
+    pub fn set_ancestors(&mut self, ancestors: EcoVec<Location>) {
+        self.make_mut().ancestors = ancestors;
+    }
+
     /// Disable a show rule recipe.
     pub fn guarded(mut self, index: RecipeIndex) -> Self {
         self.make_mut().lifecycle.insert(index.0);
@@ -194,6 +210,9 @@ impl Content {
                 return Some(label.into_value());
             }
         }
+        if let Some(custom) = self.to_packed::<CustomElem>() {
+            return custom.values().get(name).ok().cloned();
+        }
         let id = self.elem().field_id(name)?;
         self.get(id, None)
     }
@@ -520,9 +539,12 @@ impl Content {
     /// element. Can be compared with global functions to check whether you have
     /// a specific
     /// kind of element.
-    #[func]
-    pub fn func(&self) -> Element {
-        self.elem()
+    #[func(name = "func", title = "Function")]
+    pub fn to_func(&self) -> Func {
+        match self.to_packed::<CustomElem>() {
+            Some(custom) => custom.element().clone().into(),
+            None => self.elem().into(),
+        }
     }
 
     /// Whether the content has the specified field.
@@ -536,6 +558,10 @@ impl Content {
             return self.label().is_some();
         }
 
+        if let Some(custom) = self.to_packed::<CustomElem>() {
+            return custom.values().contains(&field);
+        }
+
         let Some(id) = self.elem().field_id(&field) else {
             return false;
         };
@@ -570,7 +596,10 @@ impl Content {
     /// ```
     #[func]
     pub fn fields(&self) -> Dict {
-        let mut dict = self.inner.elem.fields();
+        let mut dict = match self.to_packed::<CustomElem>() {
+            Some(custom) => custom.values().clone(),
+            None => self.inner.elem.fields(),
+        };
         if let Some(label) = self.label() {
             dict.insert("label".into(), label.into_value());
         }
@@ -693,7 +722,7 @@ impl Serialize for Content {
         S: Serializer,
     {
         serializer.collect_map(
-            iter::once(("func".into(), self.func().name().into_value()))
+            iter::once(("func".into(), self.to_func().name().into_value()))
                 .chain(self.fields()),
         )
     }
@@ -722,6 +751,7 @@ impl<T: NativeElement> Bounds for T {
             inner: Arc::new(Inner {
                 label: inner.label,
                 location: inner.location,
+                ancestors: inner.ancestors.clone(),
                 lifecycle: inner.lifecycle.clone(),
                 elem: self.clone(),
             }),
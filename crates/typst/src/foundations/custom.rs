@@ -0,0 +1,369 @@
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, func, Args, CastInfo, Content, Dict, Func, NativeElement, NoneValue,
+    Packed, Reflect, Repr, Selector, Show, StyleChain, Type, Value,
+};
+use crate::introspection::Locatable;
+use crate::syntax::{Span, Spanned};
+
+/// Defines a new kind of element.
+///
+/// Calling `element` returns an element function, just like the built-in
+/// [`heading`]($heading) or [`figure`]($figure). Calling that function creates
+/// an element with the given fields. The element can then be styled with
+/// [show rules]($styling/#show-rules), filtered with [`where`]($function.where),
+/// and found with [queries]($query), like built-in elements.
+///
+/// Fields without a default value are required and passed positionally, in the
+/// order in which they are defined. Fields with a default value are optional
+/// and passed by name. Values are checked against the field's type when the
+/// element is created.
+///
+/// Custom elements can't be configured with set rules.
+///
+/// # Operators
+/// Elements can define how [operators]($scripting/#operators) apply to them
+/// with the `ops` argument. This lets domain values like vectors, amounts of
+/// money or intervals compose naturally in expressions. Each operator is given
+/// as a function that receives the operands and returns the result:
+///
+/// - `add`, `sub`, `mul` and `div` overload `+`, `-`, `*` and `/`. They are
+///   used if at least one of the operands is an instance of the element, so
+///   the other operand may also be a number or another value.
+/// - `neg` overloads the unary `-`.
+/// - `compare` overloads `<`, `<=`, `>` and `>=`. It must return a negative
+///   integer if the first operand is smaller, zero if the operands are equal
+///   and a positive integer if the first operand is larger. If both operands
+///   are instances of the same element, it also decides `==` and `!=`.
+/// - `repr` receives the element and returns the string that the
+///   [`repr`]($repr) function produces for it.
+///
+/// ```example
+/// #let money = element(
+///   "money",
+///   fields: (cents: int),
+///   display: it => [\$#calc.quo(it.cents, 100).#calc.rem(it.cents, 100)],
+///   ops: (
+///     add: (a, b) => money(a.cents + b.cents),
+///     mul: (a, b) => if type(a) == int {
+///       money(a * b.cents)
+///     } else {
+///       money(a.cents * b)
+///     },
+///     compare: (a, b) => a.cents - b.cents,
+///   ),
+/// )
+///
+/// #let price = money(1250)
+/// #(price + money(99)) \
+/// #(3 * price) \
+/// #(price > money(1000))
+/// ```
+///
+/// # Example
+/// ```example
+/// #let task = element(
+///   "task",
+///   fields: (
+///     body: content,
+///     done: (type: bool, default: false),
+///   ),
+///   display: it => [#if it.done [☑] else [☐] #it.body],
+/// )
+///
+/// #show task.where(done: true): strike
+///
+/// #task[Write the paper] \
+/// #task(done: true)[Run the experiments]
+/// ```
+#[func]
+pub fn element(
+    /// The call site span.
+    span: Span,
+    /// The element's name.
+    ///
+    /// It is used when the element is printed and in error messages.
+    name: EcoString,
+    /// The element's fields.
+    ///
+    /// Maps each field's name to its type or to a dictionary with the keys
+    /// `type` and `default`.
+    #[named]
+    #[default]
+    fields: Dict,
+    /// How to display the element when no show rule applies to it.
+    ///
+    /// Receives the element and returns content. If this is `{none}`, the
+    /// element is not visible unless a show rule displays it.
+    #[named]
+    #[default]
+    display: Option<Func>,
+    /// Functions that overload operators for the element.
+    ///
+    /// Maps the names of operators to functions, as described
+    /// [above](#operators).
+    #[named]
+    #[default]
+    ops: Dict,
+) -> StrResult<Func> {
+    if name.is_empty() {
+        bail!("element name must not be empty");
+    }
+
+    let fields = fields
+        .into_iter()
+        .map(|(name, spec)| CustomField::new(name.into(), spec))
+        .collect::<StrResult<_>>()?;
+
+    let ops = ops
+        .into_iter()
+        .map(|(key, value)| {
+            let op = CustomOp::ALL
+                .into_iter()
+                .find(|op| op.name() == key.as_str())
+                .ok_or_else(|| eco_format!("unknown operator `{key}`"))?;
+            let func = value
+                .cast::<Func>()
+                .map_err(|err| eco_format!("invalid operator `{key}`: {err}"))?;
+            Ok((op, func))
+        })
+        .collect::<StrResult<_>>()?;
+
+    let data = CustomElementData { name, fields, display, ops, span };
+    Ok(Func::from(CustomElement(Arc::new(data))))
+}
+
+/// A kind of element defined in Typst code.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct CustomElement(Arc<CustomElementData>);
+
+/// The definition of a custom element.
+#[derive(Debug, PartialEq, Hash)]
+struct CustomElementData {
+    /// The element's name.
+    name: EcoString,
+    /// The element's fields in definition order.
+    fields: Vec<CustomField>,
+    /// How the element is displayed without a show rule.
+    display: Option<Func>,
+    /// The functions that overload operators for the element.
+    ops: Vec<(CustomOp, Func)>,
+    /// Where the element was defined, to distinguish elements with the same
+    /// name.
+    span: Span,
+}
+
+/// A field of a custom element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+struct CustomField {
+    /// The field's name.
+    name: EcoString,
+    /// The type of the field's values.
+    ty: Type,
+    /// The default value, if the field is optional.
+    default: Option<Value>,
+}
+
+impl CustomElement {
+    /// The element's name.
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// The function with which the element overloads an operator, if any.
+    pub fn op(&self, op: CustomOp) -> Option<&Func> {
+        self.0.ops.iter().find(|(o, _)| *o == op).map(|(_, func)| func)
+    }
+
+    /// Create a selector for this element.
+    pub fn select(&self) -> Selector {
+        Selector::Custom(self.clone(), None)
+    }
+
+    /// Create a selector for this element, filtering for those whose fields
+    /// match the given ones.
+    pub fn where_(&self, fields: Dict) -> StrResult<Selector> {
+        for key in fields.iter().map(|(key, _)| key) {
+            if !self.0.fields.iter().any(|field| field.name == key.as_str()) {
+                bail!("element `{}` does not have field `{}`", self.name(), key);
+            }
+        }
+        Ok(Selector::Custom(self.clone(), Some(fields)))
+    }
+
+    /// Construct an instance of this element from the arguments.
+    pub fn construct(&self, args: &mut Args) -> SourceResult<Content> {
+        let mut fields = Dict::new();
+        for field in &self.0.fields {
+            let value = match &field.default {
+                None => field.cast(args.expect::<Spanned<Value>>(&field.name)?)?,
+                Some(default) => match args.named::<Spanned<Value>>(&field.name)? {
+                    Some(value) => field.cast(value)?,
+                    None => default.clone(),
+                },
+            };
+            fields.insert(field.name.clone().into(), value);
+        }
+
+        Ok(CustomElem::new(self.clone(), fields).pack().spanned(args.span))
+    }
+}
+
+/// An operator that a custom element can overload.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CustomOp {
+    /// The binary `+` operator.
+    Add,
+    /// The binary `-` operator.
+    Sub,
+    /// The `*` operator.
+    Mul,
+    /// The `/` operator.
+    Div,
+    /// The unary `-` operator.
+    Neg,
+    /// The comparison operators.
+    Compare,
+    /// The `repr` function.
+    Repr,
+}
+
+impl CustomOp {
+    /// All operators.
+    pub const ALL: [Self; 7] = [
+        Self::Add,
+        Self::Sub,
+        Self::Mul,
+        Self::Div,
+        Self::Neg,
+        Self::Compare,
+        Self::Repr,
+    ];
+
+    /// The name under which the operator is defined.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::Div => "div",
+            Self::Neg => "neg",
+            Self::Compare => "compare",
+            Self::Repr => "repr",
+        }
+    }
+}
+
+impl CustomField {
+    /// Parse a field from its name and its type or specification dictionary.
+    fn new(name: EcoString, spec: Value) -> StrResult<Self> {
+        let (ty, default) = match spec {
+            Value::Type(ty) => (ty, None),
+            Value::Dict(mut dict) => {
+                let ty = dict
+                    .take("type")
+                    .map_err(|_| eco_format!("field `{name}` has no type"))?
+                    .cast::<Type>()?;
+                let default = dict.take("default").ok();
+                dict.finish(&["type", "default"])?;
+                (ty, default)
+            }
+            v => {
+                bail!("expected type or dictionary for field `{name}`, found {}", v.ty())
+            }
+        };
+
+        let field = Self { name, ty, default: None };
+        let default = match default {
+            None | Some(Value::None) => default,
+            Some(value) => Some(field.check(value).map_err(|err| {
+                eco_format!("invalid default for field `{}`: {err}", field.name)
+            })?),
+        };
+
+        Ok(Self { default, ..field })
+    }
+
+    /// Check that a value has the field's type, converting it if possible.
+    fn check(&self, value: Value) -> StrResult<Value> {
+        if self.ty == Type::of::<Content>() {
+            return value.cast::<Content>().map(Value::Content);
+        } else if self.ty == Type::of::<f64>() {
+            return value.cast::<f64>().map(Value::Float);
+        }
+
+        // Optional fields with a default of `none` may also be set to `none`.
+        let nullable = self.default == Some(Value::None);
+        if value.ty() == self.ty || (nullable && matches!(value, Value::None)) {
+            return Ok(value);
+        }
+
+        let mut info = CastInfo::Type(self.ty);
+        if nullable {
+            info = info + NoneValue::input();
+        }
+        Err(info.error(&value))
+    }
+
+    /// Check a value given as an argument.
+    fn cast(&self, value: Spanned<Value>) -> SourceResult<Value> {
+        self.check(value.v).at(value.span)
+    }
+}
+
+impl Repr for CustomElement {
+    fn repr(&self) -> EcoString {
+        self.name().into()
+    }
+}
+
+cast! {
+    CustomElement,
+    self => Value::Func(self.into()),
+    v: Func => v.custom().cloned().ok_or("expected custom element")?,
+}
+
+/// An instance of a custom element.
+#[elem(Repr, Locatable, Show)]
+pub struct CustomElem {
+    /// The kind of element.
+    #[required]
+    pub element: CustomElement,
+
+    /// The values of the element's fields.
+    #[required]
+    pub values: Dict,
+}
+
+impl Show for Packed<CustomElem> {
+    #[typst_macros::time(name = "custom element", span = self.span())]
+    fn show(&self, engine: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        let Some(display) = &self.element().0.display else {
+            return Ok(Content::empty());
+        };
+        Ok(display.call(engine, [self.clone().pack()])?.display())
+    }
+}
+
+impl CustomElem {
+    /// The custom element of which a value is an instance, if any.
+    pub fn element_of(value: &Value) -> Option<&CustomElement> {
+        match value {
+            Value::Content(content) => {
+                content.to_packed::<Self>().map(|elem| elem.element())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Repr for CustomElem {
+    fn repr(&self) -> EcoString {
+        eco_format!("{}{}", self.element.name(), self.values.repr())
+    }
+}
@@ -3,8 +3,11 @@ use std::hash::Hash;
 use std::ops::{Add, Sub};
 
 use ecow::{eco_format, EcoString, EcoVec};
-use time::error::{Format, InvalidFormatDescription};
+use time::error::{
+    Format, InvalidFormatDescription, Parse, ParseFromDescription, TryFromParsed,
+};
 use time::macros::format_description;
+use time::parsing::Parsable;
 use time::{format_description, Month, PrimitiveDateTime};
 
 use crate::diag::{bail, StrResult};
@@ -199,6 +202,29 @@ impl Datetime {
         }
     }
 
+    /// Parse a string with a format, trying a full datetime, a date, and a
+    /// time in that order.
+    fn parse_with<T>(string: &str, format: &T) -> Result<Self, Parse>
+    where
+        T: Parsable + ?Sized,
+    {
+        let insufficient = |err: &Parse| {
+            matches!(err, Parse::TryFromParsed(TryFromParsed::InsufficientInformation))
+        };
+
+        match PrimitiveDateTime::parse(string, format) {
+            Err(err) if insufficient(&err) => {}
+            result => return result.map(Self::Datetime),
+        }
+
+        match time::Date::parse(string, format) {
+            Err(err) if insufficient(&err) => {}
+            result => return result.map(Self::Date),
+        }
+
+        time::Time::parse(string, format).map(Self::Time)
+    }
+
     /// Which kind of variant this datetime stores.
     pub fn kind(&self) -> &'static str {
         match self {
@@ -310,6 +336,52 @@ impl Datetime {
             .ok_or("unable to get the current date")?)
     }
 
+    /// Parses a datetime from a string.
+    ///
+    /// Without a pattern, the string must be an ISO 8601 date
+    /// (`[[year]-[month]-[day]]`), time (`[[hour]:[minute]:[second]]`), or
+    /// combination of both, separated by a `T` or a space. With a pattern, the
+    /// string must match it exactly. The pattern uses the same
+    /// [format syntax]($datetime/#format) as [`display`]($datetime.display).
+    ///
+    /// Just like for the constructor, the result is a date, a time, or a full
+    /// datetime depending on which components are available.
+    ///
+    /// ```example
+    /// #datetime.parse("2023-10-13").display() \
+    /// #datetime.parse(
+    ///   "13 October 2023",
+    ///   "[day] [month repr:long] [year]",
+    /// ).weekday()
+    /// ```
+    #[func]
+    pub fn parse(
+        /// The string to parse.
+        string: Str,
+        /// The format the string is in.
+        #[default]
+        pattern: Smart<DisplayPattern>,
+    ) -> StrResult<Datetime> {
+        match pattern {
+            Smart::Auto => {
+                const PATTERNS: [&str; 4] = [
+                    "[year]-[month]-[day]T[hour]:[minute]:[second]",
+                    "[year]-[month]-[day] [hour]:[minute]:[second]",
+                    "[year]-[month]-[day]",
+                    "[hour]:[minute]:[second]",
+                ];
+                PATTERNS
+                    .into_iter()
+                    .map(|s| format_description::parse_borrowed::<2>(s).unwrap())
+                    .find_map(|format| Self::parse_with(&string, &format).ok())
+                    .ok_or_else(|| "datetime is not in ISO 8601 format".into())
+            }
+            Smart::Custom(DisplayPattern(_, format)) => {
+                Self::parse_with(&string, &format).map_err(format_time_parse_error)
+            }
+        }
+    }
+
     /// Displays the datetime in a specified format.
     ///
     /// Depending on whether you have defined just a date, a time or both, the
@@ -522,6 +594,25 @@ fn format_time_format_error(error: Format) -> EcoString {
     }
 }
 
+/// Format the `Parse` error of the time crate in an appropriate way.
+fn format_time_parse_error(error: Parse) -> EcoString {
+    match error {
+        Parse::ParseFromDescription(ParseFromDescription::InvalidLiteral) => {
+            "string does not match the pattern".into()
+        }
+        Parse::ParseFromDescription(ParseFromDescription::InvalidComponent(name)) => {
+            eco_format!("invalid {} in string", name.replace('_', " "))
+        }
+        Parse::TryFromParsed(TryFromParsed::InsufficientInformation) => {
+            "failed to parse datetime (insufficient information)".into()
+        }
+        Parse::TryFromParsed(TryFromParsed::ComponentRange(_)) => {
+            "datetime is invalid".into()
+        }
+        err => eco_format!("failed to parse datetime ({err})"),
+    }
+}
+
 /// Format the `InvalidFormatDescription` error of the time crate in an
 /// appropriate way.
 fn format_time_invalid_format_description_error(
@@ -0,0 +1,450 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter, Write};
+use std::hash::{Hash, Hasher};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::StrResult;
+use crate::foundations::{cast, func, repr, scope, ty, Repr, Str};
+
+/// A fixed-point decimal number.
+///
+/// Unlike [floats]($float), decimal numbers represent decimal fractions like
+/// `{0.1}` exactly. This makes them well-suited for computations where
+/// rounding errors in the displayed numbers are unacceptable, for example in
+/// financial documents.
+///
+/// A decimal number stores up to 28 significant digits and up to 28 digits
+/// after the decimal point. Results that need more fractional digits are
+/// rounded. Results whose integral part needs more digits produce an error.
+///
+/// Decimal numbers can be added, subtracted, multiplied, divided, and
+/// compared with each other and with [integers]($int). Mixing them with
+/// floats is not allowed, since that would reintroduce rounding errors. A
+/// decimal number keeps the number of fractional digits it was written with,
+/// so `{decimal("1.50")}` is displayed with two digits after the point.
+///
+/// # Example
+/// ```example
+/// #(0.1 + 0.2) \
+/// #(decimal("0.1") + decimal("0.2")) \
+/// #(decimal("19.99") * 3) \
+/// #(decimal("10") / 4)
+/// ```
+#[ty(scope, cast)]
+#[derive(Debug, Copy, Clone)]
+pub struct Decimal {
+    /// The digits of the number, without the decimal point.
+    mantissa: i128,
+    /// The number of digits after the decimal point.
+    scale: u32,
+}
+
+/// The largest mantissa a decimal number may have (96 bits).
+const MAX_MANTISSA: i128 = (1 << 96) - 1;
+
+/// The largest number of digits after the decimal point.
+const MAX_SCALE: u32 = 28;
+
+/// The largest magnitude of intermediate results, chosen such that two of
+/// them can be added without overflowing.
+const MAX_INTERMEDIATE: i128 = 10_i128.pow(37);
+
+impl Decimal {
+    /// The decimal number zero.
+    pub const ZERO: Self = Self { mantissa: 0, scale: 0 };
+
+    /// Create a decimal number from a mantissa and a scale, rounding off
+    /// fractional digits that don't fit.
+    ///
+    /// Returns `None` if the integral part is too large.
+    fn new(mantissa: i128, scale: u32) -> Option<Self> {
+        let fits = |digits| {
+            let factor = pow10(digits).map_or(u128::MAX, |f| f as u128);
+            mantissa.unsigned_abs() / factor <= MAX_MANTISSA as u128
+        };
+
+        let mut excess = scale.saturating_sub(MAX_SCALE);
+        while excess < scale && !fits(excess) {
+            excess += 1;
+        }
+
+        let mantissa = round_digits(mantissa, excess);
+        if mantissa.unsigned_abs() > MAX_MANTISSA as u128 {
+            return None;
+        }
+
+        Some(Self { mantissa, scale: scale - excess })
+    }
+
+    /// Parse a decimal number from a string like `-12.50`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => match s.strip_prefix(repr::MINUS_SIGN) {
+                Some(rest) => (true, rest),
+                None => (false, s.strip_prefix('+').unwrap_or(s)),
+            },
+        };
+
+        let (int, fract) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() && fract.is_empty() {
+            return None;
+        }
+
+        let mut mantissa: i128 = 0;
+        for c in int.chars().chain(fract.chars()) {
+            let digit = c.to_digit(10)?;
+            mantissa = mantissa.checked_mul(10)?.checked_add(digit as i128)?;
+        }
+
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        Self::new(mantissa, fract.len() as u32)
+    }
+
+    /// Whether the number is zero.
+    pub fn is_zero(self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// The absolute value of the number.
+    pub fn abs(self) -> Self {
+        Self { mantissa: self.mantissa.abs(), ..self }
+    }
+
+    /// Add two numbers, returning `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = align(self, other);
+        Self::new(a + b, scale)
+    }
+
+    /// Subtract two numbers, returning `None` on overflow.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    /// Multiply two numbers, returning `None` on overflow.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (mut a, mut b) = (self, other);
+        loop {
+            if let Some(product) = a.mantissa.checked_mul(b.mantissa) {
+                return Self::new(product, a.scale + b.scale);
+            }
+
+            // Give up precision in the operand with more fractional digits
+            // until the product fits.
+            let rounded = if a.scale >= b.scale { &mut a } else { &mut b };
+            if rounded.scale == 0 {
+                return None;
+            }
+            *rounded = rounded.rescale(rounded.scale - 1)?;
+        }
+    }
+
+    /// Divide two numbers, returning `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let negative = (self.mantissa < 0) != (other.mantissa < 0);
+        let dividend = self.mantissa.unsigned_abs();
+        let divisor = other.mantissa.unsigned_abs();
+
+        // Long division, one digit at a time, until the division is exact or
+        // the result doesn't have room for more digits.
+        let mut quotient = dividend / divisor;
+        let mut remainder = dividend % divisor;
+        let mut scale = self.scale as i64 - other.scale as i64;
+        let limit = (MAX_MANTISSA as u128 - 9) / 10;
+        while (remainder != 0 || scale < 0) && scale < MAX_SCALE as i64 {
+            if quotient > limit {
+                break;
+            }
+            remainder *= 10;
+            quotient = quotient * 10 + remainder / divisor;
+            remainder %= divisor;
+            scale += 1;
+        }
+
+        if scale < 0 {
+            return None;
+        }
+
+        if remainder * 2 >= divisor {
+            quotient += 1;
+        }
+
+        let mantissa = i128::try_from(quotient).ok()?;
+        Self::new(if negative { -mantissa } else { mantissa }, scale as u32)
+    }
+
+    /// Convert the number to the closest float.
+    pub fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Convert the number to an integer, truncating the fractional part.
+    pub fn to_i64(self) -> Option<i64> {
+        i64::try_from(self.mantissa / pow10(self.scale)?).ok()
+    }
+
+    /// The integral part of the number.
+    pub fn trunc(self) -> Self {
+        Self {
+            mantissa: self.mantissa / 10_i128.pow(self.scale),
+            scale: 0,
+        }
+    }
+
+    /// Change the number of fractional digits, rounding if necessary.
+    fn rescale(self, scale: u32) -> Option<Self> {
+        let mantissa = if scale >= self.scale {
+            self.mantissa.checked_mul(pow10(scale - self.scale)?)?
+        } else {
+            round_digits(self.mantissa, self.scale - scale)
+        };
+        Some(Self { mantissa, scale })
+    }
+
+    /// The same number without trailing zeros after the decimal point.
+    fn normalize(self) -> Self {
+        let Self { mut mantissa, mut scale } = self;
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Self { mantissa, scale }
+    }
+}
+
+#[scope]
+impl Decimal {
+    /// Converts a value to a decimal number.
+    ///
+    /// - Integers are converted exactly.
+    /// - Floats are converted to the shortest decimal number that rounds to
+    ///   the same float. Since the float may already contain a rounding
+    ///   error, prefer passing a string.
+    /// - Strings are parsed in base 10. They may contain a decimal point but
+    ///   no exponent.
+    ///
+    /// ```example
+    /// #decimal(4) \
+    /// #decimal(2.5) \
+    /// #decimal("-12.50")
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The value that should be converted to a decimal number.
+        value: ToDecimal,
+    ) -> Decimal {
+        value.0
+    }
+
+    /// Rounds the number to the given number of digits after the decimal
+    /// point.
+    ///
+    /// Halfway values are rounded away from zero.
+    ///
+    /// ```example
+    /// #decimal("3.14159").round(digits: 2) \
+    /// #decimal("2.5").round() \
+    /// #decimal("7").round(digits: 2)
+    /// ```
+    #[func]
+    pub fn round(
+        self,
+        /// The number of digits after the decimal point.
+        #[named]
+        #[default(0)]
+        digits: u32,
+    ) -> StrResult<Decimal> {
+        if digits > MAX_SCALE {
+            return Err(eco_format!("cannot round to more than {MAX_SCALE} digits"));
+        }
+        self.rescale(digits)
+            .and_then(|d| Self::new(d.mantissa, d.scale))
+            .ok_or_else(|| "value is too large".into())
+    }
+}
+
+/// Scale the two numbers to the same number of fractional digits.
+///
+/// If that would make the intermediate values too large, the number with more
+/// fractional digits is rounded instead.
+fn align(a: Decimal, b: Decimal) -> (i128, i128, u32) {
+    let mut scale = a.scale.max(b.scale);
+    loop {
+        let scaled = |d: Decimal| {
+            d.rescale(scale)
+                .map(|d| d.mantissa)
+                .filter(|m| m.abs() <= MAX_INTERMEDIATE)
+        };
+        if let (Some(x), Some(y)) = (scaled(a), scaled(b)) {
+            return (x, y, scale);
+        }
+        scale -= 1;
+    }
+}
+
+/// Divide by a power of ten, rounding halfway values away from zero.
+fn round_digits(mantissa: i128, digits: u32) -> i128 {
+    if digits == 0 {
+        return mantissa;
+    }
+
+    let Some(factor) = pow10(digits) else { return 0 };
+    let quotient = mantissa / factor;
+    let remainder = (mantissa % factor).abs();
+    if remainder >= factor / 2 {
+        quotient + mantissa.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Ten to the given power, if it fits.
+fn pow10(exp: u32) -> Option<i128> {
+    10_i128.checked_pow(exp)
+}
+
+impl std::ops::Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { mantissa: -self.mantissa, ..self }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(v: i64) -> Self {
+        Self { mantissa: v as i128, scale: 0 }
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(a), Some(b)) => a.mantissa.cmp(&b.mantissa),
+            // If scaling overflows, that number has the larger magnitude.
+            (None, _) => self.mantissa.cmp(&0),
+            (_, None) => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self { mantissa, scale } = self.normalize();
+        mantissa.hash(state);
+        scale.hash(state);
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.mantissa < 0 {
+            f.write_char('-')?;
+        }
+
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return f.write_str(&digits);
+        }
+
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, fract) = digits.split_at(digits.len() - scale);
+        write!(f, "{int}.{fract}")
+    }
+}
+
+impl Repr for Decimal {
+    fn repr(&self) -> EcoString {
+        eco_format!("decimal({})", Str::from(eco_format!("{self}")).repr())
+    }
+}
+
+/// A value that can be cast to a decimal number.
+pub struct ToDecimal(Decimal);
+
+cast! {
+    ToDecimal,
+    v: i64 => Self(v.into()),
+    v: f64 => Self(
+        Some(v)
+            .filter(|v| v.is_finite())
+            .and_then(|v| Decimal::parse(&v.to_string()))
+            .ok_or_else(|| eco_format!("cannot convert {} to a decimal", v.repr()))?
+    ),
+    v: Str => Self(
+        Decimal::parse(&v).ok_or_else(|| eco_format!("invalid decimal: {}", v))?
+    ),
+    v: Decimal => Self(v),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_decimal_parse_and_display() {
+        assert_eq!(dec("12.50").to_string(), "12.50");
+        assert_eq!(dec("-0.05").to_string(), "-0.05");
+        assert_eq!(dec(".5").to_string(), "0.5");
+        assert_eq!(dec("7.").to_string(), "7");
+        assert_eq!(Decimal::parse(""), None);
+        assert_eq!(Decimal::parse("1e5"), None);
+        assert_eq!(Decimal::parse("1.2.3"), None);
+    }
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+        assert_eq!(dec("1.50").checked_sub(dec("2")).unwrap().to_string(), "-0.50");
+        assert_eq!(dec("1.5").checked_mul(dec("1.5")).unwrap().to_string(), "2.25");
+        assert_eq!(dec("10").checked_div(dec("4")).unwrap().to_string(), "2.5");
+        assert_eq!(
+            dec("1").checked_div(dec("3")).unwrap().to_string(),
+            "0.3333333333333333333333333333"
+        );
+        assert_eq!(
+            dec("2").checked_div(dec("3")).unwrap().to_string(),
+            "0.6666666666666666666666666667"
+        );
+        assert_eq!(dec("1").checked_div(Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_decimal_limits() {
+        let max = dec("79228162514264337593543950335");
+        assert_eq!(max.checked_add(dec("1")), None);
+        assert_eq!(max.checked_mul(dec("2")), None);
+        assert_eq!(max.checked_add(dec("0.1")), Some(max));
+        assert!(max > dec("0.0000000000000000000000000001"));
+        assert!(-max < dec("-7922816251426433759354395033.5"));
+    }
+}
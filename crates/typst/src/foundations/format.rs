@@ -0,0 +1,417 @@
+use ecow::{eco_format, EcoString};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{repr, BigInt, Decimal, Dict, Repr, Value};
+
+/// Fill a template with values, following the format specifications in its
+/// placeholders.
+///
+/// See [`Str::format`](crate::foundations::Str::format) for the syntax.
+pub fn format_template(
+    template: &str,
+    pos: &[Value],
+    named: &Dict,
+) -> StrResult<EcoString> {
+    let mut out = EcoString::new();
+    let mut next = 0;
+    let mut rest = template;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let brace = &rest[i..i + 1];
+        rest = &rest[i + 1..];
+
+        // Doubled braces are escapes.
+        if let Some(tail) = rest.strip_prefix(brace) {
+            out.push_str(brace);
+            rest = tail;
+            continue;
+        } else if brace == "}" {
+            bail!("unmatched closing brace in format string (use `}}}}` to escape it)");
+        }
+
+        let Some(end) = rest.find('}') else {
+            bail!("unclosed placeholder in format string (use `{{{{` to escape a brace)");
+        };
+
+        let placeholder = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let (key, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+        let value = if key.is_empty() {
+            next += 1;
+            pos.get(next - 1)
+                .ok_or("format string has more placeholders than arguments")?
+        } else if let Ok(index) = key.parse::<usize>() {
+            pos.get(index).ok_or_else(|| {
+                eco_format!("format string refers to missing argument {index}")
+            })?
+        } else {
+            named.get(key).map_err(|_| {
+                eco_format!("format string refers to missing argument `{key}`")
+            })?
+        };
+
+        let spec = FormatSpec::parse(spec)?;
+        spec.apply(value, &mut out)?;
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// A parsed format specification, like the `>8.2f` in `{:>8.2f}`.
+#[derive(Debug, Clone, PartialEq)]
+struct FormatSpec<'a> {
+    /// The specification as written, for error messages.
+    text: &'a str,
+    /// The character to pad with.
+    fill: char,
+    /// How to align the value within the width.
+    align: Option<Align>,
+    /// Which signs to show.
+    sign: Sign,
+    /// Whether to pad numbers with zeros after the sign.
+    zero: bool,
+    /// The minimum width in characters.
+    width: usize,
+    /// The character to separate groups of digits with.
+    grouping: Option<char>,
+    /// The number of fractional digits for numbers or the maximum number of
+    /// characters for other values.
+    precision: Option<usize>,
+    /// The presentation type, like `f` or `x`.
+    kind: Option<char>,
+}
+
+/// How to align a formatted value within its width.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Which signs to show for a formatted number.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Sign {
+    /// Only for negative numbers.
+    Negative,
+    /// For negative and positive numbers.
+    Always,
+    /// A minus for negative and a space for positive numbers.
+    Space,
+}
+
+impl<'a> FormatSpec<'a> {
+    /// Parse a format specification.
+    fn parse(text: &'a str) -> StrResult<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut spec = Self {
+            text,
+            fill: ' ',
+            align: None,
+            sign: Sign::Negative,
+            zero: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            kind: None,
+        };
+
+        let align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        };
+
+        let mut i = 0;
+        if let Some(a) = chars.get(1).copied().and_then(align) {
+            spec.fill = chars[0];
+            spec.align = Some(a);
+            i = 2;
+        } else if let Some(a) = chars.first().copied().and_then(align) {
+            spec.align = Some(a);
+            i = 1;
+        }
+
+        if let Some(&c @ ('+' | '-' | ' ')) = chars.get(i) {
+            spec.sign = match c {
+                '+' => Sign::Always,
+                ' ' => Sign::Space,
+                _ => Sign::Negative,
+            };
+            i += 1;
+        }
+
+        if chars.get(i) == Some(&'0') {
+            spec.zero = true;
+            i += 1;
+        }
+
+        let (width, len) = digits(&chars[i..]);
+        spec.width = width.unwrap_or(0);
+        i += len;
+
+        if let Some(&c @ (',' | '_')) = chars.get(i) {
+            spec.grouping = Some(c);
+            i += 1;
+        }
+
+        if chars.get(i) == Some(&'.') {
+            let (precision, len) = digits(&chars[i + 1..]);
+            if precision.is_none() {
+                bail!("missing precision after `.` in format specification `{text}`");
+            }
+            spec.precision = precision;
+            i += 1 + len;
+        }
+
+        if let Some(&c) = chars.get(i).filter(|c| "sfe%xXob".contains(**c)) {
+            spec.kind = Some(c);
+            i += 1;
+        }
+
+        if i < chars.len() {
+            bail!("invalid format specification `{text}`");
+        }
+
+        Ok(spec)
+    }
+
+    /// Format a value according to this specification.
+    fn apply(&self, value: &Value, out: &mut EcoString) -> StrResult<()> {
+        let number = match value {
+            _ if self.kind == Some('s') => None,
+            Value::Int(v) => Some(self.int(*v)?),
+            Value::Float(v) => Some(self.float(*v)?),
+            Value::Decimal(v) => Some(self.decimal(*v)?),
+            Value::BigInt(v) => Some(self.bigint(v)?),
+            _ => None,
+        };
+
+        let numeric = number.is_some();
+        let (sign, body, default_align) = match number {
+            Some((negative, digits)) => {
+                let sign = match (negative, self.sign) {
+                    (true, _) => repr::MINUS_SIGN,
+                    (false, Sign::Always) => "+",
+                    (false, Sign::Space) => " ",
+                    (false, Sign::Negative) => "",
+                };
+                (sign, self.group(&digits), Align::Right)
+            }
+            None => ("", self.text(value)?, Align::Left),
+        };
+
+        let len = sign.graphemes(true).count() + body.graphemes(true).count();
+        let padding = self.width.saturating_sub(len);
+        if self.zero && self.align.is_none() && numeric {
+            out.push_str(sign);
+            pad(out, '0', padding);
+            out.push_str(&body);
+            return Ok(());
+        }
+
+        let (before, after) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+
+        pad(out, self.fill, before);
+        out.push_str(sign);
+        out.push_str(&body);
+        pad(out, self.fill, after);
+        Ok(())
+    }
+
+    /// Format an integer into its sign and digits.
+    fn int(&self, v: i64) -> StrResult<(bool, EcoString)> {
+        let magnitude = v.unsigned_abs();
+        let digits = match self.kind {
+            Some('x') => eco_format!("{magnitude:x}"),
+            Some('X') => eco_format!("{magnitude:X}"),
+            Some('o') => eco_format!("{magnitude:o}"),
+            Some('b') => eco_format!("{magnitude:b}"),
+            Some(_) => return self.float(v as f64),
+            None if self.precision.is_some() => return self.float(v as f64),
+            None => eco_format!("{magnitude}"),
+        };
+        Ok((v < 0, digits))
+    }
+
+    /// Format a float into its sign and digits.
+    fn float(&self, v: f64) -> StrResult<(bool, EcoString)> {
+        let magnitude = v.abs();
+        let digits = match self.kind {
+            _ if v.is_nan() => "NaN".into(),
+            _ if v.is_infinite() => "inf".into(),
+            Some('f') => eco_format!("{magnitude:.*}", self.precision.unwrap_or(6)),
+            Some('e') => match self.precision {
+                Some(p) => eco_format!("{magnitude:.p$e}"),
+                None => eco_format!("{magnitude:e}"),
+            },
+            Some('%') => {
+                eco_format!("{:.*}%", self.precision.unwrap_or(6), magnitude * 100.0)
+            }
+            Some(kind) => bail!("cannot format float with type `{kind}`"),
+            None => match self.precision {
+                Some(p) => eco_format!("{magnitude:.p$}"),
+                None => eco_format!("{magnitude}"),
+            },
+        };
+        Ok((v.is_sign_negative() && !v.is_nan(), digits))
+    }
+
+    /// Format a decimal number into its sign and digits.
+    fn decimal(&self, v: Decimal) -> StrResult<(bool, EcoString)> {
+        let negative = v < Decimal::ZERO;
+        let magnitude = v.abs();
+        let round = |v: Decimal| match self.precision {
+            Some(p) => v.round(p.try_into().unwrap_or(u32::MAX)),
+            None => Ok(v),
+        };
+
+        let digits = match self.kind {
+            None | Some('f') => eco_format!("{}", round(magnitude)?),
+            Some('%') => {
+                let percent = magnitude
+                    .checked_mul(Decimal::from(100))
+                    .ok_or("value is too large")?;
+                eco_format!("{}%", round(percent)?)
+            }
+            Some('e') => return self.float(v.to_f64()),
+            Some(kind) => bail!("cannot format decimal with type `{kind}`"),
+        };
+        Ok((negative, digits))
+    }
+
+    /// Format a big integer into its sign and digits.
+    fn bigint(&self, v: &BigInt) -> StrResult<(bool, EcoString)> {
+        let magnitude = v.abs();
+        let digits = match self.kind {
+            Some('x') => magnitude.to_str_radix(16).into(),
+            Some('X') => magnitude.to_str_radix(16).to_uppercase().into(),
+            Some('o') => magnitude.to_str_radix(8).into(),
+            Some('b') => magnitude.to_str_radix(2).into(),
+            None if self.precision.is_none() => eco_format!("{magnitude}"),
+            // Append the zeros ourselves since the conversion to a float
+            // would lose digits.
+            None | Some('f') => match self.precision.unwrap_or(6) {
+                0 => eco_format!("{magnitude}"),
+                p => eco_format!("{magnitude}.{:0>p$}", ""),
+            },
+            Some(_) => return self.float(v.to_f64()),
+        };
+        Ok((v.is_negative(), digits))
+    }
+
+    /// Format a non-numeric value.
+    fn text(&self, value: &Value) -> StrResult<EcoString> {
+        if self.sign != Sign::Negative
+            || self.zero
+            || self.grouping.is_some()
+            || self.kind.is_some_and(|kind| kind != 's')
+        {
+            bail!("cannot format {} with `{}`", value.ty(), self.text);
+        }
+
+        let text = match value {
+            Value::Str(s) => s.as_str().into(),
+            Value::Symbol(s) => s.get().into(),
+            Value::Int(v) => repr::format_int_with_base(*v, 10),
+            Value::Float(v) => repr::display_float(*v),
+            Value::Decimal(v) => repr::display_decimal(*v),
+            Value::BigInt(v) => repr::display_bigint(v),
+            v => v.repr(),
+        };
+
+        Ok(match self.precision.and_then(|p| text.grapheme_indices(true).nth(p)) {
+            Some((end, _)) => text[..end].into(),
+            None => text,
+        })
+    }
+
+    /// Insert the grouping separator into the integral part of the digits.
+    fn group(&self, digits: &str) -> EcoString {
+        let Some(separator) = self.grouping else { return digits.into() };
+        let (size, end) = if matches!(self.kind, Some('x' | 'X' | 'o' | 'b')) {
+            (4, digits.len())
+        } else {
+            (3, digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len()))
+        };
+
+        let (integral, rest) = digits.split_at(end);
+        let mut out = EcoString::new();
+        for (i, c) in integral.chars().enumerate() {
+            if i > 0 && (integral.len() - i) % size == 0 {
+                out.push(separator);
+            }
+            out.push(c);
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Push a character to the output a number of times.
+fn pad(out: &mut EcoString, c: char, count: usize) {
+    for _ in 0..count {
+        out.push(c);
+    }
+}
+
+/// Parse a leading run of ASCII digits, returning the number and how many
+/// characters it spans.
+fn digits(chars: &[char]) -> (Option<usize>, usize) {
+    let len = chars.iter().take_while(|c| c.is_ascii_digit()).count();
+    let number = chars[..len].iter().collect::<String>().parse().ok();
+    (number, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::IntoValue;
+
+    #[track_caller]
+    fn test(template: &str, pos: &[Value], expected: &str) {
+        assert_eq!(format_template(template, pos, &Dict::new()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_escapes_and_arguments() {
+        test("{{}}", &[], "{}");
+        test("{} and {}", &[Value::Int(1), Value::Int(2)], "1 and 2");
+        test("{1}{0}", &["a".into_value(), "b".into_value()], "ba");
+    }
+
+    #[test]
+    fn test_format_numbers() {
+        test("{:.2f}", &[Value::Float(3.14159)], "3.14");
+        test("{:>8}", &[Value::Int(42)], "      42");
+        test("{:08.3}", &[Value::Float(-1.5)], "−001.500");
+        test("{:+}", &[Value::Int(5)], "+5");
+        test("{:,}", &[Value::Int(1234567)], "1,234,567");
+        test("{:_x}", &[Value::Int(0xdeadbeef)], "dead_beef");
+        test("{:.1%}", &[Value::Float(0.256)], "25.6%");
+    }
+
+    #[test]
+    fn test_format_bigints() {
+        let big = |s| Value::BigInt(BigInt::parse(s).unwrap());
+        test("{:,}", &[big("-12345678901234567890")], "−12,345,678,901,234,567,890");
+        test("{:x}", &[big("18446744073709551616")], "10000000000000000");
+        test("{:.2}", &[big("100000000000000000001")], "100000000000000000001.00");
+    }
+
+    #[test]
+    fn test_format_text() {
+        test("{:*^9}", &["mid".into_value()], "***mid***");
+        test("{:.3}", &["abcdef".into_value()], "abc");
+        test("[{:<4}]", &["a".into_value()], "[a   ]");
+    }
+}
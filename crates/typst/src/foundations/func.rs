@@ -5,11 +5,11 @@ use comemo::{Prehashed, TrackedMut};
 use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, repr, scope, ty, Args, CastInfo, Content, Element, IntoArgs, Scope, Selector,
-    Type, Value,
+    cast, repr, scope, ty, Args, CastInfo, Content, CustomElement, Element, IntoArgs,
+    Scope, Selector, Type, Value,
 };
 use crate::syntax::{ast, Span, SyntaxNode};
 use crate::util::Static;
@@ -102,6 +102,25 @@ pub use typst_macros::func;
 /// ]
 /// ```
 ///
+/// # Type annotations
+/// Parameters can be annotated with the type they expect by writing `as` and
+/// the type after them. Arguments of a different type are then rejected at the
+/// call site with an error. An annotation can also be an array of types, in
+/// which case any of them is accepted. Integers are accepted for `float`
+/// parameters and strings, symbols and `{none}` for `content` parameters, just
+/// like for built-in functions. Default values are not checked, so a parameter
+/// can default to `{none}` while otherwise requiring a specific type.
+///
+/// ```example
+/// #let badge(label as str, size: 1em as length) = box(
+///   fill: aqua,
+///   inset: 2pt,
+///   text(size, label),
+/// )
+///
+/// #badge("New") #badge("Hot", size: 1.2em)
+/// ```
+///
 /// # Unnamed functions { #unnamed }
 /// You can also created an unnamed function without creating a binding by
 /// specifying a parameter list followed by `=>` and the function body. If your
@@ -140,10 +159,14 @@ enum Repr {
     Native(Static<NativeFuncData>),
     /// A function for an element.
     Element(Element),
+    /// A function for a user-defined element.
+    Custom(CustomElement),
     /// A user-defined closure.
     Closure(Arc<Prehashed<Closure>>),
     /// A nested function with pre-applied arguments.
     With(Arc<(Func, Args)>),
+    /// A function whose results are cached.
+    Memo(Arc<Func>),
 }
 
 impl Func {
@@ -154,8 +177,10 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.name),
             Repr::Element(elem) => Some(elem.name()),
+            Repr::Custom(elem) => Some(elem.name()),
             Repr::Closure(closure) => closure.name(),
             Repr::With(with) => with.0.name(),
+            Repr::Memo(inner) => inner.name(),
         }
     }
 
@@ -166,8 +191,9 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.title),
             Repr::Element(elem) => Some(elem.title()),
-            Repr::Closure(_) => None,
+            Repr::Custom(_) | Repr::Closure(_) => None,
             Repr::With(with) => with.0.title(),
+            Repr::Memo(inner) => inner.title(),
         }
     }
 
@@ -176,8 +202,9 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(native.docs),
             Repr::Element(elem) => Some(elem.docs()),
-            Repr::Closure(_) => None,
+            Repr::Custom(_) | Repr::Closure(_) => None,
             Repr::With(with) => with.0.docs(),
+            Repr::Memo(inner) => inner.docs(),
         }
     }
 
@@ -186,8 +213,9 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(&native.0.params),
             Repr::Element(elem) => Some(elem.params()),
-            Repr::Closure(_) => None,
+            Repr::Custom(_) | Repr::Closure(_) => None,
             Repr::With(with) => with.0.params(),
+            Repr::Memo(inner) => inner.params(),
         }
     }
 
@@ -202,9 +230,10 @@ impl Func {
             Lazy::new(|| CastInfo::Type(Type::of::<Content>()));
         match &self.repr {
             Repr::Native(native) => Some(&native.0.returns),
-            Repr::Element(_) => Some(&CONTENT),
+            Repr::Element(_) | Repr::Custom(_) => Some(&CONTENT),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.returns(),
+            Repr::Memo(inner) => inner.returns(),
         }
     }
 
@@ -213,8 +242,9 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => native.keywords,
             Repr::Element(elem) => elem.keywords(),
-            Repr::Closure(_) => &[],
+            Repr::Custom(_) | Repr::Closure(_) => &[],
             Repr::With(with) => with.0.keywords(),
+            Repr::Memo(inner) => inner.keywords(),
         }
     }
 
@@ -223,8 +253,9 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(&native.0.scope),
             Repr::Element(elem) => Some(elem.scope()),
-            Repr::Closure(_) => None,
+            Repr::Custom(_) | Repr::Closure(_) => None,
             Repr::With(with) => with.0.scope(),
+            Repr::Memo(inner) => inner.scope(),
         }
     }
 
@@ -249,9 +280,41 @@ impl Func {
         }
     }
 
+    /// Extract the user-defined element, if it is one.
+    pub fn custom(&self) -> Option<&CustomElement> {
+        match &self.repr {
+            Repr::Custom(elem) => Some(elem),
+            _ => None,
+        }
+    }
+
+    /// Create a selector for the elements of this function, if it is an
+    /// element function.
+    pub fn to_selector(&self) -> Option<Selector> {
+        match &self.repr {
+            Repr::Element(elem) => Some(elem.select()),
+            Repr::Custom(elem) => Some(elem.select()),
+            _ => None,
+        }
+    }
+
     /// Call the function with the given arguments.
     pub fn call(&self, engine: &mut Engine, args: impl IntoArgs) -> SourceResult<Value> {
-        self.call_impl(engine, args.into_args(self.span))
+        let args = args.into_args(self.span);
+
+        // Arguments that don't come from a call expression carry the
+        // function's own span. For named closures, we trace errors back to
+        // the definition so that the function still shows up in the call
+        // stack.
+        if let (Repr::Closure(_), Some(name)) = (&self.repr, self.name()) {
+            if args.span == self.span && !self.span.is_detached() {
+                let point = || Tracepoint::Callback(name.into());
+                let world = engine.world;
+                return self.call_impl(engine, args).trace(world, point, self.span);
+            }
+        }
+
+        self.call_impl(engine, args)
     }
 
     /// Non-generic implementation of `call`.
@@ -268,23 +331,70 @@ impl Func {
                 args.finish()?;
                 Ok(Value::Content(value))
             }
-            Repr::Closure(closure) => crate::eval::call_closure(
-                self,
-                closure,
-                engine.world,
-                engine.introspector,
-                engine.route.track(),
-                engine.locator.track(),
-                TrackedMut::reborrow_mut(&mut engine.tracer),
-                args,
-            ),
+            Repr::Custom(elem) => {
+                let value = elem.construct(&mut args)?;
+                args.finish()?;
+                Ok(Value::Content(value))
+            }
+            Repr::Closure(closure) => self.call_closure(engine, closure, args),
             Repr::With(with) => {
                 args.items = with.1.items.iter().cloned().chain(args.items).collect();
                 with.0.call(engine, args)
             }
+            Repr::Memo(inner) => {
+                // Calls are cached based on their arguments, so we detach the
+                // argument spans to let calls from different places share
+                // results.
+                let mut detached = args.clone();
+                detached.span = Span::detached();
+                for arg in detached.items.make_mut() {
+                    arg.span = Span::detached();
+                    arg.value.span = Span::detached();
+                }
+
+                // Closures receive the memoized function for recursive calls
+                // so that these are cached, too.
+                let result = match &inner.repr {
+                    Repr::Closure(closure) => {
+                        self.call_closure(engine, closure, detached)
+                    }
+                    _ => inner.call_impl(engine, detached),
+                };
+
+                // Failed calls are cached like successful ones. Errors about
+                // the detached arguments are reported at the arguments of
+                // this call.
+                result.map_err(|mut errors| {
+                    for error in errors.make_mut() {
+                        if error.span.is_detached() {
+                            error.span = args.span;
+                        }
+                    }
+                    errors
+                })
+            }
         }
     }
 
+    /// Call a closure, with `self` being available to it for recursion.
+    fn call_closure(
+        &self,
+        engine: &mut Engine,
+        closure: &Prehashed<Closure>,
+        args: Args,
+    ) -> SourceResult<Value> {
+        crate::eval::call_closure(
+            self,
+            closure,
+            engine.world,
+            engine.introspector,
+            engine.route.track(),
+            engine.locator.track(),
+            TrackedMut::reborrow_mut(&mut engine.tracer),
+            args,
+        )
+    }
+
     /// The function's span.
     pub fn span(&self) -> Span {
         self.span
@@ -320,6 +430,35 @@ impl Func {
         }
     }
 
+    /// Returns a new function that caches its results.
+    ///
+    /// Since functions in Typst are [pure]($function/#note-on-function-purity),
+    /// calling a function twice with the same arguments yields the same result.
+    /// A memoized function remembers its results during a compilation and
+    /// returns them right away when it is called with the same arguments
+    /// again, even from a different place in the document. Recursive calls of a
+    /// memoized function are cached, too. This makes expensive computations,
+    /// like aggregating data or measuring content, much faster when they are
+    /// repeated.
+    ///
+    /// ```example
+    /// #let fib(n) = if n <= 1 { n } else {
+    ///   fib(n - 1) + fib(n - 2)
+    /// }
+    ///
+    /// #let fast = fib.memo()
+    /// #fast(50)
+    /// ```
+    #[func]
+    pub fn memo(self) -> Func {
+        if matches!(self.repr, Repr::Memo(_)) {
+            return self;
+        }
+
+        let span = self.span;
+        Self { repr: Repr::Memo(Arc::new(self)), span }
+    }
+
     /// Returns a selector that filters for elements belonging to this function
     /// whose fields have the values of the given arguments.
     #[func]
@@ -336,6 +475,10 @@ impl Func {
         let fields = args.to_named();
         args.items.retain(|arg| arg.name.is_none());
 
+        if let Some(custom) = self.custom() {
+            return custom.where_(fields);
+        }
+
         let element = self
             .element()
             .ok_or("`where()` can only be called on element functions")?;
@@ -399,6 +542,12 @@ impl From<Element> for Func {
     }
 }
 
+impl From<CustomElement> for Func {
+    fn from(elem: CustomElement) -> Self {
+        Repr::Custom(elem).into()
+    }
+}
+
 /// A Typst function that is defined by a native Rust type that shadows a
 /// native Rust function.
 pub trait NativeFunc {
@@ -468,6 +617,9 @@ pub struct Closure {
     pub node: SyntaxNode,
     /// Default values of named parameters.
     pub defaults: Vec<Value>,
+    /// The types that the parameters are annotated with, one entry per
+    /// parameter. An annotation with multiple types accepts any of them.
+    pub types: Vec<Option<Vec<Type>>>,
     /// Captured values from outer scopes.
     pub captured: Scope,
 }
@@ -0,0 +1,452 @@
+use std::sync::Arc;
+
+use ecow::EcoString;
+
+use crate::diag::{bail, error, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    array, cast, func, scope, ty, Array, Func, IntoValue, Repr, Value,
+};
+use crate::syntax::Span;
+
+/// A lazy sequence of values.
+///
+/// Unlike an [array]($array), a generator doesn't hold its values. Instead, it
+/// produces them one by one when they are needed. This means that generators
+/// can describe very large or even infinite sequences, of which only the
+/// required part is ever computed.
+///
+/// Methods like [`map`]($generator.map) and [`filter`]($generator.filter) return
+/// new generators without doing any work. The values are only produced by
+/// methods that consume the generator like [`collect`]($generator.collect) and
+/// [`find`]($generator.find) or by iterating over the generator with a
+/// [for loop]($scripting/#loops).
+///
+/// Generators without a known end, like those created from a function, may
+/// produce at most as many values as a `{while}` loop may run iterations.
+///
+/// # Example
+/// ```example
+/// #let squares = generator.range(1).map(n => n * n)
+/// #squares.take(5).collect() \
+/// #squares.find(n => n > 300)
+///
+/// #let fib = generator.unfold((0, 1), ((a, b)) => (a, (b, a + b)))
+/// #fib.skip(10).take(5).collect()
+///
+/// #for n in squares [
+///   #if n > 50 { break }
+///   #n
+/// ]
+/// ```
+#[ty(scope)]
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Generator(Arc<Kind>);
+
+/// The definition of a generator.
+#[derive(Debug, PartialEq, Hash)]
+enum Kind {
+    /// Integers from `start` up to, but not including, `end`.
+    Range { start: i64, end: Option<i64>, step: i64 },
+    /// The items of an array.
+    Array(Array),
+    /// The results of calling a function with increasing indices until it
+    /// returns `none`.
+    From(Func),
+    /// The values produced by repeatedly applying a function to a state.
+    Unfold(Value, Func),
+    /// The values of another generator, mapped with a function.
+    Map(Generator, Func),
+    /// The values of another generator for which a function returns `true`.
+    Filter(Generator, Func),
+    /// The first values of another generator.
+    Take(Generator, usize),
+    /// The values of another generator, without the first ones.
+    Skip(Generator, usize),
+    /// The values of another generator, paired with their index.
+    Enumerate(Generator, i64),
+}
+
+impl Generator {
+    /// Create a generator from its definition.
+    fn new(kind: Kind) -> Self {
+        Self(Arc::new(kind))
+    }
+
+    /// Start iterating over the generator's values.
+    pub fn iter(&self) -> GeneratorIter {
+        GeneratorIter(match &*self.0 {
+            Kind::Range { start, end, step } => Cursor::Range {
+                next: Some(*start),
+                end: *end,
+                step: *step,
+                count: 0,
+            },
+            Kind::Array(array) => Cursor::Array(array.clone(), 0),
+            Kind::From(func) => Cursor::From(func.clone(), 0),
+            Kind::Unfold(init, func) => {
+                Cursor::Unfold(func.clone(), Some(init.clone()), 0)
+            }
+            Kind::Map(inner, func) => Cursor::Map(Box::new(inner.iter()), func.clone()),
+            Kind::Filter(inner, func) => {
+                Cursor::Filter(Box::new(inner.iter()), func.clone())
+            }
+            Kind::Take(inner, n) => Cursor::Take(Box::new(inner.iter()), *n),
+            Kind::Skip(inner, n) => Cursor::Skip(Box::new(inner.iter()), *n),
+            Kind::Enumerate(inner, start) => {
+                Cursor::Enumerate(Box::new(inner.iter()), *start)
+            }
+        })
+    }
+}
+
+#[scope]
+impl Generator {
+    /// Creates a generator from a function or an array.
+    ///
+    /// A function is called with the indices `{0}`, `{1}`, `{2}` and so on and
+    /// the generator ends as soon as the function returns `{none}`.
+    ///
+    /// ```example
+    /// #generator(i => if i < 4 { i * 10 }).collect() \
+    /// #generator((1, 2, 3)).map(x => x * 2).collect()
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The function that produces the values or an array with the values.
+        source: GeneratorSource,
+    ) -> Generator {
+        match source {
+            GeneratorSource::Func(func) => Self::new(Kind::From(func)),
+            GeneratorSource::Array(array) => Self::new(Kind::Array(array)),
+        }
+    }
+
+    /// Creates a generator of integers.
+    ///
+    /// Unlike the [`range`]($array.range) function, this doesn't create an
+    /// array, so the range may be arbitrarily large. If the end is omitted,
+    /// the generator counts on forever.
+    ///
+    /// ```example
+    /// #generator.range(1, 1000000000).take(5).collect() \
+    /// #generator.range(0, step: 5).take(4).collect()
+    /// ```
+    #[func]
+    pub fn range(
+        /// The start of the range (inclusive).
+        #[default(0)]
+        start: i64,
+        /// The end of the range (exclusive). If `{none}`, the range is
+        /// infinite.
+        #[default]
+        end: Option<i64>,
+        /// The distance between the generated numbers.
+        #[named]
+        #[default(1)]
+        step: i64,
+    ) -> StrResult<Generator> {
+        if step == 0 {
+            bail!("step must not be zero");
+        }
+        Ok(Self::new(Kind::Range { start, end, step }))
+    }
+
+    /// Creates a generator that repeatedly applies a function to a state.
+    ///
+    /// The function receives the current state and returns either an array
+    /// with the next value and the next state or `{none}` to end the
+    /// generator.
+    ///
+    /// ```example
+    /// #generator
+    ///   .unfold(1, n => (n, n * 2))
+    ///   .take(8)
+    ///   .collect()
+    /// ```
+    #[func]
+    pub fn unfold(
+        /// The initial state.
+        init: Value,
+        /// The function that produces the next value and state.
+        step: Func,
+    ) -> Generator {
+        Self::new(Kind::Unfold(init, step))
+    }
+
+    /// Lazily transforms each value with a function.
+    #[func]
+    pub fn map(
+        self,
+        /// The function to apply to each value.
+        mapper: Func,
+    ) -> Generator {
+        Self::new(Kind::Map(self, mapper))
+    }
+
+    /// Lazily keeps only the values for which a function returns `{true}`.
+    #[func]
+    pub fn filter(
+        self,
+        /// The function to apply to each value. Must return a boolean.
+        test: Func,
+    ) -> Generator {
+        Self::new(Kind::Filter(self, test))
+    }
+
+    /// Produces at most the given number of values.
+    #[func]
+    pub fn take(
+        self,
+        /// How many values to produce at most.
+        count: usize,
+    ) -> Generator {
+        Self::new(Kind::Take(self, count))
+    }
+
+    /// Skips the given number of values.
+    #[func]
+    pub fn skip(
+        self,
+        /// How many values to skip.
+        count: usize,
+    ) -> Generator {
+        Self::new(Kind::Skip(self, count))
+    }
+
+    /// Pairs each value with its index.
+    ///
+    /// Produces arrays of the form `{(index, value)}`.
+    #[func]
+    pub fn enumerate(
+        self,
+        /// The index of the first value.
+        #[named]
+        #[default(0)]
+        start: i64,
+    ) -> Generator {
+        Self::new(Kind::Enumerate(self, start))
+    }
+
+    /// Produces all values and collects them into an array.
+    ///
+    /// Fails for generators that don't end.
+    #[func]
+    pub fn collect(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+    ) -> SourceResult<Array> {
+        let max = engine.world.library().limits.value_size / std::mem::size_of::<Value>();
+        let mut iter = self.iter();
+        let mut array = Array::new();
+        while let Some(value) = iter.next(engine, span)? {
+            if array.len() >= max {
+                bail!(error!(span, "maximum value size exceeded").into_fatal());
+            }
+            array.push(value);
+        }
+        Ok(array)
+    }
+
+    /// Returns the first value. Fails with an error if the generator is
+    /// empty.
+    #[func]
+    pub fn first(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+    ) -> SourceResult<Value> {
+        self.iter().next(engine, span)?.ok_or("generator is empty").at(span)
+    }
+
+    /// Searches for the first value for which a function returns `{true}`.
+    /// Returns `{none}` if the generator ends without such a value.
+    #[func]
+    pub fn find(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The function to apply to each value. Must return a boolean.
+        searcher: Func,
+    ) -> SourceResult<Option<Value>> {
+        let mut iter = self.iter();
+        while let Some(value) = iter.next(engine, span)? {
+            if searcher.call(engine, [value.clone()])?.cast::<bool>().at(span)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Folds all values into a single value using an accumulator function.
+    #[func]
+    pub fn fold(
+        self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The initial value to start with.
+        init: Value,
+        /// The folding function. Must have two parameters: One for the
+        /// accumulated value and one for a value.
+        folder: Func,
+    ) -> SourceResult<Value> {
+        let mut acc = init;
+        let mut iter = self.iter();
+        while let Some(value) = iter.next(engine, span)? {
+            acc = folder.call(engine, [acc, value])?;
+        }
+        Ok(acc)
+    }
+}
+
+impl Repr for Generator {
+    fn repr(&self) -> EcoString {
+        "generator(..)".into()
+    }
+}
+
+/// The source of a generator's values.
+pub enum GeneratorSource {
+    /// A function that is called with increasing indices.
+    Func(Func),
+    /// An array with the values.
+    Array(Array),
+}
+
+cast! {
+    GeneratorSource,
+    v: Func => Self::Func(v),
+    v: Array => Self::Array(v),
+}
+
+/// An iteration over the values of a generator.
+pub struct GeneratorIter(Cursor);
+
+/// The state of an iteration.
+enum Cursor {
+    Range { next: Option<i64>, end: Option<i64>, step: i64, count: usize },
+    Array(Array, usize),
+    From(Func, usize),
+    Unfold(Func, Option<Value>, usize),
+    Map(Box<GeneratorIter>, Func),
+    Filter(Box<GeneratorIter>, Func),
+    Take(Box<GeneratorIter>, usize),
+    Skip(Box<GeneratorIter>, usize),
+    Enumerate(Box<GeneratorIter>, i64),
+}
+
+impl GeneratorIter {
+    /// Produce the next value, if any.
+    ///
+    /// Errors are reported at the given span, unless they occur within a
+    /// function of the generator.
+    pub fn next(
+        &mut self,
+        engine: &mut Engine,
+        span: Span,
+    ) -> SourceResult<Option<Value>> {
+        Ok(match &mut self.0 {
+            Cursor::Range { next, end, step, count } => {
+                let Some(current) = *next else { return Ok(None) };
+                if end.map_or(false, |end| {
+                    (*step > 0 && current >= end) || (*step < 0 && current <= end)
+                }) {
+                    return Ok(None);
+                }
+                if end.is_none() {
+                    check_count(engine, span, count)?;
+                }
+                *next = current.checked_add(*step);
+                Some(current.into_value())
+            }
+            Cursor::Array(array, index) => {
+                let value = array.as_slice().get(*index).cloned();
+                *index += 1;
+                value
+            }
+            Cursor::From(func, index) => {
+                let current = *index as i64;
+                check_count(engine, span, index)?;
+                match func.call(engine, [current])? {
+                    Value::None => None,
+                    value => Some(value),
+                }
+            }
+            Cursor::Unfold(func, state, count) => {
+                let Some(current) = state.take() else { return Ok(None) };
+                check_count(engine, span, count)?;
+                match func.call(engine, [current])? {
+                    Value::None => None,
+                    Value::Array(pair) if pair.len() == 2 => {
+                        let mut pair = pair.into_iter();
+                        let value = pair.next().unwrap();
+                        *state = pair.next();
+                        Some(value)
+                    }
+                    v => bail!(
+                        span,
+                        "expected array with value and state or none, found {}",
+                        v.ty()
+                    ),
+                }
+            }
+            Cursor::Map(inner, func) => match inner.next(engine, span)? {
+                Some(value) => Some(func.call(engine, [value])?),
+                None => None,
+            },
+            Cursor::Filter(inner, func) => loop {
+                let Some(value) = inner.next(engine, span)? else { break None };
+                if func.call(engine, [value.clone()])?.cast::<bool>().at(span)? {
+                    break Some(value);
+                }
+            },
+            Cursor::Take(inner, remaining) => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+                *remaining -= 1;
+                inner.next(engine, span)?
+            }
+            Cursor::Skip(inner, remaining) => {
+                while *remaining > 0 {
+                    *remaining -= 1;
+                    if inner.next(engine, span)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+                inner.next(engine, span)?
+            }
+            Cursor::Enumerate(inner, index) => match inner.next(engine, span)? {
+                Some(value) => {
+                    let pair = array![*index, value];
+                    *index += 1;
+                    Some(pair.into_value())
+                }
+                None => None,
+            },
+        })
+    }
+}
+
+/// Count a value produced by a generator without a known end and check that
+/// it doesn't exceed the iteration limit.
+fn check_count(engine: &Engine, span: Span, count: &mut usize) -> SourceResult<()> {
+    let max = engine.world.library().limits.iterations;
+    if *count >= max {
+        bail!(
+            span, "generator produced too many values";
+            hint: "generators without a known end may produce at most {max} values"
+        );
+    }
+    *count += 1;
+    Ok(())
+}
@@ -4,7 +4,7 @@ use ecow::{eco_format, EcoString};
 
 use crate::{
     diag::StrResult,
-    foundations::{cast, func, repr, scope, ty, Repr, Str, Value},
+    foundations::{cast, func, repr, scope, ty, BigInt, Decimal, Repr, Str, Value},
 };
 
 /// A whole number.
@@ -37,6 +37,8 @@ impl i64 {
     ///
     /// - Booleans are converted to `0` or `1`.
     /// - Floats are floored to the next 64-bit integer.
+    /// - Decimal numbers are truncated to their integral part.
+    /// - Big integers are converted exactly if they fit into 64 bits.
     /// - Strings are parsed in base 10.
     ///
     /// ```example
@@ -231,6 +233,8 @@ cast! {
     ToInt,
     v: bool => Self(v as i64),
     v: f64 => Self(v as i64),
+    v: Decimal => Self(v.to_i64().ok_or("number too large")?),
+    v: BigInt => Self(v.to_i64().ok_or("number too large")?),
     v: Str => Self(parse_int(&v).map_err(|_| eco_format!("invalid integer: {}", v))?),
     v: i64 => Self(v),
 }
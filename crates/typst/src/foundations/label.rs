@@ -1,6 +1,6 @@
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{func, scope, ty, Repr};
+use crate::foundations::{func, scope, ty, Repr, Str};
 use crate::util::PicoStr;
 
 /// A label for an element.
@@ -31,6 +31,17 @@ use crate::util::PicoStr;
 ///
 /// Currently, labels can only be attached to elements in markup mode, not in
 /// code mode. This might change in the future.
+///
+/// # Namespaces
+/// A label's name can be prefixed with a namespace, separated by a colon, as in
+/// `[<intro:motivation>]`. References can resolve such labels by their short
+/// name within a [namespace]($ref.namespace).
+///
+/// ```example
+/// #let l = <intro:motivation>
+/// #l.namespace() \
+/// #l.name()
+/// ```
 #[ty(scope, cast)]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Label(PicoStr);
@@ -62,6 +73,22 @@ impl Label {
     ) -> Label {
         Self(name)
     }
+
+    /// The namespace of the label, that is, the part of its name before the
+    /// last colon. Is `{none}` if the label has no namespace.
+    #[func]
+    pub fn namespace(self) -> Option<Str> {
+        self.as_str().rsplit_once(':').map(|(namespace, _)| namespace.into())
+    }
+
+    /// The name of the label without its namespace.
+    #[func]
+    pub fn name(self) -> Str {
+        self.as_str()
+            .rsplit_once(':')
+            .map_or(self.as_str(), |(_, name)| name)
+            .into()
+    }
 }
 
 impl Repr for Label {
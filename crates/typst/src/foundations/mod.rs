@@ -7,23 +7,29 @@ pub mod sys;
 mod args;
 mod array;
 mod auto;
+mod bigint;
 mod bool;
 mod bytes;
 mod cast;
 mod content;
+mod custom;
 mod datetime;
+mod decimal;
 mod dict;
 mod duration;
 mod element;
 mod fields;
 mod float;
+mod format;
 mod func;
+mod generator;
 mod int;
 mod label;
 mod methods;
 mod module;
 mod none;
 mod plugin;
+mod random;
 mod scope;
 mod selector;
 mod str;
@@ -35,22 +41,28 @@ mod version;
 pub use self::args::*;
 pub use self::array::*;
 pub use self::auto::*;
+pub use self::bigint::*;
 pub use self::bytes::*;
 pub use self::cast::*;
 pub use self::content::*;
+pub use self::custom::*;
 pub use self::datetime::*;
+pub use self::decimal::*;
 pub use self::dict::*;
 pub use self::duration::*;
 pub use self::element::*;
 pub use self::fields::*;
 pub use self::float::*;
+pub use self::format::*;
 pub use self::func::*;
+pub use self::generator::*;
 pub use self::int::*;
 pub use self::label::*;
 pub use self::methods::*;
 pub use self::module::*;
 pub use self::none::*;
 pub use self::plugin::*;
+pub use self::random::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
 pub use self::selector::*;
@@ -88,18 +100,22 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<bool>();
     global.define_type::<i64>();
     global.define_type::<f64>();
+    global.define_type::<Decimal>();
+    global.define_type::<BigInt>();
     global.define_type::<Str>();
     global.define_type::<Label>();
     global.define_type::<Bytes>();
     global.define_type::<Content>();
     global.define_type::<Array>();
     global.define_type::<Dict>();
+    global.define_type::<Generator>();
     global.define_type::<Func>();
     global.define_type::<Args>();
     global.define_type::<Type>();
     global.define_type::<Module>();
     global.define_type::<Regex>();
     global.define_type::<Selector>();
+    global.define_type::<Styles>();
     global.define_type::<Datetime>();
     global.define_type::<Duration>();
     global.define_type::<Version>();
@@ -107,8 +123,10 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_func::<repr::repr>();
     global.define_func::<panic>();
     global.define_func::<assert>();
+    global.define_func::<catch>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define_func::<custom::element>();
     global.define_module(calc::module());
     global.define_module(sys::module(inputs));
 }
@@ -241,6 +259,51 @@ impl assert {
     }
 }
 
+/// Calls a function and catches the error it fails with, if any.
+///
+/// Returns a dictionary with three keys: `ok` is `{true}` if the function
+/// succeeded, `value` holds its return value, and `error` holds the error
+/// message if it failed. The keys that don't apply are `{none}`. This makes it
+/// possible to recover from errors, for example when loading data that may be
+/// malformed or when an [assertion]($assert) fails.
+///
+/// If a function fails with multiple errors, only the first one is returned.
+/// Errors that abort the compilation as a whole are not caught. This is the
+/// case when the compilation is cancelled or when a limit is exceeded, for
+/// example the maximum function call depth.
+///
+/// # Example
+/// ```example
+/// #let (ok, value, error) = catch(
+///   () => json.decode("{ broken")
+/// )
+///
+/// #if ok [Loaded: #value] else [
+///   Failed to load the data: #error
+/// ]
+/// ```
+#[func]
+pub fn catch(
+    /// The engine.
+    engine: &mut Engine,
+    /// The function to call. It is called without arguments.
+    func: Func,
+) -> SourceResult<Dict> {
+    Ok(match func.call(engine, Vec::<Value>::new()) {
+        Ok(value) => dict! {
+            "ok" => true,
+            "value" => value,
+            "error" => Value::None,
+        },
+        Err(errors) if errors.iter().any(|error| error.fatal) => return Err(errors),
+        Err(errors) => dict! {
+            "ok" => false,
+            "value" => Value::None,
+            "error" => errors.first().map(|error| error.message.clone()),
+        },
+    })
+}
+
 /// Evaluates a string as Typst code.
 ///
 /// This function should only be used as a last resort.
@@ -282,6 +345,23 @@ pub fn eval(
     #[named]
     #[default]
     scope: Dict,
+    /// Whether to evaluate the string without access to the standard library
+    /// and without being able to import or include files. Only the
+    /// definitions from `scope` are available then. Markup syntax like
+    /// `[*strong*]` and `[_emphasis_]` still works.
+    ///
+    /// This makes it safe to render markup that comes from data files:
+    ///
+    /// ```example
+    /// #let cells = ("*Name*", "_Ferris_", "Crab")
+    /// #for cell in cells {
+    ///   eval(cell, mode: "markup", restricted: true)
+    ///   [ ]
+    /// }
+    /// ```
+    #[named]
+    #[default(false)]
+    restricted: bool,
 ) -> SourceResult<Value> {
     let Spanned { v: text, span } = source;
     let dict = scope;
@@ -289,5 +369,9 @@ pub fn eval(
     for (key, value) in dict {
         scope.define(key, value);
     }
-    crate::eval::eval_string(engine.world, &text, span, mode, scope)
+    if restricted {
+        crate::eval::eval_string_restricted(engine.world, &text, span, mode, scope)
+    } else {
+        crate::eval::eval_string(engine.world, &text, span, mode, scope)
+    }
 }
@@ -7,17 +7,18 @@ use wasmi::{AsContext, AsContextMut};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, repr, scope, ty, Bytes};
+use crate::foundations::{cast, func, repr, scope, ty, Bytes, Str};
+use crate::loading::{load, ResourceKind};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// A WebAssembly plugin.
 ///
 /// Typst is capable of interfacing with plugins compiled to WebAssembly. Plugin
 /// functions may accept multiple [byte buffers]($bytes) as arguments and return
-/// a single byte buffer. They should typically be wrapped in idiomatic Typst
-/// functions that perform the necessary conversions between native Typst types
-/// and bytes.
+/// a single byte buffer. [Strings]($str) may also be passed as arguments, in
+/// which case the plugin receives their UTF-8 encoding. Plugin functions should
+/// typically be wrapped in idiomatic Typst functions that perform the necessary
+/// conversions between native Typst types and bytes.
 ///
 /// Plugins run in isolation from your system, which means that printing,
 /// reading files, or anything like that will not be supported for security
@@ -46,10 +47,7 @@ use crate::World;
 /// ```example
 /// #let myplugin = plugin("hello.wasm")
 /// #let concat(a, b) = str(
-///   myplugin.concatenate(
-///     bytes(a),
-///     bytes(b),
-///   )
+///   myplugin.concatenate(a, b)
 /// )
 ///
 /// #concat("hello", "world")
@@ -159,7 +157,7 @@ impl Plugin {
     ) -> SourceResult<Plugin> {
         let Spanned { v: path, span } = path;
         let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
+        let data = load(engine.world, id, ResourceKind::Data).at(span)?;
         Plugin::new(data).at(span)
     }
 }
@@ -324,6 +322,21 @@ impl Hash for Plugin {
     }
 }
 
+/// An argument to a plugin function.
+pub struct PluginArg(Bytes);
+
+impl From<PluginArg> for Bytes {
+    fn from(arg: PluginArg) -> Self {
+        arg.0
+    }
+}
+
+cast! {
+    PluginArg,
+    v: Bytes => Self(v),
+    v: Str => Self(v.as_bytes().into()),
+}
+
 /// Write the arguments to the plugin function into the plugin's memory.
 fn wasm_minimal_protocol_write_args_to_buffer(
     mut caller: wasmi::Caller<StoreData>,
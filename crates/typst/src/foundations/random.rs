@@ -0,0 +1,97 @@
+use std::hash::Hasher;
+
+use siphasher::sip::SipHasher13;
+
+use crate::foundations::{cast, Str};
+
+/// The seed of a random number generator.
+///
+/// Typst documents must compile to the same output every time, so all random
+/// functions take an explicit seed. The same seed always produces the same
+/// numbers. A seed may be an integer or a string.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Seed(u64);
+
+cast! {
+    Seed,
+    v: i64 => Self(v as u64),
+    v: Str => Self({
+        let mut hasher = SipHasher13::new();
+        hasher.write(v.as_bytes());
+        hasher.finish()
+    }),
+}
+
+/// A small, deterministic pseudorandom number generator (SplitMix64).
+///
+/// This is not suitable for cryptography, but it is fast, has good
+/// statistical properties and produces the same sequence on every platform.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from a seed.
+    pub fn new(seed: Seed) -> Self {
+        Self(seed.0)
+    }
+
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniformly distributed integer in `[0, bound)`.
+    ///
+    /// The bound must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        // Multiply-and-reject to avoid modulo bias.
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = self.next_u64() as u128 * bound as u128;
+            if (product as u64) >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+
+    /// Shuffle a slice in place with a Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng::new(Seed(42));
+        let mut b = Rng::new(Seed(42));
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_rng_ranges() {
+        let mut rng = Rng::new(Seed(7));
+        for _ in 0..1000 {
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+            assert!(rng.below(6) < 6);
+        }
+    }
+}
@@ -2,7 +2,10 @@
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{func, Str, Value};
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, BigInt, CustomElem, CustomOp, Decimal, Str, Value};
+use crate::syntax::Span;
 
 /// The Unicode minus sign.
 pub const MINUS_SIGN: &str = "\u{2212}";
@@ -11,11 +14,15 @@ pub const MINUS_SIGN: &str = "\u{2212}";
 ///
 /// When inserted into content, most values are displayed as this representation
 /// in monospace with syntax-highlighting. The exceptions are `{none}`,
-/// integers, floats, strings, content, and functions.
+/// integers, floats, decimal numbers, big integers, strings, content, and
+/// functions.
 ///
 /// **Note:** This function is for debugging purposes. Its output should not be
 /// considered stable and may change at any time!
 ///
+/// Instances of [custom elements]($element) can define their own
+/// representation with a `repr` operator.
+///
 /// # Example
 /// ```example
 /// #none vs #repr(none) \
@@ -25,10 +32,18 @@ pub const MINUS_SIGN: &str = "\u{2212}";
 /// ```
 #[func(title = "Representation")]
 pub fn repr(
+    /// The engine.
+    engine: &mut Engine,
+    /// The call site span.
+    span: Span,
     /// The value whose string representation to produce.
     value: Value,
-) -> Str {
-    value.repr().into()
+) -> SourceResult<Str> {
+    let custom = CustomElem::element_of(&value).and_then(|elem| elem.op(CustomOp::Repr));
+    if let Some(func) = custom.cloned() {
+        return func.call(engine, [value])?.cast::<Str>().at(span);
+    }
+    Ok(value.repr().into())
 }
 
 /// A trait that defines the `repr` of a Typst value.
@@ -119,6 +134,24 @@ pub fn display_float(value: f64) -> EcoString {
     }
 }
 
+/// Display a decimal number with a proper minus sign.
+pub fn display_decimal(value: Decimal) -> EcoString {
+    if value < Decimal::ZERO {
+        eco_format!("{}{}", MINUS_SIGN, value.abs())
+    } else {
+        eco_format!("{}", value)
+    }
+}
+
+/// Display a big integer with a proper minus sign.
+pub fn display_bigint(value: &BigInt) -> EcoString {
+    if value.is_negative() {
+        eco_format!("{}{}", MINUS_SIGN, value.abs())
+    } else {
+        eco_format!("{}", value)
+    }
+}
+
 /// Format pieces separated with commas and a final "and" or "or".
 pub fn separated_list(pieces: &[impl AsRef<str>], last: &str) -> String {
     let mut buf = String::new();
@@ -4,11 +4,12 @@ use std::hash::{Hash, Hasher};
 use ecow::{eco_format, EcoString};
 use indexmap::IndexMap;
 
-use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::diag::{HintedStrResult, HintedString, SourceDiagnostic};
 use crate::foundations::{
     Element, Func, IntoValue, Module, NativeElement, NativeFunc, NativeFuncData,
     NativeType, Type, Value,
 };
+use crate::syntax::Span;
 use crate::util::Static;
 use crate::Library;
 
@@ -62,6 +63,25 @@ impl<'a> Scopes<'a> {
             .ok_or_else(|| unknown_variable(var))
     }
 
+    /// Determine whether the binding a variable refers to is deprecated.
+    pub fn get_deprecation(&self, var: &str) -> Option<&Deprecation> {
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .chain(self.base.map(|base| base.global.scope()))
+            .find(|scope| scope.get(var).is_some())?
+            .get_deprecation(var)
+    }
+
+    /// Determine whether the binding a variable refers to in math is
+    /// deprecated.
+    pub fn get_deprecation_in_math(&self, var: &str) -> Option<&Deprecation> {
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .chain(self.base.map(|base| base.math.scope()))
+            .find(|scope| scope.get(var).is_some())?
+            .get_deprecation(var)
+    }
+
     /// Try to access a variable mutably.
     pub fn get_mut(&mut self, var: &str) -> HintedStrResult<&mut Value> {
         std::iter::once(&mut self.top)
@@ -69,7 +89,11 @@ impl<'a> Scopes<'a> {
             .find_map(|scope| scope.get_mut(var))
             .ok_or_else(|| {
                 match self.base.and_then(|base| base.global.scope().get(var)) {
-                    Some(_) => eco_format!("cannot mutate a constant: {}", var).into(),
+                    Some(_) => HintedString::from(eco_format!(
+                        "cannot mutate a constant: {}",
+                        var
+                    ))
+                    .with_code("E0011"),
                     _ => unknown_variable(var),
                 }
             })?
@@ -82,6 +106,7 @@ fn unknown_variable(var: &str) -> HintedString {
     let mut res = HintedString {
         message: eco_format!("unknown variable: {}", var),
         hints: vec![],
+        code: Some("E0001"),
     };
 
     if matches!(var, "none" | "auto" | "false" | "true") {
@@ -176,17 +201,30 @@ impl Scope {
         );
     }
 
+    /// Mark a definition as deprecated, so that its uses produce a warning.
+    ///
+    /// This panics if the name is not defined.
+    #[track_caller]
+    pub fn deprecate(&mut self, var: &str, deprecation: Deprecation) {
+        match self.map.get_mut(var) {
+            Some(slot) => slot.deprecation = Some(deprecation),
+            None => panic!("cannot deprecate undefined variable: {var}"),
+        }
+    }
+
     /// Try to access a variable immutably.
     pub fn get(&self, var: &str) -> Option<&Value> {
         self.map.get(var).map(Slot::read)
     }
 
+    /// Get the deprecation of a definition, if it is deprecated.
+    pub fn get_deprecation(&self, var: &str) -> Option<&Deprecation> {
+        self.map.get(var)?.deprecation.as_ref()
+    }
+
     /// Try to access a variable mutably.
     pub fn get_mut(&mut self, var: &str) -> Option<HintedStrResult<&mut Value>> {
-        self.map
-            .get_mut(var)
-            .map(Slot::write)
-            .map(|res| res.map_err(HintedString::from))
+        self.map.get_mut(var).map(Slot::write)
     }
 
     /// Get the category of a definition.
@@ -238,6 +276,8 @@ struct Slot {
     kind: Kind,
     /// The category of the slot.
     category: Option<Category>,
+    /// Whether the binding is deprecated.
+    deprecation: Option<Deprecation>,
 }
 
 /// The different kinds of slots.
@@ -252,7 +292,7 @@ enum Kind {
 impl Slot {
     /// Create a new slot.
     fn new(value: Value, kind: Kind, category: Option<Category>) -> Self {
-        Self { value, kind, category }
+        Self { value, kind, category, deprecation: None }
     }
 
     /// Read the value.
@@ -261,16 +301,77 @@ impl Slot {
     }
 
     /// Try to write to the value.
-    fn write(&mut self) -> StrResult<&mut Value> {
+    fn write(&mut self) -> HintedStrResult<&mut Value> {
         match self.kind {
             Kind::Normal => Ok(&mut self.value),
-            Kind::Captured => {
-                bail!(
-                    "variables from outside the function are \
-                     read-only and cannot be modified"
-                )
-            }
+            Kind::Captured => Err(HintedString::from(EcoString::from(
+                "variables from outside the function are \
+                 read-only and cannot be modified",
+            ))
+            .with_code("E0011")),
+        }
+    }
+}
+
+/// Describes that a definition is deprecated and what to use instead.
+///
+/// Deprecated definitions keep working for a while, but their uses produce
+/// a warning. Native functions can report the same warning for deprecated
+/// parameters through [`warning`](Self::warning).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Deprecation {
+    message: &'static str,
+    until: Option<&'static str>,
+    replacement: Option<&'static str>,
+}
+
+impl Deprecation {
+    /// Create a deprecation with a message that suggests a replacement, like
+    /// ``"use `partial` instead"``.
+    pub const fn new(message: &'static str) -> Self {
+        Self { message, until: None, replacement: None }
+    }
+
+    /// Set the version in which the definition will be removed.
+    pub const fn with_until(self, version: &'static str) -> Self {
+        Self { until: Some(version), ..self }
+    }
+
+    /// Set the name of the definition that replaces this one, so that uses
+    /// can be fixed automatically.
+    pub const fn with_replacement(self, name: &'static str) -> Self {
+        Self { replacement: Some(name), ..self }
+    }
+
+    /// The message that suggests a replacement.
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+
+    /// The version in which the definition will be removed, if known.
+    pub fn until(&self) -> Option<&'static str> {
+        self.until
+    }
+
+    /// The name of the definition that replaces this one, if any.
+    pub fn replacement(&self) -> Option<&'static str> {
+        self.replacement
+    }
+
+    /// Create the warning for a use of the deprecated definition with the
+    /// given name.
+    pub fn warning(&self, span: Span, name: &str) -> SourceDiagnostic {
+        let mut warning =
+            SourceDiagnostic::warning(span, eco_format!("`{name}` is deprecated"))
+                .with_code("W0002");
+        warning.hint(self.message);
+        if let Some(version) = self.until {
+            warning.hint(eco_format!("it will be removed in Typst {version}"));
+        }
+        if let Some(replacement) = self.replacement {
+            warning.fix(span, replacement);
         }
+        warning
     }
 }
 
@@ -1,15 +1,17 @@
 use std::any::{Any, TypeId};
 use std::sync::Arc;
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
 use smallvec::SmallVec;
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{
-    cast, func, repr, scope, ty, CastInfo, Content, Dict, Element, FromValue, Func,
-    Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
+    cast, func, repr, scope, ty, CastInfo, Content, CustomElem, CustomElement, Dict,
+    Element, FromValue, Func, IntoValue, Label, Reflect, Regex, Repr, Str, StyleChain,
+    Type, Value,
 };
-use crate::introspection::{Locatable, Location};
+use crate::introspection::{Introspector, Locatable, Location, Meta, MetaElem};
 use crate::symbols::Symbol;
 use crate::text::TextElem;
 
@@ -84,6 +86,11 @@ pub enum Selector {
     /// If there is a dictionary, only elements with the fields from the
     /// dictionary match.
     Elem(Element, Option<SmallVec<[(u8, Value); 1]>>),
+    /// Matches a specific type of user-defined element.
+    ///
+    /// If there is a dictionary, only elements with the fields from the
+    /// dictionary match.
+    Custom(CustomElement, Option<Dict>),
     /// Matches the element at the specified location.
     Location(Location),
     /// Matches elements with a specific label.
@@ -100,6 +107,9 @@ pub enum Selector {
     Before { selector: Arc<Self>, end: Arc<Self>, inclusive: bool },
     /// Matches all matches of `selector` after `start`.
     After { selector: Arc<Self>, start: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` that are nested in a match of
+    /// `ancestor`.
+    Within { selector: Arc<Self>, ancestor: Arc<Self> },
 }
 
 impl Selector {
@@ -132,16 +142,24 @@ impl Selector {
         match self {
             Self::Elem(element, dict) => {
                 // TODO: Optimize field access to not clone.
-                target.func() == *element
+                target.elem() == *element
                     && dict.iter().flat_map(|dict| dict.iter()).all(|(id, value)| {
                         target.get(*id, styles).as_ref() == Some(value)
                     })
             }
+            Self::Custom(element, dict) => {
+                target.to_packed::<CustomElem>().map_or(false, |elem| {
+                    elem.element() == element
+                        && dict.iter().flat_map(|dict| dict.iter()).all(|(key, value)| {
+                            elem.values().get(key).ok() == Some(value)
+                        })
+                })
+            }
             Self::Label(label) => target.label() == Some(*label),
             Self::Regex(regex) => target
                 .to_packed::<TextElem>()
                 .map_or(false, |elem| regex.is_match(elem.text())),
-            Self::Can(cap) => target.func().can_type_id(*cap),
+            Self::Can(cap) => target.elem().can_type_id(*cap),
             Self::Or(selectors) => {
                 selectors.iter().any(move |sel| sel.matches(target, styles))
             }
@@ -149,6 +167,14 @@ impl Selector {
                 selectors.iter().all(move |sel| sel.matches(target, styles))
             }
             Self::Location(location) => target.location() == Some(*location),
+            Self::Within { selector, ancestor } => {
+                selector.matches(target, styles)
+                    && styles.map_or(false, |styles| {
+                        MetaElem::data_in(styles).iter().any(|meta| {
+                            matches!(meta, Meta::Elem(elem) if ancestor.matches(elem, None))
+                        })
+                    })
+            }
             // Not supported here.
             Self::Before { .. } | Self::After { .. } => false,
         }
@@ -232,6 +258,46 @@ impl Selector {
             inclusive,
         }
     }
+
+    /// Returns a modified selector that will only match elements that are
+    /// nested in an element matching `ancestor`.
+    ///
+    /// For example, `{link.within(heading)}` matches links in headings and
+    /// `{figure.within(<appendix>)}` matches all figures in content labelled
+    /// as `<appendix>`. Only elements that can be [queried]($query) and
+    /// labelled elements can be ancestors.
+    ///
+    /// ```example
+    /// #show emph.within(heading): set text(red)
+    /// = An _important_ heading
+    /// Some _less important_ text.
+    /// ```
+    #[func]
+    pub fn within(
+        self,
+        /// The element the selected elements must be nested in.
+        ancestor: LocatableSelector,
+    ) -> Selector {
+        Self::Within {
+            selector: Arc::new(self),
+            ancestor: Arc::new(ancestor.0),
+        }
+    }
+
+    /// Returns a selector that matches elements of `selector` that are nested
+    /// in an element matching this selector.
+    ///
+    /// This is the reverse of [`within`]($selector.within):
+    /// `{heading.descendant(emph)}` is the same as `{emph.within(heading)}`.
+    #[func]
+    pub fn descendant(
+        self,
+        /// The selector the nested elements must match.
+        selector: Selector,
+    ) -> StrResult<Selector> {
+        let ancestor = LocatableSelector::from_value(self.into_value())?;
+        Ok(selector.within(ancestor))
+    }
 }
 
 impl From<Location> for Selector {
@@ -255,6 +321,10 @@ impl Repr for Selector {
                     elem.name().into()
                 }
             }
+            Self::Custom(elem, dict) => match dict {
+                Some(dict) => eco_format!("{}.where{}", elem.name(), dict.repr()),
+                None => elem.name().into(),
+            },
             Self::Label(label) => label.repr(),
             Self::Regex(regex) => regex.repr(),
             Self::Can(cap) => eco_format!("{cap:?}"),
@@ -277,6 +347,9 @@ impl Repr for Selector {
                     inclusive_arg
                 )
             }
+            Self::Within { selector, ancestor } => {
+                eco_format!("{}.within({})", selector.repr(), ancestor.repr())
+            }
         }
     }
 }
@@ -284,9 +357,8 @@ impl Repr for Selector {
 cast! {
     type Selector,
     func: Func => func
-        .element()
-        .ok_or("only element functions can be used as selectors")?
-        .select(),
+        .to_selector()
+        .ok_or("only element functions can be used as selectors")?,
     label: Label => Self::Label(label),
     text: EcoString => Self::text(&text)?,
     regex: Regex => Self::regex(regex)?,
@@ -332,6 +404,7 @@ impl FromValue for LocatableSelector {
                         Err(eco_format!("{} is not locatable", elem.name()))?
                     }
                 }
+                Selector::Custom(..) => {}
                 Selector::Location(_) => {}
                 Selector::Label(_) => {}
                 Selector::Regex(_) => bail!("text is not locatable"),
@@ -342,7 +415,8 @@ impl FromValue for LocatableSelector {
                     }
                 }
                 Selector::Before { selector, end: split, .. }
-                | Selector::After { selector, start: split, .. } => {
+                | Selector::After { selector, start: split, .. }
+                | Selector::Within { selector, ancestor: split } => {
                     for selector in [selector, split] {
                         validate(selector)?;
                     }
@@ -361,6 +435,23 @@ impl FromValue for LocatableSelector {
     }
 }
 
+impl LocatableSelector {
+    /// Resolve the selector to the location of the single element it matches.
+    pub fn resolve_unique(
+        &self,
+        introspector: Tracked<Introspector>,
+    ) -> StrResult<Location> {
+        match &self.0 {
+            Selector::Location(location) => Ok(*location),
+            other => match introspector.query(other).as_slice() {
+                [elem] => Ok(elem.location().unwrap()),
+                [] => bail!("selector does not match any element"),
+                _ => bail!("selector matches multiple elements"),
+            },
+        }
+    }
+}
+
 impl From<Location> for LocatableSelector {
     fn from(loc: Location) -> Self {
         Self(Selector::Location(loc))
@@ -410,6 +501,7 @@ impl FromValue for ShowableSelector {
         fn validate(selector: &Selector, nested: bool) -> StrResult<()> {
             match selector {
                 Selector::Elem(_, _) => {}
+                Selector::Custom(_, _) => {}
                 Selector::Label(_) => {}
                 Selector::Regex(_) if !nested => {}
                 Selector::Or(list) | Selector::And(list) => {
@@ -417,6 +509,7 @@ impl FromValue for ShowableSelector {
                         validate(selector, true)?;
                     }
                 }
+                Selector::Within { selector, .. } => validate(selector, true)?,
                 Selector::Regex(_)
                 | Selector::Location(_)
                 | Selector::Can(_)
@@ -5,13 +5,15 @@ use std::ops::{Add, AddAssign, Deref, Range};
 
 use ecow::EcoString;
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, dict, func, repr, scope, ty, Array, Bytes, Dict, Func, IntoValue, Label, Repr,
-    Type, Value, Version,
+    cast, dict, format_template, func, repr, scope, ty, Args, Array, BigInt, Bytes, Cast,
+    Decimal, Dict, Func, IntoValue, Label, Repr, Type, Value, Version,
 };
 use crate::layout::Alignment;
 use crate::syntax::{Span, Spanned};
@@ -67,6 +69,22 @@ pub use ecow::eco_format;
 /// - `[\r]` for a carriage return
 /// - `[\t]` for a tab
 /// - `[\u{1f600}]` for a hexadecimal Unicode escape sequence
+///
+/// # Raw strings { #raw }
+/// A string prefixed with an `r` is a raw string, in which backslashes have no
+/// special meaning. This is useful for regular expressions and Windows paths.
+/// To include a quote in a raw string, add the same number of hashes before
+/// the opening and after the closing quote, like in `r#"..."#`. Raw strings
+/// can span multiple lines and keep their formatting as is.
+///
+/// In markup, put raw strings in parentheses, like in `#(r"...")`. Directly
+/// after a hash, `r` refers to a variable of that name.
+///
+/// ```example
+/// #(r"C:\Users\new") \
+/// #regex(r"\d+\.\d+") \
+/// #(r#"say "hi""#)
+/// ```
 #[ty(scope, cast, title = "String")]
 #[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[derive(Serialize, Deserialize)]
@@ -92,6 +110,16 @@ impl Str {
         Ok(Self(self.0.repeat(n)))
     }
 
+    /// Whether the string is non-empty and every grapheme cluster in it
+    /// starts with a character that fulfills the predicate.
+    fn all_clusters(&self, predicate: fn(char) -> bool) -> bool {
+        !self.is_empty()
+            && self
+                .as_str()
+                .graphemes(true)
+                .all(|cluster| cluster.chars().next().is_some_and(predicate))
+    }
+
     /// A string slice containing the entire string.
     pub fn as_str(&self) -> &str {
         self
@@ -129,6 +157,8 @@ impl Str {
     /// - Integers are formatted in base 10. This can be overridden with the
     ///   optional `base` parameter.
     /// - Floats are formatted in base 10 and never in exponential notation.
+    /// - Decimal numbers are formatted with all of their fractional digits.
+    /// - Big integers are formatted in base 10.
     /// - From labels the name is extracted.
     /// - Bytes are decoded as UTF-8.
     ///
@@ -251,6 +281,97 @@ impl Str {
         self.chars().map(|c| Value::Str(c.into())).collect()
     }
 
+    /// The display width of the string in columns of a monospace font.
+    ///
+    /// Most grapheme clusters take up one column, while East Asian wide
+    /// characters and most emoji take up two. This is useful to align text in
+    /// [raw blocks]($raw), where the [length]($str.len) in bytes is
+    /// misleading.
+    ///
+    /// ```example
+    /// #"abc".width() \
+    /// #"日本".width() \
+    /// #"e\u{301}".width() \
+    /// #"👩‍👩‍👧".width()
+    /// ```
+    #[func]
+    pub fn width(&self) -> usize {
+        self.as_str()
+            .graphemes(true)
+            .map(|cluster| UnicodeWidthStr::width(cluster).min(2))
+            .sum()
+    }
+
+    /// Converts the string into a Unicode normalization form.
+    ///
+    /// The same text can be encoded with different codepoints. For instance,
+    /// `é` can be a single codepoint or an `e` followed by a combining accent.
+    /// Normalizing strings before comparing them makes such strings equal.
+    ///
+    /// ```example
+    /// #let a = "\u{e9}"
+    /// #let b = "e\u{301}"
+    /// #(a == b) \
+    /// #(a.normalize() == b.normalize()) \
+    /// #a.normalize(form: "nfd").codepoints()
+    /// ```
+    #[func]
+    pub fn normalize(
+        &self,
+        /// The normalization form to convert into.
+        #[named]
+        #[default(UnicodeNormalForm::Nfc)]
+        form: UnicodeNormalForm,
+    ) -> Str {
+        match form {
+            UnicodeNormalForm::Nfc => self.nfc().collect(),
+            UnicodeNormalForm::Nfd => self.nfd().collect(),
+            UnicodeNormalForm::Nfkc => self.nfkc().collect(),
+            UnicodeNormalForm::Nfkd => self.nfkd().collect(),
+        }
+    }
+
+    /// Whether the string is non-empty and consists only of letters.
+    ///
+    /// A letter with combining marks counts as a letter.
+    ///
+    /// ```example
+    /// #"Straße".is-alphabetic() \
+    /// #"e\u{301}".is-alphabetic() \
+    /// #"R2D2".is-alphabetic()
+    /// ```
+    #[func]
+    pub fn is_alphabetic(&self) -> bool {
+        self.all_clusters(char::is_alphabetic)
+    }
+
+    /// Whether the string is non-empty and consists only of numeric
+    /// characters.
+    ///
+    /// This includes digits of all scripts as well as other numeric
+    /// characters, like fractions and Roman numerals.
+    ///
+    /// ```example
+    /// #"2024".is-numeric() \
+    /// #"٣½".is-numeric() \
+    /// #"-1".is-numeric()
+    /// ```
+    #[func]
+    pub fn is_numeric(&self) -> bool {
+        self.all_clusters(char::is_numeric)
+    }
+
+    /// Whether the string is non-empty and consists only of whitespace.
+    ///
+    /// ```example
+    /// #" \t".is-whitespace() \
+    /// #"".is-whitespace()
+    /// ```
+    #[func]
+    pub fn is_whitespace(&self) -> bool {
+        self.all_clusters(char::is_whitespace)
+    }
+
     /// Converts a character into its corresponding code point.
     ///
     /// ```example
@@ -378,6 +499,10 @@ impl Str {
     ///   group. The first item of the array contains the first matched
     ///   capturing, not the whole match! This is empty unless the `pattern` was
     ///   a regex with capturing groups.
+    /// - `named`: A dictionary containing a string for each matched named
+    ///   capturing group, like `(?<year>\d+)`, under its name. Groups that
+    ///   didn't match are `{none}`. This is empty unless the `pattern` was a
+    ///   regex with named capturing groups.
     #[func]
     pub fn match_(
         &self,
@@ -388,7 +513,9 @@ impl Str {
             StrPattern::Str(pat) => {
                 self.0.match_indices(pat.as_str()).next().map(match_to_dict)
             }
-            StrPattern::Regex(re) => re.captures(self).map(captures_to_dict),
+            StrPattern::Regex(re) => {
+                re.captures(self).map(|cap| captures_to_dict(&re, cap))
+            }
         }
     }
 
@@ -410,7 +537,7 @@ impl Str {
                 .collect(),
             StrPattern::Regex(re) => re
                 .captures_iter(self)
-                .map(captures_to_dict)
+                .map(|cap| captures_to_dict(&re, cap))
                 .map(Value::Dict)
                 .collect(),
         }
@@ -471,7 +598,7 @@ impl Str {
                 for caps in re.captures_iter(self).take(count) {
                     // Extract the entire match over all capture groups.
                     let m = caps.get(0).unwrap();
-                    handle_match(m.start()..m.end(), captures_to_dict(caps))?;
+                    handle_match(m.start()..m.end(), captures_to_dict(re, caps))?;
                 }
             }
         }
@@ -596,6 +723,58 @@ impl Str {
         }
         s.into()
     }
+
+    /// Fills this string, which acts as a template, with values.
+    ///
+    /// Each pair of braces in the template is a placeholder that is replaced
+    /// with one of the values. An empty placeholder `{}` takes the next
+    /// positional value, `{1}` takes the positional value with that index and
+    /// `{name}` takes the named value with that name. To insert a literal
+    /// brace, double it: `{{` or `}}`.
+    ///
+    /// After a colon, a placeholder may contain a format specification that
+    /// controls how the value is formatted. It consists of the following
+    /// parts, all of which are optional, in this order:
+    ///
+    /// - A fill character followed by an alignment: `<` (left), `^` (center),
+    ///   or `>` (right). The fill character defaults to a space. Numbers are
+    ///   right-aligned by default, all other values are left-aligned.
+    /// - A sign: `+` shows a sign for positive numbers, too, and a space
+    ///   leaves room for the sign of positive numbers.
+    /// - A `0` to pad numbers with zeros after the sign.
+    /// - The minimum width in characters.
+    /// - A `,` or `_` to separate groups of thousands.
+    /// - A dot and a precision: The number of digits after the decimal point
+    ///   for numbers or the maximum number of characters for other values.
+    /// - A type: `f` for fixed-point notation (six digits by default), `e`
+    ///   for exponential notation, `%` for percentages, `x`, `X`, `o`, and `b`
+    ///   for hexadecimal, octal, and binary integers, or `s` to format
+    ///   numbers like any other value.
+    ///
+    /// Values other than numbers are formatted like with the [`str`]($str)
+    /// constructor if possible and like with [`repr`]($repr) otherwise.
+    ///
+    /// ```example
+    /// #"{} costs {:.2f}".format("Tea", 3.5) \
+    /// #"{name:<8}|{value:>8,}".format(name: "total", value: 12345) \
+    /// #"{:+.1%} growth".format(0.045) \
+    /// #str.format("{0:x} = {0}", 255)
+    /// ```
+    #[func]
+    pub fn format(
+        &self,
+        /// The real arguments (the other argument is just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The values to insert into the template.
+        #[external]
+        #[variadic]
+        values: Vec<Value>,
+    ) -> StrResult<Str> {
+        let args = args.take();
+        format_template(self.as_str(), args.to_pos().as_slice(), &args.to_named())
+            .map(Into::into)
+    }
 }
 
 impl Deref for Str {
@@ -763,6 +942,8 @@ cast! {
     ToStr,
     v: i64 => Self::Int(v),
     v: f64 => Self::Str(repr::display_float(v).into()),
+    v: Decimal => Self::Str(repr::display_decimal(v).into()),
+    v: BigInt => Self::Str(repr::display_bigint(&v).into()),
     v: Version => Self::Str(format_str!("{}", v)),
     v: Bytes => Self::Str(
         std::str::from_utf8(&v)
@@ -781,11 +962,12 @@ fn match_to_dict((start, text): (usize, &str)) -> Dict {
         "end" => start + text.len(),
         "text" => text,
         "captures" => Array::new(),
+        "named" => Dict::new(),
     }
 }
 
 /// Convert regex captures to a dictionary.
-fn captures_to_dict(cap: regex::Captures) -> Dict {
+fn captures_to_dict(re: &regex::Regex, cap: regex::Captures) -> Dict {
     let m = cap.get(0).expect("missing first match");
     dict! {
         "start" => m.start(),
@@ -795,6 +977,13 @@ fn captures_to_dict(cap: regex::Captures) -> Dict {
             .skip(1)
             .map(|opt| opt.map_or(Value::None, |m| m.as_str().into_value()))
             .collect::<Array>(),
+        "named" => re.capture_names()
+            .flatten()
+            .map(|name| {
+                let m = cap.name(name);
+                (Str::from(name), m.map_or(Value::None, |m| m.as_str().into_value()))
+            })
+            .collect::<Dict>(),
     }
 }
 
@@ -936,6 +1125,23 @@ cast! {
     },
 }
 
+/// A Unicode normalization form.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum UnicodeNormalForm {
+    /// Canonical composition, where accented letters are a single codepoint
+    /// where possible.
+    Nfc,
+    /// Canonical decomposition, where accented letters are split into a base
+    /// letter and combining marks.
+    Nfd,
+    /// Compatibility composition, which additionally replaces compatibility
+    /// characters like ligatures and superscripts with their plain
+    /// equivalents.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
 /// A replacement for a matched [`Str`]
 pub enum Replacement {
     /// A string a match is replaced with.
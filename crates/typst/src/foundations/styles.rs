@@ -2,19 +2,20 @@ use std::any::{Any, TypeId};
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::ops::Add;
 use std::{iter, mem, ptr};
 
 use comemo::Prehashed;
 use ecow::{eco_vec, EcoString, EcoVec};
 use smallvec::SmallVec;
 
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{At, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, ty, Content, Element, Func, NativeElement, Packed, Repr, Selector,
-    Show,
+    cast, elem, func, scope, ty, Args, Content, Element, Func, NativeElement, Packed,
+    Repr, Selector, Show, Value,
 };
-use crate::syntax::Span;
+use crate::syntax::{Span, Spanned};
 use crate::text::{FontFamily, FontList, TextElem};
 
 /// Provides access to active styles.
@@ -63,11 +64,80 @@ impl Show for Packed<StyleElem> {
     }
 }
 
-/// A list of style properties.
-#[ty(cast)]
+/// A collection of style properties and show rules.
+///
+/// Styles let you capture the effect of [set rules]($styling/#set-rules) in a
+/// value. You can store them in variables, pass them to functions, and combine
+/// them with `+`, where the styles on the right take precedence. This is useful
+/// to build reusable themes.
+///
+/// To apply styles, use them as the transformation of a
+/// [show rule]($styling/#show-rules), either for the rest of the document or
+/// for specific elements, or call [`apply`]($styles.apply) on some content.
+///
+/// # Example
+/// ```example
+/// #let emphasis = styles(text, weight: "bold", fill: red)
+/// #let mono = styles(text, font: "DejaVu Sans Mono")
+///
+/// #show heading: emphasis
+/// = Introduction
+/// Some #emphasis.apply[important] and
+/// #(emphasis + mono).apply[very important] text.
+/// ```
+#[ty(scope, cast)]
 #[derive(Default, PartialEq, Clone, Hash)]
 pub struct Styles(EcoVec<Prehashed<Style>>);
 
+#[scope]
+impl Styles {
+    /// Creates the styles that a set rule with the given element function and
+    /// arguments would produce.
+    ///
+    /// ```example
+    /// #let big = styles(text, size: 16pt)
+    /// #big.apply[Large text]
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The engine.
+        engine: &mut Engine,
+        /// The call site span.
+        span: Span,
+        /// The element function whose properties to set.
+        element: Spanned<Func>,
+        /// The real arguments (the other argument is just for the docs).
+        /// The docs argument cannot be called `args`.
+        args: &mut Args,
+        /// The properties to set.
+        #[external]
+        #[variadic]
+        fields: Vec<Value>,
+    ) -> SourceResult<Styles> {
+        let target = element
+            .v
+            .element()
+            .ok_or("only element functions can be used to create styles")
+            .at(element.span)?;
+        Ok(target.set(engine, args.take())?.spanned(span))
+    }
+
+    /// Applies the styles to content.
+    ///
+    /// ```example
+    /// #let quiet = styles(text, fill: gray)
+    /// #quiet.apply[Psst!]
+    /// ```
+    #[func(name = "apply")]
+    pub fn apply_to(
+        &self,
+        /// The content to style.
+        body: Content,
+    ) -> Content {
+        body.styled_with_map(self.clone())
+    }
+}
+
 impl Styles {
     /// Create a new, empty style list.
     pub fn new() -> Self {
@@ -160,6 +230,16 @@ impl From<Style> for Styles {
     }
 }
 
+impl Add for Styles {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut styles = rhs;
+        styles.apply(self);
+        styles
+    }
+}
+
 impl Debug for Styles {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Styles ")?;
@@ -392,7 +472,7 @@ impl Recipe {
             Transformation::Func(func) => {
                 let mut result = func.call(engine, [content.clone()]);
                 if self.selector.is_some() {
-                    let point = || Tracepoint::Show(content.func().name().into());
+                    let point = || Tracepoint::Show(content.elem().name().into());
                     result = result.trace(engine.world, point, content.span());
                 }
                 result?.display()
@@ -446,6 +526,7 @@ cast! {
     Transformation,
     content: Content => Self::Content(content),
     func: Func => Self::Func(func),
+    styles: Styles => Self::Style(styles),
 }
 
 /// A chain of styles, similar to a linked list.
@@ -1,6 +1,8 @@
 //! System-related things.
 
-use crate::foundations::{Dict, Module, Scope, Version};
+use ecow::eco_format;
+
+use crate::foundations::{Dict, Module, Scope, Value, Version};
 
 /// A module with system-related things.
 pub fn module(inputs: Dict) -> Module {
@@ -16,3 +18,32 @@ pub fn module(inputs: Dict) -> Module {
     scope.define("inputs", inputs);
     Module::new("sys", scope)
 }
+
+/// Add `sys.features` to the `sys` module of the finished global scope.
+///
+/// The features are the names of the global functions, types, and modules and
+/// of the functions and types defined in them (like `calc.round`). Since they
+/// are collected from the scope itself, they can't fall behind what the
+/// compiler provides.
+pub(crate) fn define_features(global: &mut Scope) {
+    let mut features = Dict::new();
+    for (name, value) in global.iter() {
+        let scope = match value {
+            Value::Func(func) => func.scope(),
+            Value::Type(ty) => Some(ty.scope()),
+            Value::Module(module) => Some(module.scope()),
+            _ => continue,
+        };
+
+        features.insert(name.clone().into(), Value::Bool(true));
+        for (member, value) in scope.into_iter().flat_map(Scope::iter) {
+            if matches!(value, Value::Func(_) | Value::Type(_)) {
+                features.insert(eco_format!("{name}.{member}").into(), Value::Bool(true));
+            }
+        }
+    }
+
+    if let Some(Ok(Value::Module(sys))) = global.get_mut("sys") {
+        sys.scope_mut().define("features", features);
+    }
+}
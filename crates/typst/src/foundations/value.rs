@@ -12,9 +12,9 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::diag::StrResult;
 use crate::eval::ops;
 use crate::foundations::{
-    fields, repr, Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime, Dict,
-    Duration, FromValue, Func, IntoValue, Label, Module, NativeElement, NativeType,
-    NoneValue, Plugin, Reflect, Repr, Scope, Str, Styles, Type, Version,
+    fields, repr, Args, Array, AutoValue, BigInt, Bytes, CastInfo, Content, Datetime,
+    Decimal, Dict, Duration, FromValue, Func, IntoValue, Label, Module, NativeElement,
+    NativeType, NoneValue, Plugin, Reflect, Repr, Scope, Str, Styles, Type, Version,
 };
 use crate::layout::{Abs, Angle, Em, Fr, Length, Ratio, Rel};
 use crate::symbols::Symbol;
@@ -36,6 +36,10 @@ pub enum Value {
     Int(i64),
     /// A floating-point number: `1.2`, `10e-4`.
     Float(f64),
+    /// A decimal number: `decimal("1.2")`.
+    Decimal(Decimal),
+    /// An arbitrary-precision integer: `bigint("12345678901234567890")`.
+    BigInt(BigInt),
     /// A length: `12pt`, `3cm`, `1.5em`, `1em - 2pt`.
     Length(Length),
     /// An angle: `1.5rad`, `90deg`.
@@ -121,6 +125,8 @@ impl Value {
             Self::Bool(_) => Type::of::<bool>(),
             Self::Int(_) => Type::of::<i64>(),
             Self::Float(_) => Type::of::<f64>(),
+            Self::Decimal(_) => Type::of::<Decimal>(),
+            Self::BigInt(_) => Type::of::<BigInt>(),
             Self::Length(_) => Type::of::<Length>(),
             Self::Angle(_) => Type::of::<Angle>(),
             Self::Ratio(_) => Type::of::<Ratio>(),
@@ -203,6 +209,8 @@ impl Value {
             Self::None => Content::empty(),
             Self::Int(v) => TextElem::packed(repr::format_int_with_base(v, 10)),
             Self::Float(v) => TextElem::packed(repr::display_float(v)),
+            Self::Decimal(v) => TextElem::packed(repr::display_decimal(v)),
+            Self::BigInt(v) => TextElem::packed(repr::display_bigint(&v)),
             Self::Str(v) => TextElem::packed(v),
             Self::Version(v) => TextElem::packed(eco_format!("{v}")),
             Self::Symbol(v) => TextElem::packed(v.get()),
@@ -233,6 +241,8 @@ impl Debug for Value {
             Self::Bool(v) => Debug::fmt(v, f),
             Self::Int(v) => Debug::fmt(v, f),
             Self::Float(v) => Debug::fmt(v, f),
+            Self::Decimal(v) => Debug::fmt(v, f),
+            Self::BigInt(v) => Debug::fmt(v, f),
             Self::Length(v) => Debug::fmt(v, f),
             Self::Angle(v) => Debug::fmt(v, f),
             Self::Ratio(v) => Debug::fmt(v, f),
@@ -270,6 +280,8 @@ impl Repr for Value {
             Self::Bool(v) => v.repr(),
             Self::Int(v) => v.repr(),
             Self::Float(v) => v.repr(),
+            Self::Decimal(v) => v.repr(),
+            Self::BigInt(v) => v.repr(),
             Self::Length(v) => v.repr(),
             Self::Angle(v) => v.repr(),
             Self::Ratio(v) => v.repr(),
@@ -320,6 +332,8 @@ impl Hash for Value {
             Self::Bool(v) => v.hash(state),
             Self::Int(v) => v.hash(state),
             Self::Float(v) => v.to_bits().hash(state),
+            Self::Decimal(v) => v.hash(state),
+            Self::BigInt(v) => v.hash(state),
             Self::Length(v) => v.hash(state),
             Self::Angle(v) => v.hash(state),
             Self::Ratio(v) => v.hash(state),
@@ -626,6 +640,8 @@ macro_rules! primitive {
 primitive! { bool: "boolean", Bool }
 primitive! { i64: "integer", Int }
 primitive! { f64: "float", Float, Int(v) => v as f64 }
+primitive! { Decimal: "decimal", Decimal, Int(v) => v.into() }
+primitive! { BigInt: "bigint", BigInt, Int(v) => v.into() }
 primitive! { Length: "length", Length }
 primitive! { Angle: "angle", Angle }
 primitive! { Ratio: "ratio", Ratio }
@@ -116,6 +116,32 @@ impl Version {
             .and_then(|i| self.0.get(i).copied())
             .unwrap_or_default() as i64)
     }
+
+    /// Compares this version with another one.
+    ///
+    /// Returns `{-1}` if this version is older than the other one, `{0}` if
+    /// they are the same and `{1}` if this version is newer. Missing
+    /// components count as zero.
+    ///
+    /// ```example
+    /// #version(0, 10).compare(version(0, 11)) \
+    /// #version(1).compare(version(1, 0, 0)) \
+    /// #if sys.version.compare(version(0, 9)) >= 0 [
+    ///   At least 0.9
+    /// ]
+    /// ```
+    #[func]
+    pub fn compare(
+        &self,
+        /// The version to compare with.
+        other: Version,
+    ) -> i64 {
+        match self.cmp(&other) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    }
 }
 
 impl FromIterator<u32> for Version {
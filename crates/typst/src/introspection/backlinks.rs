@@ -0,0 +1,69 @@
+use ecow::eco_vec;
+
+use crate::engine::Engine;
+use crate::foundations::{
+    func, Array, IntoValue, Label, NativeElement, Selector, StyleChain,
+};
+use crate::introspection::Location;
+use crate::model::{BibliographyElem, CiteGroup, RefElem};
+
+/// Finds the references and citations that point to a label.
+///
+/// Returns the [locations]($location) of all [references]($ref) to the label
+/// in the order in which they appear in the document. For entries of the
+/// [bibliography]($bibliography), this includes all [citations]($cite) of the
+/// entry, where a group of citations counts only once. With the locations,
+/// labelled elements or bibliography entries can for instance be annotated
+/// with the pages on which they are referenced.
+///
+/// ```example
+/// #set heading(numbering: "1.")
+///
+/// = Introduction <intro>
+/// As @intro shows, links go
+/// both ways. See @intro.
+///
+/// #locate(loc => {
+///   let pages = backlinks(<intro>, loc)
+///     .map(l => l.page())
+///     .dedup()
+///   [Referenced on pages #pages.map(str).join(", ").]
+/// })
+/// ```
+#[func]
+pub fn backlinks(
+    /// The engine.
+    engine: &mut Engine,
+    /// The label to find the references to.
+    target: Label,
+    /// Can be an arbitrary location, as its value is irrelevant for the
+    /// function's return value. Just like for [`query`]($query), it ensures
+    /// that this function can only be called within [`locate`]($locate).
+    location: Location,
+) -> Array {
+    let _ = location;
+
+    // References to bibliography entries produce citations, so that they
+    // would be found twice if references were considered for them, too.
+    let cited = BibliographyElem::has(engine, target);
+    let selector = Selector::Or(eco_vec![
+        Selector::Elem(RefElem::elem(), None),
+        Selector::Elem(CiteGroup::elem(), None),
+    ]);
+
+    let vec = engine.introspector.query(&selector);
+    vec.into_iter()
+        .filter(|elem| {
+            if let Some(reference) = elem.to_packed::<RefElem>() {
+                !cited
+                    && *reference.target() == target
+                    && reference.from(StyleChain::default()).is_none()
+            } else if let Some(group) = elem.to_packed::<CiteGroup>() {
+                cited && group.children().iter().any(|cite| *cite.key() == target)
+            } else {
+                false
+            }
+        })
+        .map(|elem| elem.location().unwrap().into_value())
+        .collect()
+}
@@ -5,7 +5,7 @@ use comemo::{Tracked, TrackedMut};
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use smallvec::{smallvec, SmallVec};
 
-use crate::diag::{At, SourceResult, StrResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{
@@ -18,7 +18,7 @@ use crate::layout::{Frame, FrameItem, PageElem};
 use crate::math::EquationElem;
 use crate::model::{FigureElem, HeadingElem, Numbering, NumberingPattern};
 use crate::syntax::Span;
-use crate::util::NonZeroExt;
+use crate::util::{hash128, NonZeroExt};
 use crate::World;
 
 /// Counts through pages, elements, and more.
@@ -223,6 +223,32 @@ impl Counter {
         Self::construct(CounterKey::Selector(Selector::Elem(func, None)))
     }
 
+    /// Gets the value of the counter at the given location.
+    pub fn at_loc(
+        &self,
+        engine: &mut Engine,
+        location: Location,
+    ) -> SourceResult<CounterState> {
+        let sequence = self.sequence(engine)?;
+        let offset = engine
+            .introspector
+            .query(&self.selector().before(location.into(), true))
+            .len();
+        let (mut state, page) = sequence[offset].clone();
+        if self.is_page() {
+            let delta =
+                engine.introspector.page(location).get().saturating_sub(page.get());
+            state.step(NonZeroUsize::ONE, delta);
+        }
+
+        engine.tracer.observe(
+            eco_format!("counter {}", self.0.repr()),
+            location,
+            hash128(&state),
+        );
+        Ok(state)
+    }
+
     /// Gets the current and final value of the state combined in one state.
     pub fn both(
         &self,
@@ -371,8 +397,34 @@ impl Counter {
         #[named]
         #[default(false)]
         both: bool,
+        /// How many levels of the counter to display. If `{none}`, all levels
+        /// are displayed.
+        ///
+        /// ```example
+        /// #set heading(numbering: "1.1")
+        /// = Intro
+        /// == Details
+        /// #counter(heading).display(depth: 1)
+        /// ```
+        #[named]
+        #[default]
+        depth: Option<NonZeroUsize>,
+        /// If given, each level is displayed separately with the numbering and
+        /// the results are joined with this separator. Otherwise, all levels
+        /// are displayed together with the numbering.
+        ///
+        /// ```example
+        /// #let c = counter("part")
+        /// #c.update((3, 2))
+        /// #c.display("I", join: [ – ])
+        /// ```
+        #[named]
+        #[default]
+        join: Option<Content>,
     ) -> Content {
-        DisplayElem::new(self, numbering, both).pack().spanned(span)
+        DisplayElem::new(self, numbering, both, depth, join)
+            .pack()
+            .spanned(span)
     }
 
     /// Increases the value of the counter by one.
@@ -400,6 +452,13 @@ impl Counter {
     ///
     /// Just like with `step`, the update only occurs if you put the resulting
     /// content into the document.
+    ///
+    /// ```example
+    /// #let c = counter("section")
+    /// #c.update((2, 4, 1))
+    /// #c.update(nums => nums.slice(0, 2) + (9,), spread: false)
+    /// #c.display()
+    /// ```
     #[func]
     pub fn update(
         self,
@@ -410,35 +469,43 @@ impl Counter {
         /// counter value (with each number as a separate argument) and has to
         /// return the new value (integer or array).
         update: CounterUpdate,
+        /// Whether a function receives each number of the counter as a
+        /// separate argument. If `{false}`, it receives the whole counter
+        /// value as a single array instead, which is easier to work with when
+        /// the number of levels varies.
+        #[named]
+        #[default(true)]
+        spread: bool,
     ) -> Content {
+        let update = match update {
+            CounterUpdate::Func(func) if !spread => CounterUpdate::Map(func),
+            update => update,
+        };
         UpdateElem::new(self.0, update).pack().spanned(span)
     }
 
     /// Gets the value of the counter at the given location. Always returns an
     /// array of integers, even if the counter has just one number.
+    ///
+    /// Like all introspection, this may take multiple layout iterations to
+    /// converge. Typst repeats layout until all counter values are stable.
     #[func]
     pub fn at(
         &self,
         /// The engine.
         engine: &mut Engine,
-        /// The location at which the counter value should be retrieved. A
-        /// suitable location can be retrieved from [`locate`]($locate) or
-        /// [`query`]($query).
-        location: Location,
+        /// The callsite span.
+        span: Span,
+        /// The place at which the counter value should be retrieved.
+        ///
+        /// This can be a location, which can be retrieved from
+        /// [`locate`]($locate) or [`query`]($query), or a label or selector
+        /// that matches exactly one element in the document.
+        selector: LocatableSelector,
     ) -> SourceResult<CounterState> {
-        let sequence = self.sequence(engine)?;
-        let offset = engine
-            .introspector
-            .query(&self.selector().before(location.into(), true))
-            .len();
-        let (mut state, page) = sequence[offset].clone();
-        if self.is_page() {
-            let delta =
-                engine.introspector.page(location).get().saturating_sub(page.get());
-            state.step(NonZeroUsize::ONE, delta);
-        }
-
-        Ok(state)
+        check_introspectable(engine, span)?;
+        let location = selector.resolve_unique(engine.introspector).at(span)?;
+        self.at_loc(engine, location)
     }
 
     /// Gets the value of the counter at the end of the document. Always returns
@@ -448,17 +515,21 @@ impl Counter {
         &self,
         /// The engine.
         engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
         /// Can be an arbitrary location, as its value is irrelevant for the
-        /// method's return value. Why is it required then? Typst has to
-        /// evaluate parts of your code multiple times to determine all counter
-        /// values. By only allowing this method within [`locate`]($locate)
-        /// calls, the amount of code that can depend on the method's result is
-        /// reduced. If you could call `final` directly at the top level of a
-        /// module, the evaluation of the whole module and its exports could
-        /// depend on the counter's value.
-        location: Location,
+        /// method's return value. It may be omitted. Typst has to evaluate
+        /// parts of your code multiple times to determine all counter values,
+        /// so calling this within [`locate`]($locate) reduces the amount of
+        /// code that has to be evaluated again when the final value changes.
+        ///
+        /// In any case, the final value is only known during layout, so this
+        /// fails at the top level of a module.
+        #[default]
+        location: Option<Location>,
     ) -> SourceResult<CounterState> {
         let _ = location;
+        check_introspectable(engine, span)?;
         let sequence = self.sequence(engine)?;
         let (mut state, page) = sequence.last().unwrap().clone();
         if self.is_page() {
@@ -469,6 +540,18 @@ impl Counter {
     }
 }
 
+/// Ensure that the document can be introspected, which is not the case at the
+/// top level of a module.
+fn check_introspectable(engine: &Engine, span: Span) -> SourceResult<()> {
+    if engine.route.in_module() {
+        bail!(
+            span, "counters cannot be queried at the top level of a module";
+            hint: "try calling this within `locate`"
+        );
+    }
+    Ok(())
+}
+
 impl Repr for Counter {
     fn repr(&self) -> EcoString {
         eco_format!("counter({})", self.0.repr())
@@ -524,8 +607,13 @@ pub enum CounterUpdate {
     Set(CounterState),
     /// Increase the number for the given level by one.
     Step(NonZeroUsize),
+    /// Set the number for the given level to one, keeping the numbers of the
+    /// higher levels.
+    Restart(NonZeroUsize),
     /// Apply the given function to the counter's state.
     Func(Func),
+    /// Apply the given function to the counter's state as a single array.
+    Map(Func),
 }
 
 impl Repr for CounterUpdate {
@@ -569,10 +657,17 @@ impl CounterState {
         match update {
             CounterUpdate::Set(state) => *self = state,
             CounterUpdate::Step(level) => self.step(level, 1),
+            CounterUpdate::Restart(level) => {
+                self.step(level, 0);
+                self.0[level.get() - 1] = 1;
+            }
             CounterUpdate::Func(func) => {
                 *self =
                     func.call(engine, self.0.iter().copied())?.cast().at(func.span())?
             }
+            CounterUpdate::Map(func) => {
+                *self = func.call(engine, [self.clone()])?.cast().at(func.span())?
+            }
         }
         Ok(())
     }
@@ -604,6 +699,24 @@ impl CounterState {
     ) -> SourceResult<Content> {
         Ok(numbering.apply(engine, &self.0)?.display())
     }
+
+    /// Display each level of the counter state with a numbering and join the
+    /// results with a separator.
+    pub fn display_joined(
+        &self,
+        engine: &mut Engine,
+        numbering: &Numbering,
+        separator: &Content,
+    ) -> SourceResult<Content> {
+        let mut seq = vec![];
+        for (i, &n) in self.0.iter().enumerate() {
+            if i > 0 {
+                seq.push(separator.clone());
+            }
+            seq.push(numbering.apply(engine, &[n])?.display());
+        }
+        Ok(Content::sequence(seq))
+    }
 }
 
 cast! {
@@ -630,6 +743,14 @@ struct DisplayElem {
     /// Whether to display both the current and final value.
     #[required]
     both: bool,
+
+    /// How many levels to display.
+    #[required]
+    depth: Option<NonZeroUsize>,
+
+    /// The separator with which to join the separately displayed levels.
+    #[required]
+    join: Option<Content>,
 }
 
 impl Show for Packed<DisplayElem> {
@@ -657,13 +778,22 @@ impl Show for Packed<DisplayElem> {
             })
             .unwrap_or_else(|| NumberingPattern::from_str("1.1").unwrap().into());
 
+        // The combined state holds just the first level of the current and
+        // the final value, so the depth doesn't apply to it.
         let state = if *self.both() {
             counter.both(engine, location)?
         } else {
-            counter.at(engine, location)?
+            let mut state = counter.at_loc(engine, location)?;
+            if let Some(depth) = self.depth() {
+                state.0.truncate(depth.get());
+            }
+            state
         };
 
-        state.display(engine, &numbering)
+        match self.join() {
+            Some(separator) => state.display_joined(engine, &numbering, separator),
+            None => state.display(engine, &numbering),
+        }
     }
 }
 
@@ -747,3 +877,24 @@ impl Default for ManualPageCounter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::memory::tests::compile;
+
+    #[test]
+    fn test_display_depth() {
+        let display = |args: &str| {
+            let text =
+                format!("#let c = counter(\"x\")\n#c.update((3, 2))\n#c.display({args})");
+            compile(&text).plain_text()
+        };
+
+        assert_eq!(display("\"1.1\""), "3.2");
+        assert_eq!(display("\"1.1\", depth: 1"), "3");
+
+        // The depth doesn't cut off the final value of a display with both
+        // values.
+        assert_eq!(display("\"1 / 1\", both: true, depth: 1"), "3 / 3");
+    }
+}
@@ -1,19 +1,20 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
 use std::sync::RwLock;
 
 use comemo::Prehashed;
-use ecow::{eco_format, EcoVec};
+use ecow::{eco_format, eco_vec, EcoVec};
 use indexmap::IndexMap;
 use smallvec::SmallVec;
 
-use crate::diag::{bail, StrResult};
+use crate::diag::{bail, error, SourceResult, StrResult};
 use crate::foundations::{Content, Label, Repr, Selector};
 use crate::introspection::{Location, Meta};
 use crate::layout::{Frame, FrameItem, Page, Point, Position, Transform};
 use crate::model::Numbering;
+use crate::syntax::Span;
 use crate::util::NonZeroExt;
 
 /// Can be queried for elements and their positions.
@@ -47,7 +48,10 @@ impl Introspector {
 
         for (i, page) in pages.iter().enumerate() {
             let page_nr = NonZeroUsize::new(1 + i).unwrap();
-            self.extract(&page.frame, page_nr, Transform::identity());
+            // Positions are relative to the trimmed page, so that they don't
+            // change when a bleed or printer's marks are added.
+            let offset = page.bleed + page.slug;
+            self.extract(&page.frame, page_nr, Transform::translate(-offset, -offset));
             self.page_numberings.push(page.numbering.clone());
         }
     }
@@ -127,7 +131,10 @@ impl Introspector {
                     indices.iter().map(|&index| self.elems[index].0.clone()).collect()
                 })
                 .unwrap_or_default(),
-            Selector::Elem(..) | Selector::Regex(_) | Selector::Can(_) => self
+            Selector::Elem(..)
+            | Selector::Custom(..)
+            | Selector::Regex(_)
+            | Selector::Can(_) => self
                 .all()
                 .filter(|elem| selector.matches(elem, None))
                 .cloned()
@@ -163,6 +170,19 @@ impl Introspector {
                 }
                 list
             }
+            Selector::Within { selector, ancestor } => {
+                let ancestors: HashSet<Location> = self
+                    .query(ancestor)
+                    .iter()
+                    .filter_map(|elem| elem.location())
+                    .collect();
+                self.query(selector)
+                    .into_iter()
+                    .filter(|elem| {
+                        elem.ancestors().iter().any(|loc| ancestors.contains(loc))
+                    })
+                    .collect()
+            }
             Selector::And(selectors) => {
                 let mut results: Vec<_> =
                     selectors.iter().map(|sel| self.query(sel)).collect();
@@ -221,6 +241,36 @@ impl Introspector {
         Ok(&self.elems[indices[0]].0)
     }
 
+    /// Query for a unique element with the label, failing with an error at the
+    /// given span otherwise. If the label occurs multiple times, the error
+    /// points to each place in the source that defines it.
+    pub fn query_label_at(
+        &self,
+        label: Label,
+        span: Span,
+    ) -> SourceResult<&Prehashed<Content>> {
+        self.query_label(label).map_err(|message| {
+            let mut diag = error!(span, "{message}");
+            if !self.labels.contains_key(&label) {
+                diag = diag.with_code("E0010");
+            }
+            let mut defined = vec![];
+            for &i in self.labels.get(&label).into_iter().flatten() {
+                let def = self.elems[i].0.span();
+                if !def.is_detached() && !defined.contains(&def) {
+                    defined.push(def);
+                }
+            }
+            for def in defined {
+                diag.related(
+                    def,
+                    eco_format!("label `{}` is defined here", label.repr()),
+                );
+            }
+            eco_vec![diag]
+        })
+    }
+
     /// The total number pages.
     pub fn pages(&self) -> NonZeroUsize {
         NonZeroUsize::new(self.pages).unwrap_or(NonZeroUsize::ONE)
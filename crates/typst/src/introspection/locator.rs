@@ -98,6 +98,34 @@ impl<'a> Locator<'a> {
         }
     }
 
+    /// Whether none of the elements in the frames has a hash for which this
+    /// locator already handed out a disambiguator.
+    ///
+    /// In this case, the frames contain the same locations no matter whether
+    /// they were laid out with this locator or a fresh one.
+    pub fn is_disjoint<'b>(&self, frames: impl IntoIterator<Item = &'b Frame>) -> bool {
+        frames.into_iter().all(|frame| self.is_disjoint_impl(frame))
+    }
+
+    /// Whether a single frame is disjoint, see [`is_disjoint`](Self::is_disjoint).
+    fn is_disjoint_impl(&self, frame: &Frame) -> bool {
+        frame.items().all(|(_, item)| match item {
+            FrameItem::Group(group) => self.is_disjoint_impl(&group.frame),
+            FrameItem::Meta(Meta::Elem(elem), _) => {
+                self.peek(elem.location().unwrap().hash) == 0
+            }
+            _ => true,
+        })
+    }
+
+    /// The current disambiguator for the given hash, without remembering it.
+    fn peek(&self, hash: u128) -> usize {
+        match self.hashes.borrow().get(&hash) {
+            Some(&disambiguator) => disambiguator,
+            None => self.outer.map_or(0, |outer| outer.disambiguator(hash)),
+        }
+    }
+
     /// The current disambiguator for the given hash.
     fn disambiguator_impl(&self, hash: u128) -> usize {
         *self
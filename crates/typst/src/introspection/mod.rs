@@ -1,5 +1,7 @@
 //! Interaction between document parts.
 
+#[path = "backlinks.rs"]
+mod backlinks_;
 mod counter;
 mod introspector;
 #[path = "locate.rs"]
@@ -11,6 +13,7 @@ mod metadata;
 mod query_;
 mod state;
 
+pub use self::backlinks_::*;
 pub use self::counter::*;
 pub use self::introspector::*;
 pub use self::locate_::*;
@@ -29,7 +32,7 @@ use crate::foundations::Packed;
 use crate::foundations::{
     category, elem, ty, Behave, Behaviour, Category, Content, Repr, Scope, Unlabellable,
 };
-use crate::model::Destination;
+use crate::model::{Destination, FormField};
 
 /// Interactions between document parts.
 ///
@@ -50,6 +53,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<MetadataElem>();
     global.define_func::<locate>();
     global.define_func::<query>();
+    global.define_func::<backlinks>();
 }
 
 /// Hosts metadata and ensures metadata is produced even for empty elements.
@@ -87,6 +91,8 @@ pub enum Meta {
     /// An identifiable element that produces something within the area this
     /// metadata is attached to.
     Elem(Content),
+    /// An interactive form field covering the area.
+    Field(FormField),
     /// Indicates that content should be hidden. This variant doesn't appear
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
@@ -97,7 +103,8 @@ impl Debug for Meta {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Link(dest) => write!(f, "Link({dest:?})"),
-            Self::Elem(content) => write!(f, "Elem({:?})", content.func()),
+            Self::Elem(content) => write!(f, "Elem({:?})", content.elem()),
+            Self::Field(field) => write!(f, "Field({:?})", field.name),
             Self::Hide => f.pad("Hide"),
         }
     }
@@ -1,6 +1,12 @@
+use std::num::NonZeroUsize;
+
+use ecow::eco_format;
+use serde::Serialize;
+
 use crate::engine::Engine;
-use crate::foundations::{func, Array, LocatableSelector, Value};
+use crate::foundations::{func, Array, Content, LocatableSelector, Repr, Value};
 use crate::introspection::Location;
+use crate::util::hash128;
 
 /// Finds elements in the document.
 ///
@@ -130,6 +136,26 @@ use crate::introspection::Location;
 /// $ typst query example.typ "<note>" --field value --one
 /// "This is a note"
 /// ```
+///
+/// With the `--positions` flag, each element is wrapped into an object that
+/// also contains the `page` it is on and its `x` and `y` coordinates in points
+/// from the top left of the page.
+///
+/// ```sh
+/// $ typst query example.typ "<note>" --positions
+/// [
+///   {
+///     "element": {
+///       "func": "metadata",
+///       "value": "This is a note",
+///       "label": "<note>"
+///     },
+///     "page": 1,
+///     "x": 70.86614173228347,
+///     "y": 70.86614173228347
+///   }
+/// ]
+/// ```
 #[func]
 pub fn query(
     /// The engine.
@@ -138,11 +164,11 @@ pub fn query(
     /// or a more complex selector like `{heading.where(level: 1)}`.
     ///
     /// Currently, only a subset of element functions is supported. Aside from
-    /// headings and figures, this includes equations, references and all
-    /// elements with an explicit label. As a result, you _can_ query for e.g.
-    /// [`strong`]($strong) elements, but you will find only those that have an
-    /// explicit label attached to them. This limitation will be resolved in the
-    /// future.
+    /// headings and figures, this includes equations, references, tables,
+    /// footnote entries, and all elements with an explicit label. As a result,
+    /// you _can_ query for e.g. [`strong`]($strong) elements, but you will
+    /// find only those that have an explicit label attached to them. This
+    /// limitation will be resolved in the future.
     target: LocatableSelector,
     /// Can be an arbitrary location, as its value is irrelevant for the
     /// function's return value. Why is it required then? As noted before, Typst
@@ -154,9 +180,28 @@ pub fn query(
     /// could depend on the query's result.
     location: Location,
 ) -> Array {
-    let _ = location;
     let vec = engine.introspector.query(&target.0);
+    engine.tracer.observe(
+        eco_format!("query for {}", target.0.repr()),
+        location,
+        hash128(&vec),
+    );
     vec.into_iter()
         .map(|elem| Value::Content(elem.into_inner()))
         .collect()
 }
+
+/// An element that matched a query, together with its position.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryMatch {
+    /// The matched element.
+    pub element: Content,
+    /// The page the element is on, starting at 1.
+    pub page: NonZeroUsize,
+    /// The horizontal distance of the element from the left of the page, in
+    /// points.
+    pub x: f64,
+    /// The vertical distance of the element from the top of the page, in
+    /// points.
+    pub y: f64,
+}
@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 use comemo::{Tracked, TrackedMut};
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
@@ -5,11 +7,13 @@ use crate::diag::SourceResult;
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{
-    cast, elem, func, scope, select_where, ty, Content, Func, NativeElement, Packed,
-    Repr, Selector, Show, Str, StyleChain, Value,
+    cast, elem, func, scope, select_where, ty, Content, Func, IntoValue,
+    LocatableSelector, NativeElement, Packed, Repr, Selector, Show, Str, StyleChain,
+    Value,
 };
 use crate::introspection::{Introspector, Locatable, Location, Locator};
 use crate::syntax::Span;
+use crate::util::{hash128, NonZeroExt};
 use crate::World;
 
 /// Manages stateful parts of your document.
@@ -186,6 +190,31 @@ use crate::World;
 /// `locate` calls or `display` calls of state or counters. Instead, pass a
 /// function to `update` that determines the value of the state based on its
 /// previous value.
+///
+/// When the layout does not converge, Typst warns about each state whose value
+/// was still changing in the last attempt and points to the first place where
+/// it was displayed or retrieved.
+///
+/// # Scoped state { #scope }
+/// By default, updates to a state are in effect until the end of the document.
+/// With the `scope` argument, a state can instead be limited to a single page
+/// or to the sections started by some element. At the start of each page or
+/// section, the state is reset to its initial value. This way, a state's value
+/// only depends on the updates in the current page or section, so changes in
+/// other parts of the document don't affect it.
+///
+/// ```example
+/// #let notes = state("notes", 0, scope: heading)
+/// #let note() = notes.update(n => n + 1)
+///
+/// = Intro
+/// #note() #note()
+/// Notes: #notes.display()
+///
+/// = Details
+/// #note()
+/// Notes: #notes.display()
+/// ```
 #[ty(scope)]
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct State {
@@ -193,19 +222,25 @@ pub struct State {
     key: Str,
     /// The initial value of the state.
     init: Value,
+    /// The part of the document in which updates are in effect.
+    scope: StateScope,
 }
 
 impl State {
     /// Create a new state identified by a key.
     pub fn new(key: Str, init: Value) -> State {
-        Self { key, init }
+        Self { key, init, scope: StateScope::Document }
     }
 
-    /// Produce the whole sequence of states.
+    /// Produce the whole sequence of states, each with the page on which it
+    /// starts.
     ///
     /// This has to happen just once for all states, cutting down the number
     /// of state updates from quadratic to linear.
-    fn sequence(&self, engine: &mut Engine) -> SourceResult<EcoVec<Value>> {
+    fn sequence(
+        &self,
+        engine: &mut Engine,
+    ) -> SourceResult<EcoVec<(Value, NonZeroUsize)>> {
         self.sequence_impl(
             engine.world,
             engine.introspector,
@@ -224,7 +259,7 @@ impl State {
         route: Tracked<Route>,
         locator: Tracked<Locator>,
         tracer: TrackedMut<Tracer>,
-    ) -> SourceResult<EcoVec<Value>> {
+    ) -> SourceResult<EcoVec<(Value, NonZeroUsize)>> {
         let mut locator = Locator::chained(locator);
         let mut engine = Engine {
             world,
@@ -234,23 +269,53 @@ impl State {
             tracer,
         };
         let mut state = self.init.clone();
-        let mut stops = eco_vec![state.clone()];
+        let mut page = NonZeroUsize::ONE;
+        let mut stops = eco_vec![(state.clone(), page)];
 
         for elem in introspector.query(&self.selector()) {
-            let elem = elem.to_packed::<UpdateElem>().unwrap();
+            let location = elem.location().unwrap();
+            let Some(elem) = elem.to_packed::<UpdateElem>() else {
+                // A new section starts.
+                state = self.init.clone();
+                page = introspector.page(location);
+                stops.push((state.clone(), page));
+                continue;
+            };
+
+            let next = introspector.page(location);
+            if self.scope == StateScope::Page && next != page {
+                state = self.init.clone();
+            }
+
+            page = next;
             match elem.update() {
                 StateUpdate::Set(value) => state = value.clone(),
                 StateUpdate::Func(func) => state = func.call(&mut engine, [state])?,
             }
-            stops.push(state.clone());
+            stops.push((state.clone(), page));
         }
 
         Ok(stops)
     }
 
-    /// The selector for this state's updates.
+    /// Get the value of the state from a stop of its sequence, considering
+    /// that page-scoped state is reset on each new page.
+    fn resolve(&self, (value, page): (Value, NonZeroUsize), at: NonZeroUsize) -> Value {
+        if self.scope == StateScope::Page && page != at {
+            self.init.clone()
+        } else {
+            value
+        }
+    }
+
+    /// The selector for this state's updates and, for state that is scoped to
+    /// sections, the elements that start a new section.
     fn selector(&self) -> Selector {
-        select_where!(UpdateElem, Key => self.key.clone())
+        let updates = select_where!(UpdateElem, Key => self.key.clone());
+        match &self.scope {
+            StateScope::Within(start) => updates.or(vec![start.clone()]),
+            _ => updates,
+        }
     }
 }
 
@@ -264,8 +329,16 @@ impl State {
         /// The initial value of the state.
         #[default]
         init: Value,
+        /// The part of the document in which updates to the state are in
+        /// effect. Either `{"document"}`, `{"page"}`, or a selector for the
+        /// elements that start a new section, like `{heading.where(level:
+        /// 1)}`. At the start of each page or section, the state is reset to
+        /// its initial value.
+        #[named]
+        #[default(StateScope::Document)]
+        scope: StateScope,
     ) -> State {
-        Self::new(key, init)
+        Self { key, init, scope }
     }
 
     /// Displays the current value of the state.
@@ -320,7 +393,14 @@ impl State {
             .introspector
             .query(&self.selector().before(location.into(), true))
             .len();
-        Ok(sequence[offset].clone())
+        let page = engine.introspector.page(location);
+        let value = self.resolve(sequence[offset].clone(), page);
+        engine.tracer.observe(
+            eco_format!("state {}", self.key.repr()),
+            location,
+            hash128(&value),
+        );
+        Ok(value)
     }
 
     /// Get the value of the state at the end of the document.
@@ -341,16 +421,52 @@ impl State {
     ) -> SourceResult<Value> {
         let _ = location;
         let sequence = self.sequence(engine)?;
-        Ok(sequence.last().unwrap().clone())
+        let pages = engine.introspector.pages();
+        Ok(self.resolve(sequence.last().unwrap().clone(), pages))
     }
 }
 
 impl Repr for State {
     fn repr(&self) -> EcoString {
-        eco_format!("state({}, {})", self.key.repr(), self.init.repr())
+        match &self.scope {
+            StateScope::Document => {
+                eco_format!("state({}, {})", self.key.repr(), self.init.repr())
+            }
+            scope => eco_format!(
+                "state({}, {}, scope: {})",
+                self.key.repr(),
+                self.init.repr(),
+                scope.clone().into_value().repr()
+            ),
+        }
     }
 }
 
+/// The part of the document in which updates to a state are in effect.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum StateScope {
+    /// Updates are in effect until the end of the document.
+    Document,
+    /// The state is reset at the start of each page.
+    Page,
+    /// The state is reset at each element matching the selector.
+    Within(Selector),
+}
+
+cast! {
+    StateScope,
+    self => match self {
+        Self::Document => "document".into_value(),
+        Self::Page => "page".into_value(),
+        Self::Within(selector) => selector.into_value(),
+    },
+    /// Updates are in effect until the end of the document.
+    "document" => Self::Document,
+    /// The state is reset at the start of each page.
+    "page" => Self::Page,
+    v: LocatableSelector => Self::Within(v.0),
+}
+
 /// An update to perform on a state.
 #[ty(cast)]
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -1,19 +1,21 @@
 use std::fmt::{self, Debug, Formatter};
 
-use comemo::Prehashed;
+use comemo::{Prehashed, TrackedMut};
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
+use crate::eval::Tracer;
 use crate::foundations::{
     elem, Content, NativeElement, Packed, Resolve, Smart, StyleChain,
 };
 use crate::introspection::{Meta, MetaElem};
 use crate::layout::{
-    Abs, AlignElem, Axes, BlockElem, ColbreakElem, ColumnsElem, FixedAlignment, Fr,
-    Fragment, Frame, FrameItem, LayoutMultiple, LayoutSingle, PlaceElem, Point, Regions,
-    Rel, Size, Spacing, VAlignment, VElem,
+    warn_overflow, Abs, AlignElem, Axes, Axis, BlockElem, ColbreakElem, ColumnsElem,
+    FixedAlignment, Fr, Fragment, Frame, FrameItem, LayoutMultiple, LayoutSingle,
+    PlaceElem, Point, Regions, Rel, Size, Spacing, VAlignment, VElem,
 };
 use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
+use crate::syntax::Span;
 use crate::util::Numeric;
 
 /// Arranges spacing, paragraphs and block-level elements into a flow.
@@ -43,6 +45,8 @@ impl LayoutMultiple for Packed<FlowElem> {
         }
 
         let mut layouter = FlowLayouter::new(regions, styles);
+        let mut prelaid = layouter.prelayout_pars(engine, self.children()).into_iter();
+
         for mut child in self.children().iter().map(|c| &**c) {
             let outer = styles;
             let mut styles = styles;
@@ -63,9 +67,9 @@ impl LayoutMultiple for Packed<FlowElem> {
                     layouter.finish_region(engine, true)?;
                 }
             } else if let Some(elem) = child.to_packed::<ParElem>() {
-                layouter.layout_par(engine, elem, styles)?;
+                layouter.layout_par(engine, elem, styles, prelaid.next())?;
             } else if let Some(layoutable) = child.with::<dyn LayoutSingle>() {
-                layouter.layout_single(engine, layoutable, styles)?;
+                layouter.layout_single(engine, child, layoutable, styles)?;
             } else if child.can::<dyn LayoutMultiple>() {
                 layouter.layout_multiple(engine, child, styles)?;
             } else {
@@ -111,6 +115,17 @@ struct FlowLayouter<'a> {
     finished: Vec<Frame>,
 }
 
+/// A paragraph that was laid out ahead of time, in parallel with the other
+/// paragraphs of its flow.
+struct Prelaid {
+    /// Whether the paragraph was laid out as following another paragraph.
+    consecutive: bool,
+    /// The region the paragraph was laid out into.
+    region: Size,
+    /// The result of the layout and the tracer that recorded it.
+    output: (SourceResult<Fragment>, Tracer),
+}
+
 /// Cached footnote configuration.
 struct FootnoteConfig {
     separator: Content,
@@ -225,25 +240,95 @@ impl<'a> FlowLayouter<'a> {
         )
     }
 
+    /// Lay out the flow's paragraphs in parallel, ahead of the actual layout.
+    ///
+    /// Shaping makes up most of the time spent on text-heavy flows and a
+    /// paragraph only depends on its neighbours through whether it follows
+    /// another paragraph. Returns one entry per paragraph child, in order, or
+    /// nothing if there is no parallelism to gain.
+    fn prelayout_pars(
+        &self,
+        engine: &mut Engine,
+        children: &[Prehashed<Content>],
+    ) -> Vec<Prelaid> {
+        if rayon::current_num_threads() < 2 {
+            return vec![];
+        }
+
+        let mut pars = vec![];
+        let mut last_was_par = false;
+        for mut child in children.iter().map(|c| &**c) {
+            let mut styles = self.styles;
+            if let Some((elem, map)) = child.to_styled() {
+                child = elem;
+                styles = self.styles.chain(map);
+            }
+
+            // Mirrors how the actual layout updates `last_was_par`.
+            if let Some(par) = child.to_packed::<ParElem>() {
+                pars.push((par, styles, last_was_par));
+                last_was_par = true;
+            } else if !child.is::<MetaElem>()
+                && !child.is::<VElem>()
+                && !child.is::<PlaceElem>()
+                && !child.is::<ColbreakElem>()
+            {
+                last_was_par = false;
+            }
+        }
+
+        if pars.len() < 2 {
+            return vec![];
+        }
+
+        let region = self.regions.base();
+        let expand = self.regions.expand.x;
+        let outputs = engine.parallelize(&pars, |engine, &(par, styles, consecutive)| {
+            par.layout(engine, styles, consecutive, region, expand)
+        });
+
+        pars.iter()
+            .zip(outputs)
+            .map(|(&(_, _, consecutive), output)| Prelaid { consecutive, region, output })
+            .collect()
+    }
+
     /// Layout a paragraph.
     fn layout_par(
         &mut self,
         engine: &mut Engine,
         par: &Packed<ParElem>,
         styles: StyleChain,
+        prelaid: Option<Prelaid>,
     ) -> SourceResult<()> {
         let align = AlignElem::alignment_in(styles).resolve(styles);
         let leading = ParElem::leading_in(styles);
         let consecutive = self.last_was_par;
-        let lines = par
-            .layout(
-                engine,
-                styles,
-                consecutive,
-                self.regions.base(),
-                self.regions.expand.x,
-            )?
-            .into_frames();
+        let region = self.regions.base();
+        let mut height = Abs::zero();
+
+        // A paragraph laid out ahead of time is only used if it was laid out
+        // under the same conditions and its elements get the same locations
+        // as they would get now. Otherwise (and to get exactly the errors of
+        // sequential layout), it is laid out again.
+        let lines = match prelaid {
+            Some(Prelaid {
+                consecutive: c,
+                region: r,
+                output: (Ok(fragment), tracer),
+            }) if c == consecutive
+                && r == region
+                && engine.locator.is_disjoint(&fragment) =>
+            {
+                tracer.replay(TrackedMut::reborrow_mut(&mut engine.tracer));
+                engine.locator.visit_frames(&fragment);
+                fragment
+            }
+            _ => {
+                par.layout(engine, styles, consecutive, region, self.regions.expand.x)?
+            }
+        }
+        .into_frames();
 
         let mut sticky = self.items.len();
         for (i, item) in self.items.iter().enumerate().rev() {
@@ -267,14 +352,22 @@ impl<'a> FlowLayouter<'a> {
         for (i, frame) in lines.into_iter().enumerate() {
             if i > 0 {
                 self.layout_item(engine, FlowItem::Absolute(leading, true))?;
+                height += leading;
             }
 
+            height += frame.height();
             self.layout_item(
                 engine,
                 FlowItem::Frame { frame, align, sticky: false, movable: true },
             )?;
         }
 
+        self.check_overflow(
+            engine,
+            par.span(),
+            "paragraph",
+            Size::new(Abs::zero(), height),
+        );
         self.last_was_par = true;
         Ok(())
     }
@@ -283,6 +376,7 @@ impl<'a> FlowLayouter<'a> {
     fn layout_single(
         &mut self,
         engine: &mut Engine,
+        child: &Content,
         layoutable: &dyn LayoutSingle,
         styles: StyleChain,
     ) -> SourceResult<()> {
@@ -291,10 +385,12 @@ impl<'a> FlowLayouter<'a> {
         let pod = Regions::one(self.regions.base(), Axes::splat(false));
         let mut frame = layoutable.layout(engine, styles, pod)?;
         frame.meta(styles, false);
+        let size = frame.size();
         self.layout_item(
             engine,
             FlowItem::Frame { frame, align, sticky, movable: true },
         )?;
+        self.check_overflow(engine, child.span(), child.elem().name(), size);
         self.last_was_par = false;
         Ok(())
     }
@@ -366,10 +462,12 @@ impl<'a> FlowLayouter<'a> {
             }
 
             frame.meta(styles, false);
+            let size = frame.size();
             self.layout_item(
                 engine,
                 FlowItem::Frame { frame, align, sticky, movable: false },
             )?;
+            self.check_overflow(engine, child.span(), child.elem().name(), size);
         }
 
         self.try_handle_footnotes(engine, notes)?;
@@ -473,6 +571,19 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Report an element that was just laid out if it doesn't fit into the
+    /// region, because it is too wide or because there was no next region to
+    /// move it to.
+    fn check_overflow(&self, engine: &mut Engine, span: Span, what: &str, size: Size) {
+        if size.x > self.regions.size.x {
+            warn_overflow(engine, span, what, Axis::X, size.x - self.regions.size.x);
+        }
+        if self.regions.size.y < Abs::zero() {
+            let amount = (-self.regions.size.y).min(size.y);
+            warn_overflow(engine, span, what, Axis::Y, amount);
+        }
+    }
+
     /// Finish the frame for one region.
     ///
     /// Set `force` to `true` to allow creating a frame for out-of-flow elements
@@ -762,3 +873,36 @@ fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::memory::tests::compile;
+    use crate::util::hash128;
+
+    #[test]
+    fn test_prelayout_matches_sequential_layout() {
+        // Equal paragraphs contain equal elements, which exercise the
+        // fallback to sequential layout.
+        let text = "#set page(height: 120pt)\n".to_string()
+            + &(0..20)
+                .map(|i| {
+                    format!(
+                        "Paragraph #counter(\"c\").step() #counter(\"c\").display() \
+                         with a note#footnote[Note {i}.] and #box[a box] <p{i}>\n\n\
+                         A repeated paragraph.#footnote[Same.]\n\n"
+                    )
+                })
+                .collect::<String>();
+
+        let layout = |threads| {
+            comemo::evict(0);
+            let pool =
+                rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let document = pool.install(|| compile(&text));
+            let frames: Vec<_> = document.pages.iter().map(|page| &page.frame).collect();
+            (hash128(&frames), document.plain_text())
+        };
+
+        assert_eq!(layout(1), layout(4));
+    }
+}
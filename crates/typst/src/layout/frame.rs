@@ -7,7 +7,7 @@ use std::sync::Arc;
 use crate::foundations::{cast, dict, Dict, StyleChain, Value};
 use crate::introspection::{Meta, MetaElem};
 use crate::layout::{
-    Abs, Axes, Corners, FixedAlignment, Length, Point, Rel, Sides, Size, Transform,
+    Abs, Axes, Corners, FixedAlignment, Layer, Length, Point, Rel, Sides, Size, Transform,
 };
 use crate::syntax::Span;
 use crate::text::TextItem;
@@ -358,6 +358,13 @@ impl Frame {
         }
     }
 
+    /// Put the contents of the frame on a layer.
+    pub fn set_layer(&mut self, layer: Layer) {
+        if !self.is_empty() {
+            self.group(|g| g.layer = Some(layer));
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -500,6 +507,8 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clip_path: Option<Path>,
+    /// The layer the group's contents belong to.
+    pub layer: Option<Layer>,
 }
 
 impl GroupItem {
@@ -509,6 +518,7 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clip_path: None,
+            layer: None,
         }
     }
 }
@@ -525,7 +535,8 @@ impl Debug for GroupItem {
 pub struct Position {
     /// The page, starting at 1.
     pub page: NonZeroUsize,
-    /// The exact coordinates on the page (from the top left, as usual).
+    /// The exact coordinates on the page (from the top left of the trimmed
+    /// page, as usual).
     pub point: Point,
 }
 
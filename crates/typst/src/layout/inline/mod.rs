@@ -16,8 +16,8 @@ use crate::eval::Tracer;
 use crate::foundations::{Content, Packed, Resolve, Smart, StyleChain};
 use crate::introspection::{Introspector, Locator, MetaElem};
 use crate::layout::{
-    Abs, AlignElem, Axes, BoxElem, Dir, Em, FixedAlignment, Fr, Fragment, Frame, HElem,
-    Point, Regions, Size, Sizing, Spacing,
+    warn_overflow, Abs, AlignElem, Axes, Axis, BoxElem, Dir, Em, FixedAlignment, Fr,
+    Fragment, Frame, HElem, Point, Regions, Size, Sizing, Spacing,
 };
 use crate::math::{EquationElem, MathParItem};
 use crate::model::{Linebreaks, ParElem};
@@ -1296,6 +1296,13 @@ fn commit(
         }
     }
 
+    // Report lines that are still too wide or, if they should be justified,
+    // too narrow.
+    if remaining < Abs::zero() || (line.justify && fr.is_zero()) {
+        let (span, _) = p.spans.span_at(line.trimmed.start + 1);
+        warn_overflow(engine, span, "line", Axis::X, -remaining);
+    }
+
     let mut top = Abs::zero();
     let mut bottom = Abs::zero();
 
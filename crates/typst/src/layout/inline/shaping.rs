@@ -586,6 +586,7 @@ struct ShapingContext<'a, 'v> {
 
 /// Shape text into [`ShapedText`].
 #[allow(clippy::too_many_arguments)]
+#[typst_macros::time(name = "shape")]
 pub(super) fn shape<'a>(
     engine: &Engine,
     base: usize,
@@ -0,0 +1,78 @@
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, StyleChain};
+use crate::layout::{Fragment, LayoutMultiple, Regions};
+
+/// Puts content on a named layer that PDF viewers can show and hide.
+///
+/// All content on layers of the same name is toggled together. This is useful
+/// for things like solutions on an exercise sheet, annotations for proof
+/// readers, or construction grids. In the exported PDF, each layer becomes an
+/// optional content group. Other export formats always show all layers.
+///
+/// Layers are block-level. If a layer has the same name as an earlier one, the
+/// earlier layer's visibility is used.
+///
+/// # Example
+/// ```example
+/// What is $2 + 2$?
+///
+/// #layer("solutions", visible: false)[
+///   The answer is $4$.
+/// ]
+/// ```
+#[elem(LayoutMultiple)]
+pub struct LayerElem {
+    /// The layer's name, as shown in the viewer's layer panel.
+    #[required]
+    pub name: EcoString,
+
+    /// Whether the layer is initially visible.
+    #[default(true)]
+    pub visible: bool,
+
+    /// Whether the layer is printed. If this is `{false}`, the layer can be
+    /// viewed on screen, but never appears on paper.
+    #[default(true)]
+    pub printable: bool,
+
+    /// The content on the layer.
+    #[required]
+    pub body: Content,
+}
+
+impl LayoutMultiple for Packed<LayerElem> {
+    #[typst_macros::time(name = "layer", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let layer = Layer {
+            name: self.name().clone(),
+            visible: self.visible(styles),
+            printable: self.printable(styles),
+        };
+
+        let mut fragment = self.body().layout(engine, styles, regions)?;
+        for frame in &mut fragment {
+            frame.set_layer(layer.clone());
+        }
+
+        Ok(fragment)
+    }
+}
+
+/// A named layer that content in a group belongs to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Layer {
+    /// The layer's name.
+    pub name: EcoString,
+    /// Whether the layer is initially visible.
+    pub visible: bool,
+    /// Whether the layer is printed.
+    pub printable: bool,
+}
@@ -3,6 +3,7 @@ use crate::engine::Engine;
 use crate::foundations::{
     dict, elem, func, Content, Func, NativeElement, Packed, StyleChain,
 };
+use crate::introspection::Locatable;
 use crate::layout::{Fragment, LayoutMultiple, Regions, Size};
 use crate::syntax::Span;
 
@@ -88,3 +89,84 @@ impl LayoutMultiple for Packed<LayoutElem> {
         result.layout(engine, styles, regions)
     }
 }
+
+/// Provides access to the page, position, and remaining space where content
+/// is placed.
+///
+/// The given function receives a dictionary with the following keys:
+/// - `page`: The number of the page the content starts on, as an
+///   [integer]($int).
+/// - `x` and `y`: The position of the content on that page, measured from the
+///   top left corner of the page, as [lengths]($length).
+/// - `width` and `height`: The size of the outer container (or page), just
+///   like with [`layout`]($layout).
+/// - `remaining`: The space that is still free in the current region (e.g. a
+///   page or column), as a dictionary with the keys `width` and `height`.
+///
+/// This makes it possible to adapt content to where it ends up. For example,
+/// you can add a notice when there is not enough space left on a page.
+///
+/// ```example
+/// #set page(height: 100pt)
+/// #let notice = region(r => {
+///   if r.remaining.height < 30pt {
+///     align(right, emph[Continued on page #(r.page + 1)])
+///   }
+/// })
+///
+/// #lorem(15)
+/// #notice
+/// ```
+///
+/// The page and position are determined by looking at the document's previous
+/// layout, so compilation may take more passes before they settle. Errors in
+/// the function are therefore only reported if they remain in the final pass.
+#[func]
+pub fn region(
+    /// The call span of this function.
+    span: Span,
+    /// A function to call with information about the region. Its return
+    /// value is displayed in the document.
+    ///
+    /// This function is called once for each time the content returned by
+    /// `region` appears in the document. That makes it possible to generate
+    /// content that depends on where it is placed.
+    func: Func,
+) -> Content {
+    RegionElem::new(func).pack().spanned(span)
+}
+
+/// Executes a `region` call.
+#[elem(Locatable, LayoutMultiple)]
+struct RegionElem {
+    /// The function to call with information about the region.
+    #[required]
+    func: Func,
+}
+
+impl LayoutMultiple for Packed<RegionElem> {
+    #[typst_macros::time(name = "region", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let position = engine.introspector.position(self.location().unwrap());
+        let Size { x: width, y: height } = regions.base();
+        let Size { x: remaining_width, y: remaining_height } = regions.size;
+        let info = dict! {
+            "page" => position.page,
+            "x" => position.point.x,
+            "y" => position.point.y,
+            "width" => width,
+            "height" => height,
+            "remaining" => dict! {
+                "width" => remaining_width,
+                "height" => remaining_height,
+            },
+        };
+        let result = engine.delayed(|engine| self.func().call(engine, [info]));
+        result.display().layout(engine, styles, regions)
+    }
+}
@@ -16,6 +16,7 @@ mod frame;
 mod grid;
 mod hide;
 mod inline;
+mod layer;
 #[path = "layout.rs"]
 mod layout_;
 mod length;
@@ -50,6 +51,7 @@ pub use self::fragment::*;
 pub use self::frame::*;
 pub use self::grid::*;
 pub use self::hide::*;
+pub use self::layer::*;
 pub use self::layout_::*;
 pub use self::length::*;
 pub use self::measure_::*;
@@ -71,13 +73,14 @@ pub(crate) use self::inline::*;
 
 use comemo::{Tracked, TrackedMut};
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, warning, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
-use crate::foundations::{category, Category, Content, Scope, StyleChain};
+use crate::foundations::{category, Category, Content, Repr, Scope, StyleChain};
 use crate::introspection::{Introspector, Locator};
 use crate::model::Document;
 use crate::realize::{realize_block, realize_root, Scratch};
+use crate::syntax::Span;
 use crate::World;
 
 /// Arranging elements on the page in different ways.
@@ -114,8 +117,10 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<ScaleElem>();
     global.define_elem::<RotateElem>();
     global.define_elem::<HideElem>();
+    global.define_elem::<LayerElem>();
     global.define_func::<measure>();
     global.define_func::<layout>();
+    global.define_func::<region>();
 }
 
 /// Root-level layout.
@@ -242,12 +247,15 @@ impl LayoutMultiple for Content {
             };
 
             if !engine.route.within(Route::MAX_LAYOUT_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum layout depth exceeded";
                     hint: "try to reduce the amount of nesting in your layout",
-                );
+                )
+                .into_fatal());
             }
 
+            engine.check_cancelled(content.span())?;
+
             let scratch = Scratch::default();
             let (realized, styles) =
                 realize_block(&mut engine, &scratch, content, styles)?;
@@ -273,3 +281,35 @@ impl LayoutMultiple for Content {
         Ok(fragment)
     }
 }
+
+/// Warn that content is wider or taller than the space that is available to
+/// it, if such warnings are enabled for the compilation.
+///
+/// A negative amount reports that content is narrower or shorter than the
+/// space it should fill, like a justified line that can't be stretched enough.
+/// Differences of less than a tenth of a point are not reported, since they
+/// are not visible and mostly stem from rounding.
+pub(crate) fn warn_overflow(
+    engine: &mut Engine,
+    span: Span,
+    what: &str,
+    axis: Axis,
+    amount: Abs,
+) {
+    if amount.abs() <= Abs::pt(0.1) || span.is_detached() || !engine.tracer.overflow() {
+        return;
+    }
+
+    let direction = match (axis, amount > Abs::zero()) {
+        (Axis::X, true) => "wider",
+        (Axis::X, false) => "narrower",
+        (Axis::Y, true) => "taller",
+        (Axis::Y, false) => "shorter",
+    };
+
+    engine.tracer.warn(warning!(
+        span,
+        "{what} is {} {direction} than the available space",
+        amount.abs().repr(),
+    ));
+}
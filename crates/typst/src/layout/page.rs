@@ -1,25 +1,28 @@
 use std::borrow::Cow;
 use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
 use std::ptr;
 use std::str::FromStr;
 
-use crate::diag::{bail, SourceResult};
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, AutoValue, Cast, Content, Dict, Fold, Func, NativeElement, Packed,
-    Resolve, Smart, StyleChain, Value,
+    cast, elem, AutoValue, Cast, Content, Dict, Duration, Fold, Func, NativeElement,
+    Packed, Resolve, Smart, StyleChain, Value,
 };
 use crate::introspection::{Counter, CounterKey, ManualPageCounter};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, HAlignment, LayoutMultiple,
-    Length, Point, Ratio, Regions, Rel, Sides, Size, VAlignment,
+    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, FrameItem, HAlignment,
+    LayoutMultiple, Length, Point, Ratio, Regions, Rel, Sides, Size, VAlignment,
 };
 
 use crate::model::Numbering;
-use crate::syntax::Spanned;
+use crate::syntax::{Span, Spanned};
 use crate::text::TextElem;
 use crate::util::{NonZeroExt, Numeric, Scalar};
-use crate::visualize::Paint;
+use crate::visualize::{ellipse, Cmyk, Color, FixedStroke, Geometry, Paint};
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -320,6 +323,59 @@ pub struct PageElem {
     #[borrowed]
     pub foreground: Option<Content>,
 
+    /// How far the page's fill and background extend beyond its edges.
+    ///
+    /// Printers need this extra area to trim pages without leaving white
+    /// slivers at the edges. The page's width and height define the trimmed
+    /// size and the bleed is added around it. In the exported PDF, the
+    /// trimmed area becomes the page's trim box and the extended area its
+    /// bleed box.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 120pt,
+    ///   height: 80pt,
+    ///   bleed: 9pt,
+    ///   fill: aqua,
+    /// )
+    /// ```
+    #[resolve]
+    pub bleed: Length,
+
+    /// Whether to draw crop and registration marks around the page.
+    ///
+    /// The marks are placed on extra space outside of the bleed, so the page
+    /// grows accordingly. Crop marks indicate where to trim and registration
+    /// targets allow checking the alignment of the printing plates. They are
+    /// drawn in the registration color, which shows up on every plate.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 120pt,
+    ///   height: 80pt,
+    ///   bleed: 6pt,
+    ///   marks: true,
+    /// )
+    /// ```
+    #[default(false)]
+    pub marks: bool,
+
+    /// The effect with which the page appears when the PDF is shown as a
+    /// presentation.
+    ///
+    /// ```example
+    /// #set page(width: 120pt, height: 80pt, transition: "dissolve")
+    /// #set align(center + horizon)
+    /// Fading in.
+    /// ```
+    pub transition: Option<Transition>,
+
+    /// How long the page is shown in a presentation before the viewer
+    /// advances to the next page on its own.
+    ///
+    /// If this is `{none}`, the viewer waits for the presenter.
+    pub duration: Option<Duration>,
+
     /// The contents of the page(s).
     ///
     /// Multiple pages will be created if the content does not fit on a single
@@ -336,50 +392,20 @@ pub struct PageElem {
 
 impl Packed<PageElem> {
     /// A document can consist of multiple `PageElem`s, one per run of pages
-    /// with equal properties (not one per actual output page!). The `number` is
-    /// the physical page number of the first page of this run. It is mutated
-    /// while we post-process the pages in this function. This function returns
-    /// a fragment consisting of multiple frames, one per output page of this
-    /// page run.
+    /// with equal properties (not one per actual output page!). This function
+    /// lays out the body of such a run into one frame per output page.
+    ///
+    /// The frames only become pages through [`finalize`](Self::finalize),
+    /// which depends on the page number and must thus happen in document
+    /// order. The body itself does not depend on the runs before it, so the
+    /// bodies of different runs can be laid out in parallel.
     #[typst_macros::time(name = "page", span = self.span())]
     pub fn layout(
         &self,
         engine: &mut Engine,
         styles: StyleChain,
-        page_counter: &mut ManualPageCounter,
-        extend_to: Option<Parity>,
-    ) -> SourceResult<Vec<Page>> {
-        // When one of the lengths is infinite the page fits its content along
-        // that axis.
-        let width = self.width(styles).unwrap_or(Abs::inf());
-        let height = self.height(styles).unwrap_or(Abs::inf());
-        let mut size = Size::new(width, height);
-        if self.flipped(styles) {
-            std::mem::swap(&mut size.x, &mut size.y);
-        }
-
-        let mut min = width.min(height);
-        if !min.is_finite() {
-            min = Paper::A4.width();
-        }
-
-        // Determine the margins.
-        let default = Rel::<Length>::from((2.5 / 21.0) * min);
-        let margin = self.margin(styles);
-        let two_sided = margin.two_sided.unwrap_or(false);
-        let margin = margin
-            .sides
-            .map(|side| side.and_then(Smart::as_custom).unwrap_or(default))
-            .resolve(styles)
-            .relative_to(size);
-
-        // Determine the binding.
-        let binding =
-            self.binding(styles)
-                .unwrap_or_else(|| match TextElem::dir_in(styles) {
-                    Dir::LTR => Binding::Left,
-                    _ => Binding::Right,
-                });
+    ) -> SourceResult<Vec<Frame>> {
+        let (size, margin, _) = self.geometry(styles);
 
         // Realize columns.
         let mut child = self.body().clone();
@@ -396,7 +422,32 @@ impl Packed<PageElem> {
         regions.root = true;
 
         // Layout the child.
-        let mut frames = child.layout(engine, styles, regions)?.into_frames();
+        Ok(child.layout(engine, styles, regions)?.into_frames())
+    }
+
+    /// Turn the frames produced by [`layout`](Self::layout) into pages.
+    ///
+    /// The `page_counter` holds the physical page number of the first page of
+    /// this run. It is mutated while we post-process the pages in this
+    /// function.
+    pub fn finalize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        mut frames: Vec<Frame>,
+        page_counter: &mut ManualPageCounter,
+        extend_to: Option<Parity>,
+    ) -> SourceResult<Vec<Page>> {
+        let (size, margin, two_sided) = self.geometry(styles);
+        let area = size - margin.sum_by_axis();
+
+        // Determine the binding.
+        let binding =
+            self.binding(styles)
+                .unwrap_or_else(|| match TextElem::dir_in(styles) {
+                    Dir::LTR => Binding::Left,
+                    _ => Binding::Right,
+                });
 
         // Align the child to the pagebreak's parity.
         // Check for page count after adding the pending frames
@@ -409,6 +460,10 @@ impl Packed<PageElem> {
         }
 
         let fill = self.fill(styles);
+        let bleed = self.bleed(styles);
+        let slug = if self.marks(styles) { MARKS_SLUG } else { Abs::zero() };
+        let transition = self.transition(styles);
+        let duration = self.duration(styles);
         let foreground = Cow::Borrowed(self.foreground(styles));
         let background = Cow::Borrowed(self.background(styles));
         let header_ascent = self.header_ascent(styles);
@@ -482,6 +537,10 @@ impl Packed<PageElem> {
                     pos = Point::new(margin.left, size.y - margin.bottom + descent);
                     area = Size::new(pw, margin.bottom - descent);
                     align = Alignment::TOP;
+                } else if ptr::eq(marginal, &background) {
+                    pos = Point::splat(-bleed);
+                    area = size + Size::splat(2.0 * bleed);
+                    align = HAlignment::Center + VAlignment::Horizon;
                 } else {
                     pos = Point::zero();
                     area = size;
@@ -503,7 +562,23 @@ impl Packed<PageElem> {
             }
 
             if let Some(fill) = fill {
-                frame.fill(fill.clone());
+                let shape =
+                    Geometry::Rect(size + Size::splat(2.0 * bleed)).filled(fill.clone());
+                frame.prepend(
+                    Point::splat(-bleed),
+                    FrameItem::Shape(shape, Span::detached()),
+                );
+            }
+
+            // Add the bleed and the space for printer's marks around the page.
+            let offset = bleed + slug;
+            if !offset.is_zero() {
+                frame.set_size(size + Size::splat(2.0 * offset));
+                frame.translate(Point::splat(offset));
+            }
+
+            if !slug.is_zero() {
+                draw_marks(&mut frame, size, bleed);
             }
 
             page_counter.visit(engine, &frame)?;
@@ -511,6 +586,10 @@ impl Packed<PageElem> {
                 frame,
                 numbering: numbering.clone(),
                 number: page_counter.logical(),
+                bleed,
+                slug,
+                transition,
+                duration,
             });
 
             page_counter.step();
@@ -518,18 +597,217 @@ impl Packed<PageElem> {
 
         Ok(pages)
     }
+
+    /// Determine the page's size, its margins, and whether they are
+    /// two-sided.
+    fn geometry(&self, styles: StyleChain) -> (Size, Sides<Abs>, bool) {
+        // When one of the lengths is infinite the page fits its content along
+        // that axis.
+        let width = self.width(styles).unwrap_or(Abs::inf());
+        let height = self.height(styles).unwrap_or(Abs::inf());
+        let mut size = Size::new(width, height);
+        if self.flipped(styles) {
+            std::mem::swap(&mut size.x, &mut size.y);
+        }
+
+        let mut min = width.min(height);
+        if !min.is_finite() {
+            min = Paper::A4.width();
+        }
+
+        // Determine the margins.
+        let default = Rel::<Length>::from((2.5 / 21.0) * min);
+        let margin = self.margin(styles);
+        let two_sided = margin.two_sided.unwrap_or(false);
+        let margin = margin
+            .sides
+            .map(|side| side.and_then(Smart::as_custom).unwrap_or(default))
+            .resolve(styles)
+            .relative_to(size);
+
+        (size, margin, two_sided)
+    }
+}
+
+// Raw units are points, so these are 3pt, 15pt, and 21pt respectively.
+
+/// The distance between the bleed and the start of a crop mark.
+const MARKS_GAP: Abs = Abs::raw(3.0);
+
+/// The length of a crop mark.
+const MARKS_LENGTH: Abs = Abs::raw(15.0);
+
+/// The space needed around the bleed to draw printer's marks.
+const MARKS_SLUG: Abs = Abs::raw(21.0);
+
+/// Draw crop and registration marks around a page of the given trimmed size.
+///
+/// The frame must already contain the bleed and the slug.
+fn draw_marks(frame: &mut Frame, trim: Size, bleed: Abs) {
+    let registration = Color::Cmyk(Cmyk { c: 1.0, m: 1.0, y: 1.0, k: 1.0 });
+    let stroke = FixedStroke::from_pair(registration, Abs::pt(0.25));
+    let line = |delta: Point| {
+        FrameItem::Shape(Geometry::Line(delta).stroked(stroke.clone()), Span::detached())
+    };
+
+    let origin = Point::splat(bleed + MARKS_SLUG);
+    let start = bleed + MARKS_GAP;
+    let horizontal = Point::with_x(MARKS_LENGTH);
+    let vertical = Point::with_y(MARKS_LENGTH);
+
+    // The crop marks, pointing outwards from each corner.
+    let outwards = |before: bool| if before { -(start + MARKS_LENGTH) } else { start };
+    for (x, left) in [(Abs::zero(), true), (trim.x, false)] {
+        for (y, top) in [(Abs::zero(), true), (trim.y, false)] {
+            let corner = origin + Point::new(x, y);
+            frame.push(corner + Point::with_x(outwards(left)), line(horizontal));
+            frame.push(corner + Point::with_y(outwards(top)), line(vertical));
+        }
+    }
+
+    // The registration targets, centered on each side.
+    let distance = start + MARKS_LENGTH / 2.0;
+    let radius = MARKS_LENGTH / 3.0;
+    for center in [
+        Point::new(trim.x / 2.0, -distance),
+        Point::new(trim.x / 2.0, trim.y + distance),
+        Point::new(-distance, trim.y / 2.0),
+        Point::new(trim.x + distance, trim.y / 2.0),
+    ] {
+        let center = origin + center;
+        let circle = ellipse(Size::splat(2.0 * radius), None, Some(stroke.clone()));
+        frame.push(
+            center - Point::splat(radius),
+            FrameItem::Shape(circle, Span::detached()),
+        );
+        frame.push(center - horizontal / 2.0, line(horizontal));
+        frame.push(center - vertical / 2.0, line(vertical));
+    }
 }
 
 /// A finished page.
 #[derive(Debug, Default, Clone)]
 pub struct Page {
     /// The frame that defines the page.
+    ///
+    /// If the page has a bleed or printer's marks, the frame includes them
+    /// and the trimmed page is offset by `bleed + slug` on each side.
     pub frame: Frame,
     /// The page's numbering.
     pub numbering: Option<Numbering>,
     /// The logical page number (controlled by `counter(page)` and may thus not
     /// match the physical number).
     pub number: usize,
+    /// How far the page's fill and background extend beyond the trimmed page.
+    pub bleed: Abs,
+    /// The space around the bleed that holds printer's marks.
+    pub slug: Abs,
+    /// The effect with which the page appears in a presentation.
+    pub transition: Option<Transition>,
+    /// How long the page is shown in a presentation.
+    pub duration: Option<Duration>,
+}
+
+/// A transition effect between the pages of a presentation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Transition {
+    /// Two lines sweep across the screen to reveal the page.
+    Split,
+    /// Multiple lines sweep across the screen like venetian blinds.
+    Blinds,
+    /// A rectangle expands from the center to reveal the page.
+    Box,
+    /// A single line sweeps across the screen.
+    Wipe,
+    /// The old page dissolves gradually.
+    Dissolve,
+    /// Like `dissolve`, but the effect sweeps across the screen.
+    Glitter,
+    /// The page flies in.
+    Fly,
+    /// The page pushes the old page off the screen.
+    Push,
+    /// The page slides in over the old page.
+    Cover,
+    /// The old page slides off to reveal the page.
+    Uncover,
+    /// The old page fades into the page.
+    Fade,
+}
+
+/// A selection of pages, for example for export.
+///
+/// Each range is inclusive and open ranges extend to the first or last page.
+/// Page numbers are physical and start at one.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageRanges(Vec<PageRange>);
+
+/// An inclusive range of physical page numbers, possibly open on either side.
+pub type PageRange = RangeInclusive<Option<NonZeroUsize>>;
+
+impl PageRanges {
+    /// Create a selection from a list of ranges.
+    pub fn new(ranges: Vec<PageRange>) -> Self {
+        Self(ranges)
+    }
+
+    /// The ranges making up the selection.
+    pub fn ranges(&self) -> &[PageRange] {
+        &self.0
+    }
+
+    /// Whether the page with the given physical number is selected.
+    pub fn includes_page(&self, page: NonZeroUsize) -> bool {
+        self.0.iter().any(|range| {
+            range.start().map_or(true, |start| start <= page)
+                && range.end().map_or(true, |end| page <= end)
+        })
+    }
+
+    /// Whether the page with the given zero-based index is selected.
+    pub fn includes_page_index(&self, index: usize) -> bool {
+        self.includes_page(NonZeroUsize::new(index + 1).unwrap())
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = EcoString;
+
+    /// Parse a comma-separated list of pages and ranges like `1,3-5,8-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |part: &str| -> StrResult<Option<NonZeroUsize>> {
+            let part = part.trim();
+            if part.is_empty() {
+                return Ok(None);
+            }
+            match part.parse::<NonZeroUsize>() {
+                Ok(number) => Ok(Some(number)),
+                Err(_) => bail!("invalid page number `{part}`"),
+            }
+        };
+
+        let mut ranges = vec![];
+        for part in s.split(',') {
+            let range = match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse(start)?, parse(end)?);
+                    if let (Some(start), Some(end)) = (start, end) {
+                        if start > end {
+                            bail!("page range `{}` is reversed", part.trim());
+                        }
+                    }
+                    start..=end
+                }
+                None => match parse(part)? {
+                    Some(page) => Some(page)..=Some(page),
+                    None => bail!("page range must not be empty"),
+                },
+            };
+            ranges.push(range);
+        }
+
+        Ok(Self(ranges))
+    }
 }
 
 /// Specification of the page's margins.
@@ -961,3 +1239,24 @@ papers! {
     (PRESENTATION_16_9:    297.0, 167.0625, "presentation-16-9")
     (PRESENTATION_4_3:     280.0,    210.0, "presentation-4-3")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_ranges() {
+        let ranges: PageRanges = "1,3-4,7-".parse().unwrap();
+        let selected: Vec<_> =
+            (0..9).filter(|&i| ranges.includes_page_index(i)).collect();
+        assert_eq!(selected, [0, 2, 3, 6, 7, 8]);
+
+        let ranges: PageRanges = "-2".parse().unwrap();
+        assert!(ranges.includes_page_index(0));
+        assert!(!ranges.includes_page_index(2));
+
+        assert!("".parse::<PageRanges>().is_err());
+        assert!("0".parse::<PageRanges>().is_err());
+        assert!("5-3".parse::<PageRanges>().is_err());
+    }
+}
@@ -53,24 +53,35 @@ pub mod symbols;
 pub mod text;
 pub mod visualize;
 
+mod dependencies;
+mod memory;
+
 #[doc(inline)]
 pub use typst_syntax as syntax;
 
-use std::collections::HashSet;
+pub use crate::dependencies::{Dependencies, FileDependency, FontDependency};
+pub use crate::memory::MemoryWorld;
+
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 
 use comemo::{Prehashed, Track, Tracked, Validate};
 use ecow::{EcoString, EcoVec};
 use typst_timing::{timed, TimingScope};
 
-use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult};
-use crate::engine::{Engine, Route};
-use crate::eval::Tracer;
+use crate::dependencies::Recorder;
+use crate::diag::{
+    warning, FileResult, SourceDiagnostic, SourceResult, StrResult, Warned,
+};
+use crate::engine::{Engine, Limits, Route};
+use crate::eval::{eval_string, EvalMode, Tracer};
 use crate::foundations::{
-    Array, Bytes, Content, Datetime, Dict, Module, Scope, StyleChain, Styles,
+    Array, Bytes, Content, Datetime, Dict, LocatableSelector, Module, Scope, Selector,
+    StyleChain, Styles,
 };
-use crate::introspection::{Introspector, Locator};
+use crate::introspection::{Introspector, Location, Locator, QueryMatch};
 use crate::layout::{Alignment, Dir, LayoutRoot};
+use crate::loading::AccessPolicy;
 use crate::model::Document;
 use crate::syntax::{FileId, PackageSpec, Source, Span};
 use crate::text::{Font, FontBook};
@@ -102,6 +113,76 @@ pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document>
     typeset(world, tracer, &module.content()).map_err(deduplicate)
 }
 
+/// Compile a source file into a fully layouted document and report the
+/// warnings alongside it.
+///
+/// This is a shorthand for [`compile`] with a fresh tracer, for when only the
+/// document and the warnings are of interest.
+pub fn compile_with_warnings(world: &dyn World) -> Warned<SourceResult<Document>> {
+    let mut tracer = Tracer::new();
+    let output = compile(world, &mut tracer);
+    Warned { output, warnings: tracer.warnings() }
+}
+
+/// Compile a source file and record everything the compilation depends on.
+///
+/// Works like [`compile`], but additionally returns the source files, other
+/// files, fonts, and packages that were read from the world, together with
+/// hashes of their contents. The dependencies are also returned if
+/// compilation failed, as fixing the error may require changing one of them.
+pub fn compile_with_dependencies(
+    world: &dyn World,
+    tracer: &mut Tracer,
+) -> (SourceResult<Document>, Dependencies) {
+    let recorder = Recorder::new(world);
+    let output = compile(&recorder, tracer);
+    (output, recorder.finish())
+}
+
+/// Run a selector against a compiled document.
+///
+/// The selector is given as Typst code, like `heading.where(level: 1)` or
+/// `<intro>`. Returns the matching elements in document order together with
+/// their positions. The matches can be serialized to pass them on to external
+/// tools.
+pub fn query(
+    world: &dyn World,
+    document: &Document,
+    selector: &str,
+) -> StrResult<Vec<QueryMatch>> {
+    let selector = eval_string(
+        world.track(),
+        selector,
+        Span::detached(),
+        EvalMode::Code,
+        Scope::default(),
+    )
+    .map_err(|errors| {
+        let mut message = EcoString::from("failed to evaluate selector");
+        for (i, error) in errors.into_iter().enumerate() {
+            message.push_str(if i == 0 { ": " } else { ", " });
+            message.push_str(&error.message);
+        }
+        message
+    })?
+    .cast::<LocatableSelector>()?;
+
+    Ok(document
+        .introspector
+        .query(&selector.0)
+        .into_iter()
+        .map(|elem| {
+            let position = document.introspector.position(elem.location().unwrap());
+            QueryMatch {
+                element: elem.into_inner(),
+                page: position.page,
+                x: position.point.x.to_pt(),
+                y: position.point.y.to_pt(),
+            }
+        })
+        .collect())
+}
+
 /// Relayout until introspection converges.
 fn typeset(
     world: Tracked<dyn World + '_>,
@@ -117,14 +198,16 @@ fn typeset(
 
     let mut iter = 0;
     let mut document = Document::default();
+    let mut observed = EcoVec::new();
 
     // Relayout until all introspections stabilize.
     // If that doesn't happen within five attempts, we give up.
     loop {
         let _scope = TimingScope::new(ITER_NAMES[iter], None);
 
-        // Clear delayed errors.
+        // Clear delayed errors and observed state values.
         tracer.delayed();
+        let previous = std::mem::replace(&mut observed, tracer.observed());
 
         let constraint = <Introspector as Validate>::Constraint::new();
         let mut locator = Locator::new();
@@ -137,8 +220,10 @@ fn typeset(
         };
 
         // Layout!
+        engine.check_cancelled(content.span())?;
         document = content.layout_root(&mut engine, styles)?;
         document.introspector.rebuild(&document.pages);
+        observed = tracer.observed();
         iter += 1;
 
         if timed!("check stabilized", document.introspector.validate(&constraint)) {
@@ -146,10 +231,14 @@ fn typeset(
         }
 
         if iter >= 5 {
-            tracer.warn(warning!(
-                Span::detached(), "layout did not converge within 5 attempts";
-                hint: "check if any states or queries are updating themselves"
-            ));
+            tracer.warn(
+                warning!(
+                    Span::detached(), "layout did not converge within 5 attempts";
+                    hint: "check if any states or queries are updating themselves"
+                )
+                .with_code("W0003"),
+            );
+            warn_oscillating(tracer, &document.introspector, &previous, &observed);
             break;
         }
     }
@@ -163,6 +252,56 @@ fn typeset(
     Ok(document)
 }
 
+/// Warn about states, counters, and queries whose values differed between the
+/// last two layout attempts.
+fn warn_oscillating(
+    tracer: &mut Tracer,
+    introspector: &Introspector,
+    previous: &[(EcoString, Location, u128)],
+    current: &[(EcoString, Location, u128)],
+) {
+    let before: HashMap<_, _> = previous
+        .iter()
+        .map(|(what, loc, hash)| ((what, *loc), *hash))
+        .collect();
+
+    let mut oscillating: Vec<(&EcoString, Vec<Location>)> = vec![];
+    for (what, loc, hash) in current {
+        if before.get(&(what, *loc)).map_or(true, |prev| prev == hash) {
+            continue;
+        }
+        match oscillating.iter_mut().find(|(w, _)| *w == what) {
+            Some((_, locs)) if !locs.contains(loc) => locs.push(*loc),
+            Some(_) => {}
+            None => oscillating.push((what, vec![*loc])),
+        }
+    }
+
+    let span = |loc: Location| {
+        introspector
+            .query_first(&Selector::Location(loc))
+            .map_or(Span::detached(), |elem| elem.span())
+    };
+
+    for (what, locs) in oscillating {
+        let count = locs.len();
+        let mut warning = warning!(
+            span(locs[0]), "{what} did not converge";
+            hint: "its value kept changing at {count} {}",
+                if count == 1 { "location" } else { "locations" }
+        )
+        .with_code("W0004");
+        for &loc in locs.iter().skip(1).take(MAX_OSCILLATING_LOCATIONS) {
+            warning.related(span(loc), "its value also changed here");
+        }
+        tracer.warn(warning);
+    }
+}
+
+/// How many further locations are reported for a state, counter, or query
+/// that did not converge.
+const MAX_OSCILLATING_LOCATIONS: usize = 4;
+
 /// Deduplicate diagnostics.
 fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic> {
     let mut unique = HashSet::new();
@@ -184,12 +323,18 @@ fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic>
 /// information on when something can change. For example, fonts typically don't
 /// change and can thus even be cached across multiple compilations (for
 /// long-running applications like `typst watch`). Source files on the other
-/// hand can change and should thus be cleared after each compilation. Advanced
-/// clients like language servers can also retain the source files and
-/// [edit](Source::edit) them in-place to benefit from better incremental
-/// performance.
+/// hand can change and should thus be cleared after each compilation. If the
+/// world is notified about which files changed, like `typst watch` is by the
+/// file system, it is enough to clear just those. All memoized results that
+/// only depend on the other files can then be reused without reading them
+/// again. Advanced clients like language servers can also retain the source
+/// files and [edit](Source::edit) them in-place to benefit from better
+/// incremental performance.
+///
+/// The world must be thread-safe, as parts of the compilation, like the layout
+/// of independent page runs, access it from multiple threads.
 #[comemo::track]
-pub trait World {
+pub trait World: Send + Sync {
     /// The standard library.
     ///
     /// Can be created through `Library::build()`.
@@ -228,6 +373,29 @@ pub trait World {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// Whether documents may write files with the [`write`](loading::write)
+    /// function.
+    ///
+    /// This function is optional to implement and defaults to `false`. Typst
+    /// never stores written files by itself. Instead, they are collected in
+    /// the [`Tracer`] and can be retrieved with [`Tracer::outputs`] after
+    /// compilation, so that the embedder can decide where to store them.
+    fn can_write(&self) -> bool {
+        false
+    }
+
+    /// Whether the compilation should be aborted.
+    ///
+    /// This function is optional to implement and defaults to `false`. It is
+    /// polled regularly during evaluation and layout, so that an embedder can
+    /// stop an outdated compilation when a newer edit arrives, for instance
+    /// with a [`CancellationToken`](util::CancellationToken). A cancelled
+    /// compilation fails with an error. Its partial results aren't reused by
+    /// later compilations.
+    fn cancelled(&self) -> bool {
+        false
+    }
 }
 
 /// Helper methods on [`World`] implementations.
@@ -254,6 +422,10 @@ pub struct Library {
     /// The default style properties (for page size, font selection, and
     /// everything else configurable via set and show rules).
     pub styles: Styles,
+    /// Limits on the resources that evaluation may use.
+    pub limits: Limits,
+    /// Restrictions on the resources that documents may load.
+    pub policy: AccessPolicy,
 }
 
 impl Library {
@@ -276,6 +448,8 @@ impl Default for Library {
 #[derive(Debug, Clone, Default)]
 pub struct LibraryBuilder {
     inputs: Option<Dict>,
+    limits: Option<Limits>,
+    policy: Option<AccessPolicy>,
 }
 
 impl LibraryBuilder {
@@ -285,12 +459,32 @@ impl LibraryBuilder {
         self
     }
 
+    /// Configure the limits on the resources that evaluation may use.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Configure which resources documents may load.
+    pub fn with_policy(mut self, policy: AccessPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
         let math = math::module();
         let inputs = self.inputs.unwrap_or_default();
         let global = global(math.clone(), inputs);
-        Library { global, math, styles: Styles::new() }
+        let limits = self.limits.unwrap_or_default();
+        let policy = self.policy.unwrap_or_default();
+        Library {
+            global,
+            math,
+            styles: Styles::new(),
+            limits,
+            policy,
+        }
     }
 }
 
@@ -308,6 +502,7 @@ fn global(math: Module, inputs: Dict) -> Module {
     self::loading::define(&mut global);
     self::symbols::define(&mut global);
     prelude(&mut global);
+    self::foundations::sys::define_features(&mut global);
     Module::new("global", global)
 }
 
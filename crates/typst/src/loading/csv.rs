@@ -3,9 +3,8 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{cast, func, scope, Array, Dict, IntoValue, Type, Value};
-use crate::loading::Readable;
+use crate::loading::{load, Readable, ResourceKind};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a CSV file.
 ///
@@ -45,11 +44,30 @@ pub fn csv(
     #[named]
     #[default(RowType::Array)]
     row_type: RowType,
+    /// How many rows to skip before the first returned row. The header row
+    /// does not count as a row if `row-type` is `dictionary`.
+    #[named]
+    #[default(0)]
+    skip: usize,
+    /// The maximum number of rows to return.
+    ///
+    /// The file is read row by row and reading stops once enough rows were
+    /// found, so this is useful to look at only part of a very large file. If
+    /// set to `{none}`, all remaining rows are returned.
+    #[named]
+    #[default]
+    limit: Option<usize>,
 ) -> SourceResult<Array> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    self::csv::decode(Spanned::new(Readable::Bytes(data), span), delimiter, row_type)
+    let data = load(engine.world, id, ResourceKind::Data).at(span)?;
+    self::csv::decode(
+        Spanned::new(Readable::Bytes(data), span),
+        delimiter,
+        row_type,
+        skip,
+        limit,
+    )
 }
 
 #[scope]
@@ -74,6 +92,16 @@ impl csv {
         #[named]
         #[default(RowType::Array)]
         row_type: RowType,
+        /// How many rows to skip before the first returned row. The header
+        /// row does not count as a row if `row-type` is `dictionary`.
+        #[named]
+        #[default(0)]
+        skip: usize,
+        /// The maximum number of rows to return. If set to `{none}`, all
+        /// remaining rows are returned.
+        #[named]
+        #[default]
+        limit: Option<usize>,
     ) -> SourceResult<Array> {
         let Spanned { v: data, span } = data;
         let has_headers = row_type == RowType::Dict;
@@ -100,7 +128,12 @@ impl csv {
         }
 
         let mut array = Array::new();
-        for (line, result) in reader.records().enumerate() {
+        let records = reader
+            .records()
+            .enumerate()
+            .skip(skip)
+            .take(limit.unwrap_or(usize::MAX));
+        for (line, result) in records {
             // Original solution was to use line from error, but that is
             // incorrect with `has_headers` set to `false`. See issue:
             // https://github.com/BurntSushi/rust-csv/issues/184
@@ -3,9 +3,8 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{load, Readable, ResourceKind};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a JSON file.
 ///
@@ -53,7 +52,7 @@ pub fn json(
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load(engine.world, id, ResourceKind::Data).at(span)?;
     json::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
@@ -6,10 +6,13 @@ mod cbor_;
 mod csv_;
 #[path = "json.rs"]
 mod json_;
+mod policy;
 #[path = "read.rs"]
 mod read_;
 #[path = "toml.rs"]
 mod toml_;
+#[path = "write.rs"]
+mod write_;
 #[path = "xml.rs"]
 mod xml_;
 #[path = "yaml.rs"]
@@ -18,8 +21,10 @@ mod yaml_;
 pub use self::cbor_::*;
 pub use self::csv_::*;
 pub use self::json_::*;
+pub use self::policy::*;
 pub use self::read_::*;
 pub use self::toml_::*;
+pub use self::write_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
 
@@ -36,6 +41,7 @@ pub static DATA_LOADING: Category;
 pub(super) fn define(global: &mut Scope) {
     global.category(DATA_LOADING);
     global.define_func::<read>();
+    global.define_func::<write>();
     global.define_func::<csv>();
     global.define_func::<json>();
     global.define_func::<toml>();
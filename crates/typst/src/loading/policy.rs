@@ -0,0 +1,252 @@
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{HintedStrResult, HintedString};
+use crate::foundations::Bytes;
+use crate::syntax::{FileId, Source};
+use crate::World;
+
+/// Restricts which resources documents may load.
+///
+/// The policy is part of the [`Library`](crate::Library) and configured with
+/// [`LibraryBuilder::with_policy`](crate::LibraryBuilder::with_policy). By
+/// default, everything the world provides may be loaded. Services that compile
+/// untrusted documents can deny whole kinds of resources and restrict the
+/// paths that may be read. Loading a denied resource fails with an error that
+/// points to the offending expression.
+///
+/// Paths are matched against glob patterns. Files in the project are matched
+/// by their absolute path in the project, like `/images/logo.png`. Files in a
+/// package are prefixed with the package, like
+/// `@preview/example:0.1.0/lib.typ`. In patterns, `*` matches any sequence of
+/// characters except `/`, `**` matches any sequence of characters, and `?`
+/// matches a single character except `/`.
+///
+/// ```
+/// # use typst::loading::{AccessPolicy, ResourceKind};
+/// let policy = AccessPolicy::new()
+///     .deny_kind(ResourceKind::Package)
+///     .allow_path("/**/*.typ")
+///     .allow_path("/assets/**")
+///     .deny_path("**/secret*");
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct AccessPolicy {
+    /// The kinds of resources that may not be loaded.
+    denied_kinds: Vec<ResourceKind>,
+    /// Patterns of which at least one must match the path of a file, unless
+    /// there are none.
+    allowed_paths: Vec<EcoString>,
+    /// Patterns of which none may match the path of a file.
+    denied_paths: Vec<EcoString>,
+}
+
+/// A kind of resource that a document can load.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResourceKind {
+    /// Source files, through an import or include.
+    Source,
+    /// Image files.
+    Image,
+    /// Other files, like data files, bibliographies, syntax definitions,
+    /// color profiles, and plugins.
+    Data,
+    /// Fonts, selected by family name.
+    Font,
+    /// Any file from a package, in addition to its own kind.
+    Package,
+}
+
+impl ResourceKind {
+    /// A plural description of this kind of resources.
+    fn plural(self) -> &'static str {
+        match self {
+            Self::Source => "source files",
+            Self::Image => "images",
+            Self::Data => "data files",
+            Self::Font => "fonts",
+            Self::Package => "packages",
+        }
+    }
+}
+
+impl AccessPolicy {
+    /// Create a policy that permits loading everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deny loading any resource of the given kind.
+    pub fn deny_kind(mut self, kind: ResourceKind) -> Self {
+        if !self.denied_kinds.contains(&kind) {
+            self.denied_kinds.push(kind);
+        }
+        self
+    }
+
+    /// Permit reading the files matching a pattern.
+    ///
+    /// Once a pattern is permitted, only files that match one of the permitted
+    /// patterns may be read.
+    pub fn allow_path(mut self, pattern: &str) -> Self {
+        self.allowed_paths.push(normalize(pattern));
+        self
+    }
+
+    /// Deny reading the files matching a pattern, even if they match a
+    /// permitted one.
+    pub fn deny_path(mut self, pattern: &str) -> Self {
+        self.denied_paths.push(normalize(pattern));
+        self
+    }
+
+    /// Whether resources of the given kind may be loaded at all.
+    pub fn allows_kind(&self, kind: ResourceKind) -> bool {
+        !self.denied_kinds.contains(&kind)
+    }
+
+    /// Check whether a file may be read as a resource of the given kind.
+    pub fn check_file(&self, id: FileId, kind: ResourceKind) -> HintedStrResult<()> {
+        let path = policy_path(id);
+        let denied = |hint: EcoString| HintedString {
+            message: eco_format!("access to {path} was denied"),
+            hints: vec![hint],
+            code: None,
+        };
+
+        let package = id.package().map(|_| ResourceKind::Package);
+        let mut kinds = std::iter::once(kind).chain(package);
+        if let Some(kind) = kinds.find(|&kind| !self.allows_kind(kind)) {
+            return Err(denied(eco_format!(
+                "this environment does not permit loading {}",
+                kind.plural()
+            )));
+        }
+
+        let allowed = self.allowed_paths.is_empty()
+            || self.allowed_paths.iter().any(|pattern| glob(pattern, &path));
+        if !allowed || self.denied_paths.iter().any(|pattern| glob(pattern, &path)) {
+            return Err(denied(
+                "this environment does not permit loading this path".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a font family may be selected.
+    pub fn check_font(&self, family: &str) -> HintedStrResult<()> {
+        if !self.allows_kind(ResourceKind::Font) {
+            return Err(HintedString {
+                message: eco_format!("access to font family {family} was denied"),
+                hints: vec!["this environment does not permit selecting fonts".into()],
+                code: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Read a file, if the access policy of the world's library permits it.
+pub(crate) fn load(
+    world: Tracked<dyn World + '_>,
+    id: FileId,
+    kind: ResourceKind,
+) -> HintedStrResult<Bytes> {
+    world.library().policy.check_file(id, kind)?;
+    world.file(id).map_err(HintedString::from)
+}
+
+/// Read a source file, if the access policy of the world's library permits
+/// it.
+pub(crate) fn load_source(
+    world: Tracked<dyn World + '_>,
+    id: FileId,
+) -> HintedStrResult<Source> {
+    world.library().policy.check_file(id, ResourceKind::Source)?;
+    world.source(id).map_err(HintedString::from)
+}
+
+/// The path of a file that patterns are matched against.
+fn policy_path(id: FileId) -> EcoString {
+    let mut path = id.package().map(|spec| eco_format!("{spec}")).unwrap_or_default();
+    for component in id.vpath().as_rootless_path().components() {
+        path.push('/');
+        path.push_str(&component.as_os_str().to_string_lossy());
+    }
+    path
+}
+
+/// Make patterns for files in the project start with a slash.
+fn normalize(pattern: &str) -> EcoString {
+    if pattern.starts_with(['/', '@', '*']) {
+        pattern.into()
+    } else {
+        eco_format!("/{pattern}")
+    }
+}
+
+/// Whether a path matches a glob pattern.
+fn glob(pattern: &str, path: &str) -> bool {
+    if let Some(rest) = pattern.strip_prefix("**") {
+        // A `**/` also matches no directories at all.
+        if rest.strip_prefix('/').is_some_and(|rest| glob(rest, path)) {
+            return true;
+        }
+        return boundaries(path).any(|i| glob(rest, &path[i..]));
+    }
+
+    if let Some(rest) = pattern.strip_prefix('*') {
+        let segment = &path[..path.find('/').unwrap_or(path.len())];
+        return boundaries(segment).any(|i| glob(rest, &path[i..]));
+    }
+
+    let mut pattern = pattern.chars();
+    let mut path = path.chars();
+    match (pattern.next(), path.next()) {
+        (None, None) => true,
+        (Some(p), Some(c)) if p == c || (p == '?' && c != '/') => {
+            glob(pattern.as_str(), path.as_str())
+        }
+        _ => false,
+    }
+}
+
+/// The byte offsets of all character boundaries in a string, including its
+/// end.
+fn boundaries(s: &str) -> impl Iterator<Item = usize> + '_ {
+    s.char_indices().map(|(i, _)| i).chain([s.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::VirtualPath;
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("/*.typ", "/main.typ"));
+        assert!(!glob("/*.typ", "/chapters/intro.typ"));
+        assert!(glob("/**/*.typ", "/main.typ"));
+        assert!(glob("/**/*.typ", "/chapters/intro.typ"));
+        assert!(glob("/assets/**", "/assets/images/logo.png"));
+        assert!(glob("/image?.png", "/image1.png"));
+        assert!(!glob("/image?.png", "/image10.png"));
+        assert!(glob("**/secret*", "/data/secret.json"));
+        assert!(glob("@preview/**", "@preview/example:0.1.0/lib.typ"));
+    }
+
+    #[test]
+    fn test_check_file() {
+        let id = |path| FileId::new(None, VirtualPath::new(path));
+        let policy = AccessPolicy::new()
+            .deny_kind(ResourceKind::Image)
+            .allow_path("data/**")
+            .deny_path("**/secret*");
+
+        assert!(policy.check_file(id("data/values.csv"), ResourceKind::Data).is_ok());
+        assert!(policy.check_file(id("data/logo.png"), ResourceKind::Image).is_err());
+        assert!(policy.check_file(id("data/secret.csv"), ResourceKind::Data).is_err());
+        assert!(policy.check_file(id("values.csv"), ResourceKind::Data).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, NoneValue};
+use crate::loading::Readable;
+use crate::syntax::Spanned;
+
+/// Writes plain text or data to a file.
+///
+/// This lets a document produce side artifacts for build pipelines, for
+/// example a CSV file with data collected in the document or a JSON file with
+/// extracted metadata. The path is resolved like for [`read`]($read), so files
+/// can only be written within the project.
+///
+/// Writing files must be permitted by the compiler. Otherwise, this function
+/// fails with an error. The Typst CLI permits it when it is given a directory
+/// for written files with the `--write-dir` flag and then stores the files
+/// there instead of in the project. If the same file is written multiple
+/// times, the last write wins.
+///
+/// # Example
+/// ```typ
+/// #let rows = (("name", "age"), ("Ann", "31"), ("Ben", "28"))
+/// #write("people.csv", rows.map(row => row.join(",")).join("\n"))
+/// ```
+#[func]
+pub fn write(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to the file to write.
+    path: Spanned<EcoString>,
+    /// The text or bytes to write.
+    data: Readable,
+) -> SourceResult<NoneValue> {
+    let Spanned { v: path, span } = path;
+    if !engine.world.can_write() {
+        bail!(
+            span, "writing files is not permitted";
+            hint: "the compiler must permit it, e.g. with the `--write-dir` flag of the CLI"
+        );
+    }
+
+    let id = span.resolve_path(&path).at(span)?;
+    if id.package().is_some() {
+        bail!(span, "cannot write files of packages");
+    }
+
+    engine.tracer.write(id, data.into());
+    Ok(NoneValue)
+}
@@ -3,9 +3,8 @@ use ecow::EcoString;
 use crate::diag::{format_xml_like_error, At, FileError, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{dict, func, scope, Array, Dict, IntoValue, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{load, Readable, ResourceKind};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from an XML file.
 ///
@@ -64,7 +63,7 @@ pub fn xml(
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load(engine.world, id, ResourceKind::Data).at(span)?;
     xml::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
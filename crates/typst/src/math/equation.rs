@@ -244,7 +244,7 @@ impl LayoutSingle for Packed<EquationElem> {
         if let Some(numbering) = (**self).numbering(styles) {
             let pod = Regions::one(regions.base(), Axes::splat(false));
             let counter = Counter::of(EquationElem::elem())
-                .at(engine, self.location().unwrap())?
+                .at_loc(engine, self.location().unwrap())?
                 .display(engine, numbering)?
                 .spanned(self.span())
                 .layout(engine, styles, pod)?
@@ -367,7 +367,7 @@ impl Outlinable for Packed<EquationElem> {
 
         let numbers = self
             .counter()
-            .at(engine, self.location().unwrap())?
+            .at_loc(engine, self.location().unwrap())?
             .display(engine, numbering)?;
 
         Ok(Some(supplement + numbers))
@@ -1,4 +1,4 @@
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::foundations::{elem, Content, Packed, StyleChain, Value};
 use crate::layout::{Em, Frame, FrameItem, Point, Size};
 use crate::math::{
@@ -69,7 +69,7 @@ pub struct BinomElem {
         let values = args.all::<Spanned<Value>>()?;
         if values.is_empty() {
             // Prevents one element binomials
-            bail!(args.span, "missing argument: lower");
+            bail!(error!(args.span, "missing argument: lower").with_code("E0007"));
         }
         values.into_iter().map(|spanned| spanned.v.display()).collect()
     )]
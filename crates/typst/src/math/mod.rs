@@ -209,6 +209,7 @@ pub fn module() -> Module {
     for (name, symbol) in crate::symbols::SYM {
         math.define(*name, symbol.clone());
     }
+    crate::symbols::deprecate(&mut math);
 
     Module::new("math", math)
 }
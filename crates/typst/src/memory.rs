@@ -0,0 +1,213 @@
+//! A world that keeps all files in memory.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use comemo::Prehashed;
+
+use crate::diag::{FileError, FileResult, PackageError};
+use crate::foundations::{Bytes, Datetime};
+use crate::syntax::{FileId, PackageSpec, Source, VirtualPath};
+use crate::text::{Font, FontBook};
+use crate::{Library, World};
+
+/// A [`World`] whose files and fonts are all held in memory.
+///
+/// It doesn't touch the file system, so it is suitable for tests, for
+/// embedding Typst in environments without one, like WebAssembly, and for
+/// services that receive a project's files over the network.
+///
+/// Files are added under their path in the project or in a package. Sources
+/// are parsed once when they are first accessed and afterwards only
+/// incrementally reparsed when their file is updated.
+///
+/// ```
+/// # use typst::MemoryWorld;
+/// let mut world = MemoryWorld::new("#import \"data.typ\": x\n#x");
+/// world.insert_file("data.typ", "#let x = 1".as_bytes());
+/// ```
+pub struct MemoryWorld {
+    /// The standard library.
+    library: Prehashed<Library>,
+    /// Metadata about the fonts.
+    book: Prehashed<FontBook>,
+    /// The fonts, in the order of the font book.
+    fonts: Vec<Font>,
+    /// The id of the main source file.
+    main: FileId,
+    /// The contents of all files.
+    files: HashMap<FileId, Bytes>,
+    /// The already parsed source files.
+    sources: RwLock<HashMap<FileId, Source>>,
+    /// The date returned by `today`.
+    today: Option<Datetime>,
+}
+
+impl MemoryWorld {
+    /// Create a world with the default library, without fonts, and with the
+    /// given text as the main file `/main.typ`.
+    pub fn new(main: &str) -> Self {
+        let id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = Self {
+            library: Prehashed::new(Library::default()),
+            book: Prehashed::new(FontBook::new()),
+            fonts: vec![],
+            main: id,
+            files: HashMap::new(),
+            sources: RwLock::new(HashMap::new()),
+            today: None,
+        };
+        world.insert(id, main.as_bytes());
+        world
+    }
+
+    /// Use another standard library, for instance with inputs.
+    pub fn with_library(mut self, library: Library) -> Self {
+        self.library = Prehashed::new(library);
+        self
+    }
+
+    /// Make fonts available in addition to those that were already added.
+    pub fn with_fonts(mut self, fonts: impl IntoIterator<Item = Font>) -> Self {
+        self.fonts.extend(fonts);
+        self.book = Prehashed::new(FontBook::from_fonts(&self.fonts));
+        self
+    }
+
+    /// Set the date returned by `datetime.today()`.
+    ///
+    /// The same date is returned for all offsets. By default, there is no
+    /// date and `datetime.today()` fails.
+    pub fn with_today(mut self, today: Option<Datetime>) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Make another file in the project the main file.
+    ///
+    /// The file doesn't need to exist yet. As long as it doesn't, the main
+    /// file is empty.
+    pub fn set_main(&mut self, path: &str) {
+        self.main = FileId::new(None, VirtualPath::new(path));
+    }
+
+    /// Add or update a file in the project.
+    pub fn insert_file(&mut self, path: &str, data: impl Into<Bytes>) {
+        self.insert(FileId::new(None, VirtualPath::new(path)), data);
+    }
+
+    /// Add or update a file in a package.
+    pub fn insert_package_file(
+        &mut self,
+        package: PackageSpec,
+        path: &str,
+        data: impl Into<Bytes>,
+    ) {
+        self.insert(FileId::new(Some(package), VirtualPath::new(path)), data);
+    }
+
+    /// Add or update a file.
+    ///
+    /// If the file was already accessed as a source file, its syntax tree is
+    /// updated incrementally.
+    pub fn insert(&mut self, id: FileId, data: impl Into<Bytes>) {
+        let data = data.into();
+        let sources = self.sources.get_mut().unwrap();
+        if let Some(source) = sources.get_mut(&id) {
+            match decode_utf8(&data) {
+                Ok(text) => {
+                    source.replace(text);
+                }
+                Err(_) => {
+                    sources.remove(&id);
+                }
+            }
+        }
+        self.files.insert(id, data);
+    }
+
+    /// Remove a file.
+    pub fn remove(&mut self, id: FileId) {
+        self.files.remove(&id);
+        self.sources.get_mut().unwrap().remove(&id);
+    }
+
+    /// The ids of all files, in no particular order.
+    pub fn files(&self) -> impl Iterator<Item = FileId> + '_ {
+        self.files.keys().copied()
+    }
+}
+
+impl World for MemoryWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.source(self.main)
+            .unwrap_or_else(|_| Source::new(self.main, String::new()))
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if let Some(source) = self.sources.read().unwrap().get(&id) {
+            return Ok(source.clone());
+        }
+
+        let text = decode_utf8(&self.file(id)?)?.to_string();
+        let source = Source::new(id, text);
+        self.sources.write().unwrap().insert(id, source.clone());
+        Ok(source)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(data) = self.files.get(&id) {
+            return Ok(data.clone());
+        }
+
+        // Distinguish a missing package from a missing file in a package.
+        if let Some(spec) = id.package() {
+            if !self.files.keys().any(|other| other.package() == Some(spec)) {
+                return Err(PackageError::NotFound(spec.clone()).into());
+            }
+        }
+
+        Err(FileError::NotFound(id.vpath().as_rootless_path().into()))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index).cloned()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        self.today
+    }
+}
+
+/// Decode UTF-8 with an optional BOM.
+fn decode_utf8(buf: &[u8]) -> FileResult<&str> {
+    // Remove UTF-8 BOM.
+    Ok(std::str::from_utf8(buf.strip_prefix(b"\xef\xbb\xbf").unwrap_or(buf))?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::eval::Tracer;
+    use crate::model::Document;
+
+    /// Create a world with a text font.
+    pub fn world(main: &str) -> MemoryWorld {
+        let data = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        MemoryWorld::new(main).with_fonts([font])
+    }
+
+    /// Compile a document with a text font, which must succeed.
+    pub fn compile(main: &str) -> Document {
+        crate::compile(&world(main), &mut Tracer::new()).unwrap()
+    }
+}
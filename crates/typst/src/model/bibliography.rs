@@ -7,7 +7,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use comemo::{Prehashed, Tracked};
-use ecow::{eco_format, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use hayagriva::archive::ArchivedStyle;
 use hayagriva::io::BibLaTeXError;
 use hayagriva::{
@@ -23,16 +23,18 @@ use crate::diag::{bail, error, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::{eval_string, EvalMode};
 use crate::foundations::{
-    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, FromValue, IntoValue, Label,
-    NativeElement, Packed, Reflect, Repr, Scope, Show, ShowSet, Smart, Str, StyleChain,
-    Styles, Synthesize, Type, Value,
+    cast, dict, elem, ty, Args, Array, Bytes, CastInfo, Content, FromValue, Func,
+    IntoValue, Label, NativeElement, Packed, Reflect, Repr, Scope, Selector, Show,
+    ShowSet, Smart, Str, StyleChain, Styles, Synthesize, Type, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::layout::{
     BlockElem, Em, GridCell, GridElem, HElem, PadElem, Sizing, TrackSizings, VElem,
 };
+use crate::loading::{load, ResourceKind};
 use crate::model::{
-    CitationForm, CiteGroup, Destination, FootnoteElem, HeadingElem, LinkElem, ParElem,
+    CitationForm, CiteGroup, Destination, FootnoteElem, HeadingElem, LinkElem,
+    LocatorKind, ParElem,
 };
 
 use crate::syntax::{Span, Spanned};
@@ -45,13 +47,16 @@ use crate::World;
 /// A bibliography / reference listing.
 ///
 /// You can create a new bibliography by calling this function with a path
-/// to a bibliography file in either one of two formats:
+/// to a bibliography file in one of three formats:
 ///
 /// - A Hayagriva `.yml` file. Hayagriva is a new bibliography file format
 ///   designed for use with Typst. Visit its
 ///   [documentation](https://github.com/typst/hayagriva/blob/main/docs/file-format.md)
 ///   for more details.
 /// - A BibLaTeX `.bib` file.
+/// - A CSL-JSON `.json` file, as exported by reference managers like Zotero.
+///   Its items are translated into Hayagriva entries, so some rarely used
+///   fields may be lost.
 ///
 /// As soon as you add a bibliography somewhere in your document, you can start
 /// citing things with reference syntax (`[@key]`) or explicit calls to the
@@ -84,9 +89,32 @@ use crate::World;
 ///
 /// #bibliography("works.bib")
 /// ```
+///
+/// # Multiple bibliographies
+/// A document can contain more than one bibliography, for instance one at the
+/// end of each chapter of a thesis or each contribution to an edited volume.
+/// Each citation belongs to the first bibliography after it that contains the
+/// cited work. If there is no such bibliography, the citation belongs to the
+/// last bibliography before it that contains the work. Each bibliography only
+/// lists the works cited for it and numbers them independently.
+///
+/// With a [`filter`]($bibliography.filter), the works can also be split into
+/// several listings at the same place, for example into primary and secondary
+/// sources.
+///
+/// ```example
+/// = Rivers
+/// Glaciers are melting. @glacier-melt
+/// #bibliography("works.bib", title: none)
+///
+/// = Oceans
+/// Pirates love the sea. @arrgh
+/// #bibliography("works.bib", title: none)
+/// ```
 #[elem(Locatable, Synthesize, Show, ShowSet, LocalName)]
 pub struct BibliographyElem {
-    /// Path(s) to Hayagriva `.yml` and/or BibLaTeX `.bib` files.
+    /// Path(s) to Hayagriva `.yml`, BibLaTeX `.bib`, and/or CSL-JSON `.json`
+    /// files.
     #[required]
     #[parse(
         let (paths, bibliography) = Bibliography::parse(engine, args)?;
@@ -115,6 +143,34 @@ pub struct BibliographyElem {
     #[default(false)]
     pub full: bool,
 
+    /// Which works from the bibliography files to include in this
+    /// bibliography.
+    ///
+    /// The function is called with a dictionary for each work. The dictionary
+    /// contains the work's `key`, its `type` (like `{"article"}`, `{"book"}`,
+    /// or `{"web"}`), and its `title`, which is `{none}` if the work has no
+    /// title. The function should return whether the work belongs in this
+    /// bibliography. Citations of works that were filtered out are bound to
+    /// another bibliography.
+    ///
+    /// ```example
+    /// Pirates @arrgh sailed
+    /// the net-work. @netwok
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Books],
+    ///   filter: work => work.type == "book",
+    /// )
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Articles],
+    ///   filter: work => work.type != "book",
+    /// )
+    /// ```
+    #[borrowed]
+    pub filter: Option<Func>,
+
     /// The bibliography style.
     ///
     /// Should be either one of the built-in styles (see below) or a path to
@@ -154,19 +210,58 @@ cast! {
 }
 
 impl BibliographyElem {
-    /// Find the document's bibliography.
-    pub fn find(introspector: Tracked<Introspector>) -> StrResult<Packed<Self>> {
-        let query = introspector.query(&Self::elem().select());
-        let mut iter = query.iter();
-        let Some(elem) = iter.next() else {
+    /// Find the document's bibliographies together with the citation groups
+    /// that belong to each of them.
+    ///
+    /// A group belongs to the first bibliography after it that contains the
+    /// group's first key or, if there is none, to the last one before it.
+    /// Groups whose key is in no bibliography at all are assigned to the next
+    /// bibliography, which then reports the missing key.
+    fn find_all(
+        introspector: Tracked<Introspector>,
+    ) -> StrResult<Vec<(Packed<Self>, EcoVec<Prehashed<Content>>)>> {
+        let selector =
+            Selector::Or(eco_vec![Self::elem().select(), CiteGroup::elem().select()]);
+
+        let elems = introspector.query(&selector);
+        let mut bibliographies: Vec<_> = elems
+            .iter()
+            .enumerate()
+            .filter_map(|(i, elem)| {
+                let bibliography = elem.to_packed::<Self>()?.clone();
+                Some((i, bibliography, EcoVec::new()))
+            })
+            .collect();
+
+        if bibliographies.is_empty() {
             bail!("the document does not contain a bibliography");
-        };
+        }
 
-        if iter.next().is_some() {
-            bail!("multiple bibliographies are not yet supported");
+        for (i, elem) in elems.iter().enumerate() {
+            let Some(group) = elem.to_packed::<CiteGroup>() else { continue };
+            let key = group.children().first().map(|child| child.key().into_inner());
+            let contains = |bibliography: &Packed<Self>| {
+                key.map_or(true, |key| bibliography.bibliography().has(key))
+            };
+
+            let index = bibliographies
+                .iter()
+                .position(|(j, bibliography, _)| *j > i && contains(bibliography))
+                .or_else(|| {
+                    bibliographies
+                        .iter()
+                        .rposition(|(_, bibliography, _)| contains(bibliography))
+                })
+                .or_else(|| bibliographies.iter().position(|(j, _, _)| *j > i))
+                .unwrap_or(bibliographies.len() - 1);
+
+            bibliographies[index].2.push(elem.clone());
         }
 
-        Ok(elem.to_packed::<Self>().unwrap().clone())
+        Ok(bibliographies
+            .into_iter()
+            .map(|(_, bibliography, groups)| (bibliography, groups))
+            .collect())
     }
 
     /// Whether the bibliography contains the given key.
@@ -197,10 +292,23 @@ impl BibliographyElem {
 }
 
 impl Synthesize for Packed<BibliographyElem> {
-    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let filtered = match self.filter(styles) {
+            Some(filter) => Some(self.bibliography().filter(engine, filter)?),
+            None => None,
+        };
+
         let elem = self.as_mut();
         elem.push_lang(TextElem::lang_in(styles));
         elem.push_region(TextElem::region_in(styles));
+        if let Some(bibliography) = filtered {
+            elem.push_bibliography(bibliography);
+        }
+
         Ok(())
     }
 }
@@ -227,16 +335,20 @@ impl Show for Packed<BibliographyElem> {
 
         let span = self.span();
         let works = Works::generate(engine.world, engine.introspector).at(span)?;
-        let references = works
-            .references
+        let Some(references) = works.references.get(&self.location().unwrap()) else {
+            bail!(span, "failed to format bibliography (this is a bug)");
+        };
+
+        let list = references
+            .list
             .as_ref()
             .ok_or("CSL style is not suitable for bibliographies")
             .at(span)?;
 
         let row_gutter = *BlockElem::below_in(styles).amount();
-        if references.iter().any(|(prefix, _)| prefix.is_some()) {
+        if list.iter().any(|(prefix, _)| prefix.is_some()) {
             let mut cells = vec![];
-            for (prefix, reference) in references {
+            for (prefix, reference) in list {
                 cells.push(
                     Packed::new(GridCell::new(prefix.clone().unwrap_or_default()))
                         .spanned(span),
@@ -254,14 +366,14 @@ impl Show for Packed<BibliographyElem> {
                     .spanned(self.span()),
             );
         } else {
-            for (_, reference) in references {
+            for (_, reference) in list {
                 seq.push(VElem::new(row_gutter).with_weakness(3).pack());
                 seq.push(reference.clone());
             }
         }
 
         let mut content = Content::sequence(seq);
-        if works.hanging_indent {
+        if references.hanging_indent {
             content = content.styled(ParElem::set_hanging_indent(INDENT.into()));
         }
 
@@ -339,7 +451,7 @@ impl Bibliography {
             .iter()
             .map(|path| {
                 let id = span.resolve_path(path).at(span)?;
-                engine.world.file(id).at(span)
+                load(engine.world, id, ResourceKind::Data).at(span)
             })
             .collect::<SourceResult<Vec<Bytes>>>()?;
 
@@ -370,7 +482,10 @@ impl Bibliography {
                     .map_err(|err| eco_format!("failed to parse YAML ({err})"))?,
                 "bib" => hayagriva::io::from_biblatex_str(src)
                     .map_err(|errors| format_biblatex_error(path, src, errors))?,
-                _ => bail!("unknown bibliography format (must be .yml/.yaml or .bib)"),
+                "json" => from_csl_json_str(src)?,
+                _ => bail!(
+                    "unknown bibliography format (must be .yml/.yaml, .bib, or .json)"
+                ),
             };
 
             for entry in library {
@@ -395,6 +510,27 @@ impl Bibliography {
         })
     }
 
+    /// Keep only the entries for which the function returns `true`.
+    fn filter(&self, engine: &mut Engine, filter: &Func) -> SourceResult<Bibliography> {
+        let mut map = IndexMap::new();
+        for (&key, entry) in self.map.iter() {
+            let title: Option<EcoString> =
+                entry.title().map(|title| title.value.to_str().into());
+            let work = dict! {
+                "key" => entry.key(),
+                "type" => eco_format!("{:?}", entry.entry_type()).to_lowercase(),
+                "title" => title,
+            };
+
+            if filter.call(engine, [work])?.cast::<bool>().at(filter.span())? {
+                map.insert(key, entry.clone());
+            }
+        }
+
+        let hash = crate::util::hash128(&(self.hash, map.keys().collect::<Vec<_>>()));
+        Ok(Bibliography { map: Arc::new(map), hash })
+    }
+
     fn has(&self, key: impl Into<PicoStr>) -> bool {
         self.map.contains_key(&key.into())
     }
@@ -430,6 +566,153 @@ fn format_biblatex_error(path: &str, src: &str, errors: Vec<BibLaTeXError>) -> E
     eco_format!("failed to parse BibLaTeX file ({path}:{line}: {msg})")
 }
 
+/// Load a bibliography from CSL-JSON, the data format of CSL processors and
+/// reference managers like Zotero.
+///
+/// The items are translated into the Hayagriva format, which can then be
+/// loaded as usual.
+fn from_csl_json_str(src: &str) -> StrResult<hayagriva::Library> {
+    use serde_json::{json, Map, Value as Json};
+
+    let items: Vec<Map<String, Json>> = serde_json::from_str(src)
+        .map_err(|err| eco_format!("failed to parse CSL-JSON ({err})"))?;
+
+    // Convert a CSL-JSON value that may be a number or a string to a string.
+    let text = |value: &Json| match value {
+        Json::String(s) => Some(Json::String(s.clone())),
+        Json::Number(n) => Some(Json::String(n.to_string())),
+        _ => None,
+    };
+
+    // Convert CSL-JSON names to Hayagriva's `Family, Given` format.
+    let names = |value: &Json| {
+        let names = value.as_array()?.iter().filter_map(|name| {
+            if let Some(literal) = name.get("literal").and_then(Json::as_str) {
+                return Some(Json::String(literal.into()));
+            }
+            let family = name.get("family").and_then(Json::as_str)?;
+            Some(Json::String(match name.get("given").and_then(Json::as_str) {
+                Some(given) => format!("{family}, {given}"),
+                None => family.into(),
+            }))
+        });
+        Some(Json::Array(names.collect()))
+    };
+
+    // Convert a CSL-JSON date to an ISO 8601 date.
+    let date = |value: &Json| {
+        let parts = value.get("date-parts").and_then(|parts| parts.get(0));
+        let Some(parts) = parts.and_then(Json::as_array) else {
+            return value.get("raw").or_else(|| value.get("literal")).cloned();
+        };
+        let parts: Vec<i64> = parts
+            .iter()
+            .filter_map(|part| part.as_i64().or_else(|| part.as_str()?.parse().ok()))
+            .collect();
+        Some(match parts.as_slice() {
+            [] => return None,
+            [year] => json!(year),
+            [year, month] => json!(format!("{year:04}-{month:02}")),
+            [year, month, day, ..] => json!(format!("{year:04}-{month:02}-{day:02}")),
+        })
+    };
+
+    let mut library = Map::new();
+    for item in items {
+        let Some(Json::String(key)) = item.get("id").and_then(text) else {
+            bail!("failed to parse CSL-JSON (item without `id`)");
+        };
+
+        let kind = item.get("type").and_then(Json::as_str).unwrap_or_default();
+        let (kind, parent_kind) = match kind {
+            "article-journal" | "article-magazine" => ("article", Some("periodical")),
+            "article-newspaper" => ("article", Some("newspaper")),
+            "paper-conference" => ("article", Some("proceedings")),
+            "chapter" => ("chapter", Some("book")),
+            "entry" | "entry-dictionary" | "entry-encyclopedia" => {
+                ("entry", Some("reference"))
+            }
+            "post-weblog" => ("post", Some("blog")),
+            "article" | "book" | "manuscript" | "patent" | "post" | "report"
+            | "thesis" => (kind, None),
+            "legal_case" => ("case", None),
+            "legislation" | "bill" => ("legislation", None),
+            "motion_picture" | "broadcast" => ("video", None),
+            "song" => ("audio", None),
+            "webpage" => ("web", None),
+            _ => ("misc", None),
+        };
+
+        let mut entry = Map::new();
+        let mut parent = Map::new();
+        entry.insert("type".into(), kind.into());
+
+        // Fields that describe the container of a work go to its parent, if
+        // it has one.
+        let mut insert = |field: &str, value: Json, of_container: bool| {
+            if of_container && parent_kind.is_some() {
+                parent.insert(field.into(), value);
+            } else {
+                entry.insert(field.into(), value);
+            }
+        };
+
+        for (csl, field, of_container) in [
+            ("title", "title", false),
+            ("volume", "volume", true),
+            ("issue", "issue", true),
+            ("edition", "edition", false),
+            ("page", "page-range", false),
+            ("publisher", "publisher", true),
+            ("publisher-place", "location", true),
+            ("URL", "url", false),
+            ("language", "language", false),
+            ("note", "note", false),
+            ("genre", "genre", false),
+        ] {
+            if let Some(value) = item.get(csl).and_then(text) {
+                insert(field, value, of_container);
+            }
+        }
+
+        for (csl, field, of_container) in
+            [("author", "author", false), ("editor", "editor", true)]
+        {
+            if let Some(value) = item.get(csl).and_then(names) {
+                insert(field, value, of_container);
+            }
+        }
+
+        if let Some(value) = item.get("issued").and_then(date) {
+            insert("date", value, false);
+        }
+
+        let mut serial = Map::new();
+        for (csl, field) in [("DOI", "doi"), ("ISBN", "isbn"), ("ISSN", "issn")] {
+            if let Some(value) = item.get(csl).and_then(text) {
+                serial.insert(field.into(), value);
+            }
+        }
+        if !serial.is_empty() {
+            entry.insert("serial-number".into(), Json::Object(serial));
+        }
+
+        if let Some(parent_kind) = parent_kind {
+            parent.insert("type".into(), parent_kind.into());
+            if let Some(title) = item.get("container-title").and_then(text) {
+                parent.insert("title".into(), title);
+            }
+            entry.insert("parent".into(), Json::Object(parent));
+        }
+
+        library.insert(key, Json::Object(entry));
+    }
+
+    // JSON is valid YAML, so the Hayagriva YAML loader can read the result.
+    hayagriva::io::from_yaml_str(&Json::Object(library).to_string())
+        .map_err(|err| eco_format!("failed to parse CSL-JSON ({err})"))
+}
+
 /// A loaded CSL style.
 #[ty(cast)]
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -479,7 +762,8 @@ impl CslStyle {
 
         if ext == "csl" {
             let id = span.resolve_path(string)?;
-            let data = engine.world.file(id)?;
+            let data =
+                load(engine.world, id, ResourceKind::Data).map_err(|err| err.message)?;
             CslStyle::from_data(&data)
         } else {
             CslStyle::from_name(string)
@@ -575,34 +859,48 @@ impl Repr for CslStyle {
 pub(super) struct Works {
     /// Maps from the location of a citation group to its rendered content.
     pub citations: HashMap<Location, SourceResult<Content>>,
+    /// Maps from the location of a bibliography to its references.
+    pub references: HashMap<Location, References>,
+}
+
+/// The formatted references of one bibliography.
+pub(super) struct References {
     /// Lists all references in the bibliography, with optional prefix, or
     /// `None` if the citation style can't be used for bibliographies.
-    pub references: Option<Vec<(Option<Content>, Content)>>,
+    pub list: Option<Vec<(Option<Content>, Content)>>,
     /// Whether the bibliography should have hanging indent.
     pub hanging_indent: bool,
 }
 
 impl Works {
-    /// Generate all citations and the whole bibliography.
+    /// Generate all citations and all bibliographies.
     #[comemo::memoize]
     pub fn generate(
         world: Tracked<dyn World + '_>,
         introspector: Tracked<Introspector>,
     ) -> StrResult<Arc<Works>> {
-        let mut generator = Generator::new(world, introspector)?;
-        let rendered = generator.drive();
-        let works = generator.display(&rendered)?;
+        let mut works = Works {
+            citations: HashMap::new(),
+            references: HashMap::new(),
+        };
+
+        for (bibliography, groups) in BibliographyElem::find_all(introspector)? {
+            let mut generator = Generator::new(world, bibliography, groups);
+            let rendered = generator.drive();
+            generator.display(&rendered, &mut works);
+        }
+
         Ok(Arc::new(works))
     }
 }
 
-/// Context for generating the bibliography.
+/// Context for generating a bibliography.
 struct Generator<'a> {
     /// The world that is used to evaluate mathematical material in citations.
     world: Tracked<'a, dyn World + 'a>,
-    /// The document's bibliography.
+    /// The bibliography to generate.
     bibliography: Packed<BibliographyElem>,
-    /// The document's citation groups.
+    /// The citation groups that belong to the bibliography.
     groups: EcoVec<Prehashed<Content>>,
     /// Details about each group that are accumulated while driving hayagriva's
     /// bibliography driver and needed when processing hayagriva's output.
@@ -619,6 +917,8 @@ struct GroupInfo {
     location: Location,
     /// The group's span.
     span: Span,
+    /// The content to put before the group.
+    prefix: Option<Content>,
     /// Whether the group should be displayed in a footnote.
     footnote: bool,
     /// Details about the groups citations.
@@ -639,18 +939,17 @@ impl<'a> Generator<'a> {
     /// Create a new generator.
     fn new(
         world: Tracked<'a, dyn World + 'a>,
-        introspector: Tracked<Introspector>,
-    ) -> StrResult<Self> {
-        let bibliography = BibliographyElem::find(introspector)?;
-        let groups = introspector.query(&CiteGroup::elem().select());
+        bibliography: Packed<BibliographyElem>,
+        groups: EcoVec<Prehashed<Content>>,
+    ) -> Self {
         let infos = Vec::with_capacity(groups.len());
-        Ok(Self {
+        Self {
             world,
             bibliography,
             groups,
             infos,
             failures: HashMap::new(),
-        })
+        }
     }
 
     /// Drives hayagriva's citation driver.
@@ -690,12 +989,24 @@ impl<'a> Generator<'a> {
                 };
 
                 let supplement = child.supplement(StyleChain::default());
-                let locator = supplement.as_ref().map(|_| {
-                    SpecificLocator(
+                let locator = match (child.locator(StyleChain::default()), &supplement) {
+                    (Some(_), Some(_)) => {
+                        errors.push(error!(
+                            child.span(),
+                            "citation cannot have both a supplement and a locator"
+                        ));
+                        continue;
+                    }
+                    (Some(locator), None) => Some(SpecificLocator(
+                        locator_kind(locator.kind),
+                        hayagriva::LocatorPayload::Str(&locator.value),
+                    )),
+                    (None, Some(_)) => Some(SpecificLocator(
                         citationberg::taxonomy::Locator::Custom,
                         hayagriva::LocatorPayload::Transparent,
-                    )
-                });
+                    )),
+                    (None, None) => None,
+                };
 
                 let mut hidden = false;
                 let special_form = match child.form(StyleChain::default()) {
@@ -727,6 +1038,7 @@ impl<'a> Generator<'a> {
 
             self.infos.push(GroupInfo {
                 location,
+                prefix: first.prefix(StyleChain::default()),
                 subinfos,
                 span: first.span(),
                 footnote: normal
@@ -771,13 +1083,18 @@ impl<'a> Generator<'a> {
         })
     }
 
-    /// Displays hayagriva's output as content for the citations and references.
-    fn display(&mut self, rendered: &hayagriva::Rendered) -> StrResult<Works> {
+    /// Displays hayagriva's output as content for the citations and references
+    /// and adds it to the works.
+    fn display(&mut self, rendered: &hayagriva::Rendered, works: &mut Works) {
         let citations = self.display_citations(rendered);
-        let references = self.display_references(rendered);
+        let list = self.display_references(rendered);
         let hanging_indent =
             rendered.bibliography.as_ref().map_or(false, |b| b.hanging_indent);
-        Ok(Works { citations, references, hanging_indent })
+        works.citations.extend(citations);
+        works.references.insert(
+            self.bibliography.location().unwrap(),
+            References { list, hanging_indent },
+        );
     }
 
     /// Display the citation groups.
@@ -813,6 +1130,14 @@ impl<'a> Generator<'a> {
                 let mut content =
                     renderer.display_elem_children(&citation.citation, &mut None);
 
+                if let Some(prefix) = &info.prefix {
+                    content = Content::sequence([
+                        prefix.clone(),
+                        TextElem::packed(' '),
+                        content,
+                    ]);
+                }
+
                 if info.footnote {
                     content = FootnoteElem::with_content(content).pack();
                 }
@@ -1056,6 +1381,26 @@ fn apply_formatting(mut content: Content, format: &hayagriva::Formatting) -> Con
     content
 }
 
+/// The CSL locator for a kind of cited part.
+fn locator_kind(kind: LocatorKind) -> citationberg::taxonomy::Locator {
+    use citationberg::taxonomy::Locator;
+    match kind {
+        LocatorKind::Page => Locator::Page,
+        LocatorKind::Chapter => Locator::Chapter,
+        LocatorKind::Section => Locator::Section,
+        LocatorKind::Volume => Locator::Volume,
+        LocatorKind::Figure => Locator::Figure,
+        LocatorKind::Line => Locator::Line,
+        LocatorKind::Paragraph => Locator::Paragraph,
+        LocatorKind::Note => Locator::Note,
+        LocatorKind::Column => Locator::Column,
+        LocatorKind::Book => Locator::Book,
+        LocatorKind::Part => Locator::Part,
+        LocatorKind::Verse => Locator::Verse,
+        LocatorKind::Table => Locator::Table,
+    }
+}
+
 /// Create a locale code from language and optionally region.
 fn locale(lang: Lang, region: Option<Region>) -> citationberg::LocaleCode {
     let mut value = String::with_capacity(5);
@@ -1,7 +1,10 @@
-use crate::diag::{bail, At, SourceResult};
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Cast, Content, Label, Packed, Show, Smart, StyleChain, Synthesize,
+    cast, elem, Cast, Content, IntoValue, Label, Packed, Show, Smart, StyleChain,
+    Synthesize,
 };
 use crate::introspection::Locatable;
 use crate::model::bibliography::Works;
@@ -40,6 +43,13 @@ use crate::text::{Lang, Region, TextElem};
 /// This function indirectly has dedicated syntax. [References]($ref) can be
 /// used to cite works from the bibliography. The label then corresponds to the
 /// citation key.
+///
+/// # Grouping
+/// Adjacent citations, like `[@arrgh @netwok]`, are merged into a single
+/// group. The citation style decides how such a group is displayed. Numeric
+/// styles like `{"ieee"}`, for instance, collapse consecutive numbers into a
+/// range like "[1]–[3]", while author-date styles list the works separated by
+/// semicolons within one pair of parentheses.
 #[elem(Synthesize)]
 pub struct CiteElem {
     /// The citation key that identifies the entry in the bibliography that
@@ -67,6 +77,40 @@ pub struct CiteElem {
     /// ```
     pub supplement: Option<Content>,
 
+    /// The part of the work that is cited, like a page or a chapter.
+    ///
+    /// Unlike a [supplement]($cite.supplement), which is displayed as is, a
+    /// locator is formatted by the citation style, which for example decides
+    /// whether a page is abbreviated as "p." or "page". The locator is given as
+    /// a string that starts with the kind of locator, followed by the number:
+    /// `{"p. 42"}`, `{"pp. 7-9"}`, `{"ch. 3"}`, `{"sec. 2.1"}`, `{"§ 4"}`,
+    /// `{"vol. 2"}`, `{"fig. 5"}`, `{"l. 12"}`, `{"para. 3"}`, `{"n. 8"}`,
+    /// `{"col. 2"}`, `{"bk. 1"}`, `{"pt. 2"}`, `{"v. 16"}`, or `{"tab. 3"}`.
+    /// The kinds can also be written out, as in `{"page 42"}`.
+    ///
+    /// A citation can't have both a supplement and a locator.
+    ///
+    /// ```example
+    /// #cite(<netwok>, locator: "pp. 7-9")
+    /// >>> #set text(0pt)
+    /// >>> #bibliography("works.bib", style: "apa")
+    /// ```
+    #[borrowed]
+    pub locator: Option<CiteLocator>,
+
+    /// Content to put before the citation, like "see" or "compare".
+    ///
+    /// When adjacent citations are grouped, the prefix of the first one is put
+    /// before the whole group. In note styles, the prefix becomes part of the
+    /// footnote.
+    ///
+    /// ```example
+    /// #cite(<arrgh>, prefix: [see])
+    ///
+    /// #bibliography("works.bib")
+    /// ```
+    pub prefix: Option<Content>,
+
     /// The kind of citation to produce. Different forms are useful in different
     /// scenarios: A normal citation is useful as a source at the end of a
     /// sentence, while a "prose" citation is more suitable for inclusion in the
@@ -134,10 +178,100 @@ pub enum CitationForm {
     Full,
     /// Shows only the cited work's author(s).
     Author,
-    /// Shows only the cited work's year.
+    /// Shows only the cited work's year. This is useful to suppress the
+    /// author when they are already mentioned in the text.
     Year,
 }
 
+/// Identifies the part of a work that is cited.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CiteLocator {
+    /// What kind of part is cited.
+    pub kind: LocatorKind,
+    /// The number or range of the part, like `42` or `7-9`.
+    pub value: EcoString,
+}
+
+impl CiteLocator {
+    /// Parse a locator like `p. 42`.
+    fn parse(text: &str) -> StrResult<Self> {
+        let text = text.trim();
+        let (label, value) = match text.strip_prefix('§') {
+            Some(rest) => ("§", rest.trim_start_matches('§')),
+            None => text.split_once(char::is_whitespace).unwrap_or((text, "")),
+        };
+
+        let kind = match label.to_lowercase().as_str() {
+            "p." | "pp." | "page" | "pages" => LocatorKind::Page,
+            "ch." | "chap." | "chapter" | "chapters" => LocatorKind::Chapter,
+            "§" | "sec." | "section" | "sections" => LocatorKind::Section,
+            "vol." | "vols." | "volume" | "volumes" => LocatorKind::Volume,
+            "fig." | "figs." | "figure" | "figures" => LocatorKind::Figure,
+            "l." | "ll." | "line" | "lines" => LocatorKind::Line,
+            "¶" | "para." | "paragraph" | "paragraphs" => LocatorKind::Paragraph,
+            "n." | "nn." | "note" | "notes" => LocatorKind::Note,
+            "col." | "cols." | "column" | "columns" => LocatorKind::Column,
+            "bk." | "bks." | "book" | "books" => LocatorKind::Book,
+            "pt." | "pts." | "part" | "parts" => LocatorKind::Part,
+            "v." | "vv." | "verse" | "verses" => LocatorKind::Verse,
+            "tab." | "tabs." | "table" | "tables" => LocatorKind::Table,
+            _ => bail!("unknown kind of locator `{label}` (try `p.`, `ch.`, or `sec.`)"),
+        };
+
+        let value = value.trim();
+        if value.is_empty() {
+            bail!("locator is missing a number");
+        }
+
+        Ok(Self { kind, value: value.into() })
+    }
+}
+
+cast! {
+    CiteLocator,
+    self => eco_format!("{} {}", self.kind.abbreviation(), self.value).into_value(),
+    v: EcoString => Self::parse(&v)?,
+}
+
+/// The kinds of parts of a work that can be cited.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LocatorKind {
+    Page,
+    Chapter,
+    Section,
+    Volume,
+    Figure,
+    Line,
+    Paragraph,
+    Note,
+    Column,
+    Book,
+    Part,
+    Verse,
+    Table,
+}
+
+impl LocatorKind {
+    /// The abbreviation with which the kind is written in a locator.
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Page => "p.",
+            Self::Chapter => "ch.",
+            Self::Section => "§",
+            Self::Volume => "vol.",
+            Self::Figure => "fig.",
+            Self::Line => "l.",
+            Self::Paragraph => "para.",
+            Self::Note => "n.",
+            Self::Column => "col.",
+            Self::Book => "bk.",
+            Self::Part => "pt.",
+            Self::Verse => "v.",
+            Self::Table => "tab.",
+        }
+    }
+}
+
 /// A group of citations.
 ///
 /// This is automatically created from adjacent citations during show rule
@@ -1,13 +1,25 @@
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use comemo::TrackedMut;
 use ecow::EcoString;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, Array, Construct, Content, Datetime, Packed, Smart, StyleChain,
-    Value,
+    cast, elem, Args, Array, Bytes, CastInfo, Construct, Content, Datetime, Dict,
+    IntoValue, Packed, Reflect, Smart, StyleChain, Value,
+};
+use crate::introspection::{Introspector, ManualPageCounter, Meta};
+use crate::layout::{
+    Abs, Frame, FrameItem, LayoutRoot, Page, PageElem, Point, Transform,
 };
-use crate::introspection::{Introspector, ManualPageCounter};
-use crate::layout::{LayoutRoot, Page, PageElem};
+use crate::loading::{load, ResourceKind};
+use crate::model::HeadingElem;
+use crate::syntax::Spanned;
+use crate::text::{Font, Glyph, TextItem};
+use crate::util::NonZeroExt;
+use crate::visualize::{parse_icc, IccProfile, Image, ImageKind};
 
 /// The root element of a document and its metadata.
 ///
@@ -35,6 +47,14 @@ pub struct DocumentElem {
     #[ghost]
     pub title: Option<Content>,
 
+    /// The document's subject. This is a short description of what the
+    /// document is about and is shown in the document properties of most PDF
+    /// viewers.
+    ///
+    /// Like the title, this is converted to plain text.
+    #[ghost]
+    pub subject: Option<Content>,
+
     /// The document's authors.
     #[ghost]
     pub author: Author,
@@ -54,6 +74,76 @@ pub struct DocumentElem {
     #[ghost]
     pub date: Smart<Option<Datetime>>,
 
+    /// Custom metadata entries, given as a dictionary from keys to strings.
+    ///
+    /// Each entry is written into the PDF's document information dictionary
+    /// under its key. The keys of the standard entries (like `Title` or
+    /// `Author`) are reserved and cannot be overridden this way.
+    ///
+    /// ```example
+    /// #set document(custom: (
+    ///   Department: "Physics",
+    ///   Revision: "3",
+    /// ))
+    /// ```
+    #[ghost]
+    pub custom: CustomMetadata,
+
+    /// Path to an ICC profile describing the intended output device.
+    ///
+    /// The profile is embedded into the PDF as its output intent, which
+    /// print workflows use to interpret the document's colors. If the
+    /// profile describes a CMYK space, [CMYK colors]($color.cmyk) are
+    /// written in terms of this profile instead of as device colors.
+    ///
+    /// ```example
+    /// #set document(icc: "sRGB.icc")
+    /// ```
+    #[ghost]
+    #[parse(
+        let (icc_path, icc_data) = parse_icc(engine, args)?;
+        icc_path.map(Some)
+    )]
+    #[borrowed]
+    pub icc: Option<EcoString>,
+
+    /// The raw data of the ICC profile.
+    #[ghost]
+    #[internal]
+    #[parse(icc_data.map(Some))]
+    #[borrowed]
+    pub icc_data: Option<Bytes>,
+
+    /// Files to embed into the PDF as attachments.
+    ///
+    /// This can be a path to a file, a dictionary with the keys `path`, `mime`
+    /// (the file's MIME type), and `description`, or an array of these. The
+    /// attachments are listed under their file names in PDF viewers, which is
+    /// useful for shipping the source data or machine-readable versions (like
+    /// ZUGFeRD invoices) alongside a document.
+    ///
+    /// ```example
+    /// #set document(embed-file: (
+    ///   "data.csv",
+    ///   (
+    ///     path: "example.xml",
+    ///     mime: "text/xml",
+    ///     description: "Raw data",
+    ///   ),
+    /// ))
+    /// ```
+    #[ghost]
+    #[parse(EmbeddedFiles::parse(engine, args)?)]
+    pub embed_file: EmbeddedFiles,
+
+    /// Whether PDF viewers should open the document in full-screen mode.
+    ///
+    /// This is useful for presentations, together with the
+    /// [transitions]($page.transition) of the pages.
+    #[ghost]
+    #[default(false)]
+    pub fullscreen: bool,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -73,17 +163,16 @@ impl LayoutRoot for Packed<DocumentElem> {
         engine: &mut Engine,
         styles: StyleChain,
     ) -> SourceResult<Document> {
-        let mut pages = Vec::with_capacity(self.children().len());
-        let mut page_counter = ManualPageCounter::new();
-
+        let mut runs = Vec::with_capacity(self.children().len());
         let children = self.children();
         let mut iter = children.iter().peekable();
 
         while let Some(mut child) = iter.next() {
-            let outer = styles;
-            let mut styles = styles;
+            // The run's styles are stored beyond this iteration, so they must
+            // be chained from the document's styles and not a loop-local copy.
+            let mut run_styles = styles;
             if let Some((elem, local)) = child.to_styled() {
-                styles = outer.chain(local);
+                run_styles = styles.chain(local);
                 child = elem;
             }
 
@@ -95,19 +184,60 @@ impl LayoutRoot for Packed<DocumentElem> {
                         .to_packed::<PageElem>()?
                         .clear_to()?
                 });
-                let run = page.layout(engine, styles, &mut page_counter, extend_to)?;
-                pages.extend(run);
+                runs.push((page, run_styles, extend_to));
             } else {
                 bail!(child.span(), "unexpected document child");
             }
         }
 
+        // The bodies of the page runs don't depend on each other, so we lay
+        // them out in parallel. Each one starts with a fresh locator though,
+        // so if one of them contains an element that an earlier run also
+        // contains, it doesn't get the locations it would have gotten when
+        // laid out in order. Such runs (and failed ones, to get exactly the
+        // errors of sequential layout) are laid out again, in order.
+        let bodies = if runs.len() > 1 {
+            engine.parallelize(&runs, |engine, (page, styles, _)| {
+                page.layout(engine, *styles)
+            })
+        } else {
+            vec![]
+        };
+
+        let mut pages = Vec::with_capacity(runs.len());
+        let mut page_counter = ManualPageCounter::new();
+        let mut bodies = bodies.into_iter();
+
+        for (page, styles, extend_to) in runs {
+            let frames = match bodies.next() {
+                Some((Ok(frames), tracer)) if engine.locator.is_disjoint(&frames) => {
+                    tracer.replay(TrackedMut::reborrow_mut(&mut engine.tracer));
+                    engine.locator.visit_frames(&frames);
+                    frames
+                }
+                _ => page.layout(engine, styles)?,
+            };
+
+            let run =
+                page.finalize(engine, styles, frames, &mut page_counter, extend_to)?;
+            pages.extend(run);
+        }
+
         Ok(Document {
             pages,
             title: DocumentElem::title_in(styles).map(|content| content.plain_text()),
+            subject: DocumentElem::subject_in(styles).map(|content| content.plain_text()),
             author: DocumentElem::author_in(styles).0,
             keywords: DocumentElem::keywords_in(styles).0,
             date: DocumentElem::date_in(styles),
+            custom: DocumentElem::custom_in(styles).0,
+            files: DocumentElem::embed_file_in(styles).0,
+            fullscreen: DocumentElem::fullscreen_in(styles),
+            icc: DocumentElem::icc_data_in(styles)
+                .clone()
+                .map(IccProfile::new)
+                .transpose()
+                .at(self.span())?,
             introspector: Introspector::default(),
         })
     }
@@ -135,6 +265,179 @@ cast! {
     v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
 
+/// Custom key-value metadata entries.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct CustomMetadata(Vec<(EcoString, EcoString)>);
+
+impl CustomMetadata {
+    /// Keys of the document information dictionary that are written by the
+    /// exporter itself.
+    const RESERVED: &'static [&'static str] = &[
+        "Title",
+        "Author",
+        "Subject",
+        "Keywords",
+        "Creator",
+        "Producer",
+        "CreationDate",
+        "ModDate",
+        "Trapped",
+    ];
+}
+
+cast! {
+    CustomMetadata,
+    self => self.0
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            if k.is_empty() {
+                bail!("metadata key must not be empty");
+            }
+            if Self::RESERVED.contains(&k.as_str()) {
+                bail!("metadata key `{}` is reserved", k.as_str());
+            }
+            Ok((k.into(), v.cast::<EcoString>()?))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+/// Files embedded into the document.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct EmbeddedFiles(Vec<EmbeddedFile>);
+
+impl EmbeddedFiles {
+    /// Parse the `embed-file` argument and load the files.
+    fn parse(engine: &mut Engine, args: &mut Args) -> SourceResult<Option<Self>> {
+        let Some(Spanned { v: specs, span }) =
+            args.named::<Spanned<EmbedSpecs>>("embed-file")?
+        else {
+            return Ok(None);
+        };
+
+        let mut files: Vec<EmbeddedFile> = vec![];
+        for spec in specs.0 {
+            let id = span.resolve_path(&spec.path).at(span)?;
+            let data = load(engine.world, id, ResourceKind::Data).at(span)?;
+            let name: EcoString = id
+                .vpath()
+                .as_rootless_path()
+                .file_name()
+                .map_or_else(|| spec.path.clone(), |name| name.to_string_lossy().into());
+
+            if files.iter().any(|file| file.name == name) {
+                bail!(span, "duplicate embedded file name `{name}`");
+            }
+
+            files.push(EmbeddedFile {
+                path: spec.path,
+                name,
+                data,
+                mime: spec.mime,
+                description: spec.description,
+            });
+        }
+
+        Ok(Some(Self(files)))
+    }
+}
+
+// Like for `CslStyle`, this describes what the `embed-file` argument expects
+// through manual parsing rather than what `FromValue` does.
+impl Reflect for EmbeddedFiles {
+    fn input() -> CastInfo {
+        EmbedSpecs::input()
+    }
+
+    fn output() -> CastInfo {
+        Array::output()
+    }
+
+    fn castable(_: &Value) -> bool {
+        false
+    }
+}
+
+impl IntoValue for EmbeddedFiles {
+    fn into_value(self) -> Value {
+        self.0
+            .into_iter()
+            .map(|file| {
+                EmbedSpec {
+                    path: file.path,
+                    mime: file.mime,
+                    description: file.description,
+                }
+                .into_value()
+            })
+            .collect::<Array>()
+            .into_value()
+    }
+}
+
+/// A file embedded into the document.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct EmbeddedFile {
+    /// The path the file was loaded from.
+    pub path: EcoString,
+    /// The file name under which the file is embedded.
+    pub name: EcoString,
+    /// The file's contents.
+    pub data: Bytes,
+    /// The file's MIME type.
+    pub mime: Option<EcoString>,
+    /// A description of the file.
+    pub description: Option<EcoString>,
+}
+
+/// One or multiple embedded file specifications.
+struct EmbedSpecs(Vec<EmbedSpec>);
+
+cast! {
+    EmbedSpecs,
+    v: EmbedSpec => Self(vec![v]),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
+}
+
+/// The specification of an embedded file before loading.
+struct EmbedSpec {
+    path: EcoString,
+    mime: Option<EcoString>,
+    description: Option<EcoString>,
+}
+
+cast! {
+    EmbedSpec,
+    self => {
+        let mut dict = Dict::new();
+        dict.insert("path".into(), self.path.into_value());
+        if let Some(mime) = self.mime {
+            dict.insert("mime".into(), mime.into_value());
+        }
+        if let Some(description) = self.description {
+            dict.insert("description".into(), description.into_value());
+        }
+        dict.into_value()
+    },
+    path: EcoString => Self { path, mime: None, description: None },
+    mut dict: Dict => {
+        let path = dict.take("path")?.cast()?;
+        let mime: Option<EcoString> = dict.take("mime").ok().map(Value::cast).transpose()?;
+        let description = dict.take("description").ok().map(Value::cast).transpose()?;
+        dict.finish(&["path", "mime", "description"])?;
+        if let Some(mime) = &mime {
+            if !mime.contains('/') {
+                bail!("invalid MIME type `{mime}`");
+            }
+        }
+        Self { path, mime, description }
+    },
+}
+
 /// A finished document with metadata and page frames.
 #[derive(Debug, Default, Clone)]
 pub struct Document {
@@ -142,19 +445,362 @@ pub struct Document {
     pub pages: Vec<Page>,
     /// The document's title.
     pub title: Option<EcoString>,
+    /// The document's subject.
+    pub subject: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
     /// The document's keywords.
     pub keywords: Vec<EcoString>,
     /// The document's creation date.
     pub date: Smart<Option<Datetime>>,
+    /// Custom metadata entries for the document information dictionary.
+    pub custom: Vec<(EcoString, EcoString)>,
+    /// Files to embed as attachments.
+    pub files: Vec<EmbeddedFile>,
+    /// Whether the document should be opened in full-screen mode.
+    pub fullscreen: bool,
+    /// The ICC profile of the intended output device.
+    pub icc: Option<IccProfile>,
     /// Provides the ability to execute queries on the document.
     pub introspector: Introspector,
 }
 
+impl Document {
+    /// Extract the document's text as a sequence of blocks.
+    ///
+    /// This walks the laid-out frames, so the result reflects the text as it
+    /// was rendered (including the effects of show rules). Headings are
+    /// detected exactly, while paragraph boundaries are inferred from
+    /// vertical gaps that are notably larger than the line spacing at the
+    /// current font size.
+    pub fn extract(&self) -> Vec<TextBlock> {
+        let mut extractor = Extractor {
+            blocks: vec![],
+            page: NonZeroUsize::ONE,
+            last: None,
+        };
+        for (i, page) in self.pages.iter().enumerate() {
+            extractor.page = NonZeroUsize::new(1 + i).unwrap();
+            extractor.last = None;
+            extractor.walk(&page.frame, Transform::identity(), TextBlockKind::Paragraph);
+        }
+        extractor.blocks
+    }
+
+    /// The document's text, with blocks separated by blank lines.
+    pub fn plain_text(&self) -> EcoString {
+        let mut text = EcoString::new();
+        for (i, block) in self.extract().into_iter().enumerate() {
+            if i > 0 {
+                text.push_str("\n\n");
+            }
+            text.push_str(&block.text);
+        }
+        text
+    }
+
+    /// Estimate how much memory the document's frames and the resources
+    /// they use occupy.
+    ///
+    /// Fonts and images are counted once, no matter how often they are used.
+    /// The caches of memoized functions are not included, as their size
+    /// cannot be determined. They can be cleared with `comemo::evict`.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut fonts = HashSet::new();
+        let mut images = HashSet::new();
+        let mut usage = MemoryUsage::default();
+        for page in &self.pages {
+            usage.measure(&page.frame, &mut fonts, &mut images);
+        }
+        usage
+    }
+}
+
+/// How much memory a [`Document`] occupies, in bytes, by what it is used for.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryUsage {
+    /// The data of the fonts used in the document.
+    pub fonts: usize,
+    /// The data of the images in the document, including decoded pixels of
+    /// raster images.
+    pub images: usize,
+    /// The frames of the document's pages with their items, like text runs
+    /// and shapes.
+    pub frames: usize,
+    /// How many distinct fonts are used.
+    pub font_count: usize,
+    /// How many distinct images are used.
+    pub image_count: usize,
+}
+
+impl MemoryUsage {
+    /// The total number of bytes.
+    pub fn total(&self) -> usize {
+        self.fonts + self.images + self.frames
+    }
+
+    /// Add the memory a frame occupies.
+    fn measure(
+        &mut self,
+        frame: &Frame,
+        fonts: &mut HashSet<Font>,
+        images: &mut HashSet<Image>,
+    ) {
+        self.frames += std::mem::size_of::<Frame>()
+            + frame.items().len() * std::mem::size_of::<(Point, FrameItem)>();
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => self.measure(&group.frame, fonts, images),
+                FrameItem::Text(text) => {
+                    self.frames += text.text.len()
+                        + text.glyphs.len() * std::mem::size_of::<Glyph>();
+                    if fonts.insert(text.font.clone()) {
+                        self.fonts += text.font.data().len();
+                        self.font_count += 1;
+                    }
+                }
+                FrameItem::Image(image, _, _) => {
+                    if images.insert(image.clone()) {
+                        self.images += image.data().len();
+                        if let ImageKind::Raster(_) = image.kind() {
+                            self.images +=
+                                4 * image.width() as usize * image.height() as usize;
+                        }
+                        self.image_count += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A block of text extracted from a document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextBlock {
+    /// What kind of block this is.
+    pub kind: TextBlockKind,
+    /// The block's text, with lines joined by spaces.
+    pub text: EcoString,
+    /// The page number the block starts on, starting at 1.
+    pub page: NonZeroUsize,
+}
+
+/// The kind of an extracted text block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextBlockKind {
+    /// A paragraph or any other run of text.
+    Paragraph,
+    /// A heading with the given level.
+    Heading(NonZeroUsize),
+}
+
+/// Collects text blocks from frames.
+struct Extractor {
+    /// The finished blocks.
+    blocks: Vec<TextBlock>,
+    /// The current page number.
+    page: NonZeroUsize,
+    /// The baseline and font size of the last text run in the current block.
+    last: Option<(Abs, Abs)>,
+}
+
+impl Extractor {
+    /// Collect the text in a frame.
+    fn walk(&mut self, frame: &Frame, ts: Transform, kind: TextBlockKind) {
+        // The kind and vertical extent of the last heading in this frame. Only
+        // the items within it belong to the heading.
+        let mut heading: Option<(TextBlockKind, Abs, Abs)> = None;
+        for (pos, item) in frame.items() {
+            let kind = match heading {
+                Some((heading, top, bottom)) if pos.y >= top && pos.y <= bottom => {
+                    heading
+                }
+                _ => kind,
+            };
+
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    self.walk(&group.frame, ts, kind);
+                }
+                FrameItem::Text(text) => {
+                    self.push(pos.transform(ts).y, text, kind);
+                }
+                FrameItem::Meta(Meta::Elem(elem), size) => {
+                    if let Some(elem) = elem.to_packed::<HeadingElem>() {
+                        let level = elem.level(StyleChain::default());
+                        heading =
+                            Some((TextBlockKind::Heading(level), pos.y, pos.y + size.y));
+                        self.last = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Add a text run to the current block or start a new one.
+    fn push(&mut self, baseline: Abs, text: &TextItem, kind: TextBlockKind) {
+        let continues = self.blocks.last().map_or(false, |block| block.kind == kind);
+        match self.last {
+            Some((last, _)) if continues && last.approx_eq(baseline) => {}
+            Some((last, size))
+                if continues && (baseline - last).abs() < 1.5 * size.max(text.size) =>
+            {
+                let block = self.blocks.last_mut().unwrap();
+                if !block.text.ends_with(' ') {
+                    block.text.push(' ');
+                }
+            }
+            _ => self.blocks.push(TextBlock {
+                kind,
+                text: EcoString::new(),
+                page: self.page,
+            }),
+        }
+
+        let block = self.blocks.last_mut().unwrap();
+        block.text.push_str(&text.text);
+        self.last = Some((baseline, text.size));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layout::{GroupItem, Size};
+    use crate::text::Lang;
+    use crate::visualize::Color;
+
+    fn extract(frame: Frame) -> Vec<(TextBlockKind, EcoString)> {
+        let page = Page { frame, ..Default::default() };
+        let document = Document { pages: vec![page], ..Default::default() };
+        document
+            .extract()
+            .into_iter()
+            .map(|block| (block.kind, block.text))
+            .collect()
+    }
+
+    fn frame(items: Vec<(f64, FrameItem)>) -> Frame {
+        let mut frame = Frame::hard(Size::splat(Abs::pt(500.0)));
+        for (y, item) in items {
+            frame.push(Point::with_y(Abs::pt(y)), item);
+        }
+        frame
+    }
+
+    fn text(size: f64, text: &str) -> FrameItem {
+        let data = include_bytes!("../../../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        FrameItem::Text(TextItem {
+            font,
+            size: Abs::pt(size),
+            fill: Color::BLACK.into(),
+            stroke: None,
+            lang: Lang::ENGLISH,
+            text: text.into(),
+            glyphs: vec![],
+        })
+    }
+
+    fn heading_meta(level: usize, height: f64) -> FrameItem {
+        let elem = HeadingElem::new(Content::empty())
+            .with_level(NonZeroUsize::new(level).unwrap())
+            .pack();
+        FrameItem::Meta(Meta::Elem(elem), Size::new(Abs::pt(500.0), Abs::pt(height)))
+    }
+
+    fn heading(level: usize) -> TextBlockKind {
+        TextBlockKind::Heading(NonZeroUsize::new(level).unwrap())
+    }
+
+    fn paragraph(text: &str) -> (TextBlockKind, EcoString) {
+        (TextBlockKind::Paragraph, text.into())
+    }
+
+    #[test]
+    fn test_extract_heading_and_paragraph() {
+        assert_eq!(
+            extract(frame(vec![
+                (0.0, heading_meta(1, 20.0)),
+                (15.0, text(14.0, "Intro")),
+                (40.0, text(10.0, "Some")),
+                (52.0, text(10.0, "text.")),
+                (80.0, heading_meta(2, 20.0)),
+                (95.0, text(12.0, "Details")),
+                (120.0, text(10.0, "More text.")),
+            ])),
+            [
+                (heading(1), "Intro".into()),
+                paragraph("Some text."),
+                (heading(2), "Details".into()),
+                paragraph("More text."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_nested_content() {
+        let inner = frame(vec![
+            (0.0, heading_meta(1, 20.0)),
+            (15.0, text(14.0, "Inner")),
+            (40.0, text(10.0, "Text")),
+        ]);
+        assert_eq!(
+            extract(frame(vec![
+                (0.0, heading_meta(1, 20.0)),
+                (15.0, text(14.0, "Hello")),
+                (15.0, text(14.0, " World")),
+                (40.0, FrameItem::Group(GroupItem::new(inner))),
+                (120.0, text(10.0, "After")),
+            ])),
+            [
+                (heading(1), "Hello World".into()),
+                (heading(1), "Inner".into()),
+                paragraph("Text"),
+                paragraph("After"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_paragraph_gap_at_equal_sizes() {
+        // Lines of the same size belong together if their baselines are less
+        // than 1.5 times the font size apart.
+        assert_eq!(
+            extract(frame(vec![
+                (10.0, text(10.0, "One")),
+                (24.5, text(10.0, "line.")),
+                (39.5, text(10.0, "Two.")),
+            ])),
+            [paragraph("One line."), paragraph("Two.")]
+        );
+    }
+
+    #[test]
+    fn test_extract_paragraph_gap_at_mixed_sizes() {
+        // The larger of the two sizes determines the threshold, no matter
+        // which line comes first.
+        assert_eq!(
+            extract(frame(vec![
+                (0.0, text(10.0, "Small")),
+                (29.5, text(20.0, "large")),
+                (59.0, text(10.0, "small.")),
+                (89.0, text(20.0, "Large")),
+                (103.5, text(10.0, "small")),
+                (118.5, text(10.0, "split.")),
+            ])),
+            [
+                paragraph("Small large small."),
+                paragraph("Large small"),
+                paragraph("split.")
+            ]
+        );
+    }
 
     #[test]
     fn test_document_is_send_and_sync() {
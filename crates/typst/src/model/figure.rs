@@ -14,7 +14,8 @@ use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
 use crate::layout::{
-    Alignment, BlockElem, Em, HAlignment, Length, PlaceElem, VAlignment, VElem,
+    Abs, Alignment, Axes, BlockElem, Em, Fragment, Frame, HAlignment, LayoutMultiple,
+    Length, PlaceElem, Point, Regions, Size, VAlignment, VElem,
 };
 use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::syntax::Spanned;
@@ -70,7 +71,9 @@ use crate::visualize::ImageElem;
 /// ```
 ///
 /// See the [block]($block.breakable) documentation for more information about
-/// breakable and non-breakable blocks.
+/// breakable and non-breakable blocks. The caption of a breakable figure can
+/// be repeated where the figure continues with the caption's `continued`
+/// property.
 ///
 /// # Caption customization
 /// You can modify the appearance of the figure's caption with its associated
@@ -237,7 +240,7 @@ impl Synthesize for Packed<FigureElem> {
         let kind = elem.kind(styles).unwrap_or_else(|| {
             elem.body()
                 .query_first(Selector::can::<dyn Figurable>())
-                .map(|elem| FigureKind::Elem(elem.func()))
+                .map(|elem| FigureKind::Elem(elem.elem()))
                 .unwrap_or_else(|| FigureKind::Elem(ImageElem::elem()))
         });
 
@@ -309,11 +312,26 @@ impl Show for Packed<FigureElem> {
 
         // Build the caption, if any.
         if let Some(caption) = self.caption(styles) {
-            let v = VElem::weak(self.gap(styles).into()).pack();
-            realized = if caption.position(styles) == VAlignment::Bottom {
-                realized + v + caption.pack()
+            let position = caption.position(styles);
+            realized = if let Some(continued) = caption.continued(styles) {
+                // Repeat the caption in all regions the figure is split across.
+                let continued = caption.continued_caption(continued, styles);
+                CaptionedElem::new(
+                    realized,
+                    caption.pack(),
+                    continued,
+                    position,
+                    self.gap(styles),
+                )
+                .pack()
+                .spanned(self.span())
             } else {
-                caption.pack() + v + realized
+                let v = VElem::weak(self.gap(styles).into()).pack();
+                if position == VAlignment::Bottom {
+                    realized + v + caption.pack()
+                } else {
+                    caption.pack() + v + realized
+                }
             };
         }
 
@@ -398,7 +416,7 @@ impl Outlinable for Packed<FigureElem> {
             self.numbering(),
         ) {
             let numbers = counter
-                .at(engine, self.location().unwrap())?
+                .at_loc(engine, self.location().unwrap())?
                 .display(engine, numbering)?;
 
             if !supplement.is_empty() {
@@ -414,6 +432,83 @@ impl Outlinable for Packed<FigureElem> {
     }
 }
 
+/// Lays out a figure's body together with its caption, which is repeated as a
+/// continued caption in every region the body is split across.
+#[elem(LayoutMultiple)]
+struct CaptionedElem {
+    /// The figure's body.
+    #[required]
+    body: Content,
+
+    /// The caption for the part of the figure that is not continued.
+    #[required]
+    caption: Content,
+
+    /// The caption for the continued parts.
+    #[required]
+    continued: Content,
+
+    /// Whether the captions go above or below the parts.
+    #[required]
+    position: VAlignment,
+
+    /// The vertical gap between the body and the captions.
+    #[required]
+    gap: Length,
+}
+
+impl LayoutMultiple for Packed<CaptionedElem> {
+    #[typst_macros::time(name = "figure captions", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        // Layout the captions to know how much space to reserve for them.
+        let pod = Regions::one(regions.base(), Axes::new(regions.expand.x, false));
+        let caption = self.caption().layout(engine, styles, pod)?.into_frame();
+        let continued = self.continued().layout(engine, styles, pod)?.into_frame();
+        let gap = self.gap().resolve(styles);
+        let reserved = caption.height().max(continued.height()) + gap;
+
+        // Layout the body into the remaining space.
+        let mut backlog = vec![];
+        let pod = regions.map(&mut backlog, |size| size - Size::with_y(reserved));
+        let mut fragment = self.body().layout(engine, styles, pod)?;
+
+        // The parts start with the first non-empty frame, as the body may not
+        // fit into the first region at all.
+        let top = *self.position() == VAlignment::Top;
+        let first = fragment.iter().position(|frame| !frame.is_empty()).unwrap_or(0);
+        let last = fragment.len() - 1;
+
+        for (i, frame) in fragment.iter_mut().enumerate().skip(first) {
+            let shown = if (top && i == first) || (!top && i == last) {
+                caption.clone()
+            } else {
+                continued.clone()
+            };
+
+            let size = Size::new(
+                frame.width().max(shown.width()),
+                frame.height() + gap + shown.height(),
+            );
+            let body = std::mem::replace(frame, Frame::soft(size));
+            let (body_y, caption_y) = if top {
+                (shown.height() + gap, Abs::zero())
+            } else {
+                (Abs::zero(), body.height() + gap)
+            };
+
+            frame.push_frame(Point::with_y(caption_y), shown);
+            frame.push_frame(Point::with_y(body_y), body);
+        }
+
+        Ok(fragment)
+    }
+}
+
 /// The caption of a figure. This element can be used in set and show rules to
 /// customize the appearance of captions for all figures or figures of a
 /// specific kind.
@@ -485,6 +580,53 @@ pub struct FigureCaption {
     /// ```
     pub separator: Smart<Content>,
 
+    /// The caption to show where a [breakable]($block.breakable) figure
+    /// continues on another page or in another column.
+    ///
+    /// - When set to `{none}`, the caption is only shown once. This is the
+    ///   default.
+    /// - When set to `{auto}`, the continued parts get the figure's supplement
+    ///   and number followed by "(continued)" in the
+    ///   [text language]($text.lang).
+    /// - Custom content replaces the caption's body in the continued parts.
+    ///
+    /// A caption at the top is shown above the first part of the figure and
+    /// the continued caption above all others. A caption at the bottom is
+    /// shown below the last part and the continued caption below all others.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #show figure: set block(breakable: true)
+    /// #show figure.where(kind: table): set figure.caption(
+    ///   position: top,
+    ///   continued: auto,
+    /// )
+    ///
+    /// #figure(
+    ///   table(columns: 4, ..range(28).map(str)),
+    ///   caption: [Numbers],
+    /// )
+    /// ```
+    pub continued: Option<Smart<Content>>,
+
+    /// Whether continued captions repeat the figure's supplement and number.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #show figure: set block(breakable: true)
+    /// #set figure.caption(
+    ///   continued: [_Continued from the previous page._],
+    ///   continued-number: false,
+    /// )
+    ///
+    /// #figure(
+    ///   table(columns: 4, ..range(20).map(str)),
+    ///   caption: [Numbers],
+    /// )
+    /// ```
+    #[default(true)]
+    pub continued_number: bool,
+
     /// The caption's body.
     ///
     /// Can be used alongside `kind`, `supplement`, `counter`, `numbering`, and
@@ -546,6 +688,46 @@ impl FigureCaption {
             ))
         })
     }
+
+    fn local_continued(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::CHINESE => "（续）",
+            Lang::DUTCH => "(vervolg)",
+            Lang::FRENCH => "(suite)",
+            Lang::GERMAN => "(Fortsetzung)",
+            Lang::ITALIAN => "(continua)",
+            Lang::PORTUGUESE => "(continuação)",
+            Lang::RUSSIAN => "(продолжение)",
+            Lang::SPANISH => "(continuación)",
+            Lang::ENGLISH | _ => "(continued)",
+        }
+    }
+
+    /// Build the caption for the continued parts of a figure.
+    fn continued_caption(
+        &self,
+        continued: Smart<Content>,
+        styles: StyleChain,
+    ) -> Content {
+        let mut caption = self.clone();
+        if !self.continued_number(styles) {
+            caption.push_numbering(None);
+        }
+
+        match continued {
+            Smart::Auto => {
+                let text = Self::local_continued(
+                    TextElem::lang_in(styles),
+                    TextElem::region_in(styles),
+                );
+                caption.push_body(TextElem::packed(text));
+                caption.push_separator(Smart::Custom(TextElem::packed(' ')));
+            }
+            Smart::Custom(body) => caption.push_body(body),
+        }
+
+        caption.pack()
+    }
 }
 
 impl Show for Packed<FigureCaption> {
@@ -564,7 +746,8 @@ impl Show for Packed<FigureCaption> {
             self.counter(),
             self.figure_location(),
         ) {
-            let numbers = counter.at(engine, *location)?.display(engine, numbering)?;
+            let numbers =
+                counter.at_loc(engine, *location)?.display(engine, numbering)?;
             if !supplement.is_empty() {
                 supplement += TextElem::packed('\u{a0}');
             }
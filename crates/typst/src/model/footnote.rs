@@ -129,7 +129,7 @@ impl Show for Packed<FootnoteElem> {
         let loc = self.declaration_location(engine).at(self.span())?;
         let numbering = self.numbering(styles);
         let counter = Counter::of(FootnoteElem::elem());
-        let num = counter.at(engine, loc)?.display(engine, numbering)?;
+        let num = counter.at_loc(engine, loc)?.display(engine, numbering)?;
         let sup = SuperElem::new(num).pack().spanned(self.span());
         let loc = loc.variant(1);
         // Add zero-width weak spacing to make the footnote "sticky".
@@ -178,7 +178,7 @@ cast! {
 /// #footnote[It's down here]
 /// has red text!
 /// ```
-#[elem(name = "entry", title = "Footnote Entry", Show, ShowSet)]
+#[elem(name = "entry", title = "Footnote Entry", Locatable, Show, ShowSet)]
 pub struct FootnoteEntry {
     /// The footnote for this entry. It's location can be used to determine
     /// the footnote counter state.
@@ -278,7 +278,7 @@ impl Show for Packed<FootnoteEntry> {
             );
         };
 
-        let num = counter.at(engine, loc)?.display(engine, numbering)?;
+        let num = counter.at_loc(engine, loc)?.display(engine, numbering)?;
         let sup = SuperElem::new(num)
             .pack()
             .spanned(self.span())
@@ -0,0 +1,277 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use smallvec::smallvec;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, Smart, StyleChain};
+use crate::introspection::{Meta, MetaElem};
+use crate::layout::{Abs, BoxElem, Corners, Em, Length, Ratio, Rel, Sides, Sizing};
+use crate::visualize::{Color, Stroke};
+
+/// A fillable text input.
+///
+/// In the exported PDF, this becomes an interactive form field that readers
+/// can type into. Fields are identified by their name, which is also used as
+/// the key when the filled-in form data is extracted. Other export formats
+/// only show the field's outline.
+///
+/// # Example
+/// ```example
+/// Name: #text-field("name") \
+/// Comments: #text-field(
+///   "comments",
+///   multiline: true,
+///   width: 100%,
+/// )
+/// ```
+#[elem(title = "Text Field", Show)]
+pub struct TextFieldElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// The field's initial text.
+    #[default(EcoString::new())]
+    pub value: EcoString,
+
+    /// Whether the field accepts multiple lines of text.
+    #[default(false)]
+    pub multiline: bool,
+
+    /// The maximum number of characters the field accepts.
+    pub max_length: Option<NonZeroUsize>,
+
+    /// The field's width.
+    #[default(Abs::pt(120.0).into())]
+    pub width: Rel<Length>,
+
+    /// The field's height.
+    ///
+    /// If this is `{auto}`, the field is one line high, or four lines if it is
+    /// multiline.
+    pub height: Smart<Rel<Length>>,
+
+    /// The field's position in the tab order.
+    ///
+    /// Fields with a tab index are visited first, in increasing order, before
+    /// the remaining fields of the page in document order.
+    pub tab_index: Option<usize>,
+}
+
+impl Show for Packed<TextFieldElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let multiline = self.multiline(styles);
+        let height = self.height(styles).unwrap_or_else(|| {
+            Length::from(Em::new(if multiline { 4.8 } else { 1.4 })).into()
+        });
+
+        let field = FormField {
+            name: self.name().clone(),
+            kind: FormFieldKind::Text {
+                value: self.value(styles),
+                multiline,
+                max_length: self.max_length(styles),
+            },
+            tab_index: self.tab_index(styles),
+        };
+
+        Ok(field_box(field, self.width(styles), height, false))
+    }
+}
+
+/// A checkbox that can be ticked.
+///
+/// In the exported PDF, this becomes an interactive form field. See the
+/// [text field]($text-field) for details on names.
+///
+/// # Example
+/// ```example
+/// #checkbox("newsletter", checked: true)
+/// Subscribe to the newsletter
+/// ```
+#[elem(Show)]
+pub struct CheckboxElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// Whether the checkbox is initially ticked.
+    #[default(false)]
+    pub checked: bool,
+
+    /// The checkbox's width and height.
+    #[default(Em::new(0.8).into())]
+    pub size: Length,
+
+    /// The field's position in the tab order. See the
+    /// [text field]($text-field.tab-index) for details.
+    pub tab_index: Option<usize>,
+}
+
+impl Show for Packed<CheckboxElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let field = FormField {
+            name: self.name().clone(),
+            kind: FormFieldKind::Checkbox { checked: self.checked(styles) },
+            tab_index: self.tab_index(styles),
+        };
+
+        let size = Rel::from(self.size(styles));
+        Ok(field_box(field, size, size, false))
+    }
+}
+
+/// One option in a group of mutually exclusive choices.
+///
+/// All radio buttons with the same name form a group, of which at most one
+/// can be selected at a time. In the exported PDF, the group becomes an
+/// interactive form field whose value is the `value` of the selected button.
+///
+/// # Example
+/// ```example
+/// #radio-button("size", "small") Small
+/// #radio-button("size", "large", checked: true) Large
+/// ```
+#[elem(title = "Radio Button", Show)]
+pub struct RadioButtonElem {
+    /// The name of the group this button belongs to.
+    #[required]
+    pub name: EcoString,
+
+    /// The value the group takes on when this button is selected.
+    #[required]
+    pub value: EcoString,
+
+    /// Whether the button is initially selected.
+    #[default(false)]
+    pub checked: bool,
+
+    /// The button's width and height.
+    #[default(Em::new(0.8).into())]
+    pub size: Length,
+
+    /// The field's position in the tab order. See the
+    /// [text field]($text-field.tab-index) for details.
+    pub tab_index: Option<usize>,
+}
+
+impl Show for Packed<RadioButtonElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let field = FormField {
+            name: self.name().clone(),
+            kind: FormFieldKind::Radio {
+                value: self.value().clone(),
+                checked: self.checked(styles),
+            },
+            tab_index: self.tab_index(styles),
+        };
+
+        let size = Rel::from(self.size(styles));
+        Ok(field_box(field, size, size, true))
+    }
+}
+
+/// A dropdown to choose one of multiple options.
+///
+/// In the exported PDF, this becomes an interactive form field. See the
+/// [text field]($text-field) for details on names.
+///
+/// # Example
+/// ```example
+/// Country: #dropdown(
+///   "country",
+///   ("Germany", "France", "Italy"),
+///   value: "France",
+/// )
+/// ```
+#[elem(Show)]
+pub struct DropdownElem {
+    /// The field's name.
+    #[required]
+    pub name: EcoString,
+
+    /// The options to choose from.
+    #[required]
+    pub options: Vec<EcoString>,
+
+    /// The initially selected option.
+    ///
+    /// Must be one of the options.
+    pub value: Option<EcoString>,
+
+    /// The field's width.
+    #[default(Abs::pt(120.0).into())]
+    pub width: Rel<Length>,
+
+    /// The field's height.
+    #[default(Length::from(Em::new(1.4)).into())]
+    pub height: Rel<Length>,
+
+    /// The field's position in the tab order. See the
+    /// [text field]($text-field.tab-index) for details.
+    pub tab_index: Option<usize>,
+}
+
+impl Show for Packed<DropdownElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let value = self.value(styles);
+        if let Some(value) = &value {
+            if !self.options().contains(value) {
+                bail!(self.span(), "value `{value}` is not one of the options");
+            }
+        }
+
+        let field = FormField {
+            name: self.name().clone(),
+            kind: FormFieldKind::Dropdown { options: self.options().clone(), value },
+            tab_index: self.tab_index(styles),
+        };
+
+        Ok(field_box(field, self.width(styles), self.height(styles), false))
+    }
+}
+
+/// Create an inline box of the given size that carries a form field.
+fn field_box(
+    field: FormField,
+    width: Rel<Length>,
+    height: Rel<Length>,
+    round: bool,
+) -> Content {
+    let stroke = Stroke::from_pair(Color::GRAY, Abs::pt(0.5).into());
+    let mut boxed = BoxElem::new()
+        .with_width(Sizing::Rel(width))
+        .with_height(Smart::Custom(height))
+        .with_stroke(Sides::splat(Some(Some(stroke))));
+    if round {
+        boxed.push_radius(Corners::splat(Some(Ratio::new(0.5).into())));
+    }
+
+    boxed.pack().styled(MetaElem::set_data(smallvec![Meta::Field(field)]))
+}
+
+/// An interactive form field.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct FormField {
+    /// The field's name. Radio buttons with the same name form a group.
+    pub name: EcoString,
+    /// What kind of field this is.
+    pub kind: FormFieldKind,
+    /// The field's position in the tab order.
+    pub tab_index: Option<usize>,
+}
+
+/// The kind of a form field along with its initial state.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum FormFieldKind {
+    /// A text input.
+    Text { value: EcoString, multiline: bool, max_length: Option<NonZeroUsize> },
+    /// A checkbox.
+    Checkbox { checked: bool },
+    /// A radio button with its export value.
+    Radio { value: EcoString, checked: bool },
+    /// A dropdown with its options.
+    Dropdown { options: Vec<EcoString>, value: Option<EcoString> },
+}
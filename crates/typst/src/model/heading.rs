@@ -28,6 +28,31 @@ use crate::util::{option_eq, NonZeroExt};
 /// [outline]($outline) of all headings for you. To exclude one or more headings
 /// from this outline, you can set the `outlined` parameter to `{false}`.
 ///
+/// # Controlling the numbering
+/// A heading with a [`numbering`]($heading.numbering) of `{none}` is not
+/// numbered and does not advance the heading counter, so the next numbered
+/// heading continues where the previous numbered one left off. Whether a
+/// heading is listed in the outline is controlled separately by `outlined`.
+///
+/// With [`reset`]($heading.reset), the numbering restarts at a heading. This
+/// is useful for appendices, which are typically numbered with letters. To
+/// set the numbers to arbitrary values, you can also
+/// [update the heading counter]($counter.update) directly.
+///
+/// ```example
+/// #set heading(numbering: "1.1")
+///
+/// = Introduction
+/// == Background
+/// #heading(numbering: none)[Interlude]
+/// = Results
+///
+/// #set heading(numbering: "A.1")
+/// #heading(reset: true)[Proofs]
+/// == Lemmas
+/// = Data
+/// ```
+///
 /// # Example
 /// ```example
 /// #set heading(numbering: "1.a)")
@@ -121,6 +146,23 @@ pub struct HeadingElem {
     #[default(Smart::Auto)]
     pub bookmarked: Smart<bool>,
 
+    /// Whether the numbering restarts at this heading.
+    ///
+    /// If `{true}`, the heading gets the first number at its level, while the
+    /// numbers of the higher levels are kept. Subsequent headings continue
+    /// from there.
+    ///
+    /// ```example
+    /// #set heading(numbering: "1.a")
+    ///
+    /// = Methods
+    /// == Survey
+    /// == Interviews
+    /// #heading(level: 2, reset: true)[Pilot]
+    /// ```
+    #[default(false)]
+    pub reset: bool,
+
     /// The heading's title.
     #[required]
     pub body: Content,
@@ -151,7 +193,7 @@ impl Show for Packed<HeadingElem> {
         let mut realized = self.body().clone();
         if let Some(numbering) = (**self).numbering(styles).as_ref() {
             realized = Counter::of(HeadingElem::elem())
-                .at(engine, self.location().unwrap())?
+                .at_loc(engine, self.location().unwrap())?
                 .display(engine, numbering)?
                 .spanned(self.span())
                 + HElem::new(Em::new(0.3).into()).with_weak(true).pack()
@@ -186,10 +228,13 @@ impl ShowSet for Packed<HeadingElem> {
 
 impl Count for Packed<HeadingElem> {
     fn update(&self) -> Option<CounterUpdate> {
-        (**self)
-            .numbering(StyleChain::default())
-            .is_some()
-            .then(|| CounterUpdate::Step((**self).level(StyleChain::default())))
+        (**self).numbering(StyleChain::default()).as_ref()?;
+        let level = (**self).level(StyleChain::default());
+        Some(if self.reset(StyleChain::default()) {
+            CounterUpdate::Restart(level)
+        } else {
+            CounterUpdate::Step(level)
+        })
     }
 }
 
@@ -220,7 +265,7 @@ impl Outlinable for Packed<HeadingElem> {
         let mut content = self.body().clone();
         if let Some(numbering) = (**self).numbering(StyleChain::default()).as_ref() {
             let numbers = Counter::of(HeadingElem::elem())
-                .at(engine, self.location().unwrap())?
+                .at_loc(engine, self.location().unwrap())?
                 .display(engine, numbering)?;
             content = numbers + SpaceElem::new().pack() + content;
         };
@@ -0,0 +1,327 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::EcoString;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, Content, NativeElement, Packed, Selector, Show, ShowSet, Smart,
+    StyleChain, Styles,
+};
+use crate::introspection::{Counter, CounterKey, Locatable, Location};
+use crate::layout::{Em, HElem};
+use crate::model::{Destination, HeadingElem, NumberingPattern, ParbreakElem};
+use crate::text::{Lang, LinebreakElem, LocalName, Region, TextElem};
+use crate::util::NonZeroExt;
+
+/// A back-of-book index.
+///
+/// The index lists all terms that were marked with
+/// [`index.entry`]($index.entry) in the document, in alphabetical order and
+/// with the numbers of the pages on which they occur. Entries for the same
+/// term are merged, so each term is listed only once and each page only once
+/// per term.
+///
+/// Terms are sorted according to the conventions of the
+/// [text language]($text.lang). For instance, in Swedish, `å`, `ä`, and `ö`
+/// come after `z`, while in German, `ä` is sorted like `a`.
+///
+/// # Example
+/// ```example
+/// #set page(height: 120pt)
+/// = Introduction
+/// Typesetting #index.entry("Typesetting")
+/// is the art of arranging text.
+/// Fonts #index.entry("Font")
+/// #index.entry("Typesetting", sub: "of fonts")
+/// play a big role.
+///
+/// #pagebreak()
+/// #index()
+/// ```
+///
+/// # Sub-entries and references
+/// An entry can be filed below a main term with `sub`, for instance to list
+/// `{"Font"}` with the sub-entry `{"serif"}`. With `see`, an entry can refer
+/// readers to a related term, which is then listed after the page numbers as
+/// a "see also" reference.
+#[elem(scope, Show, ShowSet, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+#[scope]
+impl IndexElem {
+    #[elem]
+    type IndexEntry;
+}
+
+impl Show for Packed<IndexElem> {
+    #[typst_macros::time(name = "index", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(
+                HeadingElem::new(title)
+                    .with_level(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span()),
+            );
+        }
+
+        let lang = TextElem::lang_in(styles);
+        let mut terms: Vec<Term> = vec![];
+        let elems = engine.introspector.query(&Selector::Elem(IndexEntry::elem(), None));
+        for elem in &elems {
+            let entry = elem.to_packed::<IndexEntry>().unwrap();
+            let location = elem.location().unwrap();
+            let key = entry
+                .key(StyleChain::default())
+                .unwrap_or_else(|| entry.term().plain_text());
+
+            let mut term = Term::find_or_insert(&mut terms, key, entry.term(), lang);
+            if let Some(sub) = entry.sub(StyleChain::default()) {
+                term = Term::find_or_insert(&mut term.subs, sub.plain_text(), &sub, lang);
+            }
+
+            let page = engine.introspector.page(location);
+            if !term.pages.iter().any(|&(p, _)| p == page) {
+                term.pages.push((page, location));
+            }
+
+            if let Some(see) = entry.see(StyleChain::default()) {
+                if !term.see.contains(&see) {
+                    term.see.push(see);
+                }
+            }
+        }
+
+        let see_also = TextElem::packed(see_also_name(lang)).emph();
+        Term::sort(&mut terms);
+        for term in &terms {
+            term.layout(engine, &mut seq, &see_also, false)?;
+            for sub in &term.subs {
+                sub.layout(engine, &mut seq, &see_also, true)?;
+            }
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<IndexElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::set_numbering(None));
+        out
+    }
+}
+
+impl LocalName for Packed<IndexElem> {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::DUTCH => "Register",
+            Lang::FRENCH => "Index",
+            Lang::GERMAN => "Stichwortverzeichnis",
+            Lang::ITALIAN => "Indice analitico",
+            Lang::PORTUGUESE => "Índice remissivo",
+            Lang::SPANISH => "Índice alfabético",
+            Lang::ENGLISH | _ => "Index",
+        }
+    }
+}
+
+/// Marks a term that should be listed in the [index]($index).
+///
+/// The entry itself is invisible. It records the page at which it is placed,
+/// so it should be put right next to the text it refers to.
+///
+/// ```example
+/// Typst #index.entry("Typst") is a
+/// typesetting system.
+/// #index.entry("Typesetting", sub: "systems")
+/// #index.entry("Typst", see: "LaTeX")
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "entry", title = "Index Entry", Locatable, Show)]
+pub struct IndexEntry {
+    /// The term to list in the index.
+    #[required]
+    pub term: Content,
+
+    /// The text by which the term is sorted and merged with other entries. By
+    /// default, the plain text of the term is used.
+    ///
+    /// This is useful when the term contains formatting or should be sorted
+    /// differently than it is written, for instance a person's name by their
+    /// surname.
+    pub key: Option<EcoString>,
+
+    /// A sub-entry under which this entry is filed below the term.
+    pub sub: Option<Content>,
+
+    /// A related term to which the index refers readers with "see also".
+    pub see: Option<Content>,
+}
+
+impl Show for Packed<IndexEntry> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// A term in the index together with its pages and sub-entries.
+struct Term {
+    /// The key by which the term is merged with others.
+    key: EcoString,
+    /// The key by which the term is sorted.
+    collation: EcoString,
+    /// The term's displayed body.
+    body: Content,
+    /// The physical pages on which the term occurs, with the location of its
+    /// first entry on that page.
+    pages: Vec<(NonZeroUsize, Location)>,
+    /// The related terms to refer to.
+    see: Vec<Content>,
+    /// The term's sub-entries.
+    subs: Vec<Term>,
+}
+
+impl Term {
+    /// Find the term with the given key or add a new one.
+    fn find_or_insert<'a>(
+        terms: &'a mut Vec<Term>,
+        key: EcoString,
+        body: &Content,
+        lang: Lang,
+    ) -> &'a mut Term {
+        match terms.iter().position(|term| term.key == key) {
+            Some(i) => &mut terms[i],
+            None => {
+                terms.push(Term {
+                    collation: collation_key(&key, lang),
+                    key,
+                    body: body.clone(),
+                    pages: vec![],
+                    see: vec![],
+                    subs: vec![],
+                });
+                terms.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Sort terms and their sub-entries alphabetically and their pages by
+    /// number.
+    fn sort(terms: &mut [Term]) {
+        terms.sort_by(|a, b| {
+            a.collation.cmp(&b.collation).then_with(|| a.key.cmp(&b.key))
+        });
+        for term in terms {
+            term.pages.sort_by_key(|&(page, _)| page);
+            Self::sort(&mut term.subs);
+        }
+    }
+
+    /// Layout the term as a line of the index.
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        seq: &mut Vec<Content>,
+        see_also: &Content,
+        nested: bool,
+    ) -> SourceResult<()> {
+        if nested {
+            seq.push(HElem::new(Em::new(1.5).into()).pack());
+        }
+
+        seq.push(self.body.clone());
+        for &(_, location) in &self.pages {
+            seq.push(TextElem::packed(", "));
+            let numbering = engine
+                .introspector
+                .page_numbering(location)
+                .cloned()
+                .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
+            let page = Counter::new(CounterKey::Page)
+                .at_loc(engine, location)?
+                .display(engine, &numbering)?;
+            seq.push(page.linked(Destination::Location(location)));
+        }
+
+        for (i, see) in self.see.iter().enumerate() {
+            if i == 0 {
+                seq.push(TextElem::packed(". "));
+                seq.push(see_also.clone());
+                seq.push(TextElem::packed(" "));
+            } else {
+                seq.push(TextElem::packed(", "));
+            }
+            seq.push(see.clone());
+        }
+
+        seq.push(LinebreakElem::new().pack());
+        Ok(())
+    }
+}
+
+/// The phrase with which an index refers to related terms.
+fn see_also_name(lang: Lang) -> &'static str {
+    match lang {
+        Lang::DUTCH => "zie ook",
+        Lang::FRENCH => "voir aussi",
+        Lang::GERMAN => "siehe auch",
+        Lang::ITALIAN => "vedi anche",
+        Lang::PORTUGUESE => "ver também",
+        Lang::SPANISH => "véase también",
+        Lang::ENGLISH | _ => "see also",
+    }
+}
+
+/// Produce a key by which text is sorted alphabetically in a language.
+///
+/// Case and diacritics are ignored, except for letters that a language sorts
+/// separately, like the Swedish `å`, `ä`, and `ö`, which come after `z`.
+fn collation_key(text: &str, lang: Lang) -> EcoString {
+    // Code points that sort after all regular letters.
+    const AFTER_Z: [char; 3] = ['\u{F0000}', '\u{F0001}', '\u{F0002}'];
+
+    let mut key = EcoString::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match (lang.as_str(), c) {
+            ("sv" | "fi", 'å') | ("da" | "nb" | "nn" | "no", 'æ') => {
+                key.push(AFTER_Z[0])
+            }
+            ("sv" | "fi", 'ä') | ("da" | "nb" | "nn" | "no", 'ø') => {
+                key.push(AFTER_Z[1])
+            }
+            ("sv" | "fi", 'ö') | ("da" | "nb" | "nn" | "no", 'å') => {
+                key.push(AFTER_Z[2])
+            }
+            ("es", 'ñ') => {
+                key.push('n');
+                key.push(AFTER_Z[0]);
+            }
+            (_, 'ß') => key.push_str("ss"),
+            _ => key.extend(std::iter::once(c).nfd().filter(|&c| !is_combining_mark(c))),
+        }
+    }
+    key
+}
@@ -1,6 +1,6 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, Content, Label, Packed, Repr, Show, Smart, StyleChain,
@@ -38,6 +38,12 @@ pub struct LinkElem {
     ///   omitted, the email address or phone number will be the link's body,
     ///   without the scheme.
     ///
+    /// - To link to another PDF file, `dest` can be a relative path like
+    ///   `{"manual.pdf"}`. A fragment like `{"manual.pdf#setup"}` jumps to the
+    ///   element labelled `<setup>` in that file, since each label becomes a
+    ///   named destination in the exported PDF. Links with `javascript:` URLs
+    ///   are dropped from the PDF.
+    ///
     /// - To link to another part of the document, `dest` can take one of three
     ///   forms:
     ///   - A [label]($label) attached to an element. If you also want automatic
@@ -96,7 +102,7 @@ impl Show for Packed<LinkElem> {
         let linked = match self.dest() {
             LinkTarget::Dest(dest) => body.linked(dest.clone()),
             LinkTarget::Label(label) => {
-                let elem = engine.introspector.query_label(*label).at(self.span())?;
+                let elem = engine.introspector.query_label_at(*label, self.span())?;
                 let dest = Destination::Location(elem.location().unwrap());
                 body.clone().linked(dest)
             }
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+
+use comemo::Track;
+use ecow::{eco_format, EcoString};
+use serde::{Deserialize, Serialize};
+
+use crate::diag::{At, SourceResult, StrResult};
+use crate::engine::{Engine, Route};
+use crate::eval::Tracer;
+use crate::foundations::{ty, Args, Bytes, Repr};
+use crate::introspection::Locator;
+use crate::loading::{load, ResourceKind};
+use crate::model::{Document, Refable};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// The referenceable labels of a compiled document.
+///
+/// A manifest is exported with the `--refs` option of the CLI. Other documents
+/// can then reference the labels in it through the [`from`]($ref.from)
+/// property of references, which is useful for works that are split across
+/// multiple volumes.
+#[ty]
+#[derive(Debug, Default, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RefManifest {
+    /// The title of the document.
+    pub title: Option<EcoString>,
+    /// Maps from label names to how references to them are displayed.
+    pub labels: BTreeMap<EcoString, ManifestEntry>,
+}
+
+/// How a reference to a label in a manifest is displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The supplement, like "Section" or "Figure".
+    pub supplement: EcoString,
+    /// The formatted number, like "3.4".
+    pub number: EcoString,
+    /// The physical page number on which the labelled element is.
+    pub page: NonZeroUsize,
+}
+
+impl RefManifest {
+    /// Parse the `from` argument of a reference.
+    pub fn parse(
+        engine: &mut Engine,
+        args: &mut Args,
+    ) -> SourceResult<Option<Option<RefManifest>>> {
+        let Some(Spanned { v: path, span }) =
+            args.named::<Spanned<Option<EcoString>>>("from")?
+        else {
+            return Ok(None);
+        };
+
+        let Some(path) = path else { return Ok(Some(None)) };
+        let id = span.resolve_path(&path).at(span)?;
+        let data = load(engine.world, id, ResourceKind::Data).at(span)?;
+        Ok(Some(Some(Self::load(&data).at(span)?)))
+    }
+
+    /// Load a manifest from JSON.
+    #[comemo::memoize]
+    pub fn load(data: &Bytes) -> StrResult<RefManifest> {
+        serde_json::from_slice(data)
+            .map_err(|err| eco_format!("failed to parse reference manifest ({err})"))
+    }
+
+    /// Serialize the manifest to JSON.
+    pub fn to_json(&self) -> StrResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|err| eco_format!("failed to serialize reference manifest ({err})"))
+    }
+}
+
+impl Repr for RefManifest {
+    fn repr(&self) -> EcoString {
+        match &self.title {
+            Some(title) => eco_format!("manifest({})", title.repr()),
+            None => "manifest(..)".into(),
+        }
+    }
+}
+
+impl Document {
+    /// Collect the labelled elements that can be referenced, with their
+    /// numbers as they are displayed in this document.
+    pub fn reference_manifest(&self, world: &dyn World) -> SourceResult<RefManifest> {
+        let mut tracer = Tracer::new();
+        let mut locator = Locator::new();
+        let mut engine = Engine {
+            world: world.track(),
+            introspector: self.introspector.track(),
+            route: Route::default(),
+            locator: &mut locator,
+            tracer: tracer.track_mut(),
+        };
+
+        let mut labels = BTreeMap::new();
+        for elem in self.introspector.all() {
+            let (Some(label), Some(location)) = (elem.label(), elem.location()) else {
+                continue;
+            };
+
+            let Some(refable) = elem.with::<dyn Refable>() else { continue };
+            let Some(numbering) = refable.numbering() else { continue };
+            let number = refable
+                .counter()
+                .at_loc(&mut engine, location)?
+                .display(&mut engine, &numbering.clone().trimmed())?;
+
+            labels.insert(
+                label.as_str().into(),
+                ManifestEntry {
+                    supplement: refable.supplement().plain_text(),
+                    number: number.plain_text(),
+                    page: self.introspector.page(location),
+                },
+            );
+        }
+
+        Ok(RefManifest { title: self.title.clone(), labels })
+    }
+}
@@ -8,15 +8,19 @@ mod emph;
 mod enum_;
 mod figure;
 mod footnote;
+mod form;
 mod heading;
+mod index;
 mod link;
 mod list;
+mod manifest;
 #[path = "numbering.rs"]
 mod numbering_;
 mod outline;
 mod par;
 mod quote;
 mod reference;
+mod speaker_note;
 mod strong;
 mod table;
 mod terms;
@@ -28,14 +32,18 @@ pub use self::emph::*;
 pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::form::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
+pub use self::manifest::*;
 pub use self::numbering_::*;
 pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::speaker_note::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
@@ -57,6 +65,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<RefElem>();
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
     global.define_elem::<HeadingElem>();
     global.define_elem::<FigureElem>();
     global.define_elem::<FootnoteElem>();
@@ -71,5 +80,10 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<TermsElem>();
     global.define_elem::<EmphElem>();
     global.define_elem::<StrongElem>();
+    global.define_elem::<TextFieldElem>();
+    global.define_elem::<CheckboxElem>();
+    global.define_elem::<RadioButtonElem>();
+    global.define_elem::<DropdownElem>();
+    global.define_elem::<SpeakerNoteElem>();
     global.define_func::<numbering>();
 }
@@ -37,9 +37,9 @@ pub fn numbering(
     engine: &mut Engine,
     /// Defines how the numbering works.
     ///
-    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`,
-    /// `ㄱ`, and `*`. They are replaced by the number in the sequence, in the
-    /// given case.
+    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `α`, `Α`, `一`, `壹`, `あ`, `い`,
+    /// `ア`, `イ`, `א`, `가`, `ㄱ`, and `*`. They are replaced by the number in the
+    /// sequence, in the given case.
     ///
     /// The `*` character means that symbols should be used to count, in the
     /// order of `*`, `†`, `‡`, `§`, `¶`, and `‖`. If there are more than six
@@ -58,6 +58,29 @@ pub fn numbering(
     /// particularly useful in itself, it means that you can just give arbitrary
     /// numberings to the `numbering` function without caring whether they are
     /// defined as a pattern or function.
+    ///
+    /// Every `numbering` parameter in Typst, for instance that of
+    /// [headings]($heading.numbering), [figures]($figure.numbering), or
+    /// [pages]($page.numbering), accepts such a function. This makes it
+    /// possible to produce numberings that no pattern can express, like
+    /// zero-padded numbers or numbers that include the current chapter:
+    ///
+    /// ```example
+    /// #let padded(n) = if n < 10 { "0" + str(n) } else { str(n) }
+    /// #set enum(numbering: padded)
+    /// + Zero
+    /// + Padded
+    ///
+    /// #set heading(numbering: "1.")
+    /// #set figure(numbering: n => locate(loc => {
+    ///   let chapter = counter(heading).at(loc).first()
+    ///   numbering("1-1", chapter, n)
+    /// }))
+    ///
+    /// = Results
+    /// #figure([A], caption: [First])
+    /// #figure([B], caption: [Second])
+    /// ```
     numbering: Numbering,
     /// The numbers to apply the numbering to. Must be positive.
     ///
@@ -115,8 +138,8 @@ cast! {
 /// How to turn a number into text.
 ///
 /// A pattern consists of a prefix, followed by one of
-/// `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`, `ㄱ`, or `*`,
-/// and then a suffix.
+/// `1`, `a`, `A`, `i`, `I`, `α`, `Α`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`,
+/// `ㄱ`, or `*`, and then a suffix.
 ///
 /// Examples of valid patterns:
 /// - `1)`
@@ -222,7 +245,7 @@ cast! {
             pat.push_str(prefix);
             let mut c = kind.to_char();
             if *case == Case::Upper {
-                c = c.to_ascii_uppercase();
+                c = c.to_uppercase().next().unwrap_or(c);
             }
             pat.push(c);
         }
@@ -254,6 +277,7 @@ pub enum NumberingKind {
     KatakanaIroha,
     KoreanJamo,
     KoreanSyllable,
+    Greek,
 }
 
 impl NumberingKind {
@@ -272,6 +296,7 @@ impl NumberingKind {
             'イ' => NumberingKind::KatakanaIroha,
             'ㄱ' => NumberingKind::KoreanJamo,
             '가' => NumberingKind::KoreanSyllable,
+            'α' | 'Α' => NumberingKind::Greek,
             _ => return None,
         })
     }
@@ -292,6 +317,7 @@ impl NumberingKind {
             Self::KatakanaIroha => 'イ',
             Self::KoreanJamo => 'ㄱ',
             Self::KoreanSyllable => '가',
+            Self::Greek => 'α',
         }
     }
 
@@ -308,6 +334,19 @@ impl NumberingKind {
                 },
                 n,
             ),
+            Self::Greek => zeroless::<24>(
+                |x| match case {
+                    Case::Lower => [
+                        'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν',
+                        'ξ', 'ο', 'π', 'ρ', 'σ', 'τ', 'υ', 'φ', 'χ', 'ψ', 'ω',
+                    ][x],
+                    Case::Upper => [
+                        'Α', 'Β', 'Γ', 'Δ', 'Ε', 'Ζ', 'Η', 'Θ', 'Ι', 'Κ', 'Λ', 'Μ', 'Ν',
+                        'Ξ', 'Ο', 'Π', 'Ρ', 'Σ', 'Τ', 'Υ', 'Φ', 'Χ', 'Ψ', 'Ω',
+                    ][x],
+                },
+                n,
+            ),
             Self::HiraganaAiueo => zeroless::<46>(
                 |x| {
                     [
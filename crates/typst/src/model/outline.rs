@@ -1,18 +1,23 @@
+use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, select_where, Content, Func, LocatableSelector, NativeElement,
-    Packed, Show, ShowSet, Smart, StyleChain, Styles,
+    cast, elem, scope, select_where, Content, Fields, Func, IntoValue, LocatableSelector,
+    NativeElement, Packed, Selector, Show, ShowSet, Smart, StyleChain, Styles,
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::layout::{BoxElem, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing};
-use crate::model::{Destination, HeadingElem, NumberingPattern, ParbreakElem, Refable};
+use crate::model::{
+    Destination, FigureElem, HeadingElem, NumberingPattern, ParbreakElem, Refable,
+    TableElem,
+};
 use crate::syntax::Span;
-use crate::text::{Lang, LinebreakElem, LocalName, Region, SpaceElem, TextElem};
+use crate::text::{Lang, LinebreakElem, LocalName, RawElem, Region, SpaceElem, TextElem};
 use crate::util::{option_eq, NonZeroExt};
+use crate::visualize::ImageElem;
 
 /// A table of contents, figures, or other elements.
 ///
@@ -40,9 +45,13 @@ use crate::util::{option_eq, NonZeroExt};
 /// would also include figures containing tables or other material. For more
 /// details on the `where` selector, [see here]($function.where).
 ///
+/// For outlines of figures with images, tables, or code, the default title
+/// is a fitting name in the [text language]($text.lang), for instance "List of
+/// Figures" in English. Each kind of figure is numbered separately, so the
+/// numbers in such a list match the figures' captions.
+///
 /// ```example
 /// #outline(
-///   title: [List of Figures],
 ///   target: figure.where(kind: image),
 /// )
 ///
@@ -115,6 +124,23 @@ pub struct OutlineElem {
     /// ```
     pub depth: Option<NonZeroUsize>,
 
+    /// Elements to leave out of the outline, even though they match the
+    /// [`target`]($outline.target).
+    ///
+    /// This is useful to skip a few specific elements, for instance by giving
+    /// them a label. To hide a heading from all outlines, you can also set its
+    /// [`outlined`]($heading.outlined) property to `{false}`.
+    ///
+    /// ```example
+    /// #outline(exclude: <draft>)
+    ///
+    /// = Introduction
+    /// = Unfinished thoughts <draft>
+    /// = Conclusion
+    /// ```
+    #[borrowed]
+    pub exclude: Option<LocatableSelector>,
+
     /// How to indent the outline's entries.
     ///
     /// - `{none}`: No indent
@@ -192,7 +218,11 @@ impl Show for Packed<OutlineElem> {
         // Build the outline title.
         if let Some(title) = self.title(styles) {
             let title = title.unwrap_or_else(|| {
-                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+                let lang = TextElem::lang_in(styles);
+                let region = TextElem::region_in(styles);
+                let name = figure_list_name(&self.target(styles).0, lang)
+                    .unwrap_or_else(|| Self::local_name(lang, region));
+                TextElem::packed(name).spanned(self.span())
             });
 
             seq.push(
@@ -208,8 +238,21 @@ impl Show for Packed<OutlineElem> {
 
         let mut ancestors: Vec<&Content> = vec![];
         let elems = engine.introspector.query(&self.target(styles).0);
+        let excluded: HashSet<_> = match self.exclude(styles) {
+            Some(exclude) => engine
+                .introspector
+                .query(&exclude.0)
+                .iter()
+                .filter_map(|elem| elem.location())
+                .collect(),
+            None => HashSet::new(),
+        };
 
         for elem in &elems {
+            if elem.location().map_or(false, |loc| excluded.contains(&loc)) {
+                continue;
+            }
+
             let Some(entry) = OutlineEntry::from_outlinable(
                 engine,
                 self.span(),
@@ -298,6 +341,48 @@ impl LocalName for Packed<OutlineElem> {
     }
 }
 
+/// The local name of an outline that lists figures of a single kind, like a
+/// list of tables.
+fn figure_list_name(target: &Selector, lang: Lang) -> Option<&'static str> {
+    let Selector::Elem(elem, Some(fields)) = target else { return None };
+    if *elem != FigureElem::elem() {
+        return None;
+    }
+
+    let id = <FigureElem as Fields>::Enum::Kind as u8;
+    let (_, kind) = fields.iter().find(|(field, _)| *field == id)?;
+    if *kind == TableElem::elem().into_value() {
+        Some(match lang {
+            Lang::DUTCH => "Lijst van tabellen",
+            Lang::FRENCH => "Liste des tableaux",
+            Lang::GERMAN => "Tabellenverzeichnis",
+            Lang::ITALIAN => "Elenco delle tabelle",
+            Lang::PORTUGUESE => "Lista de tabelas",
+            Lang::SPANISH => "Índice de tablas",
+            Lang::ENGLISH => "List of Tables",
+            _ => return None,
+        })
+    } else if *kind == ImageElem::elem().into_value() {
+        Some(match lang {
+            Lang::DUTCH => "Lijst van figuren",
+            Lang::FRENCH => "Table des figures",
+            Lang::GERMAN => "Abbildungsverzeichnis",
+            Lang::ITALIAN => "Elenco delle figure",
+            Lang::PORTUGUESE => "Lista de figuras",
+            Lang::SPANISH => "Índice de figuras",
+            Lang::ENGLISH => "List of Figures",
+            _ => return None,
+        })
+    } else if *kind == RawElem::elem().into_value() {
+        Some(match lang {
+            Lang::ENGLISH => "List of Listings",
+            _ => return None,
+        })
+    } else {
+        None
+    }
+}
+
 /// Marks an element as being able to be outlined. This is used to implement the
 /// `#outline()` element.
 pub trait Outlinable: Refable {
@@ -340,7 +425,7 @@ impl OutlineIndent {
                     if let Some(numbering) = ancestor_outlinable.numbering() {
                         let numbers = ancestor_outlinable
                             .counter()
-                            .at(engine, ancestor.location().unwrap())?
+                            .at_loc(engine, ancestor.location().unwrap())?
                             .display(engine, numbering)?;
 
                         hidden += numbers + SpaceElem::new().pack();
@@ -471,7 +556,7 @@ impl OutlineEntry {
         fill: Option<Content>,
     ) -> SourceResult<Option<Self>> {
         let Some(outlinable) = elem.with::<dyn Outlinable>() else {
-            bail!(span, "cannot outline {}", elem.func().name());
+            bail!(span, "cannot outline {}", elem.elem().name());
         };
 
         let Some(body) = outlinable.outline(engine)? else {
@@ -486,7 +571,7 @@ impl OutlineEntry {
             .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
 
         let page = Counter::new(CounterKey::Page)
-            .at(engine, location)?
+            .at_loc(engine, location)?
             .display(engine, &page_numbering)?;
 
         Ok(Some(Self::new(outlinable.level(), elem, body, fill, page)))
@@ -503,11 +588,11 @@ impl Show for Packed<OutlineEntry> {
         let Some(location) = elem.location() else {
             if elem.can::<dyn Locatable>() && elem.can::<dyn Outlinable>() {
                 bail!(
-                    self.span(), "{} must have a location", elem.func().name();
+                    self.span(), "{} must have a location", elem.elem().name();
                     hint: "try using a query or a show rule to customize the outline.entry instead",
                 )
             } else {
-                bail!(self.span(), "cannot outline {}", elem.func().name())
+                bail!(self.span(), "cannot outline {}", elem.elem().name())
             }
         };
 
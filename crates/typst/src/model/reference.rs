@@ -1,15 +1,16 @@
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, At, Hint, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Content, Func, IntoValue, Label, NativeElement, Packed, Show, Smart,
-    StyleChain, Synthesize,
+    cast, elem, Content, Func, IntoValue, Label, NativeElement, Packed, Repr, Selector,
+    Show, Smart, StyleChain, Synthesize, Value,
 };
 use crate::introspection::{Counter, Locatable};
 use crate::math::EquationElem;
 use crate::model::{
     BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering,
+    RefManifest,
 };
 use crate::text::TextElem;
 
@@ -91,6 +92,27 @@ use crate::text::TextElem;
 /// In @beginning we prove @pythagoras.
 /// $ a^2 + b^2 = c^2 $ <pythagoras>
 /// ```
+///
+/// # Namespaces
+/// In large documents that are split across multiple files, the same label
+/// name is easily used twice. To avoid such collisions, labels can be prefixed
+/// with a namespace, separated by a colon, as in `[<intro:motivation>]`. With
+/// the [`namespace`]($ref.namespace) property, references within a part of
+/// the document can then refer to such labels by their short name. If a label
+/// occurs multiple times, the error for an ambiguous reference points to each
+/// of its definitions.
+///
+/// ```example
+/// #set heading(numbering: "1.")
+///
+/// = Introduction <intro>
+/// == Motivation <intro:motivation>
+///
+/// #[
+///   #set ref(namespace: "intro")
+///   See @motivation in @intro.
+/// ]
+/// ```
 #[elem(title = "Reference", Synthesize, Locatable, Show)]
 pub struct RefElem {
     /// The target label that should be referenced.
@@ -127,6 +149,33 @@ pub struct RefElem {
     #[borrowed]
     pub supplement: Smart<Option<Supplement>>,
 
+    /// The namespace in which to resolve the target label.
+    ///
+    /// If set, a reference to `[@name]` refers to the label `[<namespace:name>]`
+    /// if that exists in the document or its bibliography, and to `[<name>]`
+    /// otherwise.
+    #[borrowed]
+    pub namespace: Option<EcoString>,
+
+    /// The reference manifest of another document in which to look up the
+    /// target label.
+    ///
+    /// A manifest lists the referenceable labels of a document together with
+    /// their numbers. It is written by compiling the other document with the
+    /// `--refs` option of the CLI. When set, the reference displays the other
+    /// document's title followed by the supplement and number of the label in
+    /// it, like "Volume II, Section 3.4". If the label does not exist in the
+    /// manifest, an error is raised. If the supplement is a function, it is
+    /// called with `{none}` since the element is not available.
+    ///
+    /// ```typ
+    /// #let vol2(target) = ref(target, from: "vol2-refs.json")
+    /// For the proof, see #vol2(<proof>).
+    /// ```
+    #[parse(RefManifest::parse(engine, args)?)]
+    #[borrowed]
+    pub from: Option<RefManifest>,
+
     /// A synthesized citation.
     #[synthesized]
     pub citation: Option<Packed<CiteElem>>,
@@ -142,13 +191,22 @@ impl Synthesize for Packed<RefElem> {
         engine: &mut Engine,
         styles: StyleChain,
     ) -> SourceResult<()> {
+        if self.from(styles).is_some() {
+            let elem = self.as_mut();
+            elem.push_citation(None);
+            elem.push_element(None);
+            return Ok(());
+        }
+
+        let target = resolve_target(self, engine, styles);
+        self.as_mut().push_target(target);
+
         let citation = to_citation(self, engine, styles)?;
 
         let elem = self.as_mut();
         elem.push_citation(Some(citation));
         elem.push_element(None);
 
-        let target = *elem.target();
         if !BibliographyElem::has(engine, target) {
             if let Ok(found) = engine.introspector.query_label(target).cloned() {
                 elem.push_element(Some(found.into_inner()));
@@ -164,8 +222,12 @@ impl Show for Packed<RefElem> {
     #[typst_macros::time(name = "ref", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let target = *self.target();
-        let elem = engine.introspector.query_label(target);
         let span = self.span();
+        if let Some(manifest) = self.from(styles) {
+            return show_external(self, engine, styles, manifest);
+        }
+
+        let elem = engine.introspector.query_label(target);
 
         if BibliographyElem::has(engine, target) {
             if elem.is_ok() {
@@ -175,9 +237,12 @@ impl Show for Packed<RefElem> {
             return Ok(to_citation(self, engine, styles)?.pack().spanned(span));
         }
 
-        let elem = elem.at(span)?;
+        let elem = match elem {
+            Ok(elem) => elem,
+            Err(_) => engine.introspector.query_label_at(target, span)?,
+        };
 
-        if elem.func() == FootnoteElem::elem() {
+        if elem.elem() == FootnoteElem::elem() {
             return Ok(FootnoteElem::with_label(target).pack().spanned(span));
         }
 
@@ -188,10 +253,10 @@ impl Show for Packed<RefElem> {
                 if elem.can::<dyn Figurable>() {
                     eco_format!(
                         "cannot reference {} directly, try putting it into a figure",
-                        elem.func().name()
+                        elem.elem().name()
                     )
                 } else {
-                    eco_format!("cannot reference {}", elem.func().name())
+                    eco_format!("cannot reference {}", elem.elem().name())
                 }
             })
             .at(span)?;
@@ -199,15 +264,15 @@ impl Show for Packed<RefElem> {
         let numbering = refable
             .numbering()
             .ok_or_else(|| {
-                eco_format!("cannot reference {} without numbering", elem.func().name())
+                eco_format!("cannot reference {} without numbering", elem.elem().name())
             })
             .hint(eco_format!(
                 "you can enable {} numbering with `#set {}(numbering: \"1.\")`",
-                elem.func().name(),
-                if elem.func() == EquationElem::elem() {
+                elem.elem().name(),
+                if elem.elem() == EquationElem::elem() {
                     "math.equation"
                 } else {
-                    elem.func().name()
+                    elem.elem().name()
                 }
             ))
             .at(span)?;
@@ -215,7 +280,7 @@ impl Show for Packed<RefElem> {
         let loc = elem.location().unwrap();
         let numbers = refable
             .counter()
-            .at(engine, loc)?
+            .at_loc(engine, loc)?
             .display(engine, &numbering.clone().trimmed())?;
 
         let supplement = match self.supplement(styles).as_ref() {
@@ -233,6 +298,66 @@ impl Show for Packed<RefElem> {
     }
 }
 
+/// Display a reference to a label in another document.
+fn show_external(
+    reference: &Packed<RefElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+    manifest: &RefManifest,
+) -> SourceResult<Content> {
+    let target = reference.target();
+    let Some(entry) = manifest.labels.get(target.as_str()) else {
+        match &manifest.title {
+            Some(title) => bail!(
+                reference.span(),
+                "label `{}` does not exist in {title}",
+                target.repr()
+            ),
+            None => bail!(
+                reference.span(),
+                "label `{}` does not exist in the referenced document",
+                target.repr()
+            ),
+        }
+    };
+
+    let supplement = match reference.supplement(styles).as_ref() {
+        Smart::Auto => TextElem::packed(entry.supplement.clone()),
+        Smart::Custom(None) => Content::empty(),
+        Smart::Custom(Some(supplement)) => supplement.resolve(engine, [Value::None])?,
+    };
+
+    let mut content = TextElem::packed(entry.number.clone());
+    if !supplement.is_empty() {
+        content = supplement + TextElem::packed("\u{a0}") + content;
+    }
+
+    if let Some(title) = &manifest.title {
+        content = TextElem::packed(eco_format!("{title}, ")) + content;
+    }
+
+    Ok(content)
+}
+
+/// Resolve the target of a reference in its namespace.
+fn resolve_target(
+    reference: &Packed<RefElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> Label {
+    let target = *reference.target();
+    let Some(namespace) = reference.namespace(styles) else { return target };
+
+    let scoped = Label::new(&eco_format!("{namespace}:{}", target.as_str()));
+    if !engine.introspector.query(&Selector::Label(scoped)).is_empty()
+        || BibliographyElem::has(engine, scoped)
+    {
+        scoped
+    } else {
+        target
+    }
+}
+
 /// Turn a reference into a citation.
 fn to_citation(
     reference: &Packed<RefElem>,
@@ -0,0 +1,68 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Behave, Behaviour, Content, NativeElement, Packed, Show, StyleChain,
+};
+use crate::introspection::Locatable;
+use crate::model::Document;
+
+/// Notes for the presenter of a slide that are not shown on the slide itself.
+///
+/// Speaker notes are attached to the page they appear on. They produce no
+/// visible output, but can be exported alongside the PDF with the `--notes`
+/// option of the CLI for use in presenter tools.
+///
+/// # Example
+/// ```example
+/// = Results
+/// #speaker-note[Mention the sample size.]
+/// The treatment was effective.
+/// ```
+#[elem(title = "Speaker Note", Behave, Show, Locatable)]
+pub struct SpeakerNoteElem {
+    /// The note's text.
+    ///
+    /// Since presenter tools only support plain text, formatting is lost.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<SpeakerNoteElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+impl Behave for Packed<SpeakerNoteElem> {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
+
+/// A speaker note with the page it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpeakerNote {
+    /// The page number, starting at 1.
+    pub page: NonZeroUsize,
+    /// The note's plain text.
+    pub text: EcoString,
+}
+
+impl Document {
+    /// The document's speaker notes in document order.
+    pub fn speaker_notes(&self) -> Vec<SpeakerNote> {
+        self.introspector
+            .query(&SpeakerNoteElem::elem().select())
+            .iter()
+            .filter_map(|elem| {
+                let note = elem.to_packed::<SpeakerNoteElem>()?;
+                let page = self.introspector.position(elem.location()?).page;
+                Some(SpeakerNote { page, text: note.body().plain_text() })
+            })
+            .collect()
+    }
+}
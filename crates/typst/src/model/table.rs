@@ -7,6 +7,7 @@ use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, Content, Fold, Packed, Show, Smart, StyleChain,
 };
+use crate::introspection::Locatable;
 use crate::layout::{
     show_grid_cell, Abs, Alignment, Axes, Cell, CellGrid, Celled, Fragment, GridLayouter,
     LayoutMultiple, Length, Regions, Rel, ResolvableCell, Sides, TrackSizings,
@@ -89,7 +90,7 @@ use crate::visualize::{Paint, Stroke};
 ///   ..(table.cell(y: 4, fill: aqua)[B],) * 2,
 /// )
 /// ```
-#[elem(scope, LayoutMultiple, LocalName, Figurable)]
+#[elem(scope, LayoutMultiple, Locatable, LocalName, Figurable)]
 pub struct TableElem {
     /// The column sizes. See the [grid documentation]($grid) for more
     /// information on track sizing.
@@ -336,7 +337,7 @@ impl Figurable for Packed<TableElem> {}
 pub struct TableCell {
     /// The cell's body.
     #[required]
-    body: Content,
+    pub body: Content,
 
     /// The cell's column (zero-indexed).
     /// Functions identically to the `x` field in [`grid.cell`]($grid.cell).
@@ -351,7 +352,7 @@ pub struct TableCell {
 
     /// The amount of columns spanned by this cell.
     #[default(NonZeroUsize::ONE)]
-    colspan: NonZeroUsize,
+    pub colspan: NonZeroUsize,
 
     /// The cell's alignment override.
     align: Smart<Alignment>,
@@ -11,7 +11,7 @@ use std::mem;
 use smallvec::smallvec;
 use typed_arena::Arena;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::foundations::{
     Behave, Behaviour, Content, NativeElement, Packed, Recipe, RecipeIndex, Regex,
@@ -246,6 +246,18 @@ fn prepare(
     if target.can::<dyn Locatable>() || target.label().is_some() {
         let location = engine.locator.locate(hash128(&target));
         target.set_location(location);
+
+        // Remember the located elements this one is nested in so that it can
+        // be found with `within` selectors.
+        target.set_ancestors(
+            MetaElem::data_in(styles)
+                .iter()
+                .filter_map(|meta| match meta {
+                    Meta::Elem(elem) => elem.location(),
+                    _ => None,
+                })
+                .collect(),
+        );
     }
 
     // Apply built-in show-set rules. User-defined show-set rules are already
@@ -416,10 +428,11 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         if let Some(realized) = realize(self.engine, content, styles)? {
             self.engine.route.increase();
             if !self.engine.route.within(Route::MAX_SHOW_RULE_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum show rule depth exceeded";
                     hint: "check whether the show rule matches its own output"
-                );
+                )
+                .into_fatal());
             }
             let stored = self.scratch.content.alloc(realized);
             let v = self.accept(stored, styles);
@@ -479,7 +492,7 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         if content.is::<PagebreakElem>() {
             bail!(content.span(), "pagebreaks are not allowed inside of containers");
         } else {
-            bail!(content.span(), "{} is not allowed here", content.func().name());
+            bail!(content.span(), "{} is not allowed here", content.elem().name());
         }
     }
 
@@ -759,7 +772,7 @@ impl<'a> ListBuilder<'a> {
                 .items
                 .elems()
                 .next()
-                .map_or(true, |first| first.func() == content.func())
+                .map_or(true, |first| first.elem() == content.elem())
         {
             self.items.push(Cow::Borrowed(content), styles);
             self.tight &= self.staged.drain(..).all(|(t, _)| !t.is::<ParbreakElem>());
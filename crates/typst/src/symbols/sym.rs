@@ -1,4 +1,4 @@
-use crate::foundations::{Module, Scope};
+use crate::foundations::{Deprecation, Module, Scope};
 use crate::symbols::{symbols, Symbol};
 
 /// A module with all general symbols.
@@ -7,9 +7,20 @@ pub fn sym() -> Module {
     for (name, symbol) in SYM {
         scope.define(*name, symbol.clone());
     }
+    deprecate(&mut scope);
     Module::new("sym", scope)
 }
 
+/// Mark the deprecated symbols in a scope that defines the general symbols.
+pub(crate) fn deprecate(scope: &mut Scope) {
+    scope.deprecate(
+        "diff",
+        Deprecation::new("use `partial` instead")
+            .with_until("0.12.0")
+            .with_replacement("partial"),
+    );
+}
+
 /// The list of general symbols.
 pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     // Control.
@@ -399,7 +410,7 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     // Calculus.
     infinity: '∞',
     oo: '∞',
-    diff: '∂', // deprecated (don't forget to delete later)
+    diff: '∂', // deprecated, see `deprecate`
     partial: '∂',
     gradient: '∇',
     nabla: '∇',
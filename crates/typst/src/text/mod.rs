@@ -8,6 +8,7 @@ mod lang;
 mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
+mod num;
 mod raw;
 mod shift;
 #[path = "smallcaps.rs"]
@@ -22,6 +23,7 @@ pub use self::item::*;
 pub use self::lang::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
+pub use self::num::*;
 pub use self::raw::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
@@ -35,7 +37,7 @@ use rustybuzz::{Feature, Tag};
 use smallvec::SmallVec;
 use ttf_parser::Rect;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, warning, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::Packed;
 use crate::foundations::{
@@ -67,6 +69,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<StrikeElem>();
     global.define_elem::<HighlightElem>();
     global.define_elem::<RawElem>();
+    global.define_elem::<NumElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<smallcaps>();
@@ -120,6 +123,13 @@ pub struct TextElem {
     /// This is Latin. \
     /// هذا عربي.
     /// ```
+    #[parse({
+        let font_list: Option<Spanned<FontList>> = args.named("font")?;
+        if let Some(list) = &font_list {
+            check_font_list(engine, list)?;
+        }
+        font_list.map(|font_list| font_list.v)
+    })]
     #[default(FontList(vec![FontFamily::new("Linux Libertine")]))]
     #[borrowed]
     #[ghost]
@@ -689,6 +699,23 @@ impl PlainText for Packed<TextElem> {
     }
 }
 
+/// Warn about font families that are not available and fail for those that
+/// the access policy does not permit.
+fn check_font_list(engine: &mut Engine, list: &Spanned<FontList>) -> SourceResult<()> {
+    let book = engine.world.book();
+    let policy = &engine.world.library().policy;
+    for family in &list.v {
+        policy.check_font(family.as_str()).at(list.span)?;
+        if !book.contains_family(family.as_str()) {
+            engine.tracer.warn(
+                warning!(list.span, "unknown font family: {}", family.as_str())
+                    .with_code("W0001"),
+            );
+        }
+    }
+    Ok(())
+}
+
 /// A lowercased font family like "arial".
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct FontFamily(EcoString);
@@ -0,0 +1,246 @@
+use std::hash::{Hash, Hasher};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, func, repr, scope, BigInt, Content, Decimal, IntoValue, Packed, Show,
+    Smart, Str, StyleChain,
+};
+use crate::text::{Lang, TextElem};
+
+/// Displays a number with the digit grouping and decimal separator of a
+/// language.
+///
+/// By default, the number follows the conventions of the
+/// [text language]($text.lang), so that numbers in tables and captions
+/// match the rest of the document. Digits are grouped in thousands and the
+/// decimal separator is chosen accordingly, for example `1,234.5` in English
+/// and `1.234,5` in German.
+///
+/// To get the formatted number as a string, for instance to process it
+/// further, use [`num.format`]($num.format).
+///
+/// # Example
+/// ```example
+/// #num(1234567.89) \
+/// #num(1234567.89, locale: "de") \
+/// #num(1234567.89, locale: "fr")
+///
+/// #set text(lang: "de")
+/// #set num(precision: 2)
+/// #table(
+///   columns: 2,
+///   [Rent], num(1250),
+///   [Power], num(84.5),
+/// )
+/// ```
+#[elem(scope, title = "Number", Show)]
+pub struct NumElem {
+    /// The number to display.
+    #[required]
+    pub value: Number,
+
+    /// The language whose conventions to follow, given as an ISO 639-1/2/3
+    /// language code like `{"de"}`. If `{auto}`, the
+    /// [text language]($text.lang) is used.
+    pub locale: Smart<Lang>,
+
+    /// How many digits to show after the decimal separator. The number is
+    /// rounded if necessary. If `{none}`, integers are shown without a
+    /// fractional part and other numbers with all of their digits.
+    pub precision: Option<usize>,
+}
+
+#[scope]
+impl NumElem {
+    /// Formats a number as a string with the digit grouping and decimal
+    /// separator of a language.
+    ///
+    /// ```example
+    /// #num.format(1234567.89, locale: "de") \
+    /// #num.format(0.5, locale: "fr", precision: 2)
+    /// ```
+    #[func(title = "Format Number")]
+    pub fn format(
+        /// The number to format.
+        value: Number,
+        /// The language whose conventions to follow.
+        #[named]
+        #[default(Lang::ENGLISH)]
+        locale: Lang,
+        /// How many digits to show after the decimal separator.
+        #[named]
+        #[default]
+        precision: Option<usize>,
+    ) -> StrResult<Str> {
+        format_number(value, locale, precision).map(Into::into)
+    }
+}
+
+impl Show for Packed<NumElem> {
+    #[typst_macros::time(name = "num", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let lang = self.locale(styles).unwrap_or_else(|| TextElem::lang_in(styles));
+        let text = format_number(self.value().clone(), lang, self.precision(styles))
+            .at(self.span())?;
+        Ok(TextElem::packed(text))
+    }
+}
+
+/// A number that can be displayed with [`NumElem`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    BigInt(BigInt),
+}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Int(v) => v.hash(state),
+            Self::Float(v) => v.to_bits().hash(state),
+            Self::Decimal(v) => v.hash(state),
+            Self::BigInt(v) => v.hash(state),
+        }
+    }
+}
+
+cast! {
+    Number,
+    self => match self {
+        Self::Int(v) => v.into_value(),
+        Self::Float(v) => v.into_value(),
+        Self::Decimal(v) => v.into_value(),
+        Self::BigInt(v) => v.into_value(),
+    },
+    v: i64 => Self::Int(v),
+    v: f64 => Self::Float(v),
+    v: Decimal => Self::Decimal(v),
+    v: BigInt => Self::BigInt(v),
+}
+
+/// Format a number with the separators of a language.
+fn format_number(
+    value: Number,
+    lang: Lang,
+    precision: Option<usize>,
+) -> StrResult<EcoString> {
+    let (negative, digits) = match value {
+        Number::Int(v) => {
+            let magnitude = v.unsigned_abs();
+            let digits = match precision {
+                Some(p) if p > 0 => eco_format!("{magnitude}.{:0>p$}", ""),
+                _ => eco_format!("{magnitude}"),
+            };
+            (v < 0, digits)
+        }
+        Number::Float(v) if v.is_nan() => return Ok("NaN".into()),
+        Number::Float(v) => {
+            let magnitude = v.abs();
+            let digits = match precision {
+                _ if v.is_infinite() => "∞".into(),
+                Some(p) => eco_format!("{magnitude:.p$}"),
+                None => eco_format!("{magnitude}"),
+            };
+            (v < 0.0, digits)
+        }
+        Number::Decimal(v) => {
+            let magnitude = match precision {
+                Some(p) => v.abs().round(p.try_into().unwrap_or(u32::MAX))?,
+                None => v.abs(),
+            };
+            (v < Decimal::ZERO, eco_format!("{magnitude}"))
+        }
+        Number::BigInt(v) => {
+            let magnitude = v.abs();
+            let digits = match precision {
+                Some(p) if p > 0 => eco_format!("{magnitude}.{:0>p$}", ""),
+                _ => eco_format!("{magnitude}"),
+            };
+            (v.is_negative(), digits)
+        }
+    };
+
+    let separators = Separators::of(lang);
+    let (integral, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+    let mut out = EcoString::new();
+    if negative {
+        out.push_str(repr::MINUS_SIGN);
+    }
+
+    let grouped = integral.len() >= 3 + separators.min_grouping;
+    for (i, c) in integral.chars().enumerate() {
+        if grouped && i > 0 && (integral.len() - i) % 3 == 0 {
+            out.push(separators.group);
+        }
+        out.push(c);
+    }
+
+    if !fraction.is_empty() {
+        out.push(separators.decimal);
+        out.push_str(fraction);
+    }
+
+    Ok(out)
+}
+
+/// The separators a language uses in numbers.
+struct Separators {
+    /// The separator between groups of thousands.
+    group: char,
+    /// The decimal separator.
+    decimal: char,
+    /// How many digits the highest group must have at least so that digits
+    /// are grouped at all.
+    min_grouping: usize,
+}
+
+impl Separators {
+    /// The separators for a language, following the Unicode CLDR.
+    fn of(lang: Lang) -> Self {
+        let (group, decimal, min_grouping) = match lang.as_str() {
+            "fr" => ('\u{202F}', ',', 1),
+            "es" => ('.', ',', 2),
+            "pl" => ('\u{A0}', ',', 2),
+            "de" | "nl" | "it" | "ca" | "da" | "el" | "gr" | "id" | "pt" | "ro"
+            | "sl" | "sr" | "tr" | "vi" => ('.', ',', 1),
+            "ru" | "ua" | "uk" | "cs" | "sk" | "sv" | "nb" | "nn" | "no" | "fi"
+            | "et" | "hu" | "sq" | "bg" | "lt" | "lv" => ('\u{A0}', ',', 1),
+            _ => (',', '.', 1),
+        };
+        Self { group, decimal, min_grouping }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[track_caller]
+    fn test(value: Number, lang: &str, precision: Option<usize>, expected: &str) {
+        let lang = Lang::from_str(lang).unwrap();
+        assert_eq!(format_number(value, lang, precision).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_number() {
+        test(Number::Float(1234567.89), "en", None, "1,234,567.89");
+        test(Number::Float(1234567.89), "de", None, "1.234.567,89");
+        test(Number::Float(1234567.89), "fr", None, "1\u{202F}234\u{202F}567,89");
+        test(Number::Int(-1234), "en", Some(2), "\u{2212}1,234.00");
+        test(Number::Int(1234), "es", None, "1234");
+        test(Number::Int(12345), "es", None, "12.345");
+        test(Number::Float(0.126), "de", Some(2), "0,13");
+        test(Number::Int(999), "en", None, "999");
+        let big = BigInt::parse("-100000000000000000000").unwrap();
+        test(Number::BigInt(big), "de", None, "\u{2212}100.000.000.000.000.000.000");
+    }
+}
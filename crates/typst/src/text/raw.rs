@@ -16,7 +16,9 @@ use crate::foundations::{
     PlainText, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, Value,
 };
 use crate::layout::{BlockElem, Em, HAlignment};
+use crate::loading::{load, ResourceKind};
 use crate::model::Figurable;
+use crate::syntax;
 use crate::syntax::{split_newlines, LinkedNode, Spanned};
 use crate::text::{
     FontFamily, FontList, Hyphenate, Lang, LinebreakElem, LocalName, Region,
@@ -24,7 +26,6 @@ use crate::text::{
 };
 use crate::util::option_eq;
 use crate::visualize::Color;
-use crate::{syntax, World};
 
 // Shorthand for highlighter closures.
 type StyleFn<'a> = &'a mut dyn FnMut(&LinkedNode, Range<usize>, synt::Style) -> Content;
@@ -703,7 +704,7 @@ fn parse_syntaxes(
         .iter()
         .map(|path| {
             let id = span.resolve_path(path).at(span)?;
-            engine.world.file(id).at(span)
+            load(engine.world, id, ResourceKind::Data).at(span)
         })
         .collect::<SourceResult<Vec<Bytes>>>()?;
 
@@ -736,7 +737,7 @@ fn parse_theme(
 
     // Load theme file.
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load(engine.world, id, ResourceKind::Data).at(span)?;
 
     // Check that parsing works.
     let _ = load_theme(&path, &data).at(span)?;
@@ -0,0 +1,38 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag with which an in-flight compilation or export can be aborted.
+///
+/// Clones share the flag, so an embedder can keep one clone and hand another
+/// one to the compilation, for instance through its
+/// [`World::cancelled`](crate::World::cancelled) implementation. Once
+/// cancelled, a token stays cancelled, so a fresh one is needed for the next
+/// compilation.
+#[derive(Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that the work using this token stops.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("CancellationToken")
+            .field(&self.is_cancelled())
+            .finish()
+    }
+}
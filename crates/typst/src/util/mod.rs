@@ -5,17 +5,19 @@ pub mod fat;
 #[macro_use]
 mod macros;
 mod bitset;
+mod cancel;
 mod deferred;
 mod pico;
 mod scalar;
 
 pub use self::bitset::BitSet;
+pub use self::cancel::CancellationToken;
 pub use self::deferred::Deferred;
 pub use self::pico::PicoStr;
 pub use self::scalar::Scalar;
 
 use std::fmt::{Debug, Formatter};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::iter::{Chain, Flatten, Rev};
 use std::num::NonZeroUsize;
 use std::ops::{Add, Deref, Div, Mul, Neg, Sub};
@@ -49,6 +51,17 @@ pub fn hash128<T: Hash + ?Sized>(value: &T) -> u128 {
     state.finish128().as_u128()
 }
 
+/// Calculate a 128-bit siphash of raw bytes.
+///
+/// Unlike with [`hash128`], the result does not depend on the pointer width
+/// or endianness of the platform, so it can be used for identifiers that
+/// are written into exported files.
+pub fn hash128_bytes(bytes: &[u8]) -> u128 {
+    let mut state = SipHasher13::new();
+    state.write(bytes);
+    state.finish128().as_u128()
+}
+
 /// An extra constant for [`NonZeroUsize`].
 pub trait NonZeroExt {
     /// The number `1`.
@@ -30,6 +30,9 @@ pub type Luma = palette::luma::Luma<encoding::Srgb, f32>;
 /// Equivalent of [`std::f32::EPSILON`] but for hue angles.
 const ANGLE_EPSILON: f32 = 1e-5;
 
+/// How far color components may exceed the sRGB gamut due to rounding.
+const GAMUT_EPSILON: f32 = 1e-4;
+
 /// A color in a specific color space.
 ///
 /// Typst supports:
@@ -1244,6 +1247,24 @@ impl Color {
         self.to_rgb().to_vec4().map(|x| (x * 255.0).round() as u8)
     }
 
+    /// Whether the color can be represented in sRGB without gamut mapping.
+    ///
+    /// Only Oklab and Oklch colors can lie outside of sRGB. They are mapped
+    /// into it when converted to another color space, which may noticeably
+    /// change their appearance.
+    pub fn is_in_srgb_gamut(self) -> bool {
+        let rgb = match self {
+            Self::Oklab(c) => LinearRgb::from_color_unclamped(c),
+            Self::Oklch(c) => {
+                LinearRgb::from_color_unclamped(Oklab::from_color_unclamped(c))
+            }
+            _ => return true,
+        };
+
+        let range = -GAMUT_EPSILON..=1.0 + GAMUT_EPSILON;
+        [rgb.red, rgb.green, rgb.blue].iter().all(|v| range.contains(v))
+    }
+
     pub fn to_space(self, space: ColorSpace) -> Self {
         match space {
             ColorSpace::Oklab => self.to_oklab(),
@@ -1963,4 +1984,14 @@ mod tests {
         test("hmmm", "color string contains non-hexadecimal letters");
         test("14B2AH", "color string contains non-hexadecimal letters");
     }
+
+    #[test]
+    fn test_srgb_gamut() {
+        let red = Color::from_u8(255, 0, 0, 255);
+        assert!(red.is_in_srgb_gamut());
+        assert!(red.to_oklab().is_in_srgb_gamut());
+        assert!(red.to_oklch().is_in_srgb_gamut());
+        assert!(!Color::Oklab(Oklab::new(0.5, 0.4, 0.0, 1.0)).is_in_srgb_gamut());
+        assert!(!Color::Oklab(Oklab::new(1.2, 0.0, 0.0, 1.0)).is_in_srgb_gamut());
+    }
 }
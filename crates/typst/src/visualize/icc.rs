@@ -0,0 +1,90 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{Args, Bytes};
+use crate::loading::{load, ResourceKind};
+use crate::syntax::Spanned;
+
+/// An ICC color profile.
+///
+/// Values of this type are cheap to clone and hash.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct IccProfile {
+    /// The raw profile data.
+    data: Bytes,
+    /// The color space the profile describes.
+    space: IccSpace,
+}
+
+impl IccProfile {
+    /// Parse an ICC profile from its raw data.
+    ///
+    /// Only the header is validated, the profile itself is passed through to
+    /// the exporters as-is.
+    pub fn new(data: Bytes) -> StrResult<Self> {
+        if data.len() < 128 || &data[36..40] != b"acsp" {
+            bail!("file is not a valid ICC profile");
+        }
+
+        let space = match &data[16..20] {
+            b"GRAY" => IccSpace::Gray,
+            b"RGB " => IccSpace::Rgb,
+            b"CMYK" => IccSpace::Cmyk,
+            other => bail!(
+                "unsupported ICC profile color space `{}`",
+                String::from_utf8_lossy(other).trim()
+            ),
+        };
+
+        Ok(Self { data, space })
+    }
+
+    /// The raw profile data.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// The color space the profile describes.
+    pub fn space(&self) -> IccSpace {
+        self.space
+    }
+
+    /// The number of color components in the profile's color space.
+    pub fn components(&self) -> usize {
+        match self.space {
+            IccSpace::Gray => 1,
+            IccSpace::Rgb => 3,
+            IccSpace::Cmyk => 4,
+        }
+    }
+}
+
+/// The color space described by an ICC profile.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IccSpace {
+    /// A single gray component.
+    Gray,
+    /// Red, green, and blue components.
+    Rgb,
+    /// Cyan, magenta, yellow, and key components.
+    Cmyk,
+}
+
+/// Parse an ICC profile path argument and load the file.
+pub(crate) fn parse_icc(
+    engine: &mut Engine,
+    args: &mut Args,
+) -> SourceResult<(Option<EcoString>, Option<Bytes>)> {
+    let Some(Spanned { v: path, span }) = args.named::<Spanned<EcoString>>("icc")? else {
+        return Ok((None, None));
+    };
+
+    let id = span.resolve_path(&path).at(span)?;
+    let data = load(engine.world, id, ResourceKind::Data).at(span)?;
+
+    // Check that the profile is usable.
+    IccProfile::new(data.clone()).at(span)?;
+
+    Ok((Some(path), Some(data)))
+}
@@ -23,18 +23,22 @@ use crate::layout::{
     Abs, Axes, FixedAlignment, Frame, FrameItem, LayoutSingle, Length, Point, Regions,
     Rel, Size,
 };
-use crate::loading::Readable;
+use crate::loading::{load, Readable, ResourceKind};
 use crate::model::Figurable;
 use crate::syntax::{Span, Spanned};
 use crate::text::{families, Lang, LocalName, Region};
 use crate::util::{option_eq, Numeric};
-use crate::visualize::Path;
+use crate::visualize::{parse_icc, IccProfile, Path};
 use crate::World;
 
 /// A raster or vector graphic.
 ///
 /// Supported formats are PNG, JPEG, GIF and SVG.
 ///
+/// In PDF export, SVG images are embedded as vector graphics instead of being
+/// rasterized. Their text is set in fonts that are available to Typst, falling
+/// back to the document's fonts if the requested family can't be found.
+///
 /// _Note:_ Work on SVG export is ongoing and there might be visual inaccuracies
 /// in the resulting PDF. Make sure to double-check embedded SVG images. If you
 /// have an issue, also feel free to report it on [GitHub][gh-svg].
@@ -59,7 +63,7 @@ pub struct ImageElem {
         let Spanned { v: path, span } =
             args.expect::<Spanned<EcoString>>("path to image file")?;
         let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
+        let data = load(engine.world, id, ResourceKind::Image).at(span)?;
         path
     )]
     #[borrowed]
@@ -86,6 +90,28 @@ pub struct ImageElem {
     /// How the image should adjust itself to a given area.
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// Path to an ICC profile describing the image's colors.
+    ///
+    /// The profile replaces any profile embedded in the image file and is
+    /// carried over into the exported PDF. Its color space must match the
+    /// image's color model. Only supported for raster images.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", icc: "sRGB.icc")
+    /// ```
+    #[parse(
+        let (icc_path, icc_data) = parse_icc(engine, args)?;
+        icc_path.map(Some)
+    )]
+    #[borrowed]
+    pub icc: Option<EcoString>,
+
+    /// The raw data of the ICC profile.
+    #[internal]
+    #[parse(icc_data.map(Some))]
+    #[borrowed]
+    pub icc_data: Option<Bytes>,
 }
 
 #[scope]
@@ -180,10 +206,16 @@ impl LayoutSingle for Packed<ImageElem> {
             }
         };
 
+        let icc = match self.icc_data(styles) {
+            Some(data) => Some(IccProfile::new(data.clone()).at(self.span())?),
+            None => None,
+        };
+
         let image = Image::with_fonts(
             data.clone().into(),
             format,
             self.alt(styles),
+            icc,
             engine.world,
             &families(styles).map(|s| s.into()).collect::<Vec<_>>(),
         )
@@ -347,16 +379,23 @@ impl Image {
         data: Bytes,
         format: ImageFormat,
         alt: Option<EcoString>,
+        icc: Option<IccProfile>,
         world: Tracked<dyn World + '_>,
         families: &[String],
     ) -> StrResult<Image> {
-        let kind = match format {
-            ImageFormat::Raster(format) => {
+        let kind = match (format, icc) {
+            (ImageFormat::Raster(format), None) => {
                 ImageKind::Raster(RasterImage::new(data, format)?)
             }
-            ImageFormat::Vector(VectorFormat::Svg) => {
+            (ImageFormat::Raster(format), Some(icc)) => {
+                ImageKind::Raster(RasterImage::with_icc(data, format, icc)?)
+            }
+            (ImageFormat::Vector(VectorFormat::Svg), None) => {
                 ImageKind::Svg(SvgImage::with_fonts(data, world, families)?)
             }
+            (ImageFormat::Vector(_), Some(_)) => {
+                bail!("ICC profiles are only supported for raster images")
+            }
         };
 
         Ok(Self(Arc::new(Prehashed::new(Repr { kind, alt }))))
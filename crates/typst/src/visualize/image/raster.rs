@@ -11,6 +11,7 @@ use image::{guess_format, ImageDecoder, ImageResult};
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{Bytes, Cast};
+use crate::visualize::IccProfile;
 
 /// A decoded raster image.
 #[derive(Clone, Hash)]
@@ -28,6 +29,26 @@ impl RasterImage {
     /// Decode a raster image.
     #[comemo::memoize]
     pub fn new(data: Bytes, format: RasterFormat) -> StrResult<RasterImage> {
+        Self::decode(data, format, None)
+    }
+
+    /// Decode a raster image and tag it with a custom ICC profile, which
+    /// replaces the profile embedded in the file (if any).
+    #[comemo::memoize]
+    pub fn with_icc(
+        data: Bytes,
+        format: RasterFormat,
+        icc: IccProfile,
+    ) -> StrResult<RasterImage> {
+        Self::decode(data, format, Some(icc))
+    }
+
+    /// Decode the image data, optionally overriding its ICC profile.
+    fn decode(
+        data: Bytes,
+        format: RasterFormat,
+        profile: Option<IccProfile>,
+    ) -> StrResult<RasterImage> {
         fn decode_with<'a, T: ImageDecoder<'a>>(
             decoder: ImageResult<T>,
         ) -> ImageResult<(image::DynamicImage, Option<Vec<u8>>)> {
@@ -46,6 +67,21 @@ impl RasterImage {
         }
         .map_err(format_image_error)?;
 
+        let icc = match profile {
+            Some(profile) => {
+                let channels = if dynamic.color().has_color() { 3 } else { 1 };
+                if profile.components() != channels {
+                    bail!(
+                        "ICC profile has {} color components, but the image has {}",
+                        profile.components(),
+                        channels,
+                    );
+                }
+                Some(profile.data().to_vec())
+            }
+            None => icc,
+        };
+
         Ok(Self(Arc::new(Repr { data, format, dynamic, icc })))
     }
 
@@ -82,9 +118,10 @@ impl RasterImage {
 
 impl Hash for Repr {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // The image is fully defined by data and format.
+        // The image is fully defined by data, format, and profile.
         self.data.hash(state);
         self.format.hash(state);
+        self.icc.hash(state);
     }
 }
 
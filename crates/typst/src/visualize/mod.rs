@@ -2,6 +2,7 @@
 
 mod color;
 mod gradient;
+mod icc;
 mod image;
 mod line;
 mod paint;
@@ -13,6 +14,7 @@ mod stroke;
 
 pub use self::color::*;
 pub use self::gradient::*;
+pub use self::icc::*;
 pub use self::image::*;
 pub use self::line::*;
 pub use self::paint::*;
@@ -232,6 +232,20 @@ impl Pattern {
         self
     }
 
+    /// Replace the pattern's rendered content, keeping its size and spacing.
+    pub fn with_frame(mut self, frame: Frame) -> Self {
+        if let Some(this) = Arc::get_mut(&mut self.0) {
+            this.frame = Prehashed::new(frame);
+        } else {
+            self.0 = Arc::new(Repr {
+                frame: Prehashed::new(frame),
+                ..self.0.as_ref().clone()
+            });
+        }
+
+        self
+    }
+
     /// Return the frame of the pattern.
     pub fn frame(&self) -> &Frame {
         &self.0.frame
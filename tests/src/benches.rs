@@ -1,12 +1,10 @@
-use comemo::{Prehashed, Track, Tracked};
+use comemo::Track;
 use iai::{black_box, main, Iai};
-use typst::diag::FileResult;
 use typst::eval::Tracer;
-use typst::foundations::{Bytes, Datetime};
-use typst::syntax::{FileId, Source};
-use typst::text::{Font, FontBook};
+use typst::syntax::Source;
+use typst::text::Font;
 use typst::visualize::Color;
-use typst::{Library, World};
+use typst::{MemoryWorld, World};
 use unscanny::Scanner;
 
 const TEXT: &str = include_str!("../typ/compiler/bench.typ");
@@ -56,79 +54,35 @@ fn bench_edit(iai: &mut Iai) {
 }
 
 fn bench_eval(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = world();
+    let source = world.main();
     let route = typst::engine::Route::default();
     let mut tracer = typst::eval::Tracer::new();
     iai.run(|| {
-        typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
-            .unwrap()
+        typst::eval::eval(
+            (&world as &dyn World).track(),
+            route.track(),
+            tracer.track_mut(),
+            &source,
+        )
+        .unwrap()
     });
 }
 
 fn bench_compile(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = world();
     let mut tracer = Tracer::new();
     iai.run(|| typst::compile(&world, &mut tracer));
 }
 
 fn bench_render(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = world();
     let mut tracer = Tracer::new();
     let document = typst::compile(&world, &mut tracer).unwrap();
     iai.run(|| typst_render::render(&document.pages[0].frame, 1.0, Color::WHITE))
 }
 
-struct BenchWorld {
-    library: Prehashed<Library>,
-    book: Prehashed<FontBook>,
-    font: Font,
-    source: Source,
-}
-
-impl BenchWorld {
-    fn new() -> Self {
-        let font = Font::new(FONT.into(), 0).unwrap();
-        let book = FontBook::from_fonts([&font]);
-
-        Self {
-            library: Prehashed::new(Library::default()),
-            book: Prehashed::new(book),
-            font,
-            source: Source::detached(TEXT),
-        }
-    }
-
-    fn track(&self) -> Tracked<dyn World> {
-        (self as &dyn World).track()
-    }
-}
-
-impl World for BenchWorld {
-    fn library(&self) -> &Prehashed<Library> {
-        &self.library
-    }
-
-    fn book(&self) -> &Prehashed<FontBook> {
-        &self.book
-    }
-
-    fn main(&self) -> Source {
-        self.source.clone()
-    }
-
-    fn source(&self, _: FileId) -> FileResult<Source> {
-        unimplemented!()
-    }
-
-    fn file(&self, _: FileId) -> FileResult<Bytes> {
-        unimplemented!()
-    }
-
-    fn font(&self, _: usize) -> Option<Font> {
-        Some(self.font.clone())
-    }
-
-    fn today(&self, _: Option<i64>) -> Option<Datetime> {
-        unimplemented!()
-    }
+fn world() -> MemoryWorld {
+    let font = Font::new(FONT.into(), 0).unwrap();
+    MemoryWorld::new(TEXT).with_fonts([font])
 }
@@ -35,6 +35,10 @@ pub struct TestConfig {
     ///
     /// Defaults to `false`, can be enabled with `Autocomplete: true`.
     pub validate_autocomplete: Option<bool>,
+    /// Warnings about overflowing content will be emitted.
+    ///
+    /// Defaults to `false`, can be enabled with `Overflow: true`.
+    pub warn_overflow: Option<bool>,
 }
 
 /// Parsing error when the metadata is invalid.
@@ -81,6 +85,9 @@ pub struct Annotation {
     pub range: Option<Range<usize>>,
     /// The raw text after the annotation.
     pub text: EcoString,
+    /// For definition annotations of the form `POS -> RANGE`, the range of
+    /// the expected definition in the same file.
+    pub target: Option<Range<usize>>,
 }
 
 /// The different kinds of in-test annotations.
@@ -91,6 +98,12 @@ pub enum AnnotationKind {
     Hint,
     AutocompleteContains,
     AutocompleteExcludes,
+    Lint,
+    Definition,
+    HoverType,
+    HoverValue,
+    HoverDocs,
+    Symbol,
 }
 
 impl AnnotationKind {
@@ -102,8 +115,34 @@ impl AnnotationKind {
             AnnotationKind::Hint => "Hint",
             AnnotationKind::AutocompleteContains => "Autocomplete contains",
             AnnotationKind::AutocompleteExcludes => "Autocomplete excludes",
+            AnnotationKind::Lint => "Lint",
+            AnnotationKind::Definition => "Definition",
+            AnnotationKind::HoverType => "Hover type",
+            AnnotationKind::HoverValue => "Hover value",
+            AnnotationKind::HoverDocs => "Hover docs",
+            AnnotationKind::Symbol => "Symbol",
         }
     }
+
+    /// Whether this annotation is compared to the diagnostics of the
+    /// compiler.
+    pub fn is_diagnostic(self) -> bool {
+        matches!(self, Self::Error | Self::Warning | Self::Hint)
+    }
+
+    /// Whether this annotation refers to a cursor position instead of a
+    /// range.
+    pub fn is_cursor(self) -> bool {
+        matches!(
+            self,
+            Self::AutocompleteContains
+                | Self::AutocompleteExcludes
+                | Self::Definition
+                | Self::HoverType
+                | Self::HoverValue
+                | Self::HoverDocs
+        )
+    }
 }
 
 impl FromStr for AnnotationKind {
@@ -116,6 +155,12 @@ impl FromStr for AnnotationKind {
             "Hint" => AnnotationKind::Hint,
             "Autocomplete contains" => AnnotationKind::AutocompleteContains,
             "Autocomplete excludes" => AnnotationKind::AutocompleteExcludes,
+            "Lint" => AnnotationKind::Lint,
+            "Definition" => AnnotationKind::Definition,
+            "Hover type" => AnnotationKind::HoverType,
+            "Hover value" => AnnotationKind::HoverValue,
+            "Hover docs" => AnnotationKind::HoverDocs,
+            "Symbol" => AnnotationKind::Symbol,
             _ => return Err("invalid annotatino"),
         })
     }
@@ -157,6 +202,11 @@ pub fn parse_part_metadata(
                     &mut config.validate_autocomplete,
                     &mut invalid_data,
                 ),
+                "Overflow" => validate_set_annotation(
+                    value,
+                    &mut config.warn_overflow,
+                    &mut invalid_data,
+                ),
                 annotation_key => {
                     let Ok(kind) = AnnotationKind::from_str(annotation_key) else {
                         continue;
@@ -164,13 +214,27 @@ pub fn parse_part_metadata(
                     let mut s = Scanner::new(value);
                     let range = parse_range(&mut s, i, source);
                     let rest = if range.is_some() { s.after() } else { s.string() };
-                    let message = rest
+                    let mut message: EcoString = rest
                         .trim()
                         .replace("VERSION", &PackageVersion::compiler().to_string())
                         .into();
 
+                    // The definition may be in the same file, in another
+                    // file, or not exist.
+                    let mut target = None;
+                    if kind == AnnotationKind::Definition {
+                        let rest = message.trim_start_matches("->").trim();
+                        let mut s = Scanner::new(rest);
+                        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                            target = parse_range(&mut s, i, source);
+                            message = EcoString::new();
+                        } else {
+                            message = rest.into();
+                        }
+                    }
+
                     let annotation =
-                        Annotation { kind, range: range.clone(), text: message };
+                        Annotation { kind, range: range.clone(), text: message, target };
 
                     if is_header {
                         invalid_data.push(InvalidMetadata::InvalidAnnotation(
@@ -182,25 +246,21 @@ pub fn parse_part_metadata(
                         continue;
                     }
 
-                    if matches!(
-                        kind,
-                        AnnotationKind::AutocompleteContains
-                            | AnnotationKind::AutocompleteExcludes
-                    ) {
+                    if kind.is_cursor() {
                         if let Some(range) = range {
                             if range.start != range.end {
                                 invalid_data.push(InvalidMetadata::InvalidAnnotation(
                                     annotation,
-                                    "Error: found range in Autocomplete annotation where range.start != range.end, range.end would be ignored."
-                                        .to_string()
+                                    format!("Error: found range in {kind} annotation where range.start != range.end, range.end would be ignored.")
                                     ));
                                 continue;
                             }
                         } else {
                             invalid_data.push(InvalidMetadata::InvalidAnnotation(
                                 annotation,
-                                "Error: autocomplete annotation but no range specified"
-                                    .to_string(),
+                                format!(
+                                    "Error: {kind} annotation but no range specified"
+                                ),
                             ));
                             continue;
                         }
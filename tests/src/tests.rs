@@ -26,16 +26,20 @@ use std::{env, fs};
 
 use clap::Parser;
 use comemo::{Prehashed, Track};
+use ecow::EcoString;
 use oxipng::{InFile, Options, OutFile};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use tiny_skia as sk;
 use typst::diag::{bail, FileError, FileResult, Severity, SourceDiagnostic, StrResult};
+use typst::engine::Limits;
 use typst::eval::Tracer;
-use typst::foundations::{func, Bytes, Datetime, NoneValue, Repr, Smart, Value};
+use typst::foundations::{
+    dict, func, Bytes, Datetime, Deprecation, NoneValue, Repr, Smart, Value,
+};
 use typst::introspection::Meta;
 use typst::layout::{Abs, Frame, FrameItem, Margin, Page, PageElem, Transform};
 use typst::model::Document;
-use typst::syntax::{FileId, Source, SyntaxNode, VirtualPath};
+use typst::syntax::{FileId, Source, Span, SyntaxNode, VirtualPath};
 use typst::text::{Font, FontBook, TextElem, TextSize};
 use typst::visualize::Color;
 use typst::{Library, World, WorldExt};
@@ -226,8 +230,17 @@ fn library() -> Library {
 
     // Set page width to 120pt with 10pt margins, so that the inner page is
     // exactly 100pt wide. Page height is unbounded and font size is 10pt so
-    // that it multiplies to nice round numbers.
-    let mut lib = Library::default();
+    // that it multiplies to nice round numbers. Some inputs are provided to
+    // test `sys.inputs` and the limits are lowered to test that they can be
+    // configured.
+    let mut lib = Library::builder()
+        .with_inputs(dict! { "name" => "Typst", "draft" => "true" })
+        .with_limits(Limits {
+            call_depth: 64,
+            iterations: 5000,
+            value_size: 1 << 24,
+        })
+        .build();
     lib.styles
         .set(PageElem::set_width(Smart::Custom(Abs::pt(120.0).into())));
     lib.styles.set(PageElem::set_height(Smart::Auto));
@@ -247,6 +260,15 @@ fn library() -> Library {
         .scope_mut()
         .define("forest", Color::from_u8(0x43, 0xA1, 0x27, 0xFF));
 
+    // Hook up a deprecated definition to test deprecations with.
+    lib.global
+        .scope_mut()
+        .define("old-forest", Color::from_u8(0x43, 0xA1, 0x27, 0xFF));
+    lib.global.scope_mut().deprecate(
+        "old-forest",
+        Deprecation::new("use `forest` instead").with_replacement("forest"),
+    );
+
     lib
 }
 
@@ -606,7 +628,17 @@ fn test_part(
         print_model(world, &source, output);
     }
 
+    let metadata = parse_part_metadata(&source, false);
+    let warn_overflow = metadata
+        .as_ref()
+        .ok()
+        .and_then(|metadata| metadata.config.warn_overflow)
+        .unwrap_or(header_configuration.warn_overflow.unwrap_or(false));
+
     let mut tracer = Tracer::new();
+    if warn_overflow {
+        tracer.warn_overflow();
+    }
     let (mut frames, diagnostics) = match typst::compile(world, &mut tracer) {
         Ok(document) => (document.pages, tracer.warnings()),
         Err(errors) => {
@@ -616,7 +648,6 @@ fn test_part(
         }
     };
 
-    let metadata = parse_part_metadata(&source, false);
     match metadata {
         Ok(metadata) => {
             let mut ok = true;
@@ -649,13 +680,7 @@ fn test_part(
             let diagnostic_annotations = metadata
                 .annotations
                 .iter()
-                .filter(|a| {
-                    !matches!(
-                        a.kind,
-                        AnnotationKind::AutocompleteContains
-                            | AnnotationKind::AutocompleteExcludes
-                    )
-                })
+                .filter(|a| a.kind.is_diagnostic())
                 .cloned()
                 .collect::<HashSet<_>>();
 
@@ -696,6 +721,8 @@ fn test_part(
                 );
             }
 
+            test_ide(output, world, &source, line, i, &mut ok, &metadata.annotations);
+
             (ok, compare_ref, frames)
         }
         Err(invalid_data) => {
@@ -784,6 +811,184 @@ fn test_autocomplete<'a>(
     }
 }
 
+/// Compare the results of the IDE functions with the annotations for them.
+///
+/// Lint and symbol annotations must match the lints and symbols of the part
+/// exactly, if there are any. Definition and hover annotations check the
+/// result at their cursor.
+fn test_ide(
+    output: &mut String,
+    world: &TestWorld,
+    source: &Source,
+    line: usize,
+    i: usize,
+    ok: &mut bool,
+    annotations: &HashSet<Annotation>,
+) {
+    // Lints and symbols are compared as a whole.
+    for kind in [AnnotationKind::Lint, AnnotationKind::Symbol] {
+        let expected: HashSet<_> =
+            annotations.iter().filter(|a| a.kind == kind).cloned().collect();
+        if expected.is_empty() {
+            continue;
+        }
+
+        let actual: HashSet<_> = match kind {
+            AnnotationKind::Lint => {
+                // Check for deprecations with the test library, which has
+                // deprecated definitions of its own.
+                let mut linter = typst_ide::Linter::new();
+                linter
+                    .disable("deprecated")
+                    .register(typst_ide::Deprecated::new((**world.library()).clone()));
+                linter
+                    .lint(source)
+                    .iter()
+                    .map(|warning| Annotation {
+                        kind,
+                        range: world.range(warning.span),
+                        text: warning.message.clone(),
+                        target: None,
+                    })
+                    .collect()
+            }
+            _ => {
+                let mut symbols = vec![];
+                flatten_symbols(&typst_ide::document_symbols(source), "", &mut symbols);
+                symbols
+                    .into_iter()
+                    .map(|(span, text)| Annotation {
+                        kind,
+                        range: world.range(span),
+                        text,
+                        target: None,
+                    })
+                    .collect()
+            }
+        };
+
+        let mut unexpected = actual.difference(&expected).collect::<Vec<_>>();
+        let mut missing = expected.difference(&actual).collect::<Vec<_>>();
+        unexpected.sort_by_key(|a| a.range.as_ref().map(|r| r.start));
+        missing.sort_by_key(|a| a.range.as_ref().map(|r| r.start));
+        if !unexpected.is_empty() || !missing.is_empty() {
+            writeln!(output, "  Subtest {i} does not match expected {kind}s.").unwrap();
+            *ok = false;
+        }
+        for annotation in unexpected {
+            write!(output, "    Not annotated | ").unwrap();
+            print_annotation(output, source, line, annotation);
+        }
+        for annotation in missing {
+            write!(output, "    Not found     | ").unwrap();
+            print_annotation(output, source, line, annotation);
+        }
+    }
+
+    let mut mismatch = |output: &mut String, annotation: &Annotation, actual: &str| {
+        writeln!(output, "  Subtest {i} does not match expected IDE results.").unwrap();
+        write!(output, "    for annotation | ").unwrap();
+        print_annotation(output, source, line, annotation);
+        writeln!(output, "    but found      | {actual}").unwrap();
+        *ok = false;
+    };
+
+    for annotation in annotations {
+        let Some(cursor) = annotation.range.as_ref().map(|range| range.start) else {
+            continue;
+        };
+
+        match annotation.kind {
+            AnnotationKind::Definition => {
+                let span = typst_ide::definition(world, source, cursor);
+                let range = span.and_then(|span| world.range(span));
+                let matches = match (&annotation.target, span, &range) {
+                    (Some(target), Some(span), Some(range)) => {
+                        span.id() == Some(source.id()) && range == target
+                    }
+                    (None, None, _) => annotation.text == "none",
+                    (None, Some(span), Some(range)) => {
+                        span.id() != Some(source.id())
+                            && annotation.text == foreign_range(world, span, range)
+                    }
+                    _ => false,
+                };
+
+                if !matches {
+                    let actual = match (span, &range) {
+                        (Some(span), Some(range)) if span.id() == Some(source.id()) => {
+                            format_range(source, line, range)
+                        }
+                        (Some(span), Some(range)) => foreign_range(world, span, range),
+                        _ => "none".into(),
+                    };
+                    mismatch(output, annotation, &actual);
+                }
+            }
+            AnnotationKind::HoverType
+            | AnnotationKind::HoverValue
+            | AnnotationKind::HoverDocs => {
+                let hover = typst_ide::hover(world, source, cursor);
+                let (field, matches) = match annotation.kind {
+                    AnnotationKind::HoverType => {
+                        let ty = hover.as_ref().and_then(|h| h.ty.clone());
+                        let ty = ty.unwrap_or_else(|| "none".into());
+                        (ty.clone(), ty == annotation.text)
+                    }
+                    AnnotationKind::HoverValue => {
+                        let value = hover.as_ref().and_then(|h| h.value.clone());
+                        let value = value.unwrap_or_else(|| "none".into());
+                        (value.clone(), value == annotation.text)
+                    }
+                    _ => {
+                        let docs = hover.as_ref().and_then(|h| h.docs.clone());
+                        let docs = docs.unwrap_or_else(|| "none".into());
+                        (docs.clone(), docs.contains(annotation.text.as_str()))
+                    }
+                };
+                if !matches {
+                    mismatch(output, annotation, &field);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Flatten document symbols into their spans and descriptions of the form
+/// `{kind} {path}`, where the path contains the names of all parents,
+/// separated by ` > `.
+fn flatten_symbols(
+    symbols: &[typst_ide::DocumentSymbol],
+    parent: &str,
+    output: &mut Vec<(Span, EcoString)>,
+) {
+    for symbol in symbols {
+        let kind = match symbol.kind {
+            typst_ide::SymbolKind::Heading(level) => format!("heading {level}"),
+            typst_ide::SymbolKind::Label => "label".into(),
+            typst_ide::SymbolKind::Function => "function".into(),
+            typst_ide::SymbolKind::ShowRule => "show".into(),
+        };
+        let path = if parent.is_empty() {
+            symbol.name.to_string()
+        } else {
+            format!("{parent} > {}", symbol.name)
+        };
+        output.push((symbol.span, format!("{kind} {path}").into()));
+        flatten_symbols(&symbol.children, &path, output);
+    }
+}
+
+/// Format a range in another file than the test as `{path} {range}`, with
+/// lines counted from the start of the file.
+fn foreign_range(world: &TestWorld, span: Span, range: &Range<usize>) -> String {
+    let Some(id) = span.id() else { return "none".into() };
+    let Ok(source) = world.source(id) else { return "none".into() };
+    let path = id.vpath().as_rootless_path().to_string_lossy().replace('\\', "/");
+    format!("{path} {}", format_range(&source, 0, range))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn test_diagnostics<'a>(
     output: &mut String,
@@ -816,6 +1021,7 @@ fn test_diagnostics<'a>(
             },
             range: world.range(diagnostic.span),
             text: diagnostic.message.replace("\\", "/"),
+            target: None,
         };
 
         if validate_hints {
@@ -824,6 +1030,7 @@ fn test_diagnostics<'a>(
                     kind: AnnotationKind::Hint,
                     text: hint.clone(),
                     range: annotation.range.clone(),
+                    target: None,
                 });
             }
         }
@@ -876,18 +1083,27 @@ fn print_annotation(
     line: usize,
     annotation: &Annotation,
 ) {
-    let Annotation { range, text, kind } = annotation;
+    let Annotation { range, text, kind, target } = annotation;
     write!(output, "{kind}: ").unwrap();
     if let Some(range) = range {
-        let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
-        let start_col = 1 + source.byte_to_column(range.start).unwrap();
-        let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
-        let end_col = 1 + source.byte_to_column(range.end).unwrap();
-        write!(output, "{start_line}:{start_col}-{end_line}:{end_col}: ").unwrap();
+        write!(output, "{}: ", format_range(source, line, range)).unwrap();
+    }
+    if let Some(target) = target {
+        write!(output, "-> {}", format_range(source, line, target)).unwrap();
     }
     writeln!(output, "{text}").unwrap();
 }
 
+/// Format a range in a source file as `{line}:{col}-{line}:{col}`, with lines
+/// offset by `line`.
+fn format_range(source: &Source, line: usize, range: &Range<usize>) -> String {
+    let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
+    let start_col = 1 + source.byte_to_column(range.start).unwrap();
+    let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
+    let end_col = 1 + source.byte_to_column(range.end).unwrap();
+    format!("{start_line}:{start_col}-{end_line}:{end_col}")
+}
+
 /// Pseudorandomly edit the source file and test whether a reparse produces the
 /// same result as a clean parse.
 ///
@@ -236,6 +236,43 @@
 #test((1, 2).intersperse("a"), (1, "a", 2))
 #test((1, 2, "b").intersperse("a"), (1, "a", 2, "a", "b"))
 
+---
+// Test the `chunks` method.
+#test(().chunks(10), ())
+#test((1, 2, 3).chunks(10), ((1, 2, 3),))
+#test((1, 2, 3, 4, 5, 6).chunks(3), ((1, 2, 3), (4, 5, 6)))
+#test((1, 2, 3, 4, 5, 6, 7, 8).chunks(3), ((1, 2, 3), (4, 5, 6), (7, 8)))
+#test((1, 2, 3, 4, 5, 6, 7, 8).chunks(3, exact: true), ((1, 2, 3), (4, 5, 6)))
+#test((1, 2).chunks(3, exact: true), ())
+
+---
+// Error: 19-20 number must be positive
+#(1, 2, 3).chunks(0)
+
+---
+// Test the `shuffle` method.
+#let values = range(10)
+#test(values.shuffle(42), values.shuffle(42))
+#test(values.shuffle(42).sorted(), values)
+#test(values.shuffle("a").len(), 10)
+#test(().shuffle(1), ())
+
+---
+// Test the `sample` method.
+#let values = range(20)
+#test(values.sample(42, 5), values.sample(42, 5))
+#test(values.sample(42, 5).len(), 5)
+#test(values.sample(42, 5).dedup().len(), 5)
+#test(values.sample(42, 20).sorted(), values)
+#test(values.sample("a", 0), ())
+#for x in values.sample(7, 10) {
+  test(x in values, true)
+}
+
+---
+// Error: 2-24 cannot sample 4 items from an array of length 3
+#(1, 2, 3).sample(1, 4)
+
 ---
 // Test the `sorted` method.
 #test(().sorted(), ())
@@ -141,6 +141,39 @@
 // Error: 2-3 unclosed delimiter
 #{
 
+---
+// An unclosed block ends before the next line that isn't indented further
+// than the one that opened it, so the rest isn't parsed as code.
+// Error: 13-14 unclosed delimiter
+#let f(x) = {
+  if x { 1 } else { 2 }
+
+= Heading
+Some *text* follows.
+
+---
+// A nested unclosed block ends at its own boundary and the enclosing block
+// continues after it.
+// Error: 2-3 unclosed delimiter
+// Error: 2:11-2:12 unclosed delimiter
+#{
+  let x = {
+    1
+  x
+
+= Heading
+
+---
+// Closed blocks nested in code blocks don't end the enclosing block.
+#let f(x) = { if x { "a" } ; "b" }
+#test(f(true), "ab")
+#let g(x) = {
+  if x { "a" } else { "c" }
+  let y = { "b" }
+  y
+}
+#test(g(true), "ab")
+
 ---
 // Error: 2-3 unexpected closing brace
 #}
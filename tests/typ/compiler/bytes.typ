@@ -30,3 +30,28 @@
 ---
 // Error: 8-15 expected bytes, array, or version, found string
 #array("hello")
+
+---
+// Test hashing.
+#test(bytes("Hello").sha256().hex(), "185f8db32271fe25f561a6fc938b2e264306ec304eda518007d1764826381969")
+#test(bytes(()).sha256().hex(), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+#test(bytes("Hello").sha256().len(), 32)
+#test(bytes("Hello").crc32(), 4157704578)
+#test(bytes(()).crc32(), 0)
+
+---
+// Test encoding.
+#test(bytes("Hello 😃").base64(), "SGVsbG8g8J+Ygw==")
+#test(str(bytes.from-base64("SGVsbG8g8J+Ygw==")), "Hello 😃")
+#test(bytes(()).base64(), "")
+#test(bytes((1, 171, 255)).hex(), "01abff")
+#test(bytes.from-hex("01abFF"), bytes((1, 171, 255)))
+#test(bytes.from-hex(""), bytes(()))
+
+---
+// Error: 2-23 hexadecimal string must have an even number of digits
+#bytes.from-hex("abc")
+
+---
+// Error: 2-24 invalid hexadecimal digits: zz
+#bytes.from-hex("01zz")
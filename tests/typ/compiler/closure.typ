@@ -215,3 +215,79 @@
 ---
 // Error: 11 expected comma
 #let foo(_: 3) = none
+
+---
+// Test type annotations of parameters.
+#let scale(factor as float, size: 1pt as length) = factor * size
+#test(scale(2), 2pt)
+#test(scale(0.5, size: 4pt), 2pt)
+
+#let wrap(body as content) = body
+#test(wrap("hi"), [hi])
+
+#let pick(x: none as (int, none)) = x
+#test(pick(), none)
+#test(pick(x: none), none)
+#test(pick(x: 3), 3)
+
+#let first((a, b) as array) = a
+#test(first((1, 2)), 1)
+
+#let shout = (text as str) => upper(text) + "!"
+#test(shout("hey"), "HEY!")
+
+---
+#let f(x as int) = x
+
+// Error: 4-7 expected integer, found string
+#f("a")
+
+---
+#let f(size: 1pt as length) = size
+
+// Error: 10-12 expected length, found integer: a length needs a unit - did you mean 12pt?
+#f(size: 12)
+
+---
+#let f(x: none as (int, str, none)) = x
+
+// Error: 7-11 expected integer, string, or none, found boolean
+#f(x: true)
+
+---
+// Error: 13-16 expected type or array of types, found integer
+#let f(x as 123) = x
+
+---
+// Error: 5-11 type annotations are only allowed on parameters
+#(1 as int)
+
+---
+// Error: 9-15 type annotations are only allowed on parameters
+#let (a as int, b) = (1, 2)
+
+---
+// Error: 13-19 type annotations are only allowed on parameters
+#calc.abs(1 as int)
+
+---
+// Test memoized functions.
+#let fib(n) = if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+#let fast = fib.memo()
+#test(fast(60), 1548008755920)
+#test(fast.memo() == fast, true)
+#test(repr(fast), "fib")
+#test(((x, y: 1) => x + y).memo()(1, y: 2), 3)
+#test(calc.pow.memo()(2, 10), 1024)
+
+---
+// Memoized functions report argument errors at the call site.
+#let f(x as int) = x
+// Error: 10-15 expected integer, found string
+#f.memo()("a")
+
+---
+// Failed calls are cached, too, so the error is only produced once.
+// Error: 25-38 panicked with: "boom"
+#let g(n) = if n == 0 { panic("boom") } else { g(n - 1) + g(n - 1) }
+#g.memo()(40)
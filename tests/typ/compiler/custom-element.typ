@@ -0,0 +1,157 @@
+// Test user-defined elements.
+// Ref: false
+
+---
+// Test field access and defaults.
+#let task = element(
+  "task",
+  fields: (body: content, done: (type: bool, default: false)),
+)
+
+#let t = task("Write")
+#test(t.body, [Write])
+#test(t.done, false)
+#test(t.has("done"), true)
+#test(t.has("size"), false)
+#test(t.func(), task)
+#test(t.fields(), (body: [Write], done: false))
+#test(task(done: true)[Run].done, true)
+#test(repr(task), "task")
+
+---
+// Test show rules and where selectors.
+#let badge = element(
+  "badge",
+  fields: (label: str, level: (type: int, default: 1)),
+)
+
+#show badge: it => {
+  test(it.label, "new")
+  [Badge]
+}
+
+#show badge.where(level: 2): it => {
+  test(it.level, 2)
+  [Important badge]
+}
+
+#badge("new")
+#badge("new", level: 2)
+
+---
+// Test queries.
+#let note = element("note", fields: (level: (type: int, default: 1)))
+
+#note()
+#note(level: 2)
+#note(level: 2)
+
+#locate(loc => {
+  test(query(note, loc).len(), 3)
+  test(query(note.where(level: 2), loc).len(), 2)
+})
+
+---
+// Test that elements with the same name are distinct.
+#let a = element("thing")
+#let b = element("thing")
+#test(a == b, false)
+#test(a() == a(), true)
+
+---
+// Test the display function.
+#let tag = element(
+  "tag",
+  fields: (name: str, color: (type: color, default: none)),
+  display: it => box(fill: it.color, it.name),
+)
+
+#tag("draft")
+#tag("final", color: green)
+
+---
+#let badge = element("badge", fields: (label: str))
+
+// Error: 8-9 expected string, found integer
+#badge(1)
+
+---
+#let badge = element("badge", fields: (label: str))
+
+// Error: 7-9 missing argument: label
+#badge()
+
+---
+#let badge = element("badge", fields: (label: str))
+
+// Error: 18-27 element `badge` does not have field `size`
+#show badge.where(size: 1): none
+
+---
+#let badge = element("badge", fields: (label: str))
+
+// Error: 6-11 only element functions can be used in set rules
+#set badge(label: "x")
+
+---
+// Error: 9-57 invalid default for field `size`: expected integer, found string
+#element("x", fields: (size: (type: int, default: "1")))
+
+---
+// Test operator overloading.
+#let vec = element(
+  "vec",
+  fields: (x: int, y: int),
+  ops: (
+    add: (a, b) => vec(a.x + b.x, a.y + b.y),
+    sub: (a, b) => vec(a.x - b.x, a.y - b.y),
+    mul: (a, b) => if type(a) == int {
+      vec(a * b.x, a * b.y)
+    } else {
+      vec(a.x * b, a.y * b)
+    },
+    neg: v => vec(-v.x, -v.y),
+    compare: (a, b) => (a.x * a.x + a.y * a.y) - (b.x * b.x + b.y * b.y),
+    repr: v => "vec(" + str(v.x) + ", " + str(v.y) + ")",
+  ),
+)
+
+#test(vec(1, 2) + vec(3, 4), vec(4, 6))
+#test(vec(3, 4) - vec(1, 2), vec(2, 2))
+#test(vec(1, 2) * 3, vec(3, 6))
+#test(2 * vec(1, 2), vec(2, 4))
+#test(-vec(1, 2), vec(-1, -2))
+#test(vec(1, 0) < vec(1, 1), true)
+#test(vec(2, 2) >= vec(1, 1), true)
+#test(vec(3, 4) == vec(4, 3), true)
+#test(vec(3, 4) != vec(1, 1), true)
+#test(repr(vec(1, 2)), "vec(1, 2)")
+
+#let v = vec(1, 1)
+#(v += vec(1, 2))
+#(v *= 2)
+#test(v, vec(4, 6))
+
+---
+// Test that operators which are not overloaded keep working as before.
+#let point = element("point", fields: (x: int), ops: (add: (a, b) => a.x + b.x))
+#test(point(1) + point(2), 3)
+#test(point(1) == point(1), true)
+#test(point(1) == point(2), false)
+#test(repr(point(1)), "point(x: 1)")
+
+---
+#let point = element("point", fields: (x: int))
+
+// Error: 3-22 cannot multiply content with content
+#(point(1) * point(2))
+
+---
+#let point = element("point", fields: (x: int), ops: (compare: (a, b) => "less"))
+
+// Error: 3-22 expected integer, found string
+#(point(1) < point(2))
+
+---
+// Error: 2-58 unknown operator `pow`
+#element("point", fields: (x: int), ops: (pow: calc.pow))
@@ -78,6 +78,14 @@
 #test(for v in "" [], none)
 #test(type(for v in "1" []), content)
 
+---
+// Nested destructuring.
+#let sums = ()
+#for (k, (a, b)) in (x: (1, 2), y: (3, 4)) {
+  sums.push((k, a + b))
+}
+#test(sums, (("x", 3), ("y", 7)))
+
 ---
 // Uniterable expression.
 // Error: 11-15 cannot loop over boolean
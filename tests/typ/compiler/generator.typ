@@ -0,0 +1,79 @@
+// Test generators.
+// Ref: false
+
+---
+// Test ranges.
+#test(generator.range(1, 1000000000).take(5).collect(), (1, 2, 3, 4, 5))
+#test(generator.range(0, 10, step: 3).collect(), (0, 3, 6, 9))
+#test(generator.range(5, 0, step: -2).collect(), (5, 3, 1))
+#test(generator.range(3, 3).collect(), ())
+#test(generator.range(10).take(3).collect(), (10, 11, 12))
+#test(type(generator.range(1)), generator)
+#test(repr(generator.range(1)), "generator(..)")
+
+---
+// Test generators from functions and arrays.
+#test(generator(i => if i < 3 { i * i }).collect(), (0, 1, 4))
+#test(generator((1, 2, 3)).collect(), (1, 2, 3))
+#test(generator(()).collect(), ())
+
+---
+// Test unfolding.
+#let fib = generator.unfold((0, 1), ((a, b)) => (a, (b, a + b)))
+#test(fib.take(8).collect(), (0, 1, 1, 2, 3, 5, 8, 13))
+#test(generator.unfold(3, n => if n > 0 { (n, n - 1) }).collect(), (3, 2, 1))
+
+---
+// Test lazy adapters.
+#let naturals = generator.range(1)
+#test(naturals.map(n => n * n).take(4).collect(), (1, 4, 9, 16))
+#test(naturals.filter(calc.even).take(3).collect(), (2, 4, 6))
+#test(naturals.skip(5).take(2).collect(), (6, 7))
+#test(naturals.take(2).enumerate().collect(), ((0, 1), (1, 2)))
+#test(naturals.take(2).enumerate(start: 1).collect(), ((1, 1), (2, 2)))
+#test(generator((1, 2)).skip(5).collect(), ())
+
+---
+// Test that adapters only call functions for the values they need.
+#let g = generator.range(1).map(n => n * 10)
+#test(g.take(3).collect(), (10, 20, 30))
+#test(g.find(n => n > 45), 50)
+#test(g.first(), 10)
+#test(generator((1, 2, 3)).find(n => n > 5), none)
+#test(generator.range(1, 5).fold(0, (acc, n) => acc + n), 10)
+
+---
+// Test for loops.
+#let out = ()
+#for n in generator.range(1).map(n => n * n) {
+  if n > 30 { break }
+  out.push(n)
+}
+#test(out, (1, 4, 9, 16, 25))
+
+#let pairs = ()
+#for (i, v) in generator(("a", "b")).enumerate() {
+  pairs.push(str(i) + v)
+}
+#test(pairs, ("0a", "1b"))
+
+---
+// Error: 2-31 generator is empty
+#generator.range(0, 0).first()
+
+---
+// Error: 2-26 step must not be zero
+#generator.range(step: 0)
+
+---
+// Error: 2-47 generator produced too many values
+// Hint: 2-47 generators without a known end may produce at most 5000 values
+#generator.range(1).filter(n => n < 0).first()
+
+---
+// Error: 2-43 expected boolean, found integer
+#generator.range(1).filter(n => n).first()
+
+---
+// Error: 2-43 expected array with value and state or none, found integer
+#generator.unfold(0, n => n + 1).collect()
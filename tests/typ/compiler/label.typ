@@ -70,3 +70,13 @@ _Visible_
 #test(str(<hey>), "hey")
 #test(str(label("hey")), "hey")
 #test(str([Hmm<hey>].label), "hey")
+
+---
+// Test the namespace and name of a label.
+// Ref: false
+#test(<intro:motivation>.namespace(), "intro")
+#test(<intro:motivation>.name(), "motivation")
+#test(<a:b:c>.namespace(), "a:b")
+#test(<a:b:c>.name(), "c")
+#test(<plain>.namespace(), none)
+#test(<plain>.name(), "plain")
@@ -118,6 +118,33 @@ Three
 #let (..a) = ()
 #test(a, ())
 
+---
+// Ref: false
+// Nested destructuring.
+#let ((a, b), (c, ..d)) = ((1, 2), (3, 4, 5))
+#test((a, b, c, d), (1, 2, 3, (4, 5)))
+
+#let (x, (y: y, ..rest)) = (1, (y: 2, z: 3))
+#test((x, y, rest), (1, 2, (z: 3)))
+
+#let (point: (px, py), name: _) = (point: (3, 4), name: "p")
+#test((px, py), (3, 4))
+
+#let (values: (first, (second, third))) = (values: (1, (2, 3)))
+#test((first, second, third), (1, 2, 3))
+
+---
+// Error: 14-15 at most one binding per identifier is allowed
+#let (a, (b, a)) = (1, (2, 3))
+
+---
+// Error: 10-16 not enough elements to destructure
+#let (a, (b: b)) = (1,)
+
+---
+// Error: 6-12 expected key, found destructuring pattern
+#let ((a, b), c: c) = (c: 1)
+
 ---
 // Ref: false
 // Destructuring with unnamed sink.
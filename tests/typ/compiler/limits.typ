@@ -0,0 +1,29 @@
+// Test the configurable evaluation limits. The test library lowers them.
+// Ref: false
+
+---
+// Error: 16-27 maximum function call depth exceeded
+// Hint: 16-27 functions may be nested at most 64 levels deep
+#let down(n) = down(n + 1)
+#down(0)
+
+---
+// Error: 3:3-3:25 loop seems to be infinite
+// Hint: 3:3-3:25 loops may run at most 5000 times
+#{
+  let i = 1
+  while i > 0 { i += 1 }
+}
+
+---
+// Error: 11-29 generator produced too many values
+// Hint: 11-29 generators without a known end may produce at most 5000 values
+#for n in generator.range(1) {}
+
+---
+// Error: 3:23-3:29 maximum value size exceeded
+// Hint: 3:23-3:29 values may be at most 16777216 bytes large
+#{
+  let s = "a"
+  while s.len() > 0 { s += s }
+}
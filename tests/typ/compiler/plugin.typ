@@ -10,6 +10,12 @@
   bytes("value3-value1-value2"),
 )
 
+---
+// Test passing strings to plugins.
+#let p = plugin("/files/hello.wasm")
+#test(p.double_it("hey!"), bytes("hey!.hey!"))
+#test(p.shuffle("value1", bytes("value2"), "value3"), bytes("value3-value1-value2"))
+
 ---
 #let p = plugin("/files/hello.wasm")
 
@@ -19,8 +25,8 @@
 ---
 #let p = plugin("/files/hello.wasm")
 
-// Error: 10-14 expected bytes, found boolean
-// Error: 27-29 expected bytes, found integer
+// Error: 10-14 expected bytes or string, found boolean
+// Error: 27-29 expected bytes or string, found integer
 #p.hello(true, bytes(()), 10)
 
 ---
@@ -39,6 +39,7 @@
 
 ---
 // Error: 15-21 maximum function call depth exceeded
+// Hint: 15-21 functions may be nested at most 64 levels deep
 #let rec(n) = rec(n) + 1
 #rec(1)
 
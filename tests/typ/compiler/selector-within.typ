@@ -0,0 +1,37 @@
+// Test selecting nested elements.
+// Ref: false
+
+---
+// Test querying nested elements.
+#block[
+  = First#footnote[Note]
+  #figure[Cat]
+  #figure[Dog]
+] <part>
+#figure[Fish]
+
+#locate(loc => {
+  test(query(figure.within(<part>), loc).map(f => f.body), ([Cat], [Dog]))
+  test(query(selector(<part>).descendant(figure), loc).len(), 2)
+  test(query(footnote.within(heading), loc).len(), 1)
+  test(query(figure.within(heading), loc), ())
+  test(query(figure.within(<part>).or(footnote), loc).len(), 3)
+})
+
+---
+// Test show rules on nested elements.
+#show emph.within(heading): it => [#metadata(it.body) <hit>]
+= A _b_
+_c_
+#locate(loc => test(query(<hit>, loc).map(m => m.value), ([b],)))
+
+---
+#test(repr(emph.within(heading)), "emph.within(heading)")
+
+---
+// Error: 27-31 emph is not locatable
+#selector(heading).within(emph)
+
+---
+// Error: 7-36 this selector cannot be used with show
+#show selector("a").within(heading): none
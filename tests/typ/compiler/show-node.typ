@@ -91,7 +91,7 @@ Hey
 #show upper: it => {}
 
 ---
-// Error: 16-20 expected content or function, found integer
+// Error: 16-20 expected content, function, or styles, found integer
 #show heading: 1234
 = Heading
 
@@ -131,3 +131,45 @@
   // Error: 4-10 missing argument: d
   f(1, 2)
 }
+
+---
+// Test reflection on arguments.
+#let args = arguments(1, 2, fill: red, inset: 5pt)
+#test(args.at(0), 1)
+#test(args.at(-1), 2)
+#test(args.at("fill"), red)
+#test(args.at("stroke", default: none), none)
+#test(args.at(5, default: 0), 0)
+#test(args.only("fill").named(), (fill: red))
+#test(args.only("fill").pos(), ())
+#test(args.without("fill").pos(), (1, 2))
+#test(args.without("fill").named(), (inset: 5pt))
+#test(args.check("fill", "inset"), args)
+#test(arguments(fill: red, inset: 5pt).check(box).named().len(), 2)
+
+---
+// Test splicing arguments into other calls.
+#let wrap(..args) = {
+  args.check(box, "tone")
+  let inner(..rest) = rest.named()
+  inner(tone: args.at("tone", default: none), ..args.without("tone"))
+}
+#test(wrap(fill: red), (tone: none, fill: red))
+#test(wrap(tone: blue, inset: 2pt), (tone: blue, inset: 2pt))
+
+---
+// Error: 12-22 unexpected argument: filll
+// Hint: 12-22 did you mean `fill`?
+#arguments(filll: red).check(box)
+
+---
+// Error: 2-20 positional argument index out of bounds (index: 3, len: 1)
+#arguments(1).at(3)
+
+---
+// Error: 2-25 arguments do not contain named argument `b`
+#arguments(a: 1).at("b")
+
+---
+// Error: 20-26 cannot determine the parameters of this function
+#arguments().check(x => x)
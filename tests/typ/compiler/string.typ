@@ -98,14 +98,14 @@
 #test("Is there a".match("for this?"), none)
 #test(
   "The time of my life.".match(regex("[mit]+e")),
-  (start: 4, end: 8, text: "time", captures: ()),
+  (start: 4, end: 8, text: "time", captures: (), named: (:)),
 )
 
 // Test the `matches` method.
 #test("Hello there".matches("\d"), ())
 #test("Day by Day.".matches("Day"), (
-  (start: 0, end: 3, text: "Day", captures: ()),
-  (start: 7, end: 10, text: "Day", captures: ()),
+  (start: 0, end: 3, text: "Day", captures: (), named: (:)),
+  (start: 7, end: 10, text: "Day", captures: (), named: (:)),
 ))
 
 // Compute the sum of all timestamps in the text.
@@ -122,6 +122,14 @@
 #test(timesum("2:70"), "3:10")
 #test(timesum("1:20, 2:10, 0:40"), "4:10")
 
+// Test named capturing groups.
+#let date = "Due 2024-03 or later".match(regex("(?<year>\d+)-(?<month>\d+)(-(?<day>\d+))?"))
+#test(date.text, "2024-03")
+#test(date.named, (year: "2024", month: "03", day: none))
+#test(date.captures, ("2024", "03", none, none))
+#test("a1b2".matches(regex("(?<digit>\d)")).map(m => m.named.digit), ("1", "2"))
+#test("2024-03".replace(regex("(?<y>\d+)-(?<m>\d+)"), m => m.named.m + "/" + m.named.y), "03/2024")
+
 ---
 // Test the `replace` method with `Str` replacements.
 #test("ABC".replace("", "-"), "-A-B-C-")
@@ -222,6 +230,119 @@
 // Error: 12-15 unknown variable: arg
 #"abc".rev(arg)
 
+---
+// Test the `format` method.
+#test("{} and {}".format(1, "two"), "1 and two")
+#test("{1}{0}{1}".format("a", "b"), "bab")
+#test("{name} is {age}".format(name: "Ann", age: 30), "Ann is 30")
+#test("{{}} {{{}}}".format(1), "{} {1}")
+#test(str.format("{:.2f}", 3.14159), "3.14")
+#test("{:.2}".format(2), "2.00")
+#test("{:f}".format(0.5), "0.500000")
+#test("{:.1e}".format(1234.5), "1.2e3")
+#test("{:.1%}".format(0.256), "25.6%")
+#test("{:>6}|{:<6}|{:^6}".format(42, "ab", "c"), "    42|ab    |  c   ")
+#test("{:*>5}".format("x"), "****x")
+#test("{:05}".format(-42), "−0042")
+#test("{:+} {:+} {: }".format(5, -5, 5), "+5 −5  5")
+#test("{:,}".format(1234567), "1,234,567")
+#test("{:_.2f}".format(-9876.5), "−9_876.50")
+#test("{:x} {:X} {:o} {:b}".format(255, 255, 8, 5), "ff FF 10 101")
+#test("{:_b}".format(255), "1111_1111")
+#test("{:.3}".format("abcdef"), "abc")
+#test("{:s}".format(1.5), "1.5")
+#test("{:.2}".format(decimal("1.005")), "1.01")
+#test("{:,}".format(decimal("-1234.50")), "−1,234.50")
+#test("{}".format((1, 2)), "(1, 2)")
+#test("{}".format(none), "none")
+
+---
+// Error: 2-19 format string has more placeholders than arguments
+#"{} {}".format(1)
+
+---
+// Error: 2-23 format string refers to missing argument `name`
+#"{name}".format(a: 1)
+
+---
+// Error: 2-14 unclosed placeholder in format string (use `{{` to escape a brace)
+#"{".format()
+
+---
+// Error: 2-14 unmatched closing brace in format string (use `}}` to escape it)
+#"}".format()
+
+---
+// Error: 2-20 invalid format specification `.2q`
+#"{:.2q}".format(1)
+
+---
+// Error: 2-20 cannot format string with `+`
+#"{:+}".format("a")
+
+---
+// Error: 2-20 cannot format float with type `x`
+#"{:x}".format(1.5)
+
+---
+// Test Unicode normalization.
+#test("e\u{301}".normalize(), "\u{e9}")
+#test("\u{e9}".normalize(form: "nfd"), "e\u{301}")
+#test("\u{e9}".normalize(form: "nfd").len(), 3)
+#test("ﬁ".normalize(), "ﬁ")
+#test("ﬁ".normalize(form: "nfkc"), "fi")
+#test("x²".normalize(form: "nfkd"), "x2")
+
+---
+// Error: 22-27 expected "nfc", "nfd", "nfkc", or "nfkd"
+#"a".normalize(form: "nfx")
+
+---
+// Test the display width.
+#test("".width(), 0)
+#test("abc".width(), 3)
+#test("日本".width(), 4)
+#test("😃a".width(), 3)
+#test("e\u{301}".width(), 1)
+
+---
+// Test character properties.
+#test("Straße".is-alphabetic(), true)
+#test("e\u{301}".is-alphabetic(), true)
+#test("R2D2".is-alphabetic(), false)
+#test("".is-alphabetic(), false)
+#test("2024".is-numeric(), true)
+#test("٣½".is-numeric(), true)
+#test("-1".is-numeric(), false)
+#test(" \t\n".is-whitespace(), true)
+#test(" a ".is-whitespace(), false)
+#test("".is-whitespace(), false)
+
 ---
 // Error: 2-2:1 unclosed string
 #"hello\"
+
+---
+// Test raw strings.
+#test(r"C:\new\path", "C:\\new\\path")
+#test(r"\d+\.\d*", "\\d+\\.\\d*")
+#test(r#"say "hi""#, "say \"hi\"")
+#test(r##"a "# b"##, "a \"# b")
+#test(r#"""#, "\"")
+#test(r#"a "b" c"#, "a \"b\" c")
+#test(r###"x"##"###, "x\"##")
+#test(r##""#"#"##, "\"#\"#")
+#test(r"", "")
+#test(r"line 1
+  line 2", "line 1\n  line 2")
+#test("1.5".match(regex(r"\d\.\d")).text, "1.5")
+
+---
+// Directly after a hash, `r` is still a variable.
+#let r = "value"
+#test([#r"x"], [value"x"])
+#test([#(r"x")], [x])
+
+---
+// Error: 10-2:1 unclosed raw string
+#let s = r#"hello"
@@ -0,0 +1,35 @@
+// Test style values.
+// Ref: false
+
+---
+#let emphasis = styles(text, weight: "bold", fill: red)
+#let mono = styles(text, font: "DejaVu Sans Mono")
+#test(type(emphasis), styles)
+#test(emphasis == emphasis, true)
+#test(emphasis == mono, false)
+#test(type(emphasis + mono), styles)
+#test(emphasis + mono == emphasis + mono, true)
+#test(emphasis + mono == mono + emphasis, false)
+
+---
+// Test applying styles.
+#let emphasis = styles(text, weight: "bold")
+#let styled = emphasis.apply[Hello]
+#test(styled == [Hello], false)
+#show heading: emphasis
+#show: emphasis
+= Heading
+
+---
+// Test passing styles to functions.
+#let themed(body, theme: styles(text, fill: blue)) = theme.apply(body)
+#themed[Blue]
+#themed(theme: styles(text, fill: green))[Green]
+
+---
+// Error: 9-17 only element functions can be used to create styles
+#styles(calc.min, x: 1)
+
+---
+// Error: 15-21 unexpected argument: foo
+#styles(text, foo: 1)
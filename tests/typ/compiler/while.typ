@@ -39,6 +39,7 @@
 
 ---
 // Error: 2:2-2:24 loop seems to be infinite
+// Hint: 2:2-2:24 loops may run at most 5000 times
 #let i = 1
 #while i > 0 { i += 1 }
 
@@ -0,0 +1,64 @@
+// Test big integers.
+// Ref: false
+
+---
+// Test construction and conversion.
+#test(bigint(42), bigint("42"))
+#test(bigint(decimal("-2.7")), bigint(-2))
+#test(bigint("−17"), bigint(-17))
+#test(type(bigint(1)), bigint)
+#test(repr(bigint("-5")), "bigint(\"-5\")")
+#test(str(bigint("-123456789012345678901234567890")), "−123456789012345678901234567890")
+#test(int(bigint("9223372036854775807")), 9223372036854775807)
+#test(float(bigint(2).pow(64)), 18446744073709551616.0)
+
+---
+// Test arithmetic.
+#test(bigint(9223372036854775807) + 1, bigint("9223372036854775808"))
+#test(1 - bigint("100000000000000000000"), bigint("-99999999999999999999"))
+#test(bigint(2).pow(100), bigint("1267650600228229401496703205376"))
+#test(str(bigint(2).pow(64) * bigint(2).pow(64)), "340282366920938463463374607431768211456")
+#test(-bigint(3), bigint(-3))
+#test(calc.abs(bigint(-7)), bigint(7))
+#test(bigint(14).quo(5), bigint(2))
+#test(bigint(-14).quo(5), bigint(-3))
+#test(bigint(-14).rem(5), bigint(1))
+#test(bigint("123456789012345678901234567890").rem(97), bigint(52))
+
+---
+// Test comparison.
+#test(bigint(5) == 5, true)
+#test(5 == bigint(5), true)
+#test(bigint(2).pow(70) > 9223372036854775807, true)
+#test(bigint(-1) < bigint(0), true)
+#test((bigint(3), 1, bigint(-2)).sorted(), (bigint(-2), 1, bigint(3)))
+
+---
+// Test formatting.
+#test("{:,}".format(bigint(10).pow(21)), "1,000,000,000,000,000,000,000")
+#test(num.format(bigint(-1234567), locale: "de"), "−1.234.567")
+
+---
+// Test type annotations.
+#let f(x as bigint) = x
+#test(f(2), bigint(2))
+
+---
+// Error: 9-14 invalid big integer: 1.5
+#bigint("1.5")
+
+---
+// Error: 3-24 cannot divide bigint by bigint
+#(bigint(4) / bigint(2))
+
+---
+// Error: 3-18 cannot add bigint and float
+#(bigint(1) + 1.5)
+
+---
+// Error: 2-18 divisor must not be zero
+#bigint(1).rem(0)
+
+---
+// Error: 2-28 maximum value size exceeded
+#bigint(10).pow(4000000000)
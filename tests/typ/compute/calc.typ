@@ -353,3 +353,19 @@
 ---
 // Error: 18-19 number must not be zero
 #range(10, step: 0)
+
+---
+// Test seeded random numbers.
+#test(calc.random(42), calc.random(42))
+#test(calc.random("a"), calc.random("a"))
+#test(calc.random(1) != calc.random(2), true)
+#test(calc.random(42, count: 3).len(), 3)
+#test(calc.random(42, count: 3).first(), calc.random(42))
+#test(calc.random(42, count: 0), ())
+#for x in calc.random("range", count: 100) {
+  test(0 <= x and x < 1, true)
+}
+
+---
+// Error: 14-18 expected integer or string, found none
+#calc.random(none)
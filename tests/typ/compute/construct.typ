@@ -283,6 +283,24 @@
 #test(datetime.today(offset: auto).display(), "1970-01-01")
 #test(datetime.today(offset: 2).display(), "1970-01-01")
 
+// Test parsing
+#test(datetime.parse("2023-04-29"), datetime(year: 2023, month: 4, day: 29))
+#test(datetime.parse("14:26:50"), datetime(hour: 14, minute: 26, second: 50))
+#test(
+  datetime.parse("2023-04-29T14:26:50"),
+  datetime(year: 2023, month: 4, day: 29, hour: 14, minute: 26, second: 50),
+)
+#test(
+  datetime.parse("2023-04-29 14:26:50"),
+  datetime.parse("2023-04-29T14:26:50"),
+)
+#test(
+  datetime.parse("29 April 2023", "[day] [month repr:long] [year]"),
+  datetime(year: 2023, month: 4, day: 29),
+)
+#test(datetime.parse("2:26 PM", "[hour repr:12 padding:none]:[minute] [period]").hour(), 14)
+#test(datetime.parse("2023-04-29") + duration(days: 3), datetime.parse("2023-05-02"))
+
 ---
 // Error: 10-12 at least one of date or time must be fully specified
 #datetime()
@@ -314,3 +332,15 @@
 ---
 // Error: 2-36 failed to format datetime (insufficient information)
 #datetime.today().display("[hour]")
+
+---
+// Error: 2-30 datetime is not in ISO 8601 format
+#datetime.parse("29.04.2023")
+
+---
+// Error: 2-54 string does not match the pattern
+#datetime.parse("29/04/2023", "[day].[month].[year]")
+
+---
+// Error: 2-45 failed to parse datetime (insufficient information)
+#datetime.parse("2023-04", "[year]-[month]")
@@ -30,6 +30,16 @@
 #test(data.at(2).Weight, "150kg")
 #test(data.at(1).Species, "Tiger")
 
+---
+// Test reading only some rows of CSV data.
+#test(csv("/files/zoo.csv", limit: 1), (("Name", "Species", "Weight", "Length"),))
+#test(csv("/files/zoo.csv", skip: 3).len(), 1)
+#test(csv("/files/zoo.csv", skip: 5), ())
+#let data = csv("/files/zoo.csv", row-type: dictionary, skip: 1, limit: 1)
+#test(data.len(), 1)
+#test(data.at(0).Name, "Fluffy")
+#test(csv.decode("a,b\nc,d\ne,f", skip: 1, limit: 5), (("c", "d"), ("e", "f")))
+
 ---
 // Error: 6-16 file not found (searched at typ/compute/nope.csv)
 #csv("nope.csv")
@@ -142,3 +152,8 @@
 ---
 // Error: 6-22 failed to parse XML (found closing tag 'data' instead of 'hello' in line 3)
 #xml("/files/bad.xml")
+
+---
+// Error: 8-17 writing files is not permitted
+// Hint: 8-17 the compiler must permit it, e.g. with the `--write-dir` flag of the CLI
+#write("out.csv", "a,b")
@@ -0,0 +1,69 @@
+// Test decimal numbers.
+// Ref: false
+
+---
+// Test construction and conversion.
+#test(decimal(4), decimal("4"))
+#test(decimal(2.5), decimal("2.5"))
+#test(decimal(0.1), decimal("0.1"))
+#test(decimal("-12.50"), decimal("−12.5"))
+#test(type(decimal("1")), decimal)
+#test(repr(decimal("1.50")), "decimal(\"1.50\")")
+#test(str(decimal("-0.05")), "−0.05")
+#test(str(decimal("7")), "7")
+#test(int(decimal("-2.7")), -2)
+#test(float(decimal("2.5")), 2.5)
+
+---
+// Test arithmetic.
+#test(decimal("0.1") + decimal("0.2"), decimal("0.3"))
+#test(str(decimal("0.10") + decimal("0.20")), "0.30")
+#test(decimal("19.99") * 3, decimal("59.97"))
+#test(1 - decimal("0.25"), decimal("0.75"))
+#test(decimal("10") / 4, decimal("2.5"))
+#test(str(decimal(1) / 3), "0.3333333333333333333333333333")
+#test(str(decimal(2) / 3), "0.6666666666666666666666666667")
+#test(-decimal("1.5"), decimal("-1.5"))
+#test(calc.abs(decimal("-3.2")), decimal("3.2"))
+
+---
+// Test comparison.
+#test(decimal("1.0") == decimal("1"), true)
+#test(decimal("1") == 1, true)
+#test(decimal("0.3") == 0.3, false)
+#test(decimal("0.1") < decimal("0.11"), true)
+#test(decimal("-2") < -1, true)
+#test((decimal("2.5"), 1, decimal("-1")).sorted(), (decimal("-1"), 1, decimal("2.5")))
+#test((decimal("1.0"), decimal("1")).dedup().len(), 1)
+
+---
+// Test rounding.
+#test(str(decimal("3.14159").round(digits: 2)), "3.14")
+#test(str(decimal("2.5").round()), "3")
+#test(str(decimal("-2.5").round()), "−3")
+#test(str(decimal("7").round(digits: 2)), "7.00")
+
+---
+// Test type annotations.
+#let f(x as decimal) = x
+#test(f(2), decimal("2"))
+
+---
+// Error: 10-15 invalid decimal: abc
+#decimal("abc")
+
+---
+// Error: 10-18 cannot convert inf to a decimal
+#decimal(calc.inf)
+
+---
+// Error: 3-19 cannot divide by zero
+#(decimal("1") / 0)
+
+---
+// Error: 3-21 cannot add decimal and float
+#(decimal("1") + 1.5)
+
+---
+// Error: 3-47 value is too large
+#(decimal("79228162514264337593543950335") + 1)
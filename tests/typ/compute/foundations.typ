@@ -66,6 +66,36 @@
 #assert.eq(15, 15)
 #assert.ne(10, 12)
 
+---
+// Test catching errors.
+#let (ok, value, error) = catch(() => 1 + 2)
+#test(ok, true)
+#test(value, 3)
+#test(error, none)
+
+#let result = catch(() => assert(1 > 2, message: "math broke"))
+#test(result.ok, false)
+#test(result.value, none)
+#test(result.error, "assertion failed: math broke")
+
+#test(catch(() => json.decode("{")).ok, false)
+#test(catch(() => panic("oh no")).error, "panicked with: \"oh no\"")
+
+---
+// Error: 8-9 expected function, found integer
+#catch(5)
+
+---
+// Error: 15-21 maximum function call depth exceeded
+// Hint: 15-21 functions may be nested at most 64 levels deep
+#let rec(n) = rec(n) + 1
+#catch(() => rec(1))
+
+---
+// Error: 27-49 loop seems to be infinite
+// Hint: 27-49 loops may run at most 5000 times
+#catch(() => { let i = 1; while i > 0 { i += 1 } })
+
 ---
 // Test the `type` function.
 #test(type(1), int)
@@ -85,6 +115,24 @@
 #eval("_Hello" + " World!_", mode: "markup") \
 #eval("RR_1^NN", mode: "math", scope: (RR: math.NN, NN: math.RR))
 
+---
+// Test restricted evaluation.
+#test(eval("*Hi*", mode: "markup", restricted: true), strong[Hi])
+#test(eval("x + 1", scope: (x: 1), restricted: true), 2)
+#test(eval("f(2)", scope: (f: calc.pow.with(3)), restricted: true), 9)
+
+---
+// Error: 7-24 unknown variable: read
+#eval("read(\"a.txt\")", restricted: true)
+
+---
+// Error: 7-25 cannot import or include modules in restricted evaluation
+#eval("import \"a.typ\"", mode: "code", restricted: true)
+
+---
+// Error: 7-27 cannot import or include modules in restricted evaluation
+#eval("#include \"a.typ\"", mode: "markup", restricted: true)
+
 ---
 // Error: 7-12 expected identifier
 #eval("let")
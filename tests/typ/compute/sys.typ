@@ -0,0 +1,29 @@
+// Test the sys module.
+// Ref: false
+
+---
+// Test inputs provided by the compiler.
+#test(type(sys.inputs), dictionary)
+#test(sys.inputs.name, "Typst")
+#test(sys.inputs.at("missing", default: none), none)
+#let draft = sys.inputs.at("draft", default: "false") == "true"
+#test(draft, true)
+
+---
+// Error: 2-17 dictionary does not contain key "size"
+#sys.inputs.size
+
+---
+// Test version detection.
+#test(type(sys.version), version)
+#test(sys.version.compare(version(0, 1)), 1)
+
+---
+// Test feature detection.
+#test(type(sys.features), dictionary)
+#test(sys.features.at("decimal", default: false), true)
+#test(sys.features.at("calc.round", default: false), true)
+#test(sys.features.at("version.compare", default: false), true)
+#test(sys.features.at("time-travel", default: false), false)
+#test(sys.features.at("red", default: false), false)
+#test(sys.features.values().all(v => v == true), true)
@@ -45,3 +45,15 @@
 ---
 // Test the type of `sys.version`
 #test(type(sys.version), version)
+
+---
+// Test `version.compare`.
+#test(version(0, 10).compare(version(0, 11)), -1)
+#test(version(1, 2).compare(version(1, 2, 0)), 0)
+#test(version(2).compare(version(1, 9, 9)), 1)
+#test(version().compare(version(0, 0, 1)), -1)
+#test(sys.version.compare(sys.version), 0)
+
+---
+// Error: 24-25 expected version, found integer
+#version(1, 2).compare(1)
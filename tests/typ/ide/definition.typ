@@ -0,0 +1,26 @@
+// Test go-to-definition in the IDE.
+// Ref: false
+
+---
+// Definition: 2:2 -> 1:6-1:7
+// Definition: 3:13 -> 3:8-3:9
+#let x = 1
+#x
+#let f(y) = y
+
+---
+// Library definitions have no span.
+// Definition: 1:2 -> none
+#rect()
+
+---
+// Definition: 2:2 -> typ/compiler/module.typ 5:6-5:7
+#import "../compiler/module.typ": b
+#b
+
+---
+// A plain import binds the file's stem.
+// Definition: 2:2 -> 1:9-1:33
+// Definition: 2:9 -> typ/compiler/module.typ 5:6-5:7
+#import "../compiler/module.typ"
+#module.b
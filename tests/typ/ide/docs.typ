@@ -0,0 +1,56 @@
+// Test doc comments of user-defined items.
+// Ref: false
+
+---
+// A documented variable.
+// Hover docs: 4:2 The answer.
+#let y = 0
+/// The answer.
+#let answer = 42
+#answer
+
+---
+// A documented function with parameters and a return value.
+// Hover docs: 9:2 Computes the area of a rectangle.
+// Hover docs: 9:2 - `width`: The width of the rectangle.
+// Hover docs: 9:2 - `height`: The height of the rectangle. Defaults to the width.
+// Hover docs: 9:2 The area.
+#let w = 1
+/// Computes the area of a rectangle.
+///
+/// - width: The width of the rectangle.
+/// - height: The height of the rectangle.
+///   Defaults to the width.
+/// -> The area.
+#let area(width, height: none) = width * height
+#area(w)
+
+---
+// A documented closure bound to a variable.
+// Hover docs: 4:2 Doubles a number.
+#let v = 1
+/// Doubles a number.
+#let double = n => 2 * n
+#double(v)
+
+---
+// A documented variable in a code block.
+// Hover docs: 5:3 Inner.
+#let v = 1
+#{
+  /// Inner.
+  let inner = 1
+  inner
+}
+
+---
+// A blank line detaches a comment and destructuring isn't documented.
+// Hover docs: 7:2 none
+// Hover docs: 7:5 none
+#let v = 1
+/// Detached.
+
+#let a = 1
+/// Destructured.
+#let (b, c) = (1, 2)
+#a #b
@@ -0,0 +1,29 @@
+// Test hover information in the IDE.
+// Ref: false
+
+---
+// Hover type: 2:2 integer
+// Hover value: 2:2 1
+// Hover type: 3:1 none
+#let x = 1
+#x
+Hello
+
+---
+// Hover type: 1:2 function
+// Hover value: 1:2 none
+// Hover docs: 1:2 A rectangle with optional content.
+// Hover docs: 1:7 The rectangle's width
+// Hover value: 1:7 none
+#rect(width: 1cm)
+
+---
+// Hover type: 5:2 function
+// Hover docs: 5:2 Doubles a number.
+// Hover docs: 5:2 - `n`: The number.
+#let y = 1
+/// Doubles a number.
+///
+/// - n: The number.
+#let double(n) = 2 * n
+#double(y)
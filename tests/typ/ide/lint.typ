@@ -0,0 +1,61 @@
+// Test the built-in lints of the IDE.
+// Ref: false
+
+---
+// Lint: 2:7-2:8 unused variable `y`
+// Lint: 6:7-6:8 `x` shadows a binding from an outer scope
+#let f(x) = {
+  let y = 1
+  x
+}
+#let g(x) = {
+  let x = x + 1
+  x
+}
+
+---
+// Lint: 1:38-1:39 unused import `c`
+// Lint: 4:1-4:2 heading is empty
+#import "../compiler/module.typ": b, c
+#b
+#let h(_x) = 1
+=
+
+---
+// Lint: 1:6-1:10 `diff` is deprecated
+// Lint: 2:2-2:6 `diff` is deprecated
+// Lint: 3:14-3:18 `diff` is deprecated
+// Warning: 1:6-1:10 `diff` is deprecated
+// Hint: 1:6-1:10 use `partial` instead
+// Hint: 1:6-1:10 it will be removed in Typst 0.12.0
+// Warning: 2:2-2:6 `diff` is deprecated
+// Hint: 2:2-2:6 use `partial` instead
+// Hint: 2:2-2:6 it will be removed in Typst 0.12.0
+// Warning: 3:14-3:18 `diff` is deprecated
+// Hint: 3:14-3:18 use `partial` instead
+// Hint: 3:14-3:18 it will be removed in Typst 0.12.0
+#sym.diff
+$diff$
+#import sym: diff
+#diff
+
+---
+// Only identifiers in expression position refer to deprecated definitions.
+// Lint: 7:2-7:12 `old-forest` is deprecated
+// Warning: 7:2-7:12 `old-forest` is deprecated
+// Hint: 7:2-7:12 use `forest` instead
+#let f(..args) = args
+#f(old-forest: 1)
+#let d = (old-forest: 2)
+#let (old-forest: x) = d
+#import "../compiler/module.typ": old-forest as tree
+#tree
+#old-forest
+
+---
+// A local binding is not deprecated.
+// Lint: 4:1-4:2 heading is empty
+#let diff = 1
+#diff
+$diff$
+=
@@ -0,0 +1,30 @@
+// Test document symbols in the IDE.
+// Ref: false
+
+---
+// Variables are not symbols, but functions are, including nested ones. Sections
+// contain everything up to the next heading of the same or a higher level.
+// Symbol: 1:1-1:8 heading 1 Intro
+// Symbol: 3:2-6:2 function Intro > outer
+// Symbol: 4:3-4:19 function Intro > outer > inner
+// Symbol: 7:1-7:11 heading 2 Details
+// Symbol: 9:2-9:26 function Intro > Details > twice
+// Symbol: 10:1-10:7 heading 1 Next
+= Intro
+#let x = 1
+#let outer(y) = {
+  let inner(z) = z
+  inner(y)
+}
+== Details
+#let x = 2
+#let twice = (n) => 2 * n
+= Next
+
+---
+// Symbol: 1:2-1:29 show show heading
+// Symbol: 2:1-2:7 heading 1 Text
+// Symbol: 3:7-3:14 label Text > greet
+#show heading: set text(red)
+= Text
+Hello <greet>
@@ -0,0 +1,16 @@
+// Test layers.
+
+---
+// Ref: false
+What is $2 + 2$?
+#layer("solutions", visible: false)[The answer is $4$.]
+#layer("notes", printable: false)[Check the units.]
+#layer("solutions")[Also on the solutions layer.]
+
+---
+// Layers don't affect layout.
+// Ref: false
+#style(styles => {
+  let body = rect(width: 20pt, height: 10pt)
+  test(measure(layer("grid", body), styles), measure(body, styles))
+})
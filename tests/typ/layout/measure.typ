@@ -0,0 +1,17 @@
+// Test measuring content.
+// Ref: false
+
+---
+#style(styles => {
+  let body = lorem(30)
+  let free = measure(body, styles)
+  let fitted = measure(body, styles, width: 100pt)
+  test(fitted.width <= 100pt, true)
+  test(fitted.height > free.height, true)
+  test(measure(box(width: 50pt), styles, width: 10pt).width, 50pt)
+  test(measure(body, styles, width: 2em) == measure(body, styles, width: 22pt), true)
+})
+
+---
+// Error: 45-49 expected length or auto, found boolean
+#style(styles => measure([], styles, width: true))
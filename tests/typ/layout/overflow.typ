@@ -0,0 +1,37 @@
+// Test warnings about content that overflows its container.
+
+---
+// Ref: false
+// Overflow: true
+#set page(width: 50pt, height: auto, margin: 0pt)
+// Warning: 2-18 line is 30pt wider than the available space
+#box(width: 80pt)
+
+---
+// Ref: false
+// Overflow: true
+#set page(width: 50pt, height: auto, margin: 0pt)
+#set par(justify: true)
+// Warning: 2-18 line is 20pt narrower than the available space
+#box(width: 30pt)#linebreak(justify: true)
+#box(width: 10pt)
+
+---
+// Ref: false
+// Overflow: true
+#set page(width: 100pt, height: auto, margin: 0pt)
+// Warning: 2-30 table is 60pt wider than the available space
+#table(columns: (80pt, 80pt))
+
+---
+// Ref: false
+// Overflow: true
+#set page(width: 100pt, height: auto, margin: 0pt)
+// Warning: 20-39 block is 30pt taller than the available space
+#box(height: 20pt, block(height: 50pt))
+
+---
+// Overflowing content is not reported by default.
+// Ref: false
+#set page(width: 100pt, height: auto, margin: 0pt)
+#block(width: 150pt)
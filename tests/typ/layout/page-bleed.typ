@@ -0,0 +1,34 @@
+// Test page bleed and printer's marks.
+
+---
+// Positions are relative to the trimmed page, not the bleed.
+// Ref: false
+#set page(width: 100pt, height: 100pt, margin: 0pt, bleed: 10pt)
+= Heading
+#locate(loc => {
+  let pos = query(heading, loc).first().location().position()
+  test((pos.x, pos.y), (0pt, 0pt))
+})
+
+---
+// The same holds for the extra space around the bleed that holds the marks.
+// Ref: false
+#set page(width: 100pt, height: 100pt, margin: 0pt, bleed: 10pt, marks: true)
+= Heading
+#locate(loc => {
+  let pos = query(heading, loc).first().location().position()
+  test((pos.x, pos.y), (0pt, 0pt))
+})
+
+---
+// An element is at the same position with and without bleed.
+// Ref: false
+#set page(width: 100pt, height: 100pt, margin: 10pt)
+= Plain
+#set page(bleed: 10pt, marks: true)
+= Bleed
+#locate(loc => {
+  let (plain, bleed) = query(heading, loc).map(h => h.location().position())
+  test(bleed.page, 2)
+  test((bleed.x, bleed.y), (plain.x, plain.y))
+})
@@ -0,0 +1,14 @@
+// Test that page runs, which are laid out in parallel, still behave as if they
+// were laid out in order.
+
+---
+// Elements that occur in multiple runs must get distinct locations.
+// Ref: false
+#set page(width: 100pt, height: auto)
+#let item = figure(caption: [Item])[A]
+#item
+#page(item)
+#set page(width: 120pt)
+#item
+#locate(loc => test(query(figure, loc).len(), 3))
+#locate(loc => test(counter(page).at(loc), (3,)))
@@ -0,0 +1,26 @@
+// Test the region function.
+// Ref: false
+
+---
+#set page(width: 200pt, height: 200pt, margin: 20pt)
+#region(r => {
+  test(r.page, 1)
+  test(r.width, 160pt)
+  test(r.height, 160pt)
+  test(r.remaining.width, 160pt)
+  test(r.x, 20pt)
+  test(r.y, 20pt)
+})
+
+#v(50pt)
+#region(r => {
+  test(r.remaining.height <= 110pt, true)
+  test(r.y >= 70pt, true)
+})
+
+#pagebreak()
+#region(r => test(r.page, 2))
+
+---
+// Error: 9-14 expected function, found integer
+#region(1 + 2)
@@ -113,3 +113,15 @@
 ---
 // Error: 14-19 expected color, gradient, pattern, none, array, or function, found string
 #table(fill: "hey")
+
+---
+// Tables are locatable, so they can be queried.
+// Ref: false
+#table[A]
+#table(columns: 2)[B][C]
+#locate(loc => {
+  let tables = query(table, loc)
+  test(tables.len(), 2)
+  test(tables.last().columns, (auto, auto))
+  test(tables.first().location().page(), 1)
+})
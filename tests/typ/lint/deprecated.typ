@@ -0,0 +1,34 @@
+// Test warnings for deprecated definitions.
+// Ref: false
+
+---
+// Warning: 6-10 `diff` is deprecated
+// Hint: 6-10 use `partial` instead
+// Hint: 6-10 it will be removed in Typst 0.12.0
+#sym.diff
+
+---
+// Warning: 2-6 `diff` is deprecated
+// Hint: 2-6 use `partial` instead
+// Hint: 2-6 it will be removed in Typst 0.12.0
+$diff$
+
+---
+// Warning: 14-18 `diff` is deprecated
+// Hint: 14-18 use `partial` instead
+// Hint: 14-18 it will be removed in Typst 0.12.0
+#import sym: diff
+#diff
+
+---
+// Warning: 2:2-2:6 `diff` is deprecated
+// Hint: 2:2-2:6 use `partial` instead
+// Hint: 2:2-2:6 it will be removed in Typst 0.12.0
+#import sym: *
+#diff
+
+---
+// A shadowing definition is not deprecated.
+#let diff = 1
+#test(diff, 1)
+$diff$
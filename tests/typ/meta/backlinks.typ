@@ -0,0 +1,32 @@
+// Test finding the references to a label.
+
+---
+// Ref: false
+#set page(height: 80pt)
+#set heading(numbering: "1.")
+
+= Introduction <intro>
+See @intro.
+
+#pagebreak()
+Again @intro and #ref(<intro>, supplement: [Part]).
+
+#locate(loc => {
+  let links = backlinks(<intro>, loc)
+  test(links.len(), 3)
+  test(links.map(l => l.page()), (1, 2, 2))
+  test(backlinks(<nowhere>, loc), ())
+})
+
+---
+// Test finding the citations of a bibliography entry.
+// Ref: false
+See @netwok, @arrgh and #cite(<netwok>, form: "prose").
+Grouped @netwok @arrgh.
+
+#locate(loc => {
+  test(backlinks(<netwok>, loc).len(), 3)
+  test(backlinks(<arrgh>, loc).len(), 2)
+})
+
+#bibliography("/files/works.bib")
@@ -42,3 +42,68 @@ Now we have multiple bibliographies containing @glacier-melt @keshav2007read
 ---
 // Error: 15-55 duplicate bibliography keys: netwok, issue201, arrgh, quark, distress, glacier-melt, tolkien54, DBLP:books/lib/Knuth86a, sharing, restful, mcintosh_anxiety, psychology25
 #bibliography(("/files/works.bib", "/files/works.bib"))
+
+---
+// Test loading a CSL-JSON bibliography.
+// Ref: false
+@knuth1984 and @lamport1994
+#bibliography("/files/works.json")
+
+---
+// Error: 15-32 failed to parse CSL-JSON (item without `id`)
+#bibliography("/files/zoo.json")
+
+---
+// Test a bibliography per chapter.
+// Ref: false
+= Rivers
+Glaciers are melting. @glacier-melt
+#bibliography("/files/works.bib", title: none)
+
+= Oceans
+Pirates love the sea. @arrgh @glacier-melt
+#bibliography("/files/works.bib", title: none)
+
+---
+// Test filtered bibliographies.
+// Ref: false
+Pirates @arrgh sailed the net-work. @netwok
+#bibliography("/files/works.bib", filter: work => work.type == "book")
+#bibliography("/files/works.bib", filter: work => work.type != "book")
+
+---
+// Error: 2-15 key `arrgh` does not exist in the bibliography
+#cite(<arrgh>)
+#bibliography("/files/works.bib", filter: work => work.key != "arrgh")
+
+---
+// Error: 43-51 expected boolean, found string
+#bibliography("/files/works.bib", filter: w => "a")
+
+---
+// Test citation locators and prefixes.
+// Ref: false
+#cite(<arrgh>, locator: "p. 42") \
+#cite(<netwok>, locator: "pp. 7-9", prefix: [see]) \
+#cite(<distress>, locator: "§4") \
+#cite(<quark>, locator: "Chapter 3")
+#bibliography("/files/works.bib")
+
+---
+// Test that citation locators are displayed normalized.
+// Ref: false
+#test(cite(<arrgh>, locator: "Pages 7-9").locator, "p. 7-9")
+#test(cite(<arrgh>, locator: "§§ 4").locator, "§ 4")
+
+---
+// Error: 25-32 unknown kind of locator `xyz` (try `p.`, `ch.`, or `sec.`)
+#cite(<arrgh>, locator: "xyz 4")
+
+---
+// Error: 25-29 locator is missing a number
+#cite(<arrgh>, locator: "p.")
+
+---
+// Error: 2-49 citation cannot have both a supplement and a locator
+#cite(<arrgh>, locator: "p. 4", supplement: [x])
+#bibliography("/files/works.bib")
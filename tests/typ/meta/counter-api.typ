@@ -0,0 +1,67 @@
+// Test the counter API's update functions, displays and queries.
+// Ref: false
+
+---
+// Update with a function receiving the whole value as an array.
+#let c = counter("section")
+#c.update((2, 4, 1))
+#c.update(nums => nums.slice(0, 2) + (nums.len(),), spread: false)
+#locate(loc => test(c.at(loc), (2, 4, 3)))
+#c.update(nums => nums.rev(), spread: false)
+#locate(loc => test(c.at(loc), (3, 4, 2)))
+
+---
+// Display with depth and custom join.
+#let c = counter("part")
+#c.update((3, 2, 1))
+#c.display("1.", depth: 2)
+#locate(loc => test(c.at(loc), (3, 2, 1)))
+#c.display("I", join: [ – ])
+
+---
+// Query a counter at a label and at the end without a location.
+#let c = counter("item")
+#c.step()
+#metadata(none) <here>
+#c.step()
+#c.step()
+#locate(loc => {
+  test(c.at(<here>), (1,))
+  test(c.final(), (3,))
+  test(c.final(loc), (3,))
+})
+
+---
+#let c = counter("item")
+// Error: 16-31 selector does not match any element
+#locate(loc => c.at(<missing>))
+
+---
+#metadata(1) <twice>
+#metadata(2) <twice>
+// Error: 16-40 selector matches multiple elements
+#locate(loc => counter("x").at(<twice>))
+
+---
+// Error: 2-22 counters cannot be queried at the top level of a module
+// Hint: 2-22 try calling this within `locate`
+#counter("x").final()
+
+---
+#metadata(none) <a>
+// Error: 2-22 counters cannot be queried at the top level of a module
+// Hint: 2-22 try calling this within `locate`
+#counter("x").at(<a>)
+
+---
+// Queries by selector and by location agree.
+#let c = counter("y")
+#c.update(5)
+#metadata(none) <b>
+#c.step()
+#locate(loc => {
+  let b = query(<b>, loc).first().location()
+  test(c.at(<b>), c.at(b))
+  test(c.at(<b>), (5,))
+  test(c.final(), (6,))
+})
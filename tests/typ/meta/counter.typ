@@ -50,3 +50,14 @@ At Beta, it was #locate(loc => {
 #figure(caption: [Four 'C's], kind: image, supplement: "Figure")[_CCCC!_]
 #counter(figure.where(kind: image)).update(n => n + 3)
 #figure(caption: [Four 'D's], kind: image, supplement: "Figure")[_DDDD!_]
+
+---
+// Make sure that a warning is produced if a counter fails to converge.
+// Ref: false
+// Warning: layout did not converge within 5 attempts
+// Hint: check if any states or queries are updating themselves
+// Warning: 3:2-3:13 counter "c" did not converge
+// Hint: 3:2-3:13 its value kept changing at 1 location
+#let c = counter("c")
+#locate(loc => c.update(c.final(loc).first() + 1))
+#c.display()
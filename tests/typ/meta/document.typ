@@ -10,6 +10,44 @@ What's up?
 // Ref: false
 #set document(author: ("A", "B"), date: datetime.today())
 
+---
+// Ref: false
+#set document(
+  subject: [A test document],
+  custom: (Department: "Physics", Revision: "3"),
+)
+
+---
+// Error: 23-39 metadata key `Title` is reserved
+#set document(custom: (Title: "Hello"))
+
+---
+// Error: 23-31 expected string, found integer
+#set document(custom: (Rev: 3))
+
+---
+// Ref: false
+#set document(icc: "/files/sRGB.icc")
+
+---
+// Error: 20-38 file is not a valid ICC profile
+#set document(icc: "/files/hello.txt")
+
+---
+// Ref: false
+#set document(embed-file: (
+  "/files/data.csv",
+  (path: "/files/example.xml", mime: "text/xml", description: "Raw data"),
+))
+
+---
+// Error: 27-65 invalid MIME type `csv`
+#set document(embed-file: (path: "/files/data.csv", mime: "csv"))
+
+---
+// Error: 27-65 duplicate embedded file name `data.csv`
+#set document(embed-file: ("/files/data.csv", "/files/data.csv"))
+
 ---
 // Error: 21-28 expected datetime, none, or auto, found string
 #set document(date: "today")
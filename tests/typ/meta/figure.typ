@@ -109,3 +109,39 @@ We can clearly see that @fig-cylinder and
   table(columns: 2)[a][b],
   caption: [The table with custom separator.],
 )
+
+---
+// Test that figures are numbered per kind, float, and can be referenced and
+// outlined.
+// Ref: false
+#set page(height: 200pt)
+#set figure.caption(position: top)
+#figure(rect(), caption: [A], placement: auto) <a>
+#figure(table[x], caption: [B]) <b>
+#figure(rect(), caption: [C], placement: bottom) <c>
+See @a, @b, and @c.
+
+#locate(loc => {
+  test(counter(figure.where(kind: image)).at(<c>), (2,))
+  test(counter(figure.where(kind: table)).at(<b>), (1,))
+  test(query(figure.where(kind: image), loc).len(), 2)
+})
+
+#outline(target: figure.where(kind: image))
+
+---
+// Test continued captions for figures that break across pages.
+// Ref: false
+#set page(height: 100pt)
+#show figure: set block(breakable: true)
+#show figure.where(kind: table): set figure.caption(position: top, continued: auto)
+#figure(
+  table(columns: 4, ..range(40).map(str)),
+  caption: [Numbers],
+)
+
+#set figure.caption(position: bottom, continued: [More], continued-number: false)
+#figure(
+  table(columns: 4, ..range(40).map(str)),
+  caption: [More numbers],
+)
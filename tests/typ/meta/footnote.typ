@@ -32,3 +32,13 @@ Fourth
 )
 
 Beautiful footnotes. #footnote[Wonderful, aren't they?]
+
+---
+// Footnote entries are locatable, so they can be queried.
+// Ref: false
+A #footnote[B] #footnote[C]
+#locate(loc => {
+  let entries = query(footnote.entry, loc)
+  test(entries.len(), 2)
+  test(entries.map(entry => entry.note.body), ([B], [C]))
+})
@@ -0,0 +1,22 @@
+// Test interactive form fields.
+
+---
+// Ref: false
+Name: #text-field("name", value: "Jane", tab-index: 1) \
+Notes: #text-field("notes", multiline: true, max-length: 200) \
+#checkbox("agree", checked: true) I agree \
+#radio-button("size", "small") Small
+#radio-button("size", "large", checked: true) Large \
+#dropdown("country", ("Germany", "France"), value: "France")
+
+---
+// Fields are sized like boxes.
+// Ref: false
+#style(styles => {
+  let field = text-field("a", width: 50pt, height: 20pt)
+  test(measure(field, styles), (width: 50pt, height: 20pt))
+})
+
+---
+// Error: 2-39 value `c` is not one of the options
+#dropdown("x", ("a", "b"), value: "c")
@@ -51,3 +51,25 @@ multiline.
 =
 Not in heading
 =Nope
+
+---
+// Test restarting the heading numbering.
+// Ref: false
+#set heading(numbering: "1.1")
+= Introduction
+== Background
+#heading(numbering: none)[Interlude]
+= Results <results>
+#locate(loc => test(counter(heading).at(loc), (2,)))
+
+#set heading(numbering: "A.1")
+#heading(reset: true)[Proofs] <proofs>
+== Lemmas <lemmas>
+#heading(level: 2, reset: true)[Corollaries] <corollaries>
+= Data <data>
+#locate(loc => {
+  test(counter(heading).at(<proofs>), (1,))
+  test(counter(heading).at(<lemmas>), (1, 1))
+  test(counter(heading).at(<corollaries>), (1, 1))
+  test(counter(heading).at(<data>), (2,))
+})
@@ -0,0 +1,29 @@
+// Test the back-of-book index.
+// Ref: false
+
+---
+// Entries are invisible and can be queried.
+#set page(height: 60pt)
+Typst #index.entry("Typst")
+#index.entry("Typst", sub: "syntax")
+#pagebreak()
+#index.entry("Typst", see: "LaTeX")
+#index.entry([_Émile_], key: "Emile")
+#locate(loc => {
+  let entries = query(index.entry, loc)
+  test(entries.len(), 4)
+  test(entries.at(1).sub.text, "syntax")
+  test(entries.at(3).key, "Emile")
+})
+#index()
+
+---
+// The default title depends on the language.
+#show heading: it => test(it.body.text, "Stichwortverzeichnis")
+#set text(lang: "de")
+#index.entry("Äpfel")
+#index()
+
+---
+// Error: 14-16 expected content, found integer
+#index.entry(12)
@@ -101,3 +101,19 @@
 ---
 // Error: 17-19 number must be at least zero
 #numbering("1", -1)
+
+---
+// Test Greek numbering.
+// Ref: false
+#test(numbering("α", 1), "α")
+#test(numbering("α", 24), "ω")
+#test(numbering("α", 25), "αα")
+#test(numbering("Α.α)", 3, 2), "Γ.β)")
+#test(repr(heading(numbering: "Α.", [A]).numbering), "\"Α.\"")
+
+---
+// Test that numbering functions receive all counter values.
+// Ref: false
+#let padded(..nums) = nums.pos().map(n => if n < 10 { "0" + str(n) } else { str(n) }).join("-")
+#test(numbering(padded, 2, 13), "02-13")
+#test(numbering(padded, 7), "07")
@@ -43,3 +43,34 @@ Ok ...
 
 = #text(blue)[Sum]mary
 #lorem(10)
+
+---
+// Test excluding elements from the outline.
+// Ref: false
+#show outline.entry: it => {
+  test(it.element.body != [Draft], true)
+  it
+}
+#outline(exclude: <draft>)
+#outline(exclude: heading.where(level: 2).or(<draft>))
+
+= Introduction
+== Draft <draft>
+= Conclusion
+
+---
+// Test the default titles of figure lists.
+// Ref: false
+#let titles = state("titles", ())
+#show heading: it => titles.update(t => t + (it.body.text,))
+#outline(target: figure.where(kind: table))
+#outline(target: figure.where(kind: image))
+#outline(target: figure.where(kind: "custom"))
+#set text(lang: "de")
+#outline(target: figure.where(kind: table))
+#locate(loc => test(titles.final(loc), (
+  "List of Tables",
+  "List of Figures",
+  "Contents",
+  "Tabellenverzeichnis",
+)))
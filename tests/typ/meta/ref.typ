@@ -46,3 +46,50 @@ $ A = 1 $ <eq2>
 
 #set ref(supplement: none)
 @fig1, @fig2, @eq1, @eq2
+
+---
+// Test resolving references in a namespace.
+// Ref: false
+#set heading(numbering: "1.")
+#show ref: it => {
+  test(it.target, <intro:motivation>)
+  it
+}
+
+= Introduction <intro:motivation>
+#set ref(namespace: "intro")
+@motivation
+
+---
+// Test that references fall back to the plain label.
+// Ref: false
+#set heading(numbering: "1.")
+#show ref: it => {
+  test(it.target, <results>)
+  it
+}
+
+= Results <results>
+#set ref(namespace: "intro")
+@results
+
+---
+= First <ch1:intro>
+= Second <ch2:intro>
+
+#set ref(namespace: "ch3")
+// Error: 1-7 label `<intro>` does not exist in the document
+@intro
+
+---
+// Test references into another document.
+// Ref: false
+#let vol2(target, ..args) = ref(target, from: "/files/vol2-refs.json", ..args)
+See #vol2(<proof>) and #vol2(<results>, supplement: [Fig.]).
+
+#set ref(from: "/files/vol2-refs.json")
+See @proof.
+
+---
+// Error: 2-44 label `<nope>` does not exist in Volume II
+#ref(<nope>, from: "/files/vol2-refs.json")
@@ -0,0 +1,19 @@
+// Test speaker notes and presentation settings.
+
+---
+// Ref: false
+#set document(fullscreen: true)
+#set page(width: 80pt, height: 60pt, transition: "fade", duration: duration(seconds: 5))
+Slide
+#speaker-note[First *note*]
+#pagebreak()
+#speaker-note[Second note]
+#locate(loc => {
+  let notes = query(speaker-note, loc)
+  test(notes.len(), 2)
+  test(notes.last().location().page(), 2)
+})
+
+---
+// Error: 23-29 expected "split", "blinds", "box", "wipe", "dissolve", "glitter", "fly", "push", "cover", "uncover", "fade", or none
+#set page(transition: "zoom")
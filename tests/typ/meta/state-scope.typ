@@ -0,0 +1,39 @@
+// Test state that is scoped to pages and sections.
+// Ref: false
+
+---
+// State scoped to sections is reset at each heading.
+#let s = state("notes", 0, scope: heading)
+#s.update(n => n + 5)
+= Intro
+#s.update(n => n + 1)
+#s.update(n => n + 1)
+#locate(loc => test(s.at(loc), 2))
+= Details
+#locate(loc => test(s.at(loc), 0))
+#s.update(n => n + 1)
+#locate(loc => {
+  test(s.at(loc), 1)
+  test(s.final(loc), 1)
+})
+
+---
+// State scoped to pages is reset on each page.
+#set page(height: 60pt)
+#let s = state("page-notes", (), scope: "page")
+#s.update(v => v + (1,))
+#s.update(v => v + (2,))
+#locate(loc => test(s.at(loc), (1, 2)))
+#pagebreak()
+#locate(loc => test(s.at(loc), ()))
+#s.update(v => v + (3,))
+#locate(loc => test(s.at(loc), (3,)))
+
+---
+// The scope is part of the state's representation.
+#test(repr(state("a", 1)), "state(\"a\", 1)")
+#test(repr(state("a", 1, scope: "page")), "state(\"a\", 1, scope: \"page\")")
+
+---
+// Error: 26-28 expected "document", "page", label, function, or selector, found integer
+#state("a", none, scope: 12)
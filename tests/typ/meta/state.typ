@@ -51,6 +51,8 @@ Was: #locate(location => {
 // Make sure that a warning is produced if the layout fails to converge.
 // Warning: layout did not converge within 5 attempts
 // Hint: check if any states or queries are updating themselves
+// Warning: 3:2-3:13 state "s" did not converge
+// Hint: 3:2-3:13 its value kept changing at 1 location
 #let s = state("s", 1)
 #locate(loc => s.update(s.final(loc) + 1))
 #s.display()
@@ -1,6 +1,8 @@
 // Test top and bottom text edge.
 
 ---
+// Warning: 5:19-5:34 unknown font family: ibm plex mono
+// Warning: 10:19-10:34 unknown font family: ibm plex mono
 #set page(width: 160pt)
 #set text(size: 8pt)
 
@@ -43,6 +43,17 @@ Emoji: 🐪, 🌋, 🏞
 #text([Text], teal, font: "IBM Plex Serif") \
 #text(forest, font: "New Computer Modern", [Text]) \
 
+---
+// Ref: false
+// Warning: 17-30 unknown font family: nonexistent
+#set text(font: "Nonexistent")
+
+---
+// Ref: false
+// Warning: 17-54 unknown font family: nonexistent
+// Warning: 17-54 unknown font family: another
+#set text(font: ("Nonexistent", "PT Sans", "Another"))
+
 ---
 // Error: 11-16 unexpected argument
 #set text(false)
@@ -58,3 +69,8 @@ Emoji: 🐪, 🌋, 🏞
 ---
 // Error: 11-31 unexpected argument: something
 #set text(something: "invalid")
+
+---
+// Error: 17-22 unknown variable: Arial
+// Hint: 17-22 enclose the font name in quotes to use it as a string
+#set text(font: Arial)
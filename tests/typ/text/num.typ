@@ -0,0 +1,30 @@
+// Test locale-aware number formatting.
+// Ref: false
+
+---
+#test(num.format(1234567.89), "1,234,567.89")
+#test(num.format(1234567.89, locale: "de"), "1.234.567,89")
+#test(num.format(1234567.89, locale: "fr"), "1\u{202F}234\u{202F}567,89")
+#test(num.format(1234567, locale: "ru"), "1\u{A0}234\u{A0}567")
+#test(num.format(-1234, precision: 2), "−1,234.00")
+#test(num.format(0.126, locale: "de", precision: 2), "0,13")
+#test(num.format(decimal("1234.5"), locale: "it", precision: 2), "1.234,50")
+#test(num.format(999), "999")
+#test(num.format(1234, locale: "es"), "1234")
+#test(num.format(12345, locale: "es"), "12.345")
+
+---
+// Test that the text language is used by default.
+#set text(lang: "de")
+#num(1234.5) \
+#num(1234.5, locale: "en") \
+#set num(precision: 2)
+#num(3)
+
+---
+// Error: 13-16 expected integer, float, or decimal, found string
+#num.format("1")
+
+---
+// Error: 24-30 expected two or three letter language code (ISO 639-1/2/3)
+#num.format(1, locale: "xxxx")
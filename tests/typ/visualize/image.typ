@@ -80,3 +80,11 @@ A #box(image("/files/tiger.jpg", height: 1cm, width: 80%)) B
 ---
 // Error: 14-83 failed to decode image (Format error decoding Png: Invalid PNG signature.)
 #image.decode(read("/files/tiger.jpg", encoding: none), format: "png", width: 80%)
+
+---
+// Error: 33-51 file is not a valid ICC profile
+#image("/files/tiger.jpg", icc: "/files/hello.txt")
+
+---
+// Error: 2-50 ICC profiles are only supported for raster images
+#image("/files/logo.svg", icc: "/files/sRGB.icc")